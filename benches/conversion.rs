@@ -0,0 +1,135 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Benchmarks for color conversion, color difference, quantization, and
+//! nearest-color queries.
+//!
+//! Each conversion is measured both per color and through the slice-based
+//! batch functions.
+//!
+////////////////////////////////////////////////////////////////////////////////
+#[macro_use]
+extern crate criterion;
+extern crate palette;
+
+use criterion::{black_box, Criterion};
+use palette::Color;
+use palette::quantize::{Quantizer, Remap};
+use palette::space::{
+	colors_to_lab,
+	colors_to_oklab,
+	delta_e_2000_matrix,
+	labs_to_colors,
+	nearest_oklab,
+	Lab,
+	Oklab,
+};
+
+
+/// Returns a deterministic spread of colors for use as input.
+fn sample_colors(count: usize) -> Vec<Color> {
+	(0..count)
+		.map(|i| {
+			let v = (i as u32).wrapping_mul(2_654_435_761);
+			Color::new(v as u8, (v >> 8) as u8, (v >> 16) as u8)
+		})
+		.collect()
+}
+
+
+fn conversion(c: &mut Criterion) {
+	let colors = sample_colors(4096);
+	let mut labs = vec![Lab::default(); colors.len()];
+	colors_to_lab(&colors, &mut labs);
+
+	c.bench_function("srgb_to_lab/each", |b| {
+		b.iter(|| black_box(&colors).iter()
+			.map(|&c| Lab::from(c))
+			.collect::<Vec<_>>())
+	});
+	c.bench_function("srgb_to_lab/batch", |b| {
+		let mut out = vec![Lab::default(); colors.len()];
+		b.iter(|| colors_to_lab(black_box(&colors), &mut out))
+	});
+
+	c.bench_function("lab_to_srgb/each", |b| {
+		b.iter(|| black_box(&labs).iter()
+			.map(|&lab| Color::from(lab))
+			.collect::<Vec<_>>())
+	});
+	c.bench_function("lab_to_srgb/batch", |b| {
+		let mut out = vec![Color::new(0, 0, 0); labs.len()];
+		b.iter(|| labs_to_colors(black_box(&labs), &mut out))
+	});
+
+	c.bench_function("srgb_to_oklab/batch", |b| {
+		let mut out = vec![Oklab::default(); colors.len()];
+		b.iter(|| colors_to_oklab(black_box(&colors), &mut out))
+	});
+}
+
+
+fn color_difference(c: &mut Criterion) {
+	let mut labs = vec![Lab::default(); 256];
+	colors_to_lab(&sample_colors(256), &mut labs);
+	let mut out = vec![0.0; labs.len() * labs.len()];
+
+	c.bench_function("delta_e_2000_matrix/256", |b| {
+		b.iter(|| delta_e_2000_matrix(black_box(&labs), &mut out))
+	});
+}
+
+
+fn quantization(c: &mut Criterion) {
+	let pixels = sample_colors(64 * 64);
+
+	c.bench_function("quantize/16", |b| {
+		let quantizer = Quantizer::new(16);
+		b.iter(|| quantizer.quantize(black_box(&pixels), None))
+	});
+}
+
+
+fn nearest_color(c: &mut Criterion) {
+	let palette = sample_colors(64);
+	let pixels = sample_colors(64 * 64);
+	let mut candidates = vec![Oklab::default(); palette.len()];
+	colors_to_oklab(&palette, &mut candidates);
+	let mut targets = vec![Oklab::default(); pixels.len()];
+	colors_to_oklab(&pixels, &mut targets);
+
+	c.bench_function("nearest/batch", |b| {
+		let mut out = vec![0; targets.len()];
+		b.iter(|| nearest_oklab(black_box(&targets), &candidates, &mut out))
+	});
+	c.bench_function("nearest/remap", |b| {
+		let remap = Remap::new(&palette);
+		b.iter(|| remap.remap(black_box(&pixels), 64))
+	});
+}
+
+
+criterion_group!(benches, conversion, color_difference, quantization, 
+	nearest_color);
+criterion_main!(benches);
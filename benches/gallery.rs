@@ -0,0 +1,79 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Benchmarks for reading, writing, and rendering the reference palettes.
+//!
+//! The reference palettes stand in for typical user palettes, so these 
+//! measure saving and loading a palette and drawing its thumbnail.
+//!
+////////////////////////////////////////////////////////////////////////////////
+#[macro_use]
+extern crate criterion;
+extern crate palette;
+
+use criterion::{black_box, Criterion};
+use palette::Format;
+use palette::gallery::{Gallery, ReferencePalette};
+use palette::render::ThumbnailLayout;
+
+
+fn read_write(c: &mut Criterion) {
+	for &reference in ReferencePalette::all() {
+		let palette = reference.palette();
+		let mut text = Vec::new();
+		Format::Default.write_palette(&palette, &mut text).unwrap();
+
+		c.bench_function(&format!("write/{}", reference.name()), |b| {
+			let mut out = Vec::with_capacity(text.len());
+			b.iter(|| {
+				out.clear();
+				Format::Default.write_palette(black_box(&palette), &mut out)
+			})
+		});
+		c.bench_function(&format!("read/{}", reference.name()), |b| {
+			b.iter(|| Format::Default.read_palette(&mut black_box(&text[..])))
+		});
+	}
+}
+
+
+fn rendering(c: &mut Criterion) {
+	let gallery = Gallery::reference();
+
+	c.bench_function("thumbnails/256x64", |b| {
+		b.iter(|| black_box(&gallery).thumbnails(256, 64, 
+			ThumbnailLayout::Lines))
+	});
+	c.bench_function("html", |b| {
+		let mut out = Vec::new();
+		b.iter(|| {
+			out.clear();
+			black_box(&gallery).write_html(&mut out)
+		})
+	});
+}
+
+
+criterion_group!(benches, read_write, rendering);
+criterion_main!(benches);
@@ -0,0 +1,68 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Benchmarks for ramp regeneration.
+//!
+//! Editing a control color regenerates every ramp derived from it, so these
+//! measure regenerating a 256 color ramp as on each tick of a color slider.
+//!
+////////////////////////////////////////////////////////////////////////////////
+#[macro_use]
+extern crate criterion;
+extern crate palette;
+
+use criterion::{black_box, Criterion};
+use palette::ramp::{InterpolationSpace, RampGenerator};
+use palette::space::WideColor;
+
+
+/// The number of colors in each regenerated ramp.
+const RAMP_LEN: usize = 256;
+
+/// The control colors of each regenerated ramp.
+const STOPS: [WideColor; 3] = [
+	WideColor::srgb(0.1, 0.05, 0.2),
+	WideColor::srgb(0.8, 0.3, 0.25),
+	WideColor::srgb(1.0, 0.95, 0.8),
+];
+
+
+fn ramp_regeneration(c: &mut Criterion) {
+	for &space in InterpolationSpace::all() {
+		let gen = RampGenerator::new().space(space);
+
+		c.bench_function(&format!("multi_stop_wide/{}", space.name()), |b| {
+			b.iter(|| gen.multi_stop_wide(black_box(&STOPS), RAMP_LEN))
+		});
+
+		let mut ramp = vec![WideColor::default(); RAMP_LEN];
+		c.bench_function(&format!("fill_wide/{}", space.name()), |b| {
+			b.iter(|| gen.fill_wide(black_box(&STOPS), &mut ramp))
+		});
+	}
+}
+
+
+criterion_group!(benches, ramp_regeneration);
+criterion_main!(benches);
@@ -0,0 +1,44 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Fuzzes the indexed image color table importer with GIF, PNG, and BMP
+//! images built from arbitrary header fields.
+//!
+////////////////////////////////////////////////////////////////////////////////
+#![no_main]
+#[macro_use]
+extern crate libfuzzer_sys;
+extern crate palette;
+extern crate palette_fuzz;
+
+use palette::import::read_color_table;
+use palette_fuzz::ColorTableImage;
+
+
+fuzz_target!(|image: ColorTableImage| {
+	let bytes = image.to_bytes();
+	if let Ok(palette) = read_color_table("Image", &mut &bytes[..]) {
+		palette_fuzz::check_palette(palette);
+	}
+});
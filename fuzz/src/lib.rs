@@ -0,0 +1,559 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides structured input generators and shared checks for the palette
+//! parser fuzz targets.
+//!
+//! Each parser has a target reading arbitrary bytes. The default format, the
+//! indexed image color tables, and selectors also have structured targets, 
+//! which build inputs from well-formed entries and headers with arbitrary 
+//! fields, so the fuzzer reaches deeper into the parsers than it would by 
+//! mutating bytes.
+//!
+//! The targets are run with `cargo fuzz` from the repository root:
+//!
+//! ```text
+//! cargo +nightly fuzz list
+//! cargo +nightly fuzz run default_format_structured
+//! cargo +nightly fuzz run selector_structured -- -max_total_time=600
+//! cargo +nightly fuzz run bundle --features bundle
+//! ```
+//!
+//! The targets for parsers behind the palette crate's `bundle` and 
+//! `openraster` features require the fuzz crate feature of the same name.
+//!
+//! Short runs rarely reach the numeric edge cases of the parsers, so each 
+//! target touched by a change should be run for at least ten minutes.
+//!
+//! Parsers must return errors rather than panic on malformed input, and any 
+//! palette they return must be writable and editable.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+extern crate arbitrary;
+extern crate palette;
+
+// Non-local imports.
+use arbitrary::{
+	Arbitrary,
+	Unstructured,
+};
+use palette::{
+	Color,
+	Format,
+	Palette,
+};
+use palette::operation::InsertColor;
+use palette::select::Selector;
+
+// Standard imports.
+use std::fmt;
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Checks
+////////////////////////////////////////////////////////////////////////////////
+/// Exercises a successfully parsed palette, writing it back out, then 
+/// applying and undoing an operation on it.
+pub fn check_palette(mut palette: Palette) {
+	let mut out = Vec::new();
+	Format::Default.write_palette(&palette, &mut out)
+		.expect("write parsed palette");
+	if palette.apply(Box::new(InsertColor::new(Color::new(0, 0, 0)))).is_ok() {
+		palette.undo().expect("undo operation on parsed palette");
+	}
+}
+
+
+/// Exercises a palette read in the default format, checking that it reads 
+/// back identically once written in canonical form.
+pub fn check_round_trip(palette: Palette) {
+	let mut first = Vec::new();
+	Format::Default.write_palette(&palette, &mut first)
+		.expect("write parsed palette");
+	let reread = Format::Default.read_palette(&mut &first[..])
+		.expect("read canonical palette");
+	let mut second = Vec::new();
+	Format::Default.write_palette(&reread, &mut second)
+		.expect("write reread palette");
+	assert_eq!(first, second, "canonical form changed on round trip");
+	check_palette(reread);
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Default format
+////////////////////////////////////////////////////////////////////////////////
+/// A small number, biased towards the values the parsers treat specially.
+#[derive(Debug, Clone, Copy)]
+pub struct Number(pub u64);
+
+impl<'a> Arbitrary<'a> for Number {
+	fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+		Ok(Number(match u.int_in_range(0u8..=15)? {
+			0 => 0,
+			1 => 1,
+			2 => u64::from(u8::max_value()),
+			3 => u64::from(u16::max_value()),
+			4 => u64::from(u32::max_value()),
+			5 => u64::max_value(),
+			_ => u64::from(u.int_in_range(0u8..=16)?),
+		}))
+	}
+}
+
+
+/// A cell address, written as `page:line:column`.
+#[derive(Debug, Clone, Copy, Arbitrary)]
+pub struct AddressText(pub Number, pub Number, pub Number);
+
+impl fmt::Display for AddressText {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}:{}:{}", (self.0).0, (self.1).0, (self.2).0)
+	}
+}
+
+
+/// A string token, written quoted and escaped.
+#[derive(Debug, Clone, Arbitrary)]
+pub struct Quoted(pub String);
+
+impl fmt::Display for Quoted {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "\"")?;
+		for c in self.0.chars() {
+			match c {
+				'"'  => write!(f, "\\\"")?,
+				'\\' => write!(f, "\\\\")?,
+				'\n' => write!(f, "\\n")?,
+				_    => write!(f, "{}", c)?,
+			}
+		}
+		write!(f, "\"")
+	}
+}
+
+
+/// Returns one of the given keywords, or occasionally arbitrary text.
+fn keyword<'a>(u: &mut Unstructured<'a>, keywords: &[&str]) 
+	-> arbitrary::Result<Quoted>
+{
+	if u.ratio(1, 8)? {
+		Quoted::arbitrary(u)
+	} else {
+		Ok(Quoted(u.choose(keywords)?.to_string()))
+	}
+}
+
+
+/// Returns a color space name.
+fn color_space<'a>(u: &mut Unstructured<'a>) -> arbitrary::Result<Quoted> {
+	keyword(u, &["srgb", "display-p3", "rec2020"])
+}
+
+
+/// Returns a tone curve channel name.
+fn tone_channel<'a>(u: &mut Unstructured<'a>) -> arbitrary::Result<Quoted> {
+	keyword(u, &["all", "red", "green", "blue", "lightness"])
+}
+
+
+/// Returns a palette format name.
+fn format<'a>(u: &mut Unstructured<'a>) -> arbitrary::Result<Quoted> {
+	keyword(u, &["default", "zpl"])
+}
+
+
+/// A cell expression.
+#[derive(Debug, Clone, Arbitrary)]
+pub enum ExpressionText {
+	/// An empty cell.
+	Empty,
+	/// A hex color.
+	Color(u8, u8, u8),
+	/// A wide color in a named color space.
+	Wide(#[arbitrary(with = color_space)] Quoted, f32, f32, f32),
+}
+
+impl fmt::Display for ExpressionText {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			ExpressionText::Empty => write!(f, "empty"),
+			ExpressionText::Color(r, g, b) 
+				=> write!(f, "#{:02X}{:02X}{:02X}", r, g, b),
+			ExpressionText::Wide(ref space, r, g, b) 
+				=> write!(f, "wide {} {} {} {}", space, r, g, b),
+		}
+	}
+}
+
+
+/// A provenance entry.
+#[derive(Debug, Clone, Arbitrary)]
+pub enum ProvenanceText {
+	/// An imported color.
+	Import(Quoted, Quoted),
+	/// A quantized color.
+	Quantized(Quoted),
+	/// A color evaluated from an expression.
+	Expression(Quoted),
+	/// A color set by hand.
+	Manual,
+	/// A color written by an operation.
+	Operation(Number, Quoted),
+}
+
+impl fmt::Display for ProvenanceText {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			ProvenanceText::Import(ref format, ref source) 
+				=> write!(f, "import {} {}", format, source),
+			ProvenanceText::Quantized(ref source) 
+				=> write!(f, "quantized {}", source),
+			ProvenanceText::Expression(ref text) 
+				=> write!(f, "expression {}", text),
+			ProvenanceText::Manual => write!(f, "manual"),
+			ProvenanceText::Operation(id, ref name) 
+				=> write!(f, "operation {} {}", id.0, name),
+		}
+	}
+}
+
+
+/// A line of a default format palette file.
+#[derive(Debug, Clone, Arbitrary)]
+pub enum Entry {
+	/// The palette name.
+	Palette(Quoted),
+	/// The palette format.
+	Format(#[arbitrary(with = format)] Quoted),
+	/// The working color space.
+	ColorSpace(#[arbitrary(with = color_space)] Quoted),
+	/// A tone curve.
+	ToneCurve(#[arbitrary(with = tone_channel)] Quoted, Vec<(f32, f32)>),
+	/// A page's line count and optional name.
+	Page(Number, Number, Option<Quoted>),
+	/// A line's column count and optional name.
+	Line(Number, Number, Number, Option<Quoted>),
+	/// A cell's expression.
+	Cell(AddressText, ExpressionText),
+	/// A cell's name.
+	Name(AddressText, Quoted),
+	/// A cell's description.
+	Description(AddressText, Quoted),
+	/// A cell tag.
+	Tag(AddressText, Quoted),
+	/// A cell's usage count.
+	Usage(AddressText, Number),
+	/// A cell's provenance.
+	Source(AddressText, ProvenanceText),
+	/// A semantic alias.
+	Alias(Quoted, AddressText),
+	/// A trashed cell and its optional name.
+	Trash(AddressText, ExpressionText, Option<Quoted>),
+	/// A comment.
+	Comment(String),
+	/// An arbitrary line.
+	Raw(String),
+}
+
+impl fmt::Display for Entry {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			Entry::Palette(ref name) => write!(f, "palette {}", name),
+			Entry::Format(ref name) => write!(f, "format {}", name),
+			Entry::ColorSpace(ref name) => write!(f, "color_space {}", name),
+			Entry::ToneCurve(ref kind, ref points) => {
+				write!(f, "tone_curve {}", kind)?;
+				for &(x, y) in points {
+					write!(f, " {}:{}", x, y)?;
+				}
+				Ok(())
+			},
+			Entry::Page(page, lines, ref name) => {
+				write!(f, "page {} lines {}", page.0, lines.0)?;
+				name.as_ref().map_or(Ok(()), |n| write!(f, " name {}", n))
+			},
+			Entry::Line(page, line, columns, ref name) => {
+				write!(f, "line {}:{} columns {}", page.0, line.0, columns.0)?;
+				name.as_ref().map_or(Ok(()), |n| write!(f, " name {}", n))
+			},
+			Entry::Cell(address, ref expression) 
+				=> write!(f, "cell {} {}", address, expression),
+			Entry::Name(address, ref name) 
+				=> write!(f, "name {} name {}", address, name),
+			Entry::Description(address, ref text) 
+				=> write!(f, "description {} {}", address, text),
+			Entry::Tag(address, ref tag) 
+				=> write!(f, "tag {} {}", address, tag),
+			Entry::Usage(address, count) 
+				=> write!(f, "usage {} {}", address, count.0),
+			Entry::Source(address, ref provenance) 
+				=> write!(f, "source {} {}", address, provenance),
+			Entry::Alias(ref token, address) 
+				=> write!(f, "alias {} {}", token, address),
+			Entry::Trash(address, ref expression, ref name) => {
+				write!(f, "trash {} {}", address, expression)?;
+				name.as_ref().map_or(Ok(()), |n| write!(f, " name {}", n))
+			},
+			Entry::Comment(ref text) => write!(f, "# {}", text),
+			Entry::Raw(ref text) => write!(f, "{}", text),
+		}
+	}
+}
+
+
+/// A default format palette file built from arbitrary entries.
+#[derive(Debug, Clone, Arbitrary)]
+pub struct PaletteText(pub Vec<Entry>);
+
+impl fmt::Display for PaletteText {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		for entry in &self.0 {
+			writeln!(f, "{}", entry)?;
+		}
+		Ok(())
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Selectors
+////////////////////////////////////////////////////////////////////////////////
+/// Returns a comparable color property name.
+fn property<'a>(u: &mut Unstructured<'a>) 
+	-> arbitrary::Result<&'static str>
+{
+	Ok(*u.choose(&["lum", "light", "sat", "hue"])?)
+}
+
+
+/// Returns a comparison symbol.
+fn comparison<'a>(u: &mut Unstructured<'a>) 
+	-> arbitrary::Result<&'static str>
+{
+	Ok(*u.choose(&["<", "<=", ">", ">=", "="])?)
+}
+
+
+/// A selector term.
+#[derive(Debug, Clone, Arbitrary)]
+pub enum TermText {
+	/// Every cell.
+	All,
+	/// A single address.
+	Address(AddressText),
+	/// An inclusive range of addresses.
+	Range(AddressText, AddressText),
+	/// A page.
+	Page(Number),
+	/// A single occupied cell of a page.
+	PageCell(Number, Number),
+	/// A range of occupied cells of a page.
+	PageCells(Number, Number, Number),
+	/// A tag.
+	Tag(String),
+	/// A comparison of a color property.
+	Compare(
+		#[arbitrary(with = property)] &'static str,
+		#[arbitrary(with = comparison)] &'static str,
+		f32),
+	/// A name pattern.
+	Name(String),
+}
+
+impl fmt::Display for TermText {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			TermText::All => write!(f, "*"),
+			TermText::Address(address) => write!(f, "{}", address),
+			TermText::Range(start, end) => write!(f, "{}..{}", start, end),
+			TermText::Page(page) => write!(f, "page{}", page.0),
+			TermText::PageCell(page, index) 
+				=> write!(f, "page{}[{}]", page.0, index.0),
+			TermText::PageCells(page, start, end) 
+				=> write!(f, "page{}[{}..{}]", page.0, start.0, end.0),
+			TermText::Tag(ref tag) => write!(f, "#{}", tag),
+			TermText::Compare(property, comparison, threshold) 
+				=> write!(f, "{}{}{}", property, comparison, threshold),
+			TermText::Name(ref pattern) => write!(f, "{}", pattern),
+		}
+	}
+}
+
+
+/// A selection string built from arbitrary terms.
+#[derive(Debug, Clone, Arbitrary)]
+pub struct SelectorText(pub Vec<TermText>);
+
+impl fmt::Display for SelectorText {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		for (i, term) in self.0.iter().enumerate() {
+			if i > 0 { write!(f, ", ")?; }
+			write!(f, "{}", term)?;
+		}
+		Ok(())
+	}
+}
+
+
+/// Exercises a successfully parsed selector, checking that it parses again
+/// once written, then selecting with it from a small palette.
+pub fn check_selector(selector: Selector) {
+	let text = selector.to_string();
+	let reparsed = Selector::parse(&text).expect("reparse written selector");
+	assert_eq!(reparsed.to_string(), text, "selector changed on round trip");
+
+	let mut palette = Palette::new("Selector", Format::Default, true);
+	for value in 0..4 {
+		palette.apply(Box::new(InsertColor::new(
+				Color::new(value * 60, value * 60, value * 60))))
+			.expect("insert color");
+	}
+	let _ = selector.select(&palette);
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Color tables
+////////////////////////////////////////////////////////////////////////////////
+/// An indexed image with an arbitrary color table and header fields.
+#[derive(Debug, Clone, Arbitrary)]
+pub enum ColorTableImage {
+	/// A GIF image with global or first-frame local color table.
+	Gif {
+		/// Whether the image has a global color table.
+		global: bool,
+		/// The logical screen or image descriptor flags.
+		flags: u8,
+		/// The extension blocks preceding the first frame.
+		extensions: Vec<(u8, Vec<Vec<u8>>)>,
+		/// The color table entries.
+		table: Vec<u8>,
+	},
+	/// A PNG image with a palette chunk.
+	Png {
+		/// The chunks preceding the palette, by type.
+		chunks: Vec<([u8; 4], Vec<u8>)>,
+		/// The declared length of the palette chunk.
+		declared: Option<u32>,
+		/// The palette entries.
+		table: Vec<u8>,
+	},
+	/// A BMP image with a color table.
+	Bmp {
+		/// The size of the information header.
+		header: u32,
+		/// The offset of the pixels.
+		pixels: Option<u32>,
+		/// The bits per pixel.
+		depth: u16,
+		/// The compression method.
+		compression: u32,
+		/// The number of colors used.
+		used: u32,
+		/// The color table entries.
+		table: Vec<u8>,
+	},
+}
+
+impl ColorTableImage {
+	/// Returns the encoded image.
+	pub fn to_bytes(&self) -> Vec<u8> {
+		let mut bytes = Vec::new();
+		match *self {
+			ColorTableImage::Gif {global, flags, ref extensions, ref table} => {
+				bytes.extend_from_slice(b"GIF89a\x01\x00\x01\x00");
+				let flags = if global { flags | 0x80 } else { flags & 0x7F };
+				bytes.extend_from_slice(&[flags, 0, 0]);
+				if !global {
+					for &(label, ref blocks) in extensions {
+						bytes.extend_from_slice(&[0x21, label]);
+						for block in blocks.iter().filter(|b| !b.is_empty()) {
+							let len = block.len().min(255);
+							bytes.push(len as u8);
+							bytes.extend_from_slice(&block[..len]);
+						}
+						bytes.push(0);
+					}
+					bytes.extend_from_slice(&[0x2C, 0, 0, 0, 0, 1, 0, 1, 0]);
+					bytes.push(flags | 0x80);
+				}
+				bytes.extend_from_slice(table);
+				bytes.push(0x3B);
+			},
+
+			ColorTableImage::Png {ref chunks, declared, ref table} => {
+				bytes.extend_from_slice(b"\x89PNG\r\n\x1A\n");
+				for &(kind, ref data) in chunks {
+					push_png_chunk(&mut bytes, kind, data.len() as u32, data);
+				}
+				let len = declared.unwrap_or(table.len() as u32);
+				push_png_chunk(&mut bytes, *b"PLTE", len, table);
+				push_png_chunk(&mut bytes, *b"IEND", 0, &[]);
+			},
+
+			ColorTableImage::Bmp {
+				header, 
+				pixels, 
+				depth, 
+				compression, 
+				used, 
+				ref table
+			} => {
+				let start = 14 + header.min(124);
+				let pixels = pixels.unwrap_or(start + table.len() as u32);
+				bytes.extend_from_slice(b"BM\0\0\0\0\0\0\0\0");
+				bytes.extend_from_slice(&pixels.to_le_bytes());
+				bytes.extend_from_slice(&header.to_le_bytes());
+				let len = header.min(124).saturating_sub(4) as usize;
+				let mut info = vec![0; len];
+				if header == 12 && info.len() >= 8 {
+					info[6..8].copy_from_slice(&depth.to_le_bytes());
+				} else if info.len() >= 36 {
+					info[10..12].copy_from_slice(&depth.to_le_bytes());
+					info[12..16].copy_from_slice(&compression.to_le_bytes());
+					info[28..32].copy_from_slice(&used.to_le_bytes());
+				}
+				bytes.extend_from_slice(&info);
+				bytes.extend_from_slice(table);
+			},
+		}
+		bytes
+	}
+}
+
+
+/// Appends a PNG chunk with the given type, declared length, and data. The 
+/// CRC is not checked by the parser, so it is left zeroed.
+fn push_png_chunk(bytes: &mut Vec<u8>, kind: [u8; 4], len: u32, data: &[u8]) {
+	bytes.extend_from_slice(&len.to_be_bytes());
+	bytes.extend_from_slice(&kind);
+	bytes.extend_from_slice(data);
+	bytes.extend_from_slice(&[0; 4]);
+}
@@ -485,7 +485,7 @@ impl fmt::LowerHex for Address {
 // Selection
 ////////////////////////////////////////////////////////////////////////////////
 /// A possibly non-contiguous selection of addresses.
-#[derive(Default, Clone)]
+#[derive(Debug, Default, Clone)]
 pub struct Selection {
 	inner: Vec<Interval<Address>>
 }
@@ -501,6 +501,14 @@ impl Selection {
 		}
 	}
 
+	/// Returns a selection containing every address.
+	pub fn all() -> Self {
+		Selection::new(Some(Interval::closed(
+			Address::new(0, 0, 0),
+			Address::new(PAGE_MAX, LINE_MAX, COLUMN_MAX)
+		)))
+	}
+
 	/// Unions an interval into the selection.
 	pub fn union(&mut self, interval: Interval<Address>) {
 		self.inner.push(interval);
@@ -510,4 +518,11 @@ impl Selection {
 	pub fn contains(&self, address: &Address) -> bool {
 		self.inner.iter().any(|int| int.contains(address))
 	}
+
+	/// Returns whether the selection contains every address.
+	pub fn is_all(&self) -> bool {
+		let first = Address::new(0, 0, 0);
+		let last = Address::new(PAGE_MAX, LINE_MAX, COLUMN_MAX);
+		self.inner.iter().any(|int| int.contains(&first) && int.contains(&last))
+	}
 }
\ No newline at end of file
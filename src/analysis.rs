@@ -0,0 +1,643 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides perceptual analyses of palette colors.
+//!
+//! Analyses do not modify the palette; they return their findings as data so
+//! that they can be displayed, plotted, or used to gate palette changes.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use ::Palette;
+use address::Address;
+use progress::ProgressMonitor;
+use provenance::Provenance;
+use result::Result;
+use space::{
+	delta_e_2000_lab,
+	ColorDifference,
+	Deficiency,
+	Lab,
+};
+
+// Non-local imports.
+use color::Color;
+
+// Standard imports.
+use std::collections::BTreeMap;
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// StepFlag
+////////////////////////////////////////////////////////////////////////////////
+/// Describes a problem with a single step of a ramp.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StepFlag {
+	/// The step is much larger than the average step of the ramp.
+	TooLarge,
+	/// The step is much smaller than the average step of the ramp, or too
+	/// small to be distinguished.
+	TooSmall,
+	/// The step changes lightness in the opposite direction of the ramp.
+	NonMonotonic,
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// StepReport
+////////////////////////////////////////////////////////////////////////////////
+/// The audit results for a single step between adjacent ramp colors.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StepReport {
+	/// The index of the first color of the step.
+	pub index: usize,
+	/// The difference between the step's colors, in the audit's metric.
+	pub delta_e: f32,
+	/// The change in Lab lightness across the step.
+	pub lightness_delta: f32,
+	/// The problems found with the step.
+	pub flags: Vec<StepFlag>,
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// UniformityReport
+////////////////////////////////////////////////////////////////////////////////
+/// The results of a perceptual uniformity audit.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct UniformityReport {
+	/// The audit results for each step, in ramp order.
+	pub steps: Vec<StepReport>,
+	/// The mean difference between adjacent colors, in the audit's metric.
+	pub mean_delta_e: f32,
+}
+
+
+impl UniformityReport {
+	/// Returns whether no steps were flagged.
+	pub fn is_uniform(&self) -> bool {
+		self.steps.iter().all(|step| step.flags.is_empty())
+	}
+
+	/// Returns the flagged steps.
+	pub fn flagged(&self) -> Vec<&StepReport> {
+		self.steps.iter().filter(|step| !step.flags.is_empty()).collect()
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// UniformityAudit
+////////////////////////////////////////////////////////////////////////////////
+/// Audits a ramp for perceptual uniformity and monotonic lightness.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::Color;
+/// use palette::analysis::{StepFlag, UniformityAudit};
+///
+/// let ramp = [
+/// 	Color::new(20, 20, 20),
+/// 	Color::new(60, 60, 60),
+/// 	Color::new(50, 50, 50),
+/// 	Color::new(200, 200, 200),
+/// ];
+///
+/// let report = UniformityAudit::new().audit(&ramp);
+/// assert!(!report.is_uniform());
+/// assert!(report.steps[1].flags.contains(&StepFlag::NonMonotonic));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UniformityAudit {
+	/// The allowed deviation of each step from the mean step, as a ratio of
+	/// the mean step.
+	tolerance: f32,
+	/// The smallest allowed step.
+	min_delta_e: f32,
+	/// Whether to flag steps that reverse the ramp's lightness direction.
+	require_monotonic: bool,
+	/// The metric measuring each step.
+	difference: ColorDifference,
+}
+
+
+impl UniformityAudit {
+	/// Creates a new `UniformityAudit` with the default thresholds.
+	pub fn new() -> Self {
+		UniformityAudit {
+			tolerance: 0.5,
+			min_delta_e: 1.0,
+			require_monotonic: true,
+			difference: ColorDifference::Ciede2000,
+		}
+	}
+
+	/// Sets the allowed deviation of each step from the mean step, as a ratio
+	/// of the mean step.
+	pub fn tolerance(mut self, tolerance: f32) -> Self {
+		self.tolerance = tolerance;
+		self
+	}
+
+	/// Sets the smallest allowed step.
+	pub fn min_delta_e(mut self, min_delta_e: f32) -> Self {
+		self.min_delta_e = min_delta_e;
+		self
+	}
+
+	/// Configures whether to flag steps that reverse the ramp's lightness
+	/// direction.
+	pub fn require_monotonic(mut self, require_monotonic: bool) -> Self {
+		self.require_monotonic = require_monotonic;
+		self
+	}
+
+	/// Sets the metric measuring each step. CIEDE2000 is used by default.
+	///
+	/// # Example
+	///
+	/// ```rust
+	/// use palette::Color;
+	/// use palette::analysis::UniformityAudit;
+	/// use palette::ramp::{InterpolationSpace, RampGenerator};
+	/// use palette::space::{ColorDifference, ViewingConditions};
+	///
+	/// let (start, end) = (Color::new(10, 20, 60), Color::new(240, 250, 200));
+	/// let ramp = RampGenerator::new()
+	/// 	.space(InterpolationSpace::Cam16Ucs)
+	/// 	.ramp(start, end, 8);
+	///
+	/// let cam16 = ColorDifference::Cam16Ucs(ViewingConditions::SRGB);
+	/// let report = UniformityAudit::new()
+	/// 	.tolerance(0.1)
+	/// 	.difference(cam16)
+	/// 	.audit(&ramp);
+	/// assert!(report.is_uniform());
+	/// ```
+	pub fn difference(mut self, difference: ColorDifference) -> Self {
+		self.difference = difference;
+		self
+	}
+
+	/// Audits the given ramp colors.
+	pub fn audit(&self, colors: &[Color]) -> UniformityReport {
+		if colors.len() < 2 { return Default::default(); }
+
+		let lightness: Vec<f32> = colors.iter()
+			.map(|&c| Lab::from(c).l)
+			.collect();
+		let direction = lightness[lightness.len() - 1] - lightness[0];
+
+		let mut steps: Vec<StepReport> = colors.windows(2)
+			.enumerate()
+			.map(|(i, pair)| StepReport {
+				index: i,
+				delta_e: self.difference.difference(pair[0], pair[1]),
+				lightness_delta: lightness[i + 1] - lightness[i],
+				flags: Vec::new(),
+			})
+			.collect();
+
+		let mean = steps.iter().map(|s| s.delta_e).sum::<f32>()
+			/ steps.len() as f32;
+
+		for step in &mut steps {
+			if step.delta_e > mean * (1.0 + self.tolerance) {
+				step.flags.push(StepFlag::TooLarge);
+			}
+			if step.delta_e < mean * (1.0 - self.tolerance) ||
+				step.delta_e < self.min_delta_e
+			{
+				step.flags.push(StepFlag::TooSmall);
+			}
+			if self.require_monotonic &&
+				step.lightness_delta * direction < 0.0
+			{
+				step.flags.push(StepFlag::NonMonotonic);
+			}
+		}
+
+		UniformityReport {
+			steps: steps,
+			mean_delta_e: mean,
+		}
+	}
+}
+
+
+impl Default for UniformityAudit {
+	fn default() -> Self {
+		UniformityAudit::new()
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// ConflictPair
+////////////////////////////////////////////////////////////////////////////////
+/// A pair of colors that are too similar to be reliably distinguished.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConflictPair {
+	/// The address of the first color.
+	pub first: Address,
+	/// The address of the second color.
+	pub second: Address,
+	/// The difference between the colors in the check's metric, as seen 
+	/// under the deficiency if one is given.
+	pub delta_e: f32,
+	/// The color vision deficiency under which the conflict occurs, or `None`
+	/// for normal color vision.
+	pub deficiency: Option<Deficiency>,
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// DistinguishabilityCheck
+////////////////////////////////////////////////////////////////////////////////
+/// Checks that every pair of colors in a set differ by at least a minimum
+/// threshold, optionally under simulated color vision deficiencies. The 
+/// difference is measured with CIEDE2000 unless another metric is set.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::{Address, Color};
+/// use palette::analysis::DistinguishabilityCheck;
+/// use palette::space::Deficiency;
+///
+/// let colors = [
+/// 	(Address::new(0, 0, 0), Color::new(200, 40, 40)),
+/// 	(Address::new(0, 0, 1), Color::new(40, 160, 40)),
+/// 	(Address::new(0, 0, 2), Color::new(202, 42, 40)),
+/// ];
+///
+/// let check = DistinguishabilityCheck::new(10.0)
+/// 	.simulate(Deficiency::Deuteranopia);
+/// let conflicts = check.check(&colors);
+///
+/// assert!(conflicts.iter().any(|c| c.first == Address::new(0, 0, 0) &&
+/// 	c.second == Address::new(0, 0, 2) && c.deficiency.is_none()));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct DistinguishabilityCheck {
+	/// The smallest allowed difference between any two colors.
+	threshold: f32,
+	/// The deficiencies to additionally check under.
+	deficiencies: Vec<Deficiency>,
+	/// The metric measuring the difference between colors.
+	difference: ColorDifference,
+}
+
+
+impl DistinguishabilityCheck {
+	/// Creates a new `DistinguishabilityCheck` with the given threshold.
+	pub fn new(threshold: f32) -> Self {
+		DistinguishabilityCheck {
+			threshold: threshold,
+			deficiencies: Vec::new(),
+			difference: ColorDifference::Ciede2000,
+		}
+	}
+
+	/// Adds a color vision deficiency to check under.
+	pub fn simulate(mut self, deficiency: Deficiency) -> Self {
+		if !self.deficiencies.contains(&deficiency) {
+			self.deficiencies.push(deficiency);
+		}
+		self
+	}
+
+	/// Sets the metric measuring the difference between colors.
+	pub fn difference(mut self, difference: ColorDifference) -> Self {
+		self.difference = difference;
+		self
+	}
+
+	/// Returns every pair of the given colors which differ by less than the
+	/// threshold, under normal vision and each simulated deficiency.
+	pub fn check(&self, colors: &[(Address, Color)]) -> Vec<ConflictPair> {
+		self.check_monitored(colors, &ProgressMonitor::new())
+			.expect("an unmonitored check cannot be cancelled")
+	}
+
+	/// Checks the given colors like [`check`], reporting progress to the 
+	/// given monitor. The progress restarts for each simulated deficiency.
+	///
+	/// # Errors
+	///
+	/// Returns a `Cancelled` error if the monitor's task is cancelled.
+	///
+	/// [`check`]: #method.check
+	pub fn check_monitored(
+		&self,
+		colors: &[(Address, Color)],
+		monitor: &ProgressMonitor)
+		-> Result<Vec<ConflictPair>>
+	{
+		let mut conflicts = Vec::new();
+		self.check_under(colors, None, &mut conflicts, monitor)?;
+
+		for &deficiency in &self.deficiencies {
+			let simulated: Vec<(Address, Color)> = colors.iter()
+				.map(|&(a, c)| (a, deficiency.simulate(c)))
+				.collect();
+			self.check_under(&simulated, Some(deficiency), &mut conflicts,
+				monitor)?;
+		}
+		Ok(conflicts)
+	}
+
+	/// Collects the conflicting pairs of the given colors.
+	fn check_under(
+		&self,
+		colors: &[(Address, Color)],
+		deficiency: Option<Deficiency>,
+		conflicts: &mut Vec<ConflictPair>,
+		monitor: &ProgressMonitor)
+		-> Result<()>
+	{
+		for (i, &(first, a)) in colors.iter().enumerate() {
+			monitor.report("checking", i, colors.len())?;
+			for &(second, b) in &colors[i + 1..] {
+				let delta_e = self.difference.difference(a, b);
+				if delta_e < self.threshold {
+					conflicts.push(ConflictPair {
+						first: first,
+						second: second,
+						delta_e: delta_e,
+						deficiency: deficiency,
+					});
+				}
+			}
+		}
+		Ok(())
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// ComparisonStep
+////////////////////////////////////////////////////////////////////////////////
+/// A pair of aligned colors sampled from two compared ramps.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ComparisonStep {
+	/// The position of the step along the ramps, in the range [0, 1].
+	pub position: f32,
+	/// The color sampled from the first ramp.
+	pub first: Color,
+	/// The color sampled from the second ramp.
+	pub second: Color,
+	/// The CIEDE2000 difference between the sampled colors.
+	pub delta_e: f32,
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// RampComparison
+////////////////////////////////////////////////////////////////////////////////
+/// A step-by-step comparison of two ramps.
+///
+/// Both ramps are resampled in Lab to a common length so that ramps of
+/// different lengths can be shown side by side. If the ramps run in opposite
+/// lightness directions, the second ramp is reversed to align with the first.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::Color;
+/// use palette::analysis::RampComparison;
+///
+/// let first = [Color::new(20, 20, 80), Color::new(200, 200, 250)];
+/// let second = [
+/// 	Color::new(202, 202, 250),
+/// 	Color::new(110, 110, 165),
+/// 	Color::new(21, 21, 80),
+/// ];
+///
+/// let comparison = RampComparison::compare(&first, &second);
+/// assert_eq!(comparison.steps.len(), 3);
+/// assert!(comparison.reversed);
+/// assert!(comparison.similarity > 0.8);
+/// assert!(comparison.is_near_duplicate(0.8));
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct RampComparison {
+	/// The aligned steps of the ramps.
+	pub steps: Vec<ComparisonStep>,
+	/// Whether the second ramp was reversed to align with the first.
+	pub reversed: bool,
+	/// The mean CIEDE2000 difference between aligned steps.
+	pub mean_delta_e: f32,
+	/// The largest CIEDE2000 difference between aligned steps.
+	pub max_delta_e: f32,
+	/// The similarity of the ramps, in the range (0, 1]. Identical ramps have
+	/// a similarity of 1, and the similarity halves when the mean difference
+	/// reaches 10.
+	pub similarity: f32,
+}
+
+
+impl RampComparison {
+	/// Compares the given ramps, resampling both to the length of the longer
+	/// ramp.
+	pub fn compare(first: &[Color], second: &[Color]) -> Self {
+		let len = first.len().max(second.len());
+		RampComparison::compare_with_len(first, second, len)
+	}
+
+	/// Compares the given ramps, resampling both to the given length.
+	pub fn compare_with_len(first: &[Color], second: &[Color], len: usize)
+		-> Self
+	{
+		if first.is_empty() || second.is_empty() || len == 0 {
+			return Default::default();
+		}
+
+		let first: Vec<Lab> = first.iter().map(|&c| Lab::from(c)).collect();
+		let mut second: Vec<Lab> = second.iter()
+			.map(|&c| Lab::from(c))
+			.collect();
+		let reversed = lightness_direction(&first)
+			* lightness_direction(&second) < 0.0;
+		if reversed { second.reverse(); }
+
+		let steps: Vec<ComparisonStep> = (0..len)
+			.map(|i| {
+				let position = if len == 1 { 
+					0.0
+				} else {
+					i as f32 / (len - 1) as f32
+				};
+				let a = sample(&first, position);
+				let b = sample(&second, position);
+				ComparisonStep {
+					position: position,
+					first: Color::from(a),
+					second: Color::from(b),
+					delta_e: delta_e_2000_lab(a, b),
+				}
+			})
+			.collect();
+
+		let mean = steps.iter().map(|s| s.delta_e).sum::<f32>()
+			/ steps.len() as f32;
+		let max = steps.iter().map(|s| s.delta_e).fold(0.0, f32::max);
+
+		RampComparison {
+			steps: steps,
+			reversed: reversed,
+			mean_delta_e: mean,
+			max_delta_e: max,
+			similarity: 1.0 / (1.0 + mean / SIMILARITY_SCALE),
+		}
+	}
+
+	/// Returns whether the ramps are at least as similar as the given 
+	/// minimum similarity.
+	pub fn is_near_duplicate(&self, min_similarity: f32) -> bool {
+		!self.steps.is_empty() && self.similarity >= min_similarity
+	}
+
+	/// Returns the indices and similarity of each pair of the given ramps
+	/// which are at least as similar as the given minimum similarity, most
+	/// similar first.
+	pub fn near_duplicates(ramps: &[Vec<Color>], min_similarity: f32)
+		-> Vec<(usize, usize, f32)>
+	{
+		let mut pairs = Vec::new();
+		for (i, first) in ramps.iter().enumerate() {
+			for (j, second) in ramps.iter().enumerate().skip(i + 1) {
+				let comparison = RampComparison::compare(first, second);
+				if comparison.is_near_duplicate(min_similarity) {
+					pairs.push((i, j, comparison.similarity));
+				}
+			}
+		}
+		pairs.sort_by(|a, b| b.2.partial_cmp(&a.2)
+			.expect("similarity is not NaN"));
+		pairs
+	}
+}
+
+
+/// The mean difference at which the similarity of two ramps is one half.
+const SIMILARITY_SCALE: f32 = 10.0;
+
+
+/// Returns the change in lightness from the start to the end of a ramp.
+fn lightness_direction(ramp: &[Lab]) -> f32 {
+	ramp[ramp.len() - 1].l - ramp[0].l
+}
+
+
+/// Returns the color at the given position along a ramp, interpolating 
+/// between its colors in Lab.
+fn sample(ramp: &[Lab], position: f32) -> Lab {
+	if ramp.len() == 1 { return ramp[0]; }
+
+	let scaled = position * (ramp.len() - 1) as f32;
+	let index = (scaled.floor() as usize).min(ramp.len() - 2);
+	ramp[index].lerp(&ramp[index + 1], scaled - index as f32)
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// ProvenanceReport
+////////////////////////////////////////////////////////////////////////////////
+/// The provenance of each cell of a palette, for auditing where its colors
+/// came from.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::analysis::ProvenanceReport;
+/// use palette::operation::InsertColor;
+///
+/// let mut pal = Palette::new("Example", Format::Default, true);
+/// pal.apply(Box::new(InsertColor::new(Color::new(1, 2, 3)))).unwrap();
+/// pal.apply(Box::new(InsertColor::new(Color::new(4, 5, 6)))).unwrap();
+///
+/// let report = ProvenanceReport::new(&pal);
+/// assert_eq!(report.counts().get("operation"), Some(&2));
+/// assert_eq!(report.from_operation(2), vec![Address::new(0, 0, 1)]);
+/// assert!(report.unknown().is_empty());
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ProvenanceReport {
+	/// The address and provenance of each cell, in address order.
+	pub cells: Vec<(Address, Option<Provenance>)>,
+}
+
+
+impl ProvenanceReport {
+	/// Creates a new `ProvenanceReport` for the cells of the given palette.
+	pub fn new(palette: &Palette) -> Self {
+		ProvenanceReport {
+			cells: palette.data.cells.keys()
+				.map(|&address| (address, palette.provenance(address).cloned()))
+				.collect(),
+		}
+	}
+
+	/// Returns the number of cells with each kind of provenance.
+	pub fn counts(&self) -> BTreeMap<&'static str, usize> {
+		let mut counts = BTreeMap::new();
+		for provenance in self.cells.iter().filter_map(|c| c.1.as_ref()) {
+			*counts.entry(provenance.kind()).or_insert(0) += 1;
+		}
+		counts
+	}
+
+	/// Returns the addresses of the cells whose provenance is unknown.
+	pub fn unknown(&self) -> Vec<Address> {
+		self.cells.iter()
+			.filter(|&&(_, ref provenance)| provenance.is_none())
+			.map(|&(address, _)| address)
+			.collect()
+	}
+
+	/// Returns the addresses of the cells last colored by the operation with
+	/// the given id.
+	pub fn from_operation(&self, id: u64) -> Vec<Address> {
+		self.cells.iter()
+			.filter(|&&(_, ref provenance)| match *provenance {
+				Some(Provenance::Operation {id: op, ..}) => op == id,
+				_ => false,
+			})
+			.map(|&(address, _)| address)
+			.collect()
+	}
+}
@@ -0,0 +1,116 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides conversion between colors and the xterm 256-color palette.
+//!
+//! The palette consists of the sixteen system colors, a 6×6×6 color cube at 
+//! indices 16 to 231, and a 24 step gray ramp at indices 232 to 255. The 
+//! system colors are usually redefined by terminal themes, so matching can be
+//! restricted to the cube and gray ramp, whose colors are fixed.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use space::Oklab;
+
+// Non-local imports.
+use color::Color;
+
+
+/// The channel levels of the xterm color cube.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+
+/// The xterm default system colors.
+const SYSTEM_COLORS: [(u8, u8, u8); 16] = [
+	(0, 0, 0),       (205, 0, 0),     (0, 205, 0),     (205, 205, 0),
+	(0, 0, 238),     (205, 0, 205),   (0, 205, 205),   (229, 229, 229),
+	(127, 127, 127), (255, 0, 0),     (0, 255, 0),     (255, 255, 0),
+	(92, 92, 255),   (255, 0, 255),   (0, 255, 255),   (255, 255, 255),
+];
+
+
+/// Returns the color of the given xterm 256-color index. The system colors 
+/// use the xterm defaults.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::ansi::ansi256_color;
+///
+/// assert_eq!(ansi256_color(1), Color::new(205, 0, 0));
+/// assert_eq!(ansi256_color(16), Color::new(0, 0, 0));
+/// assert_eq!(ansi256_color(208), Color::new(255, 135, 0));
+/// assert_eq!(ansi256_color(244), Color::new(128, 128, 128));
+/// ```
+pub fn ansi256_color(index: u8) -> Color {
+	match index {
+		0..=15 => {
+			let (r, g, b) = SYSTEM_COLORS[index as usize];
+			Color::new(r, g, b)
+		},
+		16..=231 => {
+			let i = (index - 16) as usize;
+			Color::new(
+				CUBE_LEVELS[i / 36],
+				CUBE_LEVELS[(i / 6) % 6],
+				CUBE_LEVELS[i % 6])
+		},
+		_ => {
+			let v = 8 + (index - 232) * 10;
+			Color::new(v, v, v)
+		},
+	}
+}
+
+
+/// Returns the xterm 256-color index perceptually nearest to the given color,
+/// measured in Oklab space. If `system_colors` is false, only the color cube
+/// and gray ramp are considered.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::ansi::nearest_ansi256;
+///
+/// assert_eq!(nearest_ansi256(Color::new(250, 130, 10), false), 208);
+/// assert_eq!(nearest_ansi256(Color::new(126, 126, 126), false), 244);
+/// assert_eq!(nearest_ansi256(Color::new(126, 126, 126), true), 8);
+/// ```
+pub fn nearest_ansi256(color: Color, system_colors: bool) -> u8 {
+	let first = if system_colors { 0 } else { 16 };
+	let candidates: Vec<Oklab> = (first..256)
+		.map(|index| Oklab::from(ansi256_color(index as u8)))
+		.collect();
+	let nearest = Oklab::from(color).nearest(&candidates).unwrap_or(0);
+	(first + nearest) as u8
+}
+
+
+/// Returns the given color constrained to the xterm 256-color palette.
+pub fn to_ansi256(color: Color, system_colors: bool) -> Color {
+	ansi256_color(nearest_ansi256(color, system_colors))
+}
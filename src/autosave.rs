@@ -0,0 +1,247 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides periodic saving of palettes to rotating numbered backups.
+//!
+//! An `Autosave` should be notified each time an operation is applied to the
+//! palette it protects. When its trigger condition is met, it writes the
+//! palette in the default format to `<stem>.1.bak` in its backup directory,
+//! shifting older backups to higher numbers and removing any beyond the
+//! configured limit.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use ::Palette;
+use format::default;
+
+// Standard imports.
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::io::{
+	BufWriter,
+	Write,
+};
+use std::path::{
+	Path,
+	PathBuf,
+};
+use std::time::{
+	Duration,
+	Instant,
+	SystemTime,
+};
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// AutosaveTrigger
+////////////////////////////////////////////////////////////////////////////////
+/// The condition under which an `Autosave` writes a new backup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AutosaveTrigger {
+	/// Save when at least the given time has passed since the last save.
+	Interval(Duration),
+	/// Save after the given number of operations.
+	Operations(usize),
+}
+
+
+impl Default for AutosaveTrigger {
+	fn default() -> Self {
+		AutosaveTrigger::Operations(20)
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Backup
+////////////////////////////////////////////////////////////////////////////////
+/// Describes a backup file written by an `Autosave`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Backup {
+	/// The path of the backup file.
+	pub path: PathBuf,
+	/// The backup number. Lower numbers are more recent, starting from 1.
+	pub number: usize,
+	/// The time the backup was last modified.
+	pub modified: SystemTime,
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Autosave
+////////////////////////////////////////////////////////////////////////////////
+/// Writes numbered palette backups when a trigger condition is met.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use palette::*;
+/// use palette::autosave::{Autosave, AutosaveTrigger};
+/// use palette::operation::*;
+///
+/// let mut pal = Palette::new("Example", Format::Default, true);
+/// let mut autosave = Autosave::new("backups", "example")
+/// 	.trigger(AutosaveTrigger::Operations(10))
+/// 	.keep(3);
+///
+/// pal.apply(Box::new(InsertColor::new(Color::new(12, 50, 78)))).unwrap();
+/// autosave.notify(&pal).unwrap();
+///
+/// let latest = autosave.backups().unwrap()[0].number;
+/// let restored = autosave.restore(latest).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct Autosave {
+	/// The directory to write backups to.
+	directory: PathBuf,
+	/// The file name stem of the backups.
+	stem: String,
+	/// The condition under which to save.
+	trigger: AutosaveTrigger,
+	/// The number of backups to keep.
+	keep: usize,
+	/// The time of the last save.
+	last_save: Instant,
+	/// The number of operations since the last save.
+	operations: usize,
+}
+
+
+impl Autosave {
+	/// Creates a new `Autosave` writing backups with the given file name stem
+	/// to the given directory.
+	pub fn new<P, S>(directory: P, stem: S) -> Self
+		where P: Into<PathBuf>, S: Into<String>
+	{
+		Autosave {
+			directory: directory.into(),
+			stem: stem.into(),
+			trigger: Default::default(),
+			keep: 5,
+			last_save: Instant::now(),
+			operations: 0,
+		}
+	}
+
+	/// Sets the condition under which to save.
+	pub fn trigger(mut self, trigger: AutosaveTrigger) -> Self {
+		self.trigger = trigger;
+		self
+	}
+
+	/// Sets the number of backups to keep. At least one backup is always kept.
+	pub fn keep(mut self, keep: usize) -> Self {
+		self.keep = keep.max(1);
+		self
+	}
+
+	/// Records that an operation was applied to the given palette, and saves
+	/// it if the trigger condition is met. Returns the path of the new backup
+	/// if one was written.
+	pub fn notify(&mut self, palette: &Palette) -> io::Result<Option<PathBuf>> {
+		self.operations += 1;
+		let due = match self.trigger {
+			AutosaveTrigger::Interval(interval)
+				=> self.last_save.elapsed() >= interval,
+			AutosaveTrigger::Operations(count)
+				=> self.operations >= count,
+		};
+
+		if due {
+			self.save(palette).map(Some)
+		} else {
+			Ok(None)
+		}
+	}
+
+	/// Saves the given palette as the most recent backup, rotating older 
+	/// backups. Returns the path of the new backup.
+	pub fn save(&mut self, palette: &Palette) -> io::Result<PathBuf> {
+		fs::create_dir_all(&self.directory)?;
+
+		// Write to a temporary file first so a failed write leaves the
+		// existing backups intact.
+		let temp = self.directory.join(format!("{}.tmp", self.stem));
+		{
+			let mut out = BufWriter::new(File::create(&temp)?);
+			default::write_palette(palette, &mut out)?;
+			out.flush()?;
+			out.get_ref().sync_all()?;
+		}
+
+		// Rotate the existing backups.
+		let oldest = self.path(self.keep);
+		if oldest.exists() { fs::remove_file(&oldest)?; }
+		for number in (1..self.keep).rev() {
+			let path = self.path(number);
+			if path.exists() {
+				fs::rename(&path, self.path(number + 1))?;
+			}
+		}
+
+		let latest = self.path(1);
+		fs::rename(&temp, &latest)?;
+		self.last_save = Instant::now();
+		self.operations = 0;
+		Ok(latest)
+	}
+
+	/// Returns the existing backups, from most to least recent.
+	pub fn backups(&self) -> io::Result<Vec<Backup>> {
+		let mut backups = Vec::new();
+		for number in 1..(self.keep + 1) {
+			let path = self.path(number);
+			if let Ok(meta) = fs::metadata(&path) {
+				backups.push(Backup {
+					path: path,
+					number: number,
+					modified: meta.modified()?,
+				});
+			}
+		}
+		Ok(backups)
+	}
+
+	/// Reads the palette stored in the backup with the given number.
+	pub fn restore(&self, number: usize) -> io::Result<Palette> {
+		restore(self.path(number))
+	}
+
+	/// Returns the path of the backup with the given number.
+	fn path(&self, number: usize) -> PathBuf {
+		self.directory.join(format!("{}.{}.bak", self.stem, number))
+	}
+}
+
+
+/// Reads the palette stored in the backup file at the given path.
+pub fn restore<P>(path: P) -> io::Result<Palette> where P: AsRef<Path> {
+	let mut file = File::open(path)?;
+	default::read_palette(&mut file)
+}
@@ -1,17 +1,1022 @@
 
 
-extern crate color;
 extern crate palette;
+#[macro_use]
+extern crate serde_json;
+
+use palette::{
+	Address,
+	Color,
+	Format,
+	Palette,
+};
+use palette::eval::{
+	Evaluator,
+	Notation,
+	Value,
+};
+use palette::lint::{
+	Linter,
+	Severity,
+};
+use palette::export::{
+	ExportFormat,
+	Manifest,
+	ThemeRoles,
+};
+use palette::format::merge::ThreeWayMerge;
+use palette::import::{
+	is_url,
+	read_share_url,
+	share_url_colors,
+	ImportFormat,
+};
+#[cfg(feature = "net")]
+use palette::import::read_url;
+use palette::select::Selector;
+#[cfg(feature = "server")]
+use palette::server::PaletteServer;
+#[cfg(feature = "signing")]
+use palette::sign::{
+	sign_file,
+	verify_bytes,
+	verify_file,
+	Ed25519Signer,
+	Ed25519Verifier,
+};
+use palette::space::DisplayModel;
+use palette::utilities::glob_matches;
+
+use serde_json::Value as Json;
+
+use std::env;
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::io::BufWriter;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::mpsc;
+use std::thread;
+#[cfg(feature = "server")]
+use std::time::Duration;
+
+
+/// The command line usage message.
+const USAGE: &'static str = "\
+usage: palette color <expression> [--space <notation>] [--palette <file>]
+       palette select <selector> --palette <file> [--space <notation>]
+       palette lint <file> --rules <file>
+       palette convert --from-dir <dir> --to-dir <dir> --to-format <format>
+               [--glob <pattern>] [--jobs <count>] [--manifest <file>]
+               [--toned] [--display <model>]
+       palette export <file> --to-format <format> --output <file>
+               [--manifest <file>] [--toned] [--display <model>]
+               [--sign-key <file>] [--roles <file>]
+       palette import <file | url> --output <file> [--from-format <format>]
+               [--verify-key <file>]
+       palette check --manifest <file>
+       palette fmt <file>... [--check]
+       palette merge-driver <base> <ours> <theirs> [--output <file>]
+       palette verify <file> --key <file>
+       palette serve <file> [--address <address>]
+
+Every command accepts --json to print its result as JSON.
+
+commands:
+    color    evaluate a color expression and print the result
+    select   print the addresses and colors of the selected cells
+    lint     check a palette against a rule file, failing if any errors occur
+    convert  convert every palette file in a directory to another format
+    export   export a palette file to another format
+    import   import a palette file or http(s) URL from another format; the
+             format is detected from the name and contents if not given, and
+             colors in sharing URLs such as coolors.co links are read
+             directly
+    check    verify that the exports in a manifest are up to date
+    fmt      rewrite palette files in canonical form; comments are removed
+    merge-driver
+             merge the changes made to a base palette by two other palettes,
+             writing the result over ours; for use as a git merge driver
+    verify   check a file against its detached signature
+    serve    serve a palette over HTTP for remote editing, saving it after
+             each change
+
+options:
+    --space      the notation of the result: hex, rgb, hsl, hsluv, lab, or
+                 oklab
+    --palette    a palette file whose named cells can be used in expressions,
+                 or the palette to select cells from
+    --rules      a lint rule file
+    --from-dir   the directory of palette files to convert, recursively
+    --to-dir     the directory to write converted files to
+    --to-format  the format to convert to: palette, c, rust, godot-palette,
+                 godot-gradient, unity, gamemaker, html, aco, tokens,
+                 style-dictionary, design-tokens, figma, sketch, vim,
+                 vscode, base16, xresources, alacritty, kitty,
+                 windows-terminal, or png
+    --from-format
+                 the format to import from: palette, figma, sketch, base16,
+                 indexed (GIF, PNG, or BMP color tables), or ora
+    --glob       a pattern the converted file names must match
+    --jobs       the number of files to convert in parallel
+    --manifest   an export manifest recording the source and exported files
+    --roles      a theme role mapping file, with one 'role = cell' line per
+                 role, for vim, vscode, and terminal exports
+    --toned      apply the palette's tone curve to the exported colors
+    --display    simulate the exported colors on a display: crt, lcd, or dmg;
+                 implies --toned
+    --check      report files which are not in canonical form without
+                 rewriting them
+    --output     the file to export, import, or merge to
+    --sign-key   a file holding an Ed25519 secret key as hex text, used to
+                 write a detached signature beside the exported file
+    --key        a file holding an Ed25519 public key as hex text
+    --verify-key a file holding an Ed25519 public key as hex text, used to
+                 check an imported file against its detached signature
+    --address    the address to serve on; 127.0.0.1:7878 by default
+    --json       print results and errors as JSON objects
+
+exit status:
+    0    success
+    1    invalid arguments or unreadable input
+    2    lint errors were found, some files failed to convert, exports
+         are out of date, files are not in canonical form, a merge has
+         conflicts, or a signature does not match";
+
+
+/// The message reported when signing is requested without signing support.
+#[cfg(not(feature = "signing"))]
+const UNSIGNED: &'static str = 
+	"signing requires palette to be built with the 'signing' feature";
+
+
+/// The message reported when a URL import is requested without network 
+/// support.
+#[cfg(not(feature = "net"))]
+const UNNETWORKED: &'static str = 
+	"importing from URLs requires palette to be built with the 'net' feature";
+
+
+/// The message reported when serving is requested without server support.
+#[cfg(not(feature = "server"))]
+const UNSERVED: &'static str = 
+	"serving requires palette to be built with the 'server' feature";
+
+
+/// The exit status for invalid arguments or unreadable input.
+const EXIT_INVALID: i32 = 1;
+
+/// The exit status for commands which ran but reported failures.
+const EXIT_FAILED: i32 = 2;
+
+
+/// The reason a command failed.
+struct Failure {
+	/// The exit status of the failure.
+	status: i32,
+	/// A description of the failure.
+	message: String,
+}
+
+
+impl From<String> for Failure {
+	fn from(message: String) -> Self {
+		Failure {status: EXIT_INVALID, message: message}
+	}
+}
+
+
+impl<'a> From<&'a str> for Failure {
+	fn from(message: &'a str) -> Self {
+		Failure::from(message.to_owned())
+	}
+}
+
+
+/// The result of a command.
+type CommandResult = Result<(), Failure>;
 
-use palette::*;
-use palette::operation::*;
 
 pub fn main() {
-	let mut pal = Palette::new("test", Format::Default, true);
+	let mut args: Vec<String> = env::args().skip(1).collect();
+	let json = args.iter().any(|a| a == "--json");
+	args.retain(|a| a != "--json");
+
+	let result = match args.first().map(|a| &a[..]) {
+		Some("color") => color(&args[1..], json),
+		Some("select") => select(&args[1..], json),
+		Some("lint") => lint(&args[1..], json),
+		Some("convert") => convert(&args[1..], json),
+		Some("export") => export(&args[1..], json),
+		Some("import") => import(&args[1..], json),
+		Some("check") => check(&args[1..], json),
+		Some("fmt") => fmt(&args[1..], json),
+		Some("merge-driver") => merge_driver(&args[1..], json),
+		Some("verify") => verify(&args[1..], json),
+		Some("serve") => serve(&args[1..], json),
+		_ => Err(USAGE.into()),
+	};
+
+	if let Err(failure) = result {
+		if json {
+			eprintln!("{}", json!({"error": failure.message}));
+		} else {
+			eprintln!("{}", failure.message);
+		}
+		process::exit(failure.status);
+	}
+}
+
+
+/// Evaluates a color expression and prints the result.
+fn color(args: &[String], json: bool) -> CommandResult {
+	let mut expression = None;
+	let mut notation = Notation::default();
+	let mut evaluator = Evaluator::new();
+
+	let mut args = args.iter();
+	while let Some(arg) = args.next() {
+		match &arg[..] {
+			"--space" => {
+				let name = args.next().ok_or("missing notation")?;
+				notation = Notation::from_name(name)
+					.ok_or_else(|| format!("unknown notation '{}'", name))?;
+			},
+			"--palette" => {
+				let path = args.next().ok_or("missing palette file")?;
+				evaluator = evaluator.palette(&read_palette(path)?);
+			},
+			_ if expression.is_none() => expression = Some(arg),
+			_ => return Err(USAGE.into()),
+		}
+	}
+
+	let expression = expression.ok_or(USAGE)?;
+	let value = evaluator.eval(expression).map_err(|e| e.show(expression))?;
+	match value {
+		Value::Color(color) if json => println!("{}", json!({
+			"type": "color",
+			"value": notation.format(color),
+			"rgb": [color.red(), color.green(), color.blue()],
+		})),
+		Value::Number(quantity) if json => println!("{}", json!({
+			"type": "number",
+			"value": quantity.value,
+			"unit": quantity.unit.suffix(),
+		})),
+		Value::Color(color) => println!("{}", notation.format(color)),
+		value => println!("{}", value),
+	}
+	Ok(())
+}
+
+
+/// Prints the addresses and colors of the cells selected from a palette.
+fn select(args: &[String], json: bool) -> CommandResult {
+	let mut selector = None;
+	let mut notation = Notation::default();
+	let mut palette = None;
+
+	let mut args = args.iter();
+	while let Some(arg) = args.next() {
+		match &arg[..] {
+			"--space" => {
+				let name = args.next().ok_or("missing notation")?;
+				notation = Notation::from_name(name)
+					.ok_or_else(|| format!("unknown notation '{}'", name))?;
+			},
+			"--palette" => {
+				let path = args.next().ok_or("missing palette file")?;
+				palette = Some(read_palette(path)?);
+			},
+			_ if selector.is_none() => selector = Some(arg),
+			_ => return Err(USAGE.into()),
+		}
+	}
+
+	let text = selector.ok_or(USAGE)?;
+	let palette = palette.ok_or("missing palette file")?;
+	let selector = Selector::parse(text).map_err(|e| e.show(text))?;
+	let cells = palette.colors(&selector.select(&palette));
+	if json {
+		let cells: Vec<_> = cells.into_iter()
+			.map(|(address, color)| cell_json(&palette, address, color, notation))
+			.collect();
+		println!("{}", json!({"cells": cells}));
+		return Ok(());
+	}
+
+	for (address, color) in cells {
+		match palette.cell_name(address) {
+			Some(name) => println!("{}\t{}\t{}", 
+				address, notation.format(color), name),
+			None => println!("{}\t{}", address, notation.format(color)),
+		}
+	}
+	Ok(())
+}
+
+
+/// Checks a palette against a rule file and prints the diagnostics.
+fn lint(args: &[String], json: bool) -> CommandResult {
+	let mut palette = None;
+	let mut linter = None;
+
+	let mut args = args.iter();
+	while let Some(arg) = args.next() {
+		match &arg[..] {
+			"--rules" => {
+				let path = args.next().ok_or("missing rule file")?;
+				linter = Some(File::open(path)
+					.and_then(|mut file| Linter::read(&mut file))
+					.map_err(|e| format!("{}: {}", path, e))?);
+			},
+			_ if palette.is_none() => palette = Some(read_palette(arg)?),
+			_ => return Err(USAGE.into()),
+		}
+	}
+
+	let palette = palette.ok_or(USAGE)?;
+	let linter = linter.ok_or("missing rule file")?;
+	let report = linter.lint(&palette);
+	if json {
+		let diagnostics: Vec<_> = report.diagnostics.iter()
+			.map(|d| json!({
+				"rule": d.rule,
+				"severity": d.severity.name(),
+				"address": d.address.map(|a| a.to_string()),
+				"message": d.message,
+			}))
+			.collect();
+		println!("{}", json!({
+			"errors": report.count(Severity::Error),
+			"warnings": report.count(Severity::Warning),
+			"diagnostics": diagnostics,
+		}));
+	} else {
+		for diagnostic in &report.diagnostics {
+			println!("{}", diagnostic);
+		}
+	}
+
+	let message = match report.count(Severity::Error) {
+		0 => return Ok(()),
+		1 => "lint failed with 1 error".to_owned(),
+		n => format!("lint failed with {} errors", n),
+	};
+	Err(Failure {status: EXIT_FAILED, message: message})
+}
+
+
+/// Converts the palette files in a directory to another format.
+fn convert(args: &[String], json: bool) -> CommandResult {
+	let mut from_dir = None;
+	let mut to_dir = None;
+	let mut format = None;
+	let mut pattern = "*".to_owned();
+	let mut jobs = thread::available_parallelism().map_or(1, |n| n.get());
+	let mut manifest_path = None;
+	let mut toned = false;
+	let mut display = None;
+
+	let mut args = args.iter();
+	while let Some(arg) = args.next() {
+		let mut value = || args.next().ok_or(USAGE);
+		match &arg[..] {
+			"--from-dir" => from_dir = Some(PathBuf::from(value()?)),
+			"--to-dir" => to_dir = Some(PathBuf::from(value()?)),
+			"--to-format" => {
+				let name = value()?;
+				format = Some(ExportFormat::from_name(name)
+					.ok_or_else(|| format!("unknown format '{}'", name))?);
+			},
+			"--glob" => pattern = value()?.clone(),
+			"--jobs" => jobs = value()?.parse()
+				.map_err(|_| "invalid job count".to_owned())?,
+			"--manifest" => manifest_path = Some(PathBuf::from(value()?)),
+			"--toned" => toned = true,
+			"--display" => {
+				let name = value()?;
+				display = Some(DisplayModel::from_name(name)
+					.ok_or_else(|| format!("unknown display '{}'", name))?);
+			},
+			_ => return Err(USAGE.into()),
+		}
+	}
+
+	let from_dir = from_dir.ok_or("missing source directory")?;
+	let to_dir = to_dir.ok_or("missing destination directory")?;
+	let format = format.ok_or("missing destination format")?;
+
+	let mut sources = Vec::new();
+	find_files(&from_dir, &pattern, &mut sources)
+		.map_err(|e| format!("{}: {}", from_dir.display(), e))?;
+	sources.sort();
+	let total = sources.len();
+
+	// Distribute the files across worker threads.
+	let queue = Arc::new(Mutex::new(sources.into_iter()));
+	let (sender, receiver) = mpsc::channel();
+	let workers: Vec<_> = (0..jobs.max(1))
+		.map(|_| {
+			let queue = queue.clone();
+			let sender = sender.clone();
+			let (from_dir, to_dir) = (from_dir.clone(), to_dir.clone());
+			thread::spawn(move || loop {
+				let source = match queue.lock().ok().and_then(|mut q| q.next()) {
+					Some(source) => source,
+					None => break,
+				};
+				let target = to_dir
+					.join(source.strip_prefix(&from_dir).unwrap_or(&source))
+					.with_extension(format.extension());
+				let result = convert_file(
+					&source,
+					&target,
+					format,
+					&ThemeRoles::new(),
+					toned,
+					display);
+				if sender.send((source, target, result)).is_err() { break; }
+			})
+		})
+		.collect();
+	drop(sender);
+
+	let mut results: Vec<_> = receiver.iter().collect();
+	for worker in workers {
+		worker.join().map_err(|_| "conversion thread panicked")?;
+	}
+	results.sort_by(|a, b| a.0.cmp(&b.0));
+
+	if let Some(path) = manifest_path {
+		let mut manifest = read_manifest(&path)?;
+		for &(ref source, ref target, ref result) in &results {
+			if result.is_err() { continue; }
+			manifest.record(source, target, format)
+				.map_err(|e| format!("{}: {}", target.display(), e))?;
+		}
+		write_manifest(&path, &manifest)?;
+	}
+
+	let failed = results.iter().filter(|r| r.2.is_err()).count();
+	if json {
+		let files: Vec<_> = results.iter()
+			.map(|&(ref source, ref target, ref result)| json!({
+				"source": source.display().to_string(),
+				"target": target.display().to_string(),
+				"error": result.as_ref().err(),
+			}))
+			.collect();
+		println!("{}", json!({
+			"converted": total - failed,
+			"failed": failed,
+			"files": files,
+		}));
+	} else {
+		for &(ref source, _, ref result) in &results {
+			if let Err(ref e) = *result {
+				println!("{}: {}", source.display(), e);
+			}
+		}
+		println!("converted {} of {} files", total - failed, total);
+	}
+
+	if failed == 0 { return Ok(()); }
+	Err(Failure {
+		status: EXIT_FAILED, 
+		message: format!("{} of {} files failed to convert", failed, total),
+	})
+}
+
+
+/// Collects the files in the given directory and its subdirectories whose
+/// names match the given pattern.
+fn find_files(dir: &Path, pattern: &str, files: &mut Vec<PathBuf>) 
+	-> io::Result<()>
+{
+	for entry in fs::read_dir(dir)? {
+		let path = entry?.path();
+		if path.is_dir() {
+			find_files(&path, pattern, files)?;
+		} else if path.file_name()
+			.and_then(|name| name.to_str())
+			.map_or(false, |name| glob_matches(pattern, name))
+		{
+			files.push(path);
+		}
+	}
+	Ok(())
+}
+
+
+/// Converts the palette file at the source path, writing it to the target 
+/// path in the given format, optionally with its tone curve and a display
+/// simulation applied.
+fn convert_file(
+	source: &Path,
+	target: &Path,
+	format: ExportFormat,
+	roles: &ThemeRoles,
+	toned: bool,
+	display: Option<DisplayModel>)
+	-> Result<(), String>
+{
+	let mut palette = File::open(source)
+		.and_then(|mut file| Format::Default.read_palette(&mut file))
+		.map_err(|e| e.to_string())?;
+	if let Some(display) = display {
+		palette = palette.simulated(display).map_err(|e| e.to_string())?;
+	} else if toned {
+		palette = palette.toned().map_err(|e| e.to_string())?;
+	}
+	if let Some(parent) = target.parent() {
+		fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+	}
+	let mut out = BufWriter::new(File::create(target)
+		.map_err(|e| e.to_string())?);
+	format.write_with_roles(&palette, roles, &mut out)
+		.and_then(|_| out.flush())
+		.map_err(|e| e.to_string())
+}
+
+
+/// Exports a palette file to another format, recording it in a manifest.
+fn export(args: &[String], json: bool) -> CommandResult {
+	let mut source = None;
+	let mut target = None;
+	let mut format = None;
+	let mut manifest_path = None;
+	let mut toned = false;
+	let mut display = None;
+	let mut sign_key = None;
+	let mut roles_path = None;
+
+	let mut args = args.iter();
+	while let Some(arg) = args.next() {
+		let mut value = || args.next().ok_or(USAGE);
+		match &arg[..] {
+			"--to-format" => {
+				let name = value()?;
+				format = Some(ExportFormat::from_name(name)
+					.ok_or_else(|| format!("unknown format '{}'", name))?);
+			},
+			"--output" => target = Some(PathBuf::from(value()?)),
+			"--sign-key" => sign_key = Some(PathBuf::from(value()?)),
+			"--roles" => roles_path = Some(PathBuf::from(value()?)),
+			"--manifest" => manifest_path = Some(PathBuf::from(value()?)),
+			"--toned" => toned = true,
+			"--display" => {
+				let name = value()?;
+				display = Some(DisplayModel::from_name(name)
+					.ok_or_else(|| format!("unknown display '{}'", name))?);
+			},
+			_ if source.is_none() => source = Some(PathBuf::from(arg)),
+			_ => return Err(USAGE.into()),
+		}
+	}
+
+	let source = source.ok_or(USAGE)?;
+	let target = target.ok_or("missing output file")?;
+	let format = format.ok_or("missing destination format")?;
+	if roles_path.is_some() && manifest_path.is_some() {
+		return Err("role mappings cannot be recorded in a manifest".into());
+	}
+	let roles = match roles_path {
+		Some(ref path) => File::open(path)
+			.and_then(|mut file| ThemeRoles::read(&mut file))
+			.map_err(|e| format!("{}: {}", path.display(), e))?,
+		None => ThemeRoles::new(),
+	};
+	let mut manifest = match manifest_path {
+		Some(ref path) => read_manifest(path)?,
+		None => Manifest::new(),
+	};
+	convert_file(&source, &target, format, &roles, toned, display)
+		.map_err(|e| format!("{}: {}", source.display(), e))?;
+	manifest.record(&source, &target, format)
+		.map_err(|e| format!("{}: {}", target.display(), e))?;
+	if let Some(ref path) = manifest_path {
+		write_manifest(path, &manifest)?;
+	}
+	let signature = match sign_key {
+		Some(ref key) => Some(sign_export(&target, key)?),
+		None => None,
+	};
+
+	if json {
+		println!("{}", json!({
+			"source": source.display().to_string(),
+			"target": target.display().to_string(),
+			"format": format.name(),
+			"toned": toned || display.is_some(),
+			"display": display.map(|d| d.name()),
+			"signature": signature.map(|p| p.display().to_string()),
+		}));
+	}
+	Ok(())
+}
+
+
+/// Imports a file from another format as a palette file. The palette is named
+/// after the source file if the format does not store a name.
+fn import(args: &[String], json: bool) -> CommandResult {
+	let mut source = None;
+	let mut target = None;
+	let mut format = None;
+	let mut verify_key = None;
+
+	let mut args = args.iter();
+	while let Some(arg) = args.next() {
+		let mut value = || args.next().ok_or(USAGE);
+		match &arg[..] {
+			"--from-format" => {
+				let name = value()?;
+				format = Some(ImportFormat::from_name(name)
+					.ok_or_else(|| format!("unknown format '{}'", name))?);
+			},
+			"--output" => target = Some(PathBuf::from(value()?)),
+			"--verify-key" => verify_key = Some(PathBuf::from(value()?)),
+			_ if source.is_none() => source = Some(PathBuf::from(arg)),
+			_ => return Err(USAGE.into()),
+		}
+	}
+
+	let source = source.ok_or(USAGE)?;
+	let target = target.ok_or("missing output file")?;
+	let remote = is_url(&source.to_string_lossy());
+	if verify_key.is_some() && remote {
+		return Err("signatures can only be verified for local files".into());
+	}
+	let shared = remote && format.is_none() 
+		&& share_url_colors(&source.to_string_lossy()).is_some();
+	let palette = if shared {
+		read_share_url(&source.to_string_lossy())
+			.map_err(|e| format!("{}: {}", source.display(), e))?
+	} else if remote {
+		let (palette, detected) = read_url_source(&source, format)?;
+		format = Some(detected);
+		palette
+	} else {
+		let bytes = fs::read(&source)
+			.map_err(|e| format!("{}: {}", source.display(), e))?;
+		if let Some(ref key) = verify_key {
+			verify_import(&source, &bytes, key).map_err(|message| Failure {
+				status: EXIT_FAILED,
+				message: format!("{}: {}", source.display(), message),
+			})?;
+		}
+		let path = source.to_string_lossy();
+		format = format
+			.or_else(|| ImportFormat::from_extension(&path))
+			.or_else(|| ImportFormat::sniff(&bytes));
+		let name = source.file_stem()
+			.map_or_else(String::new, |s| s.to_string_lossy().into_owned());
+		format.ok_or_else(|| "unrecognized source format".to_owned())
+			.and_then(|format| format.read(&name, &mut &bytes[..])
+				.map_err(|e| e.to_string()))
+			.map_err(|e| format!("{}: {}", source.display(), e))?
+	};
+	File::create(&target)
+		.and_then(|file| {
+			let mut out = BufWriter::new(file);
+			Format::Default.write_palette(&palette, &mut out)?;
+			out.flush()
+		})
+		.map_err(|e| format!("{}: {}", target.display(), e))?;
+
+	if json {
+		println!("{}", json!({
+			"source": source.display().to_string(),
+			"target": target.display().to_string(),
+			"format": format.map(|f| f.name()),
+			"colors": palette.len(),
+			"verified": verify_key.is_some(),
+		}));
+	}
+	Ok(())
+}
+
+
+/// Downloads and reads a palette from the given URL, returning it along with
+/// the format it was read as.
+#[cfg(feature = "net")]
+fn read_url_source(source: &Path, format: Option<ImportFormat>) 
+	-> Result<(Palette, ImportFormat), String>
+{
+	let url = source.to_string_lossy();
+	read_url(&url, format).map_err(|e| format!("{}: {}", url, e))
+}
+
+
+/// Reports that URL imports are not supported.
+#[cfg(not(feature = "net"))]
+fn read_url_source(_: &Path, _: Option<ImportFormat>) 
+	-> Result<(Palette, ImportFormat), String>
+{
+	Err(UNNETWORKED.into())
+}
+
+
+/// Verifies that the exports recorded in a manifest are up to date.
+fn check(args: &[String], json: bool) -> CommandResult {
+	let path = match args {
+		[ref option, ref path] if option == "--manifest" => Path::new(path),
+		_ => return Err(USAGE.into()),
+	};
+	let manifest = File::open(path)
+		.and_then(|mut file| Manifest::read(&mut file))
+		.map_err(|e| format!("{}: {}", path.display(), e))?;
+	let drifted = manifest.check();
+
+	if json {
+		let files: Vec<_> = drifted.iter()
+			.map(|&(ref target, drift)| json!({
+				"target": target.display().to_string(),
+				"drift": drift.to_string(),
+			}))
+			.collect();
+		println!("{}", json!({
+			"checked": manifest.entries().len(),
+			"drifted": files,
+		}));
+	} else {
+		for &(ref target, drift) in &drifted {
+			println!("{}: {}", target.display(), drift);
+		}
+	}
+
+	if drifted.is_empty() { return Ok(()); }
+	Err(Failure {
+		status: EXIT_FAILED,
+		message: format!("{} of {} exports are out of date", 
+			drifted.len(), manifest.entries().len()),
+	})
+}
+
+
+/// Rewrites palette files in canonical form, or with `--check`, reports the 
+/// files which are not in canonical form.
+fn fmt(args: &[String], json: bool) -> CommandResult {
+	let check = args.iter().any(|a| a == "--check");
+	let paths: Vec<_> = args.iter().filter(|a| *a != "--check").collect();
+	if paths.is_empty() { return Err(USAGE.into()); }
+
+	let mut changed = Vec::new();
+	for path in &paths {
+		let original = fs::read(path).map_err(|e| format!("{}: {}", path, e))?;
+		let palette = Format::Default.read_palette(&mut &original[..])
+			.map_err(|e| format!("{}: {}", path, e))?;
+		let mut canonical = Vec::new();
+		Format::Default.write_palette(&palette, &mut canonical)
+			.map_err(|e| format!("{}: {}", path, e))?;
+
+		if canonical == original { continue; }
+		if !check {
+			fs::write(path, &canonical)
+				.map_err(|e| format!("{}: {}", path, e))?;
+		}
+		changed.push(path);
+	}
+
+	if json {
+		let key = if check { "unformatted" } else { "formatted" };
+		let mut result = json!({"checked": paths.len()});
+		result[key] = json!(changed);
+		println!("{}", result);
+	} else {
+		for path in &changed {
+			println!("{}", path);
+		}
+	}
+
+	if !check || changed.is_empty() { return Ok(()); }
+	Err(Failure {
+		status: EXIT_FAILED,
+		message: format!("{} of {} files are not in canonical form",
+			changed.len(), paths.len()),
+	})
+}
+
+
+/// Merges the changes made to a base palette by two other palettes, writing
+/// the result over our palette or to the given output file.
+fn merge_driver(args: &[String], json: bool) -> CommandResult {
+	let mut paths = Vec::new();
+	let mut output = None;
+
+	let mut args = args.iter();
+	while let Some(arg) = args.next() {
+		match &arg[..] {
+			"--output" => output = Some(args.next().ok_or(USAGE)?),
+			_ => paths.push(arg),
+		}
+	}
+	let (base, ours, theirs) = match paths[..] {
+		[base, ours, theirs] => (base, ours, theirs),
+		_ => return Err(USAGE.into()),
+	};
+
+	let merge = ThreeWayMerge::new(
+			&read_palette(base)?,
+			&read_palette(ours)?,
+			&read_palette(theirs)?)
+		.map_err(|e| e.to_string())?;
+	let output = output.unwrap_or(ours);
+	File::create(output)
+		.and_then(|file| {
+			let mut out = BufWriter::new(file);
+			merge.write(&mut out)?;
+			out.flush()
+		})
+		.map_err(|e| format!("{}: {}", output, e))?;
+
+	let conflicts = merge.conflicts();
+	if json {
+		let keys: Vec<_> = conflicts.iter().map(|c| &c.key).collect();
+		println!("{}", json!({"output": output, "conflicts": keys}));
+	} else {
+		for conflict in &conflicts {
+			println!("conflict: {}", conflict.key);
+		}
+	}
+
+	if conflicts.is_empty() { return Ok(()); }
+	Err(Failure {
+		status: EXIT_FAILED,
+		message: format!("merge has {} conflicts", conflicts.len()),
+	})
+}
+
+
+/// Checks a file against its detached signature.
+fn verify(args: &[String], json: bool) -> CommandResult {
+	let (path, key) = match args {
+		[ref path, ref option, ref key] if option == "--key" => (path, key),
+		_ => return Err(USAGE.into()),
+	};
+	let result = verify_signature(Path::new(path), Path::new(key));
+
+	if json {
+		println!("{}", json!({
+			"file": path,
+			"verified": result.is_ok(),
+			"reason": result.as_ref().err(),
+		}));
+	}
+	result.map_err(|message| Failure {
+		status: EXIT_FAILED,
+		message: format!("{}: {}", path, message),
+	})
+}
+
+
+/// Serves a palette over HTTP, saving it after each change.
+#[cfg(feature = "server")]
+fn serve(args: &[String], json: bool) -> CommandResult {
+	let mut path = None;
+	let mut address = "127.0.0.1:7878";
+
+	let mut args = args.iter();
+	while let Some(arg) = args.next() {
+		match &arg[..] {
+			"--address" => address = args.next().ok_or("missing address")?,
+			_ if path.is_none() => path = Some(arg),
+			_ => return Err(USAGE.into()),
+		}
+	}
+	let path = path.ok_or(USAGE)?;
+
+	let mut server = PaletteServer::bind(address, read_palette(path)?)
+		.map_err(|e| format!("{}: {}", address, e))?;
+	let bound = server.local_addr()
+		.map_or_else(|| address.to_owned(), |a| a.to_string());
+	if json {
+		println!("{}", json!({"file": path, "address": bound}));
+	} else {
+		println!("serving {} at http://{}", path, bound);
+	}
+
+	loop {
+		let changed = server.handle_next(Duration::from_secs(1))
+			.map_err(|e| e.to_string())?;
+		if !changed { continue; }
+		let mut out = Vec::new();
+		Format::Default.write_palette(server.palette(), &mut out)
+			.and_then(|_| fs::write(path, &out))
+			.map_err(|e| format!("{}: {}", path, e))?;
+	}
+}
+
+
+/// Reports that serving is not supported.
+#[cfg(not(feature = "server"))]
+fn serve(_: &[String], _: bool) -> CommandResult {
+	Err(UNSERVED.into())
+}
+
+
+/// Writes a detached signature for the given file using the secret key in
+/// the given key file. Returns the path of the signature.
+#[cfg(feature = "signing")]
+fn sign_export(target: &Path, key: &Path) -> Result<PathBuf, String> {
+	let signer = Ed25519Signer::read_key(key)
+		.map_err(|e| format!("{}: {}", key.display(), e))?;
+	sign_file(target, &signer)
+		.map_err(|e| format!("{}: {}", target.display(), e))
+}
+
+
+/// Reports that signing is unavailable.
+#[cfg(not(feature = "signing"))]
+fn sign_export(_: &Path, _: &Path) -> Result<PathBuf, String> {
+	Err(UNSIGNED.into())
+}
+
+
+/// Verifies the given file against its detached signature using the public
+/// key in the given key file.
+#[cfg(feature = "signing")]
+fn verify_signature(path: &Path, key: &Path) -> Result<(), String> {
+	let verifier = Ed25519Verifier::read_key(key)
+		.map_err(|e| format!("{}: {}", key.display(), e))?;
+	verify_file(path, &verifier).map_err(|e| e.to_string())
+}
+
+
+/// Reports that verification is unavailable.
+#[cfg(not(feature = "signing"))]
+fn verify_signature(_: &Path, _: &Path) -> Result<(), String> {
+	Err(UNSIGNED.into())
+}
+
+
+/// Verifies the given contents of a file being imported against the file's
+/// detached signature using the public key in the given key file.
+#[cfg(feature = "signing")]
+fn verify_import(path: &Path, bytes: &[u8], key: &Path) -> Result<(), String> {
+	let verifier = Ed25519Verifier::read_key(key)
+		.map_err(|e| format!("{}: {}", key.display(), e))?;
+	verify_bytes(path, bytes, &verifier).map_err(|e| e.to_string())
+}
+
+
+/// Reports that verification is unavailable.
+#[cfg(not(feature = "signing"))]
+fn verify_import(_: &Path, _: &[u8], _: &Path) -> Result<(), String> {
+	Err(UNSIGNED.into())
+}
+
+
+/// Reads the manifest at the given path, or returns an empty manifest if the
+/// file does not exist.
+fn read_manifest(path: &Path) -> Result<Manifest, String> {
+	match File::open(path) {
+		Ok(mut file) => Manifest::read(&mut file),
+		Err(ref e) if e.kind() == io::ErrorKind::NotFound 
+			=> Ok(Manifest::new()),
+		Err(e) => Err(e),
+	}.map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+
+/// Writes the manifest to the given path.
+fn write_manifest(path: &Path, manifest: &Manifest) -> Result<(), String> {
+	File::create(path)
+		.and_then(|file| {
+			let mut out = BufWriter::new(file);
+			manifest.write(&mut out)?;
+			out.flush()
+		})
+		.map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+
+/// Reads the palette file at the given path.
+fn read_palette(path: &str) -> Result<Palette, String> {
+	File::open(path)
+		.and_then(|mut file| Format::Default.read_palette(&mut file))
+		.map_err(|e| format!("{}: {}", path, e))
+}
 
-	pal.apply(Box::new(InsertCell::new())).unwrap();
-	println!("{}", pal);
-	pal.undo().unwrap();
 
-	println!("{}", pal);
-}
\ No newline at end of file
+/// Returns the JSON representation of a palette cell.
+fn cell_json(
+	palette: &Palette,
+	address: Address,
+	color: Color,
+	notation: Notation)
+	-> Json
+{
+	json!({
+		"address": address.to_string(),
+		"color": notation.format(color),
+		"name": palette.cell_name(address),
+		"tags": palette.cell_tags(address),
+	})
+}
@@ -0,0 +1,451 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides a single-file bundle format for distributing palettes.
+//!
+//! A bundle is a zip archive holding a palette in the default format, any 
+//! number of PNG thumbnails and exported files, and a `manifest` listing each
+//! file with its checksum:
+//!
+//! ```text
+//! bundle 1
+//! palette "palette.palette" 3f8c09a2d41b7e65
+//! thumbnail "thumbnails/256x64.png" 256 64 0b1e2f3a4c5d6e7f
+//! export "exports/c.h" c 9a8b7c6d5e4f3a2b
+//! ```
+//!
+//! Bundles are built with a [`BundleBuilder`] and read with [`Bundle::read`],
+//! which verifies the checksums of all listed files.
+//!
+//! [`BundleBuilder`]: struct.BundleBuilder.html
+//! [`Bundle::read`]: struct.Bundle.html#method.read
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use ::Palette;
+use export::{
+	checksum,
+	ExportFormat,
+};
+use format::Format;
+use format::default;
+use render::ThumbnailLayout;
+
+// Non-local imports.
+use zip::{
+	CompressionMethod,
+	ZipArchive,
+	ZipWriter,
+};
+use zip::write::FileOptions;
+
+// Standard imports.
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::io::{
+	BufRead,
+	Read,
+	Write,
+};
+use std::path::Path;
+
+
+/// The path of the manifest within a bundle.
+const MANIFEST_PATH: &'static str = "manifest";
+
+
+/// The path of the palette within a bundle.
+const PALETTE_PATH: &'static str = "palette.palette";
+
+
+/// The version of the bundle format written.
+const BUNDLE_VERSION: u32 = 1;
+
+
+/// The maximum unpacked size of a file within a bundle, in bytes.
+const MAX_ENTRY_SIZE: u64 = 64 * 1024 * 1024;
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// BundleItem
+////////////////////////////////////////////////////////////////////////////////
+/// The kind of a file stored in a bundle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BundleItem {
+	/// The palette, in the default format.
+	Palette,
+	/// A PNG thumbnail of the palette with the given width and height.
+	Thumbnail {
+		/// The width of the thumbnail in pixels.
+		width: usize,
+		/// The height of the thumbnail in pixels.
+		height: usize,
+	},
+	/// The palette exported in the given format.
+	Export(ExportFormat),
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// BundleEntry
+////////////////////////////////////////////////////////////////////////////////
+/// A record of a single file stored in a bundle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BundleEntry {
+	/// The path of the file within the bundle.
+	pub path: String,
+	/// The kind of the file.
+	pub item: BundleItem,
+	/// The checksum of the file.
+	pub checksum: u64,
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// BundleBuilder
+////////////////////////////////////////////////////////////////////////////////
+/// Builds a bundle from a palette.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::bundle::{Bundle, BundleBuilder, BundleItem};
+/// use palette::export::ExportFormat;
+/// use palette::operation::*;
+/// use palette::render::ThumbnailLayout;
+/// use std::io::Cursor;
+///
+/// let mut pal = Palette::new("Example", Format::Default, true);
+/// pal.apply(Box::new(InsertColor::new(Color::new(12, 50, 78)))).unwrap();
+///
+/// let mut out = Cursor::new(Vec::new());
+/// BundleBuilder::new()
+/// 	.thumbnail(64, 16, ThumbnailLayout::Strip)
+/// 	.export(ExportFormat::CHeader)
+/// 	.write(&pal, &mut out)
+/// 	.unwrap();
+///
+/// out.set_position(0);
+/// let bundle = Bundle::read(&mut out).unwrap();
+/// assert_eq!(bundle.palette().color(Address::new(0, 0, 0)), 
+/// 	Some(Color::new(12, 50, 78)));
+/// assert_eq!(bundle.entries().len(), 3);
+/// assert!(bundle.file("exports/c.h").is_some());
+/// assert_eq!(bundle.entries()[1].item, 
+/// 	BundleItem::Thumbnail { width: 64, height: 16 });
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BundleBuilder {
+	/// The size and layout of each thumbnail.
+	thumbnails: Vec<(usize, usize, ThumbnailLayout)>,
+	/// The formats to export.
+	exports: Vec<ExportFormat>,
+}
+
+
+impl BundleBuilder {
+	/// Creates a new `BundleBuilder` which bundles only the palette.
+	pub fn new() -> Self {
+		Default::default()
+	}
+
+	/// Adds a thumbnail of the given size and layout. Thumbnails are named by
+	/// their size, so a thumbnail replaces any earlier one of the same size.
+	pub fn thumbnail(mut self, width: usize, height: usize, 
+		layout: ThumbnailLayout) -> Self
+	{
+		self.thumbnails.retain(|&(w, h, _)| (w, h) != (width, height));
+		self.thumbnails.push((width, height, layout));
+		self
+	}
+
+	/// Adds an export of the palette in the given format.
+	pub fn export(mut self, format: ExportFormat) -> Self {
+		if !self.exports.contains(&format) {
+			self.exports.push(format);
+		}
+		self
+	}
+
+	/// Writes a bundle of the given palette to the given buffer.
+	pub fn write<W>(&self, palette: &Palette, out_buf: &mut W) 
+		-> io::Result<()>
+		where W: io::Write + io::Seek
+	{
+		let mut files = Vec::new();
+
+		let mut bytes = Vec::new();
+		Format::Default.write_palette(palette, &mut bytes)?;
+		files.push((PALETTE_PATH.to_owned(), BundleItem::Palette, bytes));
+
+		for &(width, height, layout) in &self.thumbnails {
+			let mut bytes = Vec::new();
+			palette.render_thumbnail(width, height, layout)
+				.write_png(&mut bytes)?;
+			files.push((
+				format!("thumbnails/{}x{}.png", width, height),
+				BundleItem::Thumbnail { width: width, height: height },
+				bytes));
+		}
+
+		for &format in &self.exports {
+			let mut bytes = Vec::new();
+			format.write(palette, &mut bytes)?;
+			files.push((
+				format!("exports/{}.{}", format.name(), format.extension()),
+				BundleItem::Export(format),
+				bytes));
+		}
+
+		let entries: Vec<_> = files.iter()
+			.map(|&(ref path, item, ref bytes)| BundleEntry {
+				path: path.clone(),
+				item: item,
+				checksum: checksum(bytes),
+			})
+			.collect();
+		let mut manifest = Vec::new();
+		write_manifest(&entries, &mut manifest)?;
+
+		let to_io = |e: ::zip::result::ZipError| 
+			io::Error::new(io::ErrorKind::Other, e);
+		let mut zip = ZipWriter::new(out_buf);
+		let deflated = FileOptions::default()
+			.compression_method(CompressionMethod::Deflated);
+		zip.start_file(MANIFEST_PATH, deflated).map_err(to_io)?;
+		zip.write_all(&manifest)?;
+		for (path, item, bytes) in files {
+			// PNG data is already compressed.
+			let options = match item {
+				BundleItem::Thumbnail { .. } => FileOptions::default()
+					.compression_method(CompressionMethod::Stored),
+				_ => deflated,
+			};
+			zip.start_file(path, options).map_err(to_io)?;
+			zip.write_all(&bytes)?;
+		}
+		zip.finish().map_err(to_io)?;
+		Ok(())
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Bundle
+////////////////////////////////////////////////////////////////////////////////
+/// The contents of a bundle.
+#[derive(Debug)]
+pub struct Bundle {
+	/// The bundled palette.
+	palette: Palette,
+	/// The manifest entries, in manifest order.
+	entries: Vec<BundleEntry>,
+	/// The contents of each file listed in the manifest.
+	files: BTreeMap<String, Vec<u8>>,
+}
+
+
+impl Bundle {
+	/// Reads a bundle from the given buffer.
+	///
+	/// Returns an `InvalidData` error if the bundle is malformed, if a listed
+	/// file is missing, too large, or does not match its checksum, or if a 
+	/// path would leave the bundle when unpacked.
+	pub fn read<R>(in_buf: &mut R) -> io::Result<Bundle>
+		where R: io::Read + io::Seek
+	{
+		let invalid = |message: String| 
+			io::Error::new(io::ErrorKind::InvalidData, message);
+		let mut zip = ZipArchive::new(in_buf)
+			.map_err(|e| invalid(e.to_string()))?;
+
+		let manifest = zip.by_name(MANIFEST_PATH)
+			.map_err(|e| invalid(format!("{}: {}", MANIFEST_PATH, e)))
+			.and_then(|file| read_limited(file, MANIFEST_PATH))?;
+		let manifest = String::from_utf8(manifest)
+			.map_err(|e| invalid(format!("{}: {}", MANIFEST_PATH, e)))?;
+
+		let mut entries = Vec::new();
+		for (i, line) in manifest.as_bytes().lines().enumerate() {
+			let tokens = default::tokenize(&line?)
+				.map_err(|e| default::invalid(i, e))?;
+			let entry = match tokens.first().map(|t| &t[..]) {
+				Some("bundle") => check_version(&tokens).map(|_| None),
+				Some(_) => read_entry(&tokens).map(Some),
+				None => Ok(None),
+			};
+			if let Some(entry) = entry.map_err(|e| default::invalid(i, e))? {
+				entries.push(entry);
+			}
+		}
+
+		let mut files = BTreeMap::new();
+		for entry in &entries {
+			let bytes = zip.by_name(&entry.path)
+				.map_err(|e| invalid(format!("{}: {}", entry.path, e)))
+				.and_then(|file| read_limited(file, &entry.path))?;
+			if checksum(&bytes) != entry.checksum {
+				return Err(invalid(
+					format!("{}: checksum mismatch", entry.path)));
+			}
+			files.insert(entry.path.clone(), bytes);
+		}
+
+		let palette = entries.iter()
+			.find(|entry| entry.item == BundleItem::Palette)
+			.and_then(|entry| files.get(&entry.path))
+			.ok_or_else(|| invalid("bundle has no palette".to_owned()))
+			.and_then(|bytes| Format::Default.read_palette(&mut &bytes[..]))?;
+
+		Ok(Bundle {
+			palette: palette,
+			entries: entries,
+			files: files,
+		})
+	}
+
+	/// Returns the bundled palette.
+	pub fn palette(&self) -> &Palette {
+		&self.palette
+	}
+
+	/// Returns the manifest entries of the bundle, in manifest order.
+	pub fn entries(&self) -> &[BundleEntry] {
+		&self.entries
+	}
+
+	/// Returns the contents of the file with the given path, if it is listed
+	/// in the manifest.
+	pub fn file(&self, path: &str) -> Option<&[u8]> {
+		self.files.get(path).map(|bytes| &bytes[..])
+	}
+
+	/// Writes every file listed in the manifest, and the manifest itself, 
+	/// into the given directory, creating it and any subdirectories as 
+	/// needed.
+	pub fn unpack<P>(&self, directory: P) -> io::Result<()>
+		where P: AsRef<Path>
+	{
+		let directory = directory.as_ref();
+		fs::create_dir_all(directory)?;
+
+		for entry in &self.entries {
+			let path = directory.join(&entry.path);
+			if let Some(parent) = path.parent() {
+				fs::create_dir_all(parent)?;
+			}
+			fs::write(&path, &self.files[&entry.path])?;
+		}
+		let mut manifest = fs::File::create(directory.join(MANIFEST_PATH))?;
+		write_manifest(&self.entries, &mut manifest)
+	}
+}
+
+
+/// Writes a bundle manifest listing the given entries to the given buffer.
+fn write_manifest<W>(entries: &[BundleEntry], out_buf: &mut W) 
+	-> io::Result<()>
+	where W: io::Write
+{
+	writeln!(out_buf, "bundle {}", BUNDLE_VERSION)?;
+	for entry in entries {
+		let path = default::quote(&entry.path);
+		match entry.item {
+			BundleItem::Palette => write!(out_buf, "palette {}", path)?,
+			BundleItem::Thumbnail { width, height } => write!(out_buf, 
+				"thumbnail {} {} {}", path, width, height)?,
+			BundleItem::Export(format) => write!(out_buf,
+				"export {} {}", path, format.name())?,
+		}
+		writeln!(out_buf, " {:016x}", entry.checksum)?;
+	}
+	Ok(())
+}
+
+
+/// Checks that a bundle version entry names a supported version.
+fn check_version(tokens: &[String]) -> Result<(), String> {
+	let version: u32 = default::parse(&default::token(tokens, 1)?)?;
+	if version > BUNDLE_VERSION {
+		Err(format!("unsupported bundle version {}", version))
+	} else {
+		Ok(())
+	}
+}
+
+
+/// Reads the contents of a file within a bundle, refusing files larger than
+/// `MAX_ENTRY_SIZE` so that a crafted bundle can't exhaust memory.
+fn read_limited<R>(file: R, path: &str) -> io::Result<Vec<u8>>
+	where R: Read
+{
+	let mut bytes = Vec::new();
+	file.take(MAX_ENTRY_SIZE + 1).read_to_end(&mut bytes)?;
+	if bytes.len() as u64 > MAX_ENTRY_SIZE {
+		return Err(io::Error::new(io::ErrorKind::InvalidData, 
+			format!("{}: file exceeds {} bytes", path, MAX_ENTRY_SIZE)));
+	}
+	Ok(bytes)
+}
+
+
+/// Parses a bundle manifest file entry.
+fn read_entry(tokens: &[String]) -> Result<BundleEntry, String> {
+	let path = default::token(tokens, 1)?;
+	let safe = !path.is_empty() 
+		&& !path.starts_with('/') 
+		&& !path.contains('\\')
+		&& !path.split('/').any(|part| part == ".." || part.contains(':'));
+	if !safe {
+		return Err(format!("invalid path '{}'", path));
+	}
+
+	let (item, next) = match &tokens[0][..] {
+		"palette" => (BundleItem::Palette, 2),
+		"thumbnail" => (BundleItem::Thumbnail {
+			width: default::parse(&default::token(tokens, 2)?)?,
+			height: default::parse(&default::token(tokens, 3)?)?,
+		}, 4),
+		"export" => {
+			let format = default::token(tokens, 2)?;
+			(BundleItem::Export(ExportFormat::from_name(&format)
+				.ok_or_else(|| format!("unknown format '{}'", format))?), 3)
+		},
+		other => return Err(format!("unknown entry '{}'", other)),
+	};
+	let hash = default::token(tokens, next)?;
+	Ok(BundleEntry {
+		path: path,
+		item: item,
+		checksum: u64::from_str_radix(&hash, 16)
+			.map_err(|_| format!("invalid checksum '{}'", hash))?,
+	})
+}
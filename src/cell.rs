@@ -28,13 +28,17 @@
 ////////////////////////////////////////////////////////////////////////////////
 
 // Local imports.
+use address::Address;
 use expression::Expression;
+use space::WideColor;
 
 // Non-local imports.
 use color::Color;
 
 // Standard imports.
 use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fmt;
 use std::ops::{
 	Deref,
 	DerefMut,
@@ -66,6 +70,12 @@ impl Cell {
 	pub fn color(&self) -> Option<Color> {
 		self.expr.borrow().color()
 	}
+
+	/// Returns the extended-range color of the internal `Expression`, or 
+	/// `None` if it is invalid.
+	pub fn wide_color(&self) -> Option<WideColor> {
+		self.expr.borrow().wide_color()
+	}
 }
 
 
@@ -83,3 +93,176 @@ impl DerefMut for Cell {
 	}
 }
 
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// CellId
+////////////////////////////////////////////////////////////////////////////////
+/// A stable identifier for a `Cell` which remains valid when the cell is moved
+/// to a different address. Each identifier carries a generation, so an 
+/// identifier for a removed cell will never refer to a cell created later.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CellId {
+	/// The slot index of the identifier.
+	index: u32,
+	/// The generation of the slot when the identifier was allocated.
+	generation: u32,
+}
+
+
+impl CellId {
+	/// Returns the slot index of the `CellId`.
+	pub fn index(&self) -> u32 {
+		self.index
+	}
+
+	/// Returns the generation of the `CellId`.
+	pub fn generation(&self) -> u32 {
+		self.generation
+	}
+}
+
+
+impl fmt::Display for CellId {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "#{}v{}", self.index, self.generation)
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// CellIdMap
+////////////////////////////////////////////////////////////////////////////////
+/// Allocates `CellId`s and maintains the mapping between them and the 
+/// addresses of the cells they identify.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::cell::CellIdMap;
+/// use palette::Address;
+/// 
+/// let mut ids = CellIdMap::new();
+/// let a = Address::new(0, 0, 0);
+/// let b = Address::new(0, 0, 1);
+///
+/// let id = ids.allocate(a);
+/// ids.relocate(a, b);
+/// assert_eq!(ids.address_of(id), Some(b));
+///
+/// ids.release(b);
+/// assert!(ids.reinstate(a, id));
+/// assert_eq!(ids.address_of(id), Some(a));
+///
+/// ids.release(a);
+/// let reused = ids.allocate(a);
+/// assert_eq!(reused.index(), id.index());
+/// assert_eq!(ids.address_of(id), None);
+/// assert!(!ids.reinstate(b, id));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CellIdMap {
+	/// The current generation and address of each slot. Every identifier
+	/// released from a slot is older than its current generation.
+	slots: Vec<(u32, Option<Address>)>,
+	/// The indices of unoccupied slots.
+	free: Vec<u32>,
+	/// The identifiers of each occupied address.
+	ids: BTreeMap<Address, CellId>,
+}
+
+
+impl CellIdMap {
+	/// Creates a new, empty `CellIdMap`.
+	pub fn new() -> Self {
+		Default::default()
+	}
+
+	/// Returns the number of allocated identifiers.
+	pub fn len(&self) -> usize {
+		self.ids.len()
+	}
+
+	/// Returns whether any identifiers are allocated.
+	pub fn is_empty(&self) -> bool {
+		self.ids.is_empty()
+	}
+
+	/// Allocates a new identifier for the given address, replacing any 
+	/// identifier already assigned to it.
+	pub fn allocate(&mut self, address: Address) -> CellId {
+		self.release(address);
+		let id = match self.free.pop() {
+			Some(index) => {
+				let slot = &mut self.slots[index as usize];
+				slot.1 = Some(address);
+				CellId {index: index, generation: slot.0}
+			},
+			None => {
+				self.slots.push((0, Some(address)));
+				CellId {index: (self.slots.len() - 1) as u32, generation: 0}
+			},
+		};
+		self.ids.insert(address, id);
+		id
+	}
+
+	/// Releases the identifier assigned to the given address, invalidating it.
+	/// Returns the released identifier, if there was one.
+	pub fn release(&mut self, address: Address) -> Option<CellId> {
+		let id = self.ids.remove(&address)?;
+		let slot = &mut self.slots[id.index as usize];
+		slot.0 = slot.0.wrapping_add(1);
+		slot.1 = None;
+		self.free.push(id.index);
+		Some(id)
+	}
+
+	/// Reassigns a released identifier to the given address, replacing any 
+	/// identifier already assigned to it, so that references to a removed
+	/// cell follow it when it is restored. Returns false, changing nothing, if
+	/// the identifier was never allocated or its slot is in use by another 
+	/// cell.
+	pub fn reinstate(&mut self, address: Address, id: CellId) -> bool {
+		// The slot may hold the identifier just allocated for the address.
+		let free = self.slots
+			.get(id.index as usize)
+			.map_or(false, |slot| id.generation < slot.0 
+				&& (slot.1.is_none() || slot.1 == Some(address)));
+		if !free { return false; }
+
+		self.release(address);
+		self.free.retain(|&index| index != id.index);
+		self.slots[id.index as usize].1 = Some(address);
+		self.ids.insert(address, id);
+		true
+	}
+
+	/// Moves the identifier assigned to one address to another address. 
+	/// Returns the moved identifier, if there was one.
+	pub fn relocate(&mut self, from: Address, to: Address) -> Option<CellId> {
+		let id = self.ids.remove(&from)?;
+		self.release(to);
+		self.slots[id.index as usize].1 = Some(to);
+		self.ids.insert(to, id);
+		Some(id)
+	}
+
+	/// Returns the identifier assigned to the given address.
+	pub fn id_of(&self, address: Address) -> Option<CellId> {
+		self.ids.get(&address).cloned()
+	}
+
+	/// Returns the address of the cell with the given identifier, or None if
+	/// the identifier has been released.
+	pub fn address_of(&self, id: CellId) -> Option<Address> {
+		// A reinstated identifier is older than its slot's generation, so the
+		// address's identifier is checked instead.
+		self.slots
+			.get(id.index as usize)
+			.and_then(|slot| slot.1)
+			.filter(|address| self.ids.get(address) == Some(&id))
+	}
+}
@@ -0,0 +1,689 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides a corpus of palette files and a conformance suite for formats.
+//!
+//! The corpus holds real files for each import format under `corpus/`, each
+//! paired with a `.golden` file holding the canonical default-format text of
+//! the palette it imports as. Checking the corpus catches changes to how 
+//! files are read:
+//!
+//! ```rust
+//! use palette::conformance::corpus;
+//!
+//! for file in corpus() {
+//! 	if let Err(e) = file.check() {
+//! 		panic!("{}: {}", file.path(), e);
+//! 	}
+//! }
+//! ```
+//!
+//! The palettes of the corpus are also used to test format plugins, which 
+//! pair a writer with a reader. The `ConformanceSuite` writes each palette,
+//! reads it back, and reports any difference the plugin's `Fidelity` does 
+//! not allow, along with nondeterministic output, output which changes when
+//! written again, and panics on truncated input.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use ::Palette;
+use address::Selection;
+use export::ExportFormat;
+use format::Format;
+use gallery::ReferencePalette;
+use import::ImportFormat;
+
+// Standard imports.
+use std::any::Any;
+use std::fmt;
+use std::io;
+use std::panic;
+use std::panic::AssertUnwindSafe;
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// CorpusFile
+////////////////////////////////////////////////////////////////////////////////
+/// A palette file of the corpus and its golden output.
+#[derive(Debug, Clone, Copy)]
+pub struct CorpusFile {
+	/// The path of the file, relative to the corpus directory.
+	path: &'static str,
+	/// The format of the file.
+	format: ImportFormat,
+	/// The contents of the file.
+	bytes: &'static [u8],
+	/// The canonical default-format text of the imported palette.
+	golden: &'static str,
+}
+
+
+/// Declares the corpus files, embedding them and their golden files.
+macro_rules! corpus_files {
+	($($format:ident $path:expr),* $(,)*) => {
+		&[$(CorpusFile {
+			path: $path,
+			format: ImportFormat::$format,
+			bytes: include_bytes!(concat!("../corpus/", $path)),
+			golden: include_str!(concat!("../corpus/", $path, ".golden")),
+		}),*]
+	};
+}
+
+
+/// The files of the corpus.
+static CORPUS: &[CorpusFile] = corpus_files![
+	Palette    "palette/skin.palette",
+	Palette    "palette/display.palette",
+	Figma      "figma/brand.json",
+	Sketch     "sketch/material.sketchpalette",
+	Base16     "base16/ocean.yaml",
+	ColorTable "indexed/pico8.gif",
+	ColorTable "indexed/gameboy.png",
+	ColorTable "indexed/cga.bmp",
+];
+
+
+/// Returns the files of the corpus.
+pub fn corpus() -> &'static [CorpusFile] {
+	CORPUS
+}
+
+
+impl CorpusFile {
+	/// Returns the path of the file, relative to the corpus directory.
+	pub fn path(&self) -> &'static str {
+		self.path
+	}
+
+	/// Returns the name the file is imported with, which is its file name
+	/// without the extension.
+	pub fn name(&self) -> &'static str {
+		let file = self.path.rsplit('/').next().unwrap_or(self.path);
+		file.rsplitn(2, '.').last().unwrap_or(file)
+	}
+
+	/// Returns the format of the file.
+	pub fn format(&self) -> ImportFormat {
+		self.format
+	}
+
+	/// Returns the contents of the file.
+	pub fn bytes(&self) -> &'static [u8] {
+		self.bytes
+	}
+
+	/// Returns the expected canonical default-format text of the palette.
+	pub fn golden(&self) -> &'static str {
+		self.golden
+	}
+
+	/// Imports the palette stored in the file.
+	pub fn read(&self) -> io::Result<Palette> {
+		self.format.read(self.name(), &mut &self.bytes[..])
+	}
+
+	/// Returns the canonical default-format text of the palette stored in the
+	/// file. This is the text the golden file is expected to hold.
+	pub fn canonical(&self) -> io::Result<String> {
+		canonical(&self.read()?)
+	}
+
+	/// Checks that the file imports as its golden text, and that the golden 
+	/// text is itself read and rewritten exactly.
+	///
+	/// # Errors
+	///
+	/// Returns a description of the first difference found.
+	pub fn check(&self) -> Result<(), String> {
+		let actual = self.canonical().map_err(|e| e.to_string())?;
+		compare_text("golden", self.golden, &actual)?;
+
+		let reread = Format::Default.read_palette(&mut self.golden.as_bytes())
+			.and_then(|palette| canonical(&palette))
+			.map_err(|e| format!("golden: {}", e))?;
+		compare_text("rewritten golden", self.golden, &reread)
+	}
+}
+
+
+/// Returns the canonical default-format text of the palette.
+fn canonical(palette: &Palette) -> io::Result<String> {
+	let mut out = Vec::new();
+	Format::Default.write_palette(palette, &mut out)?;
+	String::from_utf8(out)
+		.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+
+/// Returns an error describing the first line at which the given texts 
+/// differ, if any.
+fn compare_text(label: &str, expected: &str, actual: &str) 
+	-> Result<(), String>
+{
+	let mut expected_lines = expected.lines();
+	let mut actual_lines = actual.lines();
+	for line in 1.. {
+		match (expected_lines.next(), actual_lines.next()) {
+			(None, None) => return Ok(()),
+			(e, a) if e == a => continue,
+			(e, a) => return Err(format!(
+				"{} differs at line {}: expected {:?}, found {:?}",
+				label, line, e.unwrap_or("<end>"), a.unwrap_or("<end>"))),
+		}
+	}
+	Ok(())
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Fidelity
+////////////////////////////////////////////////////////////////////////////////
+/// How much of a palette a format is expected to preserve.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Fidelity {
+	/// Only the colors, in address order, are preserved.
+	Colors,
+	/// The colors and names of cells are preserved at their addresses.
+	Layout,
+	/// The palette is preserved exactly, as compared by its canonical 
+	/// default-format text.
+	Canonical,
+}
+
+
+impl Fidelity {
+	/// Returns a description of the first difference between the palettes
+	/// which the fidelity does not allow, if any.
+	fn compare(&self, expected: &Palette, actual: &Palette) 
+		-> Result<(), String>
+	{
+		let all = Selection::all();
+		let (expected_colors, actual_colors) = 
+			(expected.colors(&all), actual.colors(&all));
+		match *self {
+			Fidelity::Colors => {
+				let expected_colors = expected_colors.iter().map(|c| c.1);
+				let actual_colors = actual_colors.iter().map(|c| c.1);
+				if expected_colors.ne(actual_colors) {
+					return Err("colors differ".to_owned());
+				}
+			},
+			Fidelity::Layout => {
+				if expected_colors != actual_colors {
+					return Err("colors or addresses differ".to_owned());
+				}
+				let names = |p: &Palette| expected_colors.iter()
+					.map(|c| p.cell_name(c.0).map(str::to_owned))
+					.collect::<Vec<_>>();
+				if names(expected) != names(actual) {
+					return Err("cell names differ".to_owned());
+				}
+			},
+			Fidelity::Canonical => {
+				let text = |p: &Palette| canonical(p)
+					.map_err(|e| e.to_string());
+				compare_text("canonical text", 
+					&text(expected)?, 
+					&text(actual)?)?;
+			},
+		}
+		Ok(())
+	}
+}
+
+
+impl fmt::Display for Fidelity {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}", match *self {
+			Fidelity::Colors    => "colors",
+			Fidelity::Layout    => "layout",
+			Fidelity::Canonical => "canonical",
+		})
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// FormatPlugin
+////////////////////////////////////////////////////////////////////////////////
+/// A palette format which can be both written and read, tested by the 
+/// `ConformanceSuite`.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::conformance::{ConformanceSuite, Fidelity, FormatPlugin};
+/// use palette::operation::*;
+/// use std::io;
+/// use std::io::{BufRead, BufReader};
+///
+/// /// Stores one hex color per line.
+/// struct HexList;
+///
+/// impl FormatPlugin for HexList {
+/// 	fn name(&self) -> &str { "hex-list" }
+///
+/// 	fn fidelity(&self) -> Fidelity { Fidelity::Colors }
+///
+/// 	fn write(&self, palette: &Palette, out_buf: &mut io::Write) 
+/// 		-> io::Result<()>
+/// 	{
+/// 		for (_, color) in palette.colors(&Selection::all()) {
+/// 			writeln!(out_buf, "{:02X}{:02X}{:02X}", 
+/// 				color.red(), color.green(), color.blue())?;
+/// 		}
+/// 		Ok(())
+/// 	}
+///
+/// 	fn read(&self, name: &str, in_buf: &mut io::Read) 
+/// 		-> io::Result<Palette>
+/// 	{
+/// 		let mut palette = Palette::new(name, Format::Default, true);
+/// 		for line in BufReader::new(in_buf).lines() {
+/// 			let line = line?;
+/// 			let channel = |i: usize| line.get(i..i + 2)
+/// 				.and_then(|hex| u8::from_str_radix(hex, 16).ok())
+/// 				.ok_or_else(|| io::Error::new(
+/// 					io::ErrorKind::InvalidData, "invalid color"));
+/// 			let color = Color::new(channel(0)?, channel(2)?, channel(4)?);
+/// 			palette.apply(Box::new(InsertColor::new(color)))
+/// 				.map_err(|e| io::Error::new(
+/// 					io::ErrorKind::Other, e.to_string()))?;
+/// 		}
+/// 		Ok(palette)
+/// 	}
+/// }
+///
+/// let report = ConformanceSuite::new()
+/// 	.register(Box::new(HexList))
+/// 	.run();
+/// assert!(report.passed(), "{}", report);
+/// ```
+pub trait FormatPlugin {
+	/// Returns the name of the format.
+	fn name(&self) -> &str;
+
+	/// Returns how much of a palette the format is expected to preserve.
+	fn fidelity(&self) -> Fidelity;
+
+	/// Writes the palette to the given buffer.
+	///
+	/// # Errors
+	///
+	/// An `InvalidInput` error indicates the format cannot store the 
+	/// palette, and is not treated as a failure.
+	fn write(&self, palette: &Palette, out_buf: &mut io::Write) 
+		-> io::Result<()>;
+
+	/// Reads a palette from the given buffer. Formats which do not store a 
+	/// palette name use the given name.
+	fn read(&self, name: &str, in_buf: &mut io::Read) -> io::Result<Palette>;
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// BuiltinFormat
+////////////////////////////////////////////////////////////////////////////////
+/// A format plugin pairing one of the supported export formats with the
+/// import format reading it.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::conformance::{BuiltinFormat, FormatPlugin};
+/// use palette::export::ExportFormat;
+///
+/// let figma = BuiltinFormat::for_export(ExportFormat::Figma).unwrap();
+/// assert_eq!(figma.name(), "figma");
+/// assert!(BuiltinFormat::for_export(ExportFormat::CHeader).is_none());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BuiltinFormat {
+	/// The format palettes are written in.
+	export: ExportFormat,
+	/// The format palettes are read from.
+	import: ImportFormat,
+	/// The expected fidelity of the round trip.
+	fidelity: Fidelity,
+}
+
+
+impl BuiltinFormat {
+	/// Returns the plugin for the given export format, or None if it cannot
+	/// be read back or does not round trip general palettes.
+	///
+	/// Base16 schemes are not supported, as they store exactly sixteen or 
+	/// twenty-four colors.
+	pub fn for_export(export: ExportFormat) -> Option<BuiltinFormat> {
+		let (import, fidelity) = match export {
+			ExportFormat::Palette 
+				=> (ImportFormat::Palette, Fidelity::Canonical),
+			ExportFormat::Figma => (ImportFormat::Figma, Fidelity::Colors),
+			ExportFormat::Sketch => (ImportFormat::Sketch, Fidelity::Colors),
+			_ => return None,
+		};
+		Some(BuiltinFormat { export, import, fidelity })
+	}
+
+	/// Returns the plugins for all of the supported formats which round trip
+	/// general palettes.
+	pub fn all() -> Vec<BuiltinFormat> {
+		ExportFormat::all()
+			.into_iter()
+			.filter_map(BuiltinFormat::for_export)
+			.collect()
+	}
+}
+
+
+impl FormatPlugin for BuiltinFormat {
+	fn name(&self) -> &str {
+		self.export.name()
+	}
+
+	fn fidelity(&self) -> Fidelity {
+		self.fidelity
+	}
+
+	fn write(&self, palette: &Palette, out_buf: &mut io::Write) 
+		-> io::Result<()>
+	{
+		self.export.write(palette, &mut { out_buf })
+	}
+
+	fn read(&self, name: &str, in_buf: &mut io::Read) -> io::Result<Palette> {
+		match self.import {
+			// Importing records provenance, which would alter the palette.
+			ImportFormat::Palette 
+				=> Format::Default.read_palette(&mut { in_buf }),
+			import => import.read(name, &mut { in_buf }),
+		}
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// ConformanceSuite
+////////////////////////////////////////////////////////////////////////////////
+/// The largest number of truncated copies of each written file read back.
+const MAX_TRUNCATIONS: usize = 64;
+
+
+/// Tests format plugins by writing and reading back the palettes of the 
+/// corpus, the reference palettes, and any added test cases.
+///
+/// For each plugin and palette, the suite checks that:
+///
+/// + Writing the palette twice produces the same output.
+/// + Reading the output back produces a palette equal to the original, to 
+/// the plugin's fidelity.
+/// + Writing the palette read back reproduces the output exactly.
+/// + Reading truncated copies of the output does not panic.
+///
+/// Panics in the plugin are caught and reported as failures.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::conformance::ConformanceSuite;
+///
+/// let report = ConformanceSuite::builtin().run();
+/// assert!(report.passed(), "{}", report);
+/// assert_eq!(report.plugins(), vec!["palette", "figma", "sketch"]);
+/// ```
+pub struct ConformanceSuite {
+	/// The plugins under test, in registration order.
+	plugins: Vec<Box<FormatPlugin>>,
+	/// Test cases added in addition to the corpus.
+	cases: Vec<(String, Palette)>,
+}
+
+
+impl ConformanceSuite {
+	/// Constructs a new `ConformanceSuite` with no plugins.
+	pub fn new() -> Self {
+		ConformanceSuite {
+			plugins: Vec::new(),
+			cases: Vec::new(),
+		}
+	}
+
+	/// Constructs a new `ConformanceSuite` testing the supported formats.
+	pub fn builtin() -> Self {
+		BuiltinFormat::all()
+			.into_iter()
+			.fold(ConformanceSuite::new(), |suite, plugin| 
+				suite.register(Box::new(plugin)))
+	}
+
+	/// Registers a plugin to be tested.
+	pub fn register(mut self, plugin: Box<FormatPlugin>) -> Self {
+		self.plugins.push(plugin);
+		self
+	}
+
+	/// Adds a palette to test each plugin with, in addition to the corpus.
+	pub fn case<S>(mut self, name: S, palette: Palette) -> Self
+		where S: Into<String>
+	{
+		self.cases.push((name.into(), palette));
+		self
+	}
+
+	/// Runs the suite, returning a report of the results.
+	///
+	/// # Panics
+	///
+	/// Panics if a file of the corpus cannot be read.
+	pub fn run(&self) -> ConformanceReport {
+		let mut corpus: Vec<(String, Palette)> = corpus().iter()
+			.map(|file| (file.path().to_owned(), file.read()
+				.unwrap_or_else(|e| panic!("{}: {}", file.path(), e))))
+			.collect();
+		corpus.extend(ReferencePalette::all().iter()
+			.map(|r| (format!("gallery/{}", r.name()), r.palette())));
+
+		let mut report = ConformanceReport {
+			plugins: self.plugins.iter()
+				.map(|plugin| plugin.name().to_owned())
+				.collect(),
+			cases: 0,
+			skipped: 0,
+			failures: Vec::new(),
+		};
+		for plugin in &self.plugins {
+			for &(ref case, ref palette) in corpus.iter().chain(&self.cases) {
+				report.cases += 1;
+				let result = panic::catch_unwind(AssertUnwindSafe(|| 
+					check_case(&**plugin, case, palette)));
+				let message = match result {
+					Ok(Ok(true)) => continue,
+					Ok(Ok(false)) => { report.skipped += 1; continue; },
+					Ok(Err(message)) => message,
+					Err(payload) => panic_message(payload),
+				};
+				report.failures.push(ConformanceFailure {
+					plugin: plugin.name().to_owned(),
+					case: case.clone(),
+					message,
+				});
+			}
+		}
+		report
+	}
+}
+
+
+impl Default for ConformanceSuite {
+	fn default() -> Self {
+		ConformanceSuite::new()
+	}
+}
+
+
+impl fmt::Debug for ConformanceSuite {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("ConformanceSuite")
+			.field("plugins", &self.plugins.iter()
+				.map(|plugin| plugin.name())
+				.collect::<Vec<_>>())
+			.field("cases", &self.cases.iter()
+				.map(|case| &case.0)
+				.collect::<Vec<_>>())
+			.finish()
+	}
+}
+
+
+/// Checks the round trip of a palette through the plugin, returning false if
+/// the plugin cannot store the palette.
+fn check_case(plugin: &FormatPlugin, case: &str, palette: &Palette) 
+	-> Result<bool, String>
+{
+	let write = |palette: &Palette, stage: &str| {
+		let mut out = Vec::new();
+		plugin.write(palette, &mut out).map(|_| out)
+			.map_err(|e| (e.kind(), format!("{}: {}", stage, e)))
+	};
+
+	let output = match write(palette, "write") {
+		Ok(output) => output,
+		Err((io::ErrorKind::InvalidInput, _)) => return Ok(false),
+		Err((_, message)) => return Err(message),
+	};
+	if write(palette, "second write").map_err(|e| e.1)? != output {
+		return Err("output is not deterministic".to_owned());
+	}
+
+	let name = palette.name().unwrap_or(case);
+	let reread = plugin.read(name, &mut &output[..])
+		.map_err(|e| format!("read: {}", e))?;
+	plugin.fidelity().compare(palette, &reread)
+		.map_err(|e| format!("{} round trip: {}", plugin.fidelity(), e))?;
+	if write(&reread, "rewrite").map_err(|e| e.1)? != output {
+		return Err("output changes when rewritten".to_owned());
+	}
+
+	// Truncated files may fail to read, but must not panic.
+	let step = (output.len() / MAX_TRUNCATIONS).max(1);
+	for len in (0..output.len()).step_by(step) {
+		let _ = plugin.read(name, &mut &output[..len]);
+	}
+	Ok(true)
+}
+
+
+/// Returns the message of a caught panic.
+fn panic_message(payload: Box<Any + Send>) -> String {
+	let message = payload.downcast_ref::<&str>().map(|s| s.to_string())
+		.or_else(|| payload.downcast_ref::<String>().cloned())
+		.unwrap_or_else(|| "unknown panic".to_owned());
+	format!("panicked: {}", message)
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// ConformanceReport
+////////////////////////////////////////////////////////////////////////////////
+/// A failed case of a conformance suite run.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ConformanceFailure {
+	/// The name of the plugin.
+	pub plugin: String,
+	/// The name of the test case.
+	pub case: String,
+	/// A description of the failure.
+	pub message: String,
+}
+
+
+impl fmt::Display for ConformanceFailure {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}: {}: {}", self.plugin, self.case, self.message)
+	}
+}
+
+
+/// The results of a conformance suite run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConformanceReport {
+	/// The names of the plugins tested.
+	plugins: Vec<String>,
+	/// The number of cases run.
+	cases: usize,
+	/// The number of cases skipped because the plugin cannot store them.
+	skipped: usize,
+	/// The failed cases.
+	failures: Vec<ConformanceFailure>,
+}
+
+
+impl ConformanceReport {
+	/// Returns whether every case passed or was skipped.
+	pub fn passed(&self) -> bool {
+		self.failures.is_empty()
+	}
+
+	/// Returns the names of the plugins tested.
+	pub fn plugins(&self) -> Vec<&str> {
+		self.plugins.iter().map(|name| &name[..]).collect()
+	}
+
+	/// Returns the number of cases run, including those skipped.
+	pub fn cases(&self) -> usize {
+		self.cases
+	}
+
+	/// Returns the number of cases skipped because the plugin cannot store
+	/// the palette.
+	pub fn skipped(&self) -> usize {
+		self.skipped
+	}
+
+	/// Returns the failed cases.
+	pub fn failures(&self) -> &[ConformanceFailure] {
+		&self.failures
+	}
+}
+
+
+impl fmt::Display for ConformanceReport {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		writeln!(f, "{} cases, {} skipped, {} failed", 
+			self.cases, self.skipped, self.failures.len())?;
+		for failure in &self.failures {
+			writeln!(f, "{}", failure)?;
+		}
+		Ok(())
+	}
+}
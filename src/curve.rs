@@ -0,0 +1,362 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides editable curves for shaping color values.
+//!
+//! A `Curve` maps inputs in the range [0, 1] to outputs through a list of 
+//! control points, and is evaluated using a monotone cubic spline. The spline
+//! passes through every control point and does not overshoot between them,
+//! so flat sections of a curve stay flat.
+//!
+//! A `ToneCurve` groups curves by channel to adjust whole colors, and is used
+//! as a palette-level view transform for exports.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use space::{
+	from_unit_channels,
+	unit_channels,
+	Hsl,
+};
+use utilities::{
+	canonical_float,
+	clamped,
+};
+
+// Non-local imports.
+use color::Color;
+
+// Standard imports.
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Curve
+////////////////////////////////////////////////////////////////////////////////
+/// A curve defined by control points and evaluated via a monotone cubic 
+/// spline. Outside of the control points, the curve holds the value of the
+/// nearest point.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::curve::Curve;
+///
+/// let points = vec![(0.0, 0.2), (0.3, 0.5), (0.7, 0.5), (1.0, 0.8)];
+/// let curve = Curve::new(points);
+///
+/// assert_eq!(curve.evaluate(0.0), 0.2);
+/// assert_eq!(curve.evaluate(0.5), 0.5);
+/// assert_eq!(curve.evaluate(1.0), 0.8);
+/// assert!(curve.evaluate(0.15) > 0.2 && curve.evaluate(0.15) < 0.5);
+///
+/// assert_eq!(curve.to_string(), "0:0.2 0.3:0.5 0.7:0.5 1:0.8");
+/// assert_eq!(curve.to_string().parse::<Curve>(), Ok(curve));
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Curve {
+	/// The control points, ordered by input.
+	points: Vec<(f32, f32)>,
+}
+
+
+impl Curve {
+	/// Creates a new `Curve` through the given control points. Inputs are 
+	/// clamped to the range [0, 1] and rounded to six decimal places, and 
+	/// points sharing an input are replaced by the last of them.
+	pub fn new<I>(points: I) -> Self 
+		where I: IntoIterator<Item=(f32, f32)>
+	{
+		let mut curve = Curve::default();
+		for (x, y) in points {
+			curve.insert_point(x, y);
+		}
+		curve
+	}
+
+	/// Returns the identity curve, mapping each input to itself.
+	pub fn identity() -> Self {
+		Curve::new(vec![(0.0, 0.0), (1.0, 1.0)])
+	}
+
+	/// Returns the control points of the curve, ordered by input.
+	pub fn points(&self) -> &[(f32, f32)] {
+		&self.points[..]
+	}
+
+	/// Inserts a control point, replacing any point with the same input. The
+	/// input is clamped to the range [0, 1] and rounded to six decimal places,
+	/// so that points remain distinct when the curve is written.
+	pub fn insert_point(&mut self, x: f32, y: f32) {
+		let x = (clamped(x, 0.0, 1.0) * 1e6).round() / 1e6;
+		match self.points.iter().position(|&(px, _)| px >= x) {
+			Some(i) if self.points[i].0 == x => self.points[i] = (x, y),
+			Some(i) => self.points.insert(i, (x, y)),
+			None => self.points.push((x, y)),
+		}
+	}
+
+	/// Removes and returns the control point at the given index, if any.
+	pub fn remove_point(&mut self, index: usize) -> Option<(f32, f32)> {
+		if index < self.points.len() {
+			Some(self.points.remove(index))
+		} else {
+			None
+		}
+	}
+
+	/// Returns the value of the curve at the given input.
+	pub fn evaluate(&self, x: f32) -> f32 {
+		let points = &self.points;
+		match points.len() {
+			0 => return x,
+			1 => return points[0].1,
+			_ => (),
+		}
+		if x <= points[0].0 { return points[0].1; }
+		if x >= points[points.len() - 1].0 { return points[points.len() - 1].1; }
+
+		let i = points.iter().rposition(|&(px, _)| px <= x).unwrap_or(0);
+		let ((x0, y0), (x1, y1)) = (points[i], points[i + 1]);
+		let h = x1 - x0;
+		let t = (x - x0) / h;
+		let (m0, m1) = (self.tangent(i), self.tangent(i + 1));
+
+		// Cubic Hermite basis.
+		let t2 = t * t;
+		let t3 = t2 * t;
+		(2.0 * t3 - 3.0 * t2 + 1.0) * y0
+			+ (t3 - 2.0 * t2 + t) * h * m0
+			+ (-2.0 * t3 + 3.0 * t2) * y1
+			+ (t3 - t2) * h * m1
+	}
+
+	/// Returns the slope of the segment following the control point at the
+	/// given index.
+	fn secant(&self, index: usize) -> f32 {
+		let ((x0, y0), (x1, y1)) = (self.points[index], self.points[index + 1]);
+		(y1 - y0) / (x1 - x0)
+	}
+
+	/// Returns the spline tangent at the control point with the given index,
+	/// limited using the Fritsch-Carlson method to preserve monotonicity.
+	fn tangent(&self, index: usize) -> f32 {
+		let last = self.points.len() - 1;
+		if index == 0 { return self.secant(0); }
+		if index == last { return self.secant(last - 1); }
+
+		let (before, after) = (self.secant(index - 1), self.secant(index));
+		if before * after <= 0.0 { return 0.0; }
+		// Weighted harmonic mean of the neighboring secants.
+		3.0 * before * after / (before.max(after) * 2.0 + before.min(after))
+	}
+}
+
+
+impl fmt::Display for Curve {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		for (i, &(x, y)) in self.points.iter().enumerate() {
+			if i > 0 { write!(f, " ")?; }
+			write!(f, "{}:{}", canonical_float(x), canonical_float(y))?;
+		}
+		Ok(())
+	}
+}
+
+
+impl FromStr for Curve {
+	type Err = String;
+
+	fn from_str(text: &str) -> Result<Curve, String> {
+		text.split_whitespace()
+			.map(parse_point)
+			.collect::<Result<Vec<_>, _>>()
+			.map(Curve::new)
+	}
+}
+
+
+/// Parses a control point written as `x:y`.
+pub(crate) fn parse_point(text: &str) -> Result<(f32, f32), String> {
+	let invalid = || format!("invalid curve point '{}'", text);
+	let index = text.find(':').ok_or_else(invalid)?;
+	let (x, y): (f32, f32) = (
+		text[..index].parse().map_err(|_| invalid())?,
+		text[index + 1..].parse().map_err(|_| invalid())?,
+	);
+	if x.is_finite() && y.is_finite() { Ok((x, y)) } else { Err(invalid()) }
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// ToneChannel
+////////////////////////////////////////////////////////////////////////////////
+/// A channel adjusted by a `ToneCurve`. Channels are applied in the order they
+/// are declared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ToneChannel {
+	/// Each of the red, green, and blue channels.
+	All,
+	/// The red channel.
+	Red,
+	/// The green channel.
+	Green,
+	/// The blue channel.
+	Blue,
+	/// The HSL lightness of the color.
+	Lightness,
+}
+
+
+impl ToneChannel {
+	/// Returns the name of the channel.
+	pub fn name(&self) -> &'static str {
+		match *self {
+			ToneChannel::All => "all",
+			ToneChannel::Red => "red",
+			ToneChannel::Green => "green",
+			ToneChannel::Blue => "blue",
+			ToneChannel::Lightness => "lightness",
+		}
+	}
+
+	/// Returns the channel with the given name, if any. The match is not case 
+	/// sensitive.
+	pub fn from_name(name: &str) -> Option<ToneChannel> {
+		[
+			ToneChannel::All,
+			ToneChannel::Red,
+			ToneChannel::Green,
+			ToneChannel::Blue,
+			ToneChannel::Lightness,
+		].iter()
+			.find(|c| c.name().eq_ignore_ascii_case(name))
+			.cloned()
+	}
+}
+
+
+impl fmt::Display for ToneChannel {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}", self.name())
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// ToneCurve
+////////////////////////////////////////////////////////////////////////////////
+/// A set of curves adjusting the tone of colors, with at most one curve per
+/// channel. Channels without a curve are left unchanged.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::Color;
+/// use palette::curve::{Curve, ToneChannel, ToneCurve};
+///
+/// let tone = ToneCurve::new()
+/// 	.channel(ToneChannel::All, Curve::new(vec![(0.0, 0.1), (1.0, 0.9)]))
+/// 	.channel(ToneChannel::Blue, Curve::new(vec![(0.0, 0.0), (1.0, 0.5)]));
+///
+/// let color = tone.apply(Color::new(0, 255, 255));
+/// assert_eq!(color, Color::new(26, 230, 115));
+/// assert_eq!(tone.curve(ToneChannel::Red), None);
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ToneCurve {
+	/// The curves, by channel.
+	curves: BTreeMap<ToneChannel, Curve>,
+}
+
+
+impl ToneCurve {
+	/// Creates a new `ToneCurve` which leaves colors unchanged.
+	pub fn new() -> Self {
+		ToneCurve::default()
+	}
+
+	/// Sets the curve for the given channel.
+	pub fn channel(mut self, channel: ToneChannel, curve: Curve) -> Self {
+		self.set_channel(channel, curve);
+		self
+	}
+
+	/// Sets the curve for the given channel, returning the previous curve, if
+	/// any.
+	pub fn set_channel(&mut self, channel: ToneChannel, curve: Curve)
+		-> Option<Curve>
+	{
+		self.curves.insert(channel, curve)
+	}
+
+	/// Removes and returns the curve for the given channel, if any.
+	pub fn remove_channel(&mut self, channel: ToneChannel) -> Option<Curve> {
+		self.curves.remove(&channel)
+	}
+
+	/// Returns the curve for the given channel, if any.
+	pub fn curve(&self, channel: ToneChannel) -> Option<&Curve> {
+		self.curves.get(&channel)
+	}
+
+	/// Returns an iterator over the channels and their curves, in the order
+	/// they are applied.
+	pub fn curves<'a>(&'a self) 
+		-> impl Iterator<Item=(ToneChannel, &'a Curve)> + 'a
+	{
+		self.curves.iter().map(|(&channel, curve)| (channel, curve))
+	}
+
+	/// Returns whether the tone curve leaves colors unchanged.
+	pub fn is_empty(&self) -> bool {
+		self.curves.is_empty()
+	}
+
+	/// Returns the given color adjusted by the tone curve.
+	pub fn apply(&self, color: Color) -> Color {
+		let mut rgb = unit_channels(color);
+		for (channel, curve) in self.curves() {
+			let eval = |v: f32| clamped(curve.evaluate(v), 0.0, 1.0);
+			match channel {
+				ToneChannel::All => for v in rgb.iter_mut() { *v = eval(*v); },
+				ToneChannel::Red => rgb[0] = eval(rgb[0]),
+				ToneChannel::Green => rgb[1] = eval(rgb[1]),
+				ToneChannel::Blue => rgb[2] = eval(rgb[2]),
+				ToneChannel::Lightness => {
+					let mut hsl = Hsl::from_rgb(rgb);
+					hsl.l = eval(hsl.l);
+					rgb = hsl.to_rgb();
+				},
+			}
+		}
+		from_unit_channels(rgb)
+	}
+}
@@ -31,16 +31,32 @@
 use address::{
 	Address,
 	Reference,
+	Selection,
 	Page, Line, Column, 
 	PAGE_MAX, LINE_MAX, COLUMN_MAX,
 };
-use cell::Cell;
+use cell::{
+	Cell,
+	CellId,
+	CellIdMap,
+};
+use curve::ToneCurve;
 use expression::Expression;
+use provenance::Provenance;
+use space::{
+	RgbSpace,
+	WideColor,
+};
+use text::normalize;
+use trash::TrashedCell;
 use result::{
 	Error,
 	Result,
 };
 
+// Non-local imports.
+use color::Color;
+
 // Standard imports.
 use std::collections::{
 	BTreeMap,
@@ -49,7 +65,6 @@ use std::collections::{
 };
 use std::rc::Rc;
 use std::fmt;
-use std::mem;
 
 
 
@@ -62,19 +77,32 @@ fn no_op(_: &mut Data, _: &Reference) {}
 // MetaData
 ////////////////////////////////////////////////////////////////////////////////
 /// Provides metadata about palette data.
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default)]
 pub struct MetaData {
 	/// A format-generated label for the item.
 	pub format_label: Option<String>,
 
 	/// A user-provided name for the item.
 	pub name: Option<String>,
+
+	/// A user-provided description of the item.
+	pub description: Option<String>,
 	
 	/// An override to the default line count for this group.
 	pub line_count: Line,
 	
 	/// An override to the default column count for this group.
 	pub column_count: Column,
+
+	/// User-provided tags for the item.
+	pub tags: BTreeSet<String>,
+
+	/// The number of source pixels represented by the item, if it was 
+	/// imported from an image.
+	pub usage_count: Option<usize>,
+
+	/// The origin of the item's color, if it is known.
+	pub provenance: Option<Provenance>,
 }
 
 impl fmt::Display for MetaData {
@@ -104,11 +132,29 @@ pub struct Data {
 	/// A map assigning addresses to `Palette` cells.
 	pub cells: BTreeMap<Address, Rc<Cell>>,
 
+	/// The stable identifiers of the `Palette` cells.
+	pub cell_ids: CellIdMap,
+
+	/// The number of times cells have been created, modified, moved, or 
+	/// removed, used to report the cells affected by operations.
+	pub cell_writes: usize,
+
+	/// The number of operations applied to the palette, used to identify the
+	/// operations recorded in the provenance of its cells.
+	pub operation_count: u64,
+
 	/// A map assigning references to names.
-	pub names: HashMap<String, Reference>,
+	pub names: Rc<HashMap<String, Reference>>,
+
+	/// A map assigning semantic tokens to cells. Tokens follow their cells 
+	/// when the cells are moved.
+	pub aliases: Rc<BTreeMap<String, CellId>>,
 
 	/// A map assigning metadata to references.
-	pub metadata: HashMap<Reference, MetaData>,
+	pub metadata: Rc<HashMap<Reference, MetaData>>,
+
+	/// The cells deleted from the `Palette`, in order of deletion.
+	pub trash: Rc<Vec<TrashedCell>>,
 
 	/// The maximum number of pages in the `Palette`.
 	pub maximum_page_count: Page,
@@ -119,6 +165,19 @@ pub struct Data {
 	/// The default number of columns in each line.
 	pub default_column_count: Column,
 
+	/// The working color space of the palette's extended-range colors.
+	pub color_space: RgbSpace,
+
+	/// The tone curve applied to the palette's colors when they are viewed or
+	/// exported.
+	pub tone_curve: Option<ToneCurve>,
+
+	/// The address of the cell used as the palette's reference white.
+	pub white_point: Option<Address>,
+
+	/// The address of the cell used as the palette's reference black.
+	pub black_point: Option<Address>,
+
 	/// Called before a `Cell` is added to a new page in the palette. The 
 	/// expectation is that this will add the appropriate meta data to the 
 	/// palette. This will be called before the prepare_new_line function is 
@@ -149,6 +208,95 @@ impl Data {
 		self.cells.get(&address).cloned()
 	}
 
+	/// Returns a reference to the cell located at the given address for 
+	/// modification, or None if the address is empty. If the cell is shared 
+	/// with a fork of the `Data`, it is first replaced by a private copy, so 
+	/// the modification is not visible to the fork.
+	pub fn cell_mut(&mut self, address: Address) -> Option<Rc<Cell>> {
+		let cell = self.cells.get_mut(&address)?;
+		self.cell_writes += 1;
+		if Rc::strong_count(cell) > 1 {
+			*cell = Rc::new((**cell).clone());
+		}
+		Some(cell.clone())
+	}
+
+	/// Returns a mutable reference to the metadata map, first copying it if it
+	/// is shared with a fork of the `Data`.
+	pub fn metadata_mut(&mut self) -> &mut HashMap<Reference, MetaData> {
+		Rc::make_mut(&mut self.metadata)
+	}
+
+	/// Returns a logical copy of the `Data` which shares its cells, names, and
+	/// metadata with the original. Shared items are copied only when one of
+	/// the copies modifies them, making forks cheap enough to create for every
+	/// preview of an operation.
+	///
+	/// # Example
+	///
+	/// ```rust
+	/// use palette::data::Data;
+	/// use palette::{Address, Color, Expression};
+	/// 
+	/// let mut dat: Data = Default::default();
+	/// let a = Address::new(0, 0, 0);
+	/// *dat.create_cell(a).unwrap().borrow_mut() 
+	/// 	= Expression::Color(Color::new(1, 2, 3));
+	///
+	/// let mut fork = dat.fork();
+	/// *fork.cell_mut(a).unwrap().borrow_mut() 
+	/// 	= Expression::Color(Color::new(4, 5, 6));
+	///
+	/// assert_eq!(dat.cell(a).unwrap().color(), Some(Color::new(1, 2, 3)));
+	/// assert_eq!(fork.cell(a).unwrap().color(), Some(Color::new(4, 5, 6)));
+	/// ```
+	pub fn fork(&self) -> Data {
+		Data {
+			cells: self.cells.clone(),
+			cell_ids: self.cell_ids.clone(),
+			cell_writes: self.cell_writes,
+			operation_count: self.operation_count,
+			names: self.names.clone(),
+			aliases: self.aliases.clone(),
+			metadata: self.metadata.clone(),
+			trash: self.trash.clone(),
+			maximum_page_count: self.maximum_page_count,
+			default_line_count: self.default_line_count,
+			default_column_count: self.default_column_count,
+			color_space: self.color_space,
+			tone_curve: self.tone_curve.clone(),
+			white_point: self.white_point,
+			black_point: self.black_point,
+			prepare_new_page: self.prepare_new_page,
+			prepare_new_line: self.prepare_new_line,
+		}
+	}
+
+	/// Returns the addresses and colors of all cells within the given 
+	/// selection that produce a color, in address order.
+	pub fn colors(&self, selection: &Selection) -> Vec<(Address, Color)> {
+		self.cells
+			.iter()
+			.filter(|&(address, _)| selection.contains(address))
+			.filter_map(|(&address, cell)| cell.color().map(|c| (address, c)))
+			.collect()
+	}
+
+	/// Returns the addresses and extended-range colors of all cells within the
+	/// given selection that produce a color, converted into the given color 
+	/// space, in address order.
+	pub fn wide_colors(&self, selection: &Selection, space: RgbSpace) 
+		-> Vec<(Address, WideColor)> 
+	{
+		self.cells
+			.iter()
+			.filter(|&(address, _)| selection.contains(address))
+			.filter_map(|(&address, cell)| cell
+				.wide_color()
+				.map(|c| (address, c.convert(space))))
+			.collect()
+	}
+
 	/// Returns a reference to the cell located at the given address. If 
 	/// the address is empty, a new cell will be created an a weak reference 
 	/// will be returned. Returns None if the address is invalid.
@@ -172,6 +320,8 @@ impl Data {
 			self.prepare_address(address)?;
 			let new_cell = Rc::new(Cell::new(Default::default()));
 			self.cells.insert(address, new_cell.clone());
+			self.cell_ids.allocate(address);
+			self.cell_writes += 1;
 			Ok(new_cell)
 		}
 	}
@@ -184,12 +334,87 @@ impl Data {
 		let cell = self.cells
 			.remove(&address)
 			.ok_or_else(|| Error::EmptyAddress(address))?;
+		self.cell_ids.release(address);
+		self.cell_writes += 1;
 
-		// Extract Expression and discard wrappers.
-		let expr = mem::replace(&mut *cell.borrow_mut(), Default::default());
+		// Copy the Expression out rather than taking it, as the cell may still
+		// be shared with a fork.
+		let expr = *cell.borrow();
 		Ok(expr)
 	}
 
+	/// Moves the cell at one address to another, empty address. The cell keeps
+	/// its `CellId`, name, and metadata, and the white and black points follow
+	/// it.
+	///
+	/// # Example
+	///
+	/// ```rust
+	/// use palette::data::Data;
+	/// use palette::address::Reference;
+	/// use palette::Address;
+	/// 
+	/// let mut dat: Data = Default::default();
+	/// let (a, b) = (Address::new(0, 0, 0), Address::new(1, 0, 0));
+	/// dat.create_cell(a).unwrap();
+	/// dat.set_name(Reference::from(a), "base");
+	/// let id = dat.cell_id(a).unwrap();
+	///
+	/// dat.move_cell(a, b).unwrap();
+	///
+	/// assert_eq!(dat.address_of_id(id), Some(b));
+	/// assert_eq!(dat.address_of("base"), Some(b));
+	/// assert!(dat.cell(a).is_none());
+	/// ```
+	pub fn move_cell(&mut self, from: Address, to: Address) -> Result<()> {
+		if !self.cells.contains_key(&from) {
+			return Err(Error::EmptyAddress(from));
+		}
+		if self.cells.contains_key(&to) {
+			return Err(Error::AddressInUse(to));
+		}
+		self.prepare_address(to)?;
+
+		let cell = self.cells.remove(&from).expect("cell at move source");
+		self.cells.insert(to, cell);
+		self.cell_ids.relocate(from, to);
+		self.cell_writes += 2;
+
+		// Carry the cell's metadata and names along with it.
+		let (old, new) = (Reference::from(from), Reference::from(to));
+		if let Some(meta) = self.metadata_mut().remove(&old) {
+			self.metadata_mut().insert(new.clone(), meta);
+		}
+		if self.names.values().any(|reference| *reference == old) {
+			for reference in Rc::make_mut(&mut self.names).values_mut() {
+				if *reference == old { *reference = new.clone(); }
+			}
+		}
+		if self.white_point == Some(from) { self.white_point = Some(to); }
+		if self.black_point == Some(from) { self.black_point = Some(to); }
+		Ok(())
+	}
+
+	/// Gives the cell at the given address the `CellId` of a removed cell, so
+	/// that references to the removed cell follow it. Returns false, changing
+	/// nothing, if the address is empty or the identifier has been reused.
+	pub fn reinstate_id(&mut self, address: Address, id: CellId) -> bool {
+		self.cells.contains_key(&address) 
+			&& self.cell_ids.reinstate(address, id)
+	}
+
+	/// Returns the `CellId` of the cell at the given address, or None if the
+	/// address is empty.
+	pub fn cell_id(&self, address: Address) -> Option<CellId> {
+		self.cell_ids.id_of(address)
+	}
+
+	/// Returns the current address of the cell with the given `CellId`, or 
+	/// None if the cell has been removed.
+	pub fn address_of_id(&self, id: CellId) -> Option<Address> {
+		self.cell_ids.address_of(id)
+	}
+
 	/// Returns the label associated with the given group, or
 	/// None if it has no label.
 	///
@@ -211,17 +436,18 @@ impl Data {
 			.map(|label| &label[..])
 	}
 
-	/// Sets the label for the given group.
+	/// Sets the label for the given group. The label is stored in Unicode 
+	/// Normalization Form C.
 	pub fn set_label<S>(
 		&mut self, 
 		group: Reference, 
 		format_label: S) 
 		where S: Into<String> 
 	{
-		self.metadata
+		self.metadata_mut()
 			.entry(group)
 			.or_insert_with(Default::default)
-			.format_label = Some(format_label.into());
+			.format_label = Some(normalize(format_label));
 	}
 
 	/// Returns the name associated with the given group, or None if it has
@@ -243,14 +469,117 @@ impl Data {
 			.map(|name| &name[..])
 	}
 
-	/// Sets the name for the given group.
+	/// Sets the name for the given group. The name is stored in Unicode 
+	/// Normalization Form C.
 	pub fn set_name<S>(&mut self, group: Reference, name: S) 
 		where S: Into<String> 
 	{
+		self.metadata_mut()
+			.entry(group)
+			.or_insert_with(Default::default)
+			.name = Some(normalize(name));
+	}
+
+	/// Returns the description of the given group.
+	pub fn description(&self, group: &Reference) -> Option<&str> {
 		self.metadata
+			.get(group)
+			.and_then(|data| data.description.as_ref())
+			.map(|text| &text[..])
+	}
+
+	/// Sets the description of the given group, or removes it if None is 
+	/// given. The description is stored in Unicode Normalization Form C.
+	pub fn set_description(&mut self, group: Reference, text: Option<String>) {
+		self.metadata_mut()
+			.entry(group)
+			.or_insert_with(Default::default)
+			.description = text.map(normalize);
+	}
+
+	/// Returns the tags of the given group.
+	pub fn tags(&self, group: &Reference) -> Option<&BTreeSet<String>> {
+		self.metadata.get(group).map(|data| &data.tags)
+	}
+
+	/// Adds a tag to the given group. The tag is stored in Unicode 
+	/// Normalization Form C.
+	pub fn add_tag<S>(&mut self, group: Reference, tag: S) 
+		where S: Into<String> 
+	{
+		self.metadata_mut()
 			.entry(group)
 			.or_insert_with(Default::default)
-			.name = Some(name.into());
+			.tags
+			.insert(normalize(tag));
+	}
+
+	/// Removes a tag from the given group. Returns true if the group had the 
+	/// tag.
+	pub fn remove_tag(&mut self, group: &Reference, tag: &str) -> bool {
+		self.metadata_mut()
+			.get_mut(group)
+			.map_or(false, |data| data.tags.remove(&normalize(tag)))
+	}
+
+	/// Returns the address of the cell with the given name, or None if no cell
+	/// has the name.
+	pub fn address_of(&self, name: &str) -> Option<Address> {
+		let name = normalize(name);
+		self.named_cells()
+			.into_iter()
+			.find(|&(ref n, _)| *n == name)
+			.map(|(_, address)| address)
+	}
+
+	/// Assigns the given token to the cell at the given address, replacing any
+	/// previous assignment of the token. The token is stored in Unicode 
+	/// Normalization Form C.
+	pub fn set_alias<S>(&mut self, token: S, address: Address) -> Result<()>
+		where S: Into<String>
+	{
+		let id = self.cell_ids.id_of(address)
+			.ok_or(Error::EmptyAddress(address))?;
+		Rc::make_mut(&mut self.aliases).insert(normalize(token), id);
+		Ok(())
+	}
+
+	/// Removes the given token. Returns true if the token was assigned.
+	pub fn remove_alias(&mut self, token: &str) -> bool {
+		let token = normalize(token);
+		if !self.aliases.contains_key(&token) { return false; }
+		Rc::make_mut(&mut self.aliases).remove(&token).is_some()
+	}
+
+	/// Returns the address of the cell assigned to the given token, or None 
+	/// if the token is unassigned or its cell has been removed.
+	pub fn alias(&self, token: &str) -> Option<Address> {
+		self.aliases.get(&normalize(token))
+			.and_then(|&id| self.cell_ids.address_of(id))
+	}
+
+	/// Returns all tokens and the addresses of their cells, in token order.
+	/// The address is None if the token's cell has been removed.
+	pub fn aliases(&self) -> Vec<(&str, Option<Address>)> {
+		self.aliases.iter()
+			.map(|(token, &id)| (&token[..], self.cell_ids.address_of(id)))
+			.collect()
+	}
+
+	/// Returns the names and addresses of all named cells, in address order.
+	pub fn named_cells(&self) -> Vec<(String, Address)> {
+		let mut named: Vec<_> = self.metadata
+			.iter()
+			.filter_map(|(group, meta)| match (
+				group.page(), group.line(), group.column(), meta.name.as_ref()
+			) {
+				(Ok(p), Ok(l), Ok(c), Some(name))
+					=> Some((name.clone(), Address::new(p, l, c))),
+				_ => None,
+			})
+			.collect();
+		named.sort_by_key(|&(_, address)| address);
+		named
 	}
 
 	/// Returns the next free address after the given address. And error will be
@@ -285,7 +614,7 @@ impl Data {
 
 	/// Calls the prepare_new_page function and returns the current line count 
 	/// for the given group.
-	fn line_count(&mut self, group: &Reference) -> Line {
+	pub(crate) fn line_count(&self, group: &Reference) -> Line {
 		self.metadata
 			.get(group)
 			.map_or(self.default_line_count, |meta| meta.line_count)
@@ -293,7 +622,7 @@ impl Data {
 
 	/// Sets the line count for a group.
 	pub fn set_line_count(&mut self, group: Reference, line_count: Line) {
-		self.metadata
+		self.metadata_mut()
 			.entry(group)
 			.or_insert_with(Default::default)
 			.line_count = line_count;
@@ -301,7 +630,7 @@ impl Data {
 
 	/// Calls the prepare_new_line function and returns the current column count 
 	/// for the given group.
-	fn column_count(&mut self, group: &Reference) -> Column {
+	pub(crate) fn column_count(&self, group: &Reference) -> Column {
 		self.metadata
 			.get(group)
 			.map_or(self.default_column_count, |meta| meta.column_count)
@@ -313,7 +642,7 @@ impl Data {
 		group: Reference, 
 		column_count: Column) 
 	{
-		self.metadata
+		self.metadata_mut()
 			.entry(group)
 			.or_insert_with(Default::default)
 			.column_count = column_count;
@@ -424,12 +753,20 @@ impl fmt::Debug for Data {
 			names: {:#?}, \
 			maximum_page_count: {}, \
 			default_line_count: {}, \
-			default_column_count: {}",
+			default_column_count: {}, \
+			color_space: {:?}, \
+			tone_curve: {:?}, \
+			white_point: {:?}, \
+			black_point: {:?}",
 			self.cells,
 			self.names,
 			self.maximum_page_count,
 			self.default_line_count,
 			self.default_column_count,
+			self.color_space,
+			self.tone_curve,
+			self.white_point,
+			self.black_point,
 		)
 	}
 }
@@ -487,11 +824,20 @@ impl Default for Data {
 	fn default() -> Self {
 		Data {
 			cells: BTreeMap::new(),
-			names: HashMap::new(),
-			metadata: HashMap::new(),
+			cell_ids: CellIdMap::new(),
+			cell_writes: 0,
+			operation_count: 0,
+			names: Rc::new(HashMap::new()),
+			aliases: Rc::new(BTreeMap::new()),
+			metadata: Rc::new(HashMap::new()),
+			trash: Rc::new(Vec::new()),
 			maximum_page_count: PAGE_MAX,
 			default_line_count: LINE_MAX,
 			default_column_count: COLUMN_MAX,
+			color_space: RgbSpace::Srgb,
+			tone_curve: None,
+			white_point: None,
+			black_point: None,
 			prepare_new_page: no_op,
 			prepare_new_line: no_op,
 		}
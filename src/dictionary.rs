@@ -0,0 +1,373 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides named color dictionaries, such as custom swatch libraries, for
+//! matching palette colors against.
+//!
+//! Dictionaries can be read from CSV files, with one color per row given as
+//! a name and either a hex code or red, green, and blue channels:
+//!
+//! ```text
+//! name,color
+//! Warm Red,#F9423A
+//! "Blue, Process",0,133,202
+//! ```
+//!
+//! They can also be read from JSON files, either as an object mapping names
+//! to hex codes, or as an array of objects with `name` and `hex` (or `color`)
+//! fields.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use ::Palette;
+use address::{
+	Address,
+	Selection,
+};
+use format::default::invalid;
+use space::delta_e_2000;
+use utilities::parse_hex;
+
+// Non-local imports.
+use color::Color;
+use serde_json;
+use serde_json::Value;
+
+// Standard imports.
+use std::collections::HashMap;
+use std::io;
+use std::io::{
+	BufRead,
+	BufReader,
+};
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// ColorDictionary
+////////////////////////////////////////////////////////////////////////////////
+/// A named library of colors.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::dictionary::ColorDictionary;
+///
+/// let csv = "name,color\nWarm Red,#F9423A\n\"Blue, Process\",0,133,202\n";
+/// let dict = ColorDictionary::read_csv("spot", &mut csv.as_bytes()).unwrap();
+///
+/// assert_eq!(dict.len(), 2);
+/// assert_eq!(dict.color("blue, process"), Some(Color::new(0, 133, 202)));
+/// assert_eq!(dict.exact(Color::new(249, 66, 58)), Some("Warm Red"));
+/// assert_eq!(dict.nearest(Color::new(250, 66, 58), 1.0).map(|m| m.0), 
+/// 	Some("Warm Red"));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorDictionary {
+	/// The name of the dictionary.
+	name: String,
+	/// The named colors, in the order they were added.
+	entries: Vec<(String, Color)>,
+	/// The index of each entry, by lowercase name.
+	index: HashMap<String, usize>,
+}
+
+
+impl ColorDictionary {
+	/// Creates a new, empty ColorDictionary with the given name.
+	pub fn new<S>(name: S) -> ColorDictionary where S: Into<String> {
+		ColorDictionary {
+			name: name.into(),
+			entries: Vec::new(),
+			index: HashMap::new(),
+		}
+	}
+
+	/// Reads a ColorDictionary with the given name from CSV data. A first row
+	/// without a valid color is treated as a header.
+	pub fn read_csv<S, R>(name: S, in_buf: &mut R) 
+		-> io::Result<ColorDictionary>
+		where
+			S: Into<String>,
+			R: io::Read,
+	{
+		let mut dict = ColorDictionary::new(name);
+		for (i, line) in BufReader::new(in_buf).lines().enumerate() {
+			let line = line?;
+			if line.trim().is_empty() { continue; }
+
+			let fields = csv_fields(&line);
+			match csv_color(&fields[1..]) {
+				Some(color) => dict.insert(fields[0].clone(), color),
+				None if i == 0 => continue,
+				None => return Err(invalid(i, 
+					format!("invalid color for '{}'", fields[0]))),
+			}
+		}
+		Ok(dict)
+	}
+
+	/// Reads a ColorDictionary with the given name from JSON data.
+	pub fn read_json<S, R>(name: S, in_buf: &mut R)
+		-> io::Result<ColorDictionary>
+		where
+			S: Into<String>,
+			R: io::Read,
+	{
+		let json: Value = serde_json::from_reader(in_buf)
+			.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+		let invalid_entry = |name: &str| io::Error::new(
+			io::ErrorKind::InvalidData,
+			format!("invalid color for '{}'", name));
+
+		let mut dict = ColorDictionary::new(name);
+		match json {
+			Value::Object(map) => for (name, value) in map {
+				let color = json_color(&value)
+					.ok_or_else(|| invalid_entry(&name))?;
+				dict.insert(name, color);
+			},
+
+			Value::Array(entries) => for entry in entries {
+				let name = entry.get("name")
+					.and_then(|name| name.as_str())
+					.ok_or_else(|| invalid_entry("<unnamed>"))?;
+				let color = entry.get("hex")
+					.or_else(|| entry.get("color"))
+					.and_then(json_color)
+					.ok_or_else(|| invalid_entry(name))?;
+				dict.insert(name, color);
+			},
+
+			_ => return Err(io::Error::new(
+				io::ErrorKind::InvalidData,
+				"expected an object or array of colors")),
+		}
+		Ok(dict)
+	}
+
+	/// Returns the name of the dictionary.
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	/// Returns the number of colors in the dictionary.
+	pub fn len(&self) -> usize {
+		self.entries.len()
+	}
+
+	/// Returns whether the dictionary contains no colors.
+	pub fn is_empty(&self) -> bool {
+		self.entries.is_empty()
+	}
+
+	/// Returns the names and colors in the dictionary, in the order they were
+	/// added.
+	pub fn entries(&self) -> &[(String, Color)] {
+		&self.entries
+	}
+
+	/// Adds a named color to the dictionary, replacing any color with the 
+	/// same name, ignoring case.
+	pub fn insert<S>(&mut self, name: S, color: Color) where S: Into<String> {
+		let name = name.into();
+		let len = self.entries.len();
+		let index = *self.index.entry(name.to_lowercase()).or_insert(len);
+		if index == len {
+			self.entries.push((name, color));
+		} else {
+			self.entries[index] = (name, color);
+		}
+	}
+
+	/// Returns the color with the given name, ignoring case.
+	pub fn color(&self, name: &str) -> Option<Color> {
+		self.index.get(&name.to_lowercase())
+			.map(|&index| self.entries[index].1)
+	}
+
+	/// Returns the name of the first color exactly matching the given color.
+	pub fn exact(&self, color: Color) -> Option<&str> {
+		self.entries.iter()
+			.find(|&&(_, c)| c == color)
+			.map(|&(ref name, _)| &name[..])
+	}
+
+	/// Returns the name of the color nearest the given color and its 
+	/// CIEDE2000 difference, if it is within the given tolerance.
+	pub fn nearest(&self, color: Color, tolerance: f32) -> Option<(&str, f32)> {
+		self.entries.iter()
+			.map(|&(ref name, c)| (&name[..], delta_e_2000(c, color)))
+			.filter(|&(_, delta)| delta <= tolerance)
+			.fold(None, |best, (name, delta)| match best {
+				Some((_, d)) if d <= delta => best,
+				_ => Some((name, delta)),
+			})
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// DictionaryMatch
+////////////////////////////////////////////////////////////////////////////////
+/// A palette cell matched to a dictionary color.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DictionaryMatch {
+	/// The address of the matched cell.
+	pub address: Address,
+	/// The name of the matching dictionary color.
+	pub name: String,
+	/// The CIEDE2000 difference between the cell and dictionary colors.
+	pub delta: f32,
+}
+
+
+/// Returns the tag used to annotate cells matching the given color in the
+/// dictionary with the given name.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::dictionary::match_tag;
+///
+/// assert_eq!(match_tag("spot", "Warm Red"), "spot:Warm Red");
+/// ```
+pub fn match_tag(dictionary: &str, name: &str) -> String {
+	format!("{}:{}", dictionary, name)
+}
+
+
+/// Matches the selected cells of the palette against the dictionary, tagging
+/// each matched cell with the name of its nearest dictionary color within the
+/// given tolerance, as given by `match_tag`. A tolerance of zero matches only
+/// exact colors. Tags from earlier matches against the same dictionary are
+/// replaced. Returns the matched cells in address order.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::dictionary::{annotate, ColorDictionary};
+/// use palette::operation::*;
+///
+/// let mut pal = Palette::new("Example", Format::Default, true);
+/// pal.apply(Box::new(InsertColor::new(Color::new(250, 66, 58)))).unwrap();
+/// pal.apply(Box::new(InsertColor::new(Color::new(0, 255, 0)))).unwrap();
+///
+/// let mut dict = ColorDictionary::new("spot");
+/// dict.insert("Warm Red", Color::new(249, 66, 58));
+///
+/// let matches = annotate(&mut pal, &Selection::all(), &dict, 2.0);
+/// assert_eq!(matches.len(), 1);
+/// assert_eq!(pal.cell_tags(Address::new(0, 0, 0)), vec!["spot:Warm Red"]);
+/// ```
+pub fn annotate(
+	palette: &mut Palette,
+	selection: &Selection,
+	dictionary: &ColorDictionary,
+	tolerance: f32)
+	-> Vec<DictionaryMatch>
+{
+	let prefix = match_tag(dictionary.name(), "");
+	let mut matches = Vec::new();
+	for (address, color) in palette.colors(selection) {
+		let stale: Vec<String> = palette.cell_tags(address)
+			.into_iter()
+			.filter(|tag| tag.starts_with(&prefix))
+			.map(|tag| tag.to_owned())
+			.collect();
+		for tag in stale {
+			palette.remove_tag(address, &tag);
+		}
+
+		let found = match dictionary.exact(color) {
+			Some(name) => Some((name, 0.0)),
+			None => dictionary.nearest(color, tolerance),
+		};
+		if let Some((name, delta)) = found {
+			palette.add_tag(address, match_tag(dictionary.name(), name));
+			matches.push(DictionaryMatch {
+				address: address,
+				name: name.to_owned(),
+				delta: delta,
+			});
+		}
+	}
+	matches
+}
+
+
+/// Splits a CSV row into its fields, removing quotes.
+fn csv_fields(line: &str) -> Vec<String> {
+	let mut fields = vec![String::new()];
+	let mut quoted = false;
+	let mut chars = line.chars().peekable();
+	while let Some(c) = chars.next() {
+		match c {
+			'"' if quoted && chars.peek() == Some(&'"') => {
+				chars.next();
+				fields.last_mut().unwrap().push('"');
+			},
+			'"' => quoted = !quoted,
+			',' if !quoted => fields.push(String::new()),
+			_ => fields.last_mut().unwrap().push(c),
+		}
+	}
+	for field in &mut fields {
+		*field = field.trim().to_owned();
+	}
+	fields
+}
+
+
+/// Parses the color fields of a CSV row.
+fn csv_color(fields: &[String]) -> Option<Color> {
+	match fields.len() {
+		1 => parse_hex(&fields[0]),
+		3 => Some(Color::new(
+			fields[0].parse().ok()?,
+			fields[1].parse().ok()?,
+			fields[2].parse().ok()?,
+		)),
+		_ => None,
+	}
+}
+
+
+/// Parses a JSON color, given as a hex string or an array of channels.
+fn json_color(value: &Value) -> Option<Color> {
+	match *value {
+		Value::String(ref hex) => parse_hex(hex),
+		Value::Array(ref channels) if channels.len() == 3 => {
+			let channel = |i: usize| channels[i].as_u64()
+				.filter(|&v| v <= 255)
+				.map(|v| v as u8);
+			Some(Color::new(channel(0)?, channel(1)?, channel(2)?))
+		},
+		_ => None,
+	}
+}
@@ -0,0 +1,1372 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides a standalone evaluator for color expressions.
+//!
+//! Expressions are built from hex colors (`#ff0000`), numbers with optional
+//! units (`0.3`, `10%`, `30deg`, `128ch`), color names, and function calls
+//! (`mix(#ff0000, skin, 0.3)`). The pipe operator passes its left side as the
+//! first argument of the function on its right, so `skin |> darken(10%)` is
+//! equivalent to `darken(skin, 10%)`.
+//!
+//! The built-in functions are:
+//!
+//! + `mix(a, b, amount)`: interpolates between two colors in Oklab space.
+//! + `darken(color, amount)`, `lighten(color, amount)`: adjusts the L*a*b*
+//! lightness of a color by a fraction of its full range.
+//! + `saturate(color, amount)`, `desaturate(color, amount)`: adjusts the HSL
+//! saturation of a color by a fraction of its full range.
+//! + `rotate_hue(color, angle)`: rotates the HSL hue of a color. Plain numbers
+//! are read as degrees.
+//! + `contrast_against(color, background[, ratio])`: adjusts the lightness of 
+//! a color until it has at least the given WCAG contrast ratio against the
+//! background, defaulting to 4.5.
+//! + `rgb(r, g, b)`: creates a color from channel values. Plain numbers are
+//! read as 8-bit channel values.
+//! + `invert(color)`: inverts the channels of a color.
+//! + `lightness(color)`: the L*a*b* lightness of a color, from 0 to 1.
+//! + `chroma(color)`: the L*a*b* chroma of a color, where 1 is a chroma of
+//! 100.
+//! + `hue(color)`: the L*a*b* hue angle of a color, in degrees.
+//! + `contrast(a, b)`: the WCAG contrast ratio of two colors, from 1 to 21.
+//!
+//! Conditional expressions choose between two expressions, so that derived
+//! colors can adapt to the colors they are derived from:
+//!
+//! ```text
+//! if lightness(base) > 0.5 then darken(base, 60%) else lighten(base, 60%)
+//! ```
+//!
+//! Conditions compare two expressions with `<`, `<=`, `>`, `>=`, `=`, or 
+//! `!=`, as selectors do, and can be combined with `not`, `and`, and `or`, in
+//! decreasing order of precedence. Numbers are compared as fractions of their full range, so 
+//! `lightness(base) > 50%` is equivalent to the condition above, and angles
+//! are compared with angles, as in `hue(base) < 90deg`. Colors can only be
+//! compared for equality. Only the chosen expression is evaluated, and 
+//! `else if` chains conditions.
+//!
+//! Additional functions can be registered with a `FunctionRegistry`. Numeric
+//! arguments are converted between units as described in the `unit` module.
+//!
+//! Parsing and evaluation are bounded by `EvalLimits`, which cap the nesting
+//! depth and size of an expression and the time spent evaluating it, so that
+//! expressions from untrusted sources cannot exhaust the stack or hang the 
+//! editor. The default limits are generous enough for any hand-written 
+//! expression.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use ::Palette;
+use select::Comparison;
+use space::{
+	GamutMapping,
+	Hsl,
+	Hsluv,
+	Lab,
+	Oklab,
+	contrast_ratio,
+	unit_channels,
+};
+use unit::Quantity;
+use utilities::{
+	hex,
+	parse_hex,
+};
+
+// Non-local imports.
+use color::Color;
+
+// Standard imports.
+use std::collections::HashMap;
+use std::fmt;
+use std::iter::Peekable;
+use std::rc::Rc;
+use std::result;
+use std::time::{
+	Duration,
+	Instant,
+};
+
+
+/// The result of parsing or evaluating an expression.
+pub type EvalResult<T> = result::Result<T, EvalError>;
+
+/// The result of invoking an expression function.
+pub type FunctionResult = result::Result<Value, String>;
+
+/// A function callable from expressions.
+pub type Function = Rc<Fn(&[Value]) -> FunctionResult>;
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Span
+////////////////////////////////////////////////////////////////////////////////
+/// A range of byte offsets within an expression's source text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Span {
+	/// The offset of the first byte of the range.
+	pub start: usize,
+	/// The offset following the last byte of the range.
+	pub end: usize,
+}
+
+
+impl Span {
+	/// Creates a new `Span`.
+	pub fn new(start: usize, end: usize) -> Self {
+		Span {start: start, end: end}
+	}
+
+	/// Returns the smallest span containing both this span and the given 
+	/// span.
+	pub fn join(&self, other: Span) -> Span {
+		Span::new(self.start.min(other.start), self.end.max(other.end))
+	}
+}
+
+
+impl fmt::Display for Span {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}..{}", self.start, self.end)
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// EvalError
+////////////////////////////////////////////////////////////////////////////////
+/// An error produced while parsing or evaluating an expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvalError {
+	/// A description of the error.
+	pub message: String,
+	/// The span of the source text responsible for the error.
+	pub span: Span,
+}
+
+
+impl EvalError {
+	/// Creates a new `EvalError`.
+	pub fn new<S>(message: S, span: Span) -> Self 
+		where S: Into<String>
+	{
+		EvalError {message: message.into(), span: span}
+	}
+
+	/// Returns the error message followed by the given source text, with the
+	/// span of the error underlined.
+	///
+	/// # Example
+	///
+	/// ```rust
+	/// use palette::eval::Evaluator;
+	///
+	/// let source = "darken(#ff0000, 10%, 3)";
+	/// let error = Evaluator::new().eval(source).unwrap_err();
+	/// assert_eq!(error.show(source), "\
+	/// darken expects 2 arguments, found 3
+	///     darken(#ff0000, 10%, 3)
+	///     ^^^^^^^^^^^^^^^^^^^^^^^");
+	/// ```
+	pub fn show(&self, source: &str) -> String {
+		let start = source[..self.span.start.min(source.len())]
+			.chars()
+			.count();
+		let width = source.get(self.span.start..self.span.end)
+			.map_or(0, |s| s.chars().count())
+			.max(1);
+		format!("{}\n    {}\n    {}{}",
+			self.message,
+			source,
+			" ".repeat(start),
+			"^".repeat(width))
+	}
+}
+
+
+impl fmt::Display for EvalError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{} at {}", self.message, self.span)
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// EvalLimits
+////////////////////////////////////////////////////////////////////////////////
+/// Bounds on the resources used to parse and evaluate an expression.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::eval::{EvalLimits, Evaluator};
+///
+/// let nested = format!("{}#808080{}", 
+/// 	"invert(".repeat(100),
+/// 	")".repeat(100));
+/// let error = Evaluator::new().eval(&nested).unwrap_err();
+/// assert_eq!(error.message, "expression exceeds the maximum depth of 64");
+///
+/// let limits = EvalLimits::default().max_depth(200);
+/// assert!(Evaluator::new().limits(limits).eval(&nested).is_ok());
+///
+/// let limits = EvalLimits::default().max_nodes(3);
+/// let error = Evaluator::new().limits(limits)
+/// 	.eval("mix(#000000, #FFFFFF, 50%)")
+/// 	.unwrap_err();
+/// assert_eq!(error.message, "expression exceeds the maximum of 3 terms");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EvalLimits {
+	/// The maximum nesting depth of terms and conditions.
+	max_depth: usize,
+	/// The maximum number of terms and conditions parsed or evaluated.
+	max_nodes: usize,
+	/// The maximum time spent evaluating an expression, if any.
+	timeout: Option<Duration>,
+}
+
+
+impl Default for EvalLimits {
+	fn default() -> Self {
+		EvalLimits {
+			max_depth: 64,
+			max_nodes: 10_000,
+			timeout: Some(Duration::from_secs(1)),
+		}
+	}
+}
+
+
+impl EvalLimits {
+	/// Creates a new `EvalLimits` with the default limits.
+	pub fn new() -> Self {
+		Default::default()
+	}
+
+	/// Sets the maximum nesting depth of terms and conditions.
+	pub fn max_depth(mut self, max_depth: usize) -> Self {
+		self.max_depth = max_depth;
+		self
+	}
+
+	/// Sets the maximum number of terms and conditions parsed or evaluated 
+	/// for a single expression.
+	pub fn max_nodes(mut self, max_nodes: usize) -> Self {
+		self.max_nodes = max_nodes;
+		self
+	}
+
+	/// Sets the maximum time spent evaluating a single expression, or None 
+	/// for no time limit. The time spent within a single function call is 
+	/// not interrupted.
+	pub fn timeout(mut self, timeout: Option<Duration>) -> Self {
+		self.timeout = timeout;
+		self
+	}
+
+	/// Returns an error if the given depth or node count exceeds the limits.
+	fn check(&self, depth: usize, nodes: usize, span: Span) 
+		-> EvalResult<()>
+	{
+		if depth > self.max_depth {
+			Err(EvalError::new(
+				format!("expression exceeds the maximum depth of {}", 
+					self.max_depth),
+				span))
+		} else if nodes > self.max_nodes {
+			Err(EvalError::new(
+				format!("expression exceeds the maximum of {} terms", 
+					self.max_nodes),
+				span))
+		} else {
+			Ok(())
+		}
+	}
+}
+
+
+/// The resources consumed by an evaluation in progress.
+struct Budget {
+	/// The limits of the evaluation.
+	limits: EvalLimits,
+	/// The number of terms and conditions evaluated.
+	nodes: usize,
+	/// The time after which the evaluation is abandoned, if any.
+	deadline: Option<Instant>,
+}
+
+
+impl Budget {
+	/// Creates a new `Budget` for an evaluation starting now.
+	fn new(limits: EvalLimits) -> Self {
+		Budget {
+			limits: limits,
+			nodes: 0,
+			deadline: limits.timeout.map(|timeout| Instant::now() + timeout),
+		}
+	}
+
+	/// Accounts for evaluating a node at the given depth.
+	fn enter(&mut self, depth: usize, span: Span) -> EvalResult<()> {
+		self.nodes += 1;
+		self.limits.check(depth, self.nodes, span)?;
+		self.check_time(span)
+	}
+
+	/// Returns an error if the evaluation has run out of time.
+	fn check_time(&self, span: Span) -> EvalResult<()> {
+		match (self.deadline, self.limits.timeout) {
+			(Some(deadline), Some(timeout)) if Instant::now() > deadline => 
+				Err(EvalError::new(
+					format!("evaluation exceeds the time limit of {}ms", 
+						timeout.as_millis()),
+					span)),
+			_ => Ok(()),
+		}
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Value
+////////////////////////////////////////////////////////////////////////////////
+/// A value produced by evaluating an expression.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Value {
+	/// A color.
+	Color(Color),
+	/// A number with its unit.
+	Number(Quantity),
+}
+
+
+impl Value {
+	/// Returns the value as a color, or an error if it is not a color.
+	pub fn as_color(&self) -> result::Result<Color, String> {
+		match *self {
+			Value::Color(color) => Ok(color),
+			Value::Number(n) => Err(format!("expected color, found {}", n)),
+		}
+	}
+
+	/// Returns the value as a number, or an error if it is not a number.
+	pub fn as_quantity(&self) -> result::Result<Quantity, String> {
+		match *self {
+			Value::Number(n) => Ok(n),
+			Value::Color(c) => Err(format!("expected number, found {}", hex(c))),
+		}
+	}
+
+	/// Returns the value as a fraction of a full range, or an error if it is 
+	/// not a number.
+	pub fn as_fraction(&self) -> result::Result<f32, String> {
+		self.as_quantity().map(|q| q.as_fraction())
+	}
+
+	/// Returns the value as an angle in degrees, or an error if it is not a 
+	/// number.
+	pub fn as_degrees(&self) -> result::Result<f32, String> {
+		self.as_quantity().map(|q| q.as_degrees())
+	}
+
+	/// Returns the value as an 8-bit channel value, or an error if it is not
+	/// a number.
+	pub fn as_channel(&self) -> result::Result<f32, String> {
+		self.as_quantity().map(|q| q.as_channel())
+	}
+}
+
+
+impl fmt::Display for Value {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			Value::Color(color) => write!(f, "{}", hex(color)),
+			Value::Number(n) => write!(f, "{}", n),
+		}
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Notation
+////////////////////////////////////////////////////////////////////////////////
+/// A notation for displaying colors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Notation {
+	/// A hexadecimal sRGB color, e.g. `#ff8000`.
+	Hex,
+	/// Integer sRGB channels, e.g. `rgb(255, 128, 0)`.
+	Rgb,
+	/// HSL components, e.g. `hsl(30.1, 100%, 50%)`.
+	Hsl,
+	/// HSLuv components, e.g. `hsluv(12.2, 100%, 53.2%)`.
+	Hsluv,
+	/// CIE L*a*b* components.
+	Lab,
+	/// Oklab components.
+	Oklab,
+}
+
+
+impl Default for Notation {
+	fn default() -> Self {
+		Notation::Hex
+	}
+}
+
+
+impl Notation {
+	/// Returns the name of the notation.
+	pub fn name(&self) -> &'static str {
+		match *self {
+			Notation::Hex   => "hex",
+			Notation::Rgb   => "rgb",
+			Notation::Hsl   => "hsl",
+			Notation::Hsluv => "hsluv",
+			Notation::Lab   => "lab",
+			Notation::Oklab => "oklab",
+		}
+	}
+
+	/// Returns the notation with the given name, ignoring case.
+	pub fn from_name(name: &str) -> Option<Notation> {
+		[
+			Notation::Hex,
+			Notation::Rgb,
+			Notation::Hsl,
+			Notation::Hsluv,
+			Notation::Lab,
+			Notation::Oklab,
+		].iter()
+			.find(|n| n.name().eq_ignore_ascii_case(name))
+			.cloned()
+	}
+
+	/// Returns the given color written in the notation.
+	pub fn format(&self, color: Color) -> String {
+		match *self {
+			Notation::Hex => hex(color),
+			Notation::Rgb => format!("rgb({}, {}, {})",
+				color.red(), color.green(), color.blue()),
+			Notation::Hsl => {
+				let hsl = Hsl::from(color);
+				format!("hsl({:.1}, {:.1}%, {:.1}%)",
+					hsl.h, hsl.s * 100.0, hsl.l * 100.0)
+			},
+			Notation::Hsluv => {
+				let hsluv = Hsluv::from(color);
+				format!("hsluv({:.1}, {:.1}%, {:.1}%)",
+					hsluv.h, hsluv.s, hsluv.l)
+			},
+			Notation::Lab => {
+				let lab = Lab::from(color);
+				format!("lab({:.2}, {:.2}, {:.2})", lab.l, lab.a, lab.b)
+			},
+			Notation::Oklab => {
+				let lab = Oklab::from(color);
+				format!("oklab({:.4}, {:.4}, {:.4})", lab.l, lab.a, lab.b)
+			},
+		}
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// FunctionRegistry
+////////////////////////////////////////////////////////////////////////////////
+/// A collection of named functions callable from expressions.
+#[derive(Clone, Default)]
+pub struct FunctionRegistry {
+	/// The registered functions, by name.
+	functions: HashMap<String, Function>,
+}
+
+
+impl FunctionRegistry {
+	/// Creates a new, empty `FunctionRegistry`.
+	pub fn new() -> Self {
+		Default::default()
+	}
+
+	/// Creates a new `FunctionRegistry` containing the built-in functions.
+	pub fn builtins() -> Self {
+		let mut registry = FunctionRegistry::new();
+		registry.register("mix", mix);
+		registry.register("darken", |args: &[Value]| adjust_lightness(args, -1.0));
+		registry.register("lighten", |args: &[Value]| adjust_lightness(args, 1.0));
+		registry.register("saturate", 
+			|args: &[Value]| adjust_saturation(args, 1.0));
+		registry.register("desaturate", 
+			|args: &[Value]| adjust_saturation(args, -1.0));
+		registry.register("rotate_hue", rotate_hue);
+		registry.register("contrast_against", contrast_against);
+		registry.register("rgb", rgb);
+		registry.register("invert", invert);
+		registry.register("lightness", lightness);
+		registry.register("chroma", chroma);
+		registry.register("hue", hue);
+		registry.register("contrast", contrast);
+		registry
+	}
+
+	/// Registers a function with the given name, replacing any function 
+	/// already registered with that name.
+	pub fn register<S, F>(&mut self, name: S, function: F)
+		where 
+			S: Into<String>,
+			F: Fn(&[Value]) -> FunctionResult + 'static
+	{
+		self.functions.insert(name.into(), Rc::new(function));
+	}
+
+	/// Returns the function with the given name.
+	pub fn get(&self, name: &str) -> Option<&Function> {
+		self.functions.get(name)
+	}
+
+	/// Returns the names of the registered functions, in sorted order.
+	pub fn names(&self) -> Vec<&str> {
+		let mut names: Vec<_> = self.functions.keys().map(|n| &n[..]).collect();
+		names.sort();
+		names
+	}
+}
+
+
+impl fmt::Debug for FunctionRegistry {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_list().entries(self.names()).finish()
+	}
+}
+
+
+/// Returns an error if the number of arguments lies outside of the given 
+/// bounds.
+fn arity(name: &str, args: &[Value], min: usize, max: usize) 
+	-> result::Result<(), String>
+{
+	if args.len() >= min && args.len() <= max {
+		Ok(())
+	} else if min == max {
+		Err(format!("{} expects {} arguments, found {}", 
+			name, min, args.len()))
+	} else {
+		Err(format!("{} expects {} to {} arguments, found {}", 
+			name, min, max, args.len()))
+	}
+}
+
+
+/// Interpolates between two colors in Oklab space.
+fn mix(args: &[Value]) -> FunctionResult {
+	arity("mix", args, 3, 3)?;
+	let a = Oklab::from(args[0].as_color()?);
+	let b = Oklab::from(args[1].as_color()?);
+	Ok(Value::Color(Color::from(a.lerp(&b, args[2].as_fraction()?))))
+}
+
+
+/// Adjusts the lightness of a color in the given direction.
+fn adjust_lightness(args: &[Value], direction: f32) -> FunctionResult {
+	let name = if direction < 0.0 { "darken" } else { "lighten" };
+	arity(name, args, 2, 2)?;
+	let mut lab = Lab::from(args[0].as_color()?);
+	lab.l = (lab.l + direction * args[1].as_fraction()? * 100.0)
+		.max(0.0)
+		.min(100.0);
+	Ok(Value::Color(GamutMapping::default().map(lab)))
+}
+
+
+/// Adjusts the saturation of a color in the given direction.
+fn adjust_saturation(args: &[Value], direction: f32) -> FunctionResult {
+	let name = if direction < 0.0 { "desaturate" } else { "saturate" };
+	arity(name, args, 2, 2)?;
+	let mut hsl = Hsl::from(args[0].as_color()?);
+	hsl.s = (hsl.s + direction * args[1].as_fraction()?).max(0.0).min(1.0);
+	Ok(Value::Color(Color::from(hsl)))
+}
+
+
+/// Rotates the hue of a color.
+fn rotate_hue(args: &[Value]) -> FunctionResult {
+	arity("rotate_hue", args, 2, 2)?;
+	let mut hsl = Hsl::from(args[0].as_color()?);
+	hsl.h = (hsl.h + args[1].as_degrees()?).rem_euclid(360.0);
+	Ok(Value::Color(Color::from(hsl)))
+}
+
+
+/// Adjusts the lightness of a color until it reaches a minimum contrast ratio
+/// against a background color.
+fn contrast_against(args: &[Value]) -> FunctionResult {
+	arity("contrast_against", args, 2, 3)?;
+	let color = args[0].as_color()?;
+	let background = args[1].as_color()?;
+	let ratio = match args.get(2) {
+		Some(value) => value.as_quantity()?.value,
+		None => 4.5,
+	};
+	if contrast_ratio(color, background) >= ratio {
+		return Ok(Value::Color(color));
+	}
+
+	// Move away from the background's lightness, searching for the smallest
+	// change that meets the ratio.
+	let lab = Lab::from(color);
+	let target = if Lab::from(background).l > 50.0 { 0.0 } else { 100.0 };
+	let at = |t: f32| GamutMapping::default()
+		.map(Lab::new(lab.l + (target - lab.l) * t, lab.a, lab.b));
+	let (mut low, mut high) = (0.0, 1.0);
+	for _ in 0..24 {
+		let mid = (low + high) / 2.0;
+		if contrast_ratio(at(mid), background) >= ratio {
+			high = mid;
+		} else {
+			low = mid;
+		}
+	}
+	Ok(Value::Color(at(high)))
+}
+
+
+/// Creates a color from 8-bit channel values.
+fn rgb(args: &[Value]) -> FunctionResult {
+	arity("rgb", args, 3, 3)?;
+	let channel = |v: &Value| v.as_channel()
+		.map(|n| n.round().max(0.0).min(255.0) as u8);
+	Ok(Value::Color(Color::new(
+		channel(&args[0])?,
+		channel(&args[1])?,
+		channel(&args[2])?)))
+}
+
+
+/// Inverts the channels of a color.
+fn invert(args: &[Value]) -> FunctionResult {
+	arity("invert", args, 1, 1)?;
+	let [r, g, b] = unit_channels(args[0].as_color()?);
+	let channel = |v: f32| ((1.0 - v) * 255.0).round() as u8;
+	Ok(Value::Color(Color::new(channel(r), channel(g), channel(b))))
+}
+
+
+/// Returns the lightness of a color as a fraction.
+fn lightness(args: &[Value]) -> FunctionResult {
+	arity("lightness", args, 1, 1)?;
+	let lab = Lab::from(args[0].as_color()?);
+	Ok(Value::Number(Quantity::number(lab.l / 100.0)))
+}
+
+
+/// Returns the chroma of a color as a fraction of 100.
+fn chroma(args: &[Value]) -> FunctionResult {
+	arity("chroma", args, 1, 1)?;
+	let lab = Lab::from(args[0].as_color()?);
+	Ok(Value::Number(Quantity::number(lab.chroma() / 100.0)))
+}
+
+
+/// Returns the hue angle of a color.
+fn hue(args: &[Value]) -> FunctionResult {
+	arity("hue", args, 1, 1)?;
+	let lab = Lab::from(args[0].as_color()?);
+	Ok(Value::Number(Quantity::degrees(lab.hue())))
+}
+
+
+/// Returns the contrast ratio of two colors.
+fn contrast(args: &[Value]) -> FunctionResult {
+	arity("contrast", args, 2, 2)?;
+	let ratio = contrast_ratio(args[0].as_color()?, args[1].as_color()?);
+	Ok(Value::Number(Quantity::number(ratio)))
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Evaluator
+////////////////////////////////////////////////////////////////////////////////
+/// Evaluates color expressions.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::eval::{Evaluator, Value};
+///
+/// let evaluator = Evaluator::new()
+/// 	.define("skin_base", Color::new(255, 200, 170))
+/// 	.function("gray", |args: &[Value]| {
+/// 		let v = args[0].as_channel()?;
+/// 		Ok(Value::Color(Color::new(v as u8, v as u8, v as u8)))
+/// 	});
+///
+/// let value = evaluator
+/// 	.eval("mix(#ff0000, skin_base, 0.3) |> darken(10%)")
+/// 	.unwrap();
+/// assert!(value.as_color().is_ok());
+///
+/// assert_eq!(
+/// 	evaluator.eval("gray(128ch) |> rotate_hue(90deg)"),
+/// 	Ok(Value::Color(Color::new(128, 128, 128))));
+///
+/// let error = evaluator.eval("mix(#000000, shade, 0.5)").unwrap_err();
+/// assert_eq!(error.message, "undefined name 'shade'");
+/// assert_eq!((error.span.start, error.span.end), (13, 18));
+/// ```
+///
+/// Choosing a text color which contrasts with a background:
+///
+/// ```rust
+/// use palette::*;
+/// use palette::eval::{Evaluator, Value};
+///
+/// let text = "if lightness(background) > 0.5 and contrast(background, \
+/// 	#000000) >= 4.5 then #000000 else #FFFFFF";
+///
+/// let light = Evaluator::new().define("background", Color::new(240, 230, 9));
+/// assert_eq!(light.eval(text), Ok(Value::Color(Color::new(0, 0, 0))));
+///
+/// let dark = Evaluator::new().define("background", Color::new(30, 20, 60));
+/// assert_eq!(dark.eval(text), Ok(Value::Color(Color::new(255, 255, 255))));
+///
+/// let error = dark.eval("if background > 50% then #000 else #FFF");
+/// assert!(error.unwrap_err().message.starts_with("cannot compare"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Evaluator {
+	/// The named colors available to expressions.
+	names: HashMap<String, Color>,
+	/// The functions available to expressions.
+	functions: FunctionRegistry,
+	/// The limits on parsing and evaluating expressions.
+	limits: EvalLimits,
+}
+
+
+impl Default for Evaluator {
+	fn default() -> Self {
+		Evaluator {
+			names: HashMap::new(),
+			functions: FunctionRegistry::builtins(),
+			limits: EvalLimits::default(),
+		}
+	}
+}
+
+
+impl Evaluator {
+	/// Creates a new `Evaluator` with the built-in functions and no named 
+	/// colors.
+	pub fn new() -> Self {
+		Default::default()
+	}
+
+	/// Defines a named color.
+	pub fn define<S>(mut self, name: S, color: Color) -> Self 
+		where S: Into<String>
+	{
+		self.names.insert(name.into(), color);
+		self
+	}
+
+	/// Defines the named cells of the given palette.
+	pub fn palette(mut self, palette: &Palette) -> Self {
+		for (name, address) in palette.named_cells() {
+			if let Some(color) = palette.color(address) {
+				self.names.insert(name, color);
+			}
+		}
+		self
+	}
+
+	/// Registers a function with the given name.
+	pub fn function<S, F>(mut self, name: S, function: F) -> Self
+		where 
+			S: Into<String>,
+			F: Fn(&[Value]) -> FunctionResult + 'static
+	{
+		self.functions.register(name, function);
+		self
+	}
+
+	/// Sets the functions available to expressions, replacing the built-in 
+	/// functions.
+	pub fn functions(mut self, functions: FunctionRegistry) -> Self {
+		self.functions = functions;
+		self
+	}
+
+	/// Sets the limits on parsing and evaluating expressions.
+	pub fn limits(mut self, limits: EvalLimits) -> Self {
+		self.limits = limits;
+		self
+	}
+
+	/// Parses and evaluates the given expression.
+	pub fn eval(&self, text: &str) -> EvalResult<Value> {
+		self.evaluate(&parse_with_limits(text, self.limits)?)
+	}
+
+	/// Evaluates a parsed expression.
+	///
+	/// # Errors
+	///
+	/// Returns an `EvalError` if the expression cannot be evaluated or 
+	/// exceeds the evaluator's limits.
+	pub fn evaluate(&self, term: &Term) -> EvalResult<Value> {
+		self.evaluate_within(term, 1, &mut Budget::new(self.limits))
+	}
+
+	/// Evaluates a parsed condition.
+	pub fn test(&self, condition: &Condition) -> EvalResult<bool> {
+		self.test_within(condition, 1, &mut Budget::new(self.limits))
+	}
+
+	/// Evaluates a parsed expression at the given depth, within the given 
+	/// budget.
+	fn evaluate_within(&self, term: &Term, depth: usize, budget: &mut Budget)
+		-> EvalResult<Value>
+	{
+		budget.enter(depth, term.span)?;
+		match term.kind {
+			TermKind::Value(value) => Ok(value),
+			TermKind::Name(ref name) => self.names.get(name)
+				.map(|&color| Value::Color(color))
+				.ok_or_else(|| EvalError::new(
+					format!("undefined name '{}'", name),
+					term.span)),
+			TermKind::Call(ref name, ref args) => {
+				let function = self.functions.get(name)
+					.ok_or_else(|| EvalError::new(
+						format!("unknown function '{}'", name),
+						term.span))?;
+				let args = args.iter()
+					.map(|arg| self.evaluate_within(arg, depth + 1, budget))
+					.collect::<EvalResult<Vec<_>>>()?;
+				let value = function(&args)
+					.map_err(|e| EvalError::new(e, term.span))?;
+				budget.check_time(term.span)?;
+				Ok(value)
+			},
+			TermKind::Conditional(ref condition, ref then, ref otherwise) => {
+				let depth = depth + 1;
+				if self.test_within(condition, depth, budget)? {
+					self.evaluate_within(then, depth, budget)
+				} else {
+					self.evaluate_within(otherwise, depth, budget)
+				}
+			},
+		}
+	}
+
+	/// Evaluates a parsed condition at the given depth, within the given 
+	/// budget.
+	fn test_within(&self, condition: &Condition, depth: usize, 
+		budget: &mut Budget)
+		-> EvalResult<bool>
+	{
+		budget.enter(depth, condition.span())?;
+		let depth = depth + 1;
+		match *condition {
+			Condition::Compare(comparison, ref left, ref right) => {
+				let a = self.evaluate_within(left, depth, budget)?;
+				let b = self.evaluate_within(right, depth, budget)?;
+				compare(comparison, a, b)
+					.map_err(|e| EvalError::new(e, left.span.join(right.span)))
+			},
+			Condition::Not(ref inner) => 
+				Ok(!self.test_within(inner, depth, budget)?),
+			Condition::And(ref a, ref b) => 
+				Ok(self.test_within(a, depth, budget)? 
+					&& self.test_within(b, depth, budget)?),
+			Condition::Or(ref a, ref b) => 
+				Ok(self.test_within(a, depth, budget)? 
+					|| self.test_within(b, depth, budget)?),
+		}
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Term
+////////////////////////////////////////////////////////////////////////////////
+/// A parsed expression.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Term {
+	/// The kind of expression.
+	pub kind: TermKind,
+	/// The span of the expression's source text.
+	pub span: Span,
+}
+
+
+/// The kinds of parsed expressions.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TermKind {
+	/// A literal value.
+	Value(Value),
+	/// A named color.
+	Name(String),
+	/// A function call with its arguments. Piped values are included as the
+	/// first argument.
+	Call(String, Vec<Term>),
+	/// A choice between the second and third expressions, depending on the 
+	/// condition.
+	Conditional(Box<Condition>, Box<Term>, Box<Term>),
+}
+
+
+/// A parsed condition of a conditional expression.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+	/// A comparison of two expressions.
+	Compare(Comparison, Term, Term),
+	/// The negation of a condition.
+	Not(Box<Condition>),
+	/// A condition which holds if both conditions hold.
+	And(Box<Condition>, Box<Condition>),
+	/// A condition which holds if either condition holds.
+	Or(Box<Condition>, Box<Condition>),
+}
+
+
+impl Condition {
+	/// Returns the span of the condition's source text.
+	pub fn span(&self) -> Span {
+		match *self {
+			Condition::Compare(_, ref left, ref right) 
+				=> left.span.join(right.span),
+			Condition::Not(ref inner) => inner.span(),
+			Condition::And(ref a, ref b) |
+			Condition::Or(ref a, ref b) => a.span().join(b.span()),
+		}
+	}
+}
+
+
+/// Compares the given values. Numbers are compared as fractions of their full
+/// range, and colors can only be compared for equality.
+fn compare(comparison: Comparison, a: Value, b: Value) 
+	-> result::Result<bool, String>
+{
+	match (a, b) {
+		(Value::Number(a), Value::Number(b)) 
+			=> Ok(comparison.holds(a.as_fraction(), b.as_fraction())),
+		(Value::Color(a), Value::Color(b)) => match comparison {
+			Comparison::Equal    => Ok(a == b),
+			Comparison::NotEqual => Ok(a != b),
+			_ => Err(format!("cannot compare colors with '{}'", 
+				comparison.symbol())),
+		},
+		(a, b) => Err(format!("cannot compare {} with {}", a, b)),
+	}
+}
+
+
+/// Parses the given expression within the default `EvalLimits`.
+///
+/// # Errors
+///
+/// Returns an `EvalError` describing the first syntax error encountered.
+pub fn parse(text: &str) -> EvalResult<Term> {
+	parse_with_limits(text, EvalLimits::default())
+}
+
+
+/// Parses the given expression within the given limits.
+///
+/// # Errors
+///
+/// Returns an `EvalError` describing the first syntax error encountered, or
+/// the point at which the expression exceeds the limits.
+pub fn parse_with_limits(text: &str, limits: EvalLimits) -> EvalResult<Term> {
+	let mut parser = Parser {
+		tokens: tokenize(text)?,
+		position: 0,
+		end: text.len(),
+		limits: limits,
+		depth: 0,
+		nodes: 0,
+	};
+	let term = parser.pipeline()?;
+	match parser.tokens.get(parser.position) {
+		Some(&(ref token, span)) => Err(EvalError::new(
+			format!("unexpected {}", token),
+			span)),
+		None => Ok(term),
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Parsing
+////////////////////////////////////////////////////////////////////////////////
+/// A token of the expression language.
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+	/// A literal value.
+	Value(Value),
+	/// A name.
+	Name(String),
+	/// An opening parenthesis.
+	Open,
+	/// A closing parenthesis.
+	Close,
+	/// An argument separator.
+	Comma,
+	/// The pipe operator.
+	Pipe,
+	/// A comparison operator.
+	Compare(Comparison),
+}
+
+
+impl fmt::Display for Token {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			Token::Value(value) => write!(f, "'{}'", value),
+			Token::Name(ref name) => write!(f, "'{}'", name),
+			Token::Open => write!(f, "'('"),
+			Token::Close => write!(f, "')'"),
+			Token::Comma => write!(f, "','"),
+			Token::Pipe => write!(f, "'|>'"),
+			Token::Compare(op) => write!(f, "'{}'", op.symbol()),
+		}
+	}
+}
+
+
+/// Splits the given text into tokens and their spans.
+fn tokenize(text: &str) -> EvalResult<Vec<(Token, Span)>> {
+	let mut tokens = Vec::new();
+	let mut chars = text.char_indices().peekable();
+	while let Some(&(start, c)) = chars.peek() {
+		let (token, end) = match c {
+			_ if c.is_whitespace() => { chars.next(); continue; },
+			'(' => { chars.next(); (Token::Open, start + 1) },
+			')' => { chars.next(); (Token::Close, start + 1) },
+			',' => { chars.next(); (Token::Comma, start + 1) },
+			'|' => {
+				chars.next();
+				if chars.next().map(|(_, c)| c) != Some('>') {
+					return Err(EvalError::new(
+						"expected '|>'",
+						Span::new(start, start + 1)));
+				}
+				(Token::Pipe, start + 2)
+			},
+			'=' => {
+				chars.next();
+				(Token::Compare(Comparison::Equal), start + 1)
+			},
+			'<' | '>' | '!' => {
+				chars.next();
+				let equals = chars.peek().map(|&(_, c)| c) == Some('=');
+				if equals { chars.next(); }
+				let comparison = match (c, equals) {
+					('<', false) => Comparison::Less,
+					('<', true)  => Comparison::LessEqual,
+					('>', false) => Comparison::Greater,
+					('>', true)  => Comparison::GreaterEqual,
+					('!', true)  => Comparison::NotEqual,
+					_ => return Err(EvalError::new(
+						format!("expected '{}='", c),
+						Span::new(start, start + 1))),
+				};
+				let end = if equals { start + 2 } else { start + 1 };
+				(Token::Compare(comparison), end)
+			},
+			'#' => {
+				let end = take_while(&mut chars, start,
+					|c| c == '#' || c.is_alphanumeric());
+				let color = parse_hex(&text[start..end])
+					.ok_or_else(|| EvalError::new(
+						format!("invalid color '{}'", &text[start..end]),
+						Span::new(start, end)))?;
+				(Token::Value(Value::Color(color)), end)
+			},
+			_ if c.is_digit(10) || c == '.' || c == '-' => {
+				let end = take_while(&mut chars, start, |c| c.is_alphanumeric() 
+					|| c == '.' || c == '-' || c == '%' || c == '°');
+				let quantity = text[start..end].parse::<Quantity>()
+					.map_err(|e| EvalError::new(e, Span::new(start, end)))?;
+				(Token::Value(Value::Number(quantity)), end)
+			},
+			_ if c.is_alphabetic() || c == '_' => {
+				let end = take_while(&mut chars, start,
+					|c| c.is_alphanumeric() || c == '_');
+				(Token::Name(text[start..end].to_owned()), end)
+			},
+			_ => return Err(EvalError::new(
+				format!("unexpected character '{}'", c),
+				Span::new(start, start + c.len_utf8()))),
+		};
+		tokens.push((token, Span::new(start, end)));
+	}
+	Ok(tokens)
+}
+
+
+/// Consumes characters while the given predicate holds, returning the offset
+/// following the last consumed character.
+fn take_while<I, F>(chars: &mut Peekable<I>, start: usize, predicate: F) 
+	-> usize
+	where
+		I: Iterator<Item=(usize, char)>,
+		F: Fn(char) -> bool
+{
+	let mut end = start;
+	while let Some(&(i, c)) = chars.peek() {
+		if !predicate(c) { break; }
+		end = i + c.len_utf8();
+		chars.next();
+	}
+	end
+}
+
+
+/// The names reserved for conditional expressions.
+const KEYWORDS: &[&str] = &["if", "then", "else", "and", "or", "not"];
+
+
+/// A recursive descent parser for the expression language.
+struct Parser {
+	/// The tokens being parsed, with their spans.
+	tokens: Vec<(Token, Span)>,
+	/// The index of the next token.
+	position: usize,
+	/// The length of the source text.
+	end: usize,
+	/// The limits on the expression being parsed.
+	limits: EvalLimits,
+	/// The nesting depth of the term or condition being parsed.
+	depth: usize,
+	/// The number of terms and conditions parsed.
+	nodes: usize,
+}
+
+
+impl Parser {
+	/// Returns the next token without consuming it.
+	fn peek(&self) -> Option<&Token> {
+		self.tokens.get(self.position).map(|&(ref token, _)| token)
+	}
+
+	/// Consumes and returns the next token and its span.
+	fn next(&mut self) -> EvalResult<(Token, Span)> {
+		let token = self.tokens.get(self.position).cloned()
+			.ok_or_else(|| EvalError::new(
+				"unexpected end of expression",
+				Span::new(self.end, self.end)))?;
+		self.position += 1;
+		Ok(token)
+	}
+
+	/// Accounts for a nested term or condition starting at the next token.
+	fn enter(&mut self) -> EvalResult<()> {
+		self.depth += 1;
+		self.nodes += 1;
+		let span = self.tokens.get(self.position)
+			.map_or(Span::new(self.end, self.end), |&(_, span)| span);
+		self.limits.check(self.depth, self.nodes, span)
+	}
+
+	/// Parses a sequence of terms joined by pipes.
+	fn pipeline(&mut self) -> EvalResult<Term> {
+		let mut term = self.term()?;
+		while self.peek() == Some(&Token::Pipe) {
+			self.position += 1;
+			let next = self.term()?;
+			let span = term.span.join(next.span);
+			let kind = match next.kind {
+				TermKind::Call(name, mut args) => {
+					args.insert(0, term);
+					TermKind::Call(name, args)
+				},
+				TermKind::Name(name) => TermKind::Call(name, vec![term]),
+				TermKind::Value(value) => return Err(EvalError::new(
+					format!("cannot pipe into '{}'", value),
+					next.span)),
+				TermKind::Conditional(..) => return Err(EvalError::new(
+					"cannot pipe into a conditional expression",
+					next.span)),
+			};
+			term = Term {kind: kind, span: span};
+		}
+		Ok(term)
+	}
+
+	/// Parses a condition, joining conditions with `or`.
+	fn condition(&mut self) -> EvalResult<Condition> {
+		let mut condition = self.conjunction()?;
+		while self.peek_keyword("or") {
+			self.position += 1;
+			let next = self.conjunction()?;
+			condition = Condition::Or(Box::new(condition), Box::new(next));
+		}
+		Ok(condition)
+	}
+
+	/// Parses a condition, joining conditions with `and`.
+	fn conjunction(&mut self) -> EvalResult<Condition> {
+		let mut condition = self.negation()?;
+		while self.peek_keyword("and") {
+			self.position += 1;
+			let next = self.negation()?;
+			condition = Condition::And(Box::new(condition), Box::new(next));
+		}
+		Ok(condition)
+	}
+
+	/// Parses a comparison, optionally negated with `not`.
+	fn negation(&mut self) -> EvalResult<Condition> {
+		self.enter()?;
+		let condition = self.comparison();
+		self.depth -= 1;
+		condition
+	}
+
+	/// Parses the body of a possibly negated comparison.
+	fn comparison(&mut self) -> EvalResult<Condition> {
+		if self.peek_keyword("not") {
+			self.position += 1;
+			return Ok(Condition::Not(Box::new(self.negation()?)));
+		}
+		let left = self.pipeline()?;
+		match self.next()? {
+			(Token::Compare(comparison), _) => {
+				let right = self.pipeline()?;
+				Ok(Condition::Compare(comparison, left, right))
+			},
+			(token, span) => Err(EvalError::new(
+				format!("expected comparison, found {}", token),
+				span)),
+		}
+	}
+
+	/// Returns whether the next token is the given keyword.
+	fn peek_keyword(&self, keyword: &str) -> bool {
+		match self.peek() {
+			Some(&Token::Name(ref name)) => name == keyword,
+			_ => false,
+		}
+	}
+
+	/// Consumes the given keyword, returning an error if it is not next.
+	fn keyword(&mut self, keyword: &str) -> EvalResult<()> {
+		match self.next()? {
+			(Token::Name(ref name), _) if name == keyword => Ok(()),
+			(token, span) => Err(EvalError::new(
+				format!("expected '{}', found {}", keyword, token),
+				span)),
+		}
+	}
+
+	/// Parses a single term.
+	fn term(&mut self) -> EvalResult<Term> {
+		self.enter()?;
+		let term = self.single_term();
+		self.depth -= 1;
+		term
+	}
+
+	/// Parses the body of a single term.
+	fn single_term(&mut self) -> EvalResult<Term> {
+		let (token, span) = self.next()?;
+		match token {
+			Token::Name(ref name) if name == "if" => {
+				let condition = self.condition()?;
+				self.keyword("then")?;
+				let then = self.pipeline()?;
+				self.keyword("else")?;
+				let otherwise = self.pipeline()?;
+				Ok(Term {
+					span: span.join(otherwise.span),
+					kind: TermKind::Conditional(
+						Box::new(condition),
+						Box::new(then),
+						Box::new(otherwise)),
+				})
+			},
+
+			Token::Name(ref name) if KEYWORDS.contains(&&name[..]) => 
+				Err(EvalError::new(format!("unexpected '{}'", name), span)),
+
+			Token::Value(value) => Ok(Term {
+				kind: TermKind::Value(value),
+				span: span,
+			}),
+
+			Token::Open => {
+				let term = self.pipeline()?;
+				match self.next()? {
+					(Token::Close, _) => Ok(term),
+					(token, span) => Err(EvalError::new(
+						format!("expected ')', found {}", token),
+						span)),
+				}
+			},
+
+			Token::Name(name) => {
+				if self.peek() != Some(&Token::Open) {
+					return Ok(Term {kind: TermKind::Name(name), span: span});
+				}
+				self.position += 1;
+				let mut args = Vec::new();
+				let end = if self.peek() == Some(&Token::Close) {
+					self.next()?.1
+				} else {
+					loop {
+						args.push(self.pipeline()?);
+						match self.next()? {
+							(Token::Comma, _) => continue,
+							(Token::Close, close) => break close,
+							(token, span) => return Err(EvalError::new(
+								format!("expected ',' or ')', found {}", token),
+								span)),
+						}
+					}
+				};
+				Ok(Term {
+					kind: TermKind::Call(name, args),
+					span: span.join(end),
+				})
+			},
+
+			token => Err(EvalError::new(format!("unexpected {}", token), span)),
+		}
+	}
+}
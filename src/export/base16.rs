@@ -0,0 +1,121 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides exporting of Base16 and Base24 YAML schemes.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use ::Palette;
+use address::Selection;
+
+// Non-local imports.
+use color::Color;
+
+// Standard imports.
+use std::io;
+
+
+/// Writes the palette to the given buffer as a Base16 YAML scheme named after
+/// the palette, with the given author.
+///
+/// Each `baseXX` color is taken from the cell with that semantic token or 
+/// name. Colors without a matching cell are taken from the cell at the same 
+/// position in address order. If the palette has cells for all of `base10` to
+/// `base17`, a Base24 scheme is written instead.
+///
+/// # Errors
+///
+/// Returns an `InvalidInput` error if the palette has fewer than sixteen 
+/// colors.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::export::write_base16_scheme;
+/// use palette::operation::*;
+///
+/// let mut pal = Palette::new("Gray", Format::Default, true);
+/// for i in 0..16 {
+/// 	let v = i * 16;
+/// 	pal.apply(Box::new(InsertColor::new(Color::new(v, v, v)))).unwrap();
+/// }
+/// pal.set_name(Address::new(0, 0, 15), "base08");
+///
+/// let mut out = Vec::new();
+/// write_base16_scheme(&pal, "Anonymous", &mut out).unwrap();
+/// let yaml = String::from_utf8(out).unwrap();
+///
+/// assert!(yaml.starts_with("scheme: \"Gray\"\nauthor: \"Anonymous\"\n"));
+/// assert!(yaml.contains("base07: \"707070\"\nbase08: \"F0F0F0\"\n"));
+/// ```
+pub fn write_base16_scheme<W>(palette: &Palette, author: &str, out_buf: &mut W)
+	-> io::Result<()>
+	where W: io::Write
+{
+	let colors: Vec<Color> = palette.colors(&Selection::all())
+		.into_iter()
+		.map(|(_, color)| color)
+		.collect();
+	let named = |key: &str| palette.alias(key)
+		.or_else(|| palette.address_of(key))
+		.and_then(|address| palette.color(address));
+	let count = if (0x10..0x18).all(|i| named(&key(i)).is_some()) {
+		24
+	} else {
+		16
+	};
+
+	let mut scheme = Vec::with_capacity(count);
+	for index in 0..count {
+		let color = named(&key(index))
+			.or_else(|| colors.get(index).cloned())
+			.ok_or_else(|| io::Error::new(
+				io::ErrorKind::InvalidInput, 
+				format!("no color for {}", key(index))))?;
+		scheme.push(color);
+	}
+
+	writeln!(out_buf, "scheme: {}", yaml_string(
+		palette.name().unwrap_or("palette")))?;
+	writeln!(out_buf, "author: {}", yaml_string(author))?;
+	for (index, color) in scheme.into_iter().enumerate() {
+		writeln!(out_buf, "{}: \"{:02X}{:02X}{:02X}\"", 
+			key(index), color.red(), color.green(), color.blue())?;
+	}
+	Ok(())
+}
+
+
+/// Returns the scheme key for the color with the given index.
+fn key(index: usize) -> String {
+	format!("base{:02X}", index)
+}
+
+
+/// Returns the given text as a double-quoted YAML string.
+fn yaml_string(text: &str) -> String {
+	format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
@@ -0,0 +1,277 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides source code generation for embedding palettes into programs.
+//!
+//! Colors are emitted as `0xRRGGBB` integers, in address order. Named cells
+//! are additionally emitted as named constants. Names which map to the same
+//! identifier are told apart by a numeric suffix, in address order.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use ::Palette;
+use address::Selection;
+use export::single_line;
+
+// Non-local imports.
+use color::Color;
+
+// Standard imports.
+use std::collections::HashSet;
+use std::io;
+
+
+/// Returns the given color packed as a `0xRRGGBB` integer.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::Color;
+/// use palette::export::pack_rgb;
+///
+/// assert_eq!(pack_rgb(Color::new(0x12, 0x34, 0x56)), 0x123456);
+/// ```
+pub fn pack_rgb(color: Color) -> u32 {
+	(color.red() as u32) << 16 | (color.green() as u32) << 8 | color.blue() as u32
+}
+
+
+/// Returns the given name converted into an uppercase identifier, replacing
+/// invalid characters with underscores.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::export::identifier;
+///
+/// assert_eq!(identifier("skin base-2"), "SKIN_BASE_2");
+/// assert_eq!(identifier("3d"), "_3D");
+/// ```
+pub fn identifier(name: &str) -> String {
+	let mut ident: String = name.chars()
+		.map(|c| if c.is_ascii_alphanumeric() { 
+			c.to_ascii_uppercase() 
+		} else { 
+			'_' 
+		})
+		.collect();
+	if ident.chars().next().map_or(true, |c| c.is_ascii_digit()) {
+		ident.insert(0, '_');
+	}
+	ident
+}
+
+
+/// Returns the palette's selected named cells with colors, along with a 
+/// unique identifier for each. Identifiers which would repeat an earlier one
+/// or one of the given reserved identifiers are given a numeric suffix.
+fn named_colors(palette: &Palette, selection: &Selection, reserved: &[&str])
+	-> Vec<(String, String, Color)>
+{
+	let mut used: HashSet<String> = reserved.iter()
+		.map(|ident| ident.to_string())
+		.collect();
+	palette.named_cells()
+		.into_iter()
+		.filter(|&(_, address)| selection.contains(&address))
+		.filter_map(|(name, address)| palette.color(address)
+			.map(|color| (name, color)))
+		.map(|(name, color)| {
+			let base = identifier(&name);
+			let mut ident = base.clone();
+			let mut suffix = 1;
+			while used.contains(&ident) {
+				suffix += 1;
+				ident = format!("{}_{}", base, suffix);
+			}
+			used.insert(ident.clone());
+			(name, ident, color)
+		})
+		.collect()
+}
+
+
+/// Returns the given text for use within a block comment.
+fn comment_text(text: &str) -> String {
+	single_line(text).replace("*/", "* /")
+}
+
+
+/// Writes the palette's selected colors to the given buffer as a C header
+/// declaring a `static const uint32_t` array, with the given identifier prefix.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::export::write_c_header;
+/// use palette::operation::*;
+///
+/// let mut pal = Palette::new("Example", Format::Default, true);
+/// pal.apply(Box::new(InsertColor::new(Color::new(12, 50, 78)))).unwrap();
+/// pal.set_name(Address::new(0, 0, 0), "skin");
+///
+/// let mut out = Vec::new();
+/// write_c_header(&pal, &Selection::all(), "example", &mut out).unwrap();
+/// let header = String::from_utf8(out).unwrap();
+///
+/// assert!(header.contains("static const uint32_t EXAMPLE[EXAMPLE_LEN]"));
+/// assert!(header.contains("#define EXAMPLE_SKIN 0x0C324Eu"));
+///
+/// // Names which map to the same identifier are told apart.
+/// pal.apply(Box::new(InsertColor::new(Color::new(1, 2, 3)))).unwrap();
+/// pal.set_name(Address::new(0, 0, 1), "Skin");
+///
+/// let mut out = Vec::new();
+/// write_c_header(&pal, &Selection::all(), "example", &mut out).unwrap();
+/// let header = String::from_utf8(out).unwrap();
+/// assert!(header.contains("#define EXAMPLE_SKIN_2 0x010203u"));
+///
+/// // The palette name can't end the comment it is written in.
+/// let pal = Palette::new("dusk */\n#error", Format::Default, true);
+/// let mut out = Vec::new();
+/// write_c_header(&pal, &Selection::all(), "example", &mut out).unwrap();
+/// let header = String::from_utf8(out).unwrap();
+/// assert!(header.contains("palette \"dusk * / #error\". */\n"));
+/// ```
+pub fn write_c_header<W>(
+	palette: &Palette,
+	selection: &Selection,
+	prefix: &str,
+	out_buf: &mut W)
+	-> io::Result<()>
+	where W: io::Write
+{
+	let prefix = identifier(prefix);
+	let colors = palette.colors(selection);
+
+	writeln!(out_buf, "/* Generated from palette \"{}\". */", 
+		comment_text(palette.name().unwrap_or("")))?;
+	writeln!(out_buf, "#ifndef {}_PALETTE_H", prefix)?;
+	writeln!(out_buf, "#define {}_PALETTE_H", prefix)?;
+	writeln!(out_buf)?;
+	writeln!(out_buf, "#include <stdint.h>")?;
+	writeln!(out_buf)?;
+	writeln!(out_buf, "#define {}_LEN {}", prefix, colors.len())?;
+	writeln!(out_buf)?;
+
+	for (_, ident, color) in named_colors(palette, selection, &[
+		"LEN", 
+		"PALETTE_H",
+	]) {
+		writeln!(out_buf, "#define {}_{} 0x{:06X}u", 
+			prefix, 
+			ident, 
+			pack_rgb(color))?;
+	}
+	writeln!(out_buf)?;
+
+	writeln!(out_buf, "static const uint32_t {0}[{0}_LEN] = {{", prefix)?;
+	for (address, color) in colors {
+		writeln!(out_buf, "\t0x{:06X}u, /* {} */", pack_rgb(color), address)?;
+	}
+	writeln!(out_buf, "}};")?;
+	writeln!(out_buf)?;
+	writeln!(out_buf, "#endif /* {}_PALETTE_H */", prefix)
+}
+
+
+/// Writes the palette's selected colors to the given buffer as Rust source
+/// declaring a `u32` array constant, with the given identifier prefix. Named
+/// cells are emitted as constants, along with a function looking up colors by
+/// name.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::export::write_rust_source;
+/// use palette::operation::*;
+///
+/// let mut pal = Palette::new("Example", Format::Default, true);
+/// pal.apply(Box::new(InsertColor::new(Color::new(12, 50, 78)))).unwrap();
+/// pal.set_name(Address::new(0, 0, 0), "skin");
+///
+/// let mut out = Vec::new();
+/// write_rust_source(&pal, &Selection::all(), "example", &mut out).unwrap();
+/// let source = String::from_utf8(out).unwrap();
+///
+/// assert!(source.contains("pub const EXAMPLE: [u32; 1] = ["));
+/// assert!(source.contains("pub const EXAMPLE_SKIN: u32 = 0x0C324E;"));
+/// assert!(source.contains("\"skin\" => Some(EXAMPLE_SKIN),"));
+///
+/// pal.apply(Box::new(InsertColor::new(Color::new(1, 2, 3)))).unwrap();
+/// pal.set_name(Address::new(0, 0, 1), "SKIN");
+///
+/// let mut out = Vec::new();
+/// write_rust_source(&pal, &Selection::all(), "example", &mut out).unwrap();
+/// let source = String::from_utf8(out).unwrap();
+///
+/// assert!(source.contains("\"SKIN\" => Some(EXAMPLE_SKIN_2),"));
+/// ```
+pub fn write_rust_source<W>(
+	palette: &Palette,
+	selection: &Selection,
+	prefix: &str,
+	out_buf: &mut W)
+	-> io::Result<()>
+	where W: io::Write
+{
+	let prefix = identifier(prefix);
+	let colors = palette.colors(selection);
+	let named = named_colors(palette, selection, &[]);
+
+	writeln!(out_buf, "// Generated from palette \"{}\".", 
+		single_line(palette.name().unwrap_or("")))?;
+	writeln!(out_buf)?;
+
+	writeln!(out_buf, "/// The palette colors as `0xRRGGBB` values.")?;
+	writeln!(out_buf, "pub const {}: [u32; {}] = [", prefix, colors.len())?;
+	for (address, color) in colors {
+		writeln!(out_buf, "\t0x{:06X}, // {}", pack_rgb(color), address)?;
+	}
+	writeln!(out_buf, "];")?;
+
+	for &(ref name, ref ident, color) in &named {
+		writeln!(out_buf)?;
+		writeln!(out_buf, "/// The `{}` color.", single_line(name))?;
+		writeln!(out_buf, "pub const {}_{}: u32 = 0x{:06X};", 
+			prefix, 
+			ident, 
+			pack_rgb(color))?;
+	}
+
+	writeln!(out_buf)?;
+	writeln!(out_buf, "/// Returns the color with the given name.")?;
+	writeln!(out_buf, "pub fn {}_by_name(name: &str) -> Option<u32> {{",
+		prefix.to_lowercase())?;
+	writeln!(out_buf, "\tmatch name {{")?;
+	for &(ref name, ref ident, _) in &named {
+		writeln!(out_buf, "\t\t{:?} => Some({}_{}),", name, prefix, ident)?;
+	}
+	writeln!(out_buf, "\t\t_ => None,")?;
+	writeln!(out_buf, "\t}}")?;
+	writeln!(out_buf, "}}")
+}
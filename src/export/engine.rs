@@ -0,0 +1,246 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides exporters for game engine asset formats.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use ::Palette;
+use address::{
+	Address,
+	Selection,
+};
+use export::code::identifier;
+use gradient::GradientMap;
+use space::{
+	unit_channels,
+	WideColor,
+};
+
+// Non-local imports.
+use color::Color;
+
+// Standard imports.
+use std::io;
+
+
+/// Returns the selected colors of the palette along with their cell names, if
+/// any.
+fn named_colors(palette: &Palette, selection: &Selection) 
+	-> Vec<(Address, Color, Option<String>)> 
+{
+	palette.colors(selection)
+		.into_iter()
+		.map(|(address, color)| (
+			address,
+			color,
+			palette.cell_name(address).map(|n| n.to_owned())
+		))
+		.collect()
+}
+
+
+/// Returns the color as a comma separated list of unit RGBA channels.
+fn rgba_list(color: Color) -> String {
+	let [r, g, b] = unit_channels(color);
+	format!("{}, {}, {}, 1", r, g, b)
+}
+
+
+/// Writes the palette's selected colors to the given buffer as a Godot `Gradient`
+/// resource (`.tres`), with the colors evenly spaced along the gradient.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::export::write_godot_gradient;
+/// use palette::operation::*;
+///
+/// let mut pal = Palette::new("Example", Format::Default, true);
+/// pal.apply(Box::new(InsertColor::new(Color::new(0, 0, 0)))).unwrap();
+/// pal.apply(Box::new(InsertColor::new(Color::new(255, 255, 255)))).unwrap();
+///
+/// let mut out = Vec::new();
+/// write_godot_gradient(&pal, &Selection::all(), &mut out).unwrap();
+/// let res = String::from_utf8(out).unwrap();
+///
+/// assert!(res.contains("offsets = PackedFloat32Array(0, 1)"));
+/// assert!(res.contains("colors = PackedColorArray(0, 0, 0, 1, 1, 1, 1, 1)"));
+/// ```
+pub fn write_godot_gradient<W>(
+	palette: &Palette,
+	selection: &Selection,
+	out_buf: &mut W)
+	-> io::Result<()>
+	where W: io::Write
+{
+	let colors: Vec<WideColor> = palette.colors(selection)
+		.into_iter()
+		.map(|(_, color)| WideColor::from(color))
+		.collect();
+	let stops = GradientMap::evenly_spaced(&colors).stops();
+
+	let offsets: Vec<String> = stops.iter()
+		.map(|&(offset, _)| format!("{}", offset))
+		.collect();
+	let values: Vec<String> = stops.iter()
+		.map(|&(_, color)| rgba_list(color.quantize()))
+		.collect();
+
+	writeln!(out_buf, "[gd_resource type=\"Gradient\" format=3]")?;
+	writeln!(out_buf)?;
+	writeln!(out_buf, "[resource]")?;
+	writeln!(out_buf, "offsets = PackedFloat32Array({})", offsets.join(", "))?;
+	writeln!(out_buf, "colors = PackedColorArray({})", values.join(", "))
+}
+
+
+/// Writes the palette's selected colors to the given buffer as a Godot `ColorPalette`
+/// resource (`.tres`).
+pub fn write_godot_palette<W>(
+	palette: &Palette,
+	selection: &Selection,
+	out_buf: &mut W)
+	-> io::Result<()>
+	where W: io::Write
+{
+	let values: Vec<String> = palette.colors(selection)
+		.into_iter()
+		.map(|(_, color)| rgba_list(color))
+		.collect();
+
+	writeln!(out_buf, "[gd_resource type=\"ColorPalette\" format=3]")?;
+	writeln!(out_buf)?;
+	writeln!(out_buf, "[resource]")?;
+	writeln!(out_buf, "colors = PackedColorArray({})", values.join(", "))
+}
+
+
+/// Writes the palette's selected colors to the given buffer as a Unity color preset
+/// library (`.colors`). Named cells keep their names in the library.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::export::write_unity_colors;
+/// use palette::operation::*;
+///
+/// let mut pal = Palette::new("Example", Format::Default, true);
+/// pal.apply(Box::new(InsertColor::new(Color::new(255, 0, 0)))).unwrap();
+/// pal.set_name(Address::new(0, 0, 0), "red");
+///
+/// let mut out = Vec::new();
+/// write_unity_colors(&pal, &Selection::all(), &mut out).unwrap();
+/// let asset = String::from_utf8(out).unwrap();
+///
+/// assert!(asset.contains("  - m_Name: red\n    m_Color: {r: 1, g: 0, b: 0, a: 1}"));
+/// ```
+pub fn write_unity_colors<W>(
+	palette: &Palette,
+	selection: &Selection,
+	out_buf: &mut W)
+	-> io::Result<()>
+	where W: io::Write
+{
+	writeln!(out_buf, "%YAML 1.1")?;
+	writeln!(out_buf, "%TAG !u! tag:unity3d.com,2011:")?;
+	writeln!(out_buf, "--- !u!114 &1")?;
+	writeln!(out_buf, "MonoBehaviour:")?;
+	writeln!(out_buf, "  m_ObjectHideFlags: 52")?;
+	writeln!(out_buf, "  m_CorrespondingSourceObject: {{fileID: 0}}")?;
+	writeln!(out_buf, "  m_PrefabInstance: {{fileID: 0}}")?;
+	writeln!(out_buf, "  m_PrefabAsset: {{fileID: 0}}")?;
+	writeln!(out_buf, "  m_GameObject: {{fileID: 0}}")?;
+	writeln!(out_buf, "  m_Enabled: 1")?;
+	writeln!(out_buf, "  m_EditorHideFlags: 1")?;
+	writeln!(out_buf, "  m_Script: {{fileID: 12323, \
+		guid: 0000000000000000e000000000000000, type: 0}}")?;
+	writeln!(out_buf, "  m_Name: {}", palette.name().unwrap_or(""))?;
+	writeln!(out_buf, "  m_EditorClassIdentifier: ")?;
+	writeln!(out_buf, "  m_Presets:")?;
+
+	for (_, color, name) in named_colors(palette, selection) {
+		let [r, g, b] = unit_channels(color);
+		writeln!(out_buf, "  - m_Name: {}", name.unwrap_or_default())?;
+		writeln!(out_buf, "    m_Color: {{r: {}, g: {}, b: {}, a: 1}}", 
+			r, g, b)?;
+	}
+	Ok(())
+}
+
+
+/// Writes the palette's selected colors to the given buffer as GameMaker `#macro`
+/// definitions, with the given identifier prefix. GameMaker colors are stored
+/// in `$BBGGRR` order. Named cells use their names, and other cells use their
+/// index.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::export::write_gamemaker_macros;
+/// use palette::operation::*;
+///
+/// let mut pal = Palette::new("Example", Format::Default, true);
+/// pal.apply(Box::new(InsertColor::new(Color::new(12, 50, 78)))).unwrap();
+/// pal.apply(Box::new(InsertColor::new(Color::new(255, 0, 0)))).unwrap();
+/// pal.set_name(Address::new(0, 0, 0), "skin");
+///
+/// let mut out = Vec::new();
+/// write_gamemaker_macros(&pal, &Selection::all(), "col", &mut out).unwrap();
+/// let gml = String::from_utf8(out).unwrap();
+///
+/// assert!(gml.contains("#macro COL_SKIN $4E320C"));
+/// assert!(gml.contains("#macro COL_1 $0000FF"));
+/// ```
+pub fn write_gamemaker_macros<W>(
+	palette: &Palette,
+	selection: &Selection,
+	prefix: &str,
+	out_buf: &mut W)
+	-> io::Result<()>
+	where W: io::Write
+{
+	let prefix = identifier(prefix);
+	writeln!(out_buf, "// Generated from palette \"{}\".", 
+		palette.name().unwrap_or(""))?;
+
+	for (i, (address, color, name)) in named_colors(palette, selection)
+		.into_iter()
+		.enumerate()
+	{
+		let name = name.map_or_else(|| format!("{}", i), |n| identifier(&n));
+		writeln!(out_buf, "#macro {}_{} ${:02X}{:02X}{:02X} // {}",
+			prefix,
+			name.trim_start_matches('_'),
+			color.blue(),
+			color.green(),
+			color.red(),
+			address)?;
+	}
+	Ok(())
+}
@@ -0,0 +1,94 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides exporting of the JSON styles format used by Figma plugins.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use ::Palette;
+use address::Selection;
+use export::{
+	unit_channel,
+	write_json,
+};
+use utilities::hex;
+
+// Non-local imports.
+use serde_json::Value;
+
+// Standard imports.
+use std::io;
+
+
+/// Writes the palette's selected colors to the given buffer as an array of 
+/// Figma color styles, which can be read back by `read_figma_styles`. Each
+/// style has the cell's name, or its hex color if it is unnamed, the cell's 
+/// description, and a `color` object of unit channels.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::export::write_figma_styles;
+/// use palette::import::read_figma_styles;
+/// use palette::operation::*;
+///
+/// let mut pal = Palette::new("Example", Format::Default, true);
+/// pal.apply(Box::new(InsertColor::new(Color::new(0, 102, 255)))).unwrap();
+/// pal.set_name(Address::new(0, 0, 0), "Brand/Primary");
+/// pal.set_description(Address::new(0, 0, 0), Some("Links".to_owned()));
+///
+/// let mut out = Vec::new();
+/// write_figma_styles(&pal, &Selection::all(), &mut out).unwrap();
+/// let copy = read_figma_styles("Example", &mut &out[..]).unwrap();
+///
+/// let a = Address::new(0, 0, 0);
+/// assert_eq!(copy.color(a), Some(Color::new(0, 102, 255)));
+/// assert_eq!(copy.cell_name(a), Some("Brand/Primary"));
+/// assert_eq!(copy.cell_description(a), Some("Links"));
+/// ```
+pub fn write_figma_styles<W>(
+	palette: &Palette,
+	selection: &Selection,
+	out_buf: &mut W)
+	-> io::Result<()>
+	where W: io::Write
+{
+	let styles = palette.colors(selection)
+		.into_iter()
+		.map(|(address, color)| json!({
+			"name": palette.cell_name(address)
+				.map_or_else(|| hex(color), |name| name.to_owned()),
+			"description": palette.cell_description(address).unwrap_or(""),
+			"color": {
+				"r": unit_channel(color.red()),
+				"g": unit_channel(color.green()),
+				"b": unit_channel(color.blue()),
+				"a": 1.0,
+			},
+		}))
+		.collect();
+	write_json(&Value::Array(styles), out_buf)
+}
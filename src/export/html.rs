@@ -0,0 +1,191 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides a standalone HTML gallery for sharing palettes.
+//!
+//! Each palette is rendered as a section containing a preview strip for each 
+//! line of the palette, followed by a swatch grid listing each cell's address,
+//! name, and hex value. The page has no external dependencies.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use ::Palette;
+use address::{
+	Address,
+	Selection,
+};
+
+// Non-local imports.
+use color::Color;
+
+// Standard imports.
+use std::collections::BTreeMap;
+use std::io;
+
+
+/// The stylesheet embedded in the gallery page.
+const STYLE: &'static str = "\
+body { font-family: sans-serif; margin: 2em; color: #222; }
+section { margin-bottom: 3em; }
+.ramp { height: 1.5em; margin: 0.25em 0; border-radius: 3px; }
+.swatches { display: flex; flex-wrap: wrap; gap: 0.75em; margin-top: 1em; }
+.swatch { width: 7em; font-size: 0.8em; }
+.swatch div { height: 4em; border-radius: 3px; border: 1px solid #0002; }
+.swatch code { display: block; }";
+
+
+/// Returns the given text with HTML special characters escaped.
+fn escape(text: &str) -> String {
+	let mut escaped = String::with_capacity(text.len());
+	for c in text.chars() {
+		match c {
+			'&' => escaped.push_str("&amp;"),
+			'<' => escaped.push_str("&lt;"),
+			'>' => escaped.push_str("&gt;"),
+			'"' => escaped.push_str("&quot;"),
+			'\'' => escaped.push_str("&#39;"),
+			_ => escaped.push(c),
+		}
+	}
+	escaped
+}
+
+
+/// Returns the CSS hex notation of the given color.
+fn hex(color: Color) -> String {
+	format!("#{:02X}{:02X}{:02X}", color.red(), color.green(), color.blue())
+}
+
+
+/// Returns the CSS background of a ramp preview strip for the given colors.
+fn ramp_background(colors: &[Color]) -> String {
+	match colors.len() {
+		0 => "none".to_owned(),
+		1 => hex(colors[0]),
+		_ => {
+			let stops: Vec<_> = colors.iter().map(|&c| hex(c)).collect();
+			format!("linear-gradient(to right, {})", stops.join(", "))
+		},
+	}
+}
+
+
+/// Writes the given palettes to the given buffer as a standalone HTML page,
+/// with the given title.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::export::write_html_gallery;
+/// use palette::operation::*;
+///
+/// let mut pal = Palette::new("Skin & Hair", Format::Default, true);
+/// pal.apply(Box::new(InsertColor::new(Color::new(12, 50, 78)))).unwrap();
+/// pal.apply(Box::new(InsertColor::new(Color::new(255, 0, 0)))).unwrap();
+/// pal.set_name(Address::new(0, 0, 0), "skin");
+///
+/// let mut out = Vec::new();
+/// write_html_gallery(&[&pal], "Gallery", &mut out).unwrap();
+/// let html = String::from_utf8(out).unwrap();
+///
+/// assert!(html.contains("<h2>Skin &amp; Hair</h2>"));
+/// assert!(html.contains("linear-gradient(to right, #0C324E, #FF0000)"));
+/// assert!(html.contains("<strong>skin</strong>"));
+/// ```
+pub fn write_html_gallery<W>(
+	palettes: &[&Palette],
+	title: &str,
+	out_buf: &mut W)
+	-> io::Result<()>
+	where W: io::Write
+{
+	writeln!(out_buf, "<!DOCTYPE html>")?;
+	writeln!(out_buf, "<html>")?;
+	writeln!(out_buf, "<head>")?;
+	writeln!(out_buf, "<meta charset=\"utf-8\">")?;
+	writeln!(out_buf, "<title>{}</title>", escape(title))?;
+	writeln!(out_buf, "<style>\n{}\n</style>", STYLE)?;
+	writeln!(out_buf, "</head>")?;
+	writeln!(out_buf, "<body>")?;
+	writeln!(out_buf, "<h1>{}</h1>", escape(title))?;
+	for palette in palettes {
+		write_section(palette, out_buf)?;
+	}
+	writeln!(out_buf, "</body>")?;
+	writeln!(out_buf, "</html>")
+}
+
+
+/// Writes the gallery section for the given palette.
+fn write_section<W>(palette: &Palette, out_buf: &mut W) -> io::Result<()>
+	where W: io::Write
+{
+	let colors = palette.colors(&Selection::all());
+	let mut lines: BTreeMap<(_, _), Vec<Color>> = BTreeMap::new();
+	for &(address, color) in &colors {
+		lines.entry((address.page, address.line))
+			.or_insert_with(Vec::new)
+			.push(color);
+	}
+
+	writeln!(out_buf, "<section>")?;
+	writeln!(out_buf, "<h2>{}</h2>", 
+		escape(palette.name().unwrap_or("Untitled")))?;
+
+	for (&(page, line), ramp) in &lines {
+		writeln!(out_buf, 
+			"<div class=\"ramp\" title=\"{}:{}\" style=\"background: {}\">\
+			</div>",
+			page, line, ramp_background(ramp))?;
+	}
+
+	writeln!(out_buf, "<div class=\"swatches\">")?;
+	for &(address, color) in &colors {
+		write_swatch(palette, address, color, out_buf)?;
+	}
+	writeln!(out_buf, "</div>")?;
+	writeln!(out_buf, "</section>")
+}
+
+
+/// Writes the swatch for the given palette cell.
+fn write_swatch<W>(
+	palette: &Palette,
+	address: Address,
+	color: Color,
+	out_buf: &mut W)
+	-> io::Result<()>
+	where W: io::Write
+{
+	writeln!(out_buf, "<div class=\"swatch\">")?;
+	writeln!(out_buf, "<div style=\"background: {}\"></div>", hex(color))?;
+	if let Some(name) = palette.cell_name(address) {
+		writeln!(out_buf, "<strong>{}</strong>", escape(name))?;
+	}
+	writeln!(out_buf, "<code>{}</code>", hex(color))?;
+	writeln!(out_buf, "<code>{}</code>", address)?;
+	writeln!(out_buf, "</div>")
+}
@@ -0,0 +1,238 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides color lookup table export for palette-swap shader effects.
+//!
+//! A `Lut3d` samples a color mapping on a regular RGB grid. It can be written
+//! as an Adobe/Resolve `.cube` file, or with the `images` feature, as a PNG
+//! texture with the blue slices laid out horizontally.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use space::{
+	Oklab,
+	from_unit_channels,
+	unit_channels,
+};
+
+// Non-local imports.
+use color::Color;
+
+// Standard imports.
+use std::io;
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Lut3d
+////////////////////////////////////////////////////////////////////////////////
+/// A three dimensional color lookup table.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::Color;
+/// use palette::export::Lut3d;
+///
+/// let from = [Color::new(255, 0, 0), Color::new(0, 0, 255)];
+/// let to = [Color::new(0, 255, 0), Color::new(255, 255, 0)];
+/// let lut = Lut3d::swap(&from, &to, 17);
+///
+/// assert_eq!(lut.lookup(Color::new(250, 10, 10)), Color::new(0, 255, 0));
+/// assert_eq!(lut.lookup(Color::new(0, 0, 255)), Color::new(255, 255, 0));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lut3d {
+	/// The number of grid points along each axis.
+	size: usize,
+	/// The output colors, with red varying fastest and blue slowest.
+	data: Vec<[f32; 3]>,
+}
+
+
+impl Lut3d {
+	/// Creates a new `Lut3d` with the given grid size by sampling the given
+	/// mapping function at each grid point. Channels are in the range [0, 1].
+	///
+	/// # Panics
+	///
+	/// Panics if the size is less than 2.
+	pub fn from_fn<F>(size: usize, mut mapping: F) -> Self 
+		where F: FnMut([f32; 3]) -> [f32; 3]
+	{
+		assert!(size >= 2, "LUT size must be at least 2");
+		let step = 1.0 / (size - 1) as f32;
+		let mut data = Vec::with_capacity(size * size * size);
+		for b in 0..size {
+			for g in 0..size {
+				for r in 0..size {
+					data.push(mapping([
+						r as f32 * step,
+						g as f32 * step,
+						b as f32 * step,
+					]));
+				}
+			}
+		}
+		Lut3d {size: size, data: data}
+	}
+
+	/// Creates a new `Lut3d` leaving colors unchanged.
+	pub fn identity(size: usize) -> Self {
+		Lut3d::from_fn(size, |rgb| rgb)
+	}
+
+	/// Creates a new `Lut3d` mapping each color to the nearest of the given
+	/// palette colors.
+	pub fn nearest(palette: &[Color], size: usize) -> Self {
+		Lut3d::swap(palette, palette, size)
+	}
+
+	/// Creates a new `Lut3d` mapping each color to the `to` color paired with
+	/// the nearest `from` color. Colors beyond the end of the shorter slice are
+	/// ignored. If either slice is empty, the identity mapping is used.
+	pub fn swap(from: &[Color], to: &[Color], size: usize) -> Self {
+		let len = from.len().min(to.len());
+		if len == 0 { return Lut3d::identity(size); }
+
+		let keys: Vec<Oklab> = from[..len].iter()
+			.map(|&c| Oklab::from(c))
+			.collect();
+		Lut3d::from_fn(size, |rgb| {
+			let target = Oklab::from(from_unit_channels(rgb));
+			let index = target.nearest(&keys).unwrap_or(0);
+			unit_channels(to[index])
+		})
+	}
+
+	/// Returns the number of grid points along each axis.
+	pub fn size(&self) -> usize {
+		self.size
+	}
+
+	/// Returns the output at the given grid point.
+	pub fn get(&self, r: usize, g: usize, b: usize) -> [f32; 3] {
+		self.data[r + self.size * (g + self.size * b)]
+	}
+
+	/// Returns the output of the grid point nearest to the given color.
+	pub fn lookup(&self, color: Color) -> Color {
+		let scale = (self.size - 1) as f32;
+		let index = |v: f32| (v * scale).round() as usize;
+		let [r, g, b] = unit_channels(color);
+		from_unit_channels(self.get(index(r), index(g), index(b)))
+	}
+
+	/// Writes the table to the given buffer in the `.cube` format.
+	pub fn write_cube<W>(&self, title: &str, out_buf: &mut W) 
+		-> io::Result<()>
+		where W: io::Write
+	{
+		writeln!(out_buf, "TITLE \"{}\"", title.replace('"', "'"))?;
+		writeln!(out_buf, "LUT_3D_SIZE {}", self.size)?;
+		writeln!(out_buf, "DOMAIN_MIN 0.0 0.0 0.0")?;
+		writeln!(out_buf, "DOMAIN_MAX 1.0 1.0 1.0")?;
+		for rgb in &self.data {
+			writeln!(out_buf, "{:.6} {:.6} {:.6}", rgb[0], rgb[1], rgb[2])?;
+		}
+		Ok(())
+	}
+
+	/// Writes the table to the given buffer as an 8-bit RGB PNG texture. The
+	/// texture is `size * size` pixels wide and `size` pixels tall, with each
+	/// blue slice placed side by side: red increases along x within a slice,
+	/// and green increases along y.
+	#[cfg(feature = "images")]
+	pub fn write_png<W>(&self, out_buf: &mut W) -> io::Result<()>
+		where W: io::Write
+	{
+		let size = self.size as u32;
+		write_rgb_png(out_buf, size * size, size, &self.pixels())
+	}
+
+	/// Returns the 8-bit RGB pixel data of the table's PNG texture.
+	#[cfg(feature = "images")]
+	pub(crate) fn pixels(&self) -> Vec<u8> {
+		let size = self.size;
+		let mut pixels = Vec::with_capacity(size * size * size * 3);
+		for g in 0..size {
+			for b in 0..size {
+				for r in 0..size {
+					let c = from_unit_channels(self.get(r, g, b));
+					pixels.extend_from_slice(&[c.red(), c.green(), c.blue()]);
+				}
+			}
+		}
+		pixels
+	}
+}
+
+
+/// Writes the given colors to the given buffer as a PNG texture one pixel tall,
+/// for use as an indexed palette texture.
+#[cfg(feature = "images")]
+pub fn write_strip_png<W>(colors: &[Color], out_buf: &mut W) -> io::Result<()>
+	where W: io::Write
+{
+	let pixels: Vec<u8> = colors.iter()
+		.flat_map(|c| vec![c.red(), c.green(), c.blue()])
+		.collect();
+	write_rgb_png(out_buf, colors.len() as u32, 1, &pixels)
+}
+
+
+/// Writes 8-bit RGB pixel data to the given buffer as a PNG image.
+#[cfg(feature = "images")]
+pub(crate) fn write_rgb_png<W>(
+	out_buf: &mut W,
+	width: u32,
+	height: u32,
+	pixels: &[u8])
+	-> io::Result<()>
+	where W: io::Write
+{
+	write_png(out_buf, width, height, ::png::ColorType::Rgb, pixels)
+}
+
+
+/// Writes 8-bit pixel data of the given color type to the given buffer as a
+/// PNG image.
+#[cfg(feature = "images")]
+pub(crate) fn write_png<W>(
+	out_buf: &mut W,
+	width: u32,
+	height: u32,
+	color_type: ::png::ColorType,
+	pixels: &[u8])
+	-> io::Result<()>
+	where W: io::Write
+{
+	let to_io = |e: ::png::EncodingError| io::Error::new(io::ErrorKind::Other, e);
+	let mut encoder = ::png::Encoder::new(out_buf, width, height);
+	encoder.set_color(color_type);
+	encoder.set_depth(::png::BitDepth::Eight);
+	let mut writer = encoder.write_header().map_err(to_io)?;
+	writer.write_image_data(pixels).map_err(to_io)
+}
@@ -0,0 +1,291 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides export manifests for detecting drift between palettes and the
+//! assets generated from them.
+//!
+//! A manifest records, for each exported file, the source palette file, the 
+//! export format, and checksums of both the source and the exported file. 
+//! Checking the manifest reports any exports which are out of date with their
+//! source, or which were modified after being generated. Exported files 
+//! should be treated as read-only, and regenerated rather than edited.
+//!
+//! Checksums are 64-bit FNV-1a hashes. They are intended to detect accidental
+//! changes, and are not cryptographically secure.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use export::ExportFormat;
+use format::default;
+
+// Standard imports.
+use std::fmt;
+use std::fs;
+use std::io;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::path::{
+	Path,
+	PathBuf,
+};
+
+
+/// Returns the FNV-1a hash of the given bytes.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::export::checksum;
+///
+/// assert_eq!(checksum(b""), 0xCBF29CE484222325);
+/// assert_eq!(checksum(b"a"), 0xAF63DC4C8601EC8C);
+/// ```
+pub fn checksum(bytes: &[u8]) -> u64 {
+	bytes.iter().fold(0xCBF2_9CE4_8422_2325, |hash, &byte| 
+		(hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01B3))
+}
+
+
+/// Returns the checksum of the file at the given path.
+fn file_checksum(path: &Path) -> io::Result<u64> {
+	fs::read(path).map(|bytes| checksum(&bytes))
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// ManifestEntry
+////////////////////////////////////////////////////////////////////////////////
+/// A record of a single exported file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestEntry {
+	/// The path of the source palette file.
+	pub source: PathBuf,
+	/// The checksum of the source palette file at export time.
+	pub source_checksum: u64,
+	/// The path of the exported file.
+	pub target: PathBuf,
+	/// The format of the exported file.
+	pub format: ExportFormat,
+	/// The checksum of the exported file at export time.
+	pub target_checksum: u64,
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Drift
+////////////////////////////////////////////////////////////////////////////////
+/// A way in which an exported file has drifted from its manifest entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Drift {
+	/// The source palette file has changed since the export.
+	SourceChanged,
+	/// The source palette file no longer exists.
+	SourceMissing,
+	/// The exported file has been modified since the export.
+	TargetModified,
+	/// The exported file no longer exists.
+	TargetMissing,
+}
+
+
+impl fmt::Display for Drift {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}", match *self {
+			Drift::SourceChanged  => "source palette changed since export",
+			Drift::SourceMissing  => "source palette is missing",
+			Drift::TargetModified => "exported file was modified",
+			Drift::TargetMissing  => "exported file is missing",
+		})
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Manifest
+////////////////////////////////////////////////////////////////////////////////
+/// A record of exported files and the palettes they were generated from.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::export::{Drift, ExportFormat, Manifest};
+/// use std::fs;
+///
+/// let dir = std::env::temp_dir().join("palette-manifest-example");
+/// fs::create_dir_all(&dir).unwrap();
+/// let (source, target) = (dir.join("a.palette"), dir.join("a.gml"));
+/// fs::write(&source, "palette \"a\"\ncell 0:0:0 #0C324E\n").unwrap();
+///
+/// let mut manifest = Manifest::new();
+/// manifest.export(&source, &target, ExportFormat::GameMakerMacros).unwrap();
+/// assert!(manifest.check().is_empty());
+///
+/// fs::write(&source, "palette \"a\"\ncell 0:0:0 #FF0000\n").unwrap();
+/// assert_eq!(manifest.check(), vec![(target.clone(), Drift::SourceChanged)]);
+///
+/// manifest.export(&source, &target, ExportFormat::GameMakerMacros).unwrap();
+/// assert!(manifest.check().is_empty());
+/// assert_eq!(manifest.entries().len(), 1);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Manifest {
+	/// The manifest entries, in the order they were recorded.
+	entries: Vec<ManifestEntry>,
+}
+
+
+impl Manifest {
+	/// Creates a new, empty `Manifest`.
+	pub fn new() -> Self {
+		Manifest::default()
+	}
+
+	/// Returns the manifest's entries.
+	pub fn entries(&self) -> &[ManifestEntry] {
+		&self.entries[..]
+	}
+
+	/// Records that the given target file was exported from the given source
+	/// palette file in the given format, using the current contents of both
+	/// files. Replaces any existing entry for the target.
+	pub fn record(&mut self, source: &Path, target: &Path, format: ExportFormat)
+		-> io::Result<()>
+	{
+		let entry = ManifestEntry {
+			source: source.to_owned(),
+			source_checksum: file_checksum(source)?,
+			target: target.to_owned(),
+			format: format,
+			target_checksum: file_checksum(target)?,
+		};
+		self.insert(entry);
+		Ok(())
+	}
+
+	/// Exports the given source palette file to the given target file in the 
+	/// given format, and records the export.
+	pub fn export(&mut self, source: &Path, target: &Path, format: ExportFormat)
+		-> io::Result<()>
+	{
+		let bytes = fs::read(source)?;
+		let palette = default::read_palette(&mut &bytes[..])?;
+		let mut out = Vec::new();
+		format.write(&palette, &mut out)?;
+		fs::write(target, &out)?;
+
+		self.insert(ManifestEntry {
+			source: source.to_owned(),
+			source_checksum: checksum(&bytes),
+			target: target.to_owned(),
+			format: format,
+			target_checksum: checksum(&out),
+		});
+		Ok(())
+	}
+
+	/// Inserts the given entry, replacing any existing entry for its target.
+	fn insert(&mut self, entry: ManifestEntry) {
+		match self.entries.iter().position(|e| e.target == entry.target) {
+			Some(index) => self.entries[index] = entry,
+			None => self.entries.push(entry),
+		}
+	}
+
+	/// Returns the exported files which have drifted from their manifest 
+	/// entries, in entry order.
+	pub fn check(&self) -> Vec<(PathBuf, Drift)> {
+		self.entries.iter()
+			.filter_map(|entry| {
+				let drift = match file_checksum(&entry.target) {
+					Err(_) => Drift::TargetMissing,
+					Ok(c) if c != entry.target_checksum 
+						=> Drift::TargetModified,
+					Ok(_) => match file_checksum(&entry.source) {
+						Err(_) => Drift::SourceMissing,
+						Ok(c) if c != entry.source_checksum 
+							=> Drift::SourceChanged,
+						Ok(_) => return None,
+					},
+				};
+				Some((entry.target.clone(), drift))
+			})
+			.collect()
+	}
+
+	/// Writes the manifest to the given buffer.
+	pub fn write<W>(&self, out_buf: &mut W) -> io::Result<()> 
+		where W: io::Write
+	{
+		for entry in &self.entries {
+			writeln!(out_buf, "export {} {:016x} {} {} {:016x}",
+				default::quote(&entry.source.to_string_lossy()),
+				entry.source_checksum,
+				default::quote(&entry.target.to_string_lossy()),
+				entry.format.name(),
+				entry.target_checksum)?;
+		}
+		Ok(())
+	}
+
+	/// Reads a manifest from the given buffer.
+	pub fn read<R>(in_buf: &mut R) -> io::Result<Manifest> 
+		where R: io::Read
+	{
+		let mut manifest = Manifest::new();
+		for (i, line) in BufReader::new(in_buf).lines().enumerate() {
+			let line = line?;
+			let tokens = default::tokenize(&line)
+				.map_err(|e| default::invalid(i, e))?;
+			let entry = match tokens.first().map(|t| &t[..]) {
+				Some("export") => read_entry(&tokens)
+					.map(|entry| manifest.insert(entry)),
+				Some(other) => Err(format!("unknown entry '{}'", other)),
+				None => Ok(()),
+			};
+			entry.map_err(|e| default::invalid(i, e))?;
+		}
+		Ok(manifest)
+	}
+}
+
+
+/// Parses a manifest export entry.
+fn read_entry(tokens: &[String]) -> Result<ManifestEntry, String> {
+	let hash = |index| default::token(tokens, index)
+		.and_then(|text| u64::from_str_radix(&text, 16)
+			.map_err(|_| format!("invalid checksum '{}'", text)));
+	let format = default::token(tokens, 4)?;
+	Ok(ManifestEntry {
+		source: PathBuf::from(default::token(tokens, 1)?),
+		source_checksum: hash(2)?,
+		target: PathBuf::from(default::token(tokens, 3)?),
+		format: ExportFormat::from_name(&format)
+			.ok_or_else(|| format!("unknown format '{}'", format))?,
+		target_checksum: hash(5)?,
+	})
+}
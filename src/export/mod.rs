@@ -0,0 +1,420 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides exporters for writing palette colors to other formats.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Submodules.
+#[warn(missing_docs)]
+mod base16;
+#[warn(missing_docs)]
+mod code;
+#[warn(missing_docs)]
+mod engine;
+#[warn(missing_docs)]
+mod figma;
+#[warn(missing_docs)]
+mod html;
+#[warn(missing_docs)]
+pub(crate) mod lut;
+#[warn(missing_docs)]
+mod manifest;
+#[warn(missing_docs)]
+mod sketch;
+#[warn(missing_docs)]
+mod substitute;
+#[warn(missing_docs)]
+mod swatch;
+#[warn(missing_docs)]
+mod table;
+#[warn(missing_docs)]
+mod terminal;
+#[warn(missing_docs)]
+mod theme;
+#[warn(missing_docs)]
+mod tokens;
+
+// Submodule re-exports.
+pub use self::base16::write_base16_scheme;
+pub use self::code::{
+	identifier,
+	pack_rgb,
+	write_c_header,
+	write_rust_source,
+};
+pub use self::engine::{
+	write_gamemaker_macros,
+	write_godot_gradient,
+	write_godot_palette,
+	write_unity_colors,
+};
+pub use self::figma::write_figma_styles;
+pub use self::html::write_html_gallery;
+pub use self::lut::Lut3d;
+pub use self::manifest::{
+	checksum,
+	Drift,
+	Manifest,
+	ManifestEntry,
+};
+#[cfg(feature = "images")]
+pub use self::lut::write_strip_png;
+pub use self::sketch::write_sketch_palette;
+pub use self::substitute::substitute;
+pub use self::swatch::{
+	write_aco,
+	ACO_NAME_LIMIT,
+};
+pub use self::table::{
+	ColorEncoding,
+	ColorTable,
+	TableSyntax,
+};
+pub use self::terminal::{
+	write_alacritty,
+	write_kitty,
+	write_windows_terminal,
+	write_xresources,
+};
+pub use self::theme::{
+	write_vim_colorscheme,
+	write_vscode_theme,
+	ThemeRoles,
+	THEME_ROLES,
+};
+pub use self::tokens::{
+	write_design_tokens,
+	write_style_dictionary,
+	write_token_json,
+};
+
+// Local imports.
+use ::Palette;
+use address::{
+	Address,
+	Selection,
+};
+use format::default::parse_address;
+use trace::Span;
+use utilities::canonical_float;
+
+// Non-local imports.
+use serde_json;
+use serde_json::Value;
+
+// Standard imports.
+use std::io;
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// ExportFormat
+////////////////////////////////////////////////////////////////////////////////
+/// An enum of the formats palettes can be exported to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExportFormat {
+	/// The default palette format.
+	Palette,
+	/// A C header of color constants.
+	CHeader,
+	/// A Rust source file of color constants.
+	RustSource,
+	/// A Godot `ColorPalette` resource.
+	GodotPalette,
+	/// A Godot `Gradient` resource.
+	GodotGradient,
+	/// A Unity color preset library.
+	UnityColors,
+	/// GameMaker `#macro` definitions.
+	GameMakerMacros,
+	/// A standalone HTML gallery page.
+	Html,
+	/// An Adobe Color Swatch file.
+	Aco,
+	/// A JSON object of the palette's semantic tokens.
+	Tokens,
+	/// A Style Dictionary file of the palette's semantic tokens.
+	StyleDictionary,
+	/// A W3C Design Tokens file of the palette's named cells and semantic
+	/// tokens.
+	DesignTokens,
+	/// A JSON list of Figma color styles.
+	Figma,
+	/// A Sketch `.sketchpalette` file.
+	Sketch,
+	/// A Vim colorscheme of the palette's theme roles.
+	Vim,
+	/// A VS Code color theme of the palette's theme roles.
+	VsCode,
+	/// A Base16 or Base24 YAML scheme.
+	Base16,
+	/// An Xresources terminal color scheme.
+	Xresources,
+	/// An Alacritty TOML color scheme.
+	Alacritty,
+	/// A Kitty color scheme.
+	Kitty,
+	/// A Windows Terminal JSON color scheme.
+	WindowsTerminal,
+	/// A PNG image with one pixel per color.
+	#[cfg(feature = "images")]
+	StripPng,
+}
+
+
+impl ExportFormat {
+	/// Returns all of the supported export formats.
+	pub fn all() -> Vec<ExportFormat> {
+		vec![
+			ExportFormat::Palette,
+			ExportFormat::CHeader,
+			ExportFormat::RustSource,
+			ExportFormat::GodotPalette,
+			ExportFormat::GodotGradient,
+			ExportFormat::UnityColors,
+			ExportFormat::GameMakerMacros,
+			ExportFormat::Html,
+			ExportFormat::Aco,
+			ExportFormat::Tokens,
+			ExportFormat::StyleDictionary,
+			ExportFormat::DesignTokens,
+			ExportFormat::Figma,
+			ExportFormat::Sketch,
+			ExportFormat::Vim,
+			ExportFormat::VsCode,
+			ExportFormat::Base16,
+			ExportFormat::Xresources,
+			ExportFormat::Alacritty,
+			ExportFormat::Kitty,
+			ExportFormat::WindowsTerminal,
+			#[cfg(feature = "images")]
+			ExportFormat::StripPng,
+		]
+	}
+
+	/// Returns the name of the format.
+	pub fn name(&self) -> &'static str {
+		match *self {
+			ExportFormat::Palette         => "palette",
+			ExportFormat::CHeader         => "c",
+			ExportFormat::RustSource      => "rust",
+			ExportFormat::GodotPalette    => "godot-palette",
+			ExportFormat::GodotGradient   => "godot-gradient",
+			ExportFormat::UnityColors     => "unity",
+			ExportFormat::GameMakerMacros => "gamemaker",
+			ExportFormat::Html            => "html",
+			ExportFormat::Aco             => "aco",
+			ExportFormat::Tokens          => "tokens",
+			ExportFormat::StyleDictionary => "style-dictionary",
+			ExportFormat::DesignTokens    => "design-tokens",
+			ExportFormat::Figma           => "figma",
+			ExportFormat::Sketch          => "sketch",
+			ExportFormat::Vim             => "vim",
+			ExportFormat::VsCode          => "vscode",
+			ExportFormat::Base16          => "base16",
+			ExportFormat::Xresources      => "xresources",
+			ExportFormat::Alacritty       => "alacritty",
+			ExportFormat::Kitty           => "kitty",
+			ExportFormat::WindowsTerminal => "windows-terminal",
+			#[cfg(feature = "images")]
+			ExportFormat::StripPng        => "png",
+		}
+	}
+
+	/// Returns the format with the given name, ignoring case.
+	pub fn from_name(name: &str) -> Option<ExportFormat> {
+		ExportFormat::all()
+			.into_iter()
+			.find(|f| f.name().eq_ignore_ascii_case(name))
+	}
+
+	/// Returns the file extension used for the format.
+	pub fn extension(&self) -> &'static str {
+		match *self {
+			ExportFormat::Palette         => "palette",
+			ExportFormat::CHeader         => "h",
+			ExportFormat::RustSource      => "rs",
+			ExportFormat::GodotPalette    => "tres",
+			ExportFormat::GodotGradient   => "tres",
+			ExportFormat::UnityColors     => "colors",
+			ExportFormat::GameMakerMacros => "gml",
+			ExportFormat::Html            => "html",
+			ExportFormat::Aco             => "aco",
+			ExportFormat::Tokens          => "json",
+			ExportFormat::StyleDictionary => "json",
+			ExportFormat::DesignTokens    => "tokens.json",
+			ExportFormat::Figma           => "json",
+			ExportFormat::Sketch          => "sketchpalette",
+			ExportFormat::Vim             => "vim",
+			ExportFormat::VsCode          => "json",
+			ExportFormat::Base16          => "yaml",
+			ExportFormat::Xresources      => "Xresources",
+			ExportFormat::Alacritty       => "toml",
+			ExportFormat::Kitty           => "conf",
+			ExportFormat::WindowsTerminal => "json",
+			#[cfg(feature = "images")]
+			ExportFormat::StripPng        => "png",
+		}
+	}
+
+	/// Writes all of the palette's colors to the given buffer in the format.
+	/// Formats which define identifiers use the palette name as a prefix.
+	/// Theme formats map their roles to the palette's semantic tokens and 
+	/// cell names, as with `write_with_roles` given an empty mapping.
+	///
+	/// # Example
+	///
+	/// ```rust
+	/// use palette::*;
+	/// use palette::export::ExportFormat;
+	/// use palette::operation::*;
+	///
+	/// let mut pal = Palette::new("Example", Format::Default, true);
+	/// pal.apply(Box::new(InsertColor::new(Color::new(12, 50, 78)))).unwrap();
+	///
+	/// let format = ExportFormat::from_name("gamemaker").unwrap();
+	/// let mut out = Vec::new();
+	/// format.write(&pal, &mut out).unwrap();
+	///
+	/// assert_eq!(format.extension(), "gml");
+	/// assert!(String::from_utf8(out).unwrap()
+	/// 	.contains("#macro EXAMPLE_0 $4E320C"));
+	/// ```
+	pub fn write<W>(&self, palette: &Palette, out_buf: &mut W) 
+		-> io::Result<()>
+		where W: io::Write
+	{
+		self.write_with_roles(palette, &ThemeRoles::new(), out_buf)
+	}
+
+	/// Writes all of the palette's colors to the given buffer in the format,
+	/// using the given mapping for the roles of theme and terminal formats.
+	/// Other formats ignore the mapping.
+	pub fn write_with_roles<W>(
+		&self,
+		palette: &Palette,
+		roles: &ThemeRoles,
+		out_buf: &mut W)
+		-> io::Result<()>
+		where W: io::Write
+	{
+		let span = Span::enter("export", self.name());
+		let all = Selection::all();
+		let prefix = palette.name().unwrap_or("palette");
+		let result = match *self {
+			ExportFormat::Palette 
+				=> ::Format::Default.write_palette(palette, out_buf),
+			ExportFormat::CHeader 
+				=> write_c_header(palette, &all, prefix, out_buf),
+			ExportFormat::RustSource 
+				=> write_rust_source(palette, &all, prefix, out_buf),
+			ExportFormat::GodotPalette 
+				=> write_godot_palette(palette, &all, out_buf),
+			ExportFormat::GodotGradient 
+				=> write_godot_gradient(palette, &all, out_buf),
+			ExportFormat::UnityColors 
+				=> write_unity_colors(palette, &all, out_buf),
+			ExportFormat::GameMakerMacros 
+				=> write_gamemaker_macros(palette, &all, prefix, out_buf),
+			ExportFormat::Html 
+				=> write_html_gallery(&[palette], prefix, out_buf),
+			ExportFormat::Aco 
+				=> write_aco(palette, &all, out_buf),
+			ExportFormat::Tokens 
+				=> write_token_json(palette, out_buf),
+			ExportFormat::StyleDictionary 
+				=> write_style_dictionary(palette, out_buf),
+			ExportFormat::DesignTokens 
+				=> write_design_tokens(palette, out_buf),
+			ExportFormat::Figma 
+				=> write_figma_styles(palette, &all, out_buf),
+			ExportFormat::Sketch 
+				=> write_sketch_palette(palette, &all, out_buf),
+			ExportFormat::Vim 
+				=> write_vim_colorscheme(palette, roles, out_buf),
+			ExportFormat::VsCode 
+				=> write_vscode_theme(palette, roles, out_buf),
+			ExportFormat::Base16 
+				=> write_base16_scheme(palette, "", out_buf),
+			ExportFormat::Xresources 
+				=> write_xresources(palette, &all, roles, out_buf),
+			ExportFormat::Alacritty 
+				=> write_alacritty(palette, &all, roles, out_buf),
+			ExportFormat::Kitty 
+				=> write_kitty(palette, &all, roles, out_buf),
+			ExportFormat::WindowsTerminal 
+				=> write_windows_terminal(palette, &all, roles, out_buf),
+			#[cfg(feature = "images")]
+			ExportFormat::StripPng => {
+				let colors: Vec<_> = palette.colors(&all)
+					.into_iter()
+					.map(|(_, color)| color)
+					.collect();
+				write_strip_png(&colors, out_buf)
+			},
+		};
+		span.finish(palette.len());
+		result
+	}
+}
+
+
+/// Returns the address of the cell with the given semantic token, name, or 
+/// `page:line:column` address, if it exists.
+pub(crate) fn resolve_cell(palette: &Palette, target: &str) -> Option<Address> {
+	palette.alias(target)
+		.or_else(|| palette.address_of(target))
+		.or_else(|| parse_address(target).ok())
+}
+
+
+/// Writes the given JSON value to the given buffer, followed by a newline.
+pub(crate) fn write_json<W>(value: &Value, out_buf: &mut W) -> io::Result<()>
+	where W: io::Write
+{
+	serde_json::to_writer_pretty(&mut *out_buf, value)
+		.map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+	writeln!(out_buf)
+}
+
+
+/// Returns the given text with each control character, including line breaks,
+/// replaced by a space, so that it can be written within a single line of an
+/// exported file.
+pub(crate) fn single_line(text: &str) -> String {
+	text.chars()
+		.map(|c| if c.is_control() { ' ' } else { c })
+		.collect()
+}
+
+
+/// Returns the given color channel as a JSON number from 0 to 1, rounded to
+/// six decimal places.
+pub(crate) fn unit_channel(value: u8) -> Value {
+	canonical_float(value as f32 / 255.0)
+		.parse::<f64>()
+		.map(Value::from)
+		.unwrap_or(Value::Null)
+}
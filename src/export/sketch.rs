@@ -0,0 +1,98 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides exporting of Sketch `.sketchpalette` files.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use ::Palette;
+use address::Selection;
+use export::{
+	unit_channel,
+	write_json,
+};
+
+// Non-local imports.
+use serde_json::{
+	Map,
+	Value,
+};
+
+// Standard imports.
+use std::io;
+
+
+/// Writes the palette's selected colors to the given buffer as a version 2
+/// Sketch `.sketchpalette` file. Named cells are written with their names.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::export::write_sketch_palette;
+/// use palette::import::read_sketch_palette;
+/// use palette::operation::*;
+///
+/// let mut pal = Palette::new("Example", Format::Default, true);
+/// pal.apply(Box::new(InsertColor::new(Color::new(26, 26, 51)))).unwrap();
+/// pal.set_name(Address::new(0, 0, 0), "Ink");
+///
+/// let mut out = Vec::new();
+/// write_sketch_palette(&pal, &Selection::all(), &mut out).unwrap();
+/// let copy = read_sketch_palette("Example", &mut &out[..]).unwrap();
+///
+/// assert_eq!(copy.cell_name(Address::new(0, 0, 0)), Some("Ink"));
+/// assert_eq!(copy.color(Address::new(0, 0, 0)), Some(Color::new(26, 26, 51)));
+/// ```
+pub fn write_sketch_palette<W>(
+	palette: &Palette,
+	selection: &Selection,
+	out_buf: &mut W)
+	-> io::Result<()>
+	where W: io::Write
+{
+	let colors = palette.colors(selection)
+		.into_iter()
+		.map(|(address, color)| {
+			let mut entry = Map::new();
+			if let Some(name) = palette.cell_name(address) {
+				entry.insert("name".to_owned(), Value::from(name));
+			}
+			entry.insert("red".to_owned(), unit_channel(color.red()));
+			entry.insert("green".to_owned(), unit_channel(color.green()));
+			entry.insert("blue".to_owned(), unit_channel(color.blue()));
+			entry.insert("alpha".to_owned(), Value::from(1.0));
+			Value::Object(entry)
+		})
+		.collect();
+
+	write_json(&json!({
+		"compatibleVersion": "2.0",
+		"pluginVersion": "2.22",
+		"colors": Value::Array(colors),
+		"gradients": [],
+		"images": [],
+	}), out_buf)
+}
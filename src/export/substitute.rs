@@ -0,0 +1,139 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides variable substitution of named cells into text templates.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use ::Palette;
+use result::{
+	Error,
+	Result,
+};
+use space::unit_channels;
+use utilities::hex;
+
+// Non-local imports.
+use color::Color;
+
+
+/// Returns the given template with each `{{name.field}}` variable replaced by
+/// a field of the color of the cell with the given name. If the field is 
+/// omitted, the color's hex code is used. Unterminated variables are copied
+/// through unchanged.
+///
+/// The supported fields are:
+///
+/// | Field      | Example    |
+/// |------------|------------|
+/// | `hex`      | `#0C324E`  |
+/// | `hex_bare` | `0C324E`   |
+/// | `r`        | `12`       |
+/// | `g`        | `50`       |
+/// | `b`        | `78`       |
+/// | `rf`       | `0.0471`   |
+/// | `gf`       | `0.1961`   |
+/// | `bf`       | `0.3059`   |
+/// | `address`  | `0:0:0`    |
+///
+/// # Errors
+///
+/// Returns an `UndefinedName` error if a variable names a cell or field that
+/// does not exist, and an `EmptyAddress` error if the named cell has no color.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::export::substitute;
+/// use palette::operation::*;
+///
+/// let mut pal = Palette::new("Example", Format::Default, true);
+/// pal.apply(Box::new(InsertColor::new(Color::new(12, 50, 78)))).unwrap();
+/// pal.set_name(Address::new(0, 0, 0), "skin_base");
+///
+/// let text = substitute(&pal, "const SKIN = {{skin_base.hex}};").unwrap();
+/// assert_eq!(text, "const SKIN = #0C324E;");
+///
+/// let text = substitute(&pal, "vec3({{skin_base.r}}, {{skin_base.g}}, \
+/// 	{{skin_base.b}})").unwrap();
+/// assert_eq!(text, "vec3(12, 50, 78)");
+/// ```
+pub fn substitute(palette: &Palette, template: &str) -> Result<String> {
+	let mut out = String::with_capacity(template.len());
+	let mut rest = template;
+
+	while let Some(start) = rest.find("{{") {
+		out.push_str(&rest[..start]);
+		let after = &rest[start + 2..];
+		match after.find("}}") {
+			Some(end) => {
+				out.push_str(&variable(palette, after[..end].trim())?);
+				rest = &after[end + 2..];
+			},
+			None => {
+				out.push_str(&rest[start..]);
+				rest = "";
+			},
+		}
+	}
+	out.push_str(rest);
+	Ok(out)
+}
+
+
+/// Returns the value of the given variable.
+fn variable(palette: &Palette, variable: &str) -> Result<String> {
+	let (name, field) = match variable.rfind('.') {
+		Some(dot) => (&variable[..dot], &variable[dot + 1..]),
+		None      => (variable, "hex"),
+	};
+
+	let address = palette.address_of(name)
+		.ok_or_else(|| Error::UndefinedName(name.to_owned()))?;
+	if field == "address" { return Ok(format!("{}", address)); }
+
+	let color = palette.color(address)
+		.ok_or(Error::EmptyAddress(address))?;
+	field_value(color, field)
+		.ok_or_else(|| Error::UndefinedName(variable.to_owned()))
+}
+
+
+/// Returns the value of the given field of a color.
+fn field_value(color: Color, field: &str) -> Option<String> {
+	let unit = unit_channels(color);
+	Some(match field {
+		"hex"      => hex(color),
+		"hex_bare" => hex(color)[1..].to_owned(),
+		"r"        => format!("{}", color.red()),
+		"g"        => format!("{}", color.green()),
+		"b"        => format!("{}", color.blue()),
+		"rf"       => format!("{:.4}", unit[0]),
+		"gf"       => format!("{:.4}", unit[1]),
+		"bf"       => format!("{:.4}", unit[2]),
+		_          => return None,
+	})
+}
@@ -0,0 +1,109 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides export to Adobe swatch files.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use ::Palette;
+use address::Selection;
+use text::{
+	truncate_utf16,
+	utf16_be,
+};
+use utilities::hex;
+
+// Standard imports.
+use std::io;
+
+
+/// The maximum number of UTF-16 code units written for a swatch name in an 
+/// ACO file, excluding the terminating null.
+pub const ACO_NAME_LIMIT: usize = 63;
+
+
+/// Writes the palette's selected colors to the given buffer as an Adobe Color
+/// Swatch (ACO) file. The file contains a version 1 section for older readers
+/// followed by a version 2 section with swatch names. Names are written as 
+/// UTF-16, truncated on a grapheme boundary to `ACO_NAME_LIMIT` code units. 
+/// Unnamed cells are named by their hex color.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::export::write_aco;
+/// use palette::operation::*;
+///
+/// let mut pal = Palette::new("Example", Format::Default, true);
+/// pal.apply(Box::new(InsertColor::new(Color::new(255, 0, 0)))).unwrap();
+/// pal.set_name(Address::new(0, 0, 0), "r\u{E9}d");
+///
+/// let mut out = Vec::new();
+/// write_aco(&pal, &Selection::all(), &mut out).unwrap();
+///
+/// // Version 1 header and the red swatch.
+/// assert_eq!(&out[..14], &[0, 1, 0, 1, 0, 0, 255, 255, 0, 0, 0, 0, 0, 0]);
+/// // Version 2 swatch name, as a length and null-terminated UTF-16.
+/// assert_eq!(&out[28..], &[0, 0, 0, 4, 0, 114, 0, 233, 0, 100, 0, 0]);
+/// ```
+pub fn write_aco<W>(
+	palette: &Palette,
+	selection: &Selection,
+	out_buf: &mut W)
+	-> io::Result<()>
+	where W: io::Write
+{
+	let colors = palette.colors(selection);
+	if colors.len() > u16::max_value() as usize {
+		return Err(io::Error::new(
+			io::ErrorKind::InvalidInput,
+			"too many colors for an ACO file"));
+	}
+
+	for &version in &[1u16, 2] {
+		out_buf.write_all(&version.to_be_bytes())?;
+		out_buf.write_all(&(colors.len() as u16).to_be_bytes())?;
+
+		for &(address, color) in &colors {
+			// RGB color space, with 16-bit channels and an unused component.
+			out_buf.write_all(&[0, 0])?;
+			for &channel in &[color.red(), color.green(), color.blue(), 0] {
+				out_buf.write_all(&(channel as u16 * 257).to_be_bytes())?;
+			}
+
+			if version == 2 {
+				let fallback = hex(color);
+				let name = palette.cell_name(address).unwrap_or(&fallback);
+				let name = utf16_be(truncate_utf16(name, ACO_NAME_LIMIT));
+				let length = (name.len() / 2 + 1) as u32;
+				out_buf.write_all(&length.to_be_bytes())?;
+				out_buf.write_all(&name)?;
+				out_buf.write_all(&[0, 0])?;
+			}
+		}
+	}
+	Ok(())
+}
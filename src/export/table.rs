@@ -0,0 +1,417 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides exporting of game color tables keyed by id.
+//!
+//! Many games look up colors by an id, such as the map color of a block or 
+//! the tint of an item. A [`ColorTable`] maps each id to a palette cell and 
+//! writes the resulting table as JSON, CSV, or a Lua table. Tables can be 
+//! read from a JSON configuration:
+//!
+//! ```text
+//! {
+//!     "syntax": "lua",
+//!     "encoding": "unit",
+//!     "entries": [
+//!         {"id": "iron-ore", "cell": "Ore Blue"},
+//!         {"id": 12, "cell": "0:1:4"}
+//!     ]
+//! }
+//! ```
+//!
+//! The `entries` can also be given as an object mapping ids to cells, in 
+//! which case they are written in id order.
+//!
+//! [`ColorTable`]: struct.ColorTable.html
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use ::Palette;
+use export::{
+	resolve_cell,
+	unit_channel,
+	write_json,
+};
+use utilities::hex;
+
+// Non-local imports.
+use color::Color;
+use serde_json;
+use serde_json::{
+	Map,
+	Value,
+};
+
+// Standard imports.
+use std::io;
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// TableSyntax
+////////////////////////////////////////////////////////////////////////////////
+/// The file syntax of a color table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TableSyntax {
+	/// A JSON object mapping ids to colors.
+	Json,
+	/// A CSV file with one id and color per row.
+	Csv,
+	/// A Lua script returning a table mapping ids to colors.
+	Lua,
+}
+
+
+impl TableSyntax {
+	/// Returns all of the table syntaxes.
+	pub fn all() -> Vec<TableSyntax> {
+		vec![TableSyntax::Json, TableSyntax::Csv, TableSyntax::Lua]
+	}
+
+	/// Returns the name of the syntax.
+	pub fn name(&self) -> &'static str {
+		match *self {
+			TableSyntax::Json => "json",
+			TableSyntax::Csv  => "csv",
+			TableSyntax::Lua  => "lua",
+		}
+	}
+
+	/// Returns the syntax with the given name, ignoring case.
+	pub fn from_name(name: &str) -> Option<TableSyntax> {
+		TableSyntax::all()
+			.into_iter()
+			.find(|s| s.name().eq_ignore_ascii_case(name))
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// ColorEncoding
+////////////////////////////////////////////////////////////////////////////////
+/// The encoding of the colors in a color table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColorEncoding {
+	/// A `#RRGGBB` hex string.
+	Hex,
+	/// A packed `0xRRGGBB` integer.
+	Int,
+	/// Red, green, and blue channels from 0 to 255.
+	Rgb,
+	/// Red, green, and blue channels from 0 to 1.
+	Unit,
+}
+
+
+impl ColorEncoding {
+	/// Returns all of the color encodings.
+	pub fn all() -> Vec<ColorEncoding> {
+		vec![
+			ColorEncoding::Hex,
+			ColorEncoding::Int,
+			ColorEncoding::Rgb,
+			ColorEncoding::Unit,
+		]
+	}
+
+	/// Returns the name of the encoding.
+	pub fn name(&self) -> &'static str {
+		match *self {
+			ColorEncoding::Hex  => "hex",
+			ColorEncoding::Int  => "int",
+			ColorEncoding::Rgb  => "rgb",
+			ColorEncoding::Unit => "unit",
+		}
+	}
+
+	/// Returns the encoding with the given name, ignoring case.
+	pub fn from_name(name: &str) -> Option<ColorEncoding> {
+		ColorEncoding::all()
+			.into_iter()
+			.find(|e| e.name().eq_ignore_ascii_case(name))
+	}
+
+	/// Returns the encoded channels of the given color, or a single value if
+	/// the encoding is not split into channels.
+	fn encode(&self, color: Color) -> Vec<Value> {
+		let channels = [color.red(), color.green(), color.blue()];
+		match *self {
+			ColorEncoding::Hex => vec![Value::String(hex(color))],
+			ColorEncoding::Int => vec![Value::from(
+				(channels[0] as u32) << 16 
+				| (channels[1] as u32) << 8 
+				| channels[2] as u32)],
+			ColorEncoding::Rgb => channels.iter()
+				.map(|&c| Value::from(c))
+				.collect(),
+			ColorEncoding::Unit => channels.iter()
+				.map(|&c| unit_channel(c))
+				.collect(),
+		}
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// ColorTable
+////////////////////////////////////////////////////////////////////////////////
+/// A table mapping game ids to palette cells.
+///
+/// Each id is mapped to the semantic token, cell name, or `page:line:column`
+/// address of a cell. Ids which are integers are written as numeric keys 
+/// where the syntax allows it.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::export::{ColorTable, ColorEncoding, TableSyntax};
+/// use palette::operation::*;
+///
+/// let mut pal = Palette::new("Map", Format::Default, true);
+/// pal.apply(Box::new(InsertColor::new(Color::new(127, 178, 56)))).unwrap();
+/// pal.apply(Box::new(InsertColor::new(Color::new(112, 112, 112)))).unwrap();
+/// pal.set_name(Address::new(0, 0, 0), "grass");
+///
+/// let table = ColorTable::new()
+/// 	.syntax(TableSyntax::Lua)
+/// 	.encoding(ColorEncoding::Int)
+/// 	.entry("1", "grass")
+/// 	.entry("stone", "0:0:1");
+/// let mut out = Vec::new();
+/// table.write(&pal, &mut out).unwrap();
+///
+/// assert_eq!(String::from_utf8(out).unwrap(), "\
+/// return {
+/// 	[1] = 8368696,
+/// 	[\"stone\"] = 7368816,
+/// }
+/// ");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColorTable {
+	/// The syntax of the written table.
+	syntax: TableSyntax,
+	/// The encoding of the written colors.
+	encoding: ColorEncoding,
+	/// The ids and cell references of the table, in order.
+	entries: Vec<(String, String)>,
+}
+
+
+impl ColorTable {
+	/// Creates a new, empty `ColorTable` writing hex colors as JSON.
+	pub fn new() -> Self {
+		ColorTable {
+			syntax: TableSyntax::Json,
+			encoding: ColorEncoding::Hex,
+			entries: Vec::new(),
+		}
+	}
+
+	/// Reads a `ColorTable` from a JSON configuration.
+	///
+	/// Returns an `InvalidData` error if the configuration is malformed or 
+	/// names an unknown syntax or encoding.
+	pub fn read_json<R>(in_buf: &mut R) -> io::Result<ColorTable>
+		where R: io::Read
+	{
+		let invalid = |message: String| 
+			io::Error::new(io::ErrorKind::InvalidData, message);
+		let json: Value = serde_json::from_reader(in_buf)
+			.map_err(|e| invalid(e.to_string()))?;
+
+		let mut table = ColorTable::new();
+		if let Some(name) = json.get("syntax").and_then(|s| s.as_str()) {
+			table.syntax = TableSyntax::from_name(name)
+				.ok_or_else(|| invalid(format!("unknown syntax '{}'", name)))?;
+		}
+		if let Some(name) = json.get("encoding").and_then(|e| e.as_str()) {
+			table.encoding = ColorEncoding::from_name(name)
+				.ok_or_else(|| invalid(
+					format!("unknown encoding '{}'", name)))?;
+		}
+
+		let id_text = |id: &Value| match *id {
+			Value::String(ref id) => Some(id.clone()),
+			Value::Number(ref id) => Some(id.to_string()),
+			_ => None,
+		};
+		match json.get("entries") {
+			Some(&Value::Array(ref entries)) => for (i, entry) in 
+				entries.iter().enumerate() 
+			{
+				let id = entry.get("id").and_then(&id_text);
+				let cell = entry.get("cell").and_then(|c| c.as_str());
+				match (id, cell) {
+					(Some(id), Some(cell)) => table = table.entry(id, cell),
+					_ => return Err(invalid(format!("entry {}: expected an \
+						'id' and a 'cell'", i))),
+				}
+			},
+			Some(&Value::Object(ref entries)) => for (id, cell) in entries {
+				let cell = cell.as_str().ok_or_else(|| invalid(
+					format!("entry '{}': expected a cell", id)))?;
+				table = table.entry(id.clone(), cell);
+			},
+			_ => return Err(invalid("expected 'entries'".to_owned())),
+		}
+		Ok(table)
+	}
+
+	/// Sets the syntax of the written table.
+	pub fn syntax(mut self, syntax: TableSyntax) -> Self {
+		self.syntax = syntax;
+		self
+	}
+
+	/// Sets the encoding of the written colors.
+	pub fn encoding(mut self, encoding: ColorEncoding) -> Self {
+		self.encoding = encoding;
+		self
+	}
+
+	/// Adds an entry mapping the given id to the cell with the given semantic
+	/// token, name, or address.
+	pub fn entry<S, T>(mut self, id: S, cell: T) -> Self
+		where
+			S: Into<String>,
+			T: Into<String>,
+	{
+		self.entries.push((id.into(), cell.into()));
+		self
+	}
+
+	/// Returns the ids and cell references of the table, in order.
+	pub fn entries(&self) -> &[(String, String)] {
+		&self.entries
+	}
+
+	/// Writes the table for the given palette to the given buffer.
+	///
+	/// Returns an `InvalidInput` error if any entry's cell does not exist or
+	/// has no color.
+	pub fn write<W>(&self, palette: &Palette, out_buf: &mut W) 
+		-> io::Result<()>
+		where W: io::Write
+	{
+		let mut rows = Vec::with_capacity(self.entries.len());
+		for &(ref id, ref cell) in &self.entries {
+			let color = resolve_cell(palette, cell)
+				.and_then(|address| palette.color(address))
+				.ok_or_else(|| io::Error::new(
+					io::ErrorKind::InvalidInput,
+					format!("no color for '{}' (id {})", cell, id)))?;
+			rows.push((id, self.encoding.encode(color)));
+		}
+
+		match self.syntax {
+			TableSyntax::Json => {
+				let mut table = Map::new();
+				for (id, mut values) in rows {
+					let value = if values.len() == 1 {
+						values.remove(0)
+					} else {
+						Value::Array(values)
+					};
+					table.insert(id.clone(), value);
+				}
+				write_json(&Value::Object(table), out_buf)
+			},
+
+			TableSyntax::Csv => {
+				if self.encoding == ColorEncoding::Rgb 
+					|| self.encoding == ColorEncoding::Unit 
+				{
+					writeln!(out_buf, "id,red,green,blue")?;
+				} else {
+					writeln!(out_buf, "id,color")?;
+				}
+				for (id, values) in rows {
+					write!(out_buf, "{}", csv_field(id))?;
+					for value in values {
+						write!(out_buf, ",{}", plain(&value))?;
+					}
+					writeln!(out_buf)?;
+				}
+				Ok(())
+			},
+
+			TableSyntax::Lua => {
+				writeln!(out_buf, "return {{")?;
+				for (id, values) in rows {
+					let key = match id.parse::<i64>() {
+						Ok(number) => number.to_string(),
+						Err(_) => format!("{:?}", id),
+					};
+					let value = match values.len() {
+						1 => lua_value(&values[0]),
+						_ => format!("{{r = {}, g = {}, b = {}}}", 
+							values[0], values[1], values[2]),
+					};
+					writeln!(out_buf, "\t[{}] = {},", key, value)?;
+				}
+				writeln!(out_buf, "}}")
+			},
+		}
+	}
+}
+
+
+impl Default for ColorTable {
+	fn default() -> Self {
+		ColorTable::new()
+	}
+}
+
+
+/// Returns the text of the given JSON scalar without quotes.
+fn plain(value: &Value) -> String {
+	match *value {
+		Value::String(ref text) => text.clone(),
+		ref other => other.to_string(),
+	}
+}
+
+
+/// Returns the given JSON scalar as a Lua literal.
+fn lua_value(value: &Value) -> String {
+	match *value {
+		Value::String(ref text) => format!("{:?}", text),
+		ref other => other.to_string(),
+	}
+}
+
+
+/// Returns the given text as a CSV field, quoting it if necessary.
+fn csv_field(text: &str) -> String {
+	if text.contains(|c| c == ',' || c == '"' || c == '\n') {
+		format!("\"{}\"", text.replace('"', "\"\""))
+	} else {
+		text.to_owned()
+	}
+}
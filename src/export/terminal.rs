@@ -0,0 +1,347 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides exporting of terminal emulator color schemes.
+//!
+//! Terminal schemes are built from the colors of a selection, using the 
+//! [`ThemeRoles`] mapping to override individual colors. The `ansi0` to 
+//! `ansi15` roles set the sixteen ANSI colors, and the `foreground`, 
+//! `background`, `cursor`, and `selection` roles set the special colors. ANSI
+//! colors without a mapped cell are taken from the selection in address 
+//! order, and any selected colors beyond the sixteenth are written as the 
+//! extended colors 16 to 255 by the formats supporting them. Each scheme 
+//! begins with a comment holding the palette name, with any control 
+//! characters replaced by spaces.
+//!
+//! [`ThemeRoles`]: struct.ThemeRoles.html
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use ::Palette;
+use address::Selection;
+use export::{
+	single_line,
+	write_json,
+	ThemeRoles,
+};
+use utilities::hex;
+
+// Non-local imports.
+use color::Color;
+use serde_json::{
+	Map,
+	Value,
+};
+
+// Standard imports.
+use std::io;
+
+
+/// The names of the ANSI colors, in order.
+const ANSI_NAMES: [&'static str; 8] = [
+	"black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// TerminalColors
+////////////////////////////////////////////////////////////////////////////////
+/// The resolved colors of a terminal scheme.
+#[derive(Debug, Clone, PartialEq)]
+struct TerminalColors {
+	/// The default text color.
+	foreground: Color,
+	/// The default background color.
+	background: Color,
+	/// The cursor color, if mapped.
+	cursor: Option<Color>,
+	/// The selection background color, if mapped.
+	selection: Option<Color>,
+	/// The indexed colors, starting with the sixteen ANSI colors.
+	indexed: Vec<Color>,
+}
+
+
+impl TerminalColors {
+	/// Resolves the terminal colors of the given palette selection.
+	///
+	/// Returns an `InvalidInput` error if fewer than sixteen ANSI colors are
+	/// available.
+	fn new(palette: &Palette, selection: &Selection, roles: &ThemeRoles)
+		-> io::Result<TerminalColors>
+	{
+		let mut indexed: Vec<Color> = palette.colors(selection)
+			.into_iter()
+			.map(|(_, color)| color)
+			.take(256)
+			.collect();
+		let selected = indexed.len();
+		if selected < 16 {
+			indexed.resize(16, Color::new(0, 0, 0));
+		}
+		for (index, slot) in indexed.iter_mut().take(16).enumerate() {
+			let role = format!("ansi{}", index);
+			match roles.color(palette, &role) {
+				Some(color) => *slot = color,
+				None if index < selected => (),
+				None => return Err(io::Error::new(
+					io::ErrorKind::InvalidInput,
+					format!("no color for {}", role))),
+			}
+		}
+
+		Ok(TerminalColors {
+			foreground: roles.color(palette, "foreground")
+				.unwrap_or(indexed[7]),
+			background: roles.color(palette, "background")
+				.unwrap_or(indexed[0]),
+			cursor: roles.color(palette, "cursor"),
+			selection: roles.color(palette, "selection"),
+			indexed: indexed,
+		})
+	}
+}
+
+
+/// Writes an Xresources color scheme to the given buffer.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::export::{ThemeRoles, write_xresources};
+/// use palette::operation::*;
+///
+/// let mut pal = Palette::new("Example", Format::Default, true);
+/// for i in 0..16 {
+/// 	let v = i * 16;
+/// 	pal.apply(Box::new(InsertColor::new(Color::new(v, v, v)))).unwrap();
+/// }
+/// let roles = ThemeRoles::new().role("foreground", "0:0:15");
+///
+/// let mut out = Vec::new();
+/// write_xresources(&pal, &Selection::all(), &roles, &mut out).unwrap();
+/// let text = String::from_utf8(out).unwrap();
+///
+/// assert!(text.contains("*.foreground: #F0F0F0\n"));
+/// assert!(text.contains("*.background: #000000\n"));
+/// assert!(text.contains("*.color1: #101010\n"));
+/// ```
+pub fn write_xresources<W>(
+	palette: &Palette,
+	selection: &Selection,
+	roles: &ThemeRoles,
+	out_buf: &mut W)
+	-> io::Result<()>
+	where W: io::Write
+{
+	let colors = TerminalColors::new(palette, selection, roles)?;
+	writeln!(out_buf, "! {}", 
+		single_line(palette.name().unwrap_or("palette")))?;
+	writeln!(out_buf, "*.foreground: {}", hex(colors.foreground))?;
+	writeln!(out_buf, "*.background: {}", hex(colors.background))?;
+	if let Some(cursor) = colors.cursor {
+		writeln!(out_buf, "*.cursorColor: {}", hex(cursor))?;
+	}
+	for (index, &color) in colors.indexed.iter().enumerate() {
+		writeln!(out_buf, "*.color{}: {}", index, hex(color))?;
+	}
+	Ok(())
+}
+
+
+/// Writes an Alacritty TOML color scheme to the given buffer.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::export::{ThemeRoles, write_alacritty};
+/// use palette::operation::*;
+///
+/// let mut pal = Palette::new("Example", Format::Default, true);
+/// for i in 0..17 {
+/// 	let v = i * 15;
+/// 	pal.apply(Box::new(InsertColor::new(Color::new(v, v, v)))).unwrap();
+/// }
+///
+/// let mut out = Vec::new();
+/// write_alacritty(&pal, &Selection::all(), &ThemeRoles::new(), &mut out)
+/// 	.unwrap();
+/// let toml = String::from_utf8(out).unwrap();
+///
+/// assert!(toml.contains("[colors.normal]\nblack = \"#000000\"\n"));
+/// assert!(toml.contains("[colors.bright]\nblack = \"#787878\"\n"));
+/// assert!(toml.contains("index = 16\ncolor = \"#F0F0F0\"\n"));
+/// ```
+pub fn write_alacritty<W>(
+	palette: &Palette,
+	selection: &Selection,
+	roles: &ThemeRoles,
+	out_buf: &mut W)
+	-> io::Result<()>
+	where W: io::Write
+{
+	let colors = TerminalColors::new(palette, selection, roles)?;
+	writeln!(out_buf, "# {}", 
+		single_line(palette.name().unwrap_or("palette")))?;
+	writeln!(out_buf, "[colors.primary]")?;
+	writeln!(out_buf, "background = \"{}\"", hex(colors.background))?;
+	writeln!(out_buf, "foreground = \"{}\"", hex(colors.foreground))?;
+	if let Some(cursor) = colors.cursor {
+		writeln!(out_buf, "\n[colors.cursor]")?;
+		writeln!(out_buf, "text = \"{}\"", hex(colors.background))?;
+		writeln!(out_buf, "cursor = \"{}\"", hex(cursor))?;
+	}
+	if let Some(selection) = colors.selection {
+		writeln!(out_buf, "\n[colors.selection]")?;
+		writeln!(out_buf, "text = \"CellForeground\"")?;
+		writeln!(out_buf, "background = \"{}\"", hex(selection))?;
+	}
+	for (table, offset) in &[("normal", 0), ("bright", 8)] {
+		writeln!(out_buf, "\n[colors.{}]", table)?;
+		for (i, name) in ANSI_NAMES.iter().enumerate() {
+			writeln!(out_buf, "{} = \"{}\"", 
+				name, hex(colors.indexed[offset + i]))?;
+		}
+	}
+	for (index, &color) in colors.indexed.iter().enumerate().skip(16) {
+		writeln!(out_buf, "\n[[colors.indexed_colors]]")?;
+		writeln!(out_buf, "index = {}", index)?;
+		writeln!(out_buf, "color = \"{}\"", hex(color))?;
+	}
+	Ok(())
+}
+
+
+/// Writes a Kitty color scheme to the given buffer.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::export::{ThemeRoles, write_kitty};
+/// use palette::operation::*;
+///
+/// let mut pal = Palette::new("Example", Format::Default, true);
+/// for i in 0..16 {
+/// 	let v = i * 16;
+/// 	pal.apply(Box::new(InsertColor::new(Color::new(v, v, v)))).unwrap();
+/// }
+/// let roles = ThemeRoles::new().role("selection", "0:0:8");
+///
+/// let mut out = Vec::new();
+/// write_kitty(&pal, &Selection::all(), &roles, &mut out).unwrap();
+/// let conf = String::from_utf8(out).unwrap();
+///
+/// assert!(conf.contains("selection_background #808080\n"));
+/// assert!(conf.contains("color15 #F0F0F0\n"));
+/// ```
+pub fn write_kitty<W>(
+	palette: &Palette,
+	selection: &Selection,
+	roles: &ThemeRoles,
+	out_buf: &mut W)
+	-> io::Result<()>
+	where W: io::Write
+{
+	let colors = TerminalColors::new(palette, selection, roles)?;
+	writeln!(out_buf, "# {}", 
+		single_line(palette.name().unwrap_or("palette")))?;
+	writeln!(out_buf, "foreground {}", hex(colors.foreground))?;
+	writeln!(out_buf, "background {}", hex(colors.background))?;
+	if let Some(cursor) = colors.cursor {
+		writeln!(out_buf, "cursor {}", hex(cursor))?;
+	}
+	if let Some(selection) = colors.selection {
+		writeln!(out_buf, "selection_background {}", hex(selection))?;
+	}
+	for (index, &color) in colors.indexed.iter().enumerate() {
+		writeln!(out_buf, "color{} {}", index, hex(color))?;
+	}
+	Ok(())
+}
+
+
+/// Writes a Windows Terminal color scheme to the given buffer as JSON. The 
+/// scheme is named after the palette. Only the sixteen ANSI colors are 
+/// written.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::export::{ThemeRoles, write_windows_terminal};
+/// use palette::operation::*;
+///
+/// let mut pal = Palette::new("Example", Format::Default, true);
+/// for i in 0..16 {
+/// 	let v = i * 16;
+/// 	pal.apply(Box::new(InsertColor::new(Color::new(v, v, v)))).unwrap();
+/// }
+///
+/// let mut out = Vec::new();
+/// write_windows_terminal(&pal, &Selection::all(), &ThemeRoles::new(), 
+/// 	&mut out).unwrap();
+/// let json = String::from_utf8(out).unwrap();
+///
+/// assert!(json.contains("\"brightBlack\": \"#808080\""));
+/// assert!(json.contains("\"purple\": \"#505050\""));
+/// ```
+pub fn write_windows_terminal<W>(
+	palette: &Palette,
+	selection: &Selection,
+	roles: &ThemeRoles,
+	out_buf: &mut W)
+	-> io::Result<()>
+	where W: io::Write
+{
+	let colors = TerminalColors::new(palette, selection, roles)?;
+	let mut scheme = Map::new();
+	let mut insert = |key: &str, color: Color| {
+		scheme.insert(key.to_owned(), Value::String(hex(color)));
+	};
+	insert("foreground", colors.foreground);
+	insert("background", colors.background);
+	if let Some(cursor) = colors.cursor {
+		insert("cursorColor", cursor);
+	}
+	if let Some(selection) = colors.selection {
+		insert("selectionBackground", selection);
+	}
+	for (i, name) in ANSI_NAMES.iter().enumerate() {
+		// Windows Terminal calls magenta purple.
+		let name = if *name == "magenta" { "purple" } else { name };
+		insert(name, colors.indexed[i]);
+		let mut bright = String::from("bright");
+		bright.push_str(&name[..1].to_uppercase());
+		bright.push_str(&name[1..]);
+		insert(&bright, colors.indexed[8 + i]);
+	}
+	scheme.insert("name".to_owned(), 
+		Value::String(palette.name().unwrap_or("palette").to_owned()));
+	write_json(&Value::Object(scheme), out_buf)
+}
@@ -0,0 +1,384 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides exporting of editor color themes.
+//!
+//! Themes are built from a set of named roles, such as `background` or 
+//! `comment`, listed in [`THEME_ROLES`]. A [`ThemeRoles`] mapping assigns 
+//! each role to a palette cell. Roles without a mapping use the cell whose 
+//! semantic token or name matches the role, so palettes with tokens like 
+//! `keyword` can be exported without any configuration.
+//!
+//! A mapping can be read from a text file with one role per line:
+//!
+//! ```text
+//! # Role mapping for a dark theme.
+//! background = surface-0
+//! foreground = ink
+//! keyword = 00:02:04
+//! ```
+//!
+//! [`THEME_ROLES`]: constant.THEME_ROLES.html
+//! [`ThemeRoles`]: struct.ThemeRoles.html
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use ::Palette;
+use address::Address;
+use export::{
+	resolve_cell,
+	single_line,
+	write_json,
+};
+use format::default::invalid;
+use space::contrast_ratio;
+use utilities::hex;
+
+// Non-local imports.
+use color::Color;
+use serde_json::{
+	Map,
+	Value,
+};
+
+// Standard imports.
+use std::collections::BTreeMap;
+use std::io;
+use std::io::{
+	BufRead,
+	BufReader,
+};
+
+
+/// The roles used by editor themes.
+pub const THEME_ROLES: [&'static str; 17] = [
+	"background",
+	"foreground",
+	"selection",
+	"cursor",
+	"line-highlight",
+	"line-number",
+	"comment",
+	"string",
+	"number",
+	"constant",
+	"keyword",
+	"function",
+	"type",
+	"variable",
+	"operator",
+	"error",
+	"warning",
+];
+
+
+/// The Vim highlight groups written for each role, with the roles of their 
+/// foreground and background colors.
+const VIM_GROUPS: [(&'static str, Option<&'static str>, Option<&'static str>);
+	18] = [
+	("Normal",     Some("foreground"),  Some("background")),
+	("Visual",     None,                Some("selection")),
+	("Cursor",     Some("background"),  Some("cursor")),
+	("CursorLine", None,                Some("line-highlight")),
+	("LineNr",     Some("line-number"), None),
+	("Comment",    Some("comment"),     None),
+	("String",     Some("string"),      None),
+	("Number",     Some("number"),      None),
+	("Constant",   Some("constant"),    None),
+	("Statement",  Some("keyword"),     None),
+	("Keyword",    Some("keyword"),     None),
+	("Function",   Some("function"),    None),
+	("Type",       Some("type"),        None),
+	("Identifier", Some("variable"),    None),
+	("Operator",   Some("operator"),    None),
+	("Error",      Some("error"),       None),
+	("ErrorMsg",   Some("error"),       None),
+	("WarningMsg", Some("warning"),     None),
+];
+
+
+/// The VS Code workbench colors written for each role.
+const VSCODE_COLORS: [(&'static str, &'static str); 8] = [
+	("editor.background",              "background"),
+	("editor.foreground",              "foreground"),
+	("editor.selectionBackground",     "selection"),
+	("editorCursor.foreground",        "cursor"),
+	("editor.lineHighlightBackground", "line-highlight"),
+	("editorLineNumber.foreground",    "line-number"),
+	("editorError.foreground",         "error"),
+	("editorWarning.foreground",       "warning"),
+];
+
+
+/// The TextMate scopes styled by VS Code token colors for each role.
+const VSCODE_SCOPES: [(&'static str, &'static [&'static str]); 9] = [
+	("comment",  &["comment"]),
+	("string",   &["string"]),
+	("number",   &["constant.numeric"]),
+	("constant", &["constant.language", "constant.character"]),
+	("keyword",  &["keyword", "storage.modifier"]),
+	("function", &["entity.name.function", "support.function"]),
+	("type",     &["entity.name.type", "support.type", "storage.type"]),
+	("variable", &["variable"]),
+	("operator", &["keyword.operator"]),
+];
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// ThemeRoles
+////////////////////////////////////////////////////////////////////////////////
+/// A mapping from theme roles to palette cells.
+///
+/// Each role is mapped to the semantic token, cell name, or address of a 
+/// cell. Roles without a mapping are looked up as tokens or names matching 
+/// the role itself.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::export::ThemeRoles;
+/// use palette::operation::*;
+///
+/// let mut pal = Palette::new("Example", Format::Default, true);
+/// pal.apply(Box::new(InsertColor::new(Color::new(20, 20, 30)))).unwrap();
+/// pal.apply(Box::new(InsertColor::new(Color::new(220, 80, 0)))).unwrap();
+/// pal.set_alias("background", Address::new(0, 0, 0)).unwrap();
+/// pal.set_name(Address::new(0, 0, 1), "Orange");
+///
+/// let roles = ThemeRoles::read(&mut "keyword = Orange".as_bytes()).unwrap();
+///
+/// assert_eq!(roles.color(&pal, "background"), Some(Color::new(20, 20, 30)));
+/// assert_eq!(roles.color(&pal, "keyword"), Some(Color::new(220, 80, 0)));
+/// assert_eq!(roles.color(&pal, "comment"), None);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ThemeRoles {
+	/// The cell reference of each mapped role.
+	roles: BTreeMap<String, String>,
+}
+
+
+impl ThemeRoles {
+	/// Creates a new `ThemeRoles` without any mappings.
+	pub fn new() -> Self {
+		Default::default()
+	}
+
+	/// Reads a `ThemeRoles` from text with one `role = cell` mapping per 
+	/// line. Blank lines and lines starting with `#` are ignored.
+	pub fn read<R>(in_buf: &mut R) -> io::Result<ThemeRoles>
+		where R: io::Read
+	{
+		let mut roles = ThemeRoles::new();
+		for (i, line) in BufReader::new(in_buf).lines().enumerate() {
+			let line = line?;
+			let line = line.trim();
+			if line.is_empty() || line.starts_with('#') { continue; }
+
+			let mut parts = line.splitn(2, '=');
+			let role = parts.next().map_or("", str::trim);
+			let cell = parts.next().map_or("", str::trim);
+			if role.is_empty() || cell.is_empty() {
+				return Err(invalid(i, format!("invalid mapping '{}'", line)));
+			}
+			roles = roles.role(role, cell);
+		}
+		Ok(roles)
+	}
+
+	/// Maps the given role to the cell with the given semantic token, name,
+	/// or `page:line:column` address.
+	pub fn role<S, T>(mut self, role: S, cell: T) -> Self
+		where
+			S: Into<String>,
+			T: Into<String>,
+	{
+		self.roles.insert(role.into(), cell.into());
+		self
+	}
+
+	/// Returns the cell reference for the given role.
+	pub fn target<'a>(&'a self, role: &'a str) -> &'a str {
+		self.roles.get(role).map_or(role, |cell| &cell[..])
+	}
+
+	/// Returns the address of the cell for the given role in the palette, if
+	/// it exists.
+	pub fn address(&self, palette: &Palette, role: &str) -> Option<Address> {
+		resolve_cell(palette, self.target(role))
+	}
+
+	/// Returns the color for the given role in the palette, if its cell 
+	/// exists and has a color.
+	pub fn color(&self, palette: &Palette, role: &str) -> Option<Color> {
+		self.address(palette, role)
+			.and_then(|address| palette.color(address))
+	}
+
+	/// Returns whether the palette's background role is dark, or None if it 
+	/// has no background color.
+	fn is_dark(&self, palette: &Palette) -> Option<bool> {
+		self.color(palette, "background").map(|background| 
+			contrast_ratio(background, Color::new(255, 255, 255)) >
+				contrast_ratio(background, Color::new(0, 0, 0)))
+	}
+}
+
+
+/// Writes a Vim colorscheme to the given buffer, setting a highlight group 
+/// for each role with a color in the palette. The scheme is named after the
+/// palette, with any control characters in the name replaced by spaces.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::export::{ThemeRoles, write_vim_colorscheme};
+/// use palette::operation::*;
+///
+/// let mut pal = Palette::new("dusk", Format::Default, true);
+/// pal.apply(Box::new(InsertColor::new(Color::new(20, 20, 30)))).unwrap();
+/// pal.apply(Box::new(InsertColor::new(Color::new(200, 200, 180)))).unwrap();
+/// pal.set_alias("background", Address::new(0, 0, 0)).unwrap();
+/// pal.set_alias("comment", Address::new(0, 0, 1)).unwrap();
+///
+/// let mut out = Vec::new();
+/// write_vim_colorscheme(&pal, &ThemeRoles::new(), &mut out).unwrap();
+/// let vim = String::from_utf8(out).unwrap();
+///
+/// assert!(vim.contains("set background=dark\n"));
+/// assert!(vim.contains("let g:colors_name = \"dusk\"\n"));
+/// assert!(vim.contains("highlight Normal guibg=#14141E\n"));
+/// assert!(vim.contains("highlight Comment guifg=#C8C8B4\n"));
+///
+/// let pal = Palette::new("dusk\nsilent !ls", Format::Default, true);
+/// let mut out = Vec::new();
+/// write_vim_colorscheme(&pal, &ThemeRoles::new(), &mut out).unwrap();
+/// let vim = String::from_utf8(out).unwrap();
+/// assert!(!vim.contains("\nsilent"));
+/// assert!(vim.contains("let g:colors_name = \"dusk silent !ls\"\n"));
+/// ```
+pub fn write_vim_colorscheme<W>(
+	palette: &Palette,
+	roles: &ThemeRoles,
+	out_buf: &mut W)
+	-> io::Result<()>
+	where W: io::Write
+{
+	let name = single_line(palette.name().unwrap_or("palette"));
+	writeln!(out_buf, "\" Name: {}", name)?;
+	writeln!(out_buf, "highlight clear")?;
+	writeln!(out_buf, "if exists(\"syntax_on\")")?;
+	writeln!(out_buf, "\tsyntax reset")?;
+	writeln!(out_buf, "endif")?;
+	if let Some(dark) = roles.is_dark(palette) {
+		writeln!(out_buf, "set background={}", 
+			if dark { "dark" } else { "light" })?;
+	}
+	writeln!(out_buf, "let g:colors_name = \"{}\"", 
+		name.replace('\\', "\\\\").replace('"', "\\\""))?;
+	writeln!(out_buf)?;
+
+	for &(group, fg, bg) in VIM_GROUPS.iter() {
+		let fg = fg.and_then(|role| roles.color(palette, role));
+		let bg = bg.and_then(|role| roles.color(palette, role));
+		if fg.is_none() && bg.is_none() { continue; }
+
+		write!(out_buf, "highlight {}", group)?;
+		if let Some(color) = fg {
+			write!(out_buf, " guifg={}", hex(color))?;
+		}
+		if let Some(color) = bg {
+			write!(out_buf, " guibg={}", hex(color))?;
+		}
+		writeln!(out_buf)?;
+	}
+	Ok(())
+}
+
+
+/// Writes a VS Code color theme to the given buffer as JSON, setting the 
+/// workbench colors and token colors for each role with a color in the 
+/// palette. The theme is named after the palette.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::export::{ThemeRoles, write_vscode_theme};
+/// use palette::operation::*;
+///
+/// let mut pal = Palette::new("Dawn", Format::Default, true);
+/// pal.apply(Box::new(InsertColor::new(Color::new(250, 245, 235)))).unwrap();
+/// pal.apply(Box::new(InsertColor::new(Color::new(180, 40, 90)))).unwrap();
+/// pal.set_name(Address::new(0, 0, 0), "paper");
+/// pal.set_name(Address::new(0, 0, 1), "berry");
+///
+/// let roles = ThemeRoles::new()
+/// 	.role("background", "paper")
+/// 	.role("keyword", "berry");
+/// let mut out = Vec::new();
+/// write_vscode_theme(&pal, &roles, &mut out).unwrap();
+/// let json = String::from_utf8(out).unwrap();
+///
+/// assert!(json.contains("\"type\": \"light\""));
+/// assert!(json.contains("\"editor.background\": \"#FAF5EB\""));
+/// assert!(json.contains("\"foreground\": \"#B4285A\""));
+/// ```
+pub fn write_vscode_theme<W>(
+	palette: &Palette,
+	roles: &ThemeRoles,
+	out_buf: &mut W)
+	-> io::Result<()>
+	where W: io::Write
+{
+	let mut colors = Map::new();
+	for &(key, role) in VSCODE_COLORS.iter() {
+		if let Some(color) = roles.color(palette, role) {
+			colors.insert(key.to_owned(), Value::String(hex(color)));
+		}
+	}
+
+	let mut token_colors = Vec::new();
+	for &(role, scopes) in VSCODE_SCOPES.iter() {
+		if let Some(color) = roles.color(palette, role) {
+			token_colors.push(json!({
+				"name": role,
+				"scope": scopes,
+				"settings": { "foreground": hex(color) },
+			}));
+		}
+	}
+
+	let dark = roles.is_dark(palette).unwrap_or(true);
+	write_json(&json!({
+		"name": palette.name().unwrap_or("palette"),
+		"type": if dark { "dark" } else { "light" },
+		"colors": colors,
+		"tokenColors": token_colors,
+	}), out_buf)
+}
@@ -0,0 +1,282 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides exporting of a palette's named cells and semantic tokens as 
+//! design token files.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use ::Palette;
+use address::{
+	Address,
+	Reference,
+};
+use export::write_json;
+use utilities::hex;
+
+// Non-local imports.
+use color::Color;
+use serde_json::{
+	Map,
+	Value,
+};
+
+// Standard imports.
+use std::io;
+
+
+/// Writes the palette's semantic tokens to the given buffer as a flat JSON 
+/// object mapping each token to its hex color. Tokens whose cells have been 
+/// removed or have no color are omitted.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::export::write_token_json;
+/// use palette::operation::*;
+///
+/// let mut pal = Palette::new("Example", Format::Default, true);
+/// pal.apply(Box::new(InsertColor::new(Color::new(200, 0, 0)))).unwrap();
+/// pal.set_alias("danger", Address::new(0, 0, 0)).unwrap();
+///
+/// let mut out = Vec::new();
+/// write_token_json(&pal, &mut out).unwrap();
+/// assert_eq!(String::from_utf8(out).unwrap(), 
+/// 	"{\n  \"danger\": \"#C80000\"\n}\n");
+/// ```
+pub fn write_token_json<W>(palette: &Palette, out_buf: &mut W) 
+	-> io::Result<()>
+	where W: io::Write
+{
+	let mut tokens = Map::new();
+	for (token, color) in token_colors(palette) {
+		tokens.insert(token.to_owned(), Value::String(hex(color)));
+	}
+	write_json(&Value::Object(tokens), out_buf)
+}
+
+
+/// Writes the palette's semantic tokens to the given buffer in the Style 
+/// Dictionary format, as `value` properties beneath a `color` category. 
+/// Tokens containing dots are nested, so `button.primary` is written as the
+/// `primary` property of `button`. Tokens whose cells have been removed or
+/// have no color are omitted.
+///
+/// # Errors
+///
+/// Returns an `InvalidInput` error if a token is also the group of another
+/// token, such as `button` and `button.primary`, or has a part named `value`.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::export::write_style_dictionary;
+/// use palette::operation::*;
+///
+/// let mut pal = Palette::new("Example", Format::Default, true);
+/// pal.apply(Box::new(InsertColor::new(Color::new(200, 0, 0)))).unwrap();
+/// pal.set_alias("button.danger", Address::new(0, 0, 0)).unwrap();
+///
+/// let mut out = Vec::new();
+/// write_style_dictionary(&pal, &mut out).unwrap();
+/// let text = String::from_utf8(out).unwrap();
+/// assert!(text.contains("\"danger\": {\n        \"value\": \"#C80000\""));
+///
+/// pal.set_alias("button", Address::new(0, 0, 0)).unwrap();
+/// assert!(write_style_dictionary(&pal, &mut Vec::new()).is_err());
+/// ```
+pub fn write_style_dictionary<W>(palette: &Palette, out_buf: &mut W)
+	-> io::Result<()>
+	where W: io::Write
+{
+	let mut colors = Map::new();
+	for (token, color) in token_colors(palette) {
+		let path: Vec<_> = token.split('.').collect();
+		insert_leaf(&mut colors, &path, "value", Value::String(hex(color)))?;
+	}
+
+	let mut root = Map::new();
+	root.insert("color".to_owned(), Value::Object(colors));
+	write_json(&Value::Object(root), out_buf)
+}
+
+
+/// Writes the palette's named cells and semantic tokens to the given buffer
+/// in the W3C Design Tokens format, which Style Dictionary also reads. 
+///
+/// Each named cell becomes a color token, grouped beneath the names of its
+/// page and line when they are named. Each semantic token is written at the
+/// path given by splitting it on dots, and refers to its cell's token if the
+/// cell is named, or otherwise holds the cell's color. Dots and braces in 
+/// names, and dollar signs beginning them, are replaced by dashes, as they 
+/// are reserved in token paths.
+///
+/// # Errors
+///
+/// Returns an `InvalidInput` error if two tokens have the same path, such as
+/// a cell and a semantic token both named `red`, or if a token is also the 
+/// group of another token.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::export::write_design_tokens;
+/// use palette::operation::*;
+///
+/// let mut pal = Palette::new("Example", Format::Default, true);
+/// pal.apply(Box::new(InsertColor::new(Color::new(200, 0, 0)))).unwrap();
+/// pal.set_name(Address::new(0, 0, 0), "red");
+/// pal.set_alias("status.danger", Address::new(0, 0, 0)).unwrap();
+///
+/// let mut out = Vec::new();
+/// write_design_tokens(&pal, &mut out).unwrap();
+/// let text = String::from_utf8(out).unwrap();
+/// assert!(text.contains("\"red\": {\n    \"$type\": \"color\",\n    \
+/// 	\"$value\": \"#C80000\""));
+/// assert!(text.contains("\"$value\": \"{red}\""));
+///
+/// pal.set_alias("red", Address::new(0, 0, 0)).unwrap();
+/// assert!(write_design_tokens(&pal, &mut Vec::new()).is_err());
+/// ```
+pub fn write_design_tokens<W>(palette: &Palette, out_buf: &mut W)
+	-> io::Result<()>
+	where W: io::Write
+{
+	let mut root = Map::new();
+	for (name, address) in palette.named_cells() {
+		if let Some(color) = palette.color(address) {
+			let path = cell_path(palette, &name, address);
+			insert_token(&mut root, &path, Value::String(hex(color)))?;
+		}
+	}
+
+	for (token, address) in palette.aliases() {
+		let address = match address {
+			Some(address) => address,
+			None => continue,
+		};
+		let value = match palette.cell_name(address) {
+			Some(name) => Value::String(format!("{{{}}}", 
+				cell_path(palette, name, address).join("."))),
+			None => match palette.color(address) {
+				Some(color) => Value::String(hex(color)),
+				None => continue,
+			},
+		};
+		let path: Vec<_> = token.split('.').map(token_name).collect();
+		insert_token(&mut root, &path, value)?;
+	}
+	write_json(&Value::Object(root), out_buf)
+}
+
+
+/// Returns the token path of the named cell at the given address.
+fn cell_path(palette: &Palette, name: &str, address: Address) -> Vec<String> {
+	let data = &palette.data;
+	let page = data.name(&Reference::page_of(&address));
+	let line = data.name(&Reference::line_of(&address));
+	page.into_iter()
+		.chain(line)
+		.chain(Some(name))
+		.map(token_name)
+		.collect()
+}
+
+
+/// Returns the given name with the characters reserved in token paths 
+/// replaced.
+fn token_name(name: &str) -> String {
+	let name = name.replace(|c| c == '.' || c == '{' || c == '}', "-");
+	if name.starts_with('$') {
+		format!("-{}", &name[1..])
+	} else {
+		name
+	}
+}
+
+
+/// Inserts a color token with the given value at the given path, creating
+/// groups as needed. Returns an `InvalidInput` error if the path collides 
+/// with another token.
+fn insert_token(root: &mut Map<String, Value>, path: &[String], value: Value) 
+	-> io::Result<()>
+{
+	insert_leaf(root, path, "$value", value)?
+		.insert("$type".to_owned(), Value::String("color".to_owned()));
+	Ok(())
+}
+
+
+/// Inserts a token at the given path, creating groups as needed, and stores
+/// the given value in it under the given key. Returns the token's object.
+///
+/// Tokens are the objects holding the key, so an `InvalidInput` error is
+/// returned if the path passes through or ends at another token or group, or
+/// has a part named by the key.
+fn insert_leaf<'m, S>(
+	root: &'m mut Map<String, Value>,
+	path: &[S],
+	key: &str,
+	value: Value)
+	-> io::Result<&'m mut Map<String, Value>>
+	where S: AsRef<str>
+{
+	let collision = || {
+		let token: Vec<_> = path.iter().map(|part| part.as_ref()).collect();
+		io::Error::new(io::ErrorKind::InvalidInput, format!(
+			"token '{}' collides with another token", token.join(".")))
+	};
+
+	let mut group = root;
+	for part in path {
+		let part = part.as_ref();
+		if part == key || group.contains_key(key) { return Err(collision()); }
+		group = match *group
+			.entry(part.to_owned())
+			.or_insert_with(|| Value::Object(Map::new()))
+		{
+			Value::Object(ref mut map) => map,
+			_ => return Err(collision()),
+		};
+	}
+	if !group.is_empty() { return Err(collision()); }
+	group.insert(key.to_owned(), value);
+	Ok(group)
+}
+
+
+/// Returns the palette's tokens and the colors of their cells, omitting 
+/// tokens without colors.
+fn token_colors(palette: &Palette) -> Vec<(&str, Color)> {
+	palette.aliases()
+		.into_iter()
+		.filter_map(|(token, address)| address
+			.and_then(|a| palette.color(a))
+			.map(|color| (token, color)))
+		.collect()
+}
@@ -28,6 +28,12 @@
 //!
 ////////////////////////////////////////////////////////////////////////////////
 
+// Local imports.
+use space::{
+	GamutMapping,
+	WideColor,
+};
+
 // Non-local imports.
 use color::Color;
 
@@ -45,14 +51,34 @@ pub enum Expression {
 	/// An empty expression.
 	Empty,
 	/// A pure color.
-	Color(Color)
+	Color(Color),
+	/// A pure extended-range color, which may lie outside of the sRGB gamut.
+	/// The channels are stored as floats, and are only quantized when read as
+	/// a `Color`.
+	Wide(WideColor),
 }
 
 
 impl Expression {
-	/// Returns the `Color` generated by the expression.
+	/// Returns the `Color` generated by the expression. Extended-range colors
+	/// are mapped into the sRGB gamut and quantized to 8 bits per channel.
 	pub fn color(&self) -> Option<Color> {
-		None
+		match *self {
+			Expression::Empty => None,
+			Expression::Color(color) => Some(color),
+			Expression::Wide(color) 
+				=> Some(color.to_color(GamutMapping::default())),
+		}
+	}
+
+	/// Returns the extended-range color generated by the expression, without
+	/// mapping it into the sRGB gamut.
+	pub fn wide_color(&self) -> Option<WideColor> {
+		match *self {
+			Expression::Empty => None,
+			Expression::Color(color) => Some(WideColor::from(color)),
+			Expression::Wide(color) => Some(color),
+		}
 	}
 }
 
@@ -65,6 +91,9 @@ impl fmt::Debug for Expression {
 
 			Expression::Color(ref color)
 				=> write!(f, "Expression::Color({:?})", color),
+
+			Expression::Wide(ref color)
+				=> write!(f, "Expression::Wide({:?})", color),
 		}
 	}
 }
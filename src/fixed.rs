@@ -0,0 +1,199 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides palettes of a fixed, compile-time size.
+//!
+//! A `FixedPalette` stores its colors inline as sRGB channel arrays, so it
+//! never allocates. Ramps can be generated with the same `RampGenerator` used
+//! for ordinary palettes, or with integer RGB interpolation in `const` 
+//! contexts, so generated palettes can be embedded as constant data:
+//!
+//! ```rust
+//! use palette::fixed::FixedPalette;
+//!
+//! const FIRE: FixedPalette<4> = FixedPalette::multi_stop_rgb([
+//! 	[0, 0, 0],
+//! 	[255, 64, 0],
+//! 	[255, 255, 128],
+//! ]);
+//! const CHANNELS: &[[u8; 3]; 4] = FIRE.channels();
+//!
+//! assert_eq!(CHANNELS[0], [0, 0, 0]);
+//! assert_eq!(CHANNELS[3], [255, 255, 128]);
+//! ```
+//!
+//! The palettes themselves only need `core`, but this crate depends on the 
+//! standard library, so it can't be linked into `no_std` firmware. Such 
+//! targets should instead embed the generated colors, for example as source 
+//! written by `export::write_rust_source`.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use ramp::RampGenerator;
+use space::WideColor;
+
+// Non-local imports.
+use color::Color;
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// FixedPalette
+////////////////////////////////////////////////////////////////////////////////
+/// A palette of `N` colors stored inline as sRGB channel arrays.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::Color;
+/// use palette::fixed::FixedPalette;
+/// use palette::ramp::{InterpolationSpace, RampGenerator};
+///
+/// // Integer interpolation agrees with the generator's RGB interpolation.
+/// const GRAYS: FixedPalette<5> = FixedPalette::rgb_ramp(
+/// 	[0, 0, 0], 
+/// 	[255, 255, 255]);
+/// let (black, white) = (Color::new(0, 0, 0), Color::new(255, 255, 255));
+/// let ramp = RampGenerator::new().ramp(black, white, 5);
+/// assert_eq!(&GRAYS.colors()[..], &ramp[..]);
+///
+/// const NONE: FixedPalette<0> = FixedPalette::rgb_ramp([0; 3], [255; 3]);
+/// assert!(NONE.is_empty());
+///
+/// let gen = RampGenerator::new().space(InterpolationSpace::Oklab);
+/// let fixed = FixedPalette::<9>::generate(&gen, [black, white]);
+/// assert_eq!(&fixed.colors()[..], &gen.ramp(black, white, 9)[..]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FixedPalette<const N: usize> {
+	/// The sRGB channels of the palette's colors.
+	channels: [[u8; 3]; N],
+}
+
+
+impl<const N: usize> Default for FixedPalette<N> {
+	fn default() -> Self {
+		FixedPalette::new([[0; 3]; N])
+	}
+}
+
+
+impl<const N: usize> FixedPalette<N> {
+	/// Creates a new `FixedPalette` from the given sRGB channels.
+	pub const fn new(channels: [[u8; 3]; N]) -> Self {
+		FixedPalette {channels: channels}
+	}
+
+	/// Creates a new `FixedPalette` holding a ramp through the given control
+	/// colors, generated with the given `RampGenerator` without allocating.
+	/// The control colors are evenly spaced along the ramp, and the palette
+	/// is black if there are none.
+	pub fn generate<const S: usize>(
+		generator: &RampGenerator,
+		stops: [Color; S])
+		-> Self
+	{
+		let mut ramp = [WideColor::default(); N];
+		generator.fill_wide(&stops.map(WideColor::from), &mut ramp);
+
+		let mut channels = [[0; 3]; N];
+		for (channel, color) in channels.iter_mut().zip(ramp.iter()) {
+			let color = color.quantize();
+			*channel = [color.red(), color.green(), color.blue()];
+		}
+		FixedPalette::new(channels)
+	}
+
+	/// Creates a new `FixedPalette` holding a ramp between the given sRGB 
+	/// colors, interpolated in RGB space with integer arithmetic. The first
+	/// and last colors of the palette are the given colors.
+	pub const fn rgb_ramp(start: [u8; 3], end: [u8; 3]) -> Self {
+		FixedPalette::multi_stop_rgb([start, end])
+	}
+
+	/// Creates a new `FixedPalette` holding a ramp through the given sRGB 
+	/// control colors, which are evenly spaced along the ramp and 
+	/// interpolated in RGB space with integer arithmetic.
+	///
+	/// # Panics
+	///
+	/// Panics if there are no control colors.
+	pub const fn multi_stop_rgb<const S: usize>(stops: [[u8; 3]; S]) -> Self {
+		assert!(S > 0, "a fixed palette ramp requires a control color");
+		let mut channels = [[0; 3]; N];
+		if N == 0 { return FixedPalette::new(channels); }
+		if S == 1 || N == 1 {
+			let mut i = 0;
+			while i < N { channels[i] = stops[0]; i += 1; }
+			return FixedPalette::new(channels);
+		}
+
+		// Each color is located `offset / (N - 1)` of the way along the ramp,
+		// which is `offset * (S - 1) / (N - 1)` of the way through the stops.
+		let denominator = (N - 1) as u64;
+		let mut i = 0;
+		while i < N {
+			let at = (i * (S - 1)) as u64;
+			let mut index = (at / denominator) as usize;
+			if index > S - 2 { index = S - 2; }
+			let local = at - index as u64 * denominator;
+			let (a, b) = (stops[index], stops[index + 1]);
+			let mut c = 0;
+			while c < 3 {
+				let sum = a[c] as u64 * (denominator - local)
+					+ b[c] as u64 * local;
+				channels[i][c] = ((sum + denominator / 2) / denominator) as u8;
+				c += 1;
+			}
+			i += 1;
+		}
+		FixedPalette::new(channels)
+	}
+
+	/// Returns the number of colors in the palette.
+	pub const fn len(&self) -> usize {
+		N
+	}
+
+	/// Returns whether the palette has no colors.
+	pub const fn is_empty(&self) -> bool {
+		N == 0
+	}
+
+	/// Returns the sRGB channels of the palette's colors.
+	pub const fn channels(&self) -> &[[u8; 3]; N] {
+		&self.channels
+	}
+
+	/// Returns the color at the given index, if it exists.
+	pub fn color(&self, index: usize) -> Option<Color> {
+		self.channels.get(index).map(|&[r, g, b]| Color::new(r, g, b))
+	}
+
+	/// Returns the palette's colors.
+	pub fn colors(&self) -> [Color; N] {
+		self.channels.map(|[r, g, b]| Color::new(r, g, b))
+	}
+}
@@ -24,35 +24,172 @@
 //!
 //! Provides components for interacting with the default palette format.
 //!
+//! The default format is stored as UTF-8 text, with one entry per line:
+//!
+//! ```text
+//! palette "Example"
+//! format default
+//! color_space srgb
+//! tone_curve lightness 0:0.05 0.5:0.55 1:0.95
+//! white_point 0:0:1
+//! black_point 0:0:0
+//! page 0 lines 16
+//! line 0:0 columns 16 name "Skin"
+//! name 0:0:0 name "shadow"
+//! description 0:0:0 "The darkest skin tone"
+//! source 0:0:0 operation 3 "Insert Color"
+//! cell 0:0:0 #0C324E
+//! cell 0:0:1 wide display-p3 1 0.5 0.25
+//! alias "surface" 0:0:0
+//! trash 0:0:2 #A0522D name "rust" source manual
+//! ```
+//!
+//! Blank lines and lines beginning with `#` are ignored.
+//!
+//! Palettes are always written in a canonical form, so that files diff 
+//! cleanly: header entries come first in the order above, followed by group
+//! entries and then cells, both in address order, then aliases in token 
+//! order, and then trashed cells in order of deletion. Each cell's name 
+//! precedes its description, its tags, which are sorted, its usage count, and
+//! its provenance. A trashed cell is a single entry, with its optional name 
+//! and provenance following its expression. Hex colors are uppercase, and 
+//! numbers are written with at most six decimal places and no trailing 
+//! zeros. Reading and rewriting a canonical file reproduces it exactly, 
+//! except that comments are not preserved.
+//!
 ////////////////////////////////////////////////////////////////////////////////
 
 // Local imports.
 use ::Palette;
-use operation::PaletteOperation;
+use address::{
+	Address,
+	Reference,
+	Selection,
+};
+use curve::{
+	Curve,
+	ToneChannel,
+	ToneCurve,
+};
+use expression::Expression;
+use format::Format;
+use operation::{
+	Conflict,
+	HistoryEntry,
+	PaletteOperation,
+};
+use provenance::Provenance;
 use result::Result;
+use space::{
+	RgbSpace,
+	WideColor,
+};
+use trash::TrashedCell;
+use utilities::{
+	canonical_float,
+	hex,
+	parse_hex,
+};
+
+// Standard imports.
+use std::io;
+use std::io::{
+	BufRead,
+	BufReader,
+};
+use std::mem;
+use std::rc::Rc;
 
 
 
-/// Applies the given operation to the palette.
+/// Applies the given operation to the palette, returning the cells it 
+/// skipped.
 pub fn apply_operation(
 	palette: &mut Palette, 
 	mut operation: Box<PaletteOperation>) 
-	-> Result<()> 
+	-> Result<Vec<Conflict>> 
 {
 	let data = &mut palette.data;
 	let history = &mut palette.operation_history;
 	// Apply operation.
-	let entry = operation.apply(data)?;
+	let mut entry = operation.apply(data)?;
+	let conflicts = mem::replace(&mut entry.conflicts, Vec::new());
 	// Add history entry if history is enabled.
 	if let Some(ref mut history) = *history {
 		history.undo_entries.push(entry);
 		history.redo_entries.clear();
 	}
+	Ok(conflicts)
+}
+
+
+/// Applies the given operation to the palette as an interactive operation,
+/// first reverting any pending interactive operation. If the operation fails,
+/// the pending interactive operation is reinstated.
+pub fn update_interactive(
+	palette: &mut Palette, 
+	mut operation: Box<PaletteOperation>) 
+	-> Result<()> 
+{
+	let previous = match palette.interactive.take() {
+		Some(mut entry) => match entry.undo.apply(&mut palette.data) {
+			Ok(redo) => Some((entry, redo)),
+			Err(error) => {
+				palette.interactive = Some(entry);
+				return Err(error);
+			},
+		},
+		None => None,
+	};
+	let cancelled = palette.data.fork();
+
+	match operation.apply(&mut palette.data) {
+		Ok(entry) => {
+			palette.interactive = Some(entry);
+			Ok(())
+		},
+		Err(error) => {
+			// Discard the effects of the failed operation and reapply the
+			// previous one, which gives a new entry for reverting it.
+			palette.data = cancelled;
+			if let Some((entry, mut redo)) = previous {
+				if let Ok(reapplied) = redo.undo.apply(&mut palette.data) {
+					palette.interactive = Some(HistoryEntry {
+						info: entry.info,
+						undo: reapplied.undo,
+						conflicts: entry.conflicts,
+					});
+				}
+			}
+			Err(error)
+		},
+	}
+}
+
+
+/// Records the pending interactive operation in the palette's history.
+pub fn finish_interactive(palette: &mut Palette) {
+	if let Some(entry) = palette.interactive.take() {
+		// Add history entry if history is enabled.
+		if let Some(ref mut history) = palette.operation_history {
+			history.undo_entries.push(entry);
+			history.redo_entries.clear();
+		}
+	}
+}
+
+
+/// Reverts the pending interactive operation.
+pub fn cancel_interactive(palette: &mut Palette) -> Result<()> {
+	if let Some(mut entry) = palette.interactive.take() {
+		entry.undo.apply(&mut palette.data)?;
+	}
 	Ok(())
 }
 
 
-/// Reverses the most recently applied operation.
+/// Reverses the most recently applied operation. The history entry is kept if
+/// the undo fails.
 pub fn undo(palette: &mut Palette) -> Result<()> {
 	let data = &mut palette.data;
 	let history = &mut palette.operation_history;
@@ -60,8 +197,13 @@ pub fn undo(palette: &mut Palette) -> Result<()> {
 	if let Some(ref mut history) = *history {
 		// Check for history entry.
 		if let Some(mut entry) = history.undo_entries.pop() {
-			let redo = entry.undo.apply(data)?;
-			history.redo_entries.push(redo);
+			match entry.undo.apply(data) {
+				Ok(redo) => history.redo_entries.push(redo),
+				Err(error) => {
+					history.undo_entries.push(entry);
+					return Err(error);
+				},
+			}
 		}
 		Ok(())
 	} else {
@@ -70,7 +212,8 @@ pub fn undo(palette: &mut Palette) -> Result<()> {
 }
 
 
-/// Reverses the most recently applied undo operation.
+/// Reverses the most recently applied undo operation. The history entry is
+/// kept if the redo fails.
 pub fn redo(palette: &mut Palette) -> Result<()> {
 	let data = &mut palette.data;
 	let history = &mut palette.operation_history;
@@ -78,11 +221,538 @@ pub fn redo(palette: &mut Palette) -> Result<()> {
 	if let Some(ref mut history) = *history {
 		// Check for history entry.
 		if let Some(mut entry) = history.redo_entries.pop() {
-			let undo = entry.undo.apply(data)?;
-			history.undo_entries.push(undo);
+			match entry.undo.apply(data) {
+				Ok(undo) => history.undo_entries.push(undo),
+				Err(error) => {
+					history.redo_entries.push(entry);
+					return Err(error);
+				},
+			}
 		}
 		Ok(())
 	} else {
 		panic!("undo not supported")
 	}
-}
\ No newline at end of file
+}
+
+
+/// Writes the palette to the given buffer in the default text format.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+///
+/// let text = "page 1 lines 4 name \"Spare\"\n\
+/// 	line 0:3 columns 8 name \"Empty\"\n\
+/// 	cell 0:0:0 #0C324E\n";
+/// let pal = Format::Default.read_palette(&mut text.as_bytes()).unwrap();
+///
+/// let mut saved = Vec::new();
+/// Format::Default.write_palette(&pal, &mut saved).unwrap();
+/// let saved = String::from_utf8(saved).unwrap();
+/// assert!(saved.contains("page 1 lines 4 name \"Spare\"\n"));
+/// assert!(saved.contains("line 0:3 columns 8 name \"Empty\"\n"));
+///
+/// let reopened = Format::Default.read_palette(&mut saved.as_bytes())
+/// 	.unwrap();
+/// let mut resaved = Vec::new();
+/// Format::Default.write_palette(&reopened, &mut resaved).unwrap();
+/// assert_eq!(String::from_utf8(resaved).unwrap(), saved);
+/// ```
+pub fn write_palette<W>(palette: &Palette, out_buf: &mut W) -> io::Result<()>
+	where W: io::Write
+{
+	write_selection(palette, &Selection::all(), out_buf)
+}
+
+
+/// Writes the selected cells of the palette to the given buffer in the default
+/// text format. Unless every address is selected, only the pages, lines, and 
+/// metadata of groups containing selected cells are written.
+pub fn write_selection<W>(
+	palette: &Palette, 
+	selection: &Selection,
+	out_buf: &mut W)
+	-> io::Result<()>
+	where W: io::Write
+{
+	let data = &palette.data;
+	let cells: Vec<_> = data.cells
+		.iter()
+		.filter(|&(address, _)| selection.contains(address))
+		.collect();
+	let used = |group: (u16, Option<u8>, Option<u8>)| cells.iter()
+		.any(|&(a, _)| a.page == group.0 
+			&& group.1.map_or(true, |l| a.line == l)
+			&& group.2.map_or(true, |c| a.column == c));
+
+	if let Some(name) = data.name(&Reference::all()) {
+		writeln!(out_buf, "palette {}", quote(name))?;
+	}
+	writeln!(out_buf, "format {}", palette.format.name())?;
+	writeln!(out_buf, "color_space {}", data.color_space.name())?;
+	if let Some(ref tone) = data.tone_curve {
+		for (channel, curve) in tone.curves() {
+			writeln!(out_buf, "tone_curve {} {}", channel, curve)?;
+		}
+	}
+	if let Some(address) = data.white_point.filter(|a| selection.contains(a)) {
+		writeln!(out_buf, "white_point {}", address)?;
+	}
+	if let Some(address) = data.black_point.filter(|a| selection.contains(a)) {
+		writeln!(out_buf, "black_point {}", address)?;
+	}
+
+	// Sort groups so that the output is stable.
+	let mut groups: Vec<_> = data.metadata
+		.iter()
+		.filter_map(|(group, meta)| group_address(group).map(|g| (g, meta)))
+		.filter(|&(g, _)| selection.is_all() || used(g))
+		.collect();
+	groups.sort_by_key(|&(g, _)| g);
+
+	for (group, meta) in groups {
+		match group {
+			(page, None, None) => write!(out_buf, "page {} lines {}",
+				page, meta.line_count)?,
+			(page, Some(line), None) => write!(out_buf, "line {}:{} columns {}",
+				page, line, meta.column_count)?,
+			(page, Some(line), Some(column)) => {
+				let address = Address::new(page, line, column);
+				if let Some(ref name) = meta.name {
+					writeln!(out_buf, "name {} name {}", address, quote(name))?;
+				}
+				if let Some(ref text) = meta.description {
+					writeln!(out_buf, "description {} {}", 
+						address, 
+						quote(text))?;
+				}
+				for tag in &meta.tags {
+					writeln!(out_buf, "tag {} {}", address, quote(tag))?;
+				}
+				if let Some(count) = meta.usage_count {
+					writeln!(out_buf, "usage {} {}", address, count)?;
+				}
+				if let Some(ref provenance) = meta.provenance {
+					writeln!(out_buf, "source {} {}", 
+						address, 
+						write_provenance(provenance))?;
+				}
+				continue;
+			},
+			_ => continue,
+		}
+		if let Some(ref name) = meta.name {
+			write!(out_buf, " name {}", quote(name))?;
+		}
+		writeln!(out_buf)?;
+	}
+
+	for (address, cell) in cells {
+		writeln!(out_buf, "cell {} {}", 
+			address,
+			write_expression(&*cell.borrow()))?;
+	}
+
+	for (token, address) in data.aliases() {
+		if let Some(address) = address.filter(|a| selection.contains(a)) {
+			writeln!(out_buf, "alias {} {}", quote(token), address)?;
+		}
+	}
+
+	let trash = data.trash.iter()
+		.filter(|trashed| selection.contains(&trashed.address));
+	for trashed in trash {
+		write!(out_buf, "trash {} {}", 
+			trashed.address,
+			write_expression(&trashed.expression))?;
+		if let Some(ref name) = trashed.name {
+			write!(out_buf, " name {}", quote(name))?;
+		}
+		if let Some(ref provenance) = trashed.provenance {
+			write!(out_buf, " source {}", write_provenance(provenance))?;
+		}
+		writeln!(out_buf)?;
+	}
+	Ok(())
+}
+
+
+/// Reads a palette in the default text format from the given buffer.
+pub fn read_palette<R>(in_buf: &mut R) -> io::Result<Palette>
+	where R: io::Read
+{
+	let mut name = String::new();
+	let mut format = Format::Default;
+	let mut lines = Vec::new();
+
+	// Read the header entries first, as they are needed to create the palette.
+	for (i, line) in BufReader::new(in_buf).lines().enumerate() {
+		let tokens = tokenize(&line?).map_err(|e| invalid(i, e))?;
+		match tokens.first().map(|t| &t[..]) {
+			None => continue,
+			Some("palette") => name = token(&tokens, 1).map_err(|e| invalid(i, e))?,
+			Some("format") => format = token(&tokens, 1)
+				.and_then(|t| Format::from_name(&t)
+					.ok_or_else(|| format!("unknown format '{}'", t)))
+				.map_err(|e| invalid(i, e))?,
+			Some(_) => lines.push((i, tokens)),
+		}
+	}
+
+	let mut palette = Palette::new(name, format, true);
+	for (i, tokens) in lines {
+		read_entry(&mut palette, &tokens).map_err(|e| invalid(i, e))?;
+	}
+	Ok(palette)
+}
+
+
+/// Applies a single non-header entry to the palette.
+fn read_entry(palette: &mut Palette, tokens: &[String]) 
+	-> ::std::result::Result<(), String>
+{
+	let data = &mut palette.data;
+	let kind = &tokens[0][..];
+	match kind {
+		"color_space" => {
+			let t = token(tokens, 1)?;
+			data.color_space = RgbSpace::from_name(&t)
+				.ok_or_else(|| format!("unknown color space '{}'", t))?;
+		},
+
+		"tone_curve" => {
+			let t = token(tokens, 1)?;
+			let channel = ToneChannel::from_name(&t)
+				.ok_or_else(|| format!("unknown tone channel '{}'", t))?;
+			let curve: Curve = tokens[2..].join(" ").parse()?;
+			data.tone_curve
+				.get_or_insert_with(ToneCurve::new)
+				.set_channel(channel, curve);
+		},
+
+		"white_point" => {
+			data.white_point = Some(parse_address(&token(tokens, 1)?)?);
+		},
+
+		"black_point" => {
+			data.black_point = Some(parse_address(&token(tokens, 1)?)?);
+		},
+
+		"page" | "line" => {
+			let address = if kind == "page" {
+				Address::new(parse(&token(tokens, 1)?)?, 0, 0)
+			} else {
+				parse_address(&format!("{}:0", token(tokens, 1)?))?
+			};
+			let group = if kind == "page" {
+				Reference::page_of(&address)
+			} else {
+				Reference::line_of(&address)
+			};
+
+			// Let the format prepare the group before it is overridden.
+			if !data.metadata.contains_key(&group) {
+				if kind == "page" {
+					data.set_line_count(group.clone(), data.default_line_count);
+					(data.prepare_new_page)(data, &group);
+				} else {
+					data.set_column_count(
+						group.clone(),
+						data.default_column_count);
+					(data.prepare_new_line)(data, &group);
+				}
+			}
+
+			let count = parse(&token(tokens, 3)?)?;
+
+			// Groups are written before their cells, so a group read after 
+			// its cells may not exclude them.
+			let excluded = data.cells.keys().find(|cell| if kind == "page" {
+				cell.page == address.page && cell.line >= count
+			} else {
+				cell.page == address.page && cell.line == address.line &&
+					cell.column >= count
+			});
+			if let Some(cell) = excluded {
+				return Err(format!("{} {} excludes the cell at {}", 
+					kind, count, cell));
+			}
+
+			if kind == "page" {
+				data.set_line_count(group.clone(), count);
+			} else {
+				data.set_column_count(group.clone(), count);
+			}
+			if token(tokens, 4).ok().map_or(false, |t| t == "name") {
+				data.set_name(group, token(tokens, 5)?);
+			}
+		},
+
+		"name" => {
+			let address = parse_address(&token(tokens, 1)?)?;
+			data.set_name(Reference::from(address), token(tokens, 3)?);
+		},
+
+		"description" => {
+			let address = parse_address(&token(tokens, 1)?)?;
+			data.set_description(Reference::from(address), 
+				Some(token(tokens, 2)?));
+		},
+
+		"tag" => {
+			let address = parse_address(&token(tokens, 1)?)?;
+			data.add_tag(Reference::from(address), token(tokens, 2)?);
+		},
+
+		"usage" => {
+			let address = parse_address(&token(tokens, 1)?)?;
+			data.metadata_mut()
+				.entry(Reference::from(address))
+				.or_insert_with(Default::default)
+				.usage_count = Some(parse(&token(tokens, 2)?)?);
+		},
+
+		"source" => {
+			let address = parse_address(&token(tokens, 1)?)?;
+			let provenance = read_provenance(&tokens[2..])?;
+			if let Provenance::Operation {id, ..} = provenance {
+				// Leave room for the ids of later operations.
+				if id == u64::max_value() {
+					return Err(format!("operation id {} out of range", id));
+				}
+				data.operation_count = data.operation_count.max(id);
+			}
+			data.metadata_mut()
+				.entry(Reference::from(address))
+				.or_insert_with(Default::default)
+				.provenance = Some(provenance);
+		},
+
+		"trash" => {
+			let address = parse_address(&token(tokens, 1)?)?;
+
+			// Wide expressions are followed by a space and three channels.
+			let end = if token(tokens, 2)? == "wide" { 7 } else { 3 };
+			let end = end.min(tokens.len());
+			let expression = read_expression(&tokens[2..end])?;
+
+			let mut rest = &tokens[end..];
+			let mut name = None;
+			if rest.first().map_or(false, |t| t == "name") {
+				name = Some(token(rest, 1)?);
+				rest = &rest[2..];
+			}
+			let provenance = match rest.first().map(|t| &t[..]) {
+				Some("source") => Some(read_provenance(&rest[1..])?),
+				Some(t) => return Err(format!("unexpected '{}' in trash", t)),
+				None => None,
+			};
+
+			Rc::make_mut(&mut data.trash).push(TrashedCell {
+				address: address,
+				expression: expression,
+				name: name,
+				provenance: provenance,
+				id: None,
+			});
+		},
+
+		"cell" => {
+			let address = parse_address(&token(tokens, 1)?)?;
+			let expr = read_expression(&tokens[2..])?;
+			let cell = data.create_cell(address)
+				.map_err(|e| format!("{}", e))?;
+			*cell.borrow_mut() = expr;
+		},
+
+		"alias" => {
+			let address = parse_address(&token(tokens, 2)?)?;
+			data.set_alias(token(tokens, 1)?, address)
+				.map_err(|e| format!("{}", e))?;
+		},
+
+		_ => return Err(format!("unknown entry '{}'", kind)),
+	}
+	Ok(())
+}
+
+
+/// Returns the text representation of the given expression.
+pub(crate) fn write_expression(expr: &Expression) -> String {
+	match *expr {
+		Expression::Empty => "empty".to_owned(),
+		Expression::Color(color) => hex(color),
+		Expression::Wide(color) => format!("wide {} {} {} {}",
+			color.space.name(),
+			canonical_float(color.r),
+			canonical_float(color.g),
+			canonical_float(color.b)),
+	}
+}
+
+
+/// Parses an expression from the given tokens.
+pub(crate) fn read_expression(tokens: &[String]) -> ::std::result::Result<Expression, String> {
+	match tokens.first().map(|t| &t[..]) {
+		Some("empty") => Ok(Expression::Empty),
+
+		Some("wide") => {
+			let space = token(tokens, 1)?;
+			let space = RgbSpace::from_name(&space)
+				.ok_or_else(|| format!("unknown color space '{}'", space))?;
+			Ok(Expression::Wide(WideColor::new(
+				parse(&token(tokens, 2)?)?,
+				parse(&token(tokens, 3)?)?,
+				parse(&token(tokens, 4)?)?,
+				space,
+			)))
+		},
+
+		Some(text) => parse_hex(text)
+			.map(Expression::Color)
+			.ok_or_else(|| format!("invalid color '{}'", text)),
+
+		None => Err("missing expression".to_owned()),
+	}
+}
+
+
+/// Parses an address in the form `page:line:column`.
+pub(crate) fn parse_address(text: &str) -> ::std::result::Result<Address, String> {
+	let parts: Vec<&str> = text.split(':').collect();
+	if parts.len() != 3 {
+		return Err(format!("invalid address '{}'", text));
+	}
+	Ok(Address::new(parse(parts[0])?, parse(parts[1])?, parse(parts[2])?))
+}
+
+
+/// Parses a value from the given text.
+pub(crate) fn parse<T>(text: &str) -> ::std::result::Result<T, String> 
+	where T: ::std::str::FromStr
+{
+	text.parse().map_err(|_| format!("invalid value '{}'", text))
+}
+
+
+/// Returns the tokens describing the given provenance.
+fn write_provenance(provenance: &Provenance) -> String {
+	let kind = provenance.kind();
+	match *provenance {
+		Provenance::Import {ref format, ref source} 
+			=> format!("{} {} {}", kind, quote(format), quote(source)),
+		Provenance::Quantized {ref source} 
+			=> format!("{} {}", kind, quote(source)),
+		Provenance::Expression(ref text) 
+			=> format!("{} {}", kind, quote(text)),
+		Provenance::Manual => kind.to_owned(),
+		Provenance::Operation {id, ref name} 
+			=> format!("{} {} {}", kind, id, quote(name)),
+	}
+}
+
+
+/// Parses the given tokens describing a provenance.
+fn read_provenance(tokens: &[String]) 
+	-> ::std::result::Result<Provenance, String>
+{
+	let kind = token(tokens, 0)?;
+	match &kind[..] {
+		"import" => Ok(Provenance::Import {
+			format: token(tokens, 1)?,
+			source: token(tokens, 2)?,
+		}),
+		"quantized" => Ok(Provenance::Quantized {source: token(tokens, 1)?}),
+		"expression" => Ok(Provenance::Expression(token(tokens, 1)?)),
+		"manual" => Ok(Provenance::Manual),
+		"operation" => Ok(Provenance::Operation {
+			id: parse(&token(tokens, 1)?)?,
+			name: token(tokens, 2)?,
+		}),
+		_ => Err(format!("unknown provenance '{}'", kind)),
+	}
+}
+
+
+/// Returns a copy of the token at the given index.
+pub(crate) fn token(tokens: &[String], index: usize) -> ::std::result::Result<String, String> {
+	tokens.get(index)
+		.cloned()
+		.ok_or_else(|| "missing value".to_owned())
+}
+
+
+/// Returns the page, line, and column of the given group's reference, or None
+/// if it is the whole palette.
+fn group_address(group: &Reference) 
+	-> Option<(u16, Option<u8>, Option<u8>)>
+{
+	let page = group.page().ok()?;
+	Some((page, group.line().ok(), group.column().ok()))
+}
+
+
+/// Returns the given string quoted and escaped.
+pub(crate) fn quote(text: &str) -> String {
+	let mut quoted = String::with_capacity(text.len() + 2);
+	quoted.push('"');
+	for c in text.chars() {
+		match c {
+			'"'  => quoted.push_str("\\\""),
+			'\\' => quoted.push_str("\\\\"),
+			'\n' => quoted.push_str("\\n"),
+			_    => quoted.push(c),
+		}
+	}
+	quoted.push('"');
+	quoted
+}
+
+
+/// Splits a line into whitespace separated tokens, unquoting quoted strings.
+pub(crate) fn tokenize(line: &str) -> ::std::result::Result<Vec<String>, String> {
+	let mut tokens = Vec::new();
+	let mut chars = line.trim().chars().peekable();
+	if chars.peek() == Some(&'#') { return Ok(tokens); }
+
+	while let Some(&c) = chars.peek() {
+		if c.is_whitespace() {
+			chars.next();
+		} else if c == '"' {
+			chars.next();
+			let mut token = String::new();
+			loop {
+				match chars.next() {
+					Some('"') => break,
+					Some('\\') => match chars.next() {
+						Some('n') => token.push('\n'),
+						Some(e) => token.push(e),
+						None => return Err("unterminated string".to_owned()),
+					},
+					Some(c) => token.push(c),
+					None => return Err("unterminated string".to_owned()),
+				}
+			}
+			tokens.push(token);
+		} else {
+			let mut token = String::new();
+			while let Some(&c) = chars.peek() {
+				if c.is_whitespace() { break; }
+				token.push(c);
+				chars.next();
+			}
+			tokens.push(token);
+		}
+	}
+	Ok(tokens)
+}
+
+
+/// Returns an `InvalidData` error for the given line.
+pub(crate) fn invalid(line: usize, message: String) -> io::Error {
+	io::Error::new(
+		io::ErrorKind::InvalidData,
+		format!("line {}: {}", line + 1, message))
+}
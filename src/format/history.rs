@@ -0,0 +1,513 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides persistence of a palette's undo and redo history, and spilling
+//! of old history entries to disk to cap the memory the history uses.
+//!
+//! History is stored in a sidecar file beside the palette, named by appending
+//! `.history` to the palette's file name. Each history entry is recorded as
+//! the cell contents it restores, so any operation can be saved. Changes to
+//! names, tags, and other metadata are not recorded. The header records a 
+//! checksum of the palette's cells, so a history is only restored to the
+//! palette it was written for.
+//!
+//! ```text
+//! history 1 8e7cd3b1a0f24c69
+//! undo "Insert Color"
+//! remove 0:0:1
+//! redo "Undo"
+//! cell 0:0:1 #0C324E
+//! ```
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use ::Palette;
+use address::Address;
+use data::Data;
+use export::checksum;
+use expression::Expression;
+use format::default::{
+	invalid,
+	parse_address,
+	quote,
+	read_expression,
+	token,
+	tokenize,
+	write_expression,
+};
+use operation::{
+	HistoryEntry,
+	OperationHistory,
+	OperationInfo,
+	Undo,
+};
+
+// Standard imports.
+use std::ffi::OsString;
+use std::io;
+use std::io::{
+	BufRead,
+	BufReader,
+};
+use std::path::{
+	Path,
+	PathBuf,
+};
+use std::rc::Rc;
+
+
+/// The cell contents restored by a history entry, in address order.
+type Cells = Vec<(Address, Option<Expression>)>;
+
+
+/// A history entry read from a file: whether it is an undo entry, its info,
+/// and the cells it restores.
+type Step = (bool, OperationInfo, Cells);
+
+
+/// Returns the path of the history sidecar for the palette file at the given
+/// path.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::format::history::history_path;
+/// use std::path::Path;
+///
+/// assert_eq!(history_path("skin.pal"), Path::new("skin.pal.history"));
+/// ```
+pub fn history_path<P>(path: P) -> PathBuf where P: AsRef<Path> {
+	let mut name = OsString::from(path.as_ref());
+	name.push(".history");
+	PathBuf::from(name)
+}
+
+
+/// Writes the palette's undo and redo history to the given buffer. If a limit
+/// is given, only that many of the most recent undo and redo entries are 
+/// written. Any pending interactive operation is finished first.
+///
+/// The history is recorded by replaying it against a copy of the palette, 
+/// after which each entry is replaced by an equivalent one.
+///
+/// # Errors
+///
+/// Returns an error if a history entry cannot be replayed, or if the buffer
+/// cannot be written.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::format::history::{read_history, write_history};
+/// use palette::operation::*;
+///
+/// let mut pal = Palette::new("Example", Format::Default, true);
+/// pal.apply(Box::new(InsertColor::new(Color::new(12, 50, 78)))).unwrap();
+/// pal.apply(Box::new(InsertColor::new(Color::new(90, 0, 0)))).unwrap();
+///
+/// let mut history = Vec::new();
+/// write_history(&mut pal, None, &mut history).unwrap();
+///
+/// // Reopen the palette without its history, then restore it.
+/// let mut saved = Vec::new();
+/// Format::Default.write_palette(&pal, &mut saved).unwrap();
+/// let mut reopened = Format::Default.read_palette(&mut &saved[..]).unwrap();
+/// read_history(&mut reopened, &mut &history[..]).unwrap();
+///
+/// reopened.undo().unwrap();
+/// assert_eq!(reopened.len(), 1);
+/// reopened.redo().unwrap();
+/// assert_eq!(reopened.color(Address::new(0, 0, 1)), 
+/// 	Some(Color::new(90, 0, 0)));
+/// ```
+pub fn write_history<W>(
+	palette: &mut Palette,
+	limit: Option<usize>,
+	out_buf: &mut W)
+	-> io::Result<()>
+	where W: io::Write
+{
+	palette.finish_interactive();
+	let data = &palette.data;
+	let history = match palette.operation_history {
+		Some(ref mut history) => history,
+		None => return Ok(()),
+	};
+
+	let undo_count = limit.map_or(history.undo_entries.len(), 
+		|n| n.min(history.undo_entries.len()));
+	let redo_count = limit.map_or(history.redo_entries.len(), 
+		|n| n.min(history.redo_entries.len()));
+	let undos = record(&mut history.undo_entries, undo_count, data)?;
+	let redos = record(&mut history.redo_entries, redo_count, data)?;
+
+	writeln!(out_buf, "history 1 {:016x}", cells_checksum(data))?;
+	write_steps(out_buf, "undo", &undos)?;
+	write_steps(out_buf, "redo", &redos)
+}
+
+
+/// Moves all but the `keep` most recent undo entries of the palette's history
+/// into the given buffer, freeing the memory they use. Any pending 
+/// interactive operation is finished first.
+///
+/// The entries are written in the same form as `write_history`. Successive
+/// spills may be appended to the same file, and are restored in order by 
+/// `reload_history`.
+///
+/// # Errors
+///
+/// Returns an error if a history entry cannot be replayed, or if the buffer
+/// cannot be written. No entries are removed from the history if an error
+/// occurs.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::format::history::{reload_history, spill_history};
+/// use palette::operation::*;
+///
+/// let mut pal = Palette::new("Example", Format::Default, true);
+/// for v in 0..10 {
+/// 	pal.apply(Box::new(InsertColor::new(Color::new(v, v, v)))).unwrap();
+/// }
+///
+/// let mut spilled = Vec::new();
+/// spill_history(&mut pal, 3, &mut spilled).unwrap();
+/// assert_eq!(pal.history_len(), (3, 0));
+///
+/// reload_history(&mut pal, &mut &spilled[..]).unwrap();
+/// assert_eq!(pal.history_len(), (10, 0));
+/// for _ in 0..10 { pal.undo().unwrap(); }
+/// assert!(pal.is_empty());
+/// ```
+pub fn spill_history<W>(palette: &mut Palette, keep: usize, out_buf: &mut W)
+	-> io::Result<()>
+	where W: io::Write
+{
+	palette.finish_interactive();
+	let data = &palette.data;
+	let history = match palette.operation_history {
+		Some(ref mut history) => history,
+		None => return Ok(()),
+	};
+	if history.undo_entries.len() <= keep { return Ok(()); }
+
+	// Every entry is replayed to reach the states the oldest entries restore.
+	let count = history.undo_entries.len();
+	let mut steps = record(&mut history.undo_entries, count, data)?;
+	steps.truncate(count - keep);
+
+	writeln!(out_buf, "history 1")?;
+	write_steps(out_buf, "undo", &steps)?;
+	history.undo_entries.drain(..count - keep);
+	Ok(())
+}
+
+
+/// Reads undo entries written by `spill_history` from the given buffer, 
+/// placing them before the oldest undo entries of the palette's history. 
+/// History is enabled for the palette if it was not already.
+pub fn reload_history<R>(palette: &mut Palette, in_buf: &mut R)
+	-> io::Result<()>
+	where R: io::Read
+{
+	let (_, steps) = parse_history(in_buf)?;
+	let mut spilled = OperationHistory::default();
+	for step in steps { push(&mut spilled, step); }
+	palette.finish_interactive();
+	let history = palette.operation_history
+		.get_or_insert_with(Default::default);
+	spilled.undo_entries.extend(history.undo_entries.drain(..));
+	history.undo_entries = spilled.undo_entries;
+	Ok(())
+}
+
+
+/// Reads a history written by `write_history` from the given buffer, 
+/// replacing the palette's history. History is enabled for the palette if it
+/// was not already.
+///
+/// # Errors
+///
+/// Returns an `InvalidData` error if the history is malformed, was written 
+/// for a different version of the palette, or restores cells which cannot 
+/// be restored to the palette. The palette's history is unchanged if an 
+/// error occurs.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::format::history::{read_history, write_history};
+/// use palette::operation::*;
+///
+/// let mut pal = Palette::new("Example", Format::Default, true);
+/// pal.apply(Box::new(InsertColor::new(Color::new(12, 50, 78)))).unwrap();
+/// let mut history = Vec::new();
+/// write_history(&mut pal, None, &mut history).unwrap();
+///
+/// // The history no longer matches once the palette is changed.
+/// pal.apply(Box::new(InsertColor::new(Color::new(90, 0, 0)))).unwrap();
+/// assert!(read_history(&mut pal, &mut &history[..]).is_err());
+/// assert_eq!(pal.history_len(), (2, 0));
+/// ```
+pub fn read_history<R>(palette: &mut Palette, in_buf: &mut R) 
+	-> io::Result<()>
+	where R: io::Read
+{
+	let (sum, steps) = parse_history(in_buf)?;
+	palette.finish_interactive();
+	if sum.map_or(false, |sum| sum != cells_checksum(&palette.data)) {
+		return Err(io::Error::new(io::ErrorKind::InvalidData,
+			"history does not match the palette"));
+	}
+	check_steps(&steps, &palette.data)?;
+
+	let mut history = OperationHistory::default();
+	for step in steps { push(&mut history, step); }
+	palette.operation_history = Some(history);
+	Ok(())
+}
+
+
+/// Parses the palette checksum and history entries in the given buffer.
+fn parse_history<R>(in_buf: &mut R) -> io::Result<(Option<u64>, Vec<Step>)>
+	where R: io::Read
+{
+	let mut sum = None;
+	let mut steps = Vec::new();
+	let mut current: Option<Step> = None;
+
+	for (i, line) in BufReader::new(in_buf).lines().enumerate() {
+		let tokens = tokenize(&line?).map_err(|e| invalid(i, e))?;
+		let kind = match tokens.first() {
+			Some(kind) => &kind[..],
+			None => continue,
+		};
+		match kind {
+			"history" => {
+				if token(&tokens, 1).ok().map_or(true, |v| v != "1") {
+					return Err(invalid(i, 
+						"unsupported history version".into()));
+				}
+				if let Some(text) = tokens.get(2) {
+					sum = Some(u64::from_str_radix(text, 16).map_err(|_| 
+						invalid(i, format!("invalid checksum '{}'", text)))?);
+				}
+			},
+
+			"undo" | "redo" => {
+				if let Some(step) = current.take() { steps.push(step); }
+				let info = OperationInfo {
+					name: token(&tokens, 1).map_err(|e| invalid(i, e))?.into(),
+					details: tokens.get(2).cloned(),
+				};
+				current = Some((kind == "undo", info, Vec::new()));
+			},
+
+			"cell" | "remove" => {
+				let cells = match current {
+					Some((_, _, ref mut cells)) => cells,
+					None => return Err(invalid(i, "cell outside entry".into())),
+				};
+				let address = token(&tokens, 1)
+					.and_then(|t| parse_address(&t))
+					.map_err(|e| invalid(i, e))?;
+				let expr = if kind == "cell" {
+					Some(read_expression(&tokens[2..])
+						.map_err(|e| invalid(i, e))?)
+				} else {
+					None
+				};
+				cells.push((address, expr));
+			},
+
+			_ => return Err(invalid(i, format!("unknown entry '{}'", kind))),
+		}
+	}
+	if let Some(step) = current.take() { steps.push(step); }
+	Ok((sum, steps))
+}
+
+
+/// Checks that the undo and redo steps can each be replayed in turn against
+/// the given data, so that restoring a stale or edited history cannot leave
+/// an entry that removes a missing cell.
+fn check_steps(steps: &[Step], data: &Data) -> io::Result<()> {
+	for &is_undo in &[true, false] {
+		let mut fork = data.fork();
+		// The newest entries are written last, and are replayed first.
+		for &(_, ref info, ref cells) in steps.iter()
+			.rev()
+			.filter(|step| step.0 == is_undo)
+		{
+			for &(address, expr) in cells {
+				let result = match expr {
+					Some(expr) => fork.cell_mut(address)
+						.map_or_else(|| fork.create_cell(address), Ok)
+						.map(|cell| *cell.borrow_mut() = expr),
+					None => fork.remove_cell(address).map(|_| ()),
+				};
+				result.map_err(|e| io::Error::new(
+					io::ErrorKind::InvalidData,
+					format!("history entry '{}' does not match the palette: {}",
+						info.name, e)))?;
+			}
+		}
+	}
+	Ok(())
+}
+
+
+/// Writes the given history steps as entries of the given kind.
+fn write_steps<W>(
+	out_buf: &mut W,
+	kind: &str,
+	steps: &[(OperationInfo, Cells)])
+	-> io::Result<()>
+	where W: io::Write
+{
+	for &(ref info, ref cells) in steps {
+		write!(out_buf, "{} {}", kind, quote(&info.name))?;
+		if let Some(ref details) = info.details {
+			write!(out_buf, " {}", quote(details))?;
+		}
+		writeln!(out_buf)?;
+		for &(address, expr) in cells {
+			match expr {
+				Some(expr) => writeln!(out_buf, "cell {} {}", 
+					address, 
+					write_expression(&expr))?,
+				None => writeln!(out_buf, "remove {}", address)?,
+			}
+		}
+	}
+	Ok(())
+}
+
+
+/// Adds a restored entry to the history.
+fn push(history: &mut OperationHistory, step: Step) {
+	let (is_undo, info, cells) = step;
+	let mut undo = Undo::for_info(info.clone());
+	for (address, expr) in cells { undo.record(address, expr); }
+	let entry = HistoryEntry {
+		info: info,
+		undo: Box::new(undo),
+		conflicts: Vec::new(),
+	};
+	if is_undo {
+		history.undo_entries.push(entry);
+	} else {
+		history.redo_entries.push(entry);
+	}
+}
+
+
+/// Returns the info and restored cells of the `count` most recent history 
+/// entries on the given stack, oldest first, by replaying them against a fork
+/// of the given data. The replayed entries are replaced by equivalent entries.
+fn record(
+	entries: &mut Vec<HistoryEntry>,
+	count: usize,
+	data: &Data)
+	-> io::Result<Vec<(OperationInfo, Cells)>>
+{
+	let start = entries.len() - count;
+	let mut fork = data.fork();
+	let mut steps = Vec::with_capacity(count);
+	let mut reverts = Vec::with_capacity(count);
+
+	// Step back through the entries, recording what each restores. A failed
+	// entry is left as it was, so stop there and rebuild the newer ones.
+	let mut failure = None;
+	for entry in entries[start..].iter_mut().rev() {
+		let before = fork.fork();
+		match entry.undo.apply(&mut fork) {
+			Ok(revert) => {
+				steps.push((entry.info.clone(), changed_cells(&before, &fork)));
+				reverts.push(revert);
+			},
+			Err(error) => {
+				failure = Some(error);
+				break;
+			},
+		}
+	}
+
+	// Step forward again, collecting fresh entries to replace the consumed
+	// ones.
+	let end = entries.len();
+	for (entry, mut revert) in entries[end - reverts.len()..].iter_mut()
+		.zip(reverts.into_iter().rev())
+	{
+		entry.undo = revert.undo.apply(&mut fork).map_err(replay_error)?.undo;
+	}
+
+	if let Some(error) = failure {
+		return Err(replay_error(error));
+	}
+
+	steps.reverse();
+	Ok(steps)
+}
+
+
+/// Returns a checksum of the cells of the given data.
+fn cells_checksum(data: &Data) -> u64 {
+	let text: String = data.cells
+		.iter()
+		.map(|(address, cell)| 
+			format!("{} {}\n", address, write_expression(&cell.borrow())))
+		.collect();
+	checksum(text.as_bytes())
+}
+
+
+/// Returns the cells of `after` which differ from those of `before`.
+fn changed_cells(before: &Data, after: &Data) -> Cells {
+	let mut cells: Cells = after.cells
+		.iter()
+		.filter(|&(address, cell)| before.cells
+			.get(address)
+			.map_or(true, |old| !Rc::ptr_eq(old, cell)))
+		.map(|(&address, cell)| (address, Some(*cell.borrow())))
+		.collect();
+	cells.extend(before.cells
+		.keys()
+		.filter(|address| !after.cells.contains_key(address))
+		.map(|&address| (address, None)));
+	cells.sort_by_key(|&(address, _)| address);
+	cells
+}
+
+
+/// Converts an error from replaying the history into an IO error.
+fn replay_error<E>(error: E) -> io::Error where E: ::std::fmt::Display {
+	io::Error::new(io::ErrorKind::Other, format!("history replay: {}", error))
+}
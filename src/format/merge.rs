@@ -0,0 +1,334 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides a three-way merge of palettes in the default format, suitable for
+//! use as a git merge driver.
+//!
+//! Each palette is written in canonical form and split into entries keyed by
+//! what they describe, such as the cell at an address or the name of a group.
+//! An entry changed on only one side since the base takes that side's value,
+//! and an entry changed identically on both sides is kept. An entry changed
+//! differently on both sides is a conflict, and is written between git-style
+//! conflict markers.
+//!
+//! To use the merge driver, register it in `.gitattributes`:
+//!
+//! ```text
+//! *.pal merge=palette
+//! ```
+//!
+//! and in the git configuration:
+//!
+//! ```text
+//! [merge "palette"]
+//!     name = palette three-way merge
+//!     driver = palette merge-driver %O %A %B
+//! ```
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use ::Palette;
+use curve::ToneChannel;
+use format::default::{
+	parse_address,
+	read_palette,
+	tokenize,
+	write_palette,
+};
+
+// Standard imports.
+use std::collections::BTreeMap;
+use std::io;
+
+
+
+/// The position of an entry in a canonical palette file.
+type SortKey = (u8, u16, u16, u16, u8, String);
+
+
+////////////////////////////////////////////////////////////////////////////////
+// MergeConflict
+////////////////////////////////////////////////////////////////////////////////
+/// An entry which was changed differently by both sides of a merge.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MergeConflict {
+	/// The entry's key, such as `cell 0:0:1`.
+	pub key: String,
+	/// The entry in the base palette, or None if it was absent.
+	pub base: Option<String>,
+	/// The entry in our palette, or None if it was removed.
+	pub ours: Option<String>,
+	/// The entry in their palette, or None if it was removed.
+	pub theirs: Option<String>,
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// ThreeWayMerge
+////////////////////////////////////////////////////////////////////////////////
+/// The result of merging two palettes derived from a common base.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::format::merge::ThreeWayMerge;
+///
+/// let read = |text: &str| Format::Default
+/// 	.read_palette(&mut text.as_bytes())
+/// 	.unwrap();
+/// let base = read("cell 0:0:0 #000000\ncell 0:0:1 #000000\n");
+/// let ours = read("cell 0:0:0 #FF0000\ncell 0:0:1 #000000\n");
+/// let theirs = read("cell 0:0:0 #000000\ncell 0:0:1 #0000FF\n");
+///
+/// let merge = ThreeWayMerge::new(&base, &ours, &theirs).unwrap();
+/// assert!(merge.is_clean());
+///
+/// let merged = merge.palette().unwrap();
+/// let color = |column| merged.color(Address::new(0, 0, column));
+/// assert_eq!(color(0), Some(Color::new(255, 0, 0)));
+/// assert_eq!(color(1), Some(Color::new(0, 0, 255)));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ThreeWayMerge {
+	/// The merged entries, in canonical order.
+	entries: Vec<(SortKey, String)>,
+	/// The conflicting entries, in canonical order.
+	conflicts: Vec<(SortKey, MergeConflict)>,
+}
+
+
+impl ThreeWayMerge {
+	/// Merges the changes made to the base palette by our palette and their
+	/// palette.
+	///
+	/// # Example
+	///
+	/// Cells trashed on either side are kept in the merged trash.
+	///
+	/// ```rust
+	/// use palette::*;
+	/// use palette::format::merge::ThreeWayMerge;
+	///
+	/// let read = |text: &str| Format::Default
+	/// 	.read_palette(&mut text.as_bytes())
+	/// 	.unwrap();
+	/// let base = read("cell 0:0:0 #000000\ncell 0:0:1 #00FF00\n");
+	/// let ours = read("cell 0:0:0 #000000\n\
+	/// 	trash 0:0:1 #00FF00 name \"green\"\n");
+	/// let theirs = read("cell 0:0:1 #00FF00\ntrash 0:0:0 #000000\n");
+	///
+	/// let merge = ThreeWayMerge::new(&base, &ours, &theirs).unwrap();
+	/// assert!(merge.is_clean());
+	///
+	/// let merged = merge.palette().unwrap();
+	/// assert_eq!(merged.len(), 0);
+	/// let trash = merged.trash();
+	/// assert_eq!(trash.len(), 2);
+	/// assert_eq!(trash[1].address, Address::new(0, 0, 1));
+	/// assert_eq!(trash[1].name, Some("green".to_owned()));
+	/// ```
+	pub fn new(base: &Palette, ours: &Palette, theirs: &Palette)
+		-> io::Result<ThreeWayMerge>
+	{
+		let base = entries(base)?;
+		let ours = entries(ours)?;
+		let theirs = entries(theirs)?;
+
+		let mut keys: Vec<_> = base.keys()
+			.chain(ours.keys())
+			.chain(theirs.keys())
+			.cloned()
+			.collect();
+		keys.sort();
+		keys.dedup();
+
+		let mut merge = ThreeWayMerge::default();
+		for key in keys {
+			let b = base.get(&key).map(|e| &e.1);
+			let o = ours.get(&key).map(|e| &e.1);
+			let t = theirs.get(&key).map(|e| &e.1);
+			let sort_key = base.get(&key)
+				.or_else(|| ours.get(&key))
+				.or_else(|| theirs.get(&key))
+				.map(|e| e.0.clone())
+				.expect("merged key in some palette");
+
+			let merged = if o == t || t == b {
+				o
+			} else if o == b {
+				t
+			} else {
+				merge.conflicts.push((sort_key, MergeConflict {
+					key: key,
+					base: b.cloned(),
+					ours: o.cloned(),
+					theirs: t.cloned(),
+				}));
+				continue;
+			};
+			if let Some(line) = merged {
+				merge.entries.push((sort_key, line.clone()));
+			}
+		}
+		merge.entries.sort();
+		merge.conflicts.sort_by(|a, b| a.0.cmp(&b.0));
+		Ok(merge)
+	}
+
+	/// Returns whether the merge has no conflicts.
+	pub fn is_clean(&self) -> bool {
+		self.conflicts.is_empty()
+	}
+
+	/// Returns the conflicting entries, in file order.
+	pub fn conflicts(&self) -> Vec<&MergeConflict> {
+		self.conflicts.iter().map(|c| &c.1).collect()
+	}
+
+	/// Writes the merged palette to the given buffer in the default format.
+	/// Conflicting entries are written between conflict markers, which must
+	/// be resolved before the file can be read.
+	pub fn write<W>(&self, out_buf: &mut W) -> io::Result<()>
+		where W: io::Write
+	{
+		let mut conflicts = self.conflicts.iter().peekable();
+		for &(ref sort_key, ref line) in &self.entries {
+			while conflicts.peek().map_or(false, |c| c.0 < *sort_key) {
+				write_conflict(&conflicts.next().unwrap().1, out_buf)?;
+			}
+			writeln!(out_buf, "{}", line)?;
+		}
+		for conflict in conflicts {
+			write_conflict(&conflict.1, out_buf)?;
+		}
+		Ok(())
+	}
+
+	/// Returns the merged palette.
+	///
+	/// # Errors
+	///
+	/// Returns an `InvalidData` error if the merge has conflicts.
+	pub fn palette(&self) -> io::Result<Palette> {
+		if !self.is_clean() {
+			return Err(io::Error::new(
+				io::ErrorKind::InvalidData,
+				format!("{} unresolved merge conflicts", 
+					self.conflicts.len())));
+		}
+		let mut buf = Vec::new();
+		self.write(&mut buf)?;
+		read_palette(&mut &buf[..])
+	}
+}
+
+
+/// Writes the given conflict between conflict markers.
+fn write_conflict<W>(conflict: &MergeConflict, out_buf: &mut W) 
+	-> io::Result<()>
+	where W: io::Write
+{
+	writeln!(out_buf, "<<<<<<< ours")?;
+	if let Some(ref line) = conflict.ours { writeln!(out_buf, "{}", line)?; }
+	writeln!(out_buf, "=======")?;
+	if let Some(ref line) = conflict.theirs { writeln!(out_buf, "{}", line)?; }
+	writeln!(out_buf, ">>>>>>> theirs")
+}
+
+
+/// Returns the canonical entries of the given palette, with their position in
+/// the file, by key.
+fn entries(palette: &Palette) 
+	-> io::Result<BTreeMap<String, (SortKey, String)>>
+{
+	let mut buf = Vec::new();
+	write_palette(palette, &mut buf)?;
+	let text = String::from_utf8(buf)
+		.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+	let mut entries = BTreeMap::new();
+	for line in text.lines() {
+		let tokens = tokenize(line)
+			.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+		if tokens.is_empty() { continue; }
+		let (key, sort_key) = entry_key(line, &tokens, entries.len());
+		entries.insert(key, (sort_key, line.to_owned()));
+	}
+	Ok(entries)
+}
+
+
+/// Returns the key identifying the given entry and its position in the file.
+/// Trashed cells are positioned by the given index of the entry, so that they
+/// stay in order of deletion.
+fn entry_key(line: &str, tokens: &[String], index: usize) 
+	-> (String, SortKey)
+{
+	let arg = tokens.get(1).map_or("", |t| &t[..]);
+	let address = parse_address(arg).ok();
+	let at = |kind: u8, page: u16, line: u16, column: u16, sub: u8| 
+		(kind, page, line, column, sub, String::new());
+
+	let sort_key = match (&tokens[0][..], address) {
+		("palette", _)		=> at(0, 0, 0, 0, 0),
+		("format", _)		=> at(1, 0, 0, 0, 0),
+		("color_space", _)	=> at(2, 0, 0, 0, 0),
+		("tone_curve", _)	=> at(3, 0, 0, 0, ToneChannel::from_name(arg)
+			.map_or(u8::max_value(), |c| c as u8)),
+		("white_point", _)	=> at(4, 0, 0, 0, 0),
+		("black_point", _)	=> at(5, 0, 0, 0, 0),
+		("page", _)			=> at(6, arg.parse().unwrap_or(0), 0, 0, 0),
+		("line", _)			=> match parse_address(&format!("{}:0", arg)) {
+			Ok(a) => at(6, a.page, a.line as u16 + 1, 0, 0),
+			Err(_) => at(9, 0, 0, 0, 0),
+		},
+		("name", Some(a))	=> at(6, a.page, a.line as u16 + 1, 
+			a.column as u16 + 1, 0),
+		("description", Some(a)) => at(6, a.page, a.line as u16 + 1,
+			a.column as u16 + 1, 1),
+		("tag", Some(a))	=> (6, a.page, a.line as u16 + 1,
+			a.column as u16 + 1, 2, line.to_owned()),
+		("usage", Some(a))	=> at(6, a.page, a.line as u16 + 1,
+			a.column as u16 + 1, 3),
+		("cell", Some(a))	=> at(7, a.page, a.line as u16,
+			a.column as u16, 0),
+		("trash", _)		=> (10, 0, 0, 0, 0, format!("{:020}", index)),
+		_					=> (9, 0, 0, 0, 0, line.to_owned()),
+	};
+
+	// Tags and trashed cells are keyed by their whole entry, so that those
+	// added on either side are kept. Other entries are keyed by their kind
+	// and subject.
+	let key = match &tokens[0][..] {
+		"palette" | "format" | "color_space" | "white_point" | "black_point"
+			=> tokens[0].clone(),
+		"tag" | "trash" => line.to_owned(),
+		_ if tokens.len() > 1 => format!("{} {}", tokens[0], tokens[1]),
+		_ => line.to_owned(),
+	};
+	(key, sort_key)
+}
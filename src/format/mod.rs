@@ -32,13 +32,32 @@
 pub mod zpl;
 #[warn(missing_docs)]
 pub mod default;
+#[warn(missing_docs)]
+pub mod history;
+#[warn(missing_docs)]
+pub mod merge;
+#[warn(missing_docs)]
+mod readonly;
+
+// Submodule re-exports.
+pub use self::readonly::{
+	open_readonly,
+	PaletteView,
+};
 
 // Module imports.
 use Palette;
-use address::Reference;
+use address::{
+	Reference,
+	Selection,
+};
 use data::Data;
-use operation::PaletteOperation;
+use operation::{
+	Conflict,
+	PaletteOperation,
+};
 use result::Result;
+use trace::Span;
 
 // Standard imports.
 use std::io;
@@ -48,7 +67,7 @@ use std::io;
 // Format
 ////////////////////////////////////////////////////////////////////////////////
 /// An enum of the supported palette formats.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Format {
 	/// The default palette format; provides no special behaviors or 
 	/// restrictions.
@@ -62,6 +81,21 @@ pub enum Format {
 
 #[cfg_attr(feature = "cargo-clippy", allow(single_match))]
 impl Format {
+	/// Returns the name of the format.
+	pub fn name(&self) -> &'static str {
+		match *self {
+			Format::Default => "default",
+			Format::Zpl     => "zpl",
+		}
+	}
+
+	/// Returns the format with the given name, ignoring case.
+	pub fn from_name(name: &str) -> Option<Format> {
+		[Format::Default, Format::Zpl].iter()
+			.find(|f| f.name().eq_ignore_ascii_case(name))
+			.cloned()
+	}
+
 	/// Called when a new palette is created. Initializes the palette data.
 	pub fn initialize(self, data: &mut Data)  {
 		match self {
@@ -94,16 +128,38 @@ impl Format {
 		}
 	}
 
-	/// Applies the given operation to the palette. 
+	/// Applies the given operation to the palette, returning the cells it 
+	/// skipped.
 	pub fn apply_operation(
 		self, 
 		palette: &mut Palette, 
 		operation: Box<PaletteOperation>) 
-		-> Result<()>
+		-> Result<Vec<Conflict>>
 	{
 		default::apply_operation(palette, operation)
 	}
 
+	/// Applies the given operation to the palette as an interactive 
+	/// operation.
+	pub fn update_interactive(
+		self, 
+		palette: &mut Palette, 
+		operation: Box<PaletteOperation>) 
+		-> Result<()>
+	{
+		default::update_interactive(palette, operation)
+	}
+
+	/// Finishes the palette's pending interactive operation.
+	pub fn finish_interactive(self, palette: &mut Palette) {
+		default::finish_interactive(palette)
+	}
+
+	/// Reverts the palette's pending interactive operation.
+	pub fn cancel_interactive(self, palette: &mut Palette) -> Result<()> {
+		default::cancel_interactive(palette)
+	}
+
 	/// Reverses the most recently applied operation.
 	pub fn undo(self, palette: &mut Palette) -> Result<()> {
 		default::undo(palette)
@@ -115,19 +171,68 @@ impl Format {
 	}
 
 	/// Writes the palette to the given buffer.
-	#[allow(unused_variables)]
+	///
+	/// # Errors
+	///
+	/// Returns an `Other` error if the format cannot be written.
 	pub fn write_palette<W>(self, palette: &Palette, out_buf: &mut W) -> io::Result<()> 
 		where W: io::Write
 	{
-		unimplemented!()
+		let span = Span::enter("export", self.name());
+		match self {
+			Format::Default => default::write_palette(palette, out_buf)?,
+			Format::Zpl => return Err(io::Error::new(
+				io::ErrorKind::Other,
+				"writing zpl palettes is not supported")),
+		}
+		span.finish(palette.len());
+		Ok(())
+	}
+
+	/// Writes the selected cells of the palette to the given buffer, along
+	/// with the groups containing them.
+	///
+	/// # Errors
+	///
+	/// Returns an `Other` error if the format cannot be written.
+	pub fn write_selection<W>(
+		self,
+		palette: &Palette,
+		selection: &Selection,
+		out_buf: &mut W)
+		-> io::Result<()> 
+		where W: io::Write
+	{
+		let span = Span::enter("export", self.name());
+		match self {
+			Format::Default 
+				=> default::write_selection(palette, selection, out_buf)?,
+			Format::Zpl => return Err(io::Error::new(
+				io::ErrorKind::Other,
+				"writing zpl palettes is not supported")),
+		}
+		span.finish_with(|| palette.colors(selection).len());
+		Ok(())
 	}
 
 	/// Reads a palette from the given buffer.
-	#[allow(unused_variables)]
-	pub fn read_palette<R>(self, in_buf: &mut R) -> io::Result<()> 
+	///
+	/// # Errors
+	///
+	/// Returns an `InvalidData` error if the buffer is not a valid palette,
+	/// or an `Other` error if the format cannot be read.
+	pub fn read_palette<R>(self, in_buf: &mut R) -> io::Result<Palette> 
 		where R: io::Read
 	{
-		unimplemented!()
+		let span = Span::enter("import", self.name());
+		let palette = match self {
+			Format::Default => default::read_palette(in_buf)?,
+			Format::Zpl => return Err(io::Error::new(
+				io::ErrorKind::Other,
+				"reading zpl palettes is not supported")),
+		};
+		span.finish(palette.len());
+		Ok(palette)
 	}
 }
 
@@ -0,0 +1,223 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides lightweight read-only access to palettes in the default format.
+//!
+//! A `PaletteView` does not build the editable palette model. Only the 
+//! palette name, cell colors, and cell names are parsed, once when the view 
+//! is created, which makes it suitable for quickly previewing many palette 
+//! files. When the `mmap` feature is enabled, files are memory-mapped rather
+//! than read into memory.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use address::Address;
+use format::default::{
+	parse_address,
+	read_expression,
+	tokenize,
+};
+
+// Non-local imports.
+use color::Color;
+
+// Standard imports.
+use std::fs::File;
+use std::io;
+use std::path::Path;
+use std::str;
+
+
+/// The storage backing a `PaletteView`.
+enum Bytes {
+	/// Contents read into memory.
+	Owned(Vec<u8>),
+	/// Contents mapped from a file.
+	#[cfg(feature = "mmap")]
+	Mapped(::memmap2::Mmap),
+}
+
+
+impl Bytes {
+	/// Returns the contents.
+	fn as_slice(&self) -> &[u8] {
+		match *self {
+			Bytes::Owned(ref bytes) => &bytes[..],
+			#[cfg(feature = "mmap")]
+			Bytes::Mapped(ref map) => &map[..],
+		}
+	}
+}
+
+
+/// Opens the palette file at the given path for read-only viewing.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be opened or is not valid UTF-8.
+pub fn open_readonly<P>(path: P) -> io::Result<PaletteView> 
+	where P: AsRef<Path>
+{
+	let file = File::open(path)?;
+	PaletteView::new(read_bytes(file)?)
+}
+
+
+/// Returns the contents of the given file.
+#[cfg(feature = "mmap")]
+fn read_bytes(file: File) -> io::Result<Bytes> {
+	// Safe as long as the file is not modified while mapped, which would at
+	// worst produce garbled colors after the UTF-8 check.
+	unsafe { ::memmap2::Mmap::map(&file) }.map(Bytes::Mapped)
+}
+
+
+/// Returns the contents of the given file.
+#[cfg(not(feature = "mmap"))]
+fn read_bytes(mut file: File) -> io::Result<Bytes> {
+	use std::io::Read;
+	let mut bytes = Vec::new();
+	file.read_to_end(&mut bytes)?;
+	Ok(Bytes::Owned(bytes))
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// PaletteView
+////////////////////////////////////////////////////////////////////////////////
+/// A read-only view of a palette in the default format, exposing its colors
+/// and names. Malformed entries are skipped.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::{Address, Color};
+/// use palette::format::PaletteView;
+///
+/// let text = "palette \"Example\"\n\
+/// 	cell 0:0:0 #0C324E\n\
+/// 	name 0:0:0 name \"skin\"\n";
+/// let view = PaletteView::from_bytes(text.as_bytes().to_vec()).unwrap();
+///
+/// assert_eq!(view.name(), Some("Example".to_owned()));
+/// assert_eq!(view.colors().collect::<Vec<_>>(), 
+/// 	vec![(Address::new(0, 0, 0), Color::new(12, 50, 78))]);
+/// assert_eq!(view.cell_names(), vec![("skin".to_owned(), Address::new(0, 0, 0))]);
+/// ```
+pub struct PaletteView {
+	/// The name of the palette.
+	name: Option<String>,
+	/// The addresses and colors of the palette's cells, in file order.
+	colors: Vec<(Address, Color)>,
+	/// The names and addresses of the palette's named cells.
+	names: Vec<(String, Address)>,
+}
+
+
+impl PaletteView {
+	/// Creates a new `PaletteView` of the given palette file contents.
+	///
+	/// # Errors
+	///
+	/// Returns an `InvalidData` error if the contents are not valid UTF-8.
+	pub fn from_bytes(bytes: Vec<u8>) -> io::Result<Self> {
+		PaletteView::new(Bytes::Owned(bytes))
+	}
+
+	/// Creates a new `PaletteView` of the given storage.
+	fn new(bytes: Bytes) -> io::Result<Self> {
+		let text = str::from_utf8(bytes.as_slice())
+			.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+		let mut view = PaletteView {
+			name: None,
+			colors: Vec::new(),
+			names: Vec::new(),
+		};
+		for line in text.lines() {
+			// Skip tokenizing the entries which aren't viewed.
+			let kind = match line.split_whitespace().next() {
+				Some(kind) if ["palette", "cell", "name"].contains(&kind)
+					=> kind,
+				_ => continue,
+			};
+			let tokens = match tokenize(line) {
+				Ok(tokens) => tokens,
+				Err(_) => continue,
+			};
+			if tokens.first().map_or(true, |t| t != kind) { continue; }
+			match kind {
+				"palette" if view.name.is_none() 
+					=> view.name = tokens.get(1).cloned(),
+				"cell" => view.colors.extend(read_cell(&tokens)),
+				"name" => view.names.extend(read_name(&tokens)),
+				_ => (),
+			}
+		}
+		Ok(view)
+	}
+
+	/// Returns the name of the palette.
+	pub fn name(&self) -> Option<String> {
+		self.name.clone()
+	}
+
+	/// Returns an iterator over the addresses and colors of the palette's
+	/// cells, in file order. Extended-range colors are mapped into the sRGB
+	/// gamut.
+	pub fn colors<'a>(&'a self) -> impl Iterator<Item=(Address, Color)> + 'a {
+		self.colors.iter().cloned()
+	}
+
+	/// Returns the number of colored cells in the palette.
+	pub fn len(&self) -> usize {
+		self.colors.len()
+	}
+
+	/// Returns whether the palette has no colored cells.
+	pub fn is_empty(&self) -> bool {
+		self.colors.is_empty()
+	}
+
+	/// Returns the names and addresses of the palette's named cells.
+	pub fn cell_names(&self) -> Vec<(String, Address)> {
+		self.names.clone()
+	}
+}
+
+
+/// Parses the address and color of a `cell` entry, if it is well-formed.
+fn read_cell(tokens: &[String]) -> Option<(Address, Color)> {
+	let address = parse_address(tokens.get(1)?).ok()?;
+	let color = read_expression(&tokens[2..]).ok()?.color()?;
+	Some((address, color))
+}
+
+
+/// Parses the name and address of a `name` entry, if it is well-formed.
+fn read_name(tokens: &[String]) -> Option<(String, Address)> {
+	Some((tokens.get(3)?.clone(), parse_address(tokens.get(1)?).ok()?))
+}
@@ -0,0 +1,398 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides representative reference palettes and a gallery builder.
+//!
+//! The reference palettes cover the common shapes of palettes: a retro 
+//! console's fixed system colors with sprite sub-palettes, a UI theme of 
+//! generated ramps with semantic tokens, and data visualization scales. They
+//! are built directly, without recording history, so they are cheap to 
+//! construct as fixtures for examples, benchmarks, and screenshots:
+//!
+//! ```rust
+//! use palette::*;
+//! use palette::gallery::ReferencePalette;
+//!
+//! let theme = ReferencePalette::UiTheme.palette();
+//! assert_eq!(theme.alias("primary"), Some(Address::new(0, 1, 4)));
+//! assert_eq!(theme.color(Address::new(0, 1, 4)), 
+//! 	Some(Color::new(0x25, 0x63, 0xEB)));
+//! ```
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use ::Palette;
+use address::{
+	Address,
+	Reference,
+};
+use expression::Expression;
+use export::write_html_gallery;
+use format::Format;
+use ramp::{
+	InterpolationSpace,
+	RampGenerator,
+};
+use render::{
+	Image,
+	ThumbnailLayout,
+};
+
+// Non-local imports.
+use color::Color;
+
+// Standard imports.
+use std::io;
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// ReferencePalette
+////////////////////////////////////////////////////////////////////////////////
+/// A representative palette used for examples and fixtures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReferencePalette {
+	/// The sixteen named system colors of the PICO-8 fantasy console on the
+	/// first line, followed by four-color sprite sub-palettes drawn from them.
+	RetroConsole,
+	/// Neutral and primary ramps generated in Oklab, a line of feedback 
+	/// colors, and semantic tokens for their roles.
+	UiTheme,
+	/// The Okabe-Ito categorical colors, followed by a sequential and a 
+	/// diverging scale generated in Oklab.
+	DataViz,
+}
+
+
+impl ReferencePalette {
+	/// Returns all of the reference palettes.
+	pub fn all() -> &'static [ReferencePalette] {
+		&[
+			ReferencePalette::RetroConsole,
+			ReferencePalette::UiTheme,
+			ReferencePalette::DataViz,
+		]
+	}
+
+	/// Returns the name of the reference palette.
+	pub fn name(&self) -> &'static str {
+		match *self {
+			ReferencePalette::RetroConsole => "retro-console",
+			ReferencePalette::UiTheme      => "ui-theme",
+			ReferencePalette::DataViz      => "data-viz",
+		}
+	}
+
+	/// Returns the reference palette with the given name, ignoring case.
+	pub fn from_name(name: &str) -> Option<ReferencePalette> {
+		ReferencePalette::all()
+			.iter()
+			.cloned()
+			.find(|p| p.name().eq_ignore_ascii_case(name))
+	}
+
+	/// Constructs the palette.
+	///
+	/// # Example
+	///
+	/// ```rust
+	/// use palette::*;
+	/// use palette::gallery::ReferencePalette;
+	///
+	/// let retro = ReferencePalette::RetroConsole.palette();
+	/// assert_eq!(retro.name(), Some("Retro Console"));
+	/// assert_eq!(retro.address_of("red"), Some(Address::new(0, 0, 8)));
+	/// assert_eq!(retro.color(Address::new(0, 0, 8)), 
+	/// 	Some(Color::new(0xFF, 0x00, 0x4D)));
+	///
+	/// let viz = ReferencePalette::DataViz.palette();
+	/// let diverging: Vec<_> = viz.colors(&Selection::all())
+	/// 	.into_iter()
+	/// 	.filter(|&(address, _)| address.line == 2)
+	/// 	.collect();
+	/// assert_eq!(diverging.len(), 11);
+	/// assert_eq!(diverging[5].1, Color::new(0xF7, 0xF7, 0xF7));
+	/// ```
+	pub fn palette(&self) -> Palette {
+		match *self {
+			ReferencePalette::RetroConsole => retro_console(),
+			ReferencePalette::UiTheme      => ui_theme(),
+			ReferencePalette::DataViz      => data_viz(),
+		}
+	}
+}
+
+
+impl Default for ReferencePalette {
+	fn default() -> Self {
+		ReferencePalette::RetroConsole
+	}
+}
+
+
+/// Returns the color with the given `0xRRGGBB` value.
+fn rgb(value: u32) -> Color {
+	Color::new((value >> 16) as u8, (value >> 8) as u8, value as u8)
+}
+
+
+/// Stores the given colors on a line of the first page, naming the line and
+/// any cells given names. Returns the addresses of the cells.
+fn store_line(
+	palette: &mut Palette,
+	line: u8,
+	line_name: &str,
+	cells: &[(Option<&str>, Color)])
+	-> Vec<Address>
+{
+	let addresses: Vec<_> = cells.iter()
+		.enumerate()
+		.map(|(column, &(name, color))| {
+			let address = Address::new(0, line, column as u8);
+			*palette.data.create_cell(address)
+				.expect("reference palette cell")
+				.borrow_mut() = Expression::Color(color);
+			if let Some(name) = name {
+				palette.set_name(address, name);
+			}
+			address
+		})
+		.collect();
+	// Name the line once it exists, so it is prepared as a new line.
+	palette.data.set_name(
+		Reference::line_of(&Address::new(0, line, 0)), 
+		line_name);
+	addresses
+}
+
+
+/// Stores the given colors on a line as unnamed cells.
+fn store_ramp(palette: &mut Palette, line: u8, name: &str, colors: &[Color])
+	-> Vec<Address>
+{
+	let cells: Vec<_> = colors.iter().map(|&color| (None, color)).collect();
+	store_line(palette, line, name, &cells)
+}
+
+
+/// Assigns each of the given semantic tokens to a cell.
+fn store_tokens(palette: &mut Palette, tokens: &[(&str, Address)]) {
+	for &(token, address) in tokens {
+		palette.set_alias(token, address).expect("reference palette token");
+	}
+}
+
+
+/// Constructs the retro console reference palette.
+fn retro_console() -> Palette {
+	const SYSTEM: [(&str, u32); 16] = [
+		("black", 0x000000),       ("dark-blue", 0x1D2B53),
+		("dark-purple", 0x7E2553), ("dark-green", 0x008751),
+		("brown", 0xAB5236),       ("dark-grey", 0x5F574F),
+		("light-grey", 0xC2C3C7),  ("white", 0xFFF1E8),
+		("red", 0xFF004D),         ("orange", 0xFFA300),
+		("yellow", 0xFFEC27),      ("green", 0x00E436),
+		("blue", 0x29ADFF),        ("lavender", 0x83769C),
+		("pink", 0xFF77A8),        ("light-peach", 0xFFCCAA),
+	];
+	// Each sprite sub-palette is four indices into the system colors.
+	const SPRITES: [(&str, [usize; 4]); 4] = [
+		("Player", [0, 2, 14, 15]),
+		("Water",  [1, 12, 6, 7]),
+		("Fire",   [2, 8, 9, 10]),
+		("Forest", [5, 3, 11, 4]),
+	];
+
+	let mut palette = Palette::new("Retro Console", Format::Default, true);
+	let system: Vec<_> = SYSTEM.iter()
+		.map(|&(name, value)| (Some(name), rgb(value)))
+		.collect();
+	store_line(&mut palette, 0, "System", &system);
+
+	for (line, &(name, indices)) in SPRITES.iter().enumerate() {
+		let colors: Vec<_> = indices.iter().map(|&i| system[i].1).collect();
+		store_ramp(&mut palette, line as u8 + 1, name, &colors);
+	}
+	palette
+}
+
+
+/// Constructs the UI theme reference palette.
+fn ui_theme() -> Palette {
+	let gen = RampGenerator::new().space(InterpolationSpace::Oklab);
+	let mut palette = Palette::new("UI Theme", Format::Default, true);
+
+	let neutral = store_ramp(&mut palette, 0, "Neutral", 
+		&gen.ramp(rgb(0xF8FAFC), rgb(0x0F172A), 9));
+	let primary = store_ramp(&mut palette, 1, "Primary", 
+		&gen.multi_stop(&[rgb(0xEFF6FF), rgb(0x2563EB), rgb(0x172554)], 9));
+	let feedback = store_line(&mut palette, 2, "Feedback", &[
+		(Some("success"), rgb(0x16A34A)),
+		(Some("warning"), rgb(0xD97706)),
+		(Some("error"),   rgb(0xDC2626)),
+		(Some("info"),    rgb(0x0284C7)),
+	]);
+
+	store_tokens(&mut palette, &[
+		("surface",        neutral[0]),
+		("surface.raised", neutral[1]),
+		("border",         neutral[2]),
+		("text.muted",     neutral[5]),
+		("text",           neutral[8]),
+		("primary",        primary[4]),
+		("primary.hover",  primary[5]),
+		("primary.subtle", primary[0]),
+		("status.success", feedback[0]),
+		("status.warning", feedback[1]),
+		("status.error",   feedback[2]),
+		("status.info",    feedback[3]),
+	]);
+	palette
+}
+
+
+/// Constructs the data visualization reference palette.
+fn data_viz() -> Palette {
+	const CATEGORICAL: [(&str, u32); 8] = [
+		("black", 0x000000),     ("orange", 0xE69F00),
+		("sky-blue", 0x56B4E9),  ("bluish-green", 0x009E73),
+		("yellow", 0xF0E442),    ("blue", 0x0072B2),
+		("vermillion", 0xD55E00), ("reddish-purple", 0xCC79A7),
+	];
+
+	let gen = RampGenerator::new().space(InterpolationSpace::Oklab);
+	let mut palette = Palette::new("Data Visualization", Format::Default, true);
+
+	let categorical: Vec<_> = CATEGORICAL.iter()
+		.map(|&(name, value)| (Some(name), rgb(value)))
+		.collect();
+	store_line(&mut palette, 0, "Categorical", &categorical);
+	store_ramp(&mut palette, 1, "Sequential", 
+		&gen.ramp(rgb(0xF7FBFF), rgb(0x08306B), 9));
+	store_ramp(&mut palette, 2, "Diverging", 
+		&gen.multi_stop(&[rgb(0x2166AC), rgb(0xF7F7F7), rgb(0xB2182B)], 11));
+	palette
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Gallery
+////////////////////////////////////////////////////////////////////////////////
+/// A titled collection of palettes rendered together as an HTML page or as
+/// thumbnail images.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::gallery::Gallery;
+/// use palette::render::ThumbnailLayout;
+///
+/// let gallery = Gallery::reference();
+///
+/// let mut out = Vec::new();
+/// gallery.write_html(&mut out).unwrap();
+/// let html = String::from_utf8(out).unwrap();
+/// assert!(html.contains("<h2>UI Theme</h2>"));
+///
+/// let thumbnails = gallery.thumbnails(64, 16, ThumbnailLayout::Lines);
+/// assert_eq!(thumbnails[0].0, "retro-console");
+/// assert_eq!(thumbnails[0].1.width, 64);
+/// ```
+#[derive(Debug)]
+pub struct Gallery {
+	/// The title of the gallery.
+	title: String,
+	/// The palettes of the gallery, in order.
+	palettes: Vec<Palette>,
+}
+
+
+impl Gallery {
+	/// Constructs a new, empty `Gallery` with the given title.
+	pub fn new<S>(title: S) -> Self where S: Into<String> {
+		Gallery {
+			title: title.into(),
+			palettes: Vec::new(),
+		}
+	}
+
+	/// Constructs a new `Gallery` of all of the reference palettes.
+	pub fn reference() -> Self {
+		ReferencePalette::all()
+			.iter()
+			.fold(Gallery::new("Reference Palettes"), |gallery, reference|
+				gallery.palette(reference.palette()))
+	}
+
+	/// Adds a palette to the gallery.
+	pub fn palette(mut self, palette: Palette) -> Self {
+		self.palettes.push(palette);
+		self
+	}
+
+	/// Returns the title of the gallery.
+	pub fn title(&self) -> &str {
+		&self.title
+	}
+
+	/// Returns the palettes of the gallery.
+	pub fn palettes(&self) -> &[Palette] {
+		&self.palettes
+	}
+
+	/// Writes the gallery to the given buffer as a standalone HTML page.
+	pub fn write_html<W>(&self, out_buf: &mut W) -> io::Result<()>
+		where W: io::Write
+	{
+		let palettes: Vec<&Palette> = self.palettes.iter().collect();
+		write_html_gallery(&palettes, &self.title, out_buf)
+	}
+
+	/// Renders a thumbnail of each palette with the given size and layout, 
+	/// paired with a file name stem derived from the palette's name.
+	pub fn thumbnails(&self, width: usize, height: usize, 
+		layout: ThumbnailLayout) 
+		-> Vec<(String, Image)>
+	{
+		self.palettes.iter()
+			.map(|palette| (
+				file_stem(palette),
+				palette.render_thumbnail(width, height, layout)))
+			.collect()
+	}
+}
+
+
+/// Returns a lowercase file name stem for the palette's name.
+fn file_stem(palette: &Palette) -> String {
+	palette.name()
+		.unwrap_or("palette")
+		.split(|c: char| !c.is_alphanumeric())
+		.filter(|part| !part.is_empty())
+		.map(|part| part.to_lowercase())
+		.collect::<Vec<_>>()
+		.join("-")
+}
@@ -0,0 +1,411 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides deterministic generators for producing sets of colors.
+//!
+//! Generators are seeded explicitly, and will always produce the same colors
+//! for the same seed and constraints, so generated palettes can be reproduced
+//! exactly.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use intervals::{
+	IntervalNumeric,
+	NumericInterval,
+};
+use progress::ProgressMonitor;
+use result::{
+	Error,
+	Result,
+};
+use space::{
+	delta_e,
+	Hsl,
+};
+use unit::Quantity;
+
+// Non-local imports.
+use color::Color;
+use interval::Interval;
+
+
+/// The golden angle in degrees.
+const GOLDEN_ANGLE: f32 = 137.507_77;
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Rng
+////////////////////////////////////////////////////////////////////////////////
+/// A small seeded pseudo-random number generator.
+///
+/// This uses the SplitMix64 algorithm, which is fast, has a full period, and
+/// is stable across platforms and versions of this library.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rng {
+	/// The generator state.
+	state: u64,
+}
+
+
+impl Rng {
+	/// Creates a new `Rng` with the given seed.
+	pub fn new(seed: u64) -> Self {
+		Rng {state: seed}
+	}
+
+	/// Returns the next random `u64`.
+	pub fn next_u64(&mut self) -> u64 {
+		self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+		let mut z = self.state;
+		z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+		z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+		z ^ (z >> 31)
+	}
+
+	/// Returns a random `f32` in the range [0, 1).
+	pub fn next_f32(&mut self) -> f32 {
+		(self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+	}
+
+	/// Returns a random `f32` between the given bounds.
+	pub fn range(&mut self, lower_bound: f32, upper_bound: f32) -> f32 {
+		lower_bound + (upper_bound - lower_bound) * self.next_f32()
+	}
+
+	/// Returns a random value lying within the bounds of the given interval.
+	pub fn sample<T>(&mut self, interval: &Interval<T>) -> T 
+		where T: IntervalNumeric
+	{
+		interval.sample(self.next_f32())
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// RandomPalette
+////////////////////////////////////////////////////////////////////////////////
+/// Generates random colors in HSL space under a set of constraints.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::generate::RandomPalette;
+///
+/// let gen = RandomPalette::new(8, 1234)
+/// 	.min_delta_e(10.0);
+///
+/// let colors = gen.generate().unwrap();
+/// assert_eq!(colors.len(), 8);
+/// assert_eq!(colors, gen.generate().unwrap()); // Same seed, same colors.
+/// ```
+#[derive(Debug, Clone)]
+pub struct RandomPalette {
+	/// The number of colors to generate.
+	count: usize,
+	/// The generator seed.
+	seed: u64,
+	/// The allowed hue range, in degrees.
+	hue: Interval<f32>,
+	/// The allowed saturation range.
+	saturation: Interval<f32>,
+	/// The allowed lightness range.
+	lightness: Interval<f32>,
+	/// The minimum CIE76 difference between any two generated colors.
+	min_delta_e: f32,
+	/// The maximum number of candidates to try per generated color.
+	max_attempts: usize,
+}
+
+
+impl RandomPalette {
+	/// Creates a new `RandomPalette` generating the given number of colors
+	/// from the given seed.
+	pub fn new(count: usize, seed: u64) -> Self {
+		RandomPalette {
+			count: count,
+			seed: seed,
+			hue: Interval::closed(0.0, 360.0),
+			saturation: Interval::closed(0.0, 1.0),
+			lightness: Interval::closed(0.0, 1.0),
+			min_delta_e: 0.0,
+			max_attempts: 1000,
+		}
+	}
+
+	/// Sets the allowed hue range, in degrees. The range runs around the hue
+	/// circle from its left point to its right point, so it may wrap past 
+	/// 360 degrees.
+	///
+	/// # Example
+	///
+	/// ```rust
+	/// extern crate interval;
+	/// extern crate palette;
+	///
+	/// use palette::generate::RandomPalette;
+	/// use palette::space::Hsl;
+	/// use interval::Interval;
+	///
+	/// # fn main() {
+	/// let colors = RandomPalette::new(16, 99)
+	/// 	.hue_range(Interval::closed(330.0, 390.0))
+	/// 	.saturation_range(Interval::closed(1.0, 1.0))
+	/// 	.lightness_range(Interval::closed(0.5, 0.5))
+	/// 	.generate()
+	/// 	.unwrap();
+	///
+	/// for color in colors {
+	/// 	let hue = Hsl::from(color).h;
+	/// 	assert!(hue >= 328.0 || hue <= 32.0);
+	/// }
+	/// # }
+	/// ```
+	pub fn hue_range(mut self, hue: Interval<f32>) -> Self {
+		self.hue = hue;
+		self
+	}
+
+	/// Sets the allowed saturation range.
+	pub fn saturation_range(mut self, saturation: Interval<f32>) -> Self {
+		self.saturation = saturation;
+		self
+	}
+
+	/// Sets the allowed lightness range.
+	pub fn lightness_range(mut self, lightness: Interval<f32>) -> Self {
+		self.lightness = lightness;
+		self
+	}
+
+	/// Sets the minimum color difference allowed between any two generated
+	/// colors.
+	pub fn min_delta_e(mut self, min_delta_e: f32) -> Self {
+		self.min_delta_e = min_delta_e;
+		self
+	}
+
+	/// Sets the maximum number of candidate colors to try for each generated
+	/// color before giving up.
+	pub fn max_attempts(mut self, max_attempts: usize) -> Self {
+		self.max_attempts = max_attempts;
+		self
+	}
+
+	/// Generates the colors.
+	///
+	/// # Errors
+	///
+	/// Returns a `ConstraintsUnsatisfiable` error if a color satisfying the
+	/// minimum difference could not be found within the maximum number of
+	/// attempts.
+	pub fn generate(&self) -> Result<Vec<Color>> {
+		self.generate_monitored(&ProgressMonitor::new())
+	}
+
+	/// Generates the colors like [`generate`], reporting progress to the 
+	/// given monitor.
+	///
+	/// # Errors
+	///
+	/// Returns a `ConstraintsUnsatisfiable` error as for [`generate`], or a 
+	/// `Cancelled` error if the monitor's task is cancelled.
+	///
+	/// [`generate`]: #method.generate
+	pub fn generate_monitored(&self, monitor: &ProgressMonitor) 
+		-> Result<Vec<Color>>
+	{
+		let mut rng = Rng::new(self.seed);
+		let mut colors: Vec<Color> = Vec::with_capacity(self.count);
+
+		while colors.len() < self.count {
+			monitor.report("generating", colors.len(), self.count)?;
+			let mut found = None;
+			for _ in 0..self.max_attempts {
+				monitor.check()?;
+				let candidate = Color::from(Hsl::new(
+					self.sample_hue(&mut rng),
+					rng.sample(&self.saturation),
+					rng.sample(&self.lightness),
+				));
+
+				if colors.iter()
+					.all(|&c| delta_e(c, candidate) >= self.min_delta_e)
+				{
+					found = Some(candidate);
+					break;
+				}
+			}
+
+			match found {
+				Some(color) => colors.push(color),
+				None => return Err(Error::ConstraintsUnsatisfiable),
+			}
+		}
+		Ok(colors)
+	}
+
+	/// Returns a random hue from the allowed hue range, in degrees.
+	fn sample_hue(&self, rng: &mut Rng) -> f32 {
+		let start = self.hue.left_point();
+		let mut extent = self.hue.right_point() - start;
+		// A range ending before it starts wraps around the hue circle.
+		if extent < 0.0 { extent = extent.rem_euclid(360.0); }
+		let extent = extent.min(360.0);
+		(start + extent * rng.next_f32()).rem_euclid(360.0)
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// HueSequence
+////////////////////////////////////////////////////////////////////////////////
+/// A low-discrepancy sequence used to distribute hues.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HueSequence {
+	/// Steps the hue by the golden angle (roughly 137.5 degrees) for each
+	/// color.
+	GoldenAngle,
+	/// Uses the base-2 Halton (van der Corput) sequence, which repeatedly
+	/// bisects the largest remaining gap in the hue circle.
+	Halton,
+}
+
+
+/// Returns the `index`th element of the Halton sequence for the given base,
+/// in the range [0, 1).
+///
+/// # Example
+///
+/// ```rust
+/// use palette::generate::halton;
+///
+/// assert_eq!(halton(1, 2), 0.5);
+/// assert_eq!(halton(2, 2), 0.25);
+/// assert_eq!(halton(3, 2), 0.75);
+/// ```
+pub fn halton(index: usize, base: usize) -> f32 {
+	assert!(base >= 2, "halton base must be at least 2");
+	let mut result = 0.0;
+	let mut f = 1.0;
+	let mut i = index;
+	while i > 0 {
+		f /= base as f32;
+		result += f * (i % base) as f32;
+		i /= base;
+	}
+	result
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// DistinctHues
+////////////////////////////////////////////////////////////////////////////////
+/// Generates maximally distinguishable categorical colors by spacing hues
+/// along a low-discrepancy sequence.
+///
+/// Saturation and lightness are distributed within their ranges using the
+/// base-3 and base-5 Halton sequences, so that neighboring colors also differ
+/// in tone when the ranges allow it.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::generate::{DistinctHues, HueSequence};
+///
+/// let colors = DistinctHues::new(6, HueSequence::GoldenAngle).generate();
+/// assert_eq!(colors.len(), 6);
+/// ```
+#[derive(Debug, Clone)]
+pub struct DistinctHues {
+	/// The number of colors to generate.
+	count: usize,
+	/// The sequence used to distribute hues.
+	sequence: HueSequence,
+	/// The hue of the first color, in degrees.
+	start_hue: f32,
+	/// The allowed saturation range.
+	saturation: Interval<f32>,
+	/// The allowed lightness range.
+	lightness: Interval<f32>,
+}
+
+
+impl DistinctHues {
+	/// Creates a new `DistinctHues` generator producing the given number of
+	/// colors.
+	pub fn new(count: usize, sequence: HueSequence) -> Self {
+		DistinctHues {
+			count: count,
+			sequence: sequence,
+			start_hue: 0.0,
+			saturation: Interval::closed(0.65, 0.65),
+			lightness: Interval::closed(0.5, 0.5),
+		}
+	}
+
+	/// Sets the hue of the first generated color. Plain numbers are read as
+	/// degrees.
+	pub fn start_hue<Q>(mut self, start_hue: Q) -> Self 
+		where Q: Into<Quantity>
+	{
+		self.start_hue = start_hue.into().as_degrees();
+		self
+	}
+
+	/// Sets the saturation range.
+	pub fn saturation_range(mut self, saturation: Interval<f32>) -> Self {
+		self.saturation = saturation;
+		self
+	}
+
+	/// Sets the lightness range.
+	pub fn lightness_range(mut self, lightness: Interval<f32>) -> Self {
+		self.lightness = lightness;
+		self
+	}
+
+	/// Returns the hue of the `index`th generated color, in degrees.
+	pub fn hue(&self, index: usize) -> f32 {
+		let offset = match self.sequence {
+			HueSequence::GoldenAngle => GOLDEN_ANGLE * index as f32,
+			HueSequence::Halton      => 360.0 * halton(index, 2),
+		};
+		(self.start_hue + offset) % 360.0
+	}
+
+	/// Generates the colors.
+	pub fn generate(&self) -> Vec<Color> {
+		(0..self.count)
+			.map(|i| Color::from(Hsl::new(
+				self.hue(i),
+				self.saturation.sample(halton(i + 1, 3)),
+				self.lightness.sample(halton(i + 1, 5)),
+			)))
+			.collect()
+	}
+}
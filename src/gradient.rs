@@ -0,0 +1,291 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides gradients keyed by intervals of a parameter domain.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use ramp::RampGenerator;
+use space::WideColor;
+
+// Non-local imports.
+use color::Color;
+use interval::Interval;
+
+// Standard imports.
+use std::cmp::Ordering;
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Easing
+////////////////////////////////////////////////////////////////////////////////
+/// Determines how a gradient segment progresses between its colors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Easing {
+	/// The segment progresses at a constant rate.
+	Linear,
+	/// The segment progresses slowly at its ends and quickly in its middle.
+	Smooth,
+	/// The segment holds its start color until its end.
+	Step,
+}
+
+
+impl Default for Easing {
+	fn default() -> Self {
+		Easing::Linear
+	}
+}
+
+
+impl Easing {
+	/// Returns the eased progress for the given linear progress in the range
+	/// [0, 1].
+	pub fn apply(&self, t: f32) -> f32 {
+		let t = t.max(0.0).min(1.0);
+		match *self {
+			Easing::Linear => t,
+			Easing::Smooth => t * t * (3.0 - 2.0 * t),
+			Easing::Step => if t < 1.0 { 0.0 } else { 1.0 },
+		}
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// GradientSegment
+////////////////////////////////////////////////////////////////////////////////
+/// A gradient segment interpolating between two colors.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GradientSegment {
+	/// The color at the start of the segment.
+	pub start: WideColor,
+	/// The color at the end of the segment.
+	pub end: WideColor,
+	/// The easing of the segment.
+	pub easing: Easing,
+}
+
+
+impl GradientSegment {
+	/// Creates a new linear `GradientSegment`.
+	pub fn new(start: WideColor, end: WideColor) -> Self {
+		GradientSegment {start: start, end: end, easing: Easing::Linear}
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// GradientMap
+////////////////////////////////////////////////////////////////////////////////
+/// A mapping from intervals of a parameter domain to values, such as colors 
+/// or gradient segments.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::gradient::GradientMap;
+/// use palette::ramp::RampGenerator;
+/// use palette::space::WideColor;
+///
+/// let black = WideColor::from(Color::new(0, 0, 0));
+/// let white = WideColor::from(Color::new(255, 255, 255));
+/// let red = WideColor::from(Color::new(255, 0, 0));
+///
+/// let stops = [(0.0, black), (0.5, white), (1.0, red)];
+/// let map = GradientMap::from_stops(&stops);
+/// assert_eq!(map.len(), 2);
+///
+/// let generator = RampGenerator::new();
+/// assert_eq!(map.sample(0.5, &generator).unwrap().quantize(), 
+/// 	Color::new(255, 255, 255));
+/// assert_eq!(map.sample(1.0, &generator).unwrap().quantize(), 
+/// 	Color::new(255, 0, 0));
+/// assert!(map.sample(1.5, &generator).is_none());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct GradientMap<T> {
+	/// The intervals and their values, ordered by their left points.
+	entries: Vec<(Interval<f32>, T)>,
+}
+
+
+impl<T> Default for GradientMap<T> {
+	fn default() -> Self {
+		GradientMap {entries: Vec::new()}
+	}
+}
+
+
+impl<T> GradientMap<T> {
+	/// Creates a new, empty `GradientMap`.
+	pub fn new() -> Self {
+		Default::default()
+	}
+
+	/// Maps the given interval to the given value. If intervals overlap, 
+	/// lookups return the value of the earliest inserted interval.
+	pub fn insert(&mut self, interval: Interval<f32>, value: T) {
+		let left = interval.left_point();
+		let index = self.entries.iter()
+			.position(|&(ref i, _)| i.left_point()
+				.partial_cmp(&left) == Some(Ordering::Greater))
+			.unwrap_or(self.entries.len());
+		self.entries.insert(index, (interval, value));
+	}
+
+	/// Returns the interval and value containing the given parameter.
+	pub fn entry(&self, t: f32) -> Option<&(Interval<f32>, T)> {
+		self.entries.iter().find(|&&(ref i, _)| i.contains(&t))
+	}
+
+	/// Returns the value mapped to the interval containing the given 
+	/// parameter.
+	pub fn get(&self, t: f32) -> Option<&T> {
+		self.entry(t).map(|&(_, ref value)| value)
+	}
+
+	/// Returns the intervals and their values, ordered by their left points.
+	pub fn entries(&self) -> &[(Interval<f32>, T)] {
+		&self.entries
+	}
+
+	/// Returns the number of intervals in the map.
+	pub fn len(&self) -> usize {
+		self.entries.len()
+	}
+
+	/// Returns whether the map has no intervals.
+	pub fn is_empty(&self) -> bool {
+		self.entries.is_empty()
+	}
+
+	/// Returns the smallest closed interval containing all of the map's 
+	/// intervals.
+	pub fn domain(&self) -> Option<Interval<f32>> {
+		let left = self.entries.iter()
+			.map(|&(ref i, _)| i.left_point())
+			.fold(None, |min: Option<f32>, p| Some(min.map_or(p, |m| m.min(p))));
+		let right = self.entries.iter()
+			.map(|&(ref i, _)| i.right_point())
+			.fold(None, |max: Option<f32>, p| Some(max.map_or(p, |m| m.max(p))));
+		match (left, right) {
+			(Some(l), Some(r)) => Some(Interval::closed(l, r)),
+			_ => None,
+		}
+	}
+}
+
+
+impl GradientMap<Color> {
+	/// Creates a piecewise-constant gradient from the given colors, with each
+	/// color covering an equal part of the domain [0, 1].
+	pub fn bands(colors: &[Color]) -> Self {
+		let mut map = GradientMap::new();
+		let count = colors.len() as f32;
+		for (i, &color) in colors.iter().enumerate() {
+			let (l, r) = (i as f32 / count, (i + 1) as f32 / count);
+			let interval = if i + 1 == colors.len() {
+				Interval::closed(l, r)
+			} else {
+				Interval::right_open(l, r)
+			};
+			map.insert(interval, color);
+		}
+		map
+	}
+}
+
+
+impl GradientMap<GradientSegment> {
+	/// Creates a gradient interpolating between the given stops, given as 
+	/// positions and colors. The stops are sorted by position, and each 
+	/// adjacent pair forms a linear segment.
+	pub fn from_stops(stops: &[(f32, WideColor)]) -> Self {
+		let mut stops = stops.to_vec();
+		stops.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+
+		let mut map = GradientMap::new();
+		if stops.len() == 1 {
+			let (p, color) = stops[0];
+			map.insert(Interval::closed(p, p), GradientSegment::new(color, color));
+		}
+		for (i, pair) in stops.windows(2).enumerate() {
+			let ((l, start), (r, end)) = (pair[0], pair[1]);
+			let interval = if i + 2 == stops.len() {
+				Interval::closed(l, r)
+			} else {
+				Interval::right_open(l, r)
+			};
+			map.insert(interval, GradientSegment::new(start, end));
+		}
+		map
+	}
+
+	/// Creates a gradient interpolating between the given colors, which are
+	/// evenly spaced over the domain [0, 1].
+	pub fn evenly_spaced(colors: &[WideColor]) -> Self {
+		let last = colors.len().saturating_sub(1).max(1) as f32;
+		let stops: Vec<_> = colors.iter()
+			.enumerate()
+			.map(|(i, &color)| (i as f32 / last, color))
+			.collect();
+		GradientMap::from_stops(&stops)
+	}
+
+	/// Returns the color of the gradient at the given parameter, 
+	/// interpolated using the given generator, or None if the parameter lies
+	/// outside of the gradient.
+	pub fn sample(&self, t: f32, generator: &RampGenerator) 
+		-> Option<WideColor>
+	{
+		self.entry(t).map(|&(ref interval, ref segment)| {
+			let (l, r) = (interval.left_point(), interval.right_point());
+			let local = if r > l { (t - l) / (r - l) } else { 0.0 };
+			match segment.easing.apply(local) {
+				e if e <= 0.0 => segment.start,
+				e if e >= 1.0 => segment.end,
+				e => generator.interpolate_wide(segment.start, segment.end, e),
+			}
+		})
+	}
+
+	/// Returns the positions and colors of the gradient's stops.
+	pub fn stops(&self) -> Vec<(f32, WideColor)> {
+		let mut stops: Vec<_> = self.entries.iter()
+			.map(|&(ref i, ref segment)| (i.left_point(), segment.start))
+			.collect();
+		if let Some(&(ref i, ref segment)) = self.entries.last() {
+			if i.right_point() > i.left_point() || stops.is_empty() {
+				stops.push((i.right_point(), segment.end));
+			}
+		}
+		stops
+	}
+}
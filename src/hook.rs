@@ -0,0 +1,168 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides hooks for observing and validating palette operations.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use ::Palette;
+use operation::OperationInfo;
+use result::Result;
+
+// Standard imports.
+use std::fmt;
+use std::mem;
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// OperationHook
+////////////////////////////////////////////////////////////////////////////////
+/// A callback invoked before and after operations are applied to a palette.
+///
+/// Hooks allow integrations to implement custom validation or external 
+/// syncing without modifying the palette's operations.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::hook::OperationHook;
+/// use palette::operation::*;
+/// use palette::result::{Error, Result};
+///
+/// struct NoDeletes;
+///
+/// impl OperationHook for NoDeletes {
+/// 	fn before(&mut self, info: &OperationInfo, _: &Palette) -> Result<()> {
+/// 		if info.name == "Remove Cell" {
+/// 			return Err(Error::OperationVetoed("deletes are locked".into()));
+/// 		}
+/// 		Ok(())
+/// 	}
+/// }
+///
+/// let mut pal = Palette::new("Example", Format::Default, true);
+/// pal.register_hook(Box::new(NoDeletes));
+///
+/// pal.apply(Box::new(InsertColor::new(Color::new(12, 50, 78)))).unwrap();
+/// let delete = DeleteCell::new(Address::new(0, 0, 0));
+/// assert!(pal.apply(Box::new(delete)).is_err());
+/// assert_eq!(pal.len(), 1);
+/// ```
+pub trait OperationHook {
+	/// Called before an operation is applied to the palette. Returning an 
+	/// error vetoes the operation, leaving the palette unchanged.
+	fn before(&mut self, info: &OperationInfo, palette: &Palette) -> Result<()> {
+		let _ = (info, palette);
+		Ok(())
+	}
+
+	/// Called after an operation has been applied to the palette.
+	fn after(&mut self, info: &OperationInfo, palette: &Palette) {
+		let _ = (info, palette);
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// HookId
+////////////////////////////////////////////////////////////////////////////////
+/// Identifies a hook registered with a palette.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HookId(usize);
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Hooks
+////////////////////////////////////////////////////////////////////////////////
+/// The hooks registered with a palette.
+#[derive(Default)]
+pub(crate) struct Hooks {
+	/// The registered hooks, in registration order.
+	hooks: Vec<(HookId, Box<OperationHook>)>,
+	/// The id to assign to the next registered hook.
+	next_id: usize,
+}
+
+
+impl Hooks {
+	/// Registers a hook, returning its id.
+	pub(crate) fn register(&mut self, hook: Box<OperationHook>) -> HookId {
+		let id = HookId(self.next_id);
+		self.next_id += 1;
+		self.hooks.push((id, hook));
+		id
+	}
+
+	/// Unregisters the hook with the given id, returning it.
+	pub(crate) fn unregister(&mut self, id: HookId) 
+		-> Option<Box<OperationHook>>
+	{
+		self.hooks.iter()
+			.position(|&(hook_id, _)| hook_id == id)
+			.map(|i| self.hooks.remove(i).1)
+	}
+
+	/// Returns the number of registered hooks.
+	pub(crate) fn len(&self) -> usize {
+		self.hooks.len()
+	}
+}
+
+
+impl fmt::Debug for Hooks {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_list()
+			.entries(self.hooks.iter().map(|&(id, _)| id))
+			.finish()
+	}
+}
+
+
+/// Invokes the `before` method of each hook registered with the palette, 
+/// stopping at the first veto.
+pub(crate) fn before(palette: &mut Palette, info: &OperationInfo) 
+	-> Result<()>
+{
+	let mut hooks = mem::replace(&mut palette.hooks.hooks, Vec::new());
+	let result = hooks.iter_mut()
+		.map(|&mut (_, ref mut hook)| hook.before(info, palette))
+		.collect();
+	palette.hooks.hooks = hooks;
+	result
+}
+
+
+/// Invokes the `after` method of each hook registered with the palette.
+pub(crate) fn after(palette: &mut Palette, info: &OperationInfo) {
+	let mut hooks = mem::replace(&mut palette.hooks.hooks, Vec::new());
+	for &mut (_, ref mut hook) in &mut hooks {
+		hook.after(info, palette);
+	}
+	palette.hooks.hooks = hooks;
+}
@@ -0,0 +1,167 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides ICC profile based color conversion.
+//!
+//! This module is only available when the `icc` feature is enabled. It is used
+//! to convert device colors (RGB, CMYK, or grayscale values tagged with an ICC
+//! profile) into the palette's working color space. Lab values stored relative
+//! to the ICC D50 white point do not require a profile, and can be converted
+//! with `Xyz::from_d50_lab` instead.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use result::{
+	Error,
+	Result,
+};
+use space::{
+	RgbSpace,
+	WideColor,
+	Xyz,
+};
+
+// Non-local imports.
+use lcms2::{
+	ColorSpaceSignature,
+	Intent,
+	PixelFormat,
+	Profile,
+	Transform,
+};
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// DeviceColor
+////////////////////////////////////////////////////////////////////////////////
+/// A device-dependent color value, with channels in the range [0, 1].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DeviceColor {
+	/// A grayscale value.
+	Gray(f32),
+	/// An RGB value.
+	Rgb([f32; 3]),
+	/// A CMYK value.
+	Cmyk([f32; 4]),
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// IccConverter
+////////////////////////////////////////////////////////////////////////////////
+/// Converts device colors described by an ICC profile into a working color
+/// space.
+pub struct IccConverter {
+	/// The transform from the source profile into D50 XYZ.
+	transform: ProfileTransform,
+	/// The color space to convert into.
+	working_space: RgbSpace,
+}
+
+
+/// A transform from a source profile's device colors into XYZ.
+enum ProfileTransform {
+	Gray(Transform<f32, [f32; 3]>),
+	Rgb(Transform<[f32; 3], [f32; 3]>),
+	Cmyk(Transform<[f32; 4], [f32; 3]>),
+}
+
+
+impl IccConverter {
+	/// Creates a new `IccConverter` from the given ICC profile data, such as a
+	/// profile embedded in an image or palette file.
+	///
+	/// # Errors
+	///
+	/// Returns an `InvalidProfile` error if the profile could not be parsed or
+	/// describes an unsupported device color space.
+	pub fn new(profile: &[u8], working_space: RgbSpace) -> Result<Self> {
+		let source = Profile::new_icc(profile)
+			.map_err(|_| Error::InvalidProfile)?;
+		let xyz = Profile::new_xyz();
+		let intent = Intent::RelativeColorimetric;
+
+		let transform = match source.color_space() {
+			ColorSpaceSignature::GrayData => ProfileTransform::Gray(
+				Transform::new(
+					&source, PixelFormat::GRAY_FLT,
+					&xyz, PixelFormat::XYZ_FLT,
+					intent)
+				.map_err(|_| Error::InvalidProfile)?),
+
+			ColorSpaceSignature::RgbData => ProfileTransform::Rgb(
+				Transform::new(
+					&source, PixelFormat::RGB_FLT,
+					&xyz, PixelFormat::XYZ_FLT,
+					intent)
+				.map_err(|_| Error::InvalidProfile)?),
+
+			ColorSpaceSignature::CmykData => ProfileTransform::Cmyk(
+				Transform::new(
+					&source, PixelFormat::CMYK_FLT,
+					&xyz, PixelFormat::XYZ_FLT,
+					intent)
+				.map_err(|_| Error::InvalidProfile)?),
+
+			_ => return Err(Error::InvalidProfile),
+		};
+
+		Ok(IccConverter {
+			transform: transform,
+			working_space: working_space,
+		})
+	}
+
+	/// Converts the given device color into the working color space.
+	///
+	/// # Errors
+	///
+	/// Returns an `InvalidProfile` error if the color's channels do not match
+	/// the profile's device color space.
+	pub fn convert(&self, color: DeviceColor) -> Result<WideColor> {
+		let mut out = [[0.0f32; 3]];
+		match (&self.transform, color) {
+			(&ProfileTransform::Gray(ref t), DeviceColor::Gray(v))
+				=> t.transform_pixels(&[v], &mut out),
+
+			(&ProfileTransform::Rgb(ref t), DeviceColor::Rgb(v))
+				=> t.transform_pixels(&[v], &mut out),
+
+			// Floating point CMYK is expressed as ink percentages.
+			(&ProfileTransform::Cmyk(ref t), DeviceColor::Cmyk(v))
+				=> t.transform_pixels(
+					&[[v[0] * 100.0, v[1] * 100.0, v[2] * 100.0, v[3] * 100.0]],
+					&mut out),
+
+			_ => return Err(Error::InvalidProfile),
+		}
+
+		let [x, y, z] = out[0];
+		let xyz = Xyz::new(x, y, z).adapt_from_d50();
+		Ok(WideColor::from_xyz(xyz, self.working_space))
+	}
+}
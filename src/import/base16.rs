@@ -0,0 +1,133 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides importing of Base16 and Base24 YAML schemes.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use ::Palette;
+use format::Format;
+use import::{
+	invalid,
+	push_color,
+};
+use utilities::parse_hex;
+
+// Non-local imports.
+use color::Color;
+
+// Standard imports.
+use std::collections::BTreeMap;
+use std::io;
+use std::io::{
+	BufRead,
+	BufReader,
+};
+
+
+/// Reads a palette from a Base16 or Base24 YAML scheme. The palette is named
+/// after the scheme, or the given name if the scheme has none.
+///
+/// Both the original format, with `scheme` and `baseXX` keys at the top 
+/// level, and the newer format, with `name` and a nested `palette` map, are
+/// supported. Each `baseXX` color is stored in order on the first line of the
+/// palette, in a cell named after its key. Other keys are ignored.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::import::read_base16_scheme;
+///
+/// let yaml = "\
+/// scheme: \"Ocean\"
+/// author: \"Chris Kempson\"
+/// base00: \"2b303b\"
+/// base01: \"343d46\" # Lighter background
+/// ";
+/// let pal = read_base16_scheme("Scheme", &mut yaml.as_bytes()).unwrap();
+///
+/// assert_eq!(pal.name(), Some("Ocean"));
+/// assert_eq!(pal.address_of("base01"), Some(Address::new(0, 0, 1)));
+/// assert_eq!(pal.color(Address::new(0, 0, 1)), 
+/// 	Some(Color::new(0x34, 0x3D, 0x46)));
+/// ```
+pub fn read_base16_scheme<R>(name: &str, in_buf: &mut R) 
+	-> io::Result<Palette>
+	where R: io::Read
+{
+	let mut scheme_name = None;
+	let mut colors: BTreeMap<u8, Color> = BTreeMap::new();
+	for (i, line) in BufReader::new(in_buf).lines().enumerate() {
+		let line = line?;
+		let mut parts = line.trim().splitn(2, ':');
+		let key = parts.next().unwrap_or("").trim();
+		let value = match parts.next().map(yaml_scalar) {
+			Some(value) if !value.is_empty() => value,
+			_ => continue,
+		};
+
+		match key {
+			"scheme" | "name" => scheme_name = Some(value),
+			_ if key.starts_with("base") && key.len() == 6 => {
+				let index = u8::from_str_radix(&key[4..], 16)
+					.map_err(|_| invalid(
+						format!("line {}: invalid key '{}'", i + 1, key)))?;
+				let color = parse_hex(&value)
+					.ok_or_else(|| invalid(
+						format!("line {}: invalid color '{}'", i + 1, value)))?;
+				colors.insert(index, color);
+			},
+			_ => (),
+		}
+	}
+	if colors.is_empty() {
+		return Err(invalid("expected base00 to base0F colors"));
+	}
+
+	let name = scheme_name.as_ref().map_or(name, |n| &n[..]);
+	let mut palette = Palette::new(name, Format::Default, true);
+	for (index, color) in colors {
+		let address = push_color(&mut palette, color)?;
+		palette.set_name(address, format!("base{:02X}", index));
+	}
+	Ok(palette)
+}
+
+
+/// Returns the text of a YAML scalar value, removing quotes and trailing 
+/// comments.
+fn yaml_scalar(value: &str) -> String {
+	let value = value.trim();
+	for &quote in &['"', '\''] {
+		if value.starts_with(quote) {
+			let rest = &value[1..];
+			return rest.find(quote)
+				.map_or(rest, |end| &rest[..end])
+				.to_owned();
+		}
+	}
+	value.split(" #").next().unwrap_or("").trim().to_owned()
+}
@@ -0,0 +1,109 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides importing of the JSON styles format used by Figma plugins.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use ::Palette;
+use format::Format;
+use import::{
+	invalid,
+	push_color,
+	unit_color,
+};
+
+// Non-local imports.
+use serde_json;
+use serde_json::Value;
+
+// Standard imports.
+use std::io;
+
+
+/// Reads a palette with the given name from a list of Figma color styles.
+///
+/// The styles may be given as an array, or as the `styles` array of an 
+/// object. Each style has a `name`, an optional `description`, and either a
+/// `color` object of `r`, `g`, `b`, and `a` channels from 0 to 1, or a 
+/// `paints` array whose first solid paint gives the color. Style names and
+/// descriptions are stored as cell names and descriptions. Alpha is ignored.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::import::read_figma_styles;
+///
+/// let json = r#"{"styles": [{
+/// 	"name": "Brand/Primary",
+/// 	"description": "Buttons and links",
+/// 	"paints": [{"type": "SOLID", "color": {"r": 0, "g": 0.4, "b": 1}}]
+/// }]}"#;
+/// let pal = read_figma_styles("Styles", &mut json.as_bytes()).unwrap();
+///
+/// let a = Address::new(0, 0, 0);
+/// assert_eq!(pal.cell_name(a), Some("Brand/Primary"));
+/// assert_eq!(pal.cell_description(a), Some("Buttons and links"));
+/// assert_eq!(pal.color(a), Some(Color::new(0, 102, 255)));
+/// ```
+pub fn read_figma_styles<R>(name: &str, in_buf: &mut R) -> io::Result<Palette>
+	where R: io::Read
+{
+	let json: Value = serde_json::from_reader(in_buf)
+		.map_err(|e| invalid(e.to_string()))?;
+	let styles = match json {
+		Value::Array(styles) => styles,
+		Value::Object(mut map) => match map.remove("styles") {
+			Some(Value::Array(styles)) => styles,
+			_ => return Err(invalid("expected a 'styles' array")),
+		},
+		_ => return Err(invalid("expected an array of styles")),
+	};
+
+	let mut palette = Palette::new(name, Format::Default, true);
+	for (i, style) in styles.iter().enumerate() {
+		let color = style.get("color")
+			.or_else(|| style.get("paints")
+				.and_then(|paints| paints.as_array())
+				.and_then(|paints| paints.iter()
+					.find(|p| p.get("type").map_or(true, |t| t == "SOLID")))
+				.and_then(|paint| paint.get("color")))
+			.and_then(|c| unit_color(c, ["r", "g", "b"]))
+			.ok_or_else(|| invalid(format!("style {}: missing color", i)))?;
+
+		let address = push_color(&mut palette, color)?;
+		if let Some(name) = style.get("name").and_then(|n| n.as_str()) {
+			palette.set_name(address, name);
+		}
+		let description = style.get("description")
+			.and_then(|d| d.as_str())
+			.filter(|d| !d.is_empty());
+		if let Some(text) = description {
+			palette.set_description(address, Some(text.to_owned()));
+		}
+	}
+	Ok(palette)
+}
@@ -0,0 +1,246 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides importing of the color tables of indexed GIF, PNG, and BMP images.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use ::Palette;
+use format::Format;
+use import::{
+	invalid,
+	push_color,
+};
+
+// Non-local imports.
+use color::Color;
+
+// Standard imports.
+use std::io;
+
+
+/// Reads a palette with the given name from the color table of an indexed 
+/// GIF, PNG, or BMP image.
+///
+/// Only the color table is read; the image's pixels are not decoded. Each 
+/// entry of the table is stored in order, so the index of each color in the
+/// image is the index of its cell in the palette. Duplicate and unused entries
+/// are kept. GIF images use their global color table, or the local table of 
+/// their first frame if they have none. PNG images use their `PLTE` chunk, 
+/// and transparency is ignored.
+///
+/// # Errors
+///
+/// Returns an `InvalidData` error if the image is not a GIF, PNG, or BMP 
+/// image, or if it has no color table.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::import::read_color_table;
+///
+/// let mut gif = b"GIF89a\x01\x00\x01\x00\x81\x00\x00".to_vec();
+/// gif.extend_from_slice(&[0xFF, 0, 0, 0, 0xFF, 0, 0, 0, 0xFF, 0xFF, 0, 0]);
+/// gif.extend_from_slice(b"\x3B");
+/// let pal = read_color_table("Sprite", &mut &gif[..]).unwrap();
+///
+/// assert_eq!(pal.len(), 4);
+/// assert_eq!(pal.color(Address::new(0, 0, 2)), Some(Color::new(0, 0, 255)));
+/// assert_eq!(pal.color(Address::new(0, 0, 3)), Some(Color::new(255, 0, 0)));
+/// ```
+pub fn read_color_table<R>(name: &str, in_buf: &mut R) -> io::Result<Palette>
+	where R: io::Read
+{
+	let mut bytes = Vec::new();
+	in_buf.read_to_end(&mut bytes)?;
+
+	let colors = if bytes.starts_with(b"GIF87a") 
+		|| bytes.starts_with(b"GIF89a") 
+	{
+		gif_color_table(&bytes)?
+	} else if bytes.starts_with(PNG_SIGNATURE) {
+		png_color_table(&bytes)?
+	} else if bytes.starts_with(b"BM") {
+		bmp_color_table(&bytes)?
+	} else {
+		return Err(invalid("expected a GIF, PNG, or BMP image"));
+	};
+
+	let mut palette = Palette::new(name, Format::Default, true);
+	for color in colors {
+		push_color(&mut palette, color)?;
+	}
+	Ok(palette)
+}
+
+
+/// The signature at the start of every PNG file.
+pub(crate) const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1A\n";
+
+
+/// Returns the slice of the given length at the given offset, or an error if
+/// the image ends before it.
+fn slice(bytes: &[u8], offset: usize, len: usize) -> io::Result<&[u8]> {
+	offset.checked_add(len)
+		.and_then(|end| bytes.get(offset..end))
+		.ok_or_else(|| invalid("unexpected end of image"))
+}
+
+
+/// Returns the colors of a table of red, green, and blue bytes.
+fn rgb_triples(table: &[u8]) -> Vec<Color> {
+	table.chunks(3)
+		.filter(|rgb| rgb.len() == 3)
+		.map(|rgb| Color::new(rgb[0], rgb[1], rgb[2]))
+		.collect()
+}
+
+
+////////////////////////////////////////////////////////////////////////////////
+// GIF
+////////////////////////////////////////////////////////////////////////////////
+
+/// Returns the global color table of a GIF image, or the local color table of
+/// its first frame.
+fn gif_color_table(bytes: &[u8]) -> io::Result<Vec<Color>> {
+	// The flags of the logical screen descriptor follow the header.
+	let flags = slice(bytes, 10, 1)?[0];
+	let mut offset = 13;
+	if flags & 0x80 != 0 {
+		let len = 3 << ((flags & 0x07) + 1);
+		return Ok(rgb_triples(slice(bytes, offset, len)?));
+	}
+
+	loop {
+		match slice(bytes, offset, 1)?[0] {
+			// Extension: skip the label, then each sub-block.
+			0x21 => {
+				offset += 2;
+				loop {
+					let size = slice(bytes, offset, 1)?[0] as usize;
+					offset += 1 + size;
+					if size == 0 { break; }
+				}
+			},
+			// Image descriptor: the flags follow the position and size.
+			0x2C => {
+				let flags = slice(bytes, offset + 9, 1)?[0];
+				if flags & 0x80 == 0 { break; }
+				let len = 3 << ((flags & 0x07) + 1);
+				return Ok(rgb_triples(slice(bytes, offset + 10, len)?));
+			},
+			_ => break,
+		}
+	}
+	Err(invalid("the GIF image has no color table"))
+}
+
+
+////////////////////////////////////////////////////////////////////////////////
+// PNG
+////////////////////////////////////////////////////////////////////////////////
+
+/// Returns the palette of a PNG image.
+fn png_color_table(bytes: &[u8]) -> io::Result<Vec<Color>> {
+	let mut offset = PNG_SIGNATURE.len();
+	while offset < bytes.len() {
+		let header = slice(bytes, offset, 8)?;
+		let len = u32_be(&header[0..4]) as usize;
+		match &header[4..8] {
+			b"PLTE" => return Ok(rgb_triples(slice(bytes, offset + 8, len)?)),
+			b"IDAT" | b"IEND" => break,
+			_ => (),
+		}
+		// Skip the chunk's length, type, data, and CRC.
+		offset = offset.saturating_add(len).saturating_add(12);
+	}
+	Err(invalid("the PNG image has no palette"))
+}
+
+
+/// Returns the big-endian integer stored in the given bytes.
+fn u32_be(bytes: &[u8]) -> u32 {
+	bytes.iter().fold(0, |value, &b| value << 8 | b as u32)
+}
+
+
+////////////////////////////////////////////////////////////////////////////////
+// BMP
+////////////////////////////////////////////////////////////////////////////////
+
+/// Returns the color table of a BMP image.
+fn bmp_color_table(bytes: &[u8]) -> io::Result<Vec<Color>> {
+	let pixels = u32_le(slice(bytes, 10, 4)?) as usize;
+	let header = u32_le(slice(bytes, 14, 4)?) as usize;
+
+	// OS/2 headers store 16-bit sizes and three-byte table entries.
+	let (depth, used, mut start, entry) = if header == 12 {
+		(u16_le(slice(bytes, 24, 2)?) as u32, 0, 14 + header, 3)
+	} else {
+		let depth = u16_le(slice(bytes, 28, 2)?) as u32;
+		let compression = u32_le(slice(bytes, 30, 4)?);
+		let used = u32_le(slice(bytes, 46, 4)?) as usize;
+		// Version 1 headers are followed by the channel masks, if any.
+		let masks = match (header, compression) {
+			(40, 3) => 12,
+			(40, 6) => 16,
+			_ => 0,
+		};
+		(depth, used, header.saturating_add(14 + masks), 4)
+	};
+
+	let count = match (used, depth) {
+		(0, depth) if depth <= 8 => 1 << depth,
+		(0, _) => return Err(invalid("the BMP image has no color table")),
+		(used, _) => used,
+	};
+	// The table ends at the pixels, and cannot extend past the file.
+	let end = pixels.min(bytes.len());
+	let count = count.min(end.saturating_sub(start) / entry);
+	if count == 0 {
+		return Err(invalid("the BMP image has no color table"));
+	}
+
+	let mut colors = Vec::with_capacity(count);
+	for _ in 0..count {
+		let bgr = slice(bytes, start, 3)?;
+		colors.push(Color::new(bgr[2], bgr[1], bgr[0]));
+		start += entry;
+	}
+	Ok(colors)
+}
+
+
+/// Returns the little-endian integer stored in the given bytes.
+fn u32_le(bytes: &[u8]) -> u32 {
+	bytes.iter().rev().fold(0, |value, &b| value << 8 | b as u32)
+}
+
+
+/// Returns the little-endian integer stored in the given bytes.
+fn u16_le(bytes: &[u8]) -> u16 {
+	u32_le(bytes) as u16
+}
@@ -0,0 +1,327 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides importers for reading palettes from other formats.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Submodules.
+#[warn(missing_docs)]
+mod base16;
+#[warn(missing_docs)]
+mod figma;
+#[warn(missing_docs)]
+mod indexed;
+#[cfg(feature = "openraster")]
+#[warn(missing_docs)]
+mod openraster;
+#[warn(missing_docs)]
+mod share;
+#[warn(missing_docs)]
+mod sketch;
+#[cfg(feature = "net")]
+#[warn(missing_docs)]
+mod url;
+
+// Submodule re-exports.
+pub use self::base16::read_base16_scheme;
+pub use self::figma::read_figma_styles;
+pub use self::indexed::read_color_table;
+#[cfg(feature = "openraster")]
+pub use self::openraster::OpenRasterExtractor;
+pub use self::share::{
+	read_share_url,
+	share_url_colors,
+};
+pub use self::sketch::read_sketch_palette;
+#[cfg(feature = "net")]
+pub use self::url::read_url;
+#[cfg(all(feature = "async", feature = "net"))]
+pub(crate) use self::url::{
+	download,
+	read_download,
+};
+
+// Local imports.
+use ::Palette;
+use address::Address;
+use import::indexed::PNG_SIGNATURE;
+use expression::Expression;
+use format::Format;
+use provenance::Provenance;
+use trace::Span;
+
+// Non-local imports.
+use color::Color;
+use serde_json::Value;
+
+// Standard imports.
+use std::io;
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// ImportFormat
+////////////////////////////////////////////////////////////////////////////////
+/// An enum of the formats palettes can be imported from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ImportFormat {
+	/// The default palette format.
+	Palette,
+	/// The JSON styles format used by Figma plugins.
+	Figma,
+	/// A Sketch `.sketchpalette` file.
+	Sketch,
+	/// A Base16 or Base24 YAML scheme.
+	Base16,
+	/// The color table of an indexed GIF, PNG, or BMP image.
+	ColorTable,
+	/// The colors of the merged image of an OpenRaster `.ora` file.
+	#[cfg(feature = "openraster")]
+	OpenRaster,
+}
+
+
+impl ImportFormat {
+	/// Returns all of the supported import formats.
+	pub fn all() -> Vec<ImportFormat> {
+		vec![
+			ImportFormat::Palette,
+			ImportFormat::Figma,
+			ImportFormat::Sketch,
+			ImportFormat::Base16,
+			ImportFormat::ColorTable,
+			#[cfg(feature = "openraster")]
+			ImportFormat::OpenRaster,
+		]
+	}
+
+	/// Returns the name of the format.
+	pub fn name(&self) -> &'static str {
+		match *self {
+			ImportFormat::Palette    => "palette",
+			ImportFormat::Figma      => "figma",
+			ImportFormat::Sketch     => "sketch",
+			ImportFormat::Base16     => "base16",
+			ImportFormat::ColorTable => "indexed",
+			#[cfg(feature = "openraster")]
+			ImportFormat::OpenRaster => "ora",
+		}
+	}
+
+	/// Returns the format with the given name, ignoring case.
+	pub fn from_name(name: &str) -> Option<ImportFormat> {
+		ImportFormat::all()
+			.into_iter()
+			.find(|f| f.name().eq_ignore_ascii_case(name))
+	}
+
+	/// Returns the format conventionally using the extension of the given 
+	/// file name or path, ignoring case. JSON files are not recognized, as 
+	/// several formats share the extension.
+	pub fn from_extension(path: &str) -> Option<ImportFormat> {
+		let extension = path.rsplit('/').next()
+			.and_then(|file| file.rsplit('.').next()
+				.filter(|ext| ext.len() < file.len()))
+			.map(|ext| ext.to_ascii_lowercase());
+		match extension.as_ref().map(|ext| &ext[..]) {
+			Some("palette")       => Some(ImportFormat::Palette),
+			Some("sketchpalette") => Some(ImportFormat::Sketch),
+			Some("yaml") | Some("yml") => Some(ImportFormat::Base16),
+			Some("gif") | Some("png") | Some("bmp")
+				=> Some(ImportFormat::ColorTable),
+			#[cfg(feature = "openraster")]
+			Some("ora")           => Some(ImportFormat::OpenRaster),
+			_ => None,
+		}
+	}
+
+	/// Guesses the format of the given file contents, or returns None if it
+	/// is not recognized.
+	///
+	/// # Example
+	///
+	/// ```rust
+	/// use palette::import::ImportFormat;
+	///
+	/// assert_eq!(ImportFormat::sniff(b"palette \"Example\"\n"), 
+	/// 	Some(ImportFormat::Palette));
+	/// assert_eq!(ImportFormat::sniff(b"{\"colors\": []}"), 
+	/// 	Some(ImportFormat::Sketch));
+	/// assert_eq!(ImportFormat::sniff(b"scheme: \"Ocean\"\nbase00: 2b303b"),
+	/// 	Some(ImportFormat::Base16));
+	/// ```
+	pub fn sniff(bytes: &[u8]) -> Option<ImportFormat> {
+		#[cfg(feature = "openraster")]
+		{
+			if bytes.starts_with(b"PK\x03\x04") {
+				return Some(ImportFormat::OpenRaster);
+			}
+		}
+
+		if bytes.starts_with(b"GIF8") 
+			|| bytes.starts_with(PNG_SIGNATURE)
+			|| bytes.starts_with(b"BM")
+		{
+			return Some(ImportFormat::ColorTable);
+		}
+
+		let text = String::from_utf8_lossy(&bytes[..bytes.len().min(4096)]);
+		let text = text.trim_start_matches('\u{FEFF}').trim_start();
+		let first_entry = text.lines()
+			.map(str::trim)
+			.find(|line| !line.is_empty() && !line.starts_with('#'))
+			.unwrap_or("");
+
+		if text.starts_with('[') {
+			Some(ImportFormat::Figma)
+		} else if text.starts_with('{') {
+			if text.contains("\"styles\"") {
+				Some(ImportFormat::Figma)
+			} else if text.contains("\"colors\"") {
+				Some(ImportFormat::Sketch)
+			} else {
+				None
+			}
+		} else if first_entry.starts_with("palette ") 
+			|| first_entry.starts_with("cell ")
+		{
+			Some(ImportFormat::Palette)
+		} else if text.contains("base00") {
+			Some(ImportFormat::Base16)
+		} else {
+			None
+		}
+	}
+
+	/// Reads a palette in the format from the given buffer. Formats which do
+	/// not store a palette name use the given name.
+	///
+	/// # Example
+	///
+	/// ```rust
+	/// use palette::*;
+	/// use palette::import::ImportFormat;
+	///
+	/// let json = r#"[{"name": "Red", "color": {"r": 1, "g": 0, "b": 0}}]"#;
+	/// let format = ImportFormat::from_name("figma").unwrap();
+	/// let pal = format.read("Styles", &mut json.as_bytes()).unwrap();
+	///
+	/// assert_eq!(pal.address_of("Red"), Some(Address::new(0, 0, 0)));
+	/// assert_eq!(pal.color(Address::new(0, 0, 0)), 
+	/// 	Some(Color::new(255, 0, 0)));
+	/// ```
+	pub fn read<R>(&self, name: &str, in_buf: &mut R) -> io::Result<Palette>
+		where R: io::Read
+	{
+		let span = Span::enter("import", self.name());
+		let mut palette = match *self {
+			ImportFormat::Palette => Format::Default.read_palette(in_buf)?,
+			ImportFormat::Figma => read_figma_styles(name, in_buf)?,
+			ImportFormat::Sketch => read_sketch_palette(name, in_buf)?,
+			ImportFormat::Base16 => read_base16_scheme(name, in_buf)?,
+			ImportFormat::ColorTable => read_color_table(name, in_buf)?,
+			#[cfg(feature = "openraster")]
+			ImportFormat::OpenRaster => {
+				// Zip archives must be seekable, so buffer the whole file.
+				let mut bytes = Vec::new();
+				in_buf.read_to_end(&mut bytes)?;
+				OpenRasterExtractor::new()
+					.read(name, &mut io::Cursor::new(bytes))?
+			},
+		};
+
+		// Record the import as the origin of cells without a known origin.
+		let addresses: Vec<_> = palette.data.cells.keys().cloned().collect();
+		for address in addresses {
+			if palette.provenance(address).is_none() {
+				palette.set_provenance(address, Provenance::Import {
+					format: self.name().to_owned(),
+					source: name.to_owned(),
+				});
+			}
+		}
+		span.finish(palette.len());
+		Ok(palette)
+	}
+}
+
+
+/// Stores the given color in the first free cell of the palette, returning
+/// its address.
+pub(crate) fn push_color(palette: &mut Palette, color: Color) 
+	-> io::Result<Address>
+{
+	push_color_after(palette, Default::default(), color)
+}
+
+
+/// Returns true if the given text is an HTTP or HTTPS URL.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::import::is_url;
+///
+/// assert!(is_url("https://example.com/palette.yaml"));
+/// assert!(!is_url("palettes/palette.yaml"));
+/// ```
+pub fn is_url(text: &str) -> bool {
+	let lower = text.to_ascii_lowercase();
+	lower.starts_with("http://") || lower.starts_with("https://")
+}
+
+
+/// Stores the given color in the first free cell of the palette at or after
+/// the given address, returning its address.
+pub(crate) fn push_color_after(
+	palette: &mut Palette,
+	start: Address,
+	color: Color)
+	-> io::Result<Address>
+{
+	let data = &mut palette.data;
+	let address = data.first_free_address_after(start)
+		.map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
+	*data.create_cell(address)
+		.map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?
+		.borrow_mut() = Expression::Color(color);
+	Ok(address)
+}
+
+
+/// Parses a JSON object holding the red, green, and blue channels of a color
+/// from 0 to 1 under the given keys.
+pub(crate) fn unit_color(value: &Value, keys: [&str; 3]) -> Option<Color> {
+	let channel = |key: &str| value.get(key)
+		.and_then(|v| v.as_f64())
+		.map(|v| (v.max(0.0).min(1.0) * 255.0).round() as u8);
+	Some(Color::new(channel(keys[0])?, channel(keys[1])?, channel(keys[2])?))
+}
+
+
+/// Returns an `InvalidData` error with the given message.
+pub(crate) fn invalid<S>(message: S) -> io::Error where S: Into<String> {
+	io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
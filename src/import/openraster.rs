@@ -0,0 +1,296 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides extraction of swatches from OpenRaster `.ora` files.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use ::Palette;
+use address::{Address, Reference};
+use format::Format;
+use import::{invalid, push_color_after};
+use quantize::Quantizer;
+
+// Non-local imports.
+use color::Color;
+use png;
+use regex::Regex;
+use zip::ZipArchive;
+
+// Standard imports.
+use std::collections::BTreeMap;
+use std::io;
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// OpenRasterLayer
+////////////////////////////////////////////////////////////////////////////////
+/// A layer entry from the `stack.xml` of an OpenRaster file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct OpenRasterLayer {
+	/// The name of the layer.
+	name: String,
+	/// The path of the layer's PNG image within the archive.
+	source: String,
+	/// Whether the layer is visible.
+	visible: bool,
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// OpenRasterExtractor
+////////////////////////////////////////////////////////////////////////////////
+/// Builds a palette from the colors used in an OpenRaster `.ora` file, such as
+/// those written by Krita, MyPaint, and GIMP.
+///
+/// By default, the colors of the merged image are extracted. If per-layer 
+/// extraction is enabled, the colors of each visible layer are placed on 
+/// their own page, named after the layer. Fully transparent pixels are 
+/// ignored, and the alpha of all other pixels is discarded.
+///
+/// Colors are ordered from most to least used, with their pixel counts stored
+/// as cell usage counts. If a maximum color count is set, images with more 
+/// colors are reduced using a [`Quantizer`].
+///
+/// [`Quantizer`]: ../quantize/struct.Quantizer.html
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use palette::*;
+/// use palette::import::OpenRasterExtractor;
+/// use std::fs::File;
+///
+/// let mut file = File::open("artwork.ora").unwrap();
+/// let pal = OpenRasterExtractor::new()
+/// 	.per_layer(true)
+/// 	.max_colors(16)
+/// 	.read("Artwork", &mut file)
+/// 	.unwrap();
+///
+/// println!("{}", pal);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OpenRasterExtractor {
+	/// Whether to extract the colors of each layer separately.
+	per_layer: bool,
+	/// The maximum number of colors to extract from each image.
+	max_colors: Option<usize>,
+}
+
+
+impl OpenRasterExtractor {
+	/// Creates a new `OpenRasterExtractor` extracting every color of the 
+	/// merged image.
+	pub fn new() -> Self {
+		Default::default()
+	}
+
+	/// Sets whether the colors of each visible layer are extracted onto 
+	/// separate pages.
+	pub fn per_layer(mut self, per_layer: bool) -> Self {
+		self.per_layer = per_layer;
+		self
+	}
+
+	/// Sets the maximum number of colors to extract from each image.
+	pub fn max_colors(mut self, max_colors: usize) -> Self {
+		self.max_colors = Some(max_colors);
+		self
+	}
+
+	/// Reads an OpenRaster file from the given buffer and returns a palette 
+	/// with the given name holding its colors.
+	///
+	/// Returns an `InvalidData` error if the buffer is not a valid OpenRaster
+	/// file.
+	pub fn read<R>(&self, name: &str, in_buf: &mut R) -> io::Result<Palette>
+		where R: io::Read + io::Seek
+	{
+		let mut archive = ZipArchive::new(in_buf)
+			.map_err(|e| invalid(e.to_string()))?;
+		let mut palette = Palette::new(name, Format::Default, true);
+
+		if self.per_layer {
+			let layers = read_stack(&mut archive)?;
+			let visible = layers.into_iter().filter(|layer| layer.visible);
+			for (page, layer) in visible.enumerate() {
+				let pixels = read_png(&mut archive, &layer.source)?;
+				let start = Address::new(page as u16, 0, 0);
+				self.extract(&mut palette, start, &pixels)?;
+				palette.data.set_name(Reference::page_of(&start), layer.name);
+			}
+		} else {
+			let pixels = match read_png(&mut archive, "mergedimage.png") {
+				Ok(pixels) => pixels,
+				// Files written before version 0.0.5 of the specification
+				// may lack a merged image, so combine the layers instead.
+				Err(_) => {
+					let mut pixels = Vec::new();
+					for layer in read_stack(&mut archive)? {
+						if layer.visible {
+							pixels.extend(
+								read_png(&mut archive, &layer.source)?);
+						}
+					}
+					pixels
+				},
+			};
+			self.extract(&mut palette, Default::default(), &pixels)?;
+		}
+		Ok(palette)
+	}
+
+	/// Stores the colors of the given pixels in the palette, starting at the
+	/// given address.
+	fn extract(&self, palette: &mut Palette, start: Address, pixels: &[Color])
+		-> io::Result<()>
+	{
+		let colors = match self.max_colors {
+			Some(count) => Quantizer::new(count)
+				.quantize(pixels, None)
+				.into_iter()
+				.map(|q| (q.color, q.usage_count))
+				.collect(),
+			None => distinct_colors(pixels),
+		};
+
+		for (color, count) in colors {
+			let address = push_color_after(palette, start, color)?;
+			palette.set_usage_count(address, count);
+		}
+		Ok(())
+	}
+}
+
+
+/// Returns the distinct colors of the given pixels with their pixel counts,
+/// ordered from most to least used.
+fn distinct_colors(pixels: &[Color]) -> Vec<(Color, usize)> {
+	let mut histogram = BTreeMap::new();
+	for pixel in pixels {
+		*histogram
+			.entry((pixel.red(), pixel.green(), pixel.blue()))
+			.or_insert(0) += 1;
+	}
+
+	let mut colors: Vec<_> = histogram.into_iter()
+		.map(|((r, g, b), count)| (Color::new(r, g, b), count))
+		.collect();
+	colors.sort_by(|a, b| b.1.cmp(&a.1));
+	colors
+}
+
+
+/// Reads the layers listed in the `stack.xml` of the given archive, from top
+/// to bottom. Layers of nested stacks are included in document order.
+fn read_stack<R>(archive: &mut ZipArchive<R>) 
+	-> io::Result<Vec<OpenRasterLayer>>
+	where R: io::Read + io::Seek
+{
+	let mut xml = String::new();
+	io::Read::read_to_string(
+		&mut archive.by_name("stack.xml")
+			.map_err(|e| invalid(format!("stack.xml: {}", e)))?,
+		&mut xml)?;
+
+	let layer_re = Regex::new(r"<layer\b([^>]*)>").unwrap();
+	let attr_re = Regex::new(r#"([\w:-]+)\s*=\s*(?:"([^"]*)"|'([^']*)')"#)
+		.unwrap();
+
+	let mut layers = Vec::new();
+	for layer in layer_re.captures_iter(&xml) {
+		let mut attributes = BTreeMap::new();
+		for attr in attr_re.captures_iter(&layer[1]) {
+			let value = attr.get(2).or(attr.get(3)).map_or("", |m| m.as_str());
+			attributes.insert(attr[1].to_string(), unescape(value));
+		}
+
+		let source = attributes.remove("src")
+			.ok_or_else(|| invalid("stack.xml: layer without src"))?;
+		layers.push(OpenRasterLayer {
+			name: attributes.remove("name").unwrap_or_else(|| source.clone()),
+			source: source,
+			visible: attributes.get("visibility")
+				.map_or(true, |v| v != "hidden"),
+		});
+	}
+	Ok(layers)
+}
+
+
+/// Replaces the predefined XML entities in the given attribute value.
+fn unescape(value: &str) -> String {
+	value
+		.replace("&lt;", "<")
+		.replace("&gt;", ">")
+		.replace("&quot;", "\"")
+		.replace("&apos;", "'")
+		.replace("&amp;", "&")
+}
+
+
+/// Decodes the PNG image at the given path of the archive, returning its 
+/// pixels which are not fully transparent.
+fn read_png<R>(archive: &mut ZipArchive<R>, path: &str) 
+	-> io::Result<Vec<Color>>
+	where R: io::Read + io::Seek
+{
+	let to_io = |e: png::DecodingError| invalid(format!("{}: {}", path, e));
+	let file = archive.by_name(path)
+		.map_err(|e| invalid(format!("{}: {}", path, e)))?;
+
+	let mut decoder = png::Decoder::new(file);
+	decoder.set_transformations(png::Transformations::normalize_to_color8());
+	let mut reader = decoder.read_info().map_err(to_io)?;
+	let mut buffer = vec![0; reader.output_buffer_size()];
+	let info = reader.next_frame(&mut buffer).map_err(to_io)?;
+	let bytes = &buffer[..info.buffer_size()];
+
+	let pixel = |p: &[u8], alpha: u8| if alpha == 0 {
+		None
+	} else if p.len() < 3 {
+		Some(Color::new(p[0], p[0], p[0]))
+	} else {
+		Some(Color::new(p[0], p[1], p[2]))
+	};
+	let pixels = match info.color_type {
+		png::ColorType::Grayscale | png::ColorType::Rgb => {
+			let size = info.color_type.samples();
+			bytes.chunks(size).filter_map(|p| pixel(p, 255)).collect()
+		},
+		png::ColorType::GrayscaleAlpha | png::ColorType::Rgba => {
+			let size = info.color_type.samples();
+			bytes.chunks(size)
+				.filter_map(|p| pixel(&p[..size - 1], p[size - 1]))
+				.collect()
+		},
+		png::ColorType::Indexed => return Err(
+			invalid(format!("{}: unexpanded indexed image", path))),
+	};
+	Ok(pixels)
+}
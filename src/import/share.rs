@@ -0,0 +1,200 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides reading of palettes from color sharing URLs.
+//!
+//! Many palette sites encode a palette's colors directly in their sharing 
+//! URLs, so the palette can be read without downloading anything. The 
+//! following forms are recognized:
+//!
+//! ```text
+//! https://coolors.co/264653-2a9d8f-e9c46a-f4a261-e76f51
+//! https://coolors.co/palette/264653-2a9d8f-e9c46a-f4a261-e76f51
+//! https://colorhunt.co/palette/f9ed69f08a5db83b5e6a2c70
+//! https://www.colorhexa.com/ff8000
+//! https://example.com/view?colors=ff0000,00ff00,%230000ff
+//! ```
+//!
+//! Colors are only read from the path of URLs on the sites listed above. Any
+//! other URL is left to the URL importer, so a file such as
+//! `https://example.com/decade.gpl` is downloaded rather than misread.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use ::Palette;
+use format::Format;
+use import::{
+	invalid,
+	push_color,
+};
+use utilities::parse_hex;
+
+// Non-local imports.
+use color::Color;
+
+// Standard imports.
+use std::io;
+
+
+/// The query parameters which may hold a list of colors.
+const COLOR_PARAMETERS: [&'static str; 4] = ["colors", "palette", "hex", "c"];
+
+/// The sites which encode a list of colors in the path of their sharing URLs.
+const SHARE_HOSTS: [&'static str; 3] = [
+	"coolors.co",
+	"colorhunt.co",
+	"colorhexa.com",
+];
+
+
+/// Returns the colors encoded in the given sharing URL, in order, or None if 
+/// the URL does not encode any colors.
+///
+/// Colors are read from a `colors`, `palette`, `hex`, or `c` query parameter
+/// if present. Otherwise, if the URL is on a known sharing site, they are read
+/// from the last path segment if it holds a list of hex codes separated by 
+/// dashes or commas, or a run of six digit hex codes.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::import::share_url_colors;
+///
+/// let url = "https://coolors.co/264653-2a9d8f-e9c46a";
+/// assert_eq!(share_url_colors(url), Some(vec![
+/// 	Color::new(0x26, 0x46, 0x53),
+/// 	Color::new(0x2A, 0x9D, 0x8F),
+/// 	Color::new(0xE9, 0xC4, 0x6A),
+/// ]));
+///
+/// let url = "https://colorhunt.co/palette/f9ed69f08a5d";
+/// assert_eq!(share_url_colors(url).map(|c| c.len()), Some(2));
+///
+/// assert_eq!(share_url_colors("https://example.com/about"), None);
+/// assert_eq!(share_url_colors("https://example.com/c0ffee/about"), None);
+/// assert_eq!(share_url_colors("https://example.com/decade"), None);
+/// assert_eq!(share_url_colors("https://coolors.co/decade.gpl"), None);
+/// ```
+pub fn share_url_colors(url: &str) -> Option<Vec<Color>> {
+	let rest = url.splitn(2, "://").nth(1).unwrap_or(url);
+	let rest = rest.split('#').next().unwrap_or("");
+	let mut parts = rest.splitn(2, '?');
+	let location = parts.next().unwrap_or("");
+	let query = parts.next().unwrap_or("");
+
+	for pair in query.split('&') {
+		let mut pair = pair.splitn(2, '=');
+		let key = pair.next().unwrap_or("");
+		let value = pair.next().unwrap_or("");
+		if COLOR_PARAMETERS.iter().any(|p| p.eq_ignore_ascii_case(key)) {
+			let value = value.replace("%23", "").replace("%2C", ",")
+				.replace("%2c", ",");
+			if let Some(colors) = hex_list(&value) {
+				return Some(colors);
+			}
+		}
+	}
+
+	if !SHARE_HOSTS.iter().any(|h| h.eq_ignore_ascii_case(host(url))) {
+		return None;
+	}
+	location.split('/')
+		.skip(1)
+		.filter(|segment| !segment.is_empty())
+		.last()
+		.filter(|segment| !segment.contains('.'))
+		.and_then(hex_list)
+}
+
+
+/// Reads a palette from the colors encoded in the given sharing URL. The 
+/// palette is named after the site, and its colors are stored in order.
+///
+/// Returns an `InvalidData` error if the URL does not encode any colors.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::import::read_share_url;
+///
+/// let url = "https://coolors.co/palette/264653-2a9d8f-e9c46a";
+/// let pal = read_share_url(url).unwrap();
+///
+/// assert_eq!(pal.name(), Some("coolors"));
+/// assert_eq!(pal.len(), 3);
+/// assert_eq!(pal.color(Address::new(0, 0, 2)), 
+/// 	Some(Color::new(0xE9, 0xC4, 0x6A)));
+/// ```
+pub fn read_share_url(url: &str) -> io::Result<Palette> {
+	let colors = share_url_colors(url)
+		.ok_or_else(|| invalid(format!("no colors found in '{}'", url)))?;
+
+	let name = host(url).split('.').next().filter(|n| !n.is_empty())
+		.unwrap_or("palette");
+
+	let mut palette = Palette::new(name, Format::Default, true);
+	for color in colors {
+		push_color(&mut palette, color)?;
+	}
+	Ok(palette)
+}
+
+
+/// Returns the host of the given URL, without any leading `www.`.
+fn host(url: &str) -> &str {
+	url.splitn(2, "://").nth(1).unwrap_or(url)
+		.split(|c| c == '/' || c == '?' || c == '#' || c == ':')
+		.next()
+		.unwrap_or("")
+		.trim_start_matches("www.")
+}
+
+
+/// Parses a list of hex codes separated by dashes or commas, or a run of six
+/// digit hex codes.
+fn hex_list(text: &str) -> Option<Vec<Color>> {
+	let text = text.trim_matches(|c| c == '-' || c == ',');
+	if text.contains(|c| c == '-' || c == ',') {
+		return text.split(|c| c == '-' || c == ',')
+			.map(|code| match code.trim_start_matches('#').len() {
+				3 | 6 => parse_hex(code),
+				_ => None,
+			})
+			.collect();
+	}
+
+	let digits = text.trim_start_matches('#');
+	if digits.is_empty() 
+		|| digits.len() % 6 != 0 
+		|| !digits.chars().all(|c| c.is_ascii_hexdigit())
+	{
+		return None;
+	}
+	(0..digits.len() / 6)
+		.map(|i| parse_hex(&digits[i * 6..i * 6 + 6]))
+		.collect()
+}
@@ -0,0 +1,99 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides importing of Sketch `.sketchpalette` files.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use ::Palette;
+use format::Format;
+use import::{
+	invalid,
+	push_color,
+	unit_color,
+};
+use utilities::parse_hex;
+
+// Non-local imports.
+use serde_json;
+use serde_json::Value;
+
+// Standard imports.
+use std::io;
+
+
+/// Reads a palette with the given name from a Sketch `.sketchpalette` file.
+///
+/// Colors may be given as objects of `red`, `green`, `blue`, and `alpha` 
+/// channels from 0 to 1, as written by version 2 of the format, or as hex 
+/// strings, as written by version 1. Color names are stored as cell names. 
+/// Alpha, gradients, and images are ignored.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::import::read_sketch_palette;
+///
+/// let json = r##"{
+/// 	"compatibleVersion": "2.0",
+/// 	"pluginVersion": "2.22",
+/// 	"colors": [
+/// 		{"name": "Ink", "red": 0.1, "green": 0.1, "blue": 0.2, "alpha": 1},
+/// 		"#FF8000"
+/// 	]
+/// }"##;
+/// let pal = read_sketch_palette("Swatches", &mut json.as_bytes()).unwrap();
+///
+/// assert_eq!(pal.cell_name(Address::new(0, 0, 0)), Some("Ink"));
+/// assert_eq!(pal.color(Address::new(0, 0, 0)), Some(Color::new(26, 26, 51)));
+/// assert_eq!(pal.color(Address::new(0, 0, 1)), Some(Color::new(255, 128, 0)));
+/// ```
+pub fn read_sketch_palette<R>(name: &str, in_buf: &mut R) 
+	-> io::Result<Palette>
+	where R: io::Read
+{
+	let json: Value = serde_json::from_reader(in_buf)
+		.map_err(|e| invalid(e.to_string()))?;
+	let colors = json.get("colors")
+		.and_then(|colors| colors.as_array())
+		.ok_or_else(|| invalid("expected a 'colors' array"))?;
+
+	let mut palette = Palette::new(name, Format::Default, true);
+	for (i, entry) in colors.iter().enumerate() {
+		let color = match *entry {
+			Value::String(ref hex) => parse_hex(hex),
+			_ => unit_color(entry, ["red", "green", "blue"]),
+		};
+		let color = color
+			.ok_or_else(|| invalid(format!("color {}: invalid color", i)))?;
+
+		let address = push_color(&mut palette, color)?;
+		if let Some(name) = entry.get("name").and_then(|n| n.as_str()) {
+			palette.set_name(address, name);
+		}
+	}
+	Ok(palette)
+}
@@ -0,0 +1,148 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides importing of palettes from HTTP and HTTPS URLs.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use ::Palette;
+use import::{
+	invalid,
+	is_url,
+	ImportFormat,
+};
+
+// Non-local imports.
+use ureq;
+
+// Standard imports.
+use std::io;
+use std::io::Read;
+
+
+/// The maximum size of a downloaded palette, in bytes.
+const MAX_DOWNLOAD_SIZE: u64 = 64 * 1024 * 1024;
+
+
+/// Downloads and reads a palette from the given HTTP or HTTPS URL.
+///
+/// If no format is given, it is determined from the extension of the URL 
+/// path, then from the response's content type, and finally by sniffing the
+/// downloaded contents. Formats which do not store a palette name are named
+/// after the file name of the URL. The palette is returned along with the
+/// format it was read as.
+///
+/// # Errors
+///
+/// Returns an `InvalidInput` error if the URL is not an HTTP or HTTPS URL, an 
+/// `InvalidData` error if the download is too large, the format cannot be 
+/// determined, or the contents are invalid, and an `Other` error if the 
+/// request fails.
+pub fn read_url(url: &str, format: Option<ImportFormat>) 
+	-> io::Result<(Palette, ImportFormat)>
+{
+	if !is_url(url) {
+		return Err(io::Error::new(io::ErrorKind::InvalidInput, 
+			format!("'{}' is not an http or https URL", url)));
+	}
+
+	let (content_type, bytes) = download(url)?;
+	read_download(url, format, &content_type, &bytes)
+}
+
+
+/// Downloads the contents of the given URL, returning its lowercased content
+/// type and its body. Bodies larger than `MAX_DOWNLOAD_SIZE` bytes are 
+/// rejected rather than truncated.
+pub(crate) fn download(url: &str) -> io::Result<(String, Vec<u8>)> {
+	let response = ureq::get(url)
+		.call()
+		.map_err(|e| io::Error::new(io::ErrorKind::Other, match e {
+			ureq::Error::Status(code, _) => format!("status code {}", code),
+			ureq::Error::Transport(transport) => transport.message()
+				.map_or_else(|| transport.kind().to_string(), str::to_owned),
+		}))?;
+	let content_type = response.content_type().to_ascii_lowercase();
+	let mut bytes = Vec::new();
+	response.into_reader()
+		.take(MAX_DOWNLOAD_SIZE + 1)
+		.read_to_end(&mut bytes)?;
+	if bytes.len() as u64 > MAX_DOWNLOAD_SIZE {
+		return Err(invalid(
+			format!("download exceeds {} bytes", MAX_DOWNLOAD_SIZE)));
+	}
+	Ok((content_type, bytes))
+}
+
+
+/// Reads a palette from the downloaded contents of the given URL, returning it
+/// along with the format it was read as.
+pub(crate) fn read_download(
+	url: &str,
+	format: Option<ImportFormat>,
+	content_type: &str,
+	bytes: &[u8])
+	-> io::Result<(Palette, ImportFormat)>
+{
+	let path = url_path(url);
+	let format = format
+		.or_else(|| ImportFormat::from_extension(path))
+		.or_else(|| from_content_type(content_type))
+		.or_else(|| ImportFormat::sniff(bytes))
+		.ok_or_else(|| invalid(
+			format!("unrecognized palette format ({})", content_type)))?;
+
+	let file = path.rsplit('/').next().unwrap_or("");
+	let name = match file.rfind('.') {
+		Some(dot) if dot > 0 => &file[..dot],
+		_ => file,
+	};
+	format.read(name, &mut &bytes[..]).map(|palette| (palette, format))
+}
+
+
+/// Returns the path of the given URL, without its query or fragment.
+fn url_path(url: &str) -> &str {
+	let rest = url.splitn(2, "://").nth(1).unwrap_or(url);
+	let rest = rest.split(|c| c == '?' || c == '#').next().unwrap_or("");
+	rest.find('/').map_or("", |slash| &rest[slash..])
+}
+
+
+/// Returns the format with the given MIME type, if it identifies one.
+fn from_content_type(content_type: &str) -> Option<ImportFormat> {
+	match content_type.split(';').next().unwrap_or("").trim() {
+		"application/yaml" 
+			| "application/x-yaml" 
+			| "text/yaml" 
+			| "text/x-yaml" => Some(ImportFormat::Base16),
+		"image/gif" 
+			| "image/png" 
+			| "image/bmp" => Some(ImportFormat::ColorTable),
+		#[cfg(feature = "openraster")]
+		"image/openraster" => Some(ImportFormat::OpenRaster),
+		_ => None,
+	}
+}
@@ -0,0 +1,366 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides numeric operations on intervals.
+//!
+//! The operations are provided by the `NumericInterval` extension trait, 
+//! which works uniformly for integer and floating point intervals through the
+//! `IntervalNumeric` trait. Intervals over integers can additionally be
+//! canonicalized using the `DiscreteInterval` extension trait.
+//!
+//! Because `Interval` is defined in another crate, scalar arithmetic is
+//! provided by the `shift` and `scale` methods rather than through the `Add`
+//! and `Mul` operators.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Non-local imports.
+use interval::{
+	Bound,
+	Interval,
+};
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// IntervalNumeric
+////////////////////////////////////////////////////////////////////////////////
+/// A numeric type usable as an interval endpoint.
+pub trait IntervalNumeric: PartialOrd + PartialEq + Clone + Copy {
+	/// Returns the zero value of the type.
+	fn zero() -> Self;
+
+	/// Returns the value halfway between the given values, rounded toward 
+	/// the first value for integers.
+	fn midpoint(a: Self, b: Self) -> Self;
+
+	/// Returns the sum of the values, or None if it overflows.
+	fn checked_add(self, other: Self) -> Option<Self>;
+
+	/// Returns the difference of the values, or None if it overflows.
+	fn checked_sub(self, other: Self) -> Option<Self>;
+
+	/// Returns the product of the values, or None if it overflows.
+	fn checked_mul(self, other: Self) -> Option<Self>;
+
+	/// Returns the value located at the given fraction between the given 
+	/// values, rounded to the nearest value for integers.
+	fn lerp(a: Self, b: Self, amount: f64) -> Self;
+}
+
+
+macro_rules! impl_interval_numeric_int {
+	($($t:ty),*) => {$(
+		impl IntervalNumeric for $t {
+			fn zero() -> Self { 0 }
+
+			fn midpoint(a: Self, b: Self) -> Self {
+				// Avoid overflowing by halving before adding.
+				a / 2 + b / 2 + (a % 2 + b % 2) / 2
+			}
+
+			fn checked_add(self, other: Self) -> Option<Self> {
+				<$t>::checked_add(self, other)
+			}
+
+			fn checked_sub(self, other: Self) -> Option<Self> {
+				<$t>::checked_sub(self, other)
+			}
+
+			fn checked_mul(self, other: Self) -> Option<Self> {
+				<$t>::checked_mul(self, other)
+			}
+
+			fn lerp(a: Self, b: Self, amount: f64) -> Self {
+				(a as f64 + (b as f64 - a as f64) * amount).round() as $t
+			}
+		}
+	)*};
+}
+
+macro_rules! impl_interval_numeric_float {
+	($($t:ty),*) => {$(
+		impl IntervalNumeric for $t {
+			fn zero() -> Self { 0.0 }
+
+			fn midpoint(a: Self, b: Self) -> Self {
+				a + (b - a) / 2.0
+			}
+
+			fn checked_add(self, other: Self) -> Option<Self> {
+				Some(self + other).filter(|v| v.is_finite())
+			}
+
+			fn checked_sub(self, other: Self) -> Option<Self> {
+				Some(self - other).filter(|v| v.is_finite())
+			}
+
+			fn checked_mul(self, other: Self) -> Option<Self> {
+				Some(self * other).filter(|v| v.is_finite())
+			}
+
+			fn lerp(a: Self, b: Self, amount: f64) -> Self {
+				a + (b - a) * amount as $t
+			}
+		}
+	)*};
+}
+
+impl_interval_numeric_int!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+impl_interval_numeric_float!(f32, f64);
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// DiscreteNumeric
+////////////////////////////////////////////////////////////////////////////////
+/// A numeric interval endpoint type with adjacent representable values.
+pub trait DiscreteNumeric: IntervalNumeric {
+	/// Returns the next larger value, or None if there is none.
+	fn successor(self) -> Option<Self>;
+
+	/// Returns the next smaller value, or None if there is none.
+	fn predecessor(self) -> Option<Self>;
+}
+
+
+macro_rules! impl_discrete_numeric {
+	($($t:ty),*) => {$(
+		impl DiscreteNumeric for $t {
+			fn successor(self) -> Option<Self> {
+				<$t>::checked_add(self, 1)
+			}
+
+			fn predecessor(self) -> Option<Self> {
+				<$t>::checked_sub(self, 1)
+			}
+		}
+	)*};
+}
+
+impl_discrete_numeric!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// NumericInterval
+////////////////////////////////////////////////////////////////////////////////
+/// Provides numeric operations on intervals.
+///
+/// # Example
+///
+/// ```rust
+/// extern crate interval;
+/// extern crate palette;
+///
+/// use interval::Interval;
+/// use palette::intervals::NumericInterval;
+///
+/// # fn main() {
+/// let ints = Interval::closed(0u8, 200);
+/// assert_eq!(ints.extent(), Some(200));
+/// assert_eq!(ints.sample(0.5), 100);
+/// assert_eq!(ints.partition(4).len(), 4);
+/// assert_eq!(ints.widen(100), None);
+///
+/// let floats = Interval::closed(0.0f32, 1.0);
+/// assert_eq!(floats.sample(0.25), 0.25);
+/// assert_eq!(floats.widen(0.5), Some(Interval::closed(-0.5, 1.5)));
+///
+/// let page = Interval::right_open(16u16, 32);
+/// assert_eq!(page.shift(256), Some(Interval::right_open(272, 288)));
+/// assert_eq!(page.shift_back(8), Some(Interval::right_open(8, 24)));
+/// assert_eq!(page.scale(2), Some(Interval::right_open(32, 64)));
+/// assert_eq!(Interval::left_open(1i8, 2).scale(-1), 
+/// 	Some(Interval::right_open(-2, -1)));
+/// # }
+/// ```
+pub trait NumericInterval<T>: Sized {
+	/// Returns the distance between the interval's endpoints, or None if it 
+	/// cannot be represented.
+	fn extent(&self) -> Option<T>;
+
+	/// Returns the point halfway between the interval's endpoints.
+	fn midpoint(&self) -> T;
+
+	/// Returns the point located at the given fraction along the interval.
+	fn sample(&self, amount: f32) -> T;
+
+	/// Splits the interval into the given number of adjacent intervals of
+	/// equal extent. The first and last intervals keep the original bounds,
+	/// and each interior boundary belongs to the interval on its right.
+	/// Integer boundaries are rounded, and any empty pieces are omitted.
+	fn partition(&self, count: usize) -> Vec<Self>;
+
+	/// Returns the interval extended by the given amount on both sides, 
+	/// preserving its bound types, or None if an endpoint overflows.
+	fn widen(&self, amount: T) -> Option<Self>;
+
+	/// Returns the interval with both endpoints increased by the given 
+	/// amount, preserving its bound types, or None if an endpoint overflows.
+	fn shift(&self, amount: T) -> Option<Self>;
+
+	/// Returns the interval with both endpoints decreased by the given 
+	/// amount, preserving its bound types, or None if an endpoint overflows.
+	fn shift_back(&self, amount: T) -> Option<Self>;
+
+	/// Returns the interval with both endpoints multiplied by the given 
+	/// factor, preserving its bound types, or None if an endpoint overflows.
+	/// A negative factor reverses the interval, so its bounds are swapped.
+	fn scale(&self, factor: T) -> Option<Self>;
+}
+
+
+impl<T> NumericInterval<T> for Interval<T> where T: IntervalNumeric {
+	fn extent(&self) -> Option<T> {
+		self.right_point().checked_sub(self.left_point())
+	}
+
+	fn midpoint(&self) -> T {
+		T::midpoint(self.left_point(), self.right_point())
+	}
+
+	fn sample(&self, amount: f32) -> T {
+		T::lerp(self.left_point(), self.right_point(), amount as f64)
+	}
+
+	fn partition(&self, count: usize) -> Vec<Self> {
+		let (left, right) = (self.left_point(), self.right_point());
+		let points: Vec<T> = (0..count + 1)
+			.map(|i| T::lerp(left, right, i as f64 / count as f64))
+			.collect();
+
+		let mut pieces = Vec::with_capacity(count);
+		for (i, pair) in points.windows(2).enumerate() {
+			if i > 0 && pair[0] == pair[1] { continue; }
+			let start = if i == 0 {
+				self.left_bound()
+			} else {
+				Bound::Include(pair[0])
+			};
+			let end = if i + 1 == count {
+				self.right_bound()
+			} else {
+				Bound::Exclude(pair[1])
+			};
+			pieces.push(Interval::new(start, Some(end)));
+		}
+		pieces
+	}
+
+	fn widen(&self, amount: T) -> Option<Self> {
+		let start = map_bound(self.left_bound(), |p| p.checked_sub(amount))?;
+		let end = map_bound(self.right_bound(), |p| p.checked_add(amount))?;
+		Some(Interval::new(start, Some(end)))
+	}
+
+	fn shift(&self, amount: T) -> Option<Self> {
+		let start = map_bound(self.left_bound(), |p| p.checked_add(amount))?;
+		let end = map_bound(self.right_bound(), |p| p.checked_add(amount))?;
+		Some(Interval::new(start, Some(end)))
+	}
+
+	fn shift_back(&self, amount: T) -> Option<Self> {
+		let start = map_bound(self.left_bound(), |p| p.checked_sub(amount))?;
+		let end = map_bound(self.right_bound(), |p| p.checked_sub(amount))?;
+		Some(Interval::new(start, Some(end)))
+	}
+
+	fn scale(&self, factor: T) -> Option<Self> {
+		let start = map_bound(self.left_bound(), |p| p.checked_mul(factor))?;
+		let end = map_bound(self.right_bound(), |p| p.checked_mul(factor))?;
+		if factor < T::zero() {
+			Some(Interval::new(end, Some(start)))
+		} else {
+			Some(Interval::new(start, Some(end)))
+		}
+	}
+}
+
+
+/// Applies the given fallible function to the point of the bound, preserving
+/// its type.
+pub(crate) fn map_bound<T, U, F>(bound: Bound<T>, f: F) -> Option<Bound<U>>
+	where F: FnOnce(T) -> Option<U>
+{
+	match bound {
+		Bound::Include(p) => f(p).map(Bound::Include),
+		Bound::Exclude(p) => f(p).map(Bound::Exclude),
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// DiscreteInterval
+////////////////////////////////////////////////////////////////////////////////
+/// Provides canonicalization of intervals over discrete values.
+///
+/// # Example
+///
+/// ```rust
+/// extern crate interval;
+/// extern crate palette;
+///
+/// use interval::Interval;
+/// use palette::intervals::DiscreteInterval;
+///
+/// # fn main() {
+/// assert_eq!(Interval::open(3u8, 7).canonicalize(), 
+/// 	Some(Interval::closed(4, 6)));
+/// assert_eq!(Interval::open(3u8, 4).canonicalize(), None);
+///
+/// let a = Interval::right_open(3, 7);
+/// assert!(a.same_elements(&Interval::left_open(2, 6)));
+/// # }
+/// ```
+pub trait DiscreteInterval: Sized {
+	/// Returns the equivalent closed interval, or None if the interval 
+	/// contains no values. Open bounds are replaced by the adjacent value 
+	/// inside the interval.
+	fn canonicalize(&self) -> Option<Self>;
+
+	/// Returns whether the intervals contain the same values.
+	fn same_elements(&self, other: &Self) -> bool;
+}
+
+
+impl<T> DiscreteInterval for Interval<T> where T: DiscreteNumeric {
+	fn canonicalize(&self) -> Option<Self> {
+		let start = match self.left_bound() {
+			Bound::Include(p) => p,
+			Bound::Exclude(p) => p.successor()?,
+		};
+		let end = match self.right_bound() {
+			Bound::Include(p) => p,
+			Bound::Exclude(p) => p.predecessor()?,
+		};
+		if start > end { return None; }
+		Some(Interval::closed(start, end))
+	}
+
+	fn same_elements(&self, other: &Self) -> bool {
+		self.canonicalize() == other.canonicalize()
+	}
+}
@@ -42,23 +42,139 @@
 
 extern crate color;
 extern crate interval;
+extern crate regex;
+#[macro_use]
+extern crate serde_json;
+extern crate unicode_normalization;
+extern crate unicode_segmentation;
+#[cfg(all(feature = "picker", unix, not(target_os = "macos")))]
+extern crate x11;
+#[cfg(all(feature = "picker", windows))]
+extern crate winapi;
+#[cfg(feature = "icc")]
+extern crate lcms2;
+#[cfg(feature = "mmap")]
+extern crate memmap2;
+#[cfg(any(feature = "images", feature = "openraster"))]
+extern crate png;
+#[cfg(feature = "signing")]
+extern crate ed25519_dalek;
+#[cfg(feature = "trace")]
+extern crate tracing;
+#[cfg(feature = "net")]
+extern crate ureq;
+#[cfg(feature = "async")]
+extern crate futures;
+#[cfg(feature = "server")]
+extern crate tiny_http;
+#[cfg(any(feature = "bundle", feature = "openraster"))]
+extern crate zip;
 
 // Submodules.
 #[warn(missing_docs)]
 pub mod address;
 #[warn(missing_docs)]
+pub mod analysis;
+#[warn(missing_docs)]
+pub mod ansi;
+#[warn(missing_docs)]
+pub mod autosave;
+#[cfg(feature = "bundle")]
+#[warn(missing_docs)]
+pub mod bundle;
+#[warn(missing_docs)]
 pub mod cell;
 #[warn(missing_docs)]
+pub mod conformance;
+#[warn(missing_docs)]
+pub mod curve;
+#[warn(missing_docs)]
 pub mod data;
 #[warn(missing_docs)]
+pub mod dictionary;
+#[warn(missing_docs)]
+pub mod eval;
+#[warn(missing_docs)]
+pub mod export;
+#[warn(missing_docs)]
 pub mod expression;
 #[warn(missing_docs)]
+pub mod fixed;
+#[warn(missing_docs)]
 pub mod format;
 #[warn(missing_docs)]
+pub mod gallery;
+#[warn(missing_docs)]
+pub mod generate;
+#[warn(missing_docs)]
+pub mod gradient;
+#[warn(missing_docs)]
+pub mod hook;
+#[cfg(feature = "icc")]
+#[warn(missing_docs)]
+pub mod icc;
+#[warn(missing_docs)]
+pub mod import;
+#[warn(missing_docs)]
+pub mod intervals;
+#[warn(missing_docs)]
+pub mod lint;
+#[warn(missing_docs)]
+pub mod lock;
+#[cfg(feature = "async")]
+#[warn(missing_docs)]
+pub mod nonblocking;
+#[warn(missing_docs)]
+pub mod occupancy;
+#[warn(missing_docs)]
 pub mod operation;
+#[cfg(feature = "picker")]
+#[warn(missing_docs)]
+pub mod picker;
+#[warn(missing_docs)]
+pub mod preset;
+#[warn(missing_docs)]
+pub mod progress;
+#[warn(missing_docs)]
+pub mod project;
+#[warn(missing_docs)]
+pub mod provenance;
+#[warn(missing_docs)]
+pub mod quantize;
+#[warn(missing_docs)]
+pub mod ramp;
+#[warn(missing_docs)]
+pub mod render;
 #[warn(missing_docs)]
 pub mod result;
 #[warn(missing_docs)]
+pub mod scheme;
+#[warn(missing_docs)]
+pub mod search;
+#[warn(missing_docs)]
+pub mod select;
+#[warn(missing_docs)]
+pub mod sequence;
+#[cfg(feature = "server")]
+#[warn(missing_docs)]
+pub mod server;
+#[warn(missing_docs)]
+pub mod sign;
+#[warn(missing_docs)]
+pub mod space;
+#[warn(missing_docs)]
+pub mod swap;
+#[warn(missing_docs)]
+pub mod template;
+#[warn(missing_docs)]
+pub mod text;
+#[warn(missing_docs)]
+mod trace;
+#[warn(missing_docs)]
+pub mod trash;
+#[warn(missing_docs)]
+pub mod unit;
+#[warn(missing_docs)]
 pub mod utilities;
 
 
@@ -70,18 +186,59 @@ pub use color::Color;
 pub use address::{
 	Address,
 	Reference,
+	Selection,
 };
+pub use cell::CellId;
 pub use expression::Expression;
 pub use format::Format;
 
 
 // Local imports.
+use curve::ToneCurve;
 use data::Data;
-use operation::{PaletteOperation, OperationHistory};
+use hook::{
+	HookId,
+	Hooks,
+	OperationHook,
+};
+use occupancy::{
+	FreeRun,
+	OccupancyStats,
+	OccupiedCells,
+};
+use operation::{
+	HistoryEntry,
+	InsertColor,
+	MergePalette,
+	MergeReport,
+	MergeStrategy,
+	OperationHistory,
+	OperationReport,
+	PaletteOperation,
+	Preview,
+	Sequence,
+	Undo,
+};
+use provenance::Provenance;
+use ramp::{
+	InterpolationSpace,
+	RampGenerator,
+};
 use result::Result;
+use space::{
+	DisplayModel,
+	RgbSpace,
+	WideColor,
+};
+use trace::Span;
+use trash::TrashedCell;
 
 // Standard imports.
+use std::cell::RefCell;
 use std::fmt;
+use std::io;
+use std::mem;
+use std::rc::Rc;
 
 
 
@@ -96,9 +253,18 @@ pub struct Palette {
 
 	/// The operation undo and redo history.
 	operation_history: Option<OperationHistory>,
+
+	/// The history entry of the pending interactive operation, if any.
+	interactive: Option<HistoryEntry>,
+
+	/// The data as it was before the pending interactive operation, if any.
+	interactive_base: Option<Data>,
 	
 	/// The palette format.
 	format: Format,
+
+	/// The registered operation hooks.
+	hooks: Hooks,
 }
 
 
@@ -114,7 +280,10 @@ impl Palette {
 				} else {
 				    None
 				},
+			interactive: None,
+			interactive_base: None,
 			format: format,
+			hooks: Default::default(),
 		};
 		
 		pal.data.set_name(Reference::all(), name.into());
@@ -150,35 +319,898 @@ impl Palette {
 		}
 	}
 
+	/// Squashes the most recent `count` history entries into a single entry.
+	/// Any pending interactive operation is finished first.
+	pub fn squash_history(&mut self, count: usize) {
+		self.finish_interactive();
+		if let Some(ref mut history) = self.operation_history {
+			history.squash(count);
+		}
+	}
+
+	/// Squashes each run of consecutive history entries for operations of the
+	/// same name into a single entry. Any pending interactive operation is 
+	/// finished first.
+	pub fn squash_history_runs(&mut self) {
+		self.finish_interactive();
+		if let Some(ref mut history) = self.operation_history {
+			history.squash_runs();
+		}
+	}
+
 	/// Returns the color at the given address, or None if the cell is empty.
 	pub fn color(&self, address: Address) -> Option<Color> {
 		self.data.cell(address).and_then(|cell| cell.color())
 	}
 
+	/// Returns the name of the `Palette`.
+	pub fn name(&self) -> Option<&str> {
+		self.data.name(&Reference::all())
+	}
+
+	/// Returns the names and addresses of all named cells, in address order.
+	pub fn named_cells(&self) -> Vec<(String, Address)> {
+		self.data.named_cells()
+	}
+
+	/// Returns the name of the cell at the given address, or None if it has
+	/// no name.
+	pub fn cell_name(&self, address: Address) -> Option<&str> {
+		self.data.name(&Reference::from(address))
+	}
+
+	/// Sets the name of the cell at the given address, allowing the cell to be
+	/// looked up by name. Names are normalized to Unicode Normalization Form
+	/// C, so lookups match regardless of how accented letters were composed.
+	///
+	/// # Example
+	///
+	/// ```rust
+	/// use palette::*;
+	///
+	/// let mut pal = Palette::new("Example", Format::Default, true);
+	/// pal.set_name(Address::new(0, 0, 0), "cafe\u{301}");
+	///
+	/// assert_eq!(pal.address_of("caf\u{E9}"), Some(Address::new(0, 0, 0)));
+	/// ```
+	pub fn set_name<S>(&mut self, address: Address, name: S) 
+		where S: Into<String>
+	{
+		self.data.set_name(Reference::from(address), name);
+	}
+
+	/// Returns the description of the cell at the given address.
+	pub fn cell_description(&self, address: Address) -> Option<&str> {
+		self.data.description(&Reference::from(address))
+	}
+
+	/// Sets the description of the cell at the given address, or removes it if
+	/// None is given.
+	pub fn set_description(&mut self, address: Address, text: Option<String>) {
+		self.data.set_description(Reference::from(address), text);
+	}
+
+	/// Returns the tags of the cell at the given address, in sorted order.
+	pub fn cell_tags(&self, address: Address) -> Vec<&str> {
+		self.data.tags(&Reference::from(address))
+			.map_or_else(Vec::new, |tags| tags.iter().map(|t| &t[..]).collect())
+	}
+
+	/// Adds a tag to the cell at the given address.
+	pub fn add_tag<S>(&mut self, address: Address, tag: S) 
+		where S: Into<String>
+	{
+		self.data.add_tag(Reference::from(address), tag);
+	}
+
+	/// Removes a tag from the cell at the given address. Returns true if the 
+	/// cell had the tag.
+	pub fn remove_tag(&mut self, address: Address, tag: &str) -> bool {
+		self.data.remove_tag(&Reference::from(address), tag)
+	}
+
+	/// Returns the usage count of the cell at the given address, if it has 
+	/// one.
+	pub fn usage_count(&self, address: Address) -> Option<usize> {
+		self.data.metadata
+			.get(&Reference::from(address))
+			.and_then(|meta| meta.usage_count)
+	}
+
+	/// Sets the usage count of the cell at the given address.
+	pub fn set_usage_count(&mut self, address: Address, count: usize) {
+		self.data.metadata_mut()
+			.entry(Reference::from(address))
+			.or_insert_with(Default::default)
+			.usage_count = Some(count);
+	}
+
+	/// Returns the provenance of the cell at the given address, if it is 
+	/// known.
+	pub fn provenance(&self, address: Address) -> Option<&Provenance> {
+		self.data.metadata
+			.get(&Reference::from(address))
+			.and_then(|meta| meta.provenance.as_ref())
+	}
+
+	/// Sets the provenance of the cell at the given address.
+	pub fn set_provenance(&mut self, address: Address, provenance: Provenance) {
+		self.data.metadata_mut()
+			.entry(Reference::from(address))
+			.or_insert_with(Default::default)
+			.provenance = Some(provenance);
+	}
+
+	/// Returns the addresses and usage counts of all cells with usage counts,
+	/// ordered from most to least used.
+	pub fn cells_by_usage(&self) -> Vec<(Address, usize)> {
+		let mut cells: Vec<_> = self.data.cells
+			.keys()
+			.filter_map(|&address| self.usage_count(address)
+				.map(|count| (address, count)))
+			.collect();
+		cells.sort_by(|a, b| b.1.cmp(&a.1));
+		cells
+	}
+
+	/// Returns the address of the cell with the given name, or None if no cell
+	/// has the name.
+	pub fn address_of(&self, name: &str) -> Option<Address> {
+		self.data.address_of(name)
+	}
+
+	/// Returns the stable `CellId` of the cell at the given address, or None
+	/// if the address is empty.
+	pub fn cell_id(&self, address: Address) -> Option<CellId> {
+		self.data.cell_id(address)
+	}
+
+	/// Returns the current address of the cell with the given `CellId`, or
+	/// None if the cell has been removed.
+	pub fn address_of_id(&self, id: CellId) -> Option<Address> {
+		self.data.address_of_id(id)
+	}
+
+	/// Assigns a semantic token, such as "danger" or "surface-2", to the cell
+	/// at the given address. The token follows the cell when it is moved, and
+	/// when its deletion is undone or it is restored from the trash.
+	///
+	/// # Errors
+	///
+	/// Returns `Error::EmptyAddress` if there is no cell at the address.
+	///
+	/// # Example
+	///
+	/// ```rust
+	/// use palette::*;
+	/// use palette::operation::*;
+	///
+	/// let mut pal = Palette::new("Example", Format::Default, true);
+	/// pal.apply(Box::new(InsertColor::new(Color::new(200, 0, 0)))).unwrap();
+	/// pal.set_alias("danger", Address::new(0, 0, 0)).unwrap();
+	///
+	/// pal.apply(Box::new(MoveCell::new(
+	/// 	Address::new(0, 0, 0), 
+	/// 	Address::new(0, 1, 0)))).unwrap();
+	/// assert_eq!(pal.alias("danger"), Some(Address::new(0, 1, 0)));
+	///
+	/// pal.apply(Box::new(DeleteCell::new(Address::new(0, 1, 0)))).unwrap();
+	/// assert_eq!(pal.alias("danger"), None);
+	/// pal.undo().unwrap();
+	/// assert_eq!(pal.alias("danger"), Some(Address::new(0, 1, 0)));
+	/// ```
+	pub fn set_alias<S>(&mut self, token: S, address: Address) -> Result<()>
+		where S: Into<String>
+	{
+		self.data.set_alias(token, address)
+	}
+
+	/// Removes a semantic token. Returns true if the token was assigned.
+	pub fn remove_alias(&mut self, token: &str) -> bool {
+		self.data.remove_alias(token)
+	}
+
+	/// Returns the address of the cell assigned to the given token, or None if
+	/// the token is unassigned or its cell has been removed.
+	pub fn alias(&self, token: &str) -> Option<Address> {
+		self.data.alias(token)
+	}
+
+	/// Returns all semantic tokens and the addresses of their cells, in token
+	/// order. The address is None if the token's cell has been removed.
+	pub fn aliases(&self) -> Vec<(&str, Option<Address>)> {
+		self.data.aliases()
+	}
+
+	/// Returns the addresses and colors of all cells within the given 
+	/// selection that produce a color, in address order.
+	pub fn colors(&self, selection: &Selection) -> Vec<(Address, Color)> {
+		self.data.colors(selection)
+	}
+
+	/// Returns an iterator over the addresses and colors of the cells which 
+	/// produce a color, in address order, skipping free cells.
+	pub fn occupied<'a>(&'a self) -> OccupiedCells<'a> {
+		OccupiedCells::new(&self.data)
+	}
+
+	/// Returns the runs of free cells within each line of the pages up to the
+	/// one after the last occupied page, in address order.
+	pub fn free_runs(&self) -> Vec<FreeRun> {
+		occupancy::free_runs(&self.data)
+	}
+
+	/// Returns the first run of at least the given number of free cells 
+	/// within a line, or None if there is none.
+	pub fn find_free_run(&self, length: usize) -> Option<FreeRun> {
+		occupancy::find_free_run(&self.data, length)
+	}
+
+	/// Returns the first of the longest runs of free cells within a line.
+	pub fn largest_free_run(&self) -> Option<FreeRun> {
+		occupancy::largest_free_run(&self.data)
+	}
 
-	/// Applies the given operation to the `Palette`. Usually, this will just 
-	/// defer to the `PaletteOperation`'s apply method, but this could also 
-	/// provide extra functionality such as undo/redo and format-specific 
-	/// checks.
+	/// Returns statistics describing how much of the palette is occupied.
+	pub fn occupancy(&self) -> OccupancyStats {
+		occupancy::occupancy(&self.data)
+	}
+
+	/// Returns the working color space of the `Palette`.
+	pub fn color_space(&self) -> RgbSpace {
+		self.data.color_space
+	}
+
+	/// Sets the working color space of the `Palette`.
+	pub fn set_color_space(&mut self, space: RgbSpace) {
+		self.data.color_space = space;
+	}
+
+	/// Returns the tone curve applied to the `Palette`'s colors when they are
+	/// viewed or exported, if any.
+	pub fn tone_curve(&self) -> Option<&ToneCurve> {
+		self.data.tone_curve.as_ref()
+	}
+
+	/// Sets the tone curve applied to the `Palette`'s colors when they are
+	/// viewed or exported. The stored colors are not changed.
+	pub fn set_tone_curve(&mut self, tone_curve: Option<ToneCurve>) {
+		self.data.tone_curve = tone_curve;
+	}
+
+	/// Returns the address of the cell used as the `Palette`'s reference 
+	/// white, if any.
+	pub fn white_point(&self) -> Option<Address> {
+		self.data.white_point
+	}
+
+	/// Sets the address of the cell used as the `Palette`'s reference white.
+	pub fn set_white_point(&mut self, address: Option<Address>) {
+		self.data.white_point = address;
+	}
+
+	/// Returns the address of the cell used as the `Palette`'s reference 
+	/// black, if any.
+	pub fn black_point(&self) -> Option<Address> {
+		self.data.black_point
+	}
+
+	/// Sets the address of the cell used as the `Palette`'s reference black.
+	pub fn set_black_point(&mut self, address: Option<Address>) {
+		self.data.black_point = address;
+	}
+
+	/// Returns the addresses and colors of all cells within the given 
+	/// selection that produce a color, adjusted by the tone curve.
+	pub fn toned_colors(&self, selection: &Selection) -> Vec<(Address, Color)> {
+		let colors = self.colors(selection);
+		match self.data.tone_curve {
+			Some(ref tone) => colors
+				.into_iter()
+				.map(|(address, color)| (address, tone.apply(color)))
+				.collect(),
+			None => colors,
+		}
+	}
+
+	/// Returns a copy of the `Palette` with its tone curve applied to every 
+	/// cell, for exporting the adjusted colors. Cells in the copy hold plain
+	/// colors, and the copy has no tone curve or history.
+	///
+	/// # Example
+	///
+	/// ```rust
+	/// use palette::*;
+	/// use palette::curve::*;
+	/// use palette::operation::*;
+	///
+	/// let mut pal = Palette::new("Example", Format::Default, true);
+	/// pal.apply(Box::new(InsertColor::new(Color::new(0, 128, 255)))).unwrap();
+	/// let crt = Curve::new(vec![(0.0, 0.0), (1.0, 0.8)]);
+	/// pal.set_tone_curve(Some(ToneCurve::new().channel(ToneChannel::All, crt)));
+	///
+	/// let toned = pal.toned().unwrap();
+	/// let address = Address::new(0, 0, 0);
+	/// assert_eq!(pal.color(address), Some(Color::new(0, 128, 255)));
+	/// assert_eq!(toned.color(address), Some(Color::new(0, 102, 204)));
+	/// assert!(toned.tone_curve().is_none());
+	/// ```
+	pub fn toned(&self) -> io::Result<Palette> {
+		self.baked(self.toned_colors(&Selection::all()))
+	}
+
+	/// Returns the addresses and colors of all cells within the given 
+	/// selection that produce a color, as they would appear on the given 
+	/// display after the tone curve is applied.
+	pub fn simulated_colors(&self, selection: &Selection, display: DisplayModel)
+		-> Vec<(Address, Color)>
+	{
+		self.toned_colors(selection)
+			.into_iter()
+			.map(|(address, color)| (address, display.simulate(color)))
+			.collect()
+	}
+
+	/// Returns a copy of the `Palette` with its tone curve and the given
+	/// display simulation applied to every cell. Cells in the copy hold plain
+	/// colors, and the copy has no tone curve or history.
+	///
+	/// # Example
+	///
+	/// ```rust
+	/// use palette::*;
+	/// use palette::operation::*;
+	/// use palette::space::DisplayModel;
+	///
+	/// let mut pal = Palette::new("Example", Format::Default, true);
+	/// pal.apply(Box::new(InsertColor::new(Color::new(255, 0, 0)))).unwrap();
+	///
+	/// let dmg = pal.simulated(DisplayModel::GameBoy).unwrap();
+	/// let address = Address::new(0, 0, 0);
+	/// assert_eq!(pal.color(address), Some(Color::new(255, 0, 0)));
+	/// assert_eq!(dmg.color(address), Some(Color::new(48, 98, 48)));
+	/// ```
+	pub fn simulated(&self, display: DisplayModel) -> io::Result<Palette> {
+		self.baked(self.simulated_colors(&Selection::all(), display))
+	}
+
+	/// Returns a copy of the `Palette` with the given colors stored in their 
+	/// cells and no tone curve or history.
+	fn baked(&self, colors: Vec<(Address, Color)>) -> io::Result<Palette> {
+		let mut baked = self.copied(colors)?;
+		baked.data.tone_curve = None;
+		Ok(baked)
+	}
+
+	/// Returns a copy of the `Palette` with the given colors stored in their
+	/// cells and no history.
+	fn copied(&self, colors: Vec<(Address, Color)>) -> io::Result<Palette> {
+		let mut buf = Vec::new();
+		format::default::write_palette(self, &mut buf)?;
+		let copy = format::default::read_palette(&mut &buf[..])?;
+
+		for (address, color) in colors {
+			if let Some(cell) = copy.data.cell(address) {
+				*cell.borrow_mut() = Expression::Color(color);
+			}
+		}
+		Ok(copy)
+	}
+
+	/// Returns a copy of the palette `a` with its colors blended toward the 
+	/// matching colors of the palette `b` by the fraction `t`. Named cells are
+	/// matched to the cell of `b` with the same name, and other cells are 
+	/// matched by address. Cells without a match keep their colors. Colors are
+	/// blended in Oklab.
+	///
+	/// # Example
+	///
+	/// ```rust
+	/// use palette::*;
+	/// use palette::operation::*;
+	///
+	/// let mut day = Palette::new("Day", Format::Default, true);
+	/// day.apply(Box::new(InsertColor::new(Color::new(90, 160, 255)))).unwrap();
+	/// day.apply(Box::new(InsertColor::new(Color::new(255, 255, 255)))).unwrap();
+	/// day.set_name(Address::new(0, 0, 0), "sky");
+	///
+	/// let mut night = Palette::new("Night", Format::Default, true);
+	/// night.apply(Box::new(InsertColor::new(Color::new(0, 0, 0)))).unwrap();
+	/// night.apply(Box::new(InsertColor::new(Color::new(10, 20, 60)))).unwrap();
+	/// night.set_name(Address::new(0, 0, 1), "sky");
+	///
+	/// let dusk = Palette::interpolate(&day, &night, 0.5).unwrap();
+	/// let sky = dusk.color(Address::new(0, 0, 0)).unwrap();
+	/// assert!(sky.blue() < 255 && sky.blue() > 60);
+	/// assert_eq!(Palette::interpolate(&day, &night, 1.0).unwrap()
+	/// 	.color(Address::new(0, 0, 0)), Some(Color::new(10, 20, 60)));
+	/// ```
+	pub fn interpolate(a: &Palette, b: &Palette, t: f32) -> io::Result<Palette> {
+		let generator = RampGenerator::new().space(InterpolationSpace::Oklab);
+		let colors = a.colors(&Selection::all())
+			.into_iter()
+			.filter_map(|(address, color)| {
+				let matched = a.cell_name(address)
+					.and_then(|name| b.address_of(name))
+					.unwrap_or(address);
+				b.color(matched).map(|other| (address, generator
+					.interpolate_wide(color.into(), other.into(), t)
+					.quantize()))
+			})
+			.collect();
+		a.copied(colors)
+	}
+
+	/// Returns the given number of palettes evenly spaced between the palettes
+	/// `a` and `b`, excluding `a` and `b` themselves. The palettes are blended
+	/// as by `interpolate`.
+	pub fn interpolate_sequence(a: &Palette, b: &Palette, count: usize)
+		-> io::Result<Vec<Palette>>
+	{
+		(1..(count + 1))
+			.map(|i| Palette::interpolate(a, b, i as f32 / (count + 1) as f32))
+			.collect()
+	}
+
+	/// Returns the addresses and extended-range colors of all cells within the
+	/// given selection, converted into the given color space for export.
+	pub fn wide_colors(&self, selection: &Selection, space: RgbSpace) 
+		-> Vec<(Address, WideColor)> 
+	{
+		self.data.wide_colors(selection, space)
+	}
+
+
+	/// Applies the given operation to the `Palette`, returning a report of its
+	/// effects. Usually, this will just defer to the `PaletteOperation`'s 
+	/// apply method, but this could also provide extra functionality such as
+	/// undo/redo and format-specific checks. Any pending interactive operation
+	/// is finished first.
+	///
+	/// # Errors
+	///
+	/// Returns the error of the operation if it fails. Any changes it made 
+	/// before failing are discarded, leaving the palette unchanged.
+	///
+	/// # Example
+	///
+	/// ```rust
+	/// use palette::*;
+	/// use palette::data::Data;
+	/// use palette::operation::*;
+	/// use palette::result::{Error, Result};
+	///
+	/// // An operation which fails after creating a cell.
+	/// #[derive(Debug)]
+	/// struct Partial;
+	///
+	/// impl PaletteOperation for Partial {
+	/// 	fn info(&self) -> OperationInfo {
+	/// 		OperationInfo { name: "Partial".into(), details: None }
+	/// 	}
+	///
+	/// 	fn apply(&mut self, data: &mut Data) -> Result<HistoryEntry> {
+	/// 		data.create_cell(Address::new(0, 0, 1))?;
+	/// 		Err(Error::ConstraintsUnsatisfiable)
+	/// 	}
+	/// }
+	///
+	/// let mut pal = Palette::new("Example", Format::Default, true);
+	/// pal.apply(Box::new(InsertColor::new(Color::new(1, 2, 3)))).unwrap();
+	///
+	/// assert!(pal.apply(Box::new(Partial)).is_err());
+	/// assert_eq!(pal.len(), 1);
+	/// assert_eq!(pal.history_len(), (1, 0));
+	/// ```
 	#[allow(unused_variables)]
 	pub fn apply(
 		&mut self, 
 		operation: Box<PaletteOperation>)
-		-> Result<()> 
+		-> Result<OperationReport> 
 	{
-		self.format.apply_operation(self, operation)
+		self.finish_interactive();
+		let info = operation.info();
+		hook::before(self, &info)?;
+		let span = Span::enter("operation", &info.name);
+		let writes = self.data.cell_writes;
+		let before = self.data.fork();
+		let conflicts = match self.format.apply_operation(self, operation) {
+			Ok(conflicts) => conflicts,
+			Err(error) => {
+				// Discard the effects of a partially applied operation.
+				self.data = before;
+				return Err(error);
+			},
+		};
+		span.finish(self.data.cell_writes - writes);
+		let report = OperationReport::new(&before, &self.data, conflicts);
+		self.collect_trash(&before, false);
+
+		// Record the operation as the origin of the colors it set.
+		self.data.operation_count += 1;
+		let colored = self.colored_cells(&before, &report);
+		self.record_provenance(&colored, Provenance::Operation {
+			id: self.data.operation_count,
+			name: info.name.to_string(),
+		});
+		hook::after(self, &info);
+		Ok(report)
 	}
 
-	/// Reverses the most recently applied operation.
-	#[allow(unused_variables)]
+	/// Returns the addresses of the cells given a new color by the changes in
+	/// the given report. Cells which were only moved are omitted.
+	fn colored_cells(&self, before: &Data, report: &OperationReport) 
+		-> Vec<Address>
+	{
+		report.changes.iter()
+			.filter(|change| change.after.is_some())
+			.map(|change| change.address)
+			.filter(|&address| self.data.cell_id(address)
+				.and_then(|id| before.address_of_id(id))
+				.and_then(|from| before.cell(from))
+				.map_or(true, |cell| cell.color() != self.color(address)))
+			.collect()
+	}
+
+	/// Sets the provenance of the cells at the given addresses. Their previous
+	/// provenance is restored when the most recent history entry is undone.
+	fn record_provenance(
+		&mut self,
+		addresses: &[Address],
+		provenance: Provenance)
+	{
+		if addresses.is_empty() { return; }
+		let mut restore = Undo::new();
+		for &address in addresses {
+			let previous = self.provenance(address).cloned();
+			restore.record_provenance(address, previous);
+			self.set_provenance(address, provenance.clone());
+		}
+
+		let entry = self.operation_history
+			.as_mut()
+			.and_then(|history| history.undo_entries.last_mut());
+		if let Some(entry) = entry {
+			let undo = mem::replace(&mut entry.undo, Box::new(Undo::new()));
+			entry.undo = Box::new(Sequence::new(vec![Box::new(restore), undo]));
+		}
+	}
+
+	/// Returns the cells in the trash, in order of deletion.
+	pub fn trash(&self) -> &[TrashedCell] {
+		&self.data.trash
+	}
+
+	/// Restores the trashed cell at the given index of the trash, returning
+	/// the address it was restored to, or None if there is no such cell. The
+	/// cell is restored to its original address if it is free, or to the 
+	/// next free address after it, and keeps its `CellId`, so aliases and 
+	/// other references to it remain valid. The restoration is applied as an
+	/// operation and can be undone, which returns the cell to the trash.
+	pub fn restore_from_trash(&mut self, index: usize) 
+		-> Result<Option<Address>>
+	{
+		if index >= self.data.trash.len() { return Ok(None); }
+
+		let trashed = Rc::make_mut(&mut self.data.trash).remove(index);
+		let restored = self.data.first_free_address_after(trashed.address)
+			.and_then(|address| self.apply(Box::new(
+					InsertColor::expression(trashed.expression)
+						.located_at(address)))
+				.map(|_| address));
+		let address = match restored {
+			Ok(address) => address,
+			Err(error) => {
+				Rc::make_mut(&mut self.data.trash).insert(index, trashed);
+				return Err(error);
+			},
+		};
+
+		if let Some(id) = trashed.id {
+			self.data.reinstate_id(address, id);
+		}
+		if let Some(name) = trashed.name {
+			self.set_name(address, name);
+		}
+		if let Some(provenance) = trashed.provenance {
+			self.set_provenance(address, provenance);
+		}
+		Ok(Some(address))
+	}
+
+	/// Permanently removes the trashed cell at the given index of the trash,
+	/// returning it.
+	pub fn purge_from_trash(&mut self, index: usize) -> Option<TrashedCell> {
+		if index < self.data.trash.len() {
+			Some(Rc::make_mut(&mut self.data.trash).remove(index))
+		} else {
+			None
+		}
+	}
+
+	/// Permanently removes every cell from the trash, returning the number of
+	/// cells removed.
+	pub fn empty_trash(&mut self) -> usize {
+		let count = self.data.trash.len();
+		self.data.trash = Rc::new(Vec::new());
+		count
+	}
+
+	/// Moves the cells removed since the given fork of the palette's data to
+	/// the trash. Cells which were moved are not trashed. If `prune` is true,
+	/// trashed cells which reappear at their original address, as when their
+	/// deletion is undone, are removed from the trash.
+	fn collect_trash(&mut self, before: &Data, prune: bool) {
+		let removed: Vec<TrashedCell> = before.cells.iter()
+			.filter(|&(address, _)| !self.data.cells.contains_key(address))
+			.filter(|&(&address, _)| before.cell_id(address)
+				.and_then(|id| self.data.address_of_id(id))
+				.is_none())
+			.map(|(&address, cell)| TrashedCell {
+				address: address,
+				expression: *cell.borrow(),
+				name: before.name(&Reference::from(address))
+					.map(str::to_owned),
+				provenance: before.metadata.get(&Reference::from(address))
+					.and_then(|meta| meta.provenance.clone()),
+				id: before.cell_id(address),
+			})
+			.collect();
+
+		let restored: Vec<_> = self.data.cells.iter()
+			.filter(|&(address, _)| prune 
+				&& !before.cells.contains_key(address))
+			.map(|(&address, cell)| (address, cell.color()))
+			.collect();
+		if removed.is_empty() && restored.is_empty() { return; }
+
+		let trash = Rc::make_mut(&mut self.data.trash);
+		for (address, color) in restored {
+			let found = trash.iter().rposition(|trashed| 
+				trashed.address == address && 
+				trashed.expression.color() == color);
+			if let Some(i) = found { trash.remove(i); }
+		}
+		trash.extend(removed);
+	}
+
+	/// Registers a hook to be invoked before and after each operation applied
+	/// to the palette, returning an id which can be used to unregister it.
+	pub fn register_hook(&mut self, hook: Box<OperationHook>) -> HookId {
+		self.hooks.register(hook)
+	}
+
+	/// Unregisters the hook with the given id, returning it.
+	pub fn unregister_hook(&mut self, id: HookId) -> Option<Box<OperationHook>> {
+		self.hooks.unregister(id)
+	}
+
+	/// Returns the number of registered hooks.
+	pub fn hook_count(&self) -> usize {
+		self.hooks.len()
+	}
+
+	/// Merges the colors of the given palette into the `Palette` using the
+	/// given strategy, returning a report of where each color was placed. The
+	/// merge is recorded as a single history entry.
+	pub fn merge(&mut self, source: &Palette, strategy: MergeStrategy) 
+		-> Result<MergeReport>
+	{
+		let report = Rc::new(RefCell::new(MergeReport::default()));
+		self.apply(Box::new(MergePalette::new(source, strategy)
+			.share_report(report.clone())))?;
+		let report = report.borrow().clone();
+		Ok(report)
+	}
+
+	/// Applies the given operation as an interactive operation, replacing the
+	/// effects of any previously updated interactive operation. This allows an
+	/// operation to be repeatedly adjusted, such as while the user drags a
+	/// slider, without recording each intermediate value in the history.
+	///
+	/// # Example
+	///
+	/// ```rust
+	/// use palette::*;
+	/// use palette::operation::*;
+	///
+	/// let mut pal = Palette::new("Example", Format::Default, true);
+	/// let a = Address::new(0, 0, 0);
+	///
+	/// for v in 0..10 {
+	/// 	pal.update_interactive(Box::new(
+	/// 		InsertColor::new(Color::new(v, v, v)).located_at(a)
+	/// 	)).unwrap();
+	/// }
+	/// pal.finish_interactive();
+	///
+	/// assert_eq!(pal.color(a), Some(Color::new(9, 9, 9)));
+	/// assert_eq!(pal.provenance(a), Some(&provenance::Provenance::Manual));
+	/// assert_eq!(pal.history_len(), (1, 0));
+	///
+	/// pal.undo().unwrap();
+	/// assert!(pal.is_empty());
+	/// ```
+	pub fn update_interactive(&mut self, operation: Box<PaletteOperation>) 
+		-> Result<()>
+	{
+		let info = operation.info();
+		hook::before(self, &info)?;
+		if self.interactive.is_none() {
+			self.interactive_base = Some(self.data.fork());
+		}
+		if let Err(error) = self.format.update_interactive(self, operation) {
+			// Without a pending operation, there is no base to finish from.
+			if self.interactive.is_none() {
+				self.interactive_base = None;
+			}
+			return Err(error);
+		}
+		hook::after(self, &info);
+		Ok(())
+	}
+
+	/// Returns whether an interactive operation is pending.
+	pub fn is_interactive(&self) -> bool {
+		self.interactive.is_some()
+	}
+
+	/// Finishes the pending interactive operation, recording its final value
+	/// as a single history entry. The cells it colored are recorded as edited
+	/// by hand.
+	pub fn finish_interactive(&mut self) {
+		self.format.finish_interactive(self);
+		if let Some(base) = self.interactive_base.take() {
+			let report = OperationReport::new(&base, &self.data, Vec::new());
+			let colored = self.colored_cells(&base, &report);
+			self.record_provenance(&colored, Provenance::Manual);
+		}
+	}
+
+	/// Reverts the pending interactive operation without recording it.
+	pub fn cancel_interactive(&mut self) -> Result<()> {
+		self.interactive_base = None;
+		self.format.cancel_interactive(self)
+	}
+
+	/// Evaluates the given operation without committing it, returning the
+	/// color changes it would make. The palette and its history are left
+	/// unchanged.
+	///
+	/// # Example
+	///
+	/// ```rust
+	/// use palette::*;
+	/// use palette::operation::*;
+	///
+	/// let mut pal = Palette::new("Example", Format::Default, true);
+	///
+	/// let preview = pal.preview(&mut InsertColor::new(Color::new(1, 2, 3)))
+	/// 	.unwrap();
+	///
+	/// assert_eq!(preview.color(Address::new(0, 0, 0)), 
+	/// 	Some(Some(Color::new(1, 2, 3))));
+	/// assert!(pal.is_empty());
+	/// ```
+	pub fn preview<O>(&self, operation: &mut O) -> Result<Preview>
+		where O: PaletteOperation + ?Sized
+	{
+		operation::preview(&self.data, operation)
+	}
+
+	/// Returns a logical copy of the `Palette` for trying out operations. The
+	/// copy shares its cells with the original until either is modified, and
+	/// has no history or hooks. The original is unaffected by operations 
+	/// applied to the copy.
+	///
+	/// # Example
+	///
+	/// ```rust
+	/// use palette::*;
+	/// use palette::operation::*;
+	///
+	/// let mut pal = Palette::new("Example", Format::Default, true);
+	/// pal.apply(Box::new(InsertColor::new(Color::new(1, 2, 3)))).unwrap();
+	///
+	/// let mut trial = pal.fork();
+	/// let op = InsertColor::new(Color::new(4, 5, 6))
+	/// 	.located_at(Address::new(0, 0, 0))
+	/// 	.overwrite(true);
+	/// trial.apply(Box::new(op)).unwrap();
+	///
+	/// assert_eq!(pal.color(Address::new(0, 0, 0)), Some(Color::new(1, 2, 3)));
+	/// assert_eq!(trial.color(Address::new(0, 0, 0)), 
+	/// 	Some(Color::new(4, 5, 6)));
+	/// ```
+	pub fn fork(&self) -> Palette {
+		Palette {
+			data: self.data.fork(),
+			operation_history: None,
+			interactive: None,
+			interactive_base: None,
+			format: self.format,
+			hooks: Default::default(),
+		}
+	}
+
+	/// Reverses the most recently applied operation. Any pending interactive
+	/// operation is finished first.
+	///
+	/// # Errors
+	///
+	/// Returns the error of the undo operation if it fails, in which case the
+	/// history entry is kept so the undo can be retried.
+	///
+	/// # Example
+	///
+	/// ```rust
+	/// use palette::*;
+	/// use palette::data::Data;
+	/// use palette::operation::*;
+	/// use palette::result::{Error, Result};
+	///
+	/// // An operation which fails once it has been reversed `n` times.
+	/// #[derive(Debug)]
+	/// struct Brittle(usize);
+	///
+	/// impl PaletteOperation for Brittle {
+	/// 	fn info(&self) -> OperationInfo {
+	/// 		OperationInfo { name: "Brittle".into(), details: None }
+	/// 	}
+	///
+	/// 	fn apply(&mut self, _: &mut Data) -> Result<HistoryEntry> {
+	/// 		if self.0 == 0 { return Err(Error::ConstraintsUnsatisfiable); }
+	/// 		Ok(HistoryEntry {
+	/// 			info: self.info(),
+	/// 			undo: Box::new(Brittle(self.0 - 1)),
+	/// 			conflicts: Vec::new(),
+	/// 		})
+	/// 	}
+	/// }
+	///
+	/// let mut pal = Palette::new("Example", Format::Default, true);
+	/// pal.apply(Box::new(InsertColor::new(Color::new(1, 2, 3)))).unwrap();
+	/// pal.apply(Box::new(Brittle(1))).unwrap();
+	///
+	/// assert!(pal.undo().is_err());
+	/// assert_eq!(pal.history_len(), (2, 0));
+	///
+	/// pal.apply(Box::new(Brittle(2))).unwrap();
+	/// pal.undo().unwrap();
+	/// assert!(pal.redo().is_err());
+	/// assert_eq!(pal.history_len(), (2, 1));
+	/// assert_eq!(pal.color(Address::new(0, 0, 0)), Some(Color::new(1, 2, 3)));
+	/// ```
 	pub fn undo(&mut self) -> Result<()> {
-		self.format.undo(self)
+		self.finish_interactive();
+		let span = Span::enter("operation", "Undo");
+		let writes = self.data.cell_writes;
+		let before = self.data.fork();
+		if let Err(error) = self.format.undo(self) {
+			// Discard the effects of a partially applied entry.
+			self.data = before;
+			return Err(error);
+		}
+		span.finish(self.data.cell_writes - writes);
+		self.collect_trash(&before, true);
+		Ok(())
 	}
 
-	/// Reverses the most recently applied undo operation.
-	#[allow(unused_variables)]
+	/// Reverses the most recently applied undo operation. Any pending 
+	/// interactive operation is finished first.
 	pub fn redo(&mut self) -> Result<()> {
-		self.format.redo(self)
+		self.finish_interactive();
+		let span = Span::enter("operation", "Redo");
+		let writes = self.data.cell_writes;
+		let before = self.data.fork();
+		if let Err(error) = self.format.redo(self) {
+			// Discard the effects of a partially applied entry.
+			self.data = before;
+			return Err(error);
+		}
+		span.finish(self.data.cell_writes - writes);
+		self.collect_trash(&before, true);
+		Ok(())
 	}
 }
 
@@ -190,7 +1222,10 @@ impl Default for Palette {
 		Palette {
 			data: Default::default(),
 			operation_history: None,
+			interactive: None,
+			interactive_base: None,
 			format: Format::Default,
+			hooks: Default::default(),
 		}
 	}
 }
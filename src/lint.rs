@@ -0,0 +1,426 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides a configurable lint pass for checking palettes against project
+//! conventions.
+//!
+//! A `Linter` is configured with a set of rules, each with a severity, and 
+//! produces a `LintReport` listing the diagnostics for a palette. Linter 
+//! configurations can be read from rule files, which contain one rule per
+//! line:
+//!
+//! ```text
+//! # Rules are given as: <rule> <severity> <arguments...>
+//! max-ramp-colors error 16
+//! name-pattern warning "^[a-z]+(\\.[a-z0-9]+)*$"
+//! require warning name tag
+//! no-black-white error
+//! min-contrast error "text" "background" 4.5
+//! ```
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use ::Palette;
+use address::{
+	Address,
+	Selection,
+};
+use format::default;
+use space::contrast_ratio;
+
+// Non-local imports.
+use color::Color;
+use regex::Regex;
+
+// Standard imports.
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io;
+use std::io::BufRead;
+use std::io::BufReader;
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Severity
+////////////////////////////////////////////////////////////////////////////////
+/// The severity of a lint diagnostic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+	/// The diagnostic is reported, but does not fail the lint pass.
+	Warning,
+	/// The diagnostic fails the lint pass.
+	Error,
+}
+
+
+impl Severity {
+	/// Returns the name of the severity.
+	pub fn name(&self) -> &'static str {
+		match *self {
+			Severity::Warning => "warning",
+			Severity::Error   => "error",
+		}
+	}
+
+	/// Returns the severity with the given name, ignoring case.
+	pub fn from_name(name: &str) -> Option<Severity> {
+		[Severity::Warning, Severity::Error].iter()
+			.find(|s| s.name().eq_ignore_ascii_case(name))
+			.cloned()
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Field
+////////////////////////////////////////////////////////////////////////////////
+/// A metadata field of a palette cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Field {
+	/// The cell's name.
+	Name,
+	/// The cell's tags. Present if the cell has at least one tag.
+	Tag,
+	/// The cell's usage count.
+	Usage,
+}
+
+
+impl Field {
+	/// Returns the name of the field.
+	pub fn name(&self) -> &'static str {
+		match *self {
+			Field::Name  => "name",
+			Field::Tag   => "tag",
+			Field::Usage => "usage",
+		}
+	}
+
+	/// Returns the field with the given name, ignoring case.
+	pub fn from_name(name: &str) -> Option<Field> {
+		[Field::Name, Field::Tag, Field::Usage].iter()
+			.find(|f| f.name().eq_ignore_ascii_case(name))
+			.cloned()
+	}
+
+	/// Returns whether the given palette cell has the field.
+	pub fn is_present(&self, palette: &Palette, address: Address) -> bool {
+		match *self {
+			Field::Name  => palette.cell_name(address).is_some(),
+			Field::Tag   => !palette.cell_tags(address).is_empty(),
+			Field::Usage => palette.usage_count(address).is_some(),
+		}
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Rule
+////////////////////////////////////////////////////////////////////////////////
+/// A lint rule.
+#[derive(Debug, Clone)]
+pub enum Rule {
+	/// Limits the number of colors in each ramp, where each line of the
+	/// palette is considered a ramp.
+	MaxRampColors(usize),
+	/// Requires every cell name to match the given pattern.
+	NamePattern(Regex),
+	/// Requires every cell to have the given metadata fields.
+	Require(Vec<Field>),
+	/// Forbids pure black and pure white colors.
+	NoBlackWhite,
+	/// Requires every cell with the first tag to have at least the given 
+	/// contrast ratio against every cell with the second tag.
+	MinContrast(String, String, f32),
+}
+
+
+impl Rule {
+	/// Returns the name of the rule, as used in rule files and diagnostics.
+	pub fn name(&self) -> &'static str {
+		match *self {
+			Rule::MaxRampColors(..) => "max-ramp-colors",
+			Rule::NamePattern(..)   => "name-pattern",
+			Rule::Require(..)       => "require",
+			Rule::NoBlackWhite      => "no-black-white",
+			Rule::MinContrast(..)   => "min-contrast",
+		}
+	}
+
+	/// Checks the given palette cells against the rule, returning the 
+	/// addresses and messages of any violations.
+	fn check(&self, palette: &Palette, cells: &[(Address, Color)]) 
+		-> Vec<(Option<Address>, String)>
+	{
+		match *self {
+			Rule::MaxRampColors(max) => {
+				let mut lines = BTreeMap::new();
+				for &(address, _) in cells {
+					*lines.entry((address.page, address.line))
+						.or_insert(0) += 1;
+				}
+				lines.into_iter()
+					.filter(|&(_, count)| count > max)
+					.map(|((page, line), count)| (
+						Some(Address::new(page, line, 0)),
+						format!("line {}:{} has {} colors, more than {}", 
+							page, line, count, max)))
+					.collect()
+			},
+
+			Rule::NamePattern(ref pattern) => palette.named_cells()
+				.into_iter()
+				.filter(|&(ref name, _)| !pattern.is_match(name))
+				.map(|(name, address)| (Some(address), format!(
+					"name '{}' does not match '{}'", name, pattern)))
+				.collect(),
+
+			Rule::Require(ref fields) => cells.iter()
+				.flat_map(|&(address, _)| fields.iter()
+					.filter(move |f| !f.is_present(palette, address))
+					.map(move |f| (Some(address), 
+						format!("missing {}", f.name()))))
+				.collect(),
+
+			Rule::NoBlackWhite => cells.iter()
+				.filter_map(|&(address, color)| {
+					match (color.red(), color.green(), color.blue()) {
+						(0, 0, 0) => Some("pure black"),
+						(255, 255, 255) => Some("pure white"),
+						_ => None,
+					}.map(|c| (Some(address), format!("uses {}", c)))
+				})
+				.collect(),
+
+			Rule::MinContrast(ref first, ref second, ratio) => {
+				let tagged = |tag: &str| cells.iter()
+					.filter(|&&(address, _)| palette.cell_tags(address)
+						.contains(&tag))
+					.cloned()
+					.collect::<Vec<_>>();
+				let (firsts, seconds) = (tagged(first), tagged(second));
+
+				let mut violations = Vec::new();
+				for &(a, a_color) in &firsts {
+					for &(b, b_color) in &seconds {
+						let contrast = contrast_ratio(a_color, b_color);
+						if contrast < ratio {
+							violations.push((Some(a), format!(
+								"contrast against {} is {:.2}, less than {}",
+								b, contrast, ratio)));
+						}
+					}
+				}
+				violations
+			},
+		}
+	}
+
+	/// Parses a rule from its name and argument tokens.
+	fn parse(name: &str, args: &[String]) -> Result<Rule, String> {
+		let arg = |index: usize| default::token(args, index);
+		match name {
+			"max-ramp-colors" => Ok(Rule::MaxRampColors(
+				default::parse(&arg(0)?)?)),
+			"name-pattern" => Regex::new(&arg(0)?)
+				.map(Rule::NamePattern)
+				.map_err(|e| format!("{}", e)),
+			"require" => args.iter()
+				.map(|field| Field::from_name(field)
+					.ok_or_else(|| format!("unknown field '{}'", field)))
+				.collect::<Result<Vec<_>, _>>()
+				.map(Rule::Require),
+			"no-black-white" => Ok(Rule::NoBlackWhite),
+			"min-contrast" => Ok(Rule::MinContrast(
+				arg(0)?, 
+				arg(1)?, 
+				default::parse(&arg(2)?)?)),
+			_ => Err(format!("unknown rule '{}'", name)),
+		}
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Diagnostic
+////////////////////////////////////////////////////////////////////////////////
+/// A rule violation found by a lint pass.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+	/// The name of the violated rule.
+	pub rule: &'static str,
+	/// The severity of the violation.
+	pub severity: Severity,
+	/// The address of the offending cell or line, if any.
+	pub address: Option<Address>,
+	/// A description of the violation.
+	pub message: String,
+}
+
+
+impl fmt::Display for Diagnostic {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}[{}]", self.severity.name(), self.rule)?;
+		if let Some(address) = self.address {
+			write!(f, " {}", address)?;
+		}
+		write!(f, ": {}", self.message)
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// LintReport
+////////////////////////////////////////////////////////////////////////////////
+/// The diagnostics produced by a lint pass.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct LintReport {
+	/// The diagnostics, ordered by rule and then by address.
+	pub diagnostics: Vec<Diagnostic>,
+}
+
+
+impl LintReport {
+	/// Returns the number of diagnostics with the given severity.
+	pub fn count(&self, severity: Severity) -> usize {
+		self.diagnostics.iter().filter(|d| d.severity == severity).count()
+	}
+
+	/// Returns whether the report contains any errors.
+	pub fn has_errors(&self) -> bool {
+		self.count(Severity::Error) > 0
+	}
+
+	/// Returns whether the report contains no diagnostics.
+	pub fn is_empty(&self) -> bool {
+		self.diagnostics.is_empty()
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Linter
+////////////////////////////////////////////////////////////////////////////////
+/// Checks palettes against a configured set of rules.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::operation::*;
+/// use palette::lint::{Linter, Rule, Severity};
+///
+/// let mut pal = Palette::new("Example", Format::Default, true);
+/// pal.apply(Box::new(InsertColor::new(Color::new(0, 0, 0)))).unwrap();
+/// pal.apply(Box::new(InsertColor::new(Color::new(90, 90, 90)))).unwrap();
+/// pal.add_tag(Address::new(0, 0, 0), "text");
+/// pal.add_tag(Address::new(0, 0, 1), "background");
+///
+/// let linter = Linter::new()
+/// 	.rule(Rule::NoBlackWhite, Severity::Warning)
+/// 	.rule(Rule::MinContrast("text".into(), "background".into(), 4.5), 
+/// 		Severity::Error);
+/// let report = linter.lint(&pal);
+///
+/// assert_eq!(report.count(Severity::Warning), 1);
+/// assert!(report.has_errors());
+/// assert_eq!(report.diagnostics[1].to_string(), "error[min-contrast] 0:0:0: \
+/// 	contrast against 0:0:1 is 3.04, less than 4.5");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Linter {
+	/// The rules to check, with their severities.
+	rules: Vec<(Rule, Severity)>,
+}
+
+
+impl Linter {
+	/// Creates a new `Linter` with no rules.
+	pub fn new() -> Self {
+		Linter::default()
+	}
+
+	/// Adds a rule with the given severity to the linter.
+	pub fn rule(mut self, rule: Rule, severity: Severity) -> Self {
+		self.rules.push((rule, severity));
+		self
+	}
+
+	/// Returns the linter's rules and their severities.
+	pub fn rules(&self) -> &[(Rule, Severity)] {
+		&self.rules[..]
+	}
+
+	/// Checks the given palette against the linter's rules.
+	pub fn lint(&self, palette: &Palette) -> LintReport {
+		let cells = palette.colors(&Selection::all());
+		LintReport {
+			diagnostics: self.rules.iter()
+				.flat_map(|&(ref rule, severity)| rule
+					.check(palette, &cells[..])
+					.into_iter()
+					.map(move |(address, message)| Diagnostic {
+						rule: rule.name(),
+						severity: severity,
+						address: address,
+						message: message,
+					}))
+				.collect(),
+		}
+	}
+
+	/// Reads a `Linter` from the given rule file buffer.
+	///
+	/// # Errors
+	///
+	/// Returns an `InvalidData` error identifying the line of any unknown or
+	/// malformed rule.
+	pub fn read<R>(in_buf: &mut R) -> io::Result<Linter> 
+		where R: io::Read
+	{
+		let mut linter = Linter::new();
+		for (i, line) in BufReader::new(in_buf).lines().enumerate() {
+			let line = line?;
+			let tokens = default::tokenize(&line)
+				.map_err(|e| default::invalid(i, e))?;
+			if tokens.is_empty() { continue; }
+
+			let severity = default::token(&tokens, 1)
+				.and_then(|name| Severity::from_name(&name)
+					.ok_or_else(|| format!("unknown severity '{}'", name)))
+				.map_err(|e| default::invalid(i, e))?;
+			let rule = Rule::parse(&tokens[0], &tokens[2..])
+				.map_err(|e| default::invalid(i, e))?;
+			linter.rules.push((rule, severity));
+		}
+		Ok(linter)
+	}
+}
@@ -0,0 +1,482 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides advisory session locks for palettes opened from shared drives.
+//!
+//! A `SessionLock` is held by writing a lock file beside the palette, named by
+//! appending `.lock` to the palette's file name. The lock file records who 
+//! holds the lock and when it was last refreshed:
+//!
+//! ```text
+//! lock 1
+//! session "5f0e8c5d2a7b9e31"
+//! user "ana"
+//! host "studio-3"
+//! pid 4242
+//! acquired 1760000000
+//! refreshed 1760000300
+//! ```
+//!
+//! Locks are advisory: they do not prevent other programs from writing the 
+//! palette, but cooperating editors will refuse to open a locked palette, and
+//! a lock holder will refuse to save over a palette whose lock was taken over.
+//! Holders should refresh their lock periodically; a lock which has not been 
+//! refreshed for some time is considered stale, as its holder has likely 
+//! crashed or lost its connection, and may be taken over.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use ::Palette;
+use export::checksum;
+use format::default;
+
+// Standard imports.
+use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::fs::{
+	File,
+	OpenOptions,
+};
+use std::io;
+use std::io::{
+	BufRead,
+	BufWriter,
+	Write,
+};
+use std::path::{
+	Path,
+	PathBuf,
+};
+use std::process;
+use std::time::{
+	Duration,
+	SystemTime,
+	UNIX_EPOCH,
+};
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// LockOwner
+////////////////////////////////////////////////////////////////////////////////
+/// Describes the holder of a session lock.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LockOwner {
+	/// The name of the user holding the lock.
+	pub user: String,
+	/// The name of the machine holding the lock.
+	pub host: String,
+	/// The id of the process holding the lock.
+	pub pid: u32,
+}
+
+
+impl LockOwner {
+	/// Creates a new `LockOwner` for the current process with the given user
+	/// and host names.
+	pub fn new<U, H>(user: U, host: H) -> Self
+		where U: Into<String>, H: Into<String>
+	{
+		LockOwner {
+			user: user.into(),
+			host: host.into(),
+			pid: process::id(),
+		}
+	}
+
+	/// Returns the `LockOwner` for the current process, taking the user and 
+	/// host names from the environment.
+	pub fn current() -> Self {
+		let var = |names: &[&str]| names.iter()
+			.filter_map(|name| env::var(name).ok())
+			.find(|value| !value.is_empty())
+			.unwrap_or_else(|| "unknown".to_owned());
+		LockOwner::new(
+			var(&["USER", "USERNAME"]), 
+			var(&["HOSTNAME", "COMPUTERNAME"]))
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// LockInfo
+////////////////////////////////////////////////////////////////////////////////
+/// The contents of a lock file.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LockInfo {
+	/// An identifier unique to the session holding the lock.
+	pub session: String,
+	/// The holder of the lock.
+	pub owner: LockOwner,
+	/// The time the lock was acquired.
+	pub acquired: SystemTime,
+	/// The time the lock was last refreshed.
+	pub refreshed: SystemTime,
+}
+
+
+impl LockInfo {
+	/// Creates a new `LockInfo` for a session of the given owner starting now.
+	fn new(owner: LockOwner) -> Self {
+		let now = SystemTime::now();
+		let nanos = now.duration_since(UNIX_EPOCH)
+			.map_or(0, |d| d.as_nanos());
+		let seed = format!("{} {} {} {}", 
+			owner.user, owner.host, owner.pid, nanos);
+		LockInfo {
+			session: format!("{:016x}", checksum(seed.as_bytes())),
+			owner: owner,
+			acquired: now,
+			refreshed: now,
+		}
+	}
+
+	/// Returns true if the lock has not been refreshed for at least the given
+	/// duration.
+	///
+	/// # Example
+	///
+	/// ```rust
+	/// use palette::lock::{LockInfo, LockOwner};
+	/// use std::time::{Duration, SystemTime};
+	///
+	/// let hour = Duration::from_secs(3600);
+	/// let info = LockInfo {
+	/// 	session: "1".into(),
+	/// 	owner: LockOwner::new("ana", "studio-3"),
+	/// 	acquired: SystemTime::now() - 2 * hour,
+	/// 	refreshed: SystemTime::now() - hour,
+	/// };
+	/// assert!(info.is_stale(Duration::from_secs(600)));
+	/// assert!(!info.is_stale(2 * hour));
+	/// ```
+	pub fn is_stale(&self, stale_after: Duration) -> bool {
+		SystemTime::now()
+			.duration_since(self.refreshed)
+			.map_or(false, |elapsed| elapsed >= stale_after)
+	}
+
+	/// Writes the lock information to the given buffer.
+	fn write<W>(&self, out_buf: &mut W) -> io::Result<()> where W: Write {
+		writeln!(out_buf, "lock 1")?;
+		writeln!(out_buf, "session {}", default::quote(&self.session))?;
+		writeln!(out_buf, "user {}", default::quote(&self.owner.user))?;
+		writeln!(out_buf, "host {}", default::quote(&self.owner.host))?;
+		writeln!(out_buf, "pid {}", self.owner.pid)?;
+		writeln!(out_buf, "acquired {}", seconds(self.acquired))?;
+		writeln!(out_buf, "refreshed {}", seconds(self.refreshed))
+	}
+
+	/// Reads lock information from the given buffer.
+	///
+	/// # Errors
+	///
+	/// Returns an `InvalidData` error if the lock information is malformed.
+	pub fn read<R>(in_buf: &mut R) -> io::Result<LockInfo> where R: BufRead {
+		let mut info = LockInfo::new(LockOwner::new("", ""));
+		let mut version = None;
+		for (i, line) in in_buf.lines().enumerate() {
+			let tokens = default::tokenize(&line?)
+				.map_err(|e| default::invalid(i, e))?;
+			let value = || default::token(&tokens, 1);
+			let time = || value()
+				.and_then(|v| default::parse(&v))
+				.map(|secs| UNIX_EPOCH + Duration::from_secs(secs));
+			let result = match tokens.first().map(|t| &t[..]) {
+				Some("lock") => value().map(|v| version = Some(v)),
+				Some("session") => value().map(|v| info.session = v),
+				Some("user") => value().map(|v| info.owner.user = v),
+				Some("host") => value().map(|v| info.owner.host = v),
+				Some("pid") => value()
+					.and_then(|v| default::parse(&v))
+					.map(|pid| info.owner.pid = pid),
+				Some("acquired") => time().map(|t| info.acquired = t),
+				Some("refreshed") => time().map(|t| info.refreshed = t),
+				Some(key) => Err(format!("unknown lock field '{}'", key)),
+				None => Ok(()),
+			};
+			result.map_err(|e| default::invalid(i, e))?;
+		}
+
+		match version.as_ref().map(|v| &v[..]) {
+			Some("1") => Ok(info),
+			Some(v) => Err(io::Error::new(io::ErrorKind::InvalidData, 
+				format!("unsupported lock version '{}'", v))),
+			None => Err(io::Error::new(io::ErrorKind::InvalidData, 
+				"missing lock header")),
+		}
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// SessionLock
+////////////////////////////////////////////////////////////////////////////////
+/// An advisory lock on a palette file, released when dropped.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use palette::lock::{read_lock, LockOwner, SessionLock};
+/// use std::io;
+/// use std::time::Duration;
+///
+/// let path = "//share/art/skin.palette";
+/// let owner = LockOwner::current();
+/// let mut lock = match SessionLock::acquire(path, owner.clone()) {
+/// 	Err(ref e) if e.kind() == io::ErrorKind::AlreadyExists => {
+/// 		// Take over the lock only if its holder has gone away.
+/// 		let holder = read_lock(path).unwrap();
+/// 		if holder.map_or(true, |h| h.is_stale(Duration::from_secs(600))) {
+/// 			SessionLock::take_over(path, owner).unwrap().0
+/// 		} else {
+/// 			panic!("{}", e);
+/// 		}
+/// 	},
+/// 	result => result.unwrap(),
+/// };
+///
+/// let mut pal = lock.open().unwrap();
+/// // ... edit the palette, calling `lock.refresh()` periodically ...
+/// lock.save(&pal).unwrap();
+/// lock.release().unwrap();
+/// ```
+#[derive(Debug)]
+pub struct SessionLock {
+	/// The path of the locked palette.
+	path: PathBuf,
+	/// The contents of the lock file as last written.
+	info: LockInfo,
+	/// Whether the lock is still held.
+	held: bool,
+}
+
+
+impl SessionLock {
+	/// Acquires the lock on the palette at the given path.
+	///
+	/// # Errors
+	///
+	/// Returns an `AlreadyExists` error naming the holder if the palette is
+	/// already locked, whether or not the lock is stale.
+	pub fn acquire<P>(path: P, owner: LockOwner) -> io::Result<SessionLock>
+		where P: AsRef<Path>
+	{
+		let path = path.as_ref().to_path_buf();
+		let info = LockInfo::new(owner);
+		let file = OpenOptions::new()
+			.write(true)
+			.create_new(true)
+			.open(lock_path(&path));
+
+		match file {
+			Ok(file) => {
+				let mut out = BufWriter::new(file);
+				let written = info.write(&mut out).and_then(|_| out.flush());
+				if let Err(e) = written {
+					// Don't leave a partial lock which can't be read or 
+					// replaced by acquiring.
+					drop(out);
+					let _ = fs::remove_file(lock_path(&path));
+					return Err(e);
+				}
+			},
+			Err(ref e) if e.kind() == io::ErrorKind::AlreadyExists => {
+				let holder = read_lock(&path)?
+					.map_or_else(|| "another session".to_owned(), |h| 
+						format!("{} on {}", h.owner.user, h.owner.host));
+				return Err(io::Error::new(io::ErrorKind::AlreadyExists, 
+					format!("{} is locked by {}", path.display(), holder)));
+			},
+			Err(e) => return Err(e),
+		}
+
+		Ok(SessionLock {path: path, info: info, held: true})
+	}
+
+	/// Acquires the lock on the palette at the given path, replacing any 
+	/// existing lock. Returns the lock and the replaced lock's information, 
+	/// if there was one. The previous holder will fail to save or refresh 
+	/// its lock.
+	pub fn take_over<P>(path: P, owner: LockOwner) 
+		-> io::Result<(SessionLock, Option<LockInfo>)>
+		where P: AsRef<Path>
+	{
+		let path = path.as_ref().to_path_buf();
+		let previous = read_lock(&path).unwrap_or(None);
+		let lock = SessionLock {
+			path: path,
+			info: LockInfo::new(owner),
+			held: true,
+		};
+		lock.write_info()?;
+		Ok((lock, previous))
+	}
+
+	/// Returns the path of the locked palette.
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	/// Returns the information recorded in the lock file.
+	pub fn info(&self) -> &LockInfo {
+		&self.info
+	}
+
+	/// Verifies that the lock is still held by this session.
+	///
+	/// # Errors
+	///
+	/// Returns a `PermissionDenied` error naming the new holder if the lock 
+	/// was taken over or removed.
+	pub fn verify(&self) -> io::Result<()> {
+		match read_lock(&self.path)? {
+			Some(ref current) if current.session == self.info.session 
+				=> Ok(()),
+			Some(current) => Err(io::Error::new(
+				io::ErrorKind::PermissionDenied, 
+				format!("the lock on {} was taken over by {} on {}", 
+					self.path.display(), 
+					current.owner.user, 
+					current.owner.host))),
+			None => Err(io::Error::new(
+				io::ErrorKind::PermissionDenied, 
+				format!("the lock on {} was removed", self.path.display()))),
+		}
+	}
+
+	/// Records that the session is still active, so the lock does not become
+	/// stale.
+	pub fn refresh(&mut self) -> io::Result<()> {
+		self.verify()?;
+		self.info.refreshed = SystemTime::now();
+		self.write_info()
+	}
+
+	/// Reads the locked palette.
+	pub fn open(&self) -> io::Result<Palette> {
+		let mut file = File::open(&self.path)?;
+		default::read_palette(&mut file)
+	}
+
+	/// Writes the given palette over the locked palette, refreshing the lock.
+	/// The palette is written to a temporary file first, so a failed save 
+	/// leaves the existing palette intact.
+	///
+	/// # Errors
+	///
+	/// Returns a `PermissionDenied` error without saving if the lock is no 
+	/// longer held by this session.
+	pub fn save(&mut self, palette: &Palette) -> io::Result<()> {
+		self.refresh()?;
+		let temp = sidecar(&self.path, ".tmp");
+		{
+			let mut out = BufWriter::new(File::create(&temp)?);
+			default::write_palette(palette, &mut out)?;
+			out.flush()?;
+		}
+		fs::rename(&temp, &self.path)
+	}
+
+	/// Releases the lock, removing the lock file if it is still held by this
+	/// session.
+	pub fn release(mut self) -> io::Result<()> {
+		self.remove()
+	}
+
+	/// Removes the lock file if it is still held by this session.
+	fn remove(&mut self) -> io::Result<()> {
+		if !self.held { return Ok(()); }
+		self.held = false;
+		if self.verify().is_ok() {
+			fs::remove_file(lock_path(&self.path))?;
+		}
+		Ok(())
+	}
+
+	/// Writes the lock file through a temporary file, so readers never see a
+	/// partially written lock.
+	fn write_info(&self) -> io::Result<()> {
+		let temp = sidecar(&self.path, ".lock.tmp");
+		{
+			let mut out = BufWriter::new(File::create(&temp)?);
+			self.info.write(&mut out)?;
+			out.flush()?;
+		}
+		fs::rename(&temp, lock_path(&self.path))
+	}
+}
+
+
+impl Drop for SessionLock {
+	fn drop(&mut self) {
+		let _ = self.remove();
+	}
+}
+
+
+/// Returns the path of the lock file for the palette file at the given path.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::lock::lock_path;
+/// use std::path::Path;
+///
+/// assert_eq!(lock_path("skin.pal"), Path::new("skin.pal.lock"));
+/// ```
+pub fn lock_path<P>(path: P) -> PathBuf where P: AsRef<Path> {
+	sidecar(path, ".lock")
+}
+
+
+/// Reads the lock file of the palette at the given path, returning None if 
+/// the palette is not locked.
+pub fn read_lock<P>(path: P) -> io::Result<Option<LockInfo>> 
+	where P: AsRef<Path>
+{
+	match File::open(lock_path(path)) {
+		Ok(file) => LockInfo::read(&mut io::BufReader::new(file)).map(Some),
+		Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+		Err(e) => Err(e),
+	}
+}
+
+
+/// Returns the path formed by appending the given suffix to the given path.
+fn sidecar<P>(path: P, suffix: &str) -> PathBuf where P: AsRef<Path> {
+	let mut name = OsString::from(path.as_ref());
+	name.push(suffix);
+	PathBuf::from(name)
+}
+
+
+/// Returns the number of whole seconds from the Unix epoch to the given time.
+fn seconds(time: SystemTime) -> u64 {
+	time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
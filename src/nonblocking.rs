@@ -0,0 +1,214 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides non-blocking variants of file and network imports and exports.
+//!
+//! The functions of this module return futures which perform their blocking
+//! I/O on a separate thread, so embedders running an async executor, such as 
+//! a GUI or a server, are not stalled while large files are read, written, 
+//! or downloaded. The futures are independent of any particular runtime and 
+//! may be awaited from `tokio`, `async-std`, or a simple executor.
+//!
+//! Palettes cannot be sent between threads, so parsing a palette and 
+//! serializing it for export happen on the awaiting task, when the future is
+//! polled and when it is created, respectively.
+//!
+//! # Example
+//!
+//! ```rust
+//! # extern crate futures;
+//! # extern crate palette;
+//! # fn main() {
+//! use palette::*;
+//! use palette::export::ExportFormat;
+//! use palette::import::ImportFormat;
+//! use palette::nonblocking::{export_file, import_file};
+//! use palette::operation::*;
+//! use futures::executor::block_on;
+//!
+//! let mut pal = Palette::new("Example", Format::Default, true);
+//! pal.apply(Box::new(InsertColor::new(Color::new(12, 50, 78)))).unwrap();
+//!
+//! let path = std::env::temp_dir().join("nonblocking_example.palette");
+//! block_on(export_file(&pal, ExportFormat::Palette, &path)).unwrap();
+//!
+//! let read = block_on(import_file(&path, None)).unwrap();
+//! assert_eq!(read.color(Address::new(0, 0, 0)), 
+//! 	Some(Color::new(12, 50, 78)));
+//! # std::fs::remove_file(&path).unwrap();
+//! # }
+//! ```
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use ::Palette;
+use export::ExportFormat;
+use import::ImportFormat;
+#[cfg(feature = "net")]
+use import::{
+	download,
+	is_url,
+	read_download,
+};
+
+// Non-local imports.
+use futures::channel::oneshot;
+use futures::future::{
+	self,
+	Either,
+	Future,
+	FutureExt,
+};
+
+// Standard imports.
+use std::fs;
+use std::io;
+use std::path::{
+	Path,
+	PathBuf,
+};
+use std::thread;
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// File I/O
+////////////////////////////////////////////////////////////////////////////////
+/// Reads a palette from the file at the given path without blocking the 
+/// awaiting task.
+///
+/// If no format is given, it is determined from the extension of the path 
+/// and then by sniffing the file's contents. Formats which do not store a 
+/// palette name are named after the file stem of the path.
+///
+/// # Errors
+///
+/// The returned future resolves to an `InvalidData` error if the format 
+/// cannot be determined or the contents are invalid, or to the error 
+/// encountered reading the file.
+pub fn import_file<P>(path: P, format: Option<ImportFormat>)
+	-> impl Future<Output=io::Result<Palette>>
+	where P: AsRef<Path>
+{
+	let path = path.as_ref().to_path_buf();
+	let name = path.file_stem()
+		.map_or_else(String::new, |s| s.to_string_lossy().into_owned());
+	let file = path.to_string_lossy().into_owned();
+
+	unblock(move || fs::read(path)).map(move |bytes| {
+		let bytes = bytes?;
+		let format = format
+			.or_else(|| ImportFormat::from_extension(&file))
+			.or_else(|| ImportFormat::sniff(&bytes))
+			.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, 
+				"unrecognized palette format"))?;
+		format.read(&name, &mut &bytes[..])
+	})
+}
+
+
+/// Writes the palette in the given format to the file at the given path 
+/// without blocking the awaiting task. The palette is serialized before the 
+/// future is returned, so it may be modified while the file is written.
+///
+/// # Errors
+///
+/// The returned future resolves to the error encountered serializing the 
+/// palette or writing the file.
+pub fn export_file<P>(palette: &Palette, format: ExportFormat, path: P)
+	-> impl Future<Output=io::Result<()>>
+	where P: AsRef<Path>
+{
+	let mut bytes = Vec::new();
+	match format.write(palette, &mut bytes) {
+		Ok(()) => {
+			let path: PathBuf = path.as_ref().to_path_buf();
+			Either::Left(unblock(move || fs::write(path, bytes)))
+		},
+		Err(e) => Either::Right(future::ready(Err(e))),
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Network I/O
+////////////////////////////////////////////////////////////////////////////////
+/// Downloads and reads a palette from the given HTTP or HTTPS URL without 
+/// blocking the awaiting task.
+///
+/// The format is determined as in [`read_url`].
+///
+/// [`read_url`]: ../import/fn.read_url.html
+///
+/// # Errors
+///
+/// The returned future resolves to the same errors as [`read_url`].
+#[cfg(feature = "net")]
+pub fn import_url(url: &str, format: Option<ImportFormat>)
+	-> impl Future<Output=io::Result<Palette>>
+{
+	if !is_url(url) {
+		return Either::Right(future::ready(Err(io::Error::new(
+			io::ErrorKind::InvalidInput,
+			format!("'{}' is not an http or https URL", url)))));
+	}
+
+	let url = url.to_owned();
+	let source = url.clone();
+	Either::Left(unblock(move || download(&source)).map(move |contents| {
+		let (content_type, bytes) = contents?;
+		read_download(&url, format, &content_type, &bytes)
+			.map(|(palette, _)| palette)
+	}))
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Worker threads
+////////////////////////////////////////////////////////////////////////////////
+/// Runs the given blocking task on a new thread, returning a future resolving
+/// to its result.
+fn unblock<F, T>(task: F) -> impl Future<Output=io::Result<T>>
+	where
+		F: FnOnce() -> io::Result<T> + Send + 'static,
+		T: Send + 'static,
+{
+	let (sender, receiver) = oneshot::channel();
+	let spawned = thread::Builder::new()
+		.name("palette-io".into())
+		.spawn(move || {
+			// The receiver may have been dropped if the future was.
+			let _ = sender.send(task());
+		});
+
+	match spawned {
+		Ok(_) => Either::Left(receiver.map(|result| result
+			.unwrap_or_else(|_| Err(io::Error::new(io::ErrorKind::Other, 
+				"the worker thread panicked"))))),
+		Err(e) => Either::Right(future::ready(Err(e))),
+	}
+}
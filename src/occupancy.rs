@@ -0,0 +1,299 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides queries over the occupied and free cells of a palette.
+//!
+//! A cell is occupied if it produces a color; cells without colors are free,
+//! as when inserting colors. Free cells are reported as runs of consecutive 
+//! free columns within a line, as ramps and other groups of colors are laid 
+//! out along lines. Only the pages up to the one after the last occupied page
+//! are searched.
+//!
+//! # Example
+//!
+//! ```rust
+//! use palette::*;
+//! use palette::operation::*;
+//!
+//! let mut pal = Palette::new("Example", Format::Default, true);
+//! for &column in &[0, 1, 5] {
+//! 	let insert = InsertColor::new(Color::new(12, 50, 78))
+//! 		.located_at(Address::new(0, 0, column));
+//! 	pal.apply(Box::new(insert)).unwrap();
+//! }
+//!
+//! assert_eq!(pal.occupied().count(), 3);
+//!
+//! let run = pal.find_free_run(3).unwrap();
+//! assert_eq!(run.start(), Address::new(0, 0, 2));
+//! assert_eq!(run.len(), 3);
+//!
+//! let stats = pal.occupancy();
+//! assert_eq!(stats.occupied, 3);
+//! assert_eq!(stats.lines, 1);
+//! ```
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use address::{
+	Address,
+	Column,
+	Line,
+	Page,
+	Reference,
+};
+use cell::Cell;
+use data::Data;
+
+// Non-local imports.
+use color::Color;
+use interval::Interval;
+
+// Standard imports.
+use std::collections::btree_map;
+use std::collections::BTreeSet;
+use std::rc::Rc;
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// OccupiedCells
+////////////////////////////////////////////////////////////////////////////////
+/// An iterator over the addresses and colors of a palette's occupied cells, 
+/// in address order.
+#[derive(Debug, Clone)]
+pub struct OccupiedCells<'a> {
+	/// The palette's cells.
+	cells: btree_map::Iter<'a, Address, Rc<Cell>>,
+}
+
+
+impl<'a> OccupiedCells<'a> {
+	/// Creates a new iterator over the occupied cells of the given data.
+	pub(crate) fn new(data: &'a Data) -> Self {
+		OccupiedCells {cells: data.cells.iter()}
+	}
+}
+
+
+impl<'a> Iterator for OccupiedCells<'a> {
+	type Item = (Address, Color);
+
+	fn next(&mut self) -> Option<(Address, Color)> {
+		while let Some((&address, cell)) = self.cells.next() {
+			if let Some(color) = cell.color() {
+				return Some((address, color));
+			}
+		}
+		None
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// FreeRun
+////////////////////////////////////////////////////////////////////////////////
+/// A run of consecutive free cells within a line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FreeRun {
+	/// The page of the run.
+	pub page: Page,
+	/// The line of the run.
+	pub line: Line,
+	/// The free columns, as a right-open interval.
+	pub columns: Interval<Column>,
+}
+
+
+impl FreeRun {
+	/// Returns the address of the first cell of the run.
+	pub fn start(&self) -> Address {
+		Address::new(self.page, self.line, first_column(&self.columns))
+	}
+
+	/// Returns the number of cells in the run.
+	pub fn len(&self) -> usize {
+		self.columns.width() as usize
+	}
+
+	/// Returns true if the run has no cells.
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// Returns the addresses of the cells of the run, in order.
+	pub fn addresses(&self) -> Vec<Address> {
+		let start = first_column(&self.columns);
+		(0..self.len())
+			.map(|i| Address::new(self.page, self.line, start + i as Column))
+			.collect()
+	}
+}
+
+
+/// Returns the first column of a right-open interval of columns.
+fn first_column(columns: &Interval<Column>) -> Column {
+	match columns.left_bound() {
+		::interval::Bound::Include(column) => column,
+		::interval::Bound::Exclude(column) => column + 1,
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// OccupancyStats
+////////////////////////////////////////////////////////////////////////////////
+/// Statistics describing how much of a palette is occupied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct OccupancyStats {
+	/// The number of occupied cells.
+	pub occupied: usize,
+	/// The number of cells which exist but produce no color.
+	pub empty: usize,
+	/// The number of pages containing occupied cells.
+	pub pages: usize,
+	/// The number of lines containing occupied cells.
+	pub lines: usize,
+	/// The number of cells in the pages up to the last occupied page.
+	pub capacity: usize,
+	/// The length of the longest run of free cells within a line.
+	pub largest_free_run: usize,
+}
+
+
+impl OccupancyStats {
+	/// Returns the fraction of the capacity which is occupied, in the range
+	/// [0, 1].
+	pub fn fill_ratio(&self) -> f32 {
+		if self.capacity == 0 {
+			0.0
+		} else {
+			self.occupied as f32 / self.capacity as f32
+		}
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Queries
+////////////////////////////////////////////////////////////////////////////////
+/// Returns the free runs of the searched pages, in address order.
+pub(crate) fn free_runs(data: &Data) -> Vec<FreeRun> {
+	let mut runs = Vec::new();
+	for page in 0..searched_pages(data) {
+		let line_count = data.line_count(&Reference::page_of(
+			&Address::new(page, 0, 0)));
+		for line in 0..line_count {
+			line_runs(data, page, line, &mut runs);
+		}
+	}
+	runs
+}
+
+
+/// Returns the first free run of at least the given length.
+pub(crate) fn find_free_run(data: &Data, length: usize) -> Option<FreeRun> {
+	free_runs(data).into_iter().find(|run| run.len() >= length.max(1))
+}
+
+
+/// Returns the first of the longest free runs.
+pub(crate) fn largest_free_run(data: &Data) -> Option<FreeRun> {
+	free_runs(data).into_iter().fold(None, |largest, run| match largest {
+		Some(ref largest) if largest.len() >= run.len() 
+			=> Some(largest.clone()),
+		_ => Some(run),
+	})
+}
+
+
+/// Returns the occupancy statistics of the given data.
+pub(crate) fn occupancy(data: &Data) -> OccupancyStats {
+	let occupied: Vec<_> = OccupiedCells::new(data)
+		.map(|(address, _)| address)
+		.collect();
+	let pages: BTreeSet<_> = occupied.iter().map(|a| a.page).collect();
+	let lines: BTreeSet<_> = occupied.iter()
+		.map(|a| (a.page, a.line))
+		.collect();
+
+	let last_page = occupied.last().map_or(0, |a| a.page as usize + 1);
+	let capacity = (0..last_page)
+		.map(|page| {
+			let page = Address::new(page as Page, 0, 0);
+			let line_count = data.line_count(&Reference::page_of(&page));
+			(0..line_count)
+				.map(|line| data.column_count(&Reference::line_of(
+					&Address::new(page.page, line, 0))) as usize)
+				.sum::<usize>()
+		})
+		.sum();
+
+	OccupancyStats {
+		occupied: occupied.len(),
+		empty: data.cells.len() - occupied.len(),
+		pages: pages.len(),
+		lines: lines.len(),
+		capacity: capacity,
+		largest_free_run: largest_free_run(data).map_or(0, |run| run.len()),
+	}
+}
+
+
+/// Returns the number of pages to search for free cells: those up to and 
+/// including the page after the last occupied page.
+fn searched_pages(data: &Data) -> Page {
+	let last = OccupiedCells::new(data)
+		.last()
+		.map_or(0, |(address, _)| address.page as usize + 2);
+	last.max(1).min(data.maximum_page_count as usize) as Page
+}
+
+
+/// Appends the free runs of the given line to the given runs.
+fn line_runs(data: &Data, page: Page, line: Line, runs: &mut Vec<FreeRun>) {
+	let start = Address::new(page, line, 0);
+	let column_count = data.column_count(&Reference::line_of(&start));
+	let occupied = data.cells
+		.range(start..=Address::new(page, line, Column::max_value()))
+		.filter(|&(_, cell)| cell.color().is_some())
+		.map(|(address, _)| address.column)
+		.filter(|&column| column < column_count);
+
+	let mut next: Column = 0;
+	for column in occupied.chain(Some(column_count)) {
+		if column > next {
+			runs.push(FreeRun {
+				page: page,
+				line: line,
+				columns: Interval::right_open(next, column),
+			});
+		}
+		next = column.saturating_add(1);
+	}
+}
@@ -0,0 +1,194 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Defines operations for renormalizing a palette to its reference anchors.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use data::Data;
+use expression::Expression;
+use operation::{
+	set_target_or_skip,
+	ConflictPolicy,
+	HistoryEntry,
+	OperationInfo,
+	PaletteOperation,
+	Undo,
+};
+use result::{
+	Error,
+	Result,
+};
+use space::{
+	unit_channels,
+	RgbSpace,
+	WideColor,
+};
+
+// Non-local imports.
+use color::Color;
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Renormalize
+////////////////////////////////////////////////////////////////////////////////
+/// Remaps every color cell in the palette so that the palette's white point
+/// and black point cells take on the given target colors. Each channel is
+/// stretched linearly between the anchors, so the operation also corrects a
+/// tinted white or a raised black level. Derived cells follow their sources.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::operation::*;
+///
+/// let mut pal = Palette::new("Example", Format::Default, true);
+/// pal.apply(Box::new(InsertColor::new(Color::new(16, 16, 16)))).unwrap();
+/// pal.apply(Box::new(InsertColor::new(Color::new(128, 128, 128)))).unwrap();
+/// pal.apply(Box::new(InsertColor::new(Color::new(240, 240, 240)))).unwrap();
+/// pal.set_black_point(Some(Address::new(0, 0, 0)));
+/// pal.set_white_point(Some(Address::new(0, 0, 2)));
+///
+/// pal.apply(Box::new(Renormalize::new())).unwrap();
+///
+/// let colors: Vec<_> = pal.colors(&Selection::all())
+/// 	.into_iter()
+/// 	.map(|(_, color)| color)
+/// 	.collect();
+/// assert_eq!(colors, vec![
+/// 	Color::new(0, 0, 0),
+/// 	Color::new(128, 128, 128),
+/// 	Color::new(255, 255, 255),
+/// ]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Renormalize {
+	/// The color the white point is mapped to.
+	white: Color,
+	/// The color the black point is mapped to.
+	black: Color,
+	/// The policy for cells which cannot be changed.
+	policy: ConflictPolicy,
+}
+
+
+impl Renormalize {
+	/// Creates a new Renormalize operation mapping the anchors to pure white 
+	/// and pure black.
+	pub fn new() -> Renormalize {
+		Renormalize {
+			white: Color::new(255, 255, 255),
+			black: Color::new(0, 0, 0),
+			policy: ConflictPolicy::default(),
+		}
+	}
+
+	/// Sets the color the white point is mapped to.
+	pub fn white(mut self, color: Color) -> Renormalize {
+		self.white = color;
+		self
+	}
+
+	/// Sets the color the black point is mapped to.
+	pub fn black(mut self, color: Color) -> Renormalize {
+		self.black = color;
+		self
+	}
+
+	/// Sets the policy for cells which cannot be changed.
+	pub fn conflict_policy(mut self, policy: ConflictPolicy) -> Renormalize {
+		self.policy = policy;
+		self
+	}
+}
+
+
+impl Default for Renormalize {
+	fn default() -> Self {
+		Renormalize::new()
+	}
+}
+
+
+impl PaletteOperation for Renormalize {
+	fn info(&self) -> OperationInfo {
+		OperationInfo {
+			name: "Renormalize".into(),
+			details: Some(format!("{:?}", self))
+		}
+	}
+
+	fn apply(&mut self, data: &mut Data) -> Result<HistoryEntry> {
+		let (white, black) = match (data.white_point, data.black_point) {
+			(Some(white), Some(black)) => (white, black),
+			_ => return Err(Error::MissingAnchor),
+		};
+		let anchor = |address| data.cell(address)
+			.and_then(|cell| cell.wide_color())
+			.map(|color| color.convert(RgbSpace::Srgb))
+			.map(|color| [color.r, color.g, color.b])
+			.ok_or(Error::EmptyAddress(address));
+		let source = (anchor(white)?, anchor(black)?);
+		let target = (unit_channels(self.white), unit_channels(self.black));
+
+		// Collect the new colors before changing any cells.
+		let colors: Vec<_> = data.cells
+			.iter()
+			.filter_map(|(&address, cell)| cell
+				.wide_color()
+				.map(|color| (address, color.convert(RgbSpace::Srgb))))
+			.collect();
+
+		let mut undo = Undo::new_for(self);
+		let mut conflicts = Vec::new();
+		for (address, color) in colors {
+			let mut channels = [color.r, color.g, color.b];
+			for (i, v) in channels.iter_mut().enumerate() {
+				let (sw, sb) = ((source.0)[i], (source.1)[i]);
+				let (tw, tb) = ((target.0)[i], (target.1)[i]);
+				*v = if (sw - sb).abs() <= ::std::f32::EPSILON {
+					// Degenerate anchors: shift the channel instead.
+					*v - sb + tb
+				} else {
+					tb + (*v - sb) * (tw - tb) / (sw - sb)
+				};
+			}
+			let [r, g, b] = channels;
+			let mapped = WideColor::srgb(r, g, b);
+			if mapped != color {
+				set_target_or_skip(data, address, Expression::Wide(mapped), 
+					&mut undo, &self.policy, &mut conflicts)?;
+			}
+		}
+
+		Ok(HistoryEntry {
+			info: self.info(),
+			undo: Box::new(undo),
+			conflicts: conflicts,
+		})
+	}
+}
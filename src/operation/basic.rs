@@ -29,6 +29,7 @@
 // Local imports.
 use address::Address;
 use data::Data;
+use expression::Expression;
 use operation::{
 	set_target,
 	HistoryEntry,
@@ -37,7 +38,10 @@ use operation::{
 	Undo,
 };
 use result::Result;
+use space::WideColor;
 
+// Non-local imports.
+use color::Color;
 
 
 
@@ -94,7 +98,7 @@ impl InsertCell {
 impl PaletteOperation for InsertCell {
 	fn info(&self) -> OperationInfo {
 		OperationInfo {
-			name: "Insert Cell",
+			name: "Insert Cell".into(),
 			details: Some(format!("{:?}", self))
 		}
 	}
@@ -123,6 +127,118 @@ impl PaletteOperation for InsertCell {
 		Ok(HistoryEntry {
 			info: self.info(),
 			undo: Box::new(undo),
+			conflicts: Vec::new(),
+		})
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// InsertColor
+////////////////////////////////////////////////////////////////////////////////
+/// Inserts a new `Color` into the palette.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::operation::*;
+///
+/// let mut pal = Palette::new("Example", Format::Default, true);
+///
+/// pal.apply(Box::new(InsertColor::new(Color::new(12, 50, 78)))).unwrap();
+///
+/// assert_eq!(pal.color(Address::new(0, 0, 0)), Some(Color::new(12, 50, 78)));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct InsertColor {
+	/// The color expression to insert.
+	color: Expression,
+	/// The location to place the color.
+	location: Option<Address>,
+	/// Whether to overwrite existing cells when generating new ones.
+	overwrite: bool,
+}
+
+
+impl InsertColor {
+	/// Creates a new InsertColor operation.
+	#[inline]
+	pub fn new(color: Color) -> InsertColor {
+		InsertColor {
+			color: Expression::Color(color),
+			location: None,
+			overwrite: false,
+		}
+	}
+
+	/// Creates a new InsertColor operation for an extended-range color.
+	#[inline]
+	pub fn wide(color: WideColor) -> InsertColor {
+		InsertColor {
+			color: Expression::Wide(color),
+			location: None,
+			overwrite: false,
+		}
+	}
+
+	/// Creates a new InsertColor operation for the given expression.
+	#[inline]
+	pub(crate) fn expression(color: Expression) -> InsertColor {
+		InsertColor {
+			color: color,
+			location: None,
+			overwrite: false,
+		}
+	}
+
+	/// Sets the location to place the color.
+	pub fn located_at(mut self, location: Address) -> InsertColor {
+		self.location = Some(location);
+		self
+	}
+
+	/// Configures the operation to overwrite existing cells when inserted.
+	pub fn overwrite(mut self, overwrite: bool) -> InsertColor {
+		self.overwrite = overwrite;
+		self
+	}
+}
+
+
+impl PaletteOperation for InsertColor {
+	fn info(&self) -> OperationInfo {
+		OperationInfo {
+			name: "Insert Color".into(),
+			details: Some(format!("{:?}", self))
+		}
+	}
+
+	fn apply(&mut self, data: &mut Data) -> Result<HistoryEntry> {
+		// Get starting address.
+		let starting_address = if let Some(address) = self.location {
+			address
+		} else {
+			data.first_free_address_after(Default::default())?
+		};
+
+		// Get targets.
+		let target = data.find_targets(
+			1,
+			starting_address,
+			self.overwrite,
+			None
+		)?[0];
+
+		// Set target.
+		let mut undo = Undo::new_for(self);
+		set_target(data, target, self.color, &mut undo)?;
+
+		Ok(HistoryEntry {
+			info: self.info(),
+			undo: Box::new(undo),
+			conflicts: Vec::new(),
 		})
 	}
 }
@@ -165,7 +281,7 @@ impl DeleteCell {
 impl PaletteOperation for DeleteCell {
 	fn info(&self) -> OperationInfo {
 		OperationInfo {
-			name: "Remove Cell",
+			name: "Remove Cell".into(),
 			details: Some(format!("{:?}", self))
 		}
 	}
@@ -173,11 +289,75 @@ impl PaletteOperation for DeleteCell {
 	fn apply(&mut self, data: &mut Data) -> Result<HistoryEntry> {
 
 		let mut undo = Undo::new_for(self);
-		undo.record(self.address, Some(data.remove_cell(self.address)?));
+		let id = data.cell_id(self.address);
+		undo.record_removal(self.address, data.remove_cell(self.address)?, id);
 		
 		Ok(HistoryEntry {
 			info: self.info(),
 			undo: Box::new(undo),
+			conflicts: Vec::new(),
 		})
 	}
-}
\ No newline at end of file
+}
+
+
+////////////////////////////////////////////////////////////////////////////////
+// MoveCell
+////////////////////////////////////////////////////////////////////////////////
+/// Moves a cell to an empty address, preserving its `CellId` and metadata.
+/// 
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::operation::{InsertColor, MoveCell};
+/// 
+/// let mut pal = Palette::new("Example", Format::Default, true);
+/// let (a, b) = (Address::new(0, 0, 0), Address::new(0, 1, 0));
+///
+/// pal.apply(Box::new(InsertColor::new(Color::new(12, 50, 78)))).unwrap();
+/// let id = pal.cell_id(a).unwrap();
+/// pal.apply(Box::new(MoveCell::new(a, b))).unwrap();
+/// 
+/// assert_eq!(pal.address_of_id(id), Some(b));
+///
+/// pal.undo().unwrap();
+/// assert_eq!(pal.address_of_id(id), Some(a));
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MoveCell {
+	/// The address of the cell to move.
+	from: Address,
+	/// The address to move the cell to.
+	to: Address,
+}
+
+
+impl MoveCell {
+	/// Creates a new MoveCell operation moving the cell at one address to
+	/// another.
+	#[inline]
+	pub fn new(from: Address, to: Address) -> MoveCell {
+		MoveCell {from: from, to: to}
+	}
+}
+
+
+impl PaletteOperation for MoveCell {
+	fn info(&self) -> OperationInfo {
+		OperationInfo {
+			name: "Move Cell".into(),
+			details: Some(format!("{:?}", self))
+		}
+	}
+
+	fn apply(&mut self, data: &mut Data) -> Result<HistoryEntry> {
+		data.move_cell(self.from, self.to)?;
+		
+		Ok(HistoryEntry {
+			info: self.info(),
+			undo: Box::new(MoveCell::new(self.to, self.from)),
+			conflicts: Vec::new(),
+		})
+	}
+}
@@ -80,23 +80,55 @@ impl Sequence {
 impl PaletteOperation for Sequence {
 	fn info(&self) -> OperationInfo {
 		OperationInfo {
-			name: "Sequence",
+			name: "Sequence".into(),
 			details: Some(format!("{:?}", self))
 		}
 	}
 
 	fn apply(&mut self, data: &mut Data) -> Result<HistoryEntry> {
 		let mut undo_sequence: Vec<Box<PaletteOperation>> = Vec::new();
+		let mut conflicts = Vec::new();
 
 		let operations = mem::replace(&mut self.operations, Vec::new());
-		for mut operation in operations {
-			let entry = operation.apply(data)?;
-			undo_sequence.push(entry.undo);
+		let mut operations = operations.into_iter();
+		while let Some(mut operation) = operations.next() {
+			match operation.apply(data) {
+				Ok(entry) => {
+					conflicts.extend(entry.conflicts);
+					undo_sequence.push(entry.undo);
+				},
+				Err(error) => {
+					// Revert the applied operations and put the sequence
+					// back together, so that it can be retried. If an
+					// operation can't be reverted, the rollback stops and
+					// the sequence holds only the operations which remain
+					// to be applied to the partially reverted data.
+					let mut restored = Vec::new();
+					let mut result = Err(error);
+					while let Some(mut undo) = undo_sequence.pop() {
+						match undo.apply(data) {
+							Ok(entry) => restored.push(entry.undo),
+							Err(undo_error) => {
+								result = Err(undo_error);
+								break;
+							},
+						}
+					}
+					restored.reverse();
+					restored.push(operation);
+					restored.extend(operations);
+					self.operations = restored;
+					return result;
+				},
+			}
 		}
 
+		// Undo the operations in the reverse of the order they were applied.
+		undo_sequence.reverse();
 		Ok(HistoryEntry {
 			info: self.info(),
 			undo: Box::new(Sequence::new(undo_sequence)),
+			conflicts: conflicts,
 		})
 	}
 }
@@ -153,22 +185,27 @@ impl Repeat {
 impl PaletteOperation for Repeat {
 	fn info(&self) -> OperationInfo {
 		OperationInfo {
-			name: "Repeat",
+			name: "Repeat".into(),
 			details: Some(format!("{:?}", self))
 		}
 	}
 
 	fn apply(&mut self, data: &mut Data) -> Result<HistoryEntry> {
 		let mut undo_sequence: Vec<Box<PaletteOperation>> = Vec::new();
+		let mut conflicts = Vec::new();
 
 		for _ in 0..self.repeat_count {
 			let entry = self.operation.apply(data)?;
+			conflicts.extend(entry.conflicts);
 			undo_sequence.push(entry.undo);
 		}
 
+		// Undo the operations in the reverse of the order they were applied.
+		undo_sequence.reverse();
 		Ok(HistoryEntry {
 			info: self.info(),
 			undo: Box::new(Sequence::new(undo_sequence)),
+			conflicts: conflicts,
 		})
 	}
 }
\ No newline at end of file
@@ -0,0 +1,150 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides operations constraining colors to fixed color sets.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use address::Selection;
+use ansi::to_ansi256;
+use data::Data;
+use expression::Expression;
+use operation::{
+	set_target_or_skip,
+	ConflictPolicy,
+	HistoryEntry,
+	OperationInfo,
+	PaletteOperation,
+	Undo,
+};
+use result::Result;
+use space::{
+	GamutMapping,
+	WideColor,
+};
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// ConstrainAnsi256
+////////////////////////////////////////////////////////////////////////////////
+/// Replaces every color cell within a selection with the nearest color of the
+/// xterm 256-color palette. Derived cells follow their sources.
+///
+/// By default, only the color cube and gray ramp are used, as the sixteen 
+/// system colors are usually redefined by terminal themes.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::operation::*;
+///
+/// let mut pal = Palette::new("Example", Format::Default, true);
+/// pal.apply(Box::new(InsertColor::new(Color::new(250, 130, 10)))).unwrap();
+/// pal.apply(Box::new(InsertColor::new(Color::new(126, 126, 126)))).unwrap();
+///
+/// pal.apply(Box::new(ConstrainAnsi256::new(Selection::all()))).unwrap();
+///
+/// assert_eq!(pal.color(Address::new(0, 0, 0)), Some(Color::new(255, 135, 0)));
+/// assert_eq!(pal.color(Address::new(0, 0, 1)), 
+/// 	Some(Color::new(128, 128, 128)));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ConstrainAnsi256 {
+	/// The cells to constrain.
+	selection: Selection,
+	/// Whether the system colors may be used.
+	system_colors: bool,
+	/// The policy for cells which cannot be changed.
+	policy: ConflictPolicy,
+}
+
+
+impl ConstrainAnsi256 {
+	/// Creates a new ConstrainAnsi256 operation for the given selection.
+	pub fn new(selection: Selection) -> ConstrainAnsi256 {
+		ConstrainAnsi256 {
+			selection: selection,
+			system_colors: false,
+			policy: ConflictPolicy::default(),
+		}
+	}
+
+	/// Sets whether the sixteen system colors, with their xterm default 
+	/// values, may be used.
+	pub fn system_colors(mut self, system_colors: bool) -> ConstrainAnsi256 {
+		self.system_colors = system_colors;
+		self
+	}
+
+	/// Sets the policy for cells which cannot be changed.
+	pub fn conflict_policy(mut self, policy: ConflictPolicy) 
+		-> ConstrainAnsi256
+	{
+		self.policy = policy;
+		self
+	}
+}
+
+
+impl PaletteOperation for ConstrainAnsi256 {
+	fn info(&self) -> OperationInfo {
+		OperationInfo {
+			name: "Constrain to ANSI 256".into(),
+			details: Some(format!("{:?}", self))
+		}
+	}
+
+	fn apply(&mut self, data: &mut Data) -> Result<HistoryEntry> {
+		// Collect the new colors before changing any cells.
+		let colors: Vec<_> = data.cells
+			.iter()
+			.filter(|&(address, _)| self.selection.contains(address))
+			.filter_map(|(&address, cell)| cell
+				.wide_color()
+				.map(|color| (address, color)))
+			.collect();
+
+		let mut undo = Undo::new_for(self);
+		let mut conflicts = Vec::new();
+		for (address, color) in colors {
+			let nearest = color.to_color(GamutMapping::default());
+			let constrained = WideColor::from(
+				to_ansi256(nearest, self.system_colors));
+			if constrained != color {
+				set_target_or_skip(data, address, 
+					Expression::Wide(constrained), 
+					&mut undo, &self.policy, &mut conflicts)?;
+			}
+		}
+
+		Ok(HistoryEntry {
+			info: self.info(),
+			undo: Box::new(undo),
+			conflicts: conflicts,
+		})
+	}
+}
@@ -0,0 +1,361 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Defines operations for laying out ramps in the palette grid.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use ::Palette;
+use address::{
+	Address,
+	Column,
+	Line,
+	Page,
+	Reference,
+};
+use cell::CellId;
+use data::Data;
+use operation::{
+	HistoryEntry,
+	MoveCell,
+	OperationInfo,
+	PaletteOperation,
+	Sequence,
+};
+use result::{
+	Error,
+	Result,
+};
+use space::Lab;
+
+// Standard imports.
+use std::collections::{
+	BTreeMap,
+	BTreeSet,
+};
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// RampAlignment
+////////////////////////////////////////////////////////////////////////////////
+/// Determines how ramps shorter than the longest laid out ramp are placed 
+/// within their lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RampAlignment {
+	/// Ramps start at the layout's starting column.
+	Start,
+	/// Ramps are centered on the center of the longest ramp.
+	Center,
+	/// Ramps end at the end of the longest ramp.
+	End,
+}
+
+
+impl Default for RampAlignment {
+	fn default() -> Self {
+		RampAlignment::Start
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// LayoutRamps
+////////////////////////////////////////////////////////////////////////////////
+/// Moves each of a set of ramps onto its own line, so they form a grid.
+///
+/// Ramps are placed on consecutive lines from a starting address, skipping 
+/// lines where other cells are in the way. Unless disabled, ramps are grouped
+/// by the hue of their average color, with nearly neutral ramps first; ramps 
+/// in the same group keep their given order. Cells keep their names, ids, and
+/// other metadata as they are moved.
+///
+/// After inserting colors or ramps, the lines of a page can be re-flowed with
+/// `LayoutRamps::reflow`, which closes the gaps between ramps and regroups 
+/// them.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::operation::*;
+///
+/// let mut pal = Palette::new("Example", Format::Default, true);
+/// let colors = [
+/// 	Color::new(0, 0, 200), Color::new(0, 0, 100),
+/// 	Color::new(200, 0, 0), Color::new(100, 0, 0), Color::new(50, 0, 0),
+/// ];
+/// for &color in &colors {
+/// 	pal.apply(Box::new(InsertColor::new(color))).unwrap();
+/// }
+///
+/// let cells = |columns: &[u8]| columns.iter()
+/// 	.map(|&c| Address::new(0, 0, c))
+/// 	.collect::<Vec<_>>();
+/// let layout = LayoutRamps::new(vec![cells(&[0, 1]), cells(&[2, 3, 4])])
+/// 	.starting_at(Address::new(0, 2, 0))
+/// 	.alignment(RampAlignment::Center);
+/// pal.apply(Box::new(layout)).unwrap();
+///
+/// // The red ramp is placed before the blue one, and the two-color ramp is
+/// // centered over the three-color ramp.
+/// assert_eq!(pal.color(Address::new(0, 2, 0)), Some(Color::new(200, 0, 0)));
+/// assert_eq!(pal.color(Address::new(0, 3, 1)), Some(Color::new(0, 0, 100)));
+/// assert_eq!(pal.color(Address::new(0, 0, 0)), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct LayoutRamps {
+	/// The addresses of the cells of each ramp, in order.
+	ramps: Vec<Vec<Address>>,
+	/// The address of the first cell of the first line of the layout.
+	start: Address,
+	/// The placement of shorter ramps within their lines.
+	alignment: RampAlignment,
+	/// Whether to group the ramps by hue.
+	group_by_hue: bool,
+}
+
+
+impl LayoutRamps {
+	/// Creates a new LayoutRamps operation laying out the ramps with the 
+	/// given cells.
+	pub fn new(ramps: Vec<Vec<Address>>) -> LayoutRamps {
+		LayoutRamps {
+			ramps: ramps,
+			start: Address::default(),
+			alignment: RampAlignment::default(),
+			group_by_hue: true,
+		}
+	}
+
+	/// Creates a new LayoutRamps operation re-flowing the given page of the 
+	/// palette, treating the cells of each line as a ramp.
+	pub fn reflow(palette: &Palette, page: Page) -> LayoutRamps {
+		let mut lines: BTreeMap<Line, Vec<Address>> = BTreeMap::new();
+		for (&address, _) in &palette.data.cells {
+			if address.page == page {
+				lines.entry(address.line)
+					.or_insert_with(Vec::new)
+					.push(address);
+			}
+		}
+		LayoutRamps::new(lines.into_iter().map(|(_, ramp)| ramp).collect())
+			.starting_at(Address::new(page, 0, 0))
+	}
+
+	/// Sets the address of the first cell of the first line of the layout.
+	pub fn starting_at(mut self, start: Address) -> LayoutRamps {
+		self.start = start;
+		self
+	}
+
+	/// Sets the placement of shorter ramps within their lines.
+	pub fn alignment(mut self, alignment: RampAlignment) -> LayoutRamps {
+		self.alignment = alignment;
+		self
+	}
+
+	/// Sets whether to group the ramps by hue.
+	pub fn group_by_hue(mut self, group_by_hue: bool) -> LayoutRamps {
+		self.group_by_hue = group_by_hue;
+		self
+	}
+
+	/// Returns the ramps of existing cells in layout order, identified by
+	/// their cell ids.
+	fn ordered_ramps(&self, data: &Data) -> Vec<Vec<CellId>> {
+		let mut ramps: Vec<_> = self.ramps.iter()
+			.map(|ramp| ramp.iter()
+				.filter_map(|&address| data.cell_id(address))
+				.collect::<Vec<_>>())
+			.filter(|ramp| !ramp.is_empty())
+			.collect();
+
+		if self.group_by_hue {
+			ramps.sort_by_key(|ramp| hue_group(data, ramp));
+		}
+		ramps
+	}
+
+	/// Returns the target addresses of the cells of each ramp, avoiding the 
+	/// cells which are not being moved.
+	fn targets(&self, data: &Data, ramps: &[Vec<CellId>]) 
+		-> Result<Vec<Vec<Address>>>
+	{
+		let moving: BTreeSet<_> = ramps.iter()
+			.flat_map(|ramp| ramp.iter())
+			.filter_map(|&id| data.address_of_id(id))
+			.collect();
+		let longest = ramps.iter().map(|ramp| ramp.len()).max().unwrap_or(0);
+		let mut page = self.start.page;
+		let mut line = self.start.line as usize;
+
+		let mut targets = Vec::with_capacity(ramps.len());
+		for ramp in ramps {
+			let offset = match self.alignment {
+				RampAlignment::Start => 0,
+				RampAlignment::Center => (longest - ramp.len()) / 2,
+				RampAlignment::End => longest - ramp.len(),
+			};
+			let first = self.start.column as usize + offset;
+
+			loop {
+				if page >= data.maximum_page_count {
+					return Err(Error::MaxCellLimitExceeded);
+				}
+				let page_start = Address::new(page, 0, 0);
+				let lines = data.line_count(&Reference::page_of(&page_start));
+				if line >= lines as usize {
+					page += 1;
+					line = 0;
+					continue;
+				}
+
+				let line_start = Address::new(page, line as Line, 0);
+				let columns = data.column_count(
+					&Reference::line_of(&line_start)) as usize;
+				line += 1;
+				if first + ramp.len() > columns { continue; }
+
+				let cells: Vec<_> = (first..first + ramp.len())
+					.map(|c| Address::new(page, line_start.line, c as Column))
+					.collect();
+				if cells.iter().all(|a| !data.cells.contains_key(a) 
+					|| moving.contains(a))
+				{
+					targets.push(cells);
+					break;
+				}
+			}
+		}
+		Ok(targets)
+	}
+}
+
+
+impl PaletteOperation for LayoutRamps {
+	fn info(&self) -> OperationInfo {
+		OperationInfo {
+			name: "Layout Ramps".into(),
+			details: Some(format!("{:?}", self))
+		}
+	}
+
+	fn apply(&mut self, data: &mut Data) -> Result<HistoryEntry> {
+		let ramps = self.ordered_ramps(data);
+		let targets = self.targets(data, &ramps)?;
+
+		let mut moves: Vec<(CellId, Address)> = ramps.iter()
+			.zip(targets.iter())
+			.flat_map(|(ramp, cells)| ramp.iter().cloned()
+				.zip(cells.iter().cloned()))
+			.filter(|&(id, to)| data.address_of_id(id) != Some(to))
+			.collect();
+		let reserved: BTreeSet<_> = targets.iter()
+			.flat_map(|cells| cells.iter().cloned())
+			.collect();
+
+		// Move cells whose targets are free, parking a cell elsewhere when the
+		// remaining moves form a cycle.
+		let mut undo: Vec<Box<PaletteOperation>> = Vec::new();
+		while !moves.is_empty() {
+			let ready = moves.iter()
+				.position(|&(_, to)| !data.cells.contains_key(&to));
+			let (from, to) = match ready {
+				Some(i) => {
+					let (id, to) = moves.remove(i);
+					(data.address_of_id(id).expect("moved cell exists"), to)
+				},
+				None => {
+					let (id, _) = moves[0];
+					let from = data.address_of_id(id)
+						.expect("moved cell exists");
+					(from, parking_address(data, &reserved)?)
+				},
+			};
+			let entry = MoveCell::new(from, to).apply(data)?;
+			undo.push(entry.undo);
+		}
+
+		undo.reverse();
+		Ok(HistoryEntry {
+			info: self.info(),
+			undo: Box::new(Sequence::new(undo)),
+			conflicts: Vec::new(),
+		})
+	}
+}
+
+
+/// Returns the sort key grouping a ramp by the hue of its average color, with
+/// nearly neutral ramps first.
+fn hue_group(data: &Data, ramp: &[CellId]) -> (bool, u32) {
+	let labs: Vec<Lab> = ramp.iter()
+		.filter_map(|&id| data.address_of_id(id))
+		.filter_map(|address| data.cell(address))
+		.filter_map(|cell| cell.color())
+		.map(Lab::from)
+		.collect();
+	if labs.is_empty() { return (false, 0); }
+
+	let count = labs.len() as f32;
+	let mean = Lab::new(
+		labs.iter().map(|lab| lab.l).sum::<f32>() / count,
+		labs.iter().map(|lab| lab.a).sum::<f32>() / count,
+		labs.iter().map(|lab| lab.b).sum::<f32>() / count);
+	if mean.chroma() < NEUTRAL_CHROMA {
+		(false, 0)
+	} else {
+		(true, (mean.hue() / HUE_GROUP_WIDTH) as u32)
+	}
+}
+
+
+/// The chroma below which a ramp is grouped with the neutral ramps.
+const NEUTRAL_CHROMA: f32 = 10.0;
+
+/// The width of the hue range of each group of ramps, in degrees.
+const HUE_GROUP_WIDTH: f32 = 30.0;
+
+
+/// Returns a free address outside of the reserved addresses, for temporarily
+/// holding a cell.
+fn parking_address(data: &Data, reserved: &BTreeSet<Address>) 
+	-> Result<Address>
+{
+	let mut address = Address::new(data.maximum_page_count - 1, 0, 0);
+	loop {
+		if !data.cells.contains_key(&address) && !reserved.contains(&address) {
+			return Ok(address);
+		}
+		address = match (address.column.checked_add(1), address.line) {
+			(Some(column), line) => Address::new(address.page, line, column),
+			(None, line) if line < Line::max_value() 
+				=> Address::new(address.page, line + 1, 0),
+			_ => return Err(Error::MaxCellLimitExceeded),
+		};
+	}
+}
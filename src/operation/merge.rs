@@ -0,0 +1,342 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Defines operations for merging imported colors into a palette.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use ::Palette;
+use address::Address;
+use data::Data;
+use expression::Expression;
+use operation::{
+	set_target_or_skip,
+	Conflict,
+	ConflictPolicy,
+	HistoryEntry,
+	OperationInfo,
+	PaletteOperation,
+	Undo,
+};
+use result::Result;
+use space::delta_e_2000;
+
+// Non-local imports.
+use color::Color;
+
+// Standard imports.
+use std::cell::RefCell;
+use std::rc::Rc;
+
+
+////////////////////////////////////////////////////////////////////////////////
+// MergeStrategy
+////////////////////////////////////////////////////////////////////////////////
+/// Determines how imported colors are placed into an existing palette.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MergeStrategy {
+	/// Places every imported color in the free cells after the start of the
+	/// palette.
+	Append,
+	/// Places imported colors at their original addresses, skipping any which
+	/// are already occupied.
+	FillGaps,
+	/// Replaces the colors of cells sharing a name with an imported cell, and
+	/// appends the remaining colors.
+	OverwriteMatchingNames,
+	/// Appends imported colors, skipping any within the given CIEDE2000
+	/// difference of a color already in the palette.
+	SkipNearDuplicates(f32),
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// MergeAction
+////////////////////////////////////////////////////////////////////////////////
+/// Describes what happened to a single imported color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MergeAction {
+	/// The color was placed in a new cell at the given address.
+	Added(Address),
+	/// The color replaced the color of the cell at the given address.
+	Replaced(Address),
+	/// The color was skipped because its address was occupied.
+	SkippedOccupied,
+	/// The color was skipped because it duplicated the color of the cell at
+	/// the given address.
+	SkippedDuplicate(Address),
+	/// The color was skipped because the cell at the given address could not
+	/// be changed.
+	SkippedInvalid(Address),
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// MergeReport
+////////////////////////////////////////////////////////////////////////////////
+/// Records the outcome of a merge.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MergeReport {
+	/// The source address of each imported color and what happened to it.
+	pub actions: Vec<(Address, MergeAction)>,
+}
+
+
+impl MergeReport {
+	/// Returns the number of colors placed in new cells.
+	pub fn added(&self) -> usize {
+		self.count(|a| match a { MergeAction::Added(..) => true, _ => false })
+	}
+
+	/// Returns the number of colors which replaced existing colors.
+	pub fn replaced(&self) -> usize {
+		self.count(|a| match a { MergeAction::Replaced(..) => true, _ => false })
+	}
+
+	/// Returns the number of colors which were skipped.
+	pub fn skipped(&self) -> usize {
+		self.actions.len() - self.added() - self.replaced()
+	}
+
+	/// Returns the number of actions satisfying the given predicate.
+	fn count<F>(&self, predicate: F) -> usize where F: Fn(&MergeAction) -> bool {
+		self.actions.iter().filter(|&&(_, ref a)| predicate(a)).count()
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// MergePalette
+////////////////////////////////////////////////////////////////////////////////
+/// Merges the colors of another palette into the palette. Cell names are used
+/// for matching, but are not imported.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::operation::*;
+///
+/// let mut pal = Palette::new("Example", Format::Default, true);
+/// pal.apply(Box::new(InsertColor::new(Color::new(10, 10, 10)))).unwrap();
+///
+/// let mut other = Palette::new("Other", Format::Default, true);
+/// other.apply(Box::new(InsertColor::new(Color::new(11, 10, 10)))).unwrap();
+/// other.apply(Box::new(InsertColor::new(Color::new(200, 0, 0)))).unwrap();
+///
+/// let report = pal.merge(&other, MergeStrategy::SkipNearDuplicates(2.0))
+/// 	.unwrap();
+///
+/// assert_eq!(report.added(), 1);
+/// assert_eq!(report.skipped(), 1);
+/// assert_eq!(pal.color(Address::new(0, 0, 1)), Some(Color::new(200, 0, 0)));
+/// ```
+#[derive(Debug, Clone)]
+pub struct MergePalette {
+	/// The imported cells, with their source addresses and names.
+	cells: Vec<(Address, Option<String>, Expression)>,
+	/// The strategy for placing the imported cells.
+	strategy: MergeStrategy,
+	/// The policy for cells which cannot be changed.
+	policy: ConflictPolicy,
+	/// The report from the most recent application.
+	report: MergeReport,
+	/// A shared copy of the report, for callers which give up the operation.
+	shared_report: Option<Rc<RefCell<MergeReport>>>,
+}
+
+
+impl MergePalette {
+	/// Creates a new MergePalette operation importing the colored cells of the
+	/// given palette.
+	pub fn new(source: &Palette, strategy: MergeStrategy) -> MergePalette {
+		MergePalette {
+			cells: source.data.cells
+				.iter()
+				.filter(|&(_, cell)| cell.color().is_some())
+				.map(|(&address, cell)| (
+					address,
+					source.cell_name(address).map(|n| n.to_owned()),
+					*cell.borrow(),
+				))
+				.collect(),
+			strategy: strategy,
+			policy: ConflictPolicy::default(),
+			report: Default::default(),
+			shared_report: None,
+		}
+	}
+
+	/// Sets the policy for cells which cannot be changed. Skipped colors are
+	/// reported as `MergeAction::SkippedInvalid`.
+	pub fn conflict_policy(mut self, policy: ConflictPolicy) -> MergePalette {
+		self.policy = policy;
+		self
+	}
+
+	/// Returns the report from the most recent application of the operation.
+	pub fn report(&self) -> &MergeReport {
+		&self.report
+	}
+
+	/// Sets a shared report to be updated on each application of the
+	/// operation, so that the report can be read after the operation is
+	/// boxed and applied.
+	pub(crate) fn share_report(mut self, report: Rc<RefCell<MergeReport>>) 
+		-> MergePalette
+	{
+		self.shared_report = Some(report);
+		self
+	}
+}
+
+
+impl PaletteOperation for MergePalette {
+	fn info(&self) -> OperationInfo {
+		OperationInfo {
+			name: "Merge Palette".into(),
+			details: Some(format!("{:?}", self.strategy))
+		}
+	}
+
+	fn apply(&mut self, data: &mut Data) -> Result<HistoryEntry> {
+		let mut undo = Undo::new_for(self);
+		let mut report = MergeReport::default();
+		let mut conflicts = Vec::new();
+		let mut next = Address::default();
+
+		for &(source, ref name, expr) in &self.cells {
+			let color = expr.color().expect("merged cells have colors");
+			let skipped = conflicts.len();
+			let mut place = Placement {
+				data: data,
+				undo: &mut undo,
+				policy: &self.policy,
+				conflicts: &mut conflicts,
+			};
+
+			let action = match self.strategy {
+				MergeStrategy::Append => place.append(&mut next, expr)?,
+
+				MergeStrategy::FillGaps => {
+					let free = place.data.cell(source)
+						.and_then(|cell| cell.color())
+						.is_none();
+					if free {
+						place.set(source, expr)?;
+						MergeAction::Added(source)
+					} else {
+						MergeAction::SkippedOccupied
+					}
+				},
+
+				MergeStrategy::OverwriteMatchingNames => {
+					match name.as_ref().and_then(|n| place.data.address_of(n)) {
+						Some(target) => {
+							place.set(target, expr)?;
+							MergeAction::Replaced(target)
+						},
+						None => place.append(&mut next, expr)?,
+					}
+				},
+
+				MergeStrategy::SkipNearDuplicates(tolerance) => {
+					match duplicate_of(place.data, color, tolerance) {
+						Some(existing) 
+							=> MergeAction::SkippedDuplicate(existing),
+						None => place.append(&mut next, expr)?,
+					}
+				},
+			};
+
+			let action = if conflicts.len() > skipped {
+				MergeAction::SkippedInvalid(conflicts[skipped].address)
+			} else {
+				action
+			};
+			report.actions.push((source, action));
+		}
+
+		if let Some(ref shared) = self.shared_report {
+			*shared.borrow_mut() = report.clone();
+		}
+		self.report = report;
+		Ok(HistoryEntry {
+			info: self.info(),
+			undo: Box::new(undo),
+			conflicts: conflicts,
+		})
+	}
+}
+
+
+/// The state needed to place merged colors into the palette.
+struct Placement<'a> {
+	/// The palette data.
+	data: &'a mut Data,
+	/// The undo operation for the merge.
+	undo: &'a mut Undo,
+	/// The policy for cells which cannot be changed.
+	policy: &'a ConflictPolicy,
+	/// The cells which were skipped.
+	conflicts: &'a mut Vec<Conflict>,
+}
+
+
+impl<'a> Placement<'a> {
+	/// Places the given expression at the given address.
+	fn set(&mut self, address: Address, expr: Expression) -> Result<()> {
+		set_target_or_skip(
+			self.data,
+			address,
+			expr,
+			self.undo,
+			self.policy,
+			self.conflicts)
+	}
+
+	/// Places the given expression in the first free cell at or after the 
+	/// given address, advancing the address.
+	fn append(&mut self, next: &mut Address, expr: Expression)
+		-> Result<MergeAction>
+	{
+		*next = self.data.first_free_address_after(*next)?;
+		self.set(*next, expr)?;
+		Ok(MergeAction::Added(*next))
+	}
+}
+
+
+/// Returns the address of the first existing color within the given tolerance
+/// of the given color.
+fn duplicate_of(data: &Data, color: Color, tolerance: f32) -> Option<Address> {
+	data.cells.iter()
+		.filter_map(|(&a, cell)| cell.color().map(|c| (a, c)))
+		.find(|&(_, c)| delta_e_2000(c, color) <= tolerance)
+		.map(|(a, _)| a)
+}
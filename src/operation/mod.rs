@@ -30,22 +30,76 @@
 
 // Sumbodules.
 #[warn(missing_docs)]
+mod anchor;
+#[warn(missing_docs)]
 mod basic;
 #[warn(missing_docs)]
 mod combine;
 #[warn(missing_docs)]
+mod constrain;
+#[warn(missing_docs)]
+mod layout;
+#[warn(missing_docs)]
+mod merge;
+#[warn(missing_docs)]
+mod preview;
+#[warn(missing_docs)]
+mod ramp;
+#[warn(missing_docs)]
+mod replica;
+#[warn(missing_docs)]
+mod report;
+#[warn(missing_docs)]
 mod undo;
+#[warn(missing_docs)]
+mod variation;
 
 // Submodule re-exports.
+pub use self::anchor::Renormalize;
 pub use self::basic::{
 	InsertCell,
+	InsertColor,
 	DeleteCell,
+	MoveCell,
 };
 pub use self::combine::{
 	Repeat,
 	Sequence,
 };
+pub use self::constrain::ConstrainAnsi256;
+pub use self::layout::{
+	LayoutRamps,
+	RampAlignment,
+};
+pub use self::merge::{
+	MergeAction,
+	MergePalette,
+	MergeReport,
+	MergeStrategy,
+};
+pub use self::preview::{
+	preview,
+	CellChange,
+	Preview,
+};
+pub use self::ramp::{
+	FitRamp,
+	InsertRamp,
+	PasteIntoRamp,
+};
+pub use self::replica::{
+	CellEdit,
+	Replica,
+	ReplicaId,
+	Stamp,
+	SyncReport,
+};
+pub use self::report::{
+	DeltaStats,
+	OperationReport,
+};
 pub use self::undo::Undo;
+pub use self::variation::InsertVariations;
 
 // Local imports.
 use address::Address;
@@ -55,6 +109,7 @@ use expression::Expression;
 use result::{Error, Result};
 
 // Standard imports.
+use std::borrow::Cow;
 use std::fmt;
 use std::rc::{Rc, Weak};
 use std::mem;
@@ -90,7 +145,7 @@ pub(crate) fn target(
 	undo: &mut Undo)
 	-> Result<Rc<Cell>>
 {
-	if let Some(cell) = data.cell(address) {
+	if let Some(cell) = data.cell_mut(address) {
 		Ok(cell)
 	} else {
 		let cell = data.create_cell(address)?;
@@ -117,6 +172,34 @@ pub(crate) fn set_target(
 	Ok(())
 }
 
+/// Stores the given Expression in the cell at the given address in the given
+/// palette as `set_target` does, resolving any failure by the given policy. 
+/// Skipped cells are added to `conflicts`. If the operation is abandoned, the
+/// changes recorded in the `Undo` are reverted before the error is returned.
+pub(crate) fn set_target_or_skip(
+	data: &mut Data,
+	address: Address,
+	new_element: Expression,
+	undo: &mut Undo,
+	policy: &ConflictPolicy,
+	conflicts: &mut Vec<Conflict>)
+	-> Result<()>
+{
+	let error = match set_target(data, address, new_element, undo) {
+		Ok(()) => return Ok(()),
+		Err(error) => error,
+	};
+
+	let conflict = Conflict {address: address, error: error};
+	if policy.skips(&conflict) {
+		conflicts.push(conflict);
+		Ok(())
+	} else {
+		undo.apply(data)?;
+		Err(conflict.error)
+	}
+}
+
 
 
 ////////////////////////////////////////////////////////////////////////////////
@@ -148,6 +231,79 @@ pub struct OperationHistory {
 }
 
 
+impl OperationHistory {
+	/// Squashes the most recent `count` undo entries into a single entry which
+	/// undoes them all at once. If the squashed entries share a name, the new
+	/// entry keeps it; otherwise it is named "Sequence".
+	///
+	/// # Example
+	///
+	/// ```rust
+	/// use palette::*;
+	/// use palette::operation::*;
+	///
+	/// let mut pal = Palette::new("Example", Format::Default, true);
+	/// let a = Address::new(0, 0, 0);
+	/// pal.apply(Box::new(InsertColor::new(Color::new(0, 0, 0)))).unwrap();
+	/// for v in 1..100 {
+	/// 	pal.apply(Box::new(InsertColor::new(Color::new(v, 0, 0))
+	/// 		.located_at(a)
+	/// 		.overwrite(true))).unwrap();
+	/// }
+	///
+	/// pal.squash_history(99);
+	/// assert_eq!(pal.history_len(), (2, 0));
+	///
+	/// pal.undo().unwrap();
+	/// assert_eq!(pal.color(a), Some(Color::new(0, 0, 0)));
+	/// pal.redo().unwrap();
+	/// assert_eq!(pal.color(a), Some(Color::new(99, 0, 0)));
+	/// ```
+	pub fn squash(&mut self, count: usize) {
+		let count = count.min(self.undo_entries.len());
+		if count < 2 { return; }
+
+		let start = self.undo_entries.len() - count;
+		let entries: Vec<_> = self.undo_entries.drain(start..).collect();
+		let name = entries[0].info.name.clone();
+		let name = if entries.iter().all(|e| e.info.name == name) {
+			name
+		} else {
+			"Sequence".into()
+		};
+
+		// The squashed entries are undone newest first.
+		let undos = entries.into_iter().rev().map(|e| e.undo).collect();
+		self.undo_entries.push(HistoryEntry {
+			info: OperationInfo {
+				name: name,
+				details: Some(format!("{} operations", count)),
+			},
+			undo: Box::new(Sequence::new(undos)),
+			conflicts: Vec::new(),
+		});
+	}
+
+	/// Squashes each run of consecutive undo entries sharing the same name
+	/// into a single entry.
+	pub fn squash_runs(&mut self) {
+		let mut end = self.undo_entries.len();
+		while end > 0 {
+			let name = self.undo_entries[end - 1].info.name.clone();
+			let count = self.undo_entries[..end]
+				.iter()
+				.rev()
+				.take_while(|e| e.info.name == name)
+				.count();
+			let mut tail = self.undo_entries.split_off(end);
+			self.squash(count);
+			self.undo_entries.append(&mut tail);
+			end -= count;
+		}
+	}
+}
+
+
 
 
 ////////////////////////////////////////////////////////////////////////////////
@@ -160,6 +316,96 @@ pub struct HistoryEntry {
 	pub info: OperationInfo,
 	/// The operation that undoes the applied operation.
 	pub undo: Box<PaletteOperation>,
+	/// The cells which were skipped under the operation's `ConflictPolicy`.
+	pub conflicts: Vec<Conflict>,
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Conflict
+////////////////////////////////////////////////////////////////////////////////
+/// Describes a cell which an operation could not change.
+#[derive(Debug)]
+pub struct Conflict {
+	/// The address of the cell.
+	pub address: Address,
+	/// The reason the cell could not be changed.
+	pub error: Error,
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// ConflictPolicy
+////////////////////////////////////////////////////////////////////////////////
+/// Determines how an operation changing many cells proceeds when some of them
+/// cannot be changed.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::data::Data;
+/// use palette::operation::*;
+///
+/// let mut data = Data::default();
+/// data.maximum_page_count = 2;
+/// InsertColor::new(Color::new(200, 80, 40)).apply(&mut data).unwrap();
+///
+/// // Only the first of three variations fits in the palette.
+/// let variations = InsertVariations::new(Selection::all(), 3, 42);
+/// assert!(variations.clone().apply(&mut data).is_err());
+/// assert_eq!(data.len(), 1);
+///
+/// let entry = variations
+/// 	.conflict_policy(ConflictPolicy::SkipInvalid)
+/// 	.apply(&mut data)
+/// 	.unwrap();
+/// assert_eq!(data.len(), 2);
+/// assert_eq!(entry.conflicts.len(), 2);
+/// ```
+#[derive(Clone)]
+pub enum ConflictPolicy {
+	/// Abandons the operation at the first conflict, restoring any cells it 
+	/// has already changed.
+	AbortAll,
+	/// Skips every cell which cannot be changed.
+	SkipInvalid,
+	/// Calls the given function for each cell which cannot be changed. The 
+	/// cell is skipped if it returns true, and the operation is abandoned if 
+	/// it returns false.
+	Prompt(Rc<Fn(&Conflict) -> bool>),
+}
+
+
+impl ConflictPolicy {
+	/// Returns whether the given conflict should be skipped.
+	fn skips(&self, conflict: &Conflict) -> bool {
+		match *self {
+			ConflictPolicy::AbortAll => false,
+			ConflictPolicy::SkipInvalid => true,
+			ConflictPolicy::Prompt(ref prompt) => prompt(conflict),
+		}
+	}
+}
+
+
+impl Default for ConflictPolicy {
+	fn default() -> Self {
+		ConflictPolicy::AbortAll
+	}
+}
+
+
+impl fmt::Debug for ConflictPolicy {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			ConflictPolicy::AbortAll => write!(f, "AbortAll"),
+			ConflictPolicy::SkipInvalid => write!(f, "SkipInvalid"),
+			ConflictPolicy::Prompt(..) => write!(f, "Prompt(..)"),
+		}
+	}
 }
 
 
@@ -170,8 +416,9 @@ pub struct HistoryEntry {
 /// Describes an applied operation.
 #[derive(Debug, PartialOrd, PartialEq, Eq, Hash, Ord, Clone)]
 pub struct OperationInfo {
-	/// The name of the operation.
-	pub name: &'static str,
+	/// The name of the operation. Names of operations restored from a file
+	/// are owned.
+	pub name: Cow<'static, str>,
 	/// The details of the operation.
 	pub details: Option<String>,
 }
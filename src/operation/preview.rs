@@ -0,0 +1,133 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides dry-run evaluation of operations.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use address::Address;
+use data::Data;
+use operation::PaletteOperation;
+use result::Result;
+
+// Non-local imports.
+use color::Color;
+
+// Standard imports.
+use std::rc::Rc;
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// CellChange
+////////////////////////////////////////////////////////////////////////////////
+/// Describes the change in color of a single cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CellChange {
+	/// The address of the changed cell.
+	pub address: Address,
+	/// The color of the cell before the operation, or None if it had no color.
+	pub before: Option<Color>,
+	/// The color of the cell after the operation, or None if it has no color.
+	pub after: Option<Color>,
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Preview
+////////////////////////////////////////////////////////////////////////////////
+/// The set of color changes an operation would make to a palette.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Preview {
+	/// The changed cells, in address order.
+	pub changes: Vec<CellChange>,
+}
+
+
+impl Preview {
+	/// Returns the number of changed cells.
+	pub fn len(&self) -> usize {
+		self.changes.len()
+	}
+
+	/// Returns whether the operation would change no cells.
+	pub fn is_empty(&self) -> bool {
+		self.changes.is_empty()
+	}
+
+	/// Returns the color the cell at the given address would have after the
+	/// operation, or None if the cell would be unchanged.
+	pub fn color(&self, address: Address) -> Option<Option<Color>> {
+		self.changes.iter()
+			.find(|change| change.address == address)
+			.map(|change| change.after)
+	}
+}
+
+
+/// Applies the given operation to a fork of the given data and records the 
+/// resulting color changes. The data itself is not modified.
+///
+/// # Errors
+///
+/// Returns any error generated by applying the operation.
+pub fn preview<O>(data: &Data, operation: &mut O) -> Result<Preview>
+	where O: PaletteOperation + ?Sized
+{
+	let mut fork = data.fork();
+	let _ = operation.apply(&mut fork)?;
+	Ok(Preview {changes: changes(data, &fork)})
+}
+
+
+/// Returns the color changes between the given data and a later fork of it, 
+/// in address order.
+pub(crate) fn changes(data: &Data, fork: &Data) -> Vec<CellChange> {
+	// Cells still shared with the original are unchanged.
+	let mut changes = Vec::new();
+	for (&address, cell) in &fork.cells {
+		let old = data.cells.get(&address);
+		if old.map_or(false, |old| Rc::ptr_eq(old, cell)) { continue; }
+
+		let before = old.and_then(|old| old.color());
+		let after = cell.color();
+		if before != after {
+			changes.push(CellChange {
+				address: address,
+				before: before,
+				after: after,
+			});
+		}
+	}
+	for (&address, cell) in &data.cells {
+		let before = cell.color();
+		if before.is_some() && !fork.cells.contains_key(&address) {
+			changes.push(CellChange {address: address, before: before, after: None});
+		}
+	}
+	changes.sort_by_key(|change| change.address);
+	changes
+}
@@ -0,0 +1,485 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Defines ramp generating operations.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use address::{
+	Address,
+	Selection,
+};
+use data::Data;
+use expression::Expression;
+use operation::{
+	set_target_or_skip,
+	ConflictPolicy,
+	HistoryEntry,
+	OperationInfo,
+	PaletteOperation,
+	Undo,
+};
+use ramp::{
+	RampCoordinate,
+	RampFit,
+	RampGenerator,
+};
+use result::{
+	Error,
+	Result,
+};
+
+// Non-local imports.
+use color::Color;
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// InsertRamp
+////////////////////////////////////////////////////////////////////////////////
+/// Inserts a ramp of colors interpolated between the colors of two cells.
+///
+/// The ramp colors are stored unquantized, so ramps built from the results of
+/// other ramps do not accumulate 8-bit rounding error.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::operation::*;
+///
+/// let mut pal = Palette::new("Example", Format::Default, true);
+///
+/// pal.apply(Box::new(InsertColor::new(Color::new(0, 0, 0)))).unwrap();
+/// pal.apply(Box::new(InsertColor::new(Color::new(100, 100, 100)))).unwrap();
+/// pal.apply(Box::new(
+/// 	InsertRamp::new(Address::new(0, 0, 0), Address::new(0, 0, 1), 3)
+/// 		.located_at(Address::new(0, 1, 0))
+/// )).unwrap();
+///
+/// assert_eq!(pal.color(Address::new(0, 1, 1)), Some(Color::new(50, 50, 50)));
+/// ```
+#[derive(Debug, Clone)]
+pub struct InsertRamp {
+	/// The address of the ramp's starting color.
+	start: Address,
+	/// The address of the ramp's ending color.
+	end: Address,
+	/// The number of colors in the ramp.
+	count: usize,
+	/// The generator used to produce the ramp colors.
+	generator: RampGenerator,
+	/// The location to start placing the colors.
+	location: Option<Address>,
+	/// Whether to overwrite existing cells when generating new ones.
+	overwrite: bool,
+	/// The policy for cells which cannot be changed.
+	policy: ConflictPolicy,
+}
+
+
+impl InsertRamp {
+	/// Creates a new InsertRamp operation producing `count` colors between the
+	/// colors at the given addresses.
+	#[inline]
+	pub fn new(start: Address, end: Address, count: usize) -> InsertRamp {
+		InsertRamp {
+			start: start,
+			end: end,
+			count: count,
+			generator: Default::default(),
+			location: None,
+			overwrite: false,
+			policy: ConflictPolicy::default(),
+		}
+	}
+
+	/// Sets the generator used to produce the ramp colors.
+	pub fn generator(mut self, generator: RampGenerator) -> InsertRamp {
+		self.generator = generator;
+		self
+	}
+
+	/// Sets the location to start placing the colors.
+	pub fn located_at(mut self, location: Address) -> InsertRamp {
+		self.location = Some(location);
+		self
+	}
+
+	/// Configures the operation to overwrite existing cells when inserted.
+	pub fn overwrite(mut self, overwrite: bool) -> InsertRamp {
+		self.overwrite = overwrite;
+		self
+	}
+
+	/// Sets the policy for cells which cannot be changed.
+	pub fn conflict_policy(mut self, policy: ConflictPolicy) -> InsertRamp {
+		self.policy = policy;
+		self
+	}
+}
+
+
+impl PaletteOperation for InsertRamp {
+	fn info(&self) -> OperationInfo {
+		OperationInfo {
+			name: "Insert Ramp".into(),
+			details: Some(format!("{:?}", self))
+		}
+	}
+
+	fn apply(&mut self, data: &mut Data) -> Result<HistoryEntry> {
+		let start = data.cell(self.start)
+			.and_then(|cell| cell.wide_color())
+			.ok_or(Error::EmptyAddress(self.start))?;
+		let end = data.cell(self.end)
+			.and_then(|cell| cell.wide_color())
+			.ok_or(Error::EmptyAddress(self.end))?;
+
+		// Get starting address.
+		let starting_address = if let Some(address) = self.location {
+			address
+		} else {
+			data.first_free_address_after(Default::default())?
+		};
+
+		// Get targets, excluding the control colors.
+		let targets = data.find_targets(
+			self.count,
+			starting_address,
+			self.overwrite,
+			Some(vec![self.start, self.end])
+		)?;
+
+		// Set targets.
+		let mut undo = Undo::new_for(self);
+		let mut conflicts = Vec::new();
+		let colors = self.generator.ramp_wide(start, end, self.count);
+		for (&target, color) in targets.iter().zip(colors) {
+			set_target_or_skip(data, target, Expression::Wide(color),
+				&mut undo, &self.policy, &mut conflicts)?;
+		}
+
+		Ok(HistoryEntry {
+			info: self.info(),
+			undo: Box::new(undo),
+			conflicts: conflicts,
+		})
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// FitRamp
+////////////////////////////////////////////////////////////////////////////////
+/// Fits key colors and interpolation parameters to the selected run of 
+/// colors, and replaces the run with the fitted ramp.
+///
+/// The fitted key colors can optionally be placed in new cells, and the fit
+/// is available from the operation after it is applied or previewed.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::operation::*;
+/// use palette::select::Selector;
+///
+/// let mut pal = Palette::new("Example", Format::Default, true);
+/// for &v in &[0, 60, 121, 180, 240] {
+/// 	pal.apply(Box::new(InsertColor::new(Color::new(v, v, v)))).unwrap();
+/// }
+///
+/// let run = Selector::parse("page0[0..5]").unwrap().select(&pal);
+/// let mut fit = FitRamp::new(run, 1.0).located_at(Address::new(0, 1, 0));
+/// pal.preview(&mut fit).unwrap();
+/// assert_eq!(fit.fit().unwrap().keys.len(), 2);
+///
+/// pal.apply(Box::new(fit)).unwrap();
+/// let gray = |v| Some(Color::new(v, v, v));
+/// assert_eq!(pal.color(Address::new(0, 0, 2)), gray(120));
+/// assert_eq!(pal.color(Address::new(0, 1, 1)), gray(240));
+/// ```
+#[derive(Debug, Clone)]
+pub struct FitRamp {
+	/// The cells of the run to fit, in address order.
+	selection: Selection,
+	/// The largest CIEDE2000 difference allowed between the run and the fit.
+	tolerance: f32,
+	/// The location to place the key colors, if any.
+	location: Option<Address>,
+	/// Whether to overwrite existing cells when placing the key colors.
+	overwrite: bool,
+	/// The policy for cells which cannot be changed.
+	policy: ConflictPolicy,
+	/// The most recent fit.
+	fit: Option<RampFit>,
+}
+
+
+impl FitRamp {
+	/// Creates a new FitRamp operation fitting the colors in the given 
+	/// selection within the given CIEDE2000 tolerance.
+	pub fn new(selection: Selection, tolerance: f32) -> FitRamp {
+		FitRamp {
+			selection: selection,
+			tolerance: tolerance,
+			location: None,
+			overwrite: false,
+			policy: ConflictPolicy::default(),
+			fit: None,
+		}
+	}
+
+	/// Sets the location to start placing the key colors.
+	pub fn located_at(mut self, location: Address) -> FitRamp {
+		self.location = Some(location);
+		self
+	}
+
+	/// Configures the operation to overwrite existing cells when placing the
+	/// key colors.
+	pub fn overwrite(mut self, overwrite: bool) -> FitRamp {
+		self.overwrite = overwrite;
+		self
+	}
+
+	/// Sets the policy for cells which cannot be changed.
+	pub fn conflict_policy(mut self, policy: ConflictPolicy) -> FitRamp {
+		self.policy = policy;
+		self
+	}
+
+	/// Returns the fit found when the operation was last applied, if any.
+	pub fn fit(&self) -> Option<&RampFit> {
+		self.fit.as_ref()
+	}
+}
+
+
+impl PaletteOperation for FitRamp {
+	fn info(&self) -> OperationInfo {
+		OperationInfo {
+			name: "Fit Ramp".into(),
+			details: Some(format!("{:?}", self))
+		}
+	}
+
+	fn apply(&mut self, data: &mut Data) -> Result<HistoryEntry> {
+		let run = data.colors(&self.selection);
+		let colors: Vec<_> = run.iter().map(|&(_, color)| color).collect();
+		let mut undo = Undo::new_for(self);
+		let mut conflicts = Vec::new();
+
+		if let Some(fit) = RampFit::fit(&colors, self.tolerance) {
+			// Replace the run with the fitted ramp.
+			let ramp = fit.ramp(run.len());
+			for (&(address, _), color) in run.iter().zip(ramp) {
+				set_target_or_skip(data, address, Expression::Wide(color),
+					&mut undo, &self.policy, &mut conflicts)?;
+			}
+
+			// Place the key colors, excluding the run.
+			if let Some(location) = self.location {
+				let targets = data.find_targets(
+					fit.keys.len(),
+					location,
+					self.overwrite,
+					Some(run.iter().map(|&(address, _)| address).collect())
+				)?;
+				for (&target, &key) in targets.iter().zip(&fit.keys) {
+					set_target_or_skip(data, target, Expression::Wide(key),
+						&mut undo, &self.policy, &mut conflicts)?;
+				}
+			}
+			self.fit = Some(fit);
+		}
+
+		Ok(HistoryEntry {
+			info: self.info(),
+			undo: Box::new(undo),
+			conflicts: conflicts,
+		})
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// PasteIntoRamp
+////////////////////////////////////////////////////////////////////////////////
+/// Inserts a loose list of colors into the selected ramp, placing each color
+/// where its coordinate falls along the ramp, and refits the ramp.
+///
+/// The merged ramp is written from the first cell of the selection, extending
+/// into the free cells after the selection. As with `FitRamp`, the cells are
+/// replaced with the fitted ramp, which is available from the operation after
+/// it is applied or previewed.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::operation::*;
+/// use palette::select::Selector;
+///
+/// let mut pal = Palette::new("Example", Format::Default, true);
+/// for &v in &[0, 120, 240] {
+/// 	pal.apply(Box::new(InsertColor::new(Color::new(v, v, v)))).unwrap();
+/// }
+///
+/// let ramp = Selector::parse("page0[0..3]").unwrap().select(&pal);
+/// let colors = vec![Color::new(180, 180, 180), Color::new(60, 60, 60)];
+/// pal.apply(Box::new(PasteIntoRamp::new(ramp, colors).tolerance(0.0)))
+/// 	.unwrap();
+///
+/// let gray = |v| Some(Color::new(v, v, v));
+/// assert_eq!(pal.color(Address::new(0, 0, 1)), gray(60));
+/// assert_eq!(pal.color(Address::new(0, 0, 3)), gray(180));
+/// assert_eq!(pal.color(Address::new(0, 0, 4)), gray(240));
+/// ```
+#[derive(Debug, Clone)]
+pub struct PasteIntoRamp {
+	/// The cells of the ramp, in address order.
+	selection: Selection,
+	/// The colors to insert.
+	colors: Vec<Color>,
+	/// The coordinate used to place the inserted colors.
+	coordinate: RampCoordinate,
+	/// The largest CIEDE2000 difference allowed between the merged colors and
+	/// the refitted ramp.
+	tolerance: f32,
+	/// Whether to overwrite existing cells when extending the ramp.
+	overwrite: bool,
+	/// The policy for cells which cannot be changed.
+	policy: ConflictPolicy,
+	/// The most recent fit.
+	fit: Option<RampFit>,
+}
+
+
+impl PasteIntoRamp {
+	/// Creates a new PasteIntoRamp operation inserting the given colors into
+	/// the ramp in the given selection.
+	pub fn new(selection: Selection, colors: Vec<Color>) -> PasteIntoRamp {
+		PasteIntoRamp {
+			selection: selection,
+			colors: colors,
+			coordinate: RampCoordinate::default(),
+			tolerance: 1.0,
+			overwrite: false,
+			policy: ConflictPolicy::default(),
+			fit: None,
+		}
+	}
+
+	/// Sets the coordinate used to place the inserted colors.
+	pub fn coordinate(mut self, coordinate: RampCoordinate) -> PasteIntoRamp {
+		self.coordinate = coordinate;
+		self
+	}
+
+	/// Sets the largest CIEDE2000 difference allowed between the merged colors
+	/// and the refitted ramp.
+	pub fn tolerance(mut self, tolerance: f32) -> PasteIntoRamp {
+		self.tolerance = tolerance;
+		self
+	}
+
+	/// Configures the operation to overwrite existing cells when extending 
+	/// the ramp.
+	pub fn overwrite(mut self, overwrite: bool) -> PasteIntoRamp {
+		self.overwrite = overwrite;
+		self
+	}
+
+	/// Sets the policy for cells which cannot be changed.
+	pub fn conflict_policy(mut self, policy: ConflictPolicy) -> PasteIntoRamp {
+		self.policy = policy;
+		self
+	}
+
+	/// Returns the fit found when the operation was last applied, if any.
+	pub fn fit(&self) -> Option<&RampFit> {
+		self.fit.as_ref()
+	}
+}
+
+
+impl PaletteOperation for PasteIntoRamp {
+	fn info(&self) -> OperationInfo {
+		OperationInfo {
+			name: "Paste Into Ramp".into(),
+			details: Some(format!("{:?}", self))
+		}
+	}
+
+	fn apply(&mut self, data: &mut Data) -> Result<HistoryEntry> {
+		let run = data.colors(&self.selection);
+		let mut undo = Undo::new_for(self);
+		if run.is_empty() || self.colors.is_empty() {
+			return Ok(HistoryEntry {
+				info: self.info(),
+				undo: Box::new(undo),
+				conflicts: Vec::new(),
+			});
+		}
+
+		// Merge the colors into the ramp.
+		let mut merged: Vec<_> = run.iter().map(|&(_, color)| color).collect();
+		for &color in &self.colors {
+			let index = self.coordinate.insertion_index(&merged, color);
+			merged.insert(index, color);
+		}
+
+		// Extend the ramp past its last cell.
+		let mut targets: Vec<_> = run.iter()
+			.map(|&(address, _)| address)
+			.collect();
+		let extension = data.find_targets(
+			self.colors.len(),
+			targets[targets.len() - 1],
+			self.overwrite,
+			Some(targets.clone())
+		)?;
+		targets.extend(extension);
+
+		let fit = RampFit::fit(&merged, self.tolerance)
+			.expect("merged ramp is not empty");
+		let mut conflicts = Vec::new();
+		for (&target, color) in targets.iter().zip(fit.ramp(merged.len())) {
+			set_target_or_skip(data, target, Expression::Wide(color),
+				&mut undo, &self.policy, &mut conflicts)?;
+		}
+		self.fit = Some(fit);
+
+		Ok(HistoryEntry {
+			info: self.info(),
+			undo: Box::new(undo),
+			conflicts: conflicts,
+		})
+	}
+}
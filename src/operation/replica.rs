@@ -0,0 +1,481 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides merging of concurrent edits made to copies of a palette.
+//!
+//! Each copy of the palette is edited through a `Replica`, which records the 
+//! contents written to each cell as a `CellEdit`. Edits are stamped with a 
+//! Lamport timestamp and the id of the replica making them, and the cells of
+//! a palette form a last-writer-wins map: when edits to the same cell are 
+//! merged, the one with the greatest stamp wins. As the stamps are totally 
+//! ordered, replicas which have merged the same edits hold the same cells, 
+//! regardless of the order the edits were received in or whether some were 
+//! received more than once.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use ::Palette;
+use address::Address;
+use data::Data;
+use expression::Expression;
+use format::default::{
+	parse_address,
+	read_expression,
+	tokenize,
+	write_expression,
+};
+use operation::{
+	HistoryEntry,
+	OperationInfo,
+	PaletteOperation,
+	Undo,
+};
+use result::Result;
+
+// Standard imports.
+use std::collections::{
+	BTreeMap,
+	BTreeSet,
+	HashMap,
+};
+use std::fmt;
+use std::mem;
+use std::str::FromStr;
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// ReplicaId
+////////////////////////////////////////////////////////////////////////////////
+/// Identifies a replica of a palette. Each replica editing a palette 
+/// concurrently must have a distinct id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ReplicaId(pub u64);
+
+
+impl fmt::Display for ReplicaId {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}", self.0)
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Stamp
+////////////////////////////////////////////////////////////////////////////////
+/// A Lamport timestamp identifying an edit. Stamps are ordered by their 
+/// counters, with ties broken by replica id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Stamp {
+	/// The logical time of the edit.
+	pub counter: u64,
+	/// The replica which made the edit.
+	pub replica: ReplicaId,
+}
+
+
+impl fmt::Display for Stamp {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}@{}", self.counter, self.replica)
+	}
+}
+
+
+impl FromStr for Stamp {
+	type Err = String;
+
+	fn from_str(text: &str) -> ::std::result::Result<Stamp, String> {
+		let mut parts = text.splitn(2, '@');
+		let counter = parts.next().and_then(|c| c.parse().ok());
+		let replica = parts.next().and_then(|r| r.parse().ok());
+		match (counter, replica) {
+			(Some(counter), Some(replica)) => Ok(Stamp {
+				counter: counter,
+				replica: ReplicaId(replica),
+			}),
+			_ => Err(format!("invalid stamp '{}'", text)),
+		}
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// CellEdit
+////////////////////////////////////////////////////////////////////////////////
+/// The contents written to a cell by a replica.
+///
+/// Edits are written as text in the form `<stamp> <address> <contents>`, 
+/// where the contents are an expression in the default format, or `remove` 
+/// if the cell was removed.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::operation::CellEdit;
+///
+/// let edit: CellEdit = "3@1 0:0:2 #0C324E".parse().unwrap();
+/// assert_eq!(edit.stamp.counter, 3);
+/// assert_eq!(edit.to_string(), "3@1 0:0:2 #0C324E");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct CellEdit {
+	/// The stamp of the edit.
+	pub stamp: Stamp,
+	/// The address of the edited cell.
+	pub address: Address,
+	/// The new contents of the cell, or None if the cell was removed.
+	pub expression: Option<Expression>,
+}
+
+
+impl fmt::Display for CellEdit {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{} {} {}", 
+			self.stamp, 
+			self.address, 
+			self.expression
+				.as_ref()
+				.map_or_else(|| "remove".to_owned(), write_expression))
+	}
+}
+
+
+impl FromStr for CellEdit {
+	type Err = String;
+
+	fn from_str(text: &str) -> ::std::result::Result<CellEdit, String> {
+		let tokens = tokenize(text)?;
+		if tokens.len() < 3 {
+			return Err(format!("invalid edit '{}'", text));
+		}
+		let expression = match &tokens[2][..] {
+			"remove" => None,
+			_ => Some(read_expression(&tokens[2..])?),
+		};
+		Ok(CellEdit {
+			stamp: tokens[0].parse()?,
+			address: parse_address(&tokens[1])?,
+			expression: expression,
+		})
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// SyncReport
+////////////////////////////////////////////////////////////////////////////////
+/// Summarizes the effects of merging edits into a replica.
+#[derive(Debug, Clone, Default)]
+pub struct SyncReport {
+	/// The addresses of the cells changed by the merged edits, in order.
+	pub applied: Vec<Address>,
+	/// The merged edits which lost to a later edit of the same cell.
+	pub superseded: Vec<CellEdit>,
+	/// The number of merged edits which had already been merged.
+	pub duplicates: usize,
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Replica
+////////////////////////////////////////////////////////////////////////////////
+/// Tracks the edits made to one copy of a palette, so they can be exchanged 
+/// with and merged into other copies.
+///
+/// Replicas should start from identical palettes. Changes made to the palette
+/// other than through the replica are not recorded.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::operation::*;
+///
+/// let mut base = Palette::new("Example", Format::Default, true);
+/// base.apply(Box::new(InsertColor::new(Color::new(0, 0, 0)))).unwrap();
+/// let a = Address::new(0, 0, 0);
+///
+/// let mut ours = base.fork();
+/// let mut theirs = base.fork();
+/// let mut our_replica = Replica::new(ReplicaId(1));
+/// let mut their_replica = Replica::new(ReplicaId(2));
+///
+/// // Both replicas recolor the same cell concurrently.
+/// let recolor = |c| InsertColor::new(c).located_at(a).overwrite(true);
+/// let red = recolor(Color::new(255, 0, 0));
+/// let blue = recolor(Color::new(0, 0, 255));
+/// our_replica.apply(&mut ours, Box::new(red)).unwrap();
+/// their_replica.apply(&mut theirs, Box::new(blue)).unwrap();
+///
+/// // Exchanging edits resolves the conflict the same way in both copies.
+/// let our_edits = our_replica.edits_since(&their_replica.version());
+/// let their_edits = their_replica.edits_since(&our_replica.version());
+/// our_replica.merge(&mut ours, &their_edits).unwrap();
+/// their_replica.merge(&mut theirs, &our_edits).unwrap();
+///
+/// assert_eq!(ours.color(a), Some(Color::new(0, 0, 255)));
+/// assert_eq!(theirs.color(a), Some(Color::new(0, 0, 255)));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Replica {
+	/// The id of the replica.
+	id: ReplicaId,
+	/// The greatest counter of any edit seen by the replica.
+	clock: u64,
+	/// The stamp of the edit currently held by each edited cell.
+	stamps: HashMap<Address, Stamp>,
+	/// The greatest counter seen from each replica.
+	version: BTreeMap<ReplicaId, u64>,
+	/// Every edit seen by the replica, in the order it was seen.
+	log: Vec<CellEdit>,
+}
+
+
+impl Replica {
+	/// Creates a new Replica with the given id.
+	pub fn new(id: ReplicaId) -> Replica {
+		Replica {
+			id: id,
+			clock: 0,
+			stamps: HashMap::new(),
+			version: BTreeMap::new(),
+			log: Vec::new(),
+		}
+	}
+
+	/// Returns the id of the replica.
+	pub fn id(&self) -> ReplicaId {
+		self.id
+	}
+
+	/// Returns every edit seen by the replica, in the order it was seen.
+	pub fn edits(&self) -> &[CellEdit] {
+		&self.log
+	}
+
+	/// Returns the version of the replica, mapping each replica whose edits 
+	/// have been seen to the greatest counter seen from it.
+	pub fn version(&self) -> BTreeMap<ReplicaId, u64> {
+		self.version.clone()
+	}
+
+	/// Returns the edits seen by the replica which are newer than the given 
+	/// version, usually the version of another replica.
+	pub fn edits_since(&self, version: &BTreeMap<ReplicaId, u64>) 
+		-> Vec<CellEdit>
+	{
+		self.log.iter()
+			.filter(|edit| edit.stamp.counter 
+				> version.get(&edit.stamp.replica).cloned().unwrap_or(0))
+			.cloned()
+			.collect()
+	}
+
+	/// Applies the given operation to the palette, recording the edits it 
+	/// makes.
+	pub fn apply(
+		&mut self, 
+		palette: &mut Palette, 
+		operation: Box<PaletteOperation>) 
+		-> Result<Vec<CellEdit>>
+	{
+		self.track(palette, |palette| palette.apply(operation).map(|_| ()))
+	}
+
+	/// Makes the given change to the palette, such as an undo, recording the 
+	/// edits it makes.
+	pub fn track<F>(&mut self, palette: &mut Palette, change: F) 
+		-> Result<Vec<CellEdit>>
+		where F: FnOnce(&mut Palette) -> Result<()>
+	{
+		let before = palette.data.fork();
+		change(palette)?;
+
+		let edits: Vec<_> = changed_cells(&before, &palette.data)
+			.into_iter()
+			.map(|(address, expression)| {
+				self.clock += 1;
+				CellEdit {
+					stamp: Stamp {counter: self.clock, replica: self.id},
+					address: address,
+					expression: expression,
+				}
+			})
+			.collect();
+		for edit in &edits {
+			self.record(*edit);
+		}
+		Ok(edits)
+	}
+
+	/// Merges the given edits from other replicas into the palette. Edits 
+	/// which have already been merged are ignored, and edits to the same cell
+	/// are resolved in favor of the greatest stamp.
+	///
+	/// The edits of each replica must be merged without gaps, as returned by
+	/// `edits_since`, as an edit older than one already merged from the same
+	/// replica is treated as a duplicate.
+	pub fn merge(&mut self, palette: &mut Palette, edits: &[CellEdit]) 
+		-> Result<SyncReport>
+	{
+		let mut report = SyncReport::default();
+		let mut stamps = self.stamps.clone();
+		let mut winners = BTreeMap::new();
+		let mut seen = BTreeSet::new();
+
+		for edit in edits {
+			let known = self.version.get(&edit.stamp.replica).cloned()
+				.unwrap_or(0);
+			if edit.stamp.counter <= known || !seen.insert(edit.stamp) {
+				report.duplicates += 1;
+				continue;
+			}
+
+			let current = stamps.get(&edit.address).cloned();
+			if current.map_or(true, |stamp| edit.stamp > stamp) {
+				stamps.insert(edit.address, edit.stamp);
+				if let Some(lost) = winners.insert(edit.address, *edit) {
+					report.superseded.push(lost);
+				}
+			} else {
+				report.superseded.push(*edit);
+			}
+		}
+
+		if !winners.is_empty() {
+			let operation = ApplyEdits {
+				edits: winners.values()
+					.map(|edit| (edit.address, edit.expression))
+					.collect(),
+			};
+			palette.apply(Box::new(operation))?;
+		}
+
+		let mut merged: Vec<_> = edits.iter()
+			.filter(|edit| seen.contains(&edit.stamp))
+			.cloned()
+			.collect();
+		merged.sort_by_key(|edit| edit.stamp);
+		for edit in merged {
+			self.record(edit);
+		}
+		self.stamps = stamps;
+		report.applied = winners.keys().cloned().collect();
+		Ok(report)
+	}
+
+	/// Records an edit seen by the replica, advancing its clock.
+	fn record(&mut self, edit: CellEdit) {
+		self.clock = self.clock.max(edit.stamp.counter);
+		let counter = self.version.entry(edit.stamp.replica).or_insert(0);
+		*counter = (*counter).max(edit.stamp.counter);
+		let current = self.stamps.get(&edit.address).cloned();
+		if current.map_or(true, |stamp| edit.stamp > stamp) {
+			self.stamps.insert(edit.address, edit.stamp);
+		}
+		self.log.push(edit);
+	}
+}
+
+
+/// Returns the address and new contents of each cell which differs between 
+/// the given data, in address order.
+fn changed_cells(before: &Data, after: &Data) 
+	-> Vec<(Address, Option<Expression>)>
+{
+	let addresses: BTreeSet<_> = before.cells.keys()
+		.chain(after.cells.keys())
+		.cloned()
+		.collect();
+	addresses.into_iter()
+		.filter_map(|address| {
+			let old = before.cells.get(&address).map(|c| *c.borrow());
+			let new = after.cells.get(&address).map(|c| *c.borrow());
+			let text = |e: Option<Expression>| e.as_ref().map(write_expression);
+			if text(old) == text(new) {
+				None
+			} else {
+				Some((address, new))
+			}
+		})
+		.collect()
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// ApplyEdits
+////////////////////////////////////////////////////////////////////////////////
+/// Writes the winning contents of merged edits to their cells.
+#[derive(Debug)]
+struct ApplyEdits {
+	/// The address and new contents of each edited cell.
+	edits: Vec<(Address, Option<Expression>)>,
+}
+
+
+impl PaletteOperation for ApplyEdits {
+	fn info(&self) -> OperationInfo {
+		OperationInfo {
+			name: "Merge Edits".into(),
+			details: Some(format!("{} cells", self.edits.len())),
+		}
+	}
+
+	fn apply(&mut self, data: &mut Data) -> Result<HistoryEntry> {
+		let mut undo = Undo::new_for(self);
+
+		for &(address, expression) in &self.edits {
+			match (expression, data.cell(address).is_some()) {
+				(Some(expression), true) => {
+					let cell = data.cell_mut(address).expect("cell exists");
+					let old = mem::replace(&mut *cell.borrow_mut(), expression);
+					undo.record(address, Some(old));
+				},
+				(Some(expression), false) => {
+					let cell = data.create_cell(address)?;
+					*cell.borrow_mut() = expression;
+					undo.record(address, None);
+				},
+				(None, true) => {
+					let id = data.cell_id(address);
+					let old = data.remove_cell(address)?;
+					undo.record_removal(address, old, id);
+				},
+				(None, false) => (),
+			}
+		}
+
+		Ok(HistoryEntry {
+			info: self.info(),
+			undo: Box::new(undo),
+			conflicts: Vec::new(),
+		})
+	}
+}
@@ -0,0 +1,160 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides summaries of the effects of applied operations.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use data::Data;
+use operation::{
+	CellChange,
+	Conflict,
+};
+use operation::preview::changes;
+use space::delta_e_2000;
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// DeltaStats
+////////////////////////////////////////////////////////////////////////////////
+/// Statistics of the CIEDE2000 differences between the old and new colors of
+/// recolored cells.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DeltaStats {
+	/// The number of recolored cells.
+	pub count: usize,
+	/// The mean difference.
+	pub mean: f32,
+	/// The largest difference.
+	pub max: f32,
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// OperationReport
+////////////////////////////////////////////////////////////////////////////////
+/// Summarizes the effects of an operation applied to a palette.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::operation::*;
+///
+/// let mut pal = Palette::new("Example", Format::Default, true);
+/// pal.apply(Box::new(InsertColor::new(Color::new(16, 16, 16)))).unwrap();
+/// pal.apply(Box::new(InsertColor::new(Color::new(240, 240, 240)))).unwrap();
+/// pal.set_black_point(Some(Address::new(0, 0, 0)));
+/// pal.set_white_point(Some(Address::new(0, 0, 1)));
+///
+/// let report = pal.apply(Box::new(Renormalize::new())).unwrap();
+///
+/// assert_eq!(report.changed(), 2);
+/// assert_eq!(report.recolored(), 2);
+/// assert_eq!(report.skipped(), 0);
+/// assert!(report.delta().max > 0.0);
+/// ```
+#[derive(Debug, Default)]
+pub struct OperationReport {
+	/// The cells whose colors changed, in address order.
+	pub changes: Vec<CellChange>,
+	/// The cells which were skipped under the operation's `ConflictPolicy`.
+	pub conflicts: Vec<Conflict>,
+	/// Descriptions of possible problems left by the operation.
+	pub warnings: Vec<String>,
+}
+
+
+impl OperationReport {
+	/// Creates a new OperationReport describing the changes between the given
+	/// data and a later fork of it.
+	pub(crate) fn new(before: &Data, after: &Data, conflicts: Vec<Conflict>)
+		-> OperationReport
+	{
+		let changes = changes(before, after);
+		let warnings = changes.iter()
+			.filter(|change| change.after.is_none() 
+				&& after.cells.contains_key(&change.address))
+			.map(|change| format!("cell {} no longer resolves to a color", 
+				change.address))
+			.collect();
+
+		OperationReport {
+			changes: changes,
+			conflicts: conflicts,
+			warnings: warnings,
+		}
+	}
+
+	/// Returns the number of cells whose colors changed.
+	pub fn changed(&self) -> usize {
+		self.changes.len()
+	}
+
+	/// Returns the number of cells which gained a color.
+	pub fn added(&self) -> usize {
+		self.count(|c| c.before.is_none() && c.after.is_some())
+	}
+
+	/// Returns the number of cells which lost their color.
+	pub fn removed(&self) -> usize {
+		self.count(|c| c.before.is_some() && c.after.is_none())
+	}
+
+	/// Returns the number of cells whose color was replaced by another.
+	pub fn recolored(&self) -> usize {
+		self.count(|c| c.before.is_some() && c.after.is_some())
+	}
+
+	/// Returns the number of cells which were skipped.
+	pub fn skipped(&self) -> usize {
+		self.conflicts.len()
+	}
+
+	/// Returns statistics of the color differences of the recolored cells.
+	pub fn delta(&self) -> DeltaStats {
+		let deltas: Vec<f32> = self.changes.iter()
+			.filter_map(|c| match (c.before, c.after) {
+				(Some(before), Some(after)) 
+					=> Some(delta_e_2000(before, after)),
+				_ => None,
+			})
+			.collect();
+		if deltas.is_empty() { return DeltaStats::default(); }
+
+		DeltaStats {
+			count: deltas.len(),
+			mean: deltas.iter().sum::<f32>() / deltas.len() as f32,
+			max: deltas.iter().cloned().fold(0.0, f32::max),
+		}
+	}
+
+	/// Returns the number of changes satisfying the given predicate.
+	fn count<F>(&self, predicate: F) -> usize where F: Fn(&CellChange) -> bool {
+		self.changes.iter().filter(|c| predicate(c)).count()
+	}
+}
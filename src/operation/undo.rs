@@ -27,7 +27,11 @@
 ////////////////////////////////////////////////////////////////////////////////
 
 // Local imports.
-use address::Address;
+use address::{
+	Address,
+	Reference,
+};
+use cell::CellId;
 use data::Data;
 use expression::Expression;
 use operation::{
@@ -35,7 +39,8 @@ use operation::{
 	OperationInfo,
 	PaletteOperation,
 };
-use result::Result;
+use provenance::Provenance;
+use result::{Error, Result};
 
 // Standard imports.
 use std::collections::HashMap;
@@ -60,19 +65,27 @@ pub struct Undo {
 
 	/// The `Expression`s to restore when applying the Undo.
 	saved: HashMap<Address, Option<Expression>>,
+
+	/// The `CellId`s to reinstate for removed cells when they are restored.
+	ids: HashMap<Address, CellId>,
+
+	/// The cell provenance to restore when applying the Undo.
+	provenance: HashMap<Address, Option<Provenance>>,
 }
 
 
 impl Undo {
 	/// Creates a new Undo operation.
 	#[inline]
-	fn new() -> Undo {
+	pub(crate) fn new() -> Undo {
 		Undo {
 			undoing: OperationInfo {
-				name: "Undo",
+				name: "Undo".into(),
 				details: None,
 			},
 			saved: Default::default(),
+			ids: Default::default(),
+			provenance: Default::default(),
 		}
 	}
 
@@ -84,6 +97,19 @@ impl Undo {
 		Undo {
 			undoing: operation.info(),
 			saved: Default::default(),
+			ids: Default::default(),
+			provenance: Default::default(),
+		}
+	}
+
+	/// Creates a new Undo operation for the operation with the given info.
+	#[inline]
+	pub(crate) fn for_info(undoing: OperationInfo) -> Undo {
+		Undo {
+			undoing: undoing,
+			saved: Default::default(),
+			ids: Default::default(),
+			provenance: Default::default(),
 		}
 	}
 
@@ -95,13 +121,38 @@ impl Undo {
 		}
 	}
 
+	/// Records the removal of a cell with the given `CellId`, to be reversed
+	/// by the Undo operation. The identifier is reinstated when the cell is
+	/// restored, so references to the cell remain valid.
+	pub fn record_removal(
+		&mut self,
+		address: Address,
+		element: Expression,
+		id: Option<CellId>)
+	{
+		if self.saved.get(&address).map_or(true, |e| !e.is_none()) {
+			self.saved.insert(address, Some(element));
+			if let Some(id) = id { self.ids.insert(address, id); }
+		}
+	}
+
+	/// Records the provenance of a cell to be restored by the Undo operation.
+	/// Only the first provenance recorded for each address is kept.
+	pub(crate) fn record_provenance(
+		&mut self,
+		address: Address,
+		provenance: Option<Provenance>)
+	{
+		self.provenance.entry(address).or_insert(provenance);
+	}
+
 }
 
 
 impl PaletteOperation for Undo {
 	fn info(&self) -> OperationInfo {
 		OperationInfo {
-			name: "Undo",
+			name: "Undo".into(),
 			details: Some(format!("{:?}", self))
 		}
 	}
@@ -109,40 +160,73 @@ impl PaletteOperation for Undo {
 	fn apply(&mut self, data: &mut Data) -> Result<HistoryEntry> {
 		let mut redo = Undo::new();
 
-		let saved = mem::replace(&mut self.saved, HashMap::new());
-
-		for (address, item) in saved {
-			match (item.is_some(), data.cell(address).is_some()) {
-
-				(true, true) => { // The cell was modified.
-					let elem = item.unwrap();
-					let cell = data.cell(address).unwrap();
-					let cur = mem::replace(&mut *cell.borrow_mut(), elem);
-					redo.record(address, Some(cur));
-					continue;
-				},
-
-				(true, false) => { // The cell was deleted.
-					let elem = item.unwrap();
-					let cell = data.create_cell(address).unwrap();
-					mem::replace(&mut *cell.borrow_mut(), elem);
-					redo.record(address, None);
-					continue;
-				},
-
-				(false, true) => { // The cell was added.
-					let cur = data.remove_cell(address)?;
-					redo.record(address, Some(cur));
-					continue;
-				},
-
-				_ => panic!("null entry in Undo operation")
+		// The saved cells are kept until every one of them is restored, so
+		// that a failed undo leaves both the data and the entry unchanged.
+		for (&address, &item) in &self.saved {
+			let id = self.ids.get(&address).cloned();
+			if let Err(error) = restore(data, address, item, id, &mut redo) {
+				let _ = redo.apply(data);
+				return Err(error);
 			}
 		}
+		self.saved.clear();
+		self.ids.clear();
+
+		for (address, provenance) in self.provenance.drain() {
+			let reference = Reference::from(address);
+			let current = data.metadata
+				.get(&reference)
+				.and_then(|meta| meta.provenance.clone());
+			if provenance.is_some() || current.is_some() {
+				data.metadata_mut()
+					.entry(reference)
+					.or_insert_with(Default::default)
+					.provenance = provenance;
+			}
+			redo.record_provenance(address, current);
+		}
 
 		Ok(HistoryEntry {
 			info: self.info(),
 			undo: Box::new(redo),
+			conflicts: Vec::new(),
 		})
 	}
 }
+
+
+/// Restores the given element to the given address, recording the change in
+/// the given redo operation.
+fn restore(
+	data: &mut Data,
+	address: Address,
+	item: Option<Expression>,
+	id: Option<CellId>,
+	redo: &mut Undo)
+	-> Result<()>
+{
+	match (item, data.cell(address).is_some()) {
+		(Some(elem), true) => { // The cell was modified.
+			let cell = data.cell_mut(address).unwrap();
+			let cur = mem::replace(&mut *cell.borrow_mut(), elem);
+			redo.record(address, Some(cur));
+		},
+
+		(Some(elem), false) => { // The cell was deleted.
+			*data.create_cell(address)?.borrow_mut() = elem;
+			if let Some(id) = id {
+				data.reinstate_id(address, id);
+			}
+			redo.record(address, None);
+		},
+
+		(None, true) => { // The cell was added.
+			let id = data.cell_id(address);
+			let cur = data.remove_cell(address)?;
+			redo.record_removal(address, cur, id);
+		},
+
+		(None, false) => return Err(Error::EmptyAddress(address)),
+	}
+	Ok(())
+}
@@ -0,0 +1,204 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Defines operations for generating variations of existing colors.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use address::{
+	Address,
+	Selection,
+};
+use data::Data;
+use expression::Expression;
+use generate::Rng;
+use operation::{
+	set_target_or_skip,
+	ConflictPolicy,
+	HistoryEntry,
+	OperationInfo,
+	PaletteOperation,
+	Undo,
+};
+use result::{
+	Error,
+	Result,
+};
+use space::{
+	GamutMapping,
+	Hsl,
+	RgbSpace,
+	WideColor,
+};
+use utilities::clamped;
+
+// Non-local imports.
+use interval::Interval;
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// InsertVariations
+////////////////////////////////////////////////////////////////////////////////
+/// Inserts seeded variations of the selected colors, each on a new page.
+///
+/// Each variation jitters the hue, saturation, and lightness of every selected
+/// color by amounts sampled from the configured intervals. The variations keep
+/// the line and column of their source colors, so each page can be compared
+/// side by side with the original. The same seed always produces the same 
+/// variations.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::operation::*;
+///
+/// let mut pal = Palette::new("Example", Format::Default, true);
+/// pal.apply(Box::new(InsertColor::new(Color::new(200, 80, 40)))).unwrap();
+/// pal.apply(Box::new(InsertColor::new(Color::new(40, 80, 200)))).unwrap();
+///
+/// let variations = InsertVariations::new(Selection::all(), 3, 42);
+/// pal.apply(Box::new(variations)).unwrap();
+///
+/// assert_eq!(pal.len(), 8);
+/// assert!(pal.color(Address::new(3, 0, 1)).is_some());
+/// let first = pal.color(Address::new(1, 0, 0));
+/// assert!(first.is_some() && first != pal.color(Address::new(2, 0, 0)));
+/// ```
+#[derive(Debug, Clone)]
+pub struct InsertVariations {
+	/// The colors to vary.
+	selection: Selection,
+	/// The number of variations to generate.
+	count: usize,
+	/// The generator seed.
+	seed: u64,
+	/// The range of hue offsets, in degrees.
+	hue: Interval<f32>,
+	/// The range of saturation offsets.
+	saturation: Interval<f32>,
+	/// The range of lightness offsets.
+	lightness: Interval<f32>,
+	/// The policy for cells which cannot be changed.
+	policy: ConflictPolicy,
+}
+
+
+impl InsertVariations {
+	/// Creates a new InsertVariations operation producing the given number of
+	/// variations of the selected colors from the given seed.
+	pub fn new(selection: Selection, count: usize, seed: u64) 
+		-> InsertVariations
+	{
+		InsertVariations {
+			selection: selection,
+			count: count,
+			seed: seed,
+			hue: Interval::closed(-10.0, 10.0),
+			saturation: Interval::closed(-0.1, 0.1),
+			lightness: Interval::closed(-0.05, 0.05),
+			policy: ConflictPolicy::default(),
+		}
+	}
+
+	/// Sets the range of hue offsets, in degrees.
+	pub fn hue_jitter(mut self, hue: Interval<f32>) -> InsertVariations {
+		self.hue = hue;
+		self
+	}
+
+	/// Sets the range of saturation offsets.
+	pub fn saturation_jitter(mut self, saturation: Interval<f32>) 
+		-> InsertVariations
+	{
+		self.saturation = saturation;
+		self
+	}
+
+	/// Sets the range of lightness offsets.
+	pub fn lightness_jitter(mut self, lightness: Interval<f32>) 
+		-> InsertVariations
+	{
+		self.lightness = lightness;
+		self
+	}
+
+	/// Sets the policy for cells which cannot be changed.
+	pub fn conflict_policy(mut self, policy: ConflictPolicy) 
+		-> InsertVariations
+	{
+		self.policy = policy;
+		self
+	}
+}
+
+
+impl PaletteOperation for InsertVariations {
+	fn info(&self) -> OperationInfo {
+		OperationInfo {
+			name: "Insert Variations".into(),
+			details: Some(format!("{:?}", self))
+		}
+	}
+
+	fn apply(&mut self, data: &mut Data) -> Result<HistoryEntry> {
+		let colors = data.wide_colors(&self.selection, RgbSpace::Srgb);
+		let first_page = data.cells
+			.keys()
+			.map(|address| address.page)
+			.max()
+			.map_or(Some(0), |page| page.checked_add(1));
+
+		let mut rng = Rng::new(self.seed);
+		let mut undo = Undo::new_for(self);
+		let mut conflicts = Vec::new();
+		for variation in 0..self.count {
+			let page = first_page
+				.and_then(|page| page.checked_add(variation as u16))
+				.ok_or(Error::MaxCellLimitExceeded)?;
+
+			for &(address, color) in &colors {
+				let srgb = color.to_srgb(GamutMapping::default());
+				let mut hsl = Hsl::from_rgb([srgb.r, srgb.g, srgb.b]);
+				hsl.h += rng.sample(&self.hue);
+				hsl.s = clamped(hsl.s + rng.sample(&self.saturation), 0.0, 1.0);
+				hsl.l = clamped(hsl.l + rng.sample(&self.lightness), 0.0, 1.0);
+
+				let target = Address::new(page, address.line, address.column);
+				let [r, g, b] = hsl.to_rgb();
+				let varied = Expression::Wide(WideColor::srgb(r, g, b));
+				set_target_or_skip(data, target, varied, &mut undo, 
+					&self.policy, &mut conflicts)?;
+			}
+		}
+
+		Ok(HistoryEntry {
+			info: self.info(),
+			undo: Box::new(undo),
+			conflicts: conflicts,
+		})
+	}
+}
@@ -0,0 +1,242 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides screen color sampling for eyedropper-style color picking.
+//!
+//! This module is only available when the `picker` feature is enabled. The
+//! sampling backend is chosen by platform: X11 on unix-like systems and GDI on
+//! Windows. Other platforms will return a `ScreenSampleFailed` error.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use address::Address;
+use data::Data;
+use operation::{
+	HistoryEntry,
+	InsertColor,
+	OperationInfo,
+	PaletteOperation,
+};
+use result::{
+	Error,
+	Result,
+};
+
+// Non-local imports.
+use color::Color;
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// ScreenSampler
+////////////////////////////////////////////////////////////////////////////////
+/// Provides a method for reading colors from the screen.
+pub trait ScreenSampler {
+	/// Returns the color of the screen pixel at the given coordinates.
+	///
+	/// # Errors
+	///
+	/// Returns a `ScreenSampleFailed` error if the screen could not be read or
+	/// the coordinates lie outside of it.
+	fn sample(&self, x: i32, y: i32) -> Result<Color>;
+}
+
+
+/// Returns the color of the screen pixel at the given coordinates using the
+/// platform's default sampling backend.
+pub fn sample(x: i32, y: i32) -> Result<Color> {
+	PlatformSampler.sample(x, y)
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// PlatformSampler
+////////////////////////////////////////////////////////////////////////////////
+/// The default `ScreenSampler` for the current platform.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PlatformSampler;
+
+
+#[cfg(all(unix, not(target_os = "macos")))]
+impl ScreenSampler for PlatformSampler {
+	fn sample(&self, x: i32, y: i32) -> Result<Color> {
+		use x11::xlib;
+		use std::ptr;
+
+		if x < 0 || y < 0 { return Err(Error::ScreenSampleFailed); }
+
+		unsafe {
+			let display = xlib::XOpenDisplay(ptr::null());
+			if display.is_null() { return Err(Error::ScreenSampleFailed); }
+
+			let root = xlib::XDefaultRootWindow(display);
+			let image = xlib::XGetImage(
+				display,
+				root,
+				x, y,
+				1, 1,
+				!0, // AllPlanes
+				xlib::ZPixmap
+			);
+			if image.is_null() {
+				xlib::XCloseDisplay(display);
+				return Err(Error::ScreenSampleFailed);
+			}
+
+			let pixel = xlib::XGetPixel(image, 0, 0) as u64;
+			let color = Color::new(
+				masked_channel(pixel, (*image).red_mask as u64),
+				masked_channel(pixel, (*image).green_mask as u64),
+				masked_channel(pixel, (*image).blue_mask as u64)
+			);
+			xlib::XDestroyImage(image);
+			xlib::XCloseDisplay(display);
+
+			Ok(color)
+		}
+	}
+}
+
+
+/// Returns the channel of the given pixel selected by the given mask, scaled
+/// to 8 bits. The masks of an X image describe its visual's channel layout.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn masked_channel(pixel: u64, mask: u64) -> u8 {
+	if mask == 0 { return 0; }
+	let shift = mask.trailing_zeros();
+	let max = mask >> shift;
+	let value = (pixel & mask) >> shift;
+	((value * 255 + max / 2) / max) as u8
+}
+
+
+#[cfg(windows)]
+impl ScreenSampler for PlatformSampler {
+	fn sample(&self, x: i32, y: i32) -> Result<Color> {
+		use winapi::um::winuser::{GetDC, ReleaseDC};
+		use winapi::um::wingdi::{GetPixel, CLR_INVALID};
+		use std::ptr;
+
+		unsafe {
+			let dc = GetDC(ptr::null_mut());
+			if dc.is_null() { return Err(Error::ScreenSampleFailed); }
+
+			let pixel = GetPixel(dc, x, y);
+			ReleaseDC(ptr::null_mut(), dc);
+			if pixel == CLR_INVALID { return Err(Error::ScreenSampleFailed); }
+
+			// COLORREF values are stored as 0x00BBGGRR.
+			Ok(Color::new(
+				(pixel & 0xFF) as u8,
+				((pixel >> 8) & 0xFF) as u8,
+				((pixel >> 16) & 0xFF) as u8
+			))
+		}
+	}
+}
+
+
+#[cfg(not(any(windows, all(unix, not(target_os = "macos")))))]
+impl ScreenSampler for PlatformSampler {
+	#[allow(unused_variables)]
+	fn sample(&self, x: i32, y: i32) -> Result<Color> {
+		Err(Error::ScreenSampleFailed)
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// PickColor
+////////////////////////////////////////////////////////////////////////////////
+/// Samples a color from the screen and inserts it into the palette.
+///
+/// The screen is sampled when the operation is applied. Undoing the operation
+/// removes the inserted color, and redoing it restores the originally sampled
+/// color rather than sampling the screen again.
+#[derive(Debug, Clone, Copy)]
+pub struct PickColor {
+	/// The screen x coordinate to sample.
+	x: i32,
+	/// The screen y coordinate to sample.
+	y: i32,
+	/// The location to place the color.
+	location: Option<Address>,
+	/// Whether to overwrite existing cells when generating new ones.
+	overwrite: bool,
+}
+
+
+impl PickColor {
+	/// Creates a new PickColor operation sampling the given screen coordinates.
+	#[inline]
+	pub fn new(x: i32, y: i32) -> PickColor {
+		PickColor {
+			x: x,
+			y: y,
+			location: None,
+			overwrite: false,
+		}
+	}
+
+	/// Sets the location to place the color.
+	pub fn located_at(mut self, location: Address) -> PickColor {
+		self.location = Some(location);
+		self
+	}
+
+	/// Configures the operation to overwrite existing cells when inserted.
+	pub fn overwrite(mut self, overwrite: bool) -> PickColor {
+		self.overwrite = overwrite;
+		self
+	}
+}
+
+
+impl PaletteOperation for PickColor {
+	fn info(&self) -> OperationInfo {
+		OperationInfo {
+			name: "Pick Color".into(),
+			details: Some(format!("{:?}", self))
+		}
+	}
+
+	fn apply(&mut self, data: &mut Data) -> Result<HistoryEntry> {
+		let color = sample(self.x, self.y)?;
+
+		let mut insert = InsertColor::new(color).overwrite(self.overwrite);
+		if let Some(address) = self.location {
+			insert = insert.located_at(address);
+		}
+
+		let entry = insert.apply(data)?;
+		Ok(HistoryEntry {
+			info: self.info(),
+			undo: entry.undo,
+			conflicts: entry.conflicts,
+		})
+	}
+}
@@ -0,0 +1,270 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides built-in scientific and cartographic palette presets.
+//!
+//! Sequential and diverging presets are defined by a small set of control
+//! colors, and can be resampled into ramps of any length by interpolating
+//! between them in Lab space. Qualitative presets are fixed sets of colors.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use space::Lab;
+
+// Non-local imports.
+use color::Color;
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// PresetKind
+////////////////////////////////////////////////////////////////////////////////
+/// The kind of data a `Preset` is designed to represent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PresetKind {
+	/// Ordered data ranging from low to high.
+	Sequential,
+	/// Ordered data diverging from a meaningful midpoint.
+	Diverging,
+	/// Unordered categorical data.
+	Qualitative,
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Preset
+////////////////////////////////////////////////////////////////////////////////
+/// A built-in palette preset.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::Color;
+/// use palette::preset::Preset;
+///
+/// let ramp = Preset::Viridis.ramp(5);
+///
+/// assert_eq!(ramp.len(), 5);
+/// assert_eq!(ramp[0], Color::new(0x44, 0x01, 0x54));
+/// assert_eq!(ramp[4], Color::new(0xFD, 0xE7, 0x25));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Preset {
+	/// The matplotlib viridis colormap.
+	Viridis,
+	/// The matplotlib magma colormap.
+	Magma,
+	/// The matplotlib inferno colormap.
+	Inferno,
+	/// The matplotlib plasma colormap.
+	Plasma,
+	/// The cividis colormap, optimized for color vision deficiency.
+	Cividis,
+	/// The ColorBrewer sequential Blues scheme.
+	Blues,
+	/// The ColorBrewer sequential Greys scheme.
+	Greys,
+	/// The ColorBrewer diverging red-blue scheme.
+	RdBu,
+	/// The ColorBrewer diverging brown-blue-green scheme.
+	BrBG,
+	/// The ColorBrewer diverging purple-orange scheme.
+	PuOr,
+	/// The ColorBrewer diverging spectral scheme.
+	Spectral,
+	/// The ColorBrewer qualitative Set1 scheme.
+	Set1,
+	/// The ColorBrewer qualitative Set2 scheme.
+	Set2,
+	/// The ColorBrewer qualitative Dark2 scheme.
+	Dark2,
+}
+
+
+/// Every available preset.
+pub const PRESETS: [Preset; 14] = [
+	Preset::Viridis, Preset::Magma, Preset::Inferno, Preset::Plasma,
+	Preset::Cividis, Preset::Blues, Preset::Greys, Preset::RdBu, Preset::BrBG,
+	Preset::PuOr, Preset::Spectral, Preset::Set1, Preset::Set2, Preset::Dark2,
+];
+
+
+impl Preset {
+	/// Returns the preset's name.
+	pub fn name(&self) -> &'static str {
+		match *self {
+			Preset::Viridis  => "viridis",
+			Preset::Magma    => "magma",
+			Preset::Inferno  => "inferno",
+			Preset::Plasma   => "plasma",
+			Preset::Cividis  => "cividis",
+			Preset::Blues    => "Blues",
+			Preset::Greys    => "Greys",
+			Preset::RdBu     => "RdBu",
+			Preset::BrBG     => "BrBG",
+			Preset::PuOr     => "PuOr",
+			Preset::Spectral => "Spectral",
+			Preset::Set1     => "Set1",
+			Preset::Set2     => "Set2",
+			Preset::Dark2    => "Dark2",
+		}
+	}
+
+	/// Returns the preset with the given name, ignoring case.
+	pub fn from_name(name: &str) -> Option<Preset> {
+		PRESETS.iter()
+			.find(|p| p.name().eq_ignore_ascii_case(name))
+			.cloned()
+	}
+
+	/// Returns the kind of data the preset is designed for.
+	pub fn kind(&self) -> PresetKind {
+		match *self {
+			Preset::Viridis | Preset::Magma | Preset::Inferno |
+			Preset::Plasma | Preset::Cividis | Preset::Blues |
+			Preset::Greys
+				=> PresetKind::Sequential,
+
+			Preset::RdBu | Preset::BrBG | Preset::PuOr | Preset::Spectral
+				=> PresetKind::Diverging,
+
+			Preset::Set1 | Preset::Set2 | Preset::Dark2
+				=> PresetKind::Qualitative,
+		}
+	}
+
+	/// Returns the control colors defining the preset.
+	pub fn control_colors(&self) -> Vec<Color> {
+		self.stops().iter().map(|&hex| hex_color(hex)).collect()
+	}
+
+	/// Returns a ramp of the given length built from the preset.
+	///
+	/// Sequential and diverging presets are resampled evenly between their
+	/// first and last control colors. Qualitative presets return their colors
+	/// in order, repeating from the beginning if more colors are requested
+	/// than the preset defines.
+	pub fn ramp(&self, len: usize) -> Vec<Color> {
+		let controls = self.control_colors();
+		if self.kind() == PresetKind::Qualitative {
+			return controls.iter().cycle().take(len).cloned().collect();
+		}
+
+		(0..len)
+			.map(|i| {
+				let t = if len > 1 { i as f32 / (len - 1) as f32 } else { 0.0 };
+				sample_stops(&controls, t)
+			})
+			.collect()
+	}
+
+	/// Returns the hex values of the preset's control colors.
+	fn stops(&self) -> &'static [u32] {
+		match *self {
+			Preset::Viridis => &[
+				0x440154, 0x482878, 0x3E4A89, 0x31688E, 0x26828E,
+				0x1F9E89, 0x35B779, 0x6DCD59, 0xB4DE2C, 0xFDE725,
+			],
+			Preset::Magma => &[
+				0x000004, 0x180F3D, 0x440F76, 0x721F81, 0x9E2F7F,
+				0xCD4071, 0xF1605D, 0xFD9668, 0xFEC98D, 0xFCFDBF,
+			],
+			Preset::Inferno => &[
+				0x000004, 0x1B0C41, 0x4A0C6B, 0x781C6D, 0xA52C60,
+				0xCF4446, 0xED6925, 0xFB9B06, 0xF7D13D, 0xFCFFA4,
+			],
+			Preset::Plasma => &[
+				0x0D0887, 0x46039F, 0x7201A8, 0x9C179E, 0xBD3786,
+				0xD8576B, 0xED7953, 0xFB9F3A, 0xFDCA26, 0xF0F921,
+			],
+			Preset::Cividis => &[
+				0x00204D, 0x00336F, 0x39486B, 0x575C6D, 0x707173,
+				0x8A8779, 0xA69D75, 0xC4B56C, 0xE4CF5B, 0xFFEA46,
+			],
+			Preset::Blues => &[
+				0xF7FBFF, 0xDEEBF7, 0xC6DBEF, 0x9ECAE1, 0x6BAED6,
+				0x4292C6, 0x2171B5, 0x08519C, 0x08306B,
+			],
+			Preset::Greys => &[
+				0xFFFFFF, 0xF0F0F0, 0xD9D9D9, 0xBDBDBD, 0x969696,
+				0x737373, 0x525252, 0x252525, 0x000000,
+			],
+			Preset::RdBu => &[
+				0x67001F, 0xB2182B, 0xD6604D, 0xF4A582, 0xFDDBC7, 0xF7F7F7,
+				0xD1E5F0, 0x92C5DE, 0x4393C3, 0x2166AC, 0x053061,
+			],
+			Preset::BrBG => &[
+				0x543005, 0x8C510A, 0xBF812D, 0xDFC27D, 0xF6E8C3, 0xF5F5F5,
+				0xC7EAE5, 0x80CDC1, 0x35978F, 0x01665E, 0x003C30,
+			],
+			Preset::PuOr => &[
+				0x7F3B08, 0xB35806, 0xE08214, 0xFDB863, 0xFEE0B6, 0xF7F7F7,
+				0xD8DAEB, 0xB2ABD2, 0x8073AC, 0x542788, 0x2D004B,
+			],
+			Preset::Spectral => &[
+				0x9E0142, 0xD53E4F, 0xF46D43, 0xFDAE61, 0xFEE08B, 0xFFFFBF,
+				0xE6F598, 0xABDDA4, 0x66C2A5, 0x3288BD, 0x5E4FA2,
+			],
+			Preset::Set1 => &[
+				0xE41A1C, 0x377EB8, 0x4DAF4A, 0x984EA3, 0xFF7F00,
+				0xFFFF33, 0xA65628, 0xF781BF, 0x999999,
+			],
+			Preset::Set2 => &[
+				0x66C2A5, 0xFC8D62, 0x8DA0CB, 0xE78AC3, 0xA6D854,
+				0xFFD92F, 0xE5C494, 0xB3B3B3,
+			],
+			Preset::Dark2 => &[
+				0x1B9E77, 0xD95F02, 0x7570B3, 0xE7298A, 0x66A61E,
+				0xE6AB02, 0xA6761D, 0x666666,
+			],
+		}
+	}
+}
+
+
+/// Returns the `Color` for the given 0xRRGGBB value.
+fn hex_color(hex: u32) -> Color {
+	Color::new((hex >> 16) as u8, (hex >> 8) as u8, hex as u8)
+}
+
+/// Samples the piecewise-linear curve through the given stops at the given
+/// ratio.
+fn sample_stops(stops: &[Color], t: f32) -> Color {
+	let segments = stops.len() - 1;
+	let position = t * segments as f32;
+	let index = (position as usize).min(segments - 1);
+	let local = position - index as f32;
+
+	// Return the exact control color at the ends to avoid rounding drift.
+	if local <= 0.0 {
+		stops[index]
+	} else if local >= 1.0 {
+		stops[index + 1]
+	} else {
+		let (a, b) = (Lab::from(stops[index]), Lab::from(stops[index + 1]));
+		Color::from(a.lerp(&b, local))
+	}
+}
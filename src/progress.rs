@@ -0,0 +1,211 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides progress reporting and cancellation for long-running tasks.
+//!
+//! Expensive tasks, such as image quantization, constrained palette 
+//! generation, and pairwise checks of large palettes, have `_monitored` 
+//! variants accepting a [`ProgressMonitor`]. The monitor's callback is called
+//! periodically with the task's [`Progress`], and the task stops with a 
+//! `Cancelled` error soon after its [`CancelToken`] is cancelled, which may 
+//! be done from another thread.
+//!
+//! [`ProgressMonitor`]: struct.ProgressMonitor.html
+//! [`Progress`]: struct.Progress.html
+//! [`CancelToken`]: struct.CancelToken.html
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use result::{
+	Error,
+	Result,
+};
+
+// Standard imports.
+use std::fmt;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::sync::atomic::{
+	AtomicBool,
+	Ordering,
+};
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Progress
+////////////////////////////////////////////////////////////////////////////////
+/// The progress of a task through one of its stages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Progress {
+	/// The name of the current stage of the task.
+	pub stage: &'static str,
+	/// The number of steps of the stage completed.
+	pub completed: usize,
+	/// The total number of steps in the stage.
+	pub total: usize,
+}
+
+
+impl Progress {
+	/// Returns the completed fraction of the stage, in the range [0, 1].
+	pub fn fraction(&self) -> f32 {
+		if self.total == 0 {
+			1.0
+		} else {
+			(self.completed as f32 / self.total as f32).min(1.0)
+		}
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// CancelToken
+////////////////////////////////////////////////////////////////////////////////
+/// A shared flag used to cancel a task. Clones of a token share the flag, so
+/// a task can be cancelled from another thread.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+	/// Whether the task has been cancelled.
+	cancelled: Arc<AtomicBool>,
+}
+
+
+impl CancelToken {
+	/// Creates a new, uncancelled `CancelToken`.
+	pub fn new() -> Self {
+		Default::default()
+	}
+
+	/// Cancels the tasks using the token.
+	pub fn cancel(&self) {
+		self.cancelled.store(true, Ordering::SeqCst);
+	}
+
+	/// Returns true if the token has been cancelled.
+	pub fn is_cancelled(&self) -> bool {
+		self.cancelled.load(Ordering::SeqCst)
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// ProgressMonitor
+////////////////////////////////////////////////////////////////////////////////
+/// Receives the progress of a task and determines whether it continues.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::progress::{CancelToken, ProgressMonitor};
+/// use palette::quantize::Quantizer;
+/// use std::cell::Cell;
+/// use std::rc::Rc;
+///
+/// let pixels: Vec<_> = (0..=255).map(|v| Color::new(v, v, 0)).collect();
+///
+/// let reports = Rc::new(Cell::new(0));
+/// let counter = reports.clone();
+/// let monitor = ProgressMonitor::new()
+/// 	.on_progress(move |_| counter.set(counter.get() + 1));
+/// let colors = Quantizer::new(4)
+/// 	.quantize_monitored(&pixels, None, &monitor)
+/// 	.unwrap();
+/// assert_eq!(colors.len(), 4);
+/// assert!(reports.get() > 0);
+///
+/// let token = CancelToken::new();
+/// token.cancel();
+/// let monitor = ProgressMonitor::new().cancel_token(token);
+/// assert!(Quantizer::new(4)
+/// 	.quantize_monitored(&pixels, None, &monitor)
+/// 	.is_err());
+/// ```
+#[derive(Clone, Default)]
+pub struct ProgressMonitor {
+	/// The function called with each progress report.
+	callback: Option<Rc<Fn(Progress)>>,
+	/// The token used to cancel the task.
+	token: Option<CancelToken>,
+}
+
+
+impl ProgressMonitor {
+	/// Creates a new `ProgressMonitor` which ignores progress and never 
+	/// cancels.
+	pub fn new() -> Self {
+		Default::default()
+	}
+
+	/// Sets the function called with each progress report.
+	pub fn on_progress<F>(mut self, callback: F) -> Self 
+		where F: Fn(Progress) + 'static
+	{
+		self.callback = Some(Rc::new(callback));
+		self
+	}
+
+	/// Sets the token used to cancel the task.
+	pub fn cancel_token(mut self, token: CancelToken) -> Self {
+		self.token = Some(token);
+		self
+	}
+
+	/// Returns a `Cancelled` error if the task has been cancelled.
+	pub fn check(&self) -> Result<()> {
+		match self.token {
+			Some(ref token) if token.is_cancelled() => Err(Error::Cancelled),
+			_ => Ok(()),
+		}
+	}
+
+	/// Reports the progress of the given stage, then returns a `Cancelled` 
+	/// error if the task has been cancelled.
+	pub fn report(&self, stage: &'static str, completed: usize, total: usize)
+		-> Result<()>
+	{
+		if let Some(ref callback) = self.callback {
+			callback(Progress {
+				stage: stage,
+				completed: completed,
+				total: total,
+			});
+		}
+		self.check()
+	}
+}
+
+
+impl fmt::Debug for ProgressMonitor {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("ProgressMonitor")
+			.field("callback", &self.callback.as_ref().map(|_| ".."))
+			.field("token", &self.token)
+			.finish()
+	}
+}
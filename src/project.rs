@@ -0,0 +1,722 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides a workspace model for groups of related palettes.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use ::Palette;
+use address::Address;
+use format::default;
+use operation::InsertColor;
+use result::{
+	Error,
+	Result,
+};
+use space::relative_luminance;
+use utilities::{
+	hex,
+	parse_hex,
+};
+
+// Non-local imports.
+use color::Color;
+use serde_json::Value;
+
+// Standard imports.
+use std::collections::{
+	BTreeMap,
+	BTreeSet,
+};
+use std::collections::btree_map;
+use std::fmt;
+use std::io;
+use std::io::{
+	BufRead,
+	BufReader,
+};
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// CellRef
+////////////////////////////////////////////////////////////////////////////////
+/// Identifies a cell within one of a project's palettes.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CellRef {
+	/// The key of the palette containing the cell.
+	pub palette: String,
+	/// The address of the cell within the palette.
+	pub address: Address,
+}
+
+
+impl CellRef {
+	/// Creates a new `CellRef`.
+	pub fn new<S>(palette: S, address: Address) -> Self 
+		where S: Into<String>
+	{
+		CellRef {palette: palette.into(), address: address}
+	}
+}
+
+
+impl fmt::Display for CellRef {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}:{}", self.palette, self.address)
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// ColorSource
+////////////////////////////////////////////////////////////////////////////////
+/// The source of a cross-palette color reference.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ColorSource {
+	/// A shared named color of the project.
+	Shared(String),
+	/// A cell in one of the project's palettes.
+	Cell(CellRef),
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// SyncReport
+////////////////////////////////////////////////////////////////////////////////
+/// A cell updated by a project sync.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyncUpdate {
+	/// The referencing cell which was updated.
+	pub target: CellRef,
+	/// The color of the cell before the sync, if any.
+	pub before: Option<Color>,
+	/// The color of the cell after the sync.
+	pub after: Color,
+}
+
+
+/// Describes the results of a project sync.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SyncReport {
+	/// The cells whose colors were changed.
+	pub updated: Vec<SyncUpdate>,
+	/// The referencing cells whose sources could not be resolved, either 
+	/// because the source is empty or because the references form a cycle.
+	pub unresolved: Vec<CellRef>,
+	/// The referencing cells which could not be changed, with the reason for
+	/// each failure.
+	pub failed: Vec<(CellRef, String)>,
+}
+
+
+impl SyncReport {
+	/// Returns whether the sync changed no cells.
+	pub fn is_empty(&self) -> bool {
+		self.updated.is_empty()
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// DependencyGraph
+////////////////////////////////////////////////////////////////////////////////
+/// A shared color or cell participating in a project's references.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependencyNode {
+	/// The shared color or cell the node represents.
+	pub source: ColorSource,
+	/// The resolved color of the node, or None if it is empty or its 
+	/// references form a cycle.
+	pub color: Option<Color>,
+}
+
+
+impl DependencyNode {
+	/// Returns a unique identifier for the node.
+	pub fn id(&self) -> String {
+		match self.source {
+			ColorSource::Shared(ref name) => format!("shared:{}", name),
+			ColorSource::Cell(ref cell) => format!("cell:{}", cell),
+		}
+	}
+
+	/// Returns a readable label for the node.
+	pub fn label(&self) -> String {
+		match self.source {
+			ColorSource::Shared(ref name) => name.clone(),
+			ColorSource::Cell(ref cell) => format!("{}", cell),
+		}
+	}
+}
+
+
+/// The graph of color derivations between a project's shared colors and 
+/// cells, for visualizing and debugging reference networks.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::operation::*;
+/// use palette::project::*;
+///
+/// let mut ui = Palette::new("UI", Format::Default, true);
+/// ui.apply(Box::new(InsertColor::new(Color::new(0, 0, 0)))).unwrap();
+/// ui.apply(Box::new(InsertColor::new(Color::new(0, 0, 0)))).unwrap();
+///
+/// let mut project = Project::new("Game");
+/// project.add_palette("ui", ui);
+/// project.set_shared("accent", Color::new(200, 40, 40));
+///
+/// let button = CellRef::new("ui", Address::new(0, 0, 0));
+/// let border = CellRef::new("ui", Address::new(0, 0, 1));
+/// project.link(button.clone(), ColorSource::Shared("accent".into()))
+/// 	.unwrap();
+/// project.link(border, ColorSource::Cell(button)).unwrap();
+///
+/// let graph = project.dependency_graph();
+/// assert_eq!(graph.nodes.len(), 3);
+/// assert_eq!(graph.edges.len(), 2);
+///
+/// let dot = graph.to_dot();
+/// assert!(dot.contains("\"shared:accent\" -> \"cell:ui:0:0:0\""));
+///
+/// let json = graph.to_json();
+/// assert_eq!(json["nodes"][1]["id"], "cell:ui:0:0:0");
+/// assert_eq!(json["nodes"][1]["source"], "shared:accent");
+/// assert_eq!(json["nodes"][1]["dependents"][0], "cell:ui:0:0:1");
+/// assert_eq!(json["nodes"][2]["color"], "#C82828");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct DependencyGraph {
+	/// The nodes of the graph, ordered by source.
+	pub nodes: Vec<DependencyNode>,
+	/// The edges of the graph, as indices into the nodes, each running from 
+	/// a source to the cell referencing it.
+	pub edges: Vec<(usize, usize)>,
+}
+
+
+impl DependencyGraph {
+	/// Returns the indices of the nodes directly referencing the given node.
+	pub fn dependents(&self, index: usize) -> Vec<usize> {
+		self.edges.iter()
+			.filter(|&&(from, _)| from == index)
+			.map(|&(_, to)| to)
+			.collect()
+	}
+
+	/// Returns the index of the node the given node references, if any.
+	pub fn source_of(&self, index: usize) -> Option<usize> {
+		self.edges.iter()
+			.find(|&&(_, to)| to == index)
+			.map(|&(from, _)| from)
+	}
+
+	/// Returns the graph in the Graphviz DOT language. Nodes are filled with
+	/// their resolved colors, and unresolved nodes are drawn dashed.
+	pub fn to_dot(&self) -> String {
+		let mut dot = String::from("digraph dependencies {\n");
+		dot.push_str("\trankdir=LR;\n");
+		dot.push_str("\tnode [style=filled, fillcolor=white];\n");
+		for node in &self.nodes {
+			let shape = match node.source {
+				ColorSource::Shared(_) => "ellipse",
+				ColorSource::Cell(_) => "box",
+			};
+			let style = match node.color {
+				Some(color) => format!(
+					"fillcolor=\"{}\", fontcolor=\"{}\"",
+					hex(color),
+					if relative_luminance(color) > 0.179 { "black" } 
+					else { "white" }),
+				None => "style=\"filled,dashed\"".into(),
+			};
+			dot.push_str(&format!("\t{} [label={}, shape={}, {}];\n",
+				default::quote(&node.id()),
+				default::quote(&node.label()),
+				shape,
+				style));
+		}
+		for &(from, to) in &self.edges {
+			dot.push_str(&format!("\t{} -> {};\n",
+				default::quote(&self.nodes[from].id()),
+				default::quote(&self.nodes[to].id())));
+		}
+		dot.push_str("}\n");
+		dot
+	}
+
+	/// Returns the graph as a JSON adjacency list. Each node records its 
+	/// kind, resolved color, the node it references, and the nodes 
+	/// referencing it.
+	pub fn to_json(&self) -> Value {
+		let nodes: Vec<Value> = self.nodes.iter().enumerate()
+			.map(|(index, node)| {
+				let (kind, palette, address) = match node.source {
+					ColorSource::Shared(_) => ("shared", None, None),
+					ColorSource::Cell(ref cell) => ("cell", 
+						Some(cell.palette.clone()), 
+						Some(format!("{}", cell.address))),
+				};
+				json!({
+					"id": node.id(),
+					"label": node.label(),
+					"kind": kind,
+					"palette": palette,
+					"address": address,
+					"color": node.color.map(hex),
+					"source": self.source_of(index)
+						.map(|from| self.nodes[from].id()),
+					"dependents": self.dependents(index).into_iter()
+						.map(|to| self.nodes[to].id())
+						.collect::<Vec<_>>(),
+				})
+			})
+			.collect();
+		json!({ "nodes": nodes })
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Project
+////////////////////////////////////////////////////////////////////////////////
+/// A collection of related palettes with shared named colors and 
+/// cross-palette color references, stored together as a single workspace.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::operation::*;
+/// use palette::project::*;
+///
+/// let mut ui = Palette::new("UI", Format::Default, true);
+/// ui.apply(Box::new(InsertColor::new(Color::new(0, 0, 0)))).unwrap();
+///
+/// let mut project = Project::new("Game");
+/// project.add_palette("ui", ui);
+/// project.set_shared("accent", Color::new(200, 40, 40));
+/// project.link(
+/// 	CellRef::new("ui", Address::new(0, 0, 0)),
+/// 	ColorSource::Shared("accent".into())).unwrap();
+///
+/// let mut buf = Vec::new();
+/// project.write(&mut buf).unwrap();
+/// let read = Project::read(&mut &buf[..]).unwrap();
+///
+/// assert_eq!(read.shared("accent"), Some(Color::new(200, 40, 40)));
+/// assert_eq!(read.palette("ui").unwrap().len(), 1);
+/// assert_eq!(read.links().count(), 1);
+/// ```
+pub struct Project {
+	/// The name of the project.
+	name: String,
+	/// The palettes of the project, by key.
+	palettes: BTreeMap<String, Palette>,
+	/// The shared named colors of the project.
+	shared: BTreeMap<String, Color>,
+	/// The cross-palette color references, by referencing cell.
+	links: BTreeMap<CellRef, ColorSource>,
+}
+
+
+impl Project {
+	/// Creates a new, empty `Project` with the given name.
+	pub fn new<S>(name: S) -> Self 
+		where S: Into<String>
+	{
+		Project {
+			name: name.into(),
+			palettes: BTreeMap::new(),
+			shared: BTreeMap::new(),
+			links: BTreeMap::new(),
+		}
+	}
+
+	/// Returns the name of the project.
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	/// Adds a palette to the project under the given key, returning the 
+	/// palette it replaces, if any.
+	pub fn add_palette<S>(&mut self, key: S, palette: Palette) 
+		-> Option<Palette>
+		where S: Into<String>
+	{
+		self.palettes.insert(key.into(), palette)
+	}
+
+	/// Removes the palette with the given key from the project, along with 
+	/// any references to or from its cells.
+	pub fn remove_palette(&mut self, key: &str) -> Option<Palette> {
+		let removed = self.palettes.remove(key);
+		if removed.is_some() {
+			self.links.retain(|target, source| target.palette != key && 
+				match *source {
+					ColorSource::Cell(ref cell) => cell.palette != key,
+					ColorSource::Shared(_) => true,
+				});
+		}
+		removed
+	}
+
+	/// Returns the palette with the given key.
+	pub fn palette(&self, key: &str) -> Option<&Palette> {
+		self.palettes.get(key)
+	}
+
+	/// Returns the palette with the given key for modification.
+	pub fn palette_mut(&mut self, key: &str) -> Option<&mut Palette> {
+		self.palettes.get_mut(key)
+	}
+
+	/// Returns an iterator over the project's palettes and their keys.
+	pub fn palettes<'a>(&'a self) -> btree_map::Iter<'a, String, Palette> {
+		self.palettes.iter()
+	}
+
+	/// Returns the shared color with the given name.
+	pub fn shared(&self, name: &str) -> Option<Color> {
+		self.shared.get(name).cloned()
+	}
+
+	/// Sets the shared color with the given name, returning its previous 
+	/// value, if any.
+	pub fn set_shared<S>(&mut self, name: S, color: Color) -> Option<Color>
+		where S: Into<String>
+	{
+		self.shared.insert(name.into(), color)
+	}
+
+	/// Removes the shared color with the given name, along with any 
+	/// references to it.
+	pub fn remove_shared(&mut self, name: &str) -> Option<Color> {
+		let removed = self.shared.remove(name);
+		if removed.is_some() {
+			self.links.retain(|_, source| 
+				*source != ColorSource::Shared(name.into()));
+		}
+		removed
+	}
+
+	/// Returns an iterator over the project's shared colors and their names.
+	pub fn shared_colors<'a>(&'a self) -> btree_map::Iter<'a, String, Color> {
+		self.shared.iter()
+	}
+
+	/// Makes the target cell reference the given color source, returning the
+	/// source it previously referenced, if any.
+	///
+	/// # Errors
+	///
+	/// Returns `Error::UndefinedName` if the referenced palettes or shared 
+	/// color do not exist.
+	pub fn link(&mut self, target: CellRef, source: ColorSource) 
+		-> Result<Option<ColorSource>>
+	{
+		self.check_palette(&target.palette)?;
+		match source {
+			ColorSource::Cell(ref cell) => self.check_palette(&cell.palette)?,
+			ColorSource::Shared(ref name) => if !self.shared.contains_key(name) {
+				return Err(Error::UndefinedName(name.clone()));
+			},
+		}
+		Ok(self.links.insert(target, source))
+	}
+
+	/// Removes the reference held by the given cell, returning its source.
+	pub fn unlink(&mut self, target: &CellRef) -> Option<ColorSource> {
+		self.links.remove(target)
+	}
+
+	/// Returns the color source referenced by the given cell, if any.
+	pub fn source_of(&self, target: &CellRef) -> Option<&ColorSource> {
+		self.links.get(target)
+	}
+
+	/// Returns an iterator over the project's cross-palette references.
+	pub fn links<'a>(&'a self) -> btree_map::Iter<'a, CellRef, ColorSource> {
+		self.links.iter()
+	}
+
+	/// Returns the color of the given cell, if it exists.
+	pub fn color(&self, cell: &CellRef) -> Option<Color> {
+		self.palettes.get(&cell.palette)
+			.and_then(|palette| palette.color(cell.address))
+	}
+
+	/// Resolves the color of the given source, following references through
+	/// any intermediate cells. Returns None if the source is empty or the
+	/// references form a cycle.
+	pub fn resolve(&self, source: &ColorSource) -> Option<Color> {
+		let mut visited = BTreeSet::new();
+		let mut source = source;
+		loop {
+			match *source {
+				ColorSource::Shared(ref name) => return self.shared(name),
+				ColorSource::Cell(ref cell) => {
+					if !visited.insert(cell) { return None; }
+					match self.links.get(cell) {
+						Some(next) => source = next,
+						None => return self.color(cell),
+					}
+				},
+			}
+		}
+	}
+
+	/// Returns the graph of color derivations formed by the project's 
+	/// references.
+	pub fn dependency_graph(&self) -> DependencyGraph {
+		let mut sources = BTreeSet::new();
+		for (target, source) in &self.links {
+			sources.insert(ColorSource::Cell(target.clone()));
+			sources.insert(source.clone());
+		}
+
+		let nodes: Vec<_> = sources.into_iter()
+			.map(|source| DependencyNode {
+				color: self.resolve(&source),
+				source: source,
+			})
+			.collect();
+		let index_of = |source: &ColorSource| nodes.iter()
+			.position(|node| node.source == *source)
+			.expect("link node in graph");
+		let edges = self.links.iter()
+			.map(|(target, source)| (
+				index_of(source),
+				index_of(&ColorSource::Cell(target.clone()))))
+			.collect();
+
+		DependencyGraph { nodes: nodes, edges: edges }
+	}
+
+	/// Propagates the colors of all referenced sources into the cells 
+	/// referencing them. Each change is applied as an operation on the 
+	/// referencing palette, so it can be undone there. A change which fails 
+	/// is recorded in the report, and the remaining changes are still made.
+	///
+	/// # Example
+	///
+	/// ```rust
+	/// use palette::*;
+	/// use palette::operation::*;
+	/// use palette::project::*;
+	///
+	/// let mut character = Palette::new("Character", Format::Default, true);
+	/// character.apply(Box::new(InsertColor::new(Color::new(255, 200, 170))))
+	/// 	.unwrap();
+	/// let mut ui = Palette::new("UI", Format::Default, true);
+	/// ui.apply(Box::new(InsertColor::new(Color::new(0, 0, 0)))).unwrap();
+	///
+	/// let mut project = Project::new("Game");
+	/// project.add_palette("character", character);
+	/// project.add_palette("ui", ui);
+	///
+	/// let skin = CellRef::new("character", Address::new(0, 0, 0));
+	/// let icon = CellRef::new("ui", Address::new(0, 0, 0));
+	/// project.link(icon.clone(), ColorSource::Cell(skin)).unwrap();
+	///
+	/// let report = project.sync();
+	/// assert_eq!(report.updated.len(), 1);
+	/// assert!(report.failed.is_empty());
+	/// assert_eq!(project.color(&icon), Some(Color::new(255, 200, 170)));
+	///
+	/// // A second sync has nothing to do.
+	/// assert!(project.sync().is_empty());
+	/// ```
+	pub fn sync(&mut self) -> SyncReport {
+		let mut report = SyncReport::default();
+
+		// Sources are resolved through the reference chains, so the updates 
+		// do not depend on each other and can be computed up front.
+		let pending: Vec<_> = self.links.iter()
+			.map(|(target, source)| (target.clone(), self.resolve(source)))
+			.collect();
+
+		for (target, resolved) in pending {
+			let after = match resolved {
+				Some(color) => color,
+				None => { report.unresolved.push(target); continue; },
+			};
+			let before = self.color(&target);
+			if before == Some(after) { continue; }
+
+			let applied = match self.palettes.get_mut(&target.palette) {
+				Some(palette) => palette
+					.apply(Box::new(InsertColor::new(after)
+						.located_at(target.address)
+						.overwrite(true)))
+					.map(|_| ()),
+				None => Err(Error::UndefinedName(target.palette.clone())),
+			};
+			match applied {
+				Ok(()) => report.updated.push(SyncUpdate {
+					target: target,
+					before: before,
+					after: after,
+				}),
+				Err(error) => report.failed.push((target, error.to_string())),
+			}
+		}
+		report
+	}
+
+	/// Returns an error if no palette has the given key.
+	fn check_palette(&self, key: &str) -> Result<()> {
+		if self.palettes.contains_key(key) {
+			Ok(())
+		} else {
+			Err(Error::UndefinedName(key.into()))
+		}
+	}
+
+	/// Writes the project to the given buffer as a workspace file.
+	pub fn write<W>(&self, out_buf: &mut W) -> io::Result<()> 
+		where W: io::Write
+	{
+		writeln!(out_buf, "project {}", default::quote(&self.name))?;
+		for (name, &color) in &self.shared {
+			writeln!(out_buf, "shared {} {}", default::quote(name), hex(color))?;
+		}
+		for (target, source) in &self.links {
+			write!(out_buf, "link {} {} ", 
+				default::quote(&target.palette),
+				target.address)?;
+			match *source {
+				ColorSource::Shared(ref name) => writeln!(out_buf, 
+					"shared {}",
+					default::quote(name))?,
+				ColorSource::Cell(ref cell) => writeln!(out_buf, 
+					"cell {} {}",
+					default::quote(&cell.palette),
+					cell.address)?,
+			}
+		}
+		for (key, palette) in &self.palettes {
+			writeln!(out_buf, "begin {}", default::quote(key))?;
+			default::write_palette(palette, out_buf)?;
+			writeln!(out_buf, "end")?;
+		}
+		Ok(())
+	}
+
+	/// Reads a project from the given workspace file buffer.
+	pub fn read<R>(in_buf: &mut R) -> io::Result<Project> 
+		where R: io::Read
+	{
+		let mut project = Project::new("");
+		let mut links = Vec::new();
+		// The key and text of the palette section being read, if any.
+		let mut section: Option<(String, String)> = None;
+
+		for (i, line) in BufReader::new(in_buf).lines().enumerate() {
+			let line = line?;
+			let tokens = default::tokenize(&line)
+				.map_err(|e| default::invalid(i, e))?;
+			let first = tokens.first().map(|t| &t[..]);
+
+			if let Some((key, mut text)) = section.take() {
+				if first == Some("end") {
+					let palette = default::read_palette(&mut text.as_bytes())?;
+					project.palettes.insert(key, palette);
+				} else {
+					text.push_str(&line);
+					text.push('\n');
+					section = Some((key, text));
+				}
+				continue;
+			}
+
+			let entry = match first {
+				Some("project") => default::token(&tokens, 1)
+					.map(|name| project.name = name),
+				Some("shared") => read_shared(&tokens)
+					.map(|(name, color)| { project.shared.insert(name, color); }),
+				Some("link") => read_link(&tokens)
+					.map(|(target, source)| links.push((i, target, source))),
+				// Keep line numbering intact for palette errors.
+				Some("begin") => default::token(&tokens, 1)
+					.map(|key| section = Some((key, "\n".repeat(i + 1)))),
+				Some(other) => Err(format!("unknown entry '{}'", other)),
+				None => Ok(()),
+			};
+			entry.map_err(|e| default::invalid(i, e))?;
+		}
+
+		if section.is_some() {
+			return Err(io::Error::new(
+				io::ErrorKind::UnexpectedEof,
+				"unterminated palette section"));
+		}
+
+		for (i, target, source) in links {
+			project.link(target, source)
+				.map_err(|e| default::invalid(i, format!("{}", e)))?;
+		}
+		Ok(project)
+	}
+}
+
+
+/// Parses a shared color entry.
+fn read_shared(tokens: &[String]) 
+	-> ::std::result::Result<(String, Color), String>
+{
+	let color = default::token(tokens, 2)?;
+	Ok((
+		default::token(tokens, 1)?,
+		parse_hex(&color)
+			.ok_or_else(|| format!("invalid color '{}'", color))?,
+	))
+}
+
+
+/// Parses a link entry.
+fn read_link(tokens: &[String]) 
+	-> ::std::result::Result<(CellRef, ColorSource), String>
+{
+	let target = CellRef::new(
+		default::token(tokens, 1)?,
+		default::parse_address(&default::token(tokens, 2)?)?);
+
+	let source = match &default::token(tokens, 3)?[..] {
+		"shared" => ColorSource::Shared(default::token(tokens, 4)?),
+		"cell" => ColorSource::Cell(CellRef::new(
+			default::token(tokens, 4)?,
+			default::parse_address(&default::token(tokens, 5)?)?)),
+		other => return Err(format!("unknown color source '{}'", other)),
+	};
+	Ok((target, source))
+}
@@ -0,0 +1,138 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides `Provenance`, which records where the color of a cell came from.
+//!
+//! Provenance is stored in the metadata of each cell, so it follows the cell
+//! when it is moved and is saved with the palette. Operations applied to a
+//! `Palette` record themselves as the provenance of each cell they color, 
+//! and importers and quantizers record the source of the cells they create.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Standard imports.
+use std::fmt;
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Provenance
+////////////////////////////////////////////////////////////////////////////////
+/// The origin of the color of a cell.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::operation::InsertColor;
+/// use palette::provenance::Provenance;
+///
+/// let mut pal = Palette::new("Example", Format::Default, true);
+/// pal.apply(Box::new(InsertColor::new(Color::new(1, 2, 3)))).unwrap();
+/// pal.apply(Box::new(InsertColor::new(Color::new(4, 5, 6)))).unwrap();
+///
+/// let second = pal.provenance(Address::new(0, 0, 1)).unwrap();
+/// assert_eq!(second, &Provenance::Operation {
+/// 	id: 2,
+/// 	name: "Insert Color".to_owned(),
+/// });
+/// assert_eq!(second.to_string(), "operation 2 (Insert Color)");
+///
+/// pal.set_provenance(Address::new(0, 0, 0), Provenance::Manual);
+/// assert_eq!(pal.provenance(Address::new(0, 0, 0)), 
+/// 	Some(&Provenance::Manual));
+///
+/// // Undoing an operation restores the provenance it replaced.
+/// pal.apply(Box::new(InsertColor::new(Color::new(7, 8, 9))
+/// 	.located_at(Address::new(0, 0, 1))
+/// 	.overwrite(true))).unwrap();
+/// pal.undo().unwrap();
+/// assert_eq!(pal.provenance(Address::new(0, 0, 1)).unwrap().to_string(),
+/// 	"operation 2 (Insert Color)");
+/// pal.redo().unwrap();
+/// assert_eq!(pal.provenance(Address::new(0, 0, 1)).unwrap().to_string(),
+/// 	"operation 3 (Insert Color)");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Provenance {
+	/// The color was imported from a file.
+	Import {
+		/// The name of the import format.
+		format: String,
+		/// The name or path of the imported file.
+		source: String,
+	},
+	/// The color was produced by quantizing an image.
+	Quantized {
+		/// The name of the quantized image.
+		source: String,
+	},
+	/// The color was evaluated from an expression.
+	Expression(String),
+	/// The color was edited by hand.
+	Manual,
+	/// The color was set by an operation applied to the palette.
+	Operation {
+		/// The sequence number of the operation within the palette.
+		id: u64,
+		/// The name of the operation.
+		name: String,
+	},
+}
+
+
+impl Provenance {
+	/// Returns the name of the kind of provenance.
+	pub fn kind(&self) -> &'static str {
+		match *self {
+			Provenance::Import {..}    => "import",
+			Provenance::Quantized {..} => "quantized",
+			Provenance::Expression(_)  => "expression",
+			Provenance::Manual         => "manual",
+			Provenance::Operation {..} => "operation",
+		}
+	}
+
+	/// Returns the names of all kinds of provenance.
+	pub fn kinds() -> &'static [&'static str] {
+		&["import", "quantized", "expression", "manual", "operation"]
+	}
+}
+
+
+impl fmt::Display for Provenance {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			Provenance::Import {ref format, ref source} 
+				=> write!(f, "import {} ({})", source, format),
+			Provenance::Quantized {ref source} 
+				=> write!(f, "quantized {}", source),
+			Provenance::Expression(ref text) 
+				=> write!(f, "expression {}", text),
+			Provenance::Manual => write!(f, "manual"),
+			Provenance::Operation {id, ref name} 
+				=> write!(f, "operation {} ({})", id, name),
+		}
+	}
+}
@@ -0,0 +1,546 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides color quantization for building palettes from images, and for
+//! remapping images onto palettes.
+//!
+//! Images are provided as slices of pixel colors, so that any image decoder
+//! can be used to supply them.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use ::Palette;
+use expression::Expression;
+use format::Format;
+use progress::ProgressMonitor;
+use provenance::Provenance;
+use result::Result;
+use space::{
+	colors_to_oklab,
+	nearest_oklab,
+	Oklab,
+	unit_channels,
+};
+
+// Non-local imports.
+use color::Color;
+
+// Standard imports.
+use std::collections::BTreeMap;
+
+
+/// The number of pixels sampled between progress reports.
+const PIXELS_PER_REPORT: usize = 1 << 16;
+
+
+////////////////////////////////////////////////////////////////////////////////
+// QuantizedColor
+////////////////////////////////////////////////////////////////////////////////
+/// A color produced by quantizing an image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuantizedColor {
+	/// The representative color.
+	pub color: Color,
+	/// The number of image pixels nearest to the color.
+	pub usage_count: usize,
+}
+
+
+/// A distinct image color, with its statistics.
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+	/// The color in Oklab space.
+	lab: Oklab,
+	/// The number of pixels with the color.
+	count: usize,
+	/// The clustering weight of the color.
+	weight: f32,
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Quantizer
+////////////////////////////////////////////////////////////////////////////////
+/// Reduces the colors of an image to a fixed number of representative colors
+/// using weighted k-means clustering in Oklab space.
+///
+/// By default, each distinct color is weighted by the number of pixels using
+/// it, so that large areas of the image are represented more accurately than
+/// small details. An importance mask can be given to emphasize or ignore 
+/// regions of the image.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::quantize::Quantizer;
+///
+/// let red = Color::new(200, 30, 30);
+/// let blue = Color::new(30, 30, 200);
+/// let mut pixels = vec![red; 90];
+/// pixels.extend(vec![blue; 10]);
+///
+/// let pal = Quantizer::new(2).palette("Image", &pixels, None).unwrap();
+///
+/// assert_eq!(pal.color(Address::new(0, 0, 0)), Some(red));
+/// assert_eq!(pal.usage_count(Address::new(0, 0, 0)), Some(90));
+/// assert_eq!(pal.usage_count(Address::new(0, 0, 1)), Some(10));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Quantizer {
+	/// The number of colors to produce.
+	count: usize,
+	/// Whether to weight colors by their pixel frequency.
+	frequency_weighting: bool,
+	/// The maximum number of clustering iterations.
+	iterations: usize,
+}
+
+
+impl Quantizer {
+	/// Creates a new `Quantizer` producing the given number of colors.
+	pub fn new(count: usize) -> Self {
+		Quantizer {
+			count: count,
+			frequency_weighting: true,
+			iterations: 16,
+		}
+	}
+
+	/// Sets whether colors are weighted by the number of pixels using them. 
+	/// If disabled, each distinct color is weighted equally.
+	pub fn frequency_weighting(mut self, frequency_weighting: bool) -> Self {
+		self.frequency_weighting = frequency_weighting;
+		self
+	}
+
+	/// Sets the maximum number of clustering iterations.
+	pub fn iterations(mut self, iterations: usize) -> Self {
+		self.iterations = iterations;
+		self
+	}
+
+	/// Quantizes the given pixels, returning the representative colors 
+	/// ordered from most to least used.
+	///
+	/// If a mask is given, each pixel's weight is multiplied by the 
+	/// corresponding mask value. Pixels with a mask value of zero do not 
+	/// influence the resulting colors, but are still counted in their usage.
+	/// Pixels beyond the end of the mask have a mask value of one.
+	pub fn quantize(&self, pixels: &[Color], mask: Option<&[f32]>) 
+		-> Vec<QuantizedColor>
+	{
+		self.quantize_monitored(pixels, mask, &ProgressMonitor::new())
+			.expect("unmonitored quantization cannot be cancelled")
+	}
+
+	/// Quantizes the given pixels like [`quantize`], reporting progress to
+	/// the given monitor.
+	///
+	/// # Errors
+	///
+	/// Returns a `Cancelled` error if the monitor's task is cancelled.
+	///
+	/// [`quantize`]: #method.quantize
+	pub fn quantize_monitored(
+		&self,
+		pixels: &[Color],
+		mask: Option<&[f32]>,
+		monitor: &ProgressMonitor)
+		-> Result<Vec<QuantizedColor>>
+	{
+		let samples = self.samples(pixels, mask, monitor)?;
+		let centers = self.cluster(&samples, monitor)?;
+
+		let mut usage = vec![0; centers.len()];
+		for sample in &samples {
+			if let Some(i) = sample.lab.nearest(&centers) {
+				usage[i] += sample.count;
+			}
+		}
+
+		let mut colors: Vec<_> = centers.into_iter()
+			.zip(usage)
+			.map(|(lab, usage_count)| QuantizedColor {
+				color: Color::from(lab),
+				usage_count: usage_count,
+			})
+			.collect();
+		colors.sort_by(|a, b| b.usage_count.cmp(&a.usage_count));
+		Ok(colors)
+	}
+
+	/// Quantizes the given pixels and returns a palette containing the 
+	/// resulting colors, ordered from most to least used, with their usage
+	/// counts stored in the cell metadata.
+	pub fn palette<S>(&self, name: S, pixels: &[Color], mask: Option<&[f32]>)
+		-> Result<Palette>
+		where S: Into<String>
+	{
+		let mut palette = Palette::new(name, Format::Default, true);
+		let source = palette.name().unwrap_or("").to_owned();
+		for quantized in self.quantize(pixels, mask) {
+			let address = {
+				let data = &mut palette.data;
+				let address = data.first_free_address_after(Default::default())?;
+				*data.create_cell(address)?.borrow_mut() 
+					= Expression::Color(quantized.color);
+				address
+			};
+			palette.set_usage_count(address, quantized.usage_count);
+			palette.set_provenance(address, Provenance::Quantized {
+				source: source.clone(),
+			});
+		}
+		Ok(palette)
+	}
+
+	/// Collects the distinct colors of the given pixels.
+	fn samples(
+		&self,
+		pixels: &[Color],
+		mask: Option<&[f32]>,
+		monitor: &ProgressMonitor)
+		-> Result<Vec<Sample>>
+	{
+		let mut histogram = BTreeMap::new();
+		for (i, &pixel) in pixels.iter().enumerate() {
+			if i % PIXELS_PER_REPORT == 0 {
+				monitor.report("sampling", i, pixels.len())?;
+			}
+			let importance = mask
+				.and_then(|mask| mask.get(i))
+				.map_or(1.0, |&m| m.max(0.0));
+			let entry = histogram
+				.entry((pixel.red(), pixel.green(), pixel.blue()))
+				.or_insert((0, 0.0));
+			entry.0 += 1;
+			entry.1 += importance;
+		}
+
+		let colors: Vec<Color> = histogram.keys()
+			.map(|&(r, g, b)| Color::new(r, g, b))
+			.collect();
+		let mut labs = vec![Oklab::default(); colors.len()];
+		colors_to_oklab(&colors, &mut labs);
+
+		Ok(histogram.values()
+			.zip(labs)
+			.map(|(&(count, weight), lab)| Sample {
+				lab: lab,
+				count: count,
+				weight: if self.frequency_weighting {
+					weight
+				} else {
+					weight / count as f32
+				},
+			})
+			.collect())
+	}
+
+	/// Returns the cluster centers for the given samples.
+	fn cluster(&self, samples: &[Sample], monitor: &ProgressMonitor) 
+		-> Result<Vec<Oklab>>
+	{
+		let weighted: Vec<_> = samples.iter()
+			.filter(|s| s.weight > 0.0)
+			.collect();
+		if weighted.len() <= self.count {
+			return Ok(weighted.iter().map(|s| s.lab).collect());
+		}
+
+		// Choose initial centers deterministically, preferring heavy samples 
+		// far from the existing centers.
+		let mut centers: Vec<Oklab> = Vec::with_capacity(self.count);
+		while centers.len() < self.count {
+			monitor.report("seeding", centers.len(), self.count)?;
+			let next = weighted.iter()
+				.map(|s| {
+					let d = s.lab.nearest(&centers)
+						.map_or(1.0, |i| s.lab.distance(&centers[i]));
+					(s.lab, s.weight * d * d)
+				})
+				.fold(None, |best: Option<(Oklab, f32)>, (lab, score)| 
+					match best {
+						Some((_, bs)) if bs >= score => best,
+						_ => Some((lab, score)),
+					})
+				.map(|(lab, _)| lab);
+			match next {
+				Some(lab) => centers.push(lab),
+				None => break,
+			}
+		}
+
+		for iteration in 0..self.iterations {
+			monitor.report("clustering", iteration, self.iterations)?;
+			let mut sums = vec![(0.0, 0.0, 0.0, 0.0); centers.len()];
+			for s in &weighted {
+				if let Some(i) = s.lab.nearest(&centers) {
+					let sum = &mut sums[i];
+					sum.0 += s.lab.l * s.weight;
+					sum.1 += s.lab.a * s.weight;
+					sum.2 += s.lab.b * s.weight;
+					sum.3 += s.weight;
+				}
+			}
+
+			let mut moved = false;
+			for (center, (l, a, b, w)) in centers.iter_mut().zip(sums) {
+				if w <= 0.0 { continue; }
+				let updated = Oklab::new(l / w, a / w, b / w);
+				moved |= center.distance(&updated) > 1e-5;
+				*center = updated;
+			}
+			if !moved { break; }
+		}
+		Ok(centers)
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Dither
+////////////////////////////////////////////////////////////////////////////////
+/// A dithering method used when remapping images onto a palette.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dither {
+	/// Each pixel is mapped to its nearest palette color.
+	None,
+	/// Floyd-Steinberg error diffusion.
+	FloydSteinberg,
+	/// Atkinson error diffusion. Only three quarters of the error is 
+	/// diffused, which preserves contrast at the expense of detail in
+	/// highlights and shadows.
+	Atkinson,
+	/// Ordered dithering using a 2x2 Bayer matrix.
+	Bayer2,
+	/// Ordered dithering using a 4x4 Bayer matrix.
+	Bayer4,
+	/// Ordered dithering using an 8x8 Bayer matrix.
+	Bayer8,
+}
+
+
+impl Default for Dither {
+	fn default() -> Self {
+		Dither::None
+	}
+}
+
+
+impl Dither {
+	/// Returns the error diffusion kernel of the method as a list of 
+	/// `(dx, dy, weight)` entries, or None if it does not diffuse error.
+	fn kernel(&self) -> Option<&'static [(isize, usize, f32)]> {
+		const FLOYD_STEINBERG: [(isize, usize, f32); 4] = [
+			(1, 0, 7.0 / 16.0),
+			(-1, 1, 3.0 / 16.0),
+			(0, 1, 5.0 / 16.0),
+			(1, 1, 1.0 / 16.0),
+		];
+		const ATKINSON: [(isize, usize, f32); 6] = [
+			(1, 0, 1.0 / 8.0),
+			(2, 0, 1.0 / 8.0),
+			(-1, 1, 1.0 / 8.0),
+			(0, 1, 1.0 / 8.0),
+			(1, 1, 1.0 / 8.0),
+			(0, 2, 1.0 / 8.0),
+		];
+		match *self {
+			Dither::FloydSteinberg => Some(&FLOYD_STEINBERG),
+			Dither::Atkinson => Some(&ATKINSON),
+			_ => None,
+		}
+	}
+
+	/// Returns the size of the method's Bayer matrix, or None if it is not an
+	/// ordered dither.
+	fn bayer_size(&self) -> Option<usize> {
+		match *self {
+			Dither::Bayer2 => Some(2),
+			Dither::Bayer4 => Some(4),
+			Dither::Bayer8 => Some(8),
+			_ => None,
+		}
+	}
+}
+
+
+/// Returns the threshold of the given position within a Bayer matrix of the
+/// given size, in the range [0, 1).
+fn bayer_threshold(x: usize, y: usize, size: usize) -> f32 {
+	fn index(x: usize, y: usize, size: usize) -> usize {
+		if size <= 1 { return 0; }
+		let half = size / 2;
+		let quadrant = [[0, 2], [3, 1]][y / half][x / half];
+		4 * index(x % half, y % half, half) + quadrant
+	}
+	index(x % size, y % size, size) as f32 / (size * size) as f32
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Remap
+////////////////////////////////////////////////////////////////////////////////
+/// Remaps image pixels onto a fixed set of palette colors.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::quantize::{Dither, Remap};
+///
+/// let colors = [Color::new(0, 0, 0), Color::new(255, 255, 255)];
+/// let gray = vec![Color::new(128, 128, 128); 16];
+///
+/// // A flat remap maps every pixel to the same color.
+/// let flat = Remap::new(&colors).remap(&gray, 4);
+/// assert!(flat.iter().all(|&i| i == flat[0]));
+///
+/// // A dithered remap mixes both colors.
+/// let dithered = Remap::new(&colors)
+/// 	.dither(Dither::FloydSteinberg)
+/// 	.remap(&gray, 4);
+/// assert!(dithered.contains(&0) && dithered.contains(&1));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Remap {
+	/// The palette colors.
+	colors: Vec<Color>,
+	/// The palette colors in Oklab space.
+	labs: Vec<Oklab>,
+	/// The dithering method.
+	dither: Dither,
+	/// The dithering strength.
+	strength: f32,
+}
+
+
+impl Remap {
+	/// Creates a new `Remap` onto the given colors.
+	pub fn new(colors: &[Color]) -> Self {
+		let mut labs = vec![Oklab::default(); colors.len()];
+		colors_to_oklab(colors, &mut labs);
+		Remap {
+			colors: colors.to_vec(),
+			labs: labs,
+			dither: Dither::None,
+			strength: 1.0,
+		}
+	}
+
+	/// Sets the dithering method.
+	pub fn dither(mut self, dither: Dither) -> Self {
+		self.dither = dither;
+		self
+	}
+
+	/// Sets the dithering strength. A strength of 0 disables dithering, and a
+	/// strength of 1 applies the full effect.
+	pub fn strength(mut self, strength: f32) -> Self {
+		self.strength = strength.max(0.0);
+		self
+	}
+
+	/// Remaps the given pixels, in row-major order with the given row width,
+	/// returning the index of the chosen color for each pixel. Returns an 
+	/// empty vector if there are no colors to map onto.
+	pub fn remap(&self, pixels: &[Color], width: usize) -> Vec<usize> {
+		if self.colors.is_empty() || width == 0 { return Vec::new(); }
+
+		// Without dithering, each pixel maps independently.
+		if self.dither == Dither::None || self.strength == 0.0 {
+			let mut labs = vec![Oklab::default(); pixels.len()];
+			colors_to_oklab(pixels, &mut labs);
+			let mut indices = vec![0; pixels.len()];
+			nearest_oklab(&labs, &self.labs, &mut indices);
+			return indices;
+		}
+
+		let mut values: Vec<[f32; 3]> = pixels.iter()
+			.map(|&p| {
+				let [r, g, b] = unit_channels(p);
+				[r * 255.0, g * 255.0, b * 255.0]
+			})
+			.collect();
+		let spread = 255.0 / (self.colors.len() as f32).cbrt();
+
+		let mut indices = Vec::with_capacity(pixels.len());
+		for i in 0..values.len() {
+			let (x, y) = (i % width, i / width);
+			let mut value = values[i];
+			if let Some(size) = self.dither.bayer_size() {
+				let offset = (bayer_threshold(x, y, size) - 0.5) 
+					* spread * self.strength;
+				for channel in &mut value { *channel += offset; }
+			}
+
+			let index = self.nearest(value);
+			indices.push(index);
+
+			if let Some(kernel) = self.dither.kernel() {
+				let [r, g, b] = unit_channels(self.colors[index]);
+				let error = [
+					(value[0] - r * 255.0) * self.strength,
+					(value[1] - g * 255.0) * self.strength,
+					(value[2] - b * 255.0) * self.strength,
+				];
+				for &(dx, dy, weight) in kernel {
+					let tx = x as isize + dx;
+					if tx < 0 || tx >= width as isize { continue; }
+					let target = (y + dy) * width + tx as usize;
+					if let Some(v) = values.get_mut(target) {
+						for c in 0..3 { v[c] += error[c] * weight; }
+					}
+				}
+			}
+		}
+		indices
+	}
+
+	/// Remaps the given pixels, in row-major order with the given row width,
+	/// returning the chosen color for each pixel.
+	pub fn remap_colors(&self, pixels: &[Color], width: usize) -> Vec<Color> {
+		self.remap(pixels, width)
+			.into_iter()
+			.map(|i| self.colors[i])
+			.collect()
+	}
+
+	/// Returns the index of the color nearest to the given 8-bit channel 
+	/// values.
+	fn nearest(&self, value: [f32; 3]) -> usize {
+		let channel = |v: f32| v.round().max(0.0).min(255.0) as u8;
+		let color = Color::new(
+			channel(value[0]),
+			channel(value[1]),
+			channel(value[2]));
+		Oklab::from(color).nearest(&self.labs).unwrap_or(0)
+	}
+}
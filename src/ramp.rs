@@ -0,0 +1,683 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides color ramp generation.
+//!
+//! A ramp is a sequence of colors interpolated between two or more control
+//! colors. Interpolation may be performed in several color spaces, and colors
+//! which fall outside of the sRGB gamut are brought back into it using a
+//! configurable `GamutMapping` strategy.
+//!
+//! Interpolation is carried out on `WideColor` values, so ramps generated from
+//! previously generated colors do not accumulate 8-bit rounding error. The
+//! `Color` methods quantize only their final results.
+//!
+//! The saturation and value trajectories of a ramp can be shaped separately
+//! from its hue path using curves. Each curve gives the HSV saturation or 
+//! value of the ramp color at each position along the ramp.
+//!
+//! A `RampFit` recovers the key colors and generator settings reproducing an
+//! existing run of colors, so hand-made ramps can be edited parametrically.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use curve::Curve;
+use space::{
+	delta_e_2000,
+	Cam16Ucs,
+	GamutMapping,
+	Hpluv,
+	Hsl,
+	Hsluv,
+	Hsv,
+	Lab,
+	Oklab,
+	RgbSpace,
+	ViewingConditions,
+	WideColor,
+	Xyz,
+};
+use unit::Quantity;
+use utilities::lerp_f32;
+
+// Non-local imports.
+use color::Color;
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// InterpolationSpace
+////////////////////////////////////////////////////////////////////////////////
+/// The color space in which ramp colors are interpolated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InterpolationSpace {
+	/// Interpolates sRGB channels directly.
+	Rgb,
+	/// Interpolates in HSL, taking the shortest path around the hue circle.
+	Hsl,
+	/// Interpolates in HSLuv, taking the shortest path around the hue circle.
+	Hsluv,
+	/// Interpolates in HPLuv, taking the shortest path around the hue circle.
+	Hpluv,
+	/// Interpolates in CIE L*a*b*.
+	Lab,
+	/// Interpolates in Oklab.
+	Oklab,
+	/// Interpolates in CAM16-UCS, under the generator's viewing conditions.
+	Cam16Ucs,
+}
+
+
+impl InterpolationSpace {
+	/// Returns the name of the color space.
+	pub fn name(&self) -> &'static str {
+		match *self {
+			InterpolationSpace::Rgb   => "rgb",
+			InterpolationSpace::Hsl   => "hsl",
+			InterpolationSpace::Hsluv => "hsluv",
+			InterpolationSpace::Hpluv => "hpluv",
+			InterpolationSpace::Lab   => "lab",
+			InterpolationSpace::Oklab => "oklab",
+			InterpolationSpace::Cam16Ucs => "cam16-ucs",
+		}
+	}
+
+	/// Returns the color space with the given name, ignoring case.
+	pub fn from_name(name: &str) -> Option<InterpolationSpace> {
+		InterpolationSpace::all()
+			.iter()
+			.find(|s| s.name().eq_ignore_ascii_case(name))
+			.cloned()
+	}
+
+	/// Returns all of the interpolation color spaces.
+	pub fn all() -> &'static [InterpolationSpace] {
+		&[
+			InterpolationSpace::Rgb,
+			InterpolationSpace::Hsl,
+			InterpolationSpace::Hsluv,
+			InterpolationSpace::Hpluv,
+			InterpolationSpace::Lab,
+			InterpolationSpace::Oklab,
+			InterpolationSpace::Cam16Ucs,
+		]
+	}
+}
+
+
+impl Default for InterpolationSpace {
+	fn default() -> Self {
+		InterpolationSpace::Rgb
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// RampCoordinate
+////////////////////////////////////////////////////////////////////////////////
+/// A color coordinate along which the colors of a ramp are ordered.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::Color;
+/// use palette::ramp::RampCoordinate;
+///
+/// let ramp = [
+/// 	Color::new(20, 20, 20),
+/// 	Color::new(120, 120, 120),
+/// 	Color::new(220, 220, 220),
+/// ];
+///
+/// let position = RampCoordinate::Lightness
+/// 	.insertion_index(&ramp, Color::new(60, 60, 60));
+/// assert_eq!(position, 1);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RampCoordinate {
+	/// The CIE L*a*b* lightness.
+	Lightness,
+	/// The CIE L*a*b* chroma.
+	Chroma,
+	/// The CIE L*a*b* green-red component.
+	GreenRed,
+	/// The CIE L*a*b* blue-yellow component.
+	BlueYellow,
+	/// The Oklab lightness.
+	OklabLightness,
+}
+
+
+impl RampCoordinate {
+	/// Returns the name of the coordinate.
+	pub fn name(&self) -> &'static str {
+		match *self {
+			RampCoordinate::Lightness      => "lightness",
+			RampCoordinate::Chroma         => "chroma",
+			RampCoordinate::GreenRed       => "green-red",
+			RampCoordinate::BlueYellow     => "blue-yellow",
+			RampCoordinate::OklabLightness => "oklab-lightness",
+		}
+	}
+
+	/// Returns the coordinate with the given name, ignoring case.
+	pub fn from_name(name: &str) -> Option<RampCoordinate> {
+		RampCoordinate::all()
+			.iter()
+			.find(|c| c.name().eq_ignore_ascii_case(name))
+			.cloned()
+	}
+
+	/// Returns all of the ramp coordinates.
+	pub fn all() -> &'static [RampCoordinate] {
+		&[
+			RampCoordinate::Lightness,
+			RampCoordinate::Chroma,
+			RampCoordinate::GreenRed,
+			RampCoordinate::BlueYellow,
+			RampCoordinate::OklabLightness,
+		]
+	}
+
+	/// Returns the value of the coordinate for the given color.
+	pub fn value(&self, color: Color) -> f32 {
+		match *self {
+			RampCoordinate::Lightness      => Lab::from(color).l,
+			RampCoordinate::Chroma         => Lab::from(color).chroma(),
+			RampCoordinate::GreenRed       => Lab::from(color).a,
+			RampCoordinate::BlueYellow     => Lab::from(color).b,
+			RampCoordinate::OklabLightness => Oklab::from(color).l,
+		}
+	}
+
+	/// Returns the index at which the given color should be inserted into the
+	/// given ramp to keep the coordinate running in the ramp's direction. 
+	/// Colors with an equal coordinate are inserted after existing colors.
+	pub fn insertion_index(&self, ramp: &[Color], color: Color) -> usize {
+		if ramp.is_empty() { return 0; }
+
+		let direction = self.value(ramp[ramp.len() - 1]) - self.value(ramp[0]);
+		let direction = if direction < 0.0 { -1.0 } else { 1.0 };
+		let value = self.value(color);
+		ramp.iter()
+			.position(|&c| (self.value(c) - value) * direction > 0.0)
+			.unwrap_or(ramp.len())
+	}
+}
+
+
+impl Default for RampCoordinate {
+	fn default() -> Self {
+		RampCoordinate::Lightness
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// RampGenerator
+////////////////////////////////////////////////////////////////////////////////
+/// Generates ramps of colors between control colors.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::Color;
+/// use palette::ramp::{InterpolationSpace, RampGenerator};
+///
+/// let gen = RampGenerator::new().space(InterpolationSpace::Oklab);
+/// let ramp = gen.ramp(Color::new(0, 0, 0), Color::new(255, 255, 255), 5);
+///
+/// assert_eq!(ramp.len(), 5);
+/// assert_eq!(ramp[0], Color::new(0, 0, 0));
+/// assert_eq!(ramp[4], Color::new(255, 255, 255));
+/// ```
+///
+/// Flattening the saturation of a ramp in its midtones:
+///
+/// ```rust
+/// use palette::Color;
+/// use palette::curve::Curve;
+/// use palette::ramp::{InterpolationSpace, RampGenerator};
+/// use palette::space::Hsv;
+///
+/// let (start, end) = (Color::new(40, 20, 10), Color::new(250, 230, 120));
+/// let gen = RampGenerator::new().space(InterpolationSpace::Hsl);
+/// let (mut saturation, _) = gen.trajectories(&[start.into(), end.into()], 5);
+/// saturation.insert_point(0.25, 0.6);
+/// saturation.insert_point(0.5, 0.6);
+/// saturation.insert_point(0.75, 0.6);
+///
+/// let ramp = gen.saturation_curve(saturation).ramp(start, end, 5);
+/// assert_eq!(ramp[0], start);
+/// assert_eq!(ramp[4], end);
+/// for &color in &ramp[1..4] {
+/// 	assert!((Hsv::from(color).s - 0.6).abs() < 0.01);
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct RampGenerator {
+	/// The interpolation color space.
+	space: InterpolationSpace,
+	/// The strategy for handling out-of-gamut colors.
+	gamut_mapping: GamutMapping,
+	/// The curve giving the saturation along the ramp, if any.
+	saturation: Option<Curve>,
+	/// The curve giving the value along the ramp, if any.
+	value: Option<Curve>,
+	/// The viewing conditions used by appearance-based spaces.
+	viewing_conditions: ViewingConditions,
+}
+
+
+impl RampGenerator {
+	/// Creates a new `RampGenerator` interpolating in RGB space.
+	pub fn new() -> Self {
+		Default::default()
+	}
+
+	/// Sets the interpolation color space.
+	pub fn space(mut self, space: InterpolationSpace) -> Self {
+		self.space = space;
+		self
+	}
+
+	/// Sets the strategy for handling out-of-gamut colors.
+	pub fn gamut_mapping(mut self, gamut_mapping: GamutMapping) -> Self {
+		self.gamut_mapping = gamut_mapping;
+		self
+	}
+
+	/// Sets the viewing conditions under which colors are interpolated in
+	/// CAM16-UCS. The standard sRGB viewing conditions are used by default.
+	///
+	/// # Example
+	///
+	/// ```rust
+	/// use palette::Color;
+	/// use palette::ramp::{InterpolationSpace, RampGenerator};
+	/// use palette::space::{Surround, ViewingConditions};
+	///
+	/// let (start, end) = (Color::new(20, 30, 90), Color::new(250, 220, 120));
+	/// let gen = RampGenerator::new().space(InterpolationSpace::Cam16Ucs);
+	/// let dark = gen.clone()
+	/// 	.viewing(ViewingConditions::SRGB.surround(Surround::Dark));
+	///
+	/// let standard = gen.ramp(start, end, 5);
+	/// let projected = dark.ramp(start, end, 5);
+	/// assert_eq!((standard[0], standard[4]), (start, end));
+	/// assert_eq!((projected[0], projected[4]), (start, end));
+	/// assert!(standard[2] != projected[2]);
+	/// ```
+	pub fn viewing(mut self, conditions: ViewingConditions) -> Self {
+		self.viewing_conditions = conditions;
+		self
+	}
+
+	/// Sets the curve giving the HSV saturation of the colors along the ramp.
+	pub fn saturation_curve(mut self, curve: Curve) -> Self {
+		self.saturation = Some(curve);
+		self
+	}
+
+	/// Sets the curve giving the HSV value of the colors along the ramp.
+	pub fn value_curve(mut self, curve: Curve) -> Self {
+		self.value = Some(curve);
+		self
+	}
+
+	/// Returns the interpolation color space.
+	pub fn interpolation_space(&self) -> InterpolationSpace {
+		self.space
+	}
+
+	/// Returns the strategy for handling out-of-gamut colors.
+	pub fn gamut_mapping_strategy(&self) -> GamutMapping {
+		self.gamut_mapping
+	}
+
+	/// Returns the viewing conditions used by appearance-based spaces.
+	pub fn viewing_conditions(&self) -> ViewingConditions {
+		self.viewing_conditions
+	}
+
+	/// Returns the saturation curve, if any.
+	pub fn saturation(&self) -> Option<&Curve> {
+		self.saturation.as_ref()
+	}
+
+	/// Returns the value curve, if any.
+	pub fn value(&self) -> Option<&Curve> {
+		self.value.as_ref()
+	}
+
+	/// Returns the saturation and value trajectories of the ramp of the given
+	/// length through the given control colors, as curves with a control 
+	/// point for each ramp color. Any curves set on the generator are applied.
+	/// The returned curves can be edited and set on the generator to reshape 
+	/// the ramp.
+	pub fn trajectories(&self, stops: &[WideColor], len: usize) 
+		-> (Curve, Curve)
+	{
+		let ramp = self.multi_stop_wide(stops, len);
+		let positions = ramp.iter()
+			.enumerate()
+			.map(|(i, color)| (position(i, len), Hsv::from_rgb(
+				[color.r, color.g, color.b])));
+		let (mut saturation, mut value) = (Curve::default(), Curve::default());
+		for (t, hsv) in positions {
+			saturation.insert_point(t, hsv.s);
+			value.insert_point(t, hsv.v);
+		}
+		(saturation, value)
+	}
+
+	/// Returns the color located at the ratio given by `amount` between the
+	/// given colors. Plain numbers are read as fractions.
+	pub fn interpolate<Q>(&self, start: Color, end: Color, amount: Q) -> Color 
+		where Q: Into<Quantity>
+	{
+		self.interpolate_wide(start.into(), end.into(), amount.into().as_fraction())
+			.quantize()
+	}
+
+	/// Returns the color located at the ratio given by `amount` between the
+	/// given colors, without quantizing it. The returned color is an in-gamut
+	/// sRGB color.
+	pub fn interpolate_wide(&self, start: WideColor, end: WideColor, amount: f32)
+		-> WideColor
+	{
+		let start = start.to_srgb(self.gamut_mapping);
+		let end = end.to_srgb(self.gamut_mapping);
+
+		match self.space {
+			InterpolationSpace::Rgb => WideColor::new(
+				lerp_f32(start.r, end.r, amount),
+				lerp_f32(start.g, end.g, amount),
+				lerp_f32(start.b, end.b, amount),
+				RgbSpace::Srgb,
+			),
+
+			InterpolationSpace::Hsl => {
+				let s = Hsl::from_rgb([start.r, start.g, start.b]);
+				let e = Hsl::from_rgb([end.r, end.g, end.b]);
+				let mut dh = e.h - s.h;
+				if dh > 180.0 { dh -= 360.0; }
+				if dh < -180.0 { dh += 360.0; }
+				let [r, g, b] = Hsl::new(
+					s.h + dh * amount,
+					lerp_f32(s.s, e.s, amount),
+					lerp_f32(s.l, e.l, amount),
+				).to_rgb();
+				WideColor::new(r, g, b, RgbSpace::Srgb)
+			},
+
+			InterpolationSpace::Hsluv => {
+				let s = Hsluv::from(Xyz::from(start));
+				let e = Hsluv::from(Xyz::from(end));
+				let xyz = Xyz::from(s.lerp(&e, amount));
+				self.gamut_mapping.map_wide(Lab::from(xyz))
+			},
+
+			InterpolationSpace::Hpluv => {
+				let s = Hpluv::from(Xyz::from(start));
+				let e = Hpluv::from(Xyz::from(end));
+				let xyz = Xyz::from(s.lerp(&e, amount));
+				self.gamut_mapping.map_wide(Lab::from(xyz))
+			},
+
+			InterpolationSpace::Lab => {
+				let s = Lab::from(Xyz::from(start));
+				let e = Lab::from(Xyz::from(end));
+				self.gamut_mapping.map_wide(s.lerp(&e, amount))
+			},
+
+			InterpolationSpace::Oklab => {
+				let s = Oklab::from_linear_rgb(start.to_linear());
+				let e = Oklab::from_linear_rgb(end.to_linear());
+				self.gamut_mapping.map_wide(Lab::from(s.lerp(&e, amount)))
+			},
+
+			InterpolationSpace::Cam16Ucs => {
+				let conditions = &self.viewing_conditions;
+				let s = Cam16Ucs::from_xyz(Xyz::from(start), conditions);
+				let e = Cam16Ucs::from_xyz(Xyz::from(end), conditions);
+				let xyz = s.lerp(&e, amount).to_xyz(conditions);
+				self.gamut_mapping.map_wide(Lab::from(xyz))
+			},
+		}
+	}
+
+	/// Returns a ramp of the given length between the given colors. The first
+	/// and last colors of the ramp are the given colors.
+	pub fn ramp(&self, start: Color, end: Color, len: usize) -> Vec<Color> {
+		self.multi_stop(&[start, end], len)
+	}
+
+	/// Returns a ramp of the given length passing through each of the given
+	/// control colors, which are evenly spaced along the ramp.
+	pub fn multi_stop(&self, stops: &[Color], len: usize) -> Vec<Color> {
+		let stops: Vec<WideColor> = stops.iter()
+			.map(|&c| WideColor::from(c))
+			.collect();
+		self.multi_stop_wide(&stops, len)
+			.iter()
+			.map(WideColor::quantize)
+			.collect()
+	}
+
+	/// Returns an unquantized ramp of the given length between the given
+	/// colors. The first and last colors of the ramp are the given colors.
+	pub fn ramp_wide(&self, start: WideColor, end: WideColor, len: usize)
+		-> Vec<WideColor>
+	{
+		self.multi_stop_wide(&[start, end], len)
+	}
+
+	/// Returns an unquantized ramp of the given length passing through each of
+	/// the given control colors, which are evenly spaced along the ramp.
+	pub fn multi_stop_wide(&self, stops: &[WideColor], len: usize)
+		-> Vec<WideColor>
+	{
+		if stops.is_empty() { return Vec::new(); }
+		let mut ramp = vec![WideColor::default(); len];
+		self.fill_wide(stops, &mut ramp);
+		ramp
+	}
+
+	/// Fills the given buffer with an unquantized ramp passing through each of
+	/// the given control colors, which are evenly spaced along the ramp. The
+	/// buffer is left unchanged if there are no control colors.
+	///
+	/// Unlike `multi_stop_wide`, this does not allocate, so a ramp can be 
+	/// regenerated into the same buffer repeatedly while its control colors 
+	/// are edited.
+	///
+	/// # Example
+	///
+	/// ```rust
+	/// use palette::Color;
+	/// use palette::ramp::RampGenerator;
+	/// use palette::space::WideColor;
+	///
+	/// let gen = RampGenerator::new();
+	/// let stops = [WideColor::BLACK, WideColor::WHITE];
+	/// let mut ramp = [WideColor::default(); 5];
+	/// gen.fill_wide(&stops, &mut ramp);
+	///
+	/// assert_eq!(ramp[2].quantize(), Color::new(128, 128, 128));
+	/// assert_eq!(&ramp[..], &gen.multi_stop_wide(&stops, 5)[..]);
+	/// ```
+	pub fn fill_wide(&self, stops: &[WideColor], out: &mut [WideColor]) {
+		if stops.is_empty() { return; }
+		let len = out.len();
+		for (i, color) in out.iter_mut().enumerate() {
+			let t = position(i, len);
+			*color = self.apply_curves(self.sample_stops(stops, t), t);
+		}
+	}
+
+	/// Returns the color at the given position in the range [0, 1] along a 
+	/// gradient through the given evenly spaced control colors.
+	fn sample_stops(&self, stops: &[WideColor], t: f32) -> WideColor {
+		if stops.len() == 1 { return stops[0]; }
+		let last = stops.len() - 1;
+		let at = t.max(0.0).min(1.0) * last as f32;
+		let index = (at.floor() as usize).min(last - 1);
+		match at - index as f32 {
+			local if local <= 0.0 => stops[index],
+			local if local >= 1.0 => stops[index + 1],
+			local => self.interpolate_wide(stops[index], stops[index + 1], local),
+		}
+	}
+
+	/// Applies the saturation and value curves to the given sRGB ramp color
+	/// at the given position along the ramp.
+	fn apply_curves(&self, color: WideColor, t: f32) -> WideColor {
+		if self.saturation.is_none() && self.value.is_none() { return color; }
+
+		let mut hsv = Hsv::from_rgb([color.r, color.g, color.b]);
+		if let Some(ref curve) = self.saturation {
+			hsv.s = curve.evaluate(t);
+		}
+		if let Some(ref curve) = self.value {
+			hsv.v = curve.evaluate(t);
+		}
+		let [r, g, b] = hsv.to_rgb();
+		WideColor::new(r, g, b, RgbSpace::Srgb)
+	}
+}
+
+
+/// Returns the position of the color at the given index along a ramp of the 
+/// given length, in the range [0, 1].
+fn position(index: usize, len: usize) -> f32 {
+	if len > 1 { index as f32 / (len - 1) as f32 } else { 0.0 }
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// RampFit
+////////////////////////////////////////////////////////////////////////////////
+/// The key colors and generator settings reproducing a run of colors.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::Color;
+/// use palette::ramp::{InterpolationSpace, RampFit, RampGenerator};
+///
+/// let gen = RampGenerator::new().space(InterpolationSpace::Oklab);
+/// let stops = [
+/// 	Color::new(20, 10, 40),
+/// 	Color::new(200, 80, 60),
+/// 	Color::new(250, 240, 200),
+/// ];
+/// let run = gen.multi_stop(&stops, 9);
+///
+/// let fit = RampFit::fit(&run, 1.0).unwrap();
+/// assert_eq!(fit.keys.len(), 3);
+/// assert_eq!(fit.generator.interpolation_space(), InterpolationSpace::Oklab);
+/// assert!(fit.error <= 1.0);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RampFit {
+	/// The key colors of the ramp, evenly spaced along it.
+	pub keys: Vec<WideColor>,
+	/// The generator interpolating between the key colors.
+	pub generator: RampGenerator,
+	/// The largest CIEDE2000 difference between the run and the fitted ramp.
+	pub error: f32,
+}
+
+
+impl RampFit {
+	/// Fits the fewest key colors reproducing the given run of colors within
+	/// the given CIEDE2000 tolerance, trying each interpolation space. The key
+	/// colors are sampled from the run at their evenly spaced positions. If
+	/// no smaller fit is found, every color of the run becomes a key. Returns
+	/// `None` if the run is empty.
+	pub fn fit(colors: &[Color], tolerance: f32) -> Option<RampFit> {
+		let len = colors.len();
+		if len < 3 {
+			return colors.first().map(|_| RampFit {
+				keys: colors.iter().map(|&c| WideColor::from(c)).collect(),
+				generator: RampGenerator::new(),
+				error: 0.0,
+			});
+		}
+
+		let mut best: Option<RampFit> = None;
+		for count in 2..(len + 1) {
+			for &space in InterpolationSpace::all() {
+				let fit = RampFit::fit_keys(colors, count, space);
+				if best.as_ref().map_or(true, |b| fit.error < b.error) {
+					best = Some(fit);
+				}
+			}
+			if best.as_ref().map_or(false, |b| b.error <= tolerance) { break; }
+		}
+		best
+	}
+
+	/// Returns the fit of the given number of key colors to the run of colors
+	/// using the given interpolation space.
+	fn fit_keys(colors: &[Color], count: usize, space: InterpolationSpace)
+		-> RampFit
+	{
+		let generator = RampGenerator::new().space(space);
+		let last = (colors.len() - 1) as f32;
+		let keys: Vec<WideColor> = (0..count)
+			.map(|j| {
+				let at = position(j, count) * last;
+				let index = at.floor() as usize;
+				let amount = at - index as f32;
+				if amount <= 0.0 || index + 1 >= colors.len() {
+					WideColor::from(colors[index])
+				} else {
+					generator.interpolate_wide(
+						colors[index].into(),
+						colors[index + 1].into(),
+						amount)
+				}
+			})
+			.collect();
+
+		let error = generator.multi_stop_wide(&keys, colors.len())
+			.iter()
+			.zip(colors)
+			.map(|(fitted, &color)| delta_e_2000(fitted.quantize(), color))
+			.fold(0.0, f32::max);
+
+		RampFit {keys: keys, generator: generator, error: error}
+	}
+
+	/// Returns the fitted ramp of the given length.
+	pub fn ramp(&self, len: usize) -> Vec<WideColor> {
+		self.generator.multi_stop_wide(&self.keys, len)
+	}
+}
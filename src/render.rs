@@ -0,0 +1,365 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides rendering of palette thumbnails and artwork previews.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use address::{
+	Address,
+	Selection,
+};
+use operation::PaletteOperation;
+use result::Result;
+
+// Non-local imports.
+use color::Color;
+
+// Standard imports.
+#[cfg(feature = "images")]
+use std::io;
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// ThumbnailLayout
+////////////////////////////////////////////////////////////////////////////////
+/// Determines how palette cells are arranged in a thumbnail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ThumbnailLayout {
+	/// Each palette line containing colors is drawn as a row, with each cell
+	/// placed according to its column. Ramps stored along lines therefore
+	/// appear as rows.
+	Lines,
+	/// Colors are drawn in address order, wrapping after the given number of
+	/// columns.
+	Grid(usize),
+	/// Colors are drawn in address order in a single row.
+	Strip,
+}
+
+
+impl Default for ThumbnailLayout {
+	fn default() -> Self {
+		ThumbnailLayout::Lines
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Image
+////////////////////////////////////////////////////////////////////////////////
+/// An 8-bit RGBA image.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Image {
+	/// The width of the image in pixels.
+	pub width: usize,
+	/// The height of the image in pixels.
+	pub height: usize,
+	/// The RGBA pixel data, in row-major order.
+	pub pixels: Vec<u8>,
+}
+
+
+impl Image {
+	/// Creates a new fully transparent `Image` of the given size.
+	pub fn new(width: usize, height: usize) -> Self {
+		Image {
+			width: width,
+			height: height,
+			pixels: vec![0; width * height * 4],
+		}
+	}
+
+	/// Returns the color of the pixel at the given coordinates, or None if it
+	/// is transparent or out of bounds.
+	pub fn pixel(&self, x: usize, y: usize) -> Option<Color> {
+		if x >= self.width || y >= self.height { return None; }
+		let i = (y * self.width + x) * 4;
+		let p = &self.pixels[i..i + 4];
+		if p[3] == 0 { None } else { Some(Color::new(p[0], p[1], p[2])) }
+	}
+
+	/// Fills the given rectangle with an opaque color, clipping it to the
+	/// image bounds.
+	pub fn fill(&mut self, x: usize, y: usize, w: usize, h: usize, color: Color) {
+		let rgba = [color.red(), color.green(), color.blue(), 255];
+		for row in y..(y + h).min(self.height) {
+			for col in x..(x + w).min(self.width) {
+				let i = (row * self.width + col) * 4;
+				self.pixels[i..i + 4].copy_from_slice(&rgba);
+			}
+		}
+	}
+
+	/// Copies the given image into this image with its top-left corner at the
+	/// given coordinates, clipping it to the image bounds.
+	pub fn blit(&mut self, source: &Image, x: usize, y: usize) {
+		for row in 0..source.height.min(self.height.saturating_sub(y)) {
+			let columns = source.width.min(self.width.saturating_sub(x));
+			let from = row * source.width * 4;
+			let to = ((y + row) * self.width + x) * 4;
+			self.pixels[to..to + columns * 4]
+				.copy_from_slice(&source.pixels[from..from + columns * 4]);
+		}
+	}
+
+	/// Writes the image to the given buffer as a PNG.
+	#[cfg(feature = "images")]
+	pub fn write_png<W>(&self, out_buf: &mut W) -> io::Result<()>
+		where W: io::Write
+	{
+		::export::lut::write_png(
+			out_buf,
+			self.width as u32,
+			self.height as u32,
+			::png::ColorType::Rgba,
+			&self.pixels)
+	}
+}
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// IndexedImage
+////////////////////////////////////////////////////////////////////////////////
+/// An image whose pixels refer to palette cells, used to preview palettes 
+/// applied to sample artwork.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IndexedImage {
+	/// The width of the image in pixels.
+	pub width: usize,
+	/// The height of the image in pixels.
+	pub height: usize,
+	/// The palette cell of each pixel, in row-major order. Pixels without a 
+	/// cell are transparent.
+	pub pixels: Vec<Option<Address>>,
+}
+
+
+impl IndexedImage {
+	/// Creates a new `IndexedImage` from color indices and a table mapping 
+	/// each index to a palette address. Indices outside of the table are 
+	/// transparent. Indices beyond the last full row are ignored, so an image
+	/// of zero width is empty.
+	pub fn from_indices(width: usize, indices: &[usize], table: &[Address]) 
+		-> Self
+	{
+		let height = if width == 0 { 0 } else { indices.len() / width };
+		IndexedImage {
+			width: width,
+			height: height,
+			pixels: indices.iter()
+				.take(width * height)
+				.map(|&i| table.get(i).cloned())
+				.collect(),
+		}
+	}
+
+	/// Renders the image using the given function to look up cell colors,
+	/// repeating it the given number of times horizontally and vertically so 
+	/// that seams between tiles can be inspected.
+	pub fn render<F>(&self, tiles: (usize, usize), color_of: F) -> Image
+		where F: Fn(Address) -> Option<Color>
+	{
+		let mut tile = Image::new(self.width, self.height);
+		let count = self.width * self.height;
+		for (i, pixel) in self.pixels.iter().take(count).enumerate() {
+			if let Some(color) = pixel.and_then(|address| color_of(address)) {
+				tile.fill(i % self.width, i / self.width, 1, 1, color);
+			}
+		}
+
+		let mut image = Image::new(self.width * tiles.0, self.height * tiles.1);
+		for ty in 0..tiles.1 {
+			for tx in 0..tiles.0 {
+				image.blit(&tile, tx * self.width, ty * self.height);
+			}
+		}
+		image
+	}
+}
+
+
+
+/// Returns the start and end of the `index`th of `count` equal divisions of 
+/// the given length.
+fn span(length: usize, index: usize, count: usize) -> (usize, usize) {
+	(length * index / count, length * (index + 1) / count)
+}
+
+
+/// Renders the given colors into an image of the given size using the given
+/// layout.
+pub fn render_thumbnail(
+	colors: &[(Address, Color)],
+	width: usize,
+	height: usize,
+	layout: ThumbnailLayout)
+	-> Image
+{
+	let mut image = Image::new(width, height);
+	if colors.is_empty() { return image; }
+
+	// Assign each color a row and column.
+	let (cells, rows, columns): (Vec<(usize, usize, Color)>, usize, usize) = 
+		match layout 
+	{
+		ThumbnailLayout::Lines => {
+			let mut lines: Vec<(u16, u8)> = colors.iter()
+				.map(|&(a, _)| (a.page, a.line))
+				.collect();
+			lines.dedup();
+			let cells = colors.iter()
+				.map(|&(a, c)| (
+					lines.iter().position(|&l| l == (a.page, a.line)).unwrap(),
+					a.column as usize,
+					c,
+				))
+				.collect();
+			let columns = colors.iter()
+				.map(|&(a, _)| a.column as usize + 1)
+				.max()
+				.unwrap_or(1);
+			(cells, lines.len(), columns)
+		},
+
+		ThumbnailLayout::Grid(columns) => {
+			let columns = columns.max(1);
+			let cells = colors.iter()
+				.enumerate()
+				.map(|(i, &(_, c))| (i / columns, i % columns, c))
+				.collect();
+			(cells, (colors.len() + columns - 1) / columns, columns)
+		},
+
+		ThumbnailLayout::Strip => {
+			let cells = colors.iter()
+				.enumerate()
+				.map(|(i, &(_, c))| (0, i, c))
+				.collect();
+			(cells, 1, colors.len())
+		},
+	};
+
+	for (row, column, color) in cells {
+		let (x0, x1) = span(width, column, columns);
+		let (y0, y1) = span(height, row, rows);
+		image.fill(x0, y0, x1 - x0, y1 - y0, color);
+	}
+	image
+}
+
+
+impl ::Palette {
+	/// Renders a thumbnail image of the given size summarizing the palette's
+	/// colors.
+	///
+	/// # Example
+	///
+	/// ```rust
+	/// use palette::*;
+	/// use palette::operation::*;
+	/// use palette::render::ThumbnailLayout;
+	///
+	/// let mut pal = Palette::new("Example", Format::Default, true);
+	/// pal.apply(Box::new(InsertColor::new(Color::new(255, 0, 0)))).unwrap();
+	/// pal.apply(Box::new(InsertColor::new(Color::new(0, 0, 255)))).unwrap();
+	///
+	/// let image = pal.render_thumbnail(4, 2, ThumbnailLayout::Lines);
+	/// assert_eq!(image.pixels.len(), 4 * 2 * 4);
+	/// assert_eq!(image.pixel(0, 1), Some(Color::new(255, 0, 0)));
+	/// assert_eq!(image.pixel(3, 0), Some(Color::new(0, 0, 255)));
+	/// ```
+	pub fn render_thumbnail(
+		&self,
+		width: usize,
+		height: usize,
+		layout: ThumbnailLayout)
+		-> Image
+	{
+		render_thumbnail(&self.colors(&Selection::all()), width, height, layout)
+	}
+
+	/// Renders the given artwork using the palette's current colors, tiled 
+	/// the given number of times horizontally and vertically.
+	pub fn render_artwork(&self, artwork: &IndexedImage, tiles: (usize, usize))
+		-> Image
+	{
+		artwork.render(tiles, |address| self.color(address))
+	}
+
+	/// Renders the given artwork using the palette's current colors and using
+	/// the colors the given operation would produce, side by side in a single
+	/// image. The palette is left unchanged.
+	///
+	/// # Errors
+	///
+	/// Returns any error generated while previewing the operation.
+	///
+	/// # Example
+	///
+	/// ```rust
+	/// use palette::*;
+	/// use palette::operation::*;
+	/// use palette::render::IndexedImage;
+	///
+	/// let mut pal = Palette::new("Example", Format::Default, true);
+	/// pal.apply(Box::new(InsertColor::new(Color::new(255, 0, 0)))).unwrap();
+	///
+	/// let a = Address::new(0, 0, 0);
+	/// let artwork = IndexedImage::from_indices(2, &[0, 1, 1, 0], &[a]);
+	/// let mut change = InsertColor::new(Color::new(0, 0, 255))
+	/// 	.located_at(a)
+	/// 	.overwrite(true);
+	///
+	/// let image = pal.render_change(&artwork, &mut change, (1, 1)).unwrap();
+	/// assert_eq!((image.width, image.height), (4, 2));
+	/// assert_eq!(image.pixel(0, 0), Some(Color::new(255, 0, 0)));
+	/// assert_eq!(image.pixel(1, 0), None);
+	/// assert_eq!(image.pixel(2, 0), Some(Color::new(0, 0, 255)));
+	/// assert_eq!(pal.color(a), Some(Color::new(255, 0, 0)));
+	/// ```
+	pub fn render_change<O>(
+		&mut self,
+		artwork: &IndexedImage,
+		operation: &mut O,
+		tiles: (usize, usize))
+		-> Result<Image>
+		where O: PaletteOperation + ?Sized
+	{
+		let preview = self.preview(operation)?;
+		let before = self.render_artwork(artwork, tiles);
+		let after = artwork.render(tiles, |address| preview.color(address)
+			.unwrap_or_else(|| self.color(address)));
+
+		let mut image = Image::new(before.width * 2, before.height);
+		image.blit(&before, 0, 0);
+		image.blit(&after, before.width, 0);
+		Ok(image)
+	}
+}
@@ -71,6 +71,31 @@ pub enum Error {
 	
 	/// An element could not be created because the address was occupied.
 	AddressInUse(Address),
+
+	/// A color could not be sampled from the screen.
+	ScreenSampleFailed,
+
+	/// A generator could not produce colors satisfying its constraints.
+	ConstraintsUnsatisfiable,
+
+	/// An ICC profile was invalid or unsupported.
+	InvalidProfile,
+
+	/// A name was used which has not been defined.
+	UndefinedName(String),
+
+	/// A mapping did not provide a destination for the given address.
+	UnmappedAddress(Address),
+
+	/// An operation was vetoed by a hook, for the given reason.
+	OperationVetoed(String),
+
+	/// An operation required a white point or black point which the palette
+	/// does not have.
+	MissingAnchor,
+
+	/// A task was cancelled before it completed.
+	Cancelled,
 }
 
 
@@ -83,6 +108,24 @@ impl fmt::Display for Error {
 					address
 				),
 
+			Error::UnmappedAddress(address)
+				=> write!(f, "{}: {}", 
+					error::Error::description(self), 
+					address
+				),
+
+			Error::UndefinedName(ref name)
+				=> write!(f, "{}: {}", 
+					error::Error::description(self), 
+					name
+				),
+
+			Error::OperationVetoed(ref reason)
+				=> write!(f, "{}: {}", 
+					error::Error::description(self), 
+					reason
+				),
+
 			_	=> write!(f, "{}", error::Error::description(self))
 		}
 	}
@@ -116,6 +159,30 @@ impl error::Error for Error {
 
 			Error::AddressInUse(..)
 				=> "the address is in use",
+
+			Error::ScreenSampleFailed
+				=> "failed to sample color from screen",
+
+			Error::ConstraintsUnsatisfiable
+				=> "unable to generate colors satisfying the given constraints",
+
+			Error::InvalidProfile
+				=> "invalid or unsupported ICC profile",
+
+			Error::UndefinedName(..)
+				=> "undefined name",
+
+			Error::UnmappedAddress(..)
+				=> "no mapping provided for address",
+
+			Error::OperationVetoed(..)
+				=> "operation vetoed by hook",
+
+			Error::MissingAnchor
+				=> "the palette has no white point or black point",
+
+			Error::Cancelled
+				=> "the task was cancelled",
 		}
 	}
 }
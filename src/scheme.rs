@@ -0,0 +1,421 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides native addressing schemes for palettes.
+//!
+//! Internally, every cell is identified by a page:line:column `Address`. Many
+//! target platforms address their colors differently, such as by a flat 
+//! index, by grid coordinates, or by a hardware bank and an index within it.
+//! An `AddressScheme` maps such native addresses onto palette addresses, so 
+//! tools can accept and display native addresses while the rest of the engine
+//! works with `Address`es.
+//!
+//! # Example
+//!
+//! ```rust
+//! use palette::*;
+//! use palette::operation::*;
+//! use palette::scheme::{AddressScheme, Banked};
+//!
+//! // Store the NES palette's background banks as lines of four colors.
+//! let nes = Banked::nes();
+//! let address = nes.resolve("1:2").unwrap();
+//! assert_eq!(address, Address::new(0, 1, 2));
+//!
+//! let mut pal = Palette::new("NES", Format::Default, true);
+//! let insert = InsertColor::new(Color::new(12, 50, 78)).located_at(address);
+//! pal.apply(Box::new(insert)).unwrap();
+//!
+//! assert_eq!(nes.color(&pal, (1, 2)), Some(Color::new(12, 50, 78)));
+//! assert_eq!(nes.display(address), Some("1:2".to_owned()));
+//! ```
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use ::Palette;
+use address::{
+	Address,
+	Column,
+	Line,
+	Page,
+	Selection,
+};
+use format::default::parse_address;
+
+// Non-local imports.
+use color::Color;
+
+// Standard imports.
+use std::fmt;
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// AddressScheme
+////////////////////////////////////////////////////////////////////////////////
+/// A mapping between a platform's native cell addresses and palette 
+/// addresses.
+///
+/// Implementations must map each native address to at most one palette 
+/// address and back, so `from_address(to_address(n)?) == Some(n)`.
+pub trait AddressScheme: fmt::Debug {
+	/// The native address type.
+	type Native: Copy + fmt::Debug + PartialEq;
+
+	/// Returns the name of the scheme.
+	fn name(&self) -> &'static str;
+
+	/// Returns the number of addressable cells, if the scheme is bounded.
+	fn capacity(&self) -> Option<usize>;
+
+	/// Returns the palette address of the given native address, or None if 
+	/// it is out of range.
+	fn to_address(&self, native: Self::Native) -> Option<Address>;
+
+	/// Returns the native address of the given palette address, or None if 
+	/// it has no native address.
+	fn from_address(&self, address: Address) -> Option<Self::Native>;
+
+	/// Parses a native address from the given text.
+	fn parse(&self, text: &str) -> Option<Self::Native>;
+
+	/// Returns the text form of a native address.
+	fn format(&self, native: Self::Native) -> String;
+
+	/// Returns the palette address of the native address in the given text.
+	fn resolve(&self, text: &str) -> Option<Address> {
+		self.parse(text.trim()).and_then(|native| self.to_address(native))
+	}
+
+	/// Returns the native text form of the given palette address.
+	fn display(&self, address: Address) -> Option<String> {
+		self.from_address(address).map(|native| self.format(native))
+	}
+
+	/// Returns the color of the cell at the given native address.
+	fn color(&self, palette: &Palette, native: Self::Native) -> Option<Color> {
+		self.to_address(native).and_then(|address| palette.color(address))
+	}
+
+	/// Returns the native addresses and colors of the palette's cells which 
+	/// have native addresses, in address order.
+	fn colors(&self, palette: &Palette) -> Vec<(Self::Native, Color)> {
+		palette.colors(&Selection::all())
+			.into_iter()
+			.filter_map(|(address, color)| self.from_address(address)
+				.map(|native| (native, color)))
+			.collect()
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// PageLineColumn
+////////////////////////////////////////////////////////////////////////////////
+/// The palette's own page:line:column addressing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct PageLineColumn;
+
+
+impl AddressScheme for PageLineColumn {
+	type Native = Address;
+
+	fn name(&self) -> &'static str {
+		"page-line-column"
+	}
+
+	fn capacity(&self) -> Option<usize> {
+		None
+	}
+
+	fn to_address(&self, native: Address) -> Option<Address> {
+		Some(native)
+	}
+
+	fn from_address(&self, address: Address) -> Option<Address> {
+		Some(address)
+	}
+
+	fn parse(&self, text: &str) -> Option<Address> {
+		parse_address(text).ok()
+	}
+
+	fn format(&self, native: Address) -> String {
+		native.to_string()
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Flat
+////////////////////////////////////////////////////////////////////////////////
+/// Addresses cells by a single index, filling each line of the given number 
+/// of columns and each page of the given number of lines in turn.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::Address;
+/// use palette::scheme::{AddressScheme, Flat};
+///
+/// let flat = Flat::new(16, 16);
+/// assert_eq!(flat.to_address(300), Some(Address::new(1, 2, 12)));
+/// assert_eq!(flat.from_address(Address::new(1, 2, 12)), Some(300));
+/// assert_eq!(flat.resolve("0x12C"), Some(Address::new(1, 2, 12)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Flat {
+	/// The number of columns in each line.
+	columns: usize,
+	/// The number of lines in each page.
+	lines: usize,
+}
+
+
+impl Flat {
+	/// Creates a new `Flat` scheme with the given number of columns in each 
+	/// line and lines in each page. Zero counts are treated as one.
+	pub fn new(columns: Column, lines: Line) -> Self {
+		Flat {
+			columns: (columns as usize).max(1),
+			lines: (lines as usize).max(1),
+		}
+	}
+}
+
+
+impl AddressScheme for Flat {
+	type Native = usize;
+
+	fn name(&self) -> &'static str {
+		"flat"
+	}
+
+	fn capacity(&self) -> Option<usize> {
+		Some((Page::max_value() as usize + 1) * self.lines * self.columns)
+	}
+
+	fn to_address(&self, native: usize) -> Option<Address> {
+		let page = native / (self.lines * self.columns);
+		let rest = native % (self.lines * self.columns);
+		if page > Page::max_value() as usize { return None; }
+		Some(Address::new(
+			page as Page,
+			(rest / self.columns) as Line,
+			(rest % self.columns) as Column))
+	}
+
+	fn from_address(&self, address: Address) -> Option<usize> {
+		let (line, column) = (address.line as usize, address.column as usize);
+		if line >= self.lines || column >= self.columns { return None; }
+		Some((address.page as usize * self.lines + line) * self.columns 
+			+ column)
+	}
+
+	fn parse(&self, text: &str) -> Option<usize> {
+		parse_index(text)
+	}
+
+	fn format(&self, native: usize) -> String {
+		native.to_string()
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Grid
+////////////////////////////////////////////////////////////////////////////////
+/// Addresses cells of a single page by `(x, y)` coordinates, where `x` is the
+/// column and `y` is the line.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::Address;
+/// use palette::scheme::{AddressScheme, Grid};
+///
+/// let grid = Grid::new(8, 4);
+/// assert_eq!(grid.resolve("5,3"), Some(Address::new(0, 3, 5)));
+/// assert_eq!(grid.to_address((8, 0)), None);
+/// assert_eq!(grid.display(Address::new(0, 1, 2)), Some("2,1".to_owned()));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Grid {
+	/// The width of the grid, in columns.
+	width: usize,
+	/// The height of the grid, in lines.
+	height: usize,
+}
+
+
+impl Grid {
+	/// Creates a new `Grid` scheme with the given width and height.
+	pub fn new(width: Column, height: Line) -> Self {
+		Grid {
+			width: width as usize,
+			height: height as usize,
+		}
+	}
+}
+
+
+impl AddressScheme for Grid {
+	type Native = (usize, usize);
+
+	fn name(&self) -> &'static str {
+		"grid"
+	}
+
+	fn capacity(&self) -> Option<usize> {
+		Some(self.width * self.height)
+	}
+
+	fn to_address(&self, (x, y): (usize, usize)) -> Option<Address> {
+		if x >= self.width || y >= self.height { return None; }
+		Some(Address::new(0, y as Line, x as Column))
+	}
+
+	fn from_address(&self, address: Address) -> Option<(usize, usize)> {
+		let native = (address.column as usize, address.line as usize);
+		if address.page != 0 || self.to_address(native).is_none() {
+			return None;
+		}
+		Some(native)
+	}
+
+	fn parse(&self, text: &str) -> Option<(usize, usize)> {
+		parse_pair(text, ',')
+	}
+
+	fn format(&self, (x, y): (usize, usize)) -> String {
+		format!("{},{}", x, y)
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Banked
+////////////////////////////////////////////////////////////////////////////////
+/// Addresses cells by a hardware palette bank and an index within the bank, 
+/// written `bank:index`. Each bank is stored as a line of the first page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Banked {
+	/// The number of banks.
+	banks: usize,
+	/// The number of colors in each bank.
+	bank_size: usize,
+}
+
+
+impl Banked {
+	/// Creates a new `Banked` scheme with the given number of banks and 
+	/// colors in each bank.
+	pub fn new(banks: Line, bank_size: Column) -> Self {
+		Banked {
+			banks: banks as usize,
+			bank_size: bank_size as usize,
+		}
+	}
+
+	/// Returns the scheme of the NES, with eight banks of four colors: four 
+	/// for backgrounds followed by four for sprites.
+	pub fn nes() -> Self {
+		Banked::new(8, 4)
+	}
+
+	/// Returns the scheme of the Game Boy Color, with sixteen banks of four 
+	/// colors: eight for backgrounds followed by eight for objects.
+	pub fn game_boy_color() -> Self {
+		Banked::new(16, 4)
+	}
+
+	/// Returns the scheme of the SNES, with sixteen banks of sixteen colors.
+	pub fn snes() -> Self {
+		Banked::new(16, 16)
+	}
+}
+
+
+impl AddressScheme for Banked {
+	type Native = (usize, usize);
+
+	fn name(&self) -> &'static str {
+		"banked"
+	}
+
+	fn capacity(&self) -> Option<usize> {
+		Some(self.banks * self.bank_size)
+	}
+
+	fn to_address(&self, (bank, index): (usize, usize)) -> Option<Address> {
+		if bank >= self.banks || index >= self.bank_size { return None; }
+		Some(Address::new(0, bank as Line, index as Column))
+	}
+
+	fn from_address(&self, address: Address) -> Option<(usize, usize)> {
+		let native = (address.line as usize, address.column as usize);
+		if address.page != 0 || self.to_address(native).is_none() {
+			return None;
+		}
+		Some(native)
+	}
+
+	fn parse(&self, text: &str) -> Option<(usize, usize)> {
+		parse_pair(text, ':')
+	}
+
+	fn format(&self, (bank, index): (usize, usize)) -> String {
+		format!("{}:{}", bank, index)
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Parsing
+////////////////////////////////////////////////////////////////////////////////
+/// Parses a decimal or `0x`-prefixed hexadecimal index.
+fn parse_index(text: &str) -> Option<usize> {
+	let text = text.trim();
+	if text.starts_with("0x") || text.starts_with("0X") {
+		usize::from_str_radix(&text[2..], 16).ok()
+	} else {
+		text.parse().ok()
+	}
+}
+
+
+/// Parses a pair of indices separated by the given character.
+fn parse_pair(text: &str, separator: char) -> Option<(usize, usize)> {
+	let mut parts = text.splitn(2, separator);
+	match (parts.next().and_then(parse_index), parts.next()) {
+		(Some(a), Some(b)) => parse_index(b).map(|b| (a, b)),
+		_ => None,
+	}
+}
@@ -0,0 +1,138 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides searching for cells within palettes and projects.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use ::Palette;
+use address::{
+	Address,
+	Selection,
+};
+use project::{
+	CellRef,
+	Project,
+};
+use space::delta_e_2000;
+
+// Non-local imports.
+use color::Color;
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Query
+////////////////////////////////////////////////////////////////////////////////
+/// A search criterion for palette cells.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Query {
+	/// Matches cells whose name contains the given text, ignoring case.
+	Name(String),
+	/// Matches cells with the given tag.
+	Tag(String),
+	/// Matches cells with exactly the given color.
+	Color(Color),
+	/// Matches cells whose color lies within the given CIEDE2000 distance of
+	/// the given color.
+	Similar(Color, f32),
+}
+
+
+impl Query {
+	/// Returns whether the given palette cell matches the query.
+	pub fn matches(&self, palette: &Palette, address: Address) -> bool {
+		match *self {
+			Query::Name(ref text) => palette.cell_name(address)
+				.map_or(false, |name| name.to_lowercase()
+					.contains(&text.to_lowercase())),
+			Query::Tag(ref tag) => palette.cell_tags(address)
+				.contains(&&tag[..]),
+			Query::Color(color) => palette.color(address) == Some(color),
+			Query::Similar(color, distance) => palette.color(address)
+				.map_or(false, |c| delta_e_2000(c, color) <= distance),
+		}
+	}
+}
+
+
+/// Returns the addresses of the given palette's cells which match the query,
+/// in address order.
+fn search_palette(palette: &Palette, query: &Query) -> Vec<Address> {
+	let mut addresses: Vec<_> = palette.colors(&Selection::all())
+		.into_iter()
+		.map(|(address, _)| address)
+		.chain(palette.named_cells().into_iter().map(|(_, address)| address))
+		.filter(|&address| query.matches(palette, address))
+		.collect();
+	addresses.sort();
+	addresses.dedup();
+	addresses
+}
+
+
+impl Palette {
+	/// Returns the addresses of all cells matching the given query, in 
+	/// address order.
+	///
+	/// # Example
+	///
+	/// ```rust
+	/// use palette::*;
+	/// use palette::operation::*;
+	/// use palette::search::Query;
+	///
+	/// let mut pal = Palette::new("Example", Format::Default, true);
+	/// pal.apply(Box::new(InsertColor::new(Color::new(200, 40, 40)))).unwrap();
+	/// pal.apply(Box::new(InsertColor::new(Color::new(202, 41, 40)))).unwrap();
+	/// pal.set_name(Address::new(0, 0, 0), "Blood Red");
+	/// pal.add_tag(Address::new(0, 0, 1), "accent");
+	///
+	/// let first = vec![Address::new(0, 0, 0)];
+	/// let second = vec![Address::new(0, 0, 1)];
+	/// assert_eq!(pal.search(&Query::Name("red".into())), first);
+	/// assert_eq!(pal.search(&Query::Tag("accent".into())), second);
+	/// assert_eq!(pal.search(&Query::Color(Color::new(200, 40, 40))), first);
+	/// assert_eq!(
+	/// 	pal.search(&Query::Similar(Color::new(200, 40, 40), 2.0)).len(),
+	/// 	2);
+	/// ```
+	pub fn search(&self, query: &Query) -> Vec<Address> {
+		search_palette(self, query)
+	}
+}
+
+
+impl Project {
+	/// Returns references to all cells in the project's palettes matching the
+	/// given query, ordered by palette key and then by address.
+	pub fn search(&self, query: &Query) -> Vec<CellRef> {
+		self.palettes()
+			.flat_map(|(key, palette)| search_palette(palette, query)
+				.into_iter()
+				.map(move |address| CellRef::new(key.clone(), address)))
+			.collect()
+	}
+}
@@ -0,0 +1,481 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides a textual syntax for selecting palette cells.
+//!
+//! A selector is a comma-separated list of terms, and selects every cell 
+//! matched by any of its terms. The supported terms are:
+//!
+//! | Term           | Selects                                               |
+//! |----------------|-------------------------------------------------------|
+//! | `*`            | every cell                                            |
+//! | `1:2:3`        | the cell at the given address                         |
+//! | `1:0:0..1:2:0` | the cells between the given addresses, inclusive      |
+//! | `page1`        | the cells on page 1                                   |
+//! | `page1[0..16]` | the first 16 occupied cells on page 1                 |
+//! | `page1[4]`     | the fifth occupied cell on page 1                     |
+//! | `#accent`      | the cells tagged `accent`                             |
+//! | `lum<0.3`      | the cells whose color property satisfies a comparison |
+//! | `*.shadow*`    | the named cells whose name matches a pattern          |
+//!
+//! Occupied cells are counted in address order. Name patterns ignore case, 
+//! and may use `*` to match any text and `?` to match any single character.
+//! The comparable properties are `lum` (relative luminance), `light` and `sat`
+//! (HSL lightness and saturation, from 0 to 1), and `hue` (in degrees), 
+//! compared using `<`, `<=`, `>`, `>=`, `=`, or `!=`.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use ::Palette;
+use address::{
+	Address,
+	Page,
+	Selection,
+	PAGE_MAX,
+	LINE_MAX,
+	COLUMN_MAX,
+};
+use eval::{
+	EvalError,
+	EvalResult,
+	Span,
+};
+use format::default::parse_address;
+use space::{
+	relative_luminance,
+	unit_channels,
+	Hsl,
+};
+use utilities::glob_matches;
+
+// Non-local imports.
+use color::Color;
+use interval::Interval;
+
+// Standard imports.
+use std::fmt;
+use std::str::FromStr;
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Property
+////////////////////////////////////////////////////////////////////////////////
+/// A comparable property of a cell's color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Property {
+	/// The relative luminance of the color.
+	Luminance,
+	/// The HSL lightness of the color.
+	Lightness,
+	/// The HSL saturation of the color.
+	Saturation,
+	/// The HSL hue of the color, in degrees.
+	Hue,
+}
+
+
+impl Property {
+	/// Returns the name of the property.
+	pub fn name(&self) -> &'static str {
+		match *self {
+			Property::Luminance  => "lum",
+			Property::Lightness  => "light",
+			Property::Saturation => "sat",
+			Property::Hue        => "hue",
+		}
+	}
+
+	/// Returns the property with the given name, ignoring case.
+	pub fn from_name(name: &str) -> Option<Property> {
+		[
+			Property::Luminance,
+			Property::Lightness,
+			Property::Saturation,
+			Property::Hue,
+		].iter()
+			.find(|p| p.name().eq_ignore_ascii_case(name))
+			.cloned()
+	}
+
+	/// Returns the value of the property for the given color.
+	pub fn of(&self, color: Color) -> f32 {
+		let hsl = Hsl::from_rgb(unit_channels(color));
+		match *self {
+			Property::Luminance  => relative_luminance(color),
+			Property::Lightness  => hsl.l,
+			Property::Saturation => hsl.s,
+			Property::Hue        => hsl.h,
+		}
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Comparison
+////////////////////////////////////////////////////////////////////////////////
+/// A comparison between a value and a threshold. Comparisons are shared by
+/// selectors and conditional expressions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Comparison {
+	/// The value is less than the threshold.
+	Less,
+	/// The value is less than or equal to the threshold.
+	LessEqual,
+	/// The value is greater than the threshold.
+	Greater,
+	/// The value is greater than or equal to the threshold.
+	GreaterEqual,
+	/// The value is equal to the threshold.
+	Equal,
+	/// The value is not equal to the threshold.
+	NotEqual,
+}
+
+
+impl Comparison {
+	/// Returns the operator symbol of the comparison.
+	pub fn symbol(&self) -> &'static str {
+		match *self {
+			Comparison::Less         => "<",
+			Comparison::LessEqual    => "<=",
+			Comparison::Greater      => ">",
+			Comparison::GreaterEqual => ">=",
+			Comparison::Equal        => "=",
+			Comparison::NotEqual     => "!=",
+		}
+	}
+
+	/// Returns whether the value satisfies the comparison with the given 
+	/// threshold.
+	pub fn holds(&self, value: f32, threshold: f32) -> bool {
+		match *self {
+			Comparison::Less         => value < threshold,
+			Comparison::LessEqual    => value <= threshold,
+			Comparison::Greater      => value > threshold,
+			Comparison::GreaterEqual => value >= threshold,
+			Comparison::Equal        => value == threshold,
+			Comparison::NotEqual     => value != threshold,
+		}
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Term
+////////////////////////////////////////////////////////////////////////////////
+/// A single term of a selector.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Term {
+	/// Selects every cell.
+	All,
+	/// Selects the cell at the given address.
+	Address(Address),
+	/// Selects the cells between the given addresses, inclusive.
+	Range(Address, Address),
+	/// Selects the cells on the given page.
+	Page(Page),
+	/// Selects the occupied cells of the given page whose positions in
+	/// address order lie in the given half-open range.
+	PageCells(Page, usize, usize),
+	/// Selects the cells with the given tag.
+	Tag(String),
+	/// Selects the cells whose color property satisfies the comparison with 
+	/// the given threshold.
+	Compare(Property, Comparison, f32),
+	/// Selects the named cells whose name matches the given pattern.
+	Name(String),
+}
+
+
+impl Term {
+	/// Returns the intervals of the term's cells in the given palette, given
+	/// its occupied addresses in address order.
+	fn intervals(&self, palette: &Palette, occupied: &[Address]) 
+		-> Vec<Interval<Address>>
+	{
+		match *self {
+			Term::All => vec![Interval::closed(
+				Address::new(0, 0, 0),
+				Address::new(PAGE_MAX, LINE_MAX, COLUMN_MAX))],
+			Term::Address(address) => vec![Interval::closed(address, address)],
+			Term::Range(start, end) => vec![Interval::closed(start, end)],
+			Term::Page(page) => vec![Interval::closed(
+				Address::new(page, 0, 0),
+				Address::new(page, LINE_MAX, COLUMN_MAX))],
+			Term::PageCells(page, start, end) => cells(occupied.iter()
+				.filter(|address| address.page == page)
+				.skip(start)
+				.take(end.saturating_sub(start))),
+			Term::Tag(ref tag) => cells(occupied.iter()
+				.filter(|&&address| palette.cell_tags(address)
+					.contains(&&tag[..]))),
+			Term::Compare(property, comparison, threshold) 
+				=> cells(occupied.iter()
+					.filter(|&&address| palette.color(address)
+						.map_or(false, |color| comparison
+							.holds(property.of(color), threshold)))),
+			Term::Name(ref pattern) => cells(occupied.iter()
+				.filter(|&&address| palette.cell_name(address)
+					.map_or(false, |name| glob_matches(pattern, name)))),
+		}
+	}
+}
+
+
+/// Returns intervals containing each of the given cells.
+fn cells<'a, I>(addresses: I) -> Vec<Interval<Address>> 
+	where I: Iterator<Item=&'a Address>
+{
+	addresses.map(|&address| Interval::closed(address, address)).collect()
+}
+
+
+impl fmt::Display for Term {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			Term::All => write!(f, "*"),
+			Term::Address(address) => write!(f, "{}", address),
+			Term::Range(start, end) => write!(f, "{}..{}", start, end),
+			Term::Page(page) => write!(f, "page{}", page),
+			Term::PageCells(page, start, end) 
+				if start.checked_add(1) == Some(end)
+				=> write!(f, "page{}[{}]", page, start),
+			Term::PageCells(page, start, end) 
+				=> write!(f, "page{}[{}..{}]", page, start, end),
+			Term::Tag(ref tag) => write!(f, "#{}", tag),
+			Term::Compare(property, comparison, threshold) => write!(f, 
+				"{}{}{}", property.name(), comparison.symbol(), threshold),
+			Term::Name(ref pattern) => write!(f, "{}", pattern),
+		}
+	}
+}
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Selector
+////////////////////////////////////////////////////////////////////////////////
+/// A parsed selection string, which can be resolved into a `Selection` of a
+/// palette's cells.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::operation::*;
+/// use palette::select::Selector;
+///
+/// let mut pal = Palette::new("Example", Format::Default, true);
+/// for &value in &[20, 60, 120, 200] {
+/// 	pal.apply(Box::new(InsertColor::new(Color::new(value, value, value))))
+/// 		.unwrap();
+/// }
+/// pal.set_name(Address::new(0, 0, 3), "skin.highlight");
+///
+/// let selector: Selector = "page0[0..2], *.HIGH*".parse().unwrap();
+/// let selected: Vec<_> = pal.colors(&selector.select(&pal))
+/// 	.into_iter()
+/// 	.map(|(address, _)| address.column)
+/// 	.collect();
+/// assert_eq!(selected, vec![0, 1, 3]);
+///
+/// let dark = Selector::parse("lum<0.1").unwrap();
+/// assert_eq!(pal.colors(&dark.select(&pal)).len(), 2);
+/// assert_eq!(dark.to_string(), "lum<0.1");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Selector {
+	/// The terms of the selector.
+	terms: Vec<Term>,
+}
+
+
+impl Selector {
+	/// Creates a new `Selector` from the given terms.
+	pub fn new<I>(terms: I) -> Self 
+		where I: IntoIterator<Item=Term>
+	{
+		Selector {terms: terms.into_iter().collect()}
+	}
+
+	/// Parses a `Selector` from the given text.
+	///
+	/// # Errors
+	///
+	/// Returns an `EvalError` locating the first malformed term.
+	///
+	/// # Example
+	///
+	/// ```rust
+	/// use palette::select::Selector;
+	///
+	/// let selector = Selector::parse("page1[3], page1[0..2]").unwrap();
+	/// assert_eq!(selector.to_string(), "page1[3], page1[0..2]");
+	///
+	/// assert!(Selector::parse("page1[18446744073709551615]").is_err());
+	/// ```
+	pub fn parse(text: &str) -> EvalResult<Selector> {
+		let mut terms = Vec::new();
+		let mut offset = 0;
+		for part in text.split(',') {
+			let start = offset + (part.len() - part.trim_start().len());
+			let span = Span::new(start, start + part.trim().len());
+			terms.push(parse_term(part.trim(), span)?);
+			offset += part.len() + 1;
+		}
+		Ok(Selector {terms: terms})
+	}
+
+	/// Returns the terms of the selector.
+	pub fn terms(&self) -> &[Term] {
+		&self.terms[..]
+	}
+
+	/// Returns the selection of cells matched by the selector in the given 
+	/// palette.
+	pub fn select(&self, palette: &Palette) -> Selection {
+		let mut occupied: Vec<_> = palette.colors(&Selection::all())
+			.into_iter()
+			.map(|(address, _)| address)
+			.chain(palette.named_cells().into_iter().map(|(_, a)| a))
+			.collect();
+		occupied.sort();
+		occupied.dedup();
+
+		Selection::new(self.terms
+			.iter()
+			.flat_map(|term| term.intervals(palette, &occupied[..])))
+	}
+}
+
+
+impl FromStr for Selector {
+	type Err = EvalError;
+
+	fn from_str(text: &str) -> EvalResult<Selector> {
+		Selector::parse(text)
+	}
+}
+
+
+impl fmt::Display for Selector {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		for (i, term) in self.terms.iter().enumerate() {
+			if i > 0 { write!(f, ", ")?; }
+			write!(f, "{}", term)?;
+		}
+		Ok(())
+	}
+}
+
+
+/// Parses a single trimmed selector term.
+fn parse_term(text: &str, span: Span) -> EvalResult<Term> {
+	let error = |message: String| EvalError::new(message, span);
+
+	if text.is_empty() {
+		return Err(error("expected selector term".to_owned()));
+	}
+	if text == "*" {
+		return Ok(Term::All);
+	}
+	if text.starts_with('#') {
+		return match &text[1..] {
+			"" => Err(error("missing tag name".to_owned())),
+			tag => Ok(Term::Tag(tag.to_owned())),
+		};
+	}
+	if let Some(index) = text.find(|c| c == '<' || c == '>' || c == '=') {
+		return parse_comparison(&text[..index], &text[index..])
+			.map_err(error);
+	}
+	if text.starts_with("page") 
+		&& text[4..].starts_with(|c: char| c.is_ascii_digit())
+	{
+		return parse_page(&text[4..]).map_err(error);
+	}
+	if text.starts_with(|c: char| c.is_ascii_digit()) && text.contains(':') {
+		return match text.find("..") {
+			Some(index) => Ok(Term::Range(
+				parse_address(&text[..index]).map_err(&error)?,
+				parse_address(&text[index + 2..]).map_err(&error)?)),
+			None => parse_address(text).map(Term::Address).map_err(error),
+		};
+	}
+	Ok(Term::Name(text.to_owned()))
+}
+
+
+/// Parses a property comparison term from its property name and the 
+/// remaining text.
+fn parse_comparison(name: &str, rest: &str) -> Result<Term, String> {
+	let property = Property::from_name(name.trim())
+		.ok_or_else(|| format!("unknown property '{}'", name.trim()))?;
+	let comparison = [
+		Comparison::LessEqual,
+		Comparison::GreaterEqual,
+		Comparison::Less,
+		Comparison::Greater,
+		Comparison::Equal,
+		Comparison::NotEqual,
+	].iter()
+		.find(|c| rest.starts_with(c.symbol()))
+		.cloned()
+		.ok_or_else(|| format!("invalid comparison '{}'", rest))?;
+	let value = rest[comparison.symbol().len()..].trim();
+	let threshold = value.parse()
+		.map_err(|_| format!("invalid threshold '{}'", value))?;
+	Ok(Term::Compare(property, comparison, threshold))
+}
+
+
+/// Parses a page term from the text following the `page` keyword.
+fn parse_page(text: &str) -> Result<Term, String> {
+	let invalid = || format!("invalid page selector 'page{}'", text);
+	let (page, range) = match text.find('[') {
+		Some(index) if text.ends_with(']') 
+			=> (&text[..index], Some(&text[index + 1..text.len() - 1])),
+		Some(_) => return Err(invalid()),
+		None => (text, None),
+	};
+	let page = page.parse().map_err(|_| invalid())?;
+
+	let range = match range {
+		Some(range) => range,
+		None => return Ok(Term::Page(page)),
+	};
+	let (start, end) = match range.find("..") {
+		Some(index) => (
+			range[..index].trim().parse().map_err(|_| invalid())?,
+			range[index + 2..].trim().parse().map_err(|_| invalid())?),
+		None => {
+			let index: usize = range.trim().parse().map_err(|_| invalid())?;
+			(index, index.checked_add(1).ok_or_else(invalid)?)
+		},
+	};
+	Ok(Term::PageCells(page, start, end))
+}
@@ -0,0 +1,278 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides keyframed sequences of palettes over a timeline.
+//!
+//! A `PaletteSequence` holds palettes keyed at points in time, such as the
+//! palettes of a day and night cycle. Evaluating the sequence at any time 
+//! blends the surrounding keyframes, and the sequence can be sampled into a
+//! strip of palettes or an atlas of lookup tables for export.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use ::Palette;
+use address::{
+	Address,
+	Selection,
+};
+use gradient::Easing;
+
+// Non-local imports.
+use color::Color;
+
+// Standard imports.
+use std::io;
+
+#[cfg(feature = "images")]
+use export::Lut3d;
+#[cfg(feature = "images")]
+use export::lut::write_rgb_png;
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Keyframe
+////////////////////////////////////////////////////////////////////////////////
+/// A palette keyed at a point in a sequence's timeline.
+#[derive(Debug)]
+pub struct Keyframe {
+	/// The time of the keyframe.
+	pub time: f32,
+	/// The palette at the time of the keyframe.
+	pub palette: Palette,
+	/// The easing of the blend from this keyframe to the next.
+	pub easing: Easing,
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// PaletteSequence
+////////////////////////////////////////////////////////////////////////////////
+/// A sequence of palettes keyed over a timeline.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::gradient::Easing;
+/// use palette::operation::*;
+/// use palette::sequence::PaletteSequence;
+///
+/// let keyed = |color| {
+/// 	let mut pal = Palette::new("Sky", Format::Default, true);
+/// 	pal.apply(Box::new(InsertColor::new(color))).unwrap();
+/// 	pal
+/// };
+///
+/// let sequence = PaletteSequence::new()
+/// 	.key(0.0, keyed(Color::new(10, 20, 60)), Easing::Smooth)
+/// 	.key(12.0, keyed(Color::new(90, 160, 255)), Easing::Step)
+/// 	.key(24.0, keyed(Color::new(10, 20, 60)), Easing::Linear);
+///
+/// let sky = |t| sequence.evaluate(t).unwrap().color(Address::new(0, 0, 0));
+/// assert_eq!(sky(0.0), Some(Color::new(10, 20, 60)));
+/// assert_eq!(sky(12.0), Some(Color::new(90, 160, 255)));
+/// assert_eq!(sky(18.0), Some(Color::new(90, 160, 255)));
+/// assert_eq!(sky(30.0), Some(Color::new(10, 20, 60)));
+/// assert!(sky(6.0).unwrap().blue() > 60);
+///
+/// assert_eq!(sequence.sample(5).unwrap().len(), 5);
+/// ```
+#[derive(Debug, Default)]
+pub struct PaletteSequence {
+	/// The keyframes, ordered by time.
+	keys: Vec<Keyframe>,
+}
+
+
+impl PaletteSequence {
+	/// Creates a new, empty `PaletteSequence`.
+	pub fn new() -> Self {
+		PaletteSequence::default()
+	}
+
+	/// Adds a keyframe to the sequence.
+	pub fn key(mut self, time: f32, palette: Palette, easing: Easing) -> Self {
+		self.insert_key(time, palette, easing);
+		self
+	}
+
+	/// Inserts a keyframe, replacing and returning any keyframe at the same
+	/// time.
+	pub fn insert_key(&mut self, time: f32, palette: Palette, easing: Easing)
+		-> Option<Keyframe>
+	{
+		let key = Keyframe {time: time, palette: palette, easing: easing};
+		match self.keys.iter().position(|k| k.time >= time) {
+			Some(i) if self.keys[i].time == time => {
+				Some(::std::mem::replace(&mut self.keys[i], key))
+			},
+			Some(i) => { self.keys.insert(i, key); None },
+			None => { self.keys.push(key); None },
+		}
+	}
+
+	/// Removes and returns the keyframe at the given index, if any.
+	pub fn remove_key(&mut self, index: usize) -> Option<Keyframe> {
+		if index < self.keys.len() {
+			Some(self.keys.remove(index))
+		} else {
+			None
+		}
+	}
+
+	/// Returns the keyframes of the sequence, ordered by time.
+	pub fn keys(&self) -> &[Keyframe] {
+		&self.keys[..]
+	}
+
+	/// Returns the number of keyframes in the sequence.
+	pub fn len(&self) -> usize {
+		self.keys.len()
+	}
+
+	/// Returns whether the sequence has no keyframes.
+	pub fn is_empty(&self) -> bool {
+		self.keys.is_empty()
+	}
+
+	/// Returns the palette of the sequence at the given time. Times outside
+	/// of the keyframes hold the nearest keyframe's palette.
+	///
+	/// # Errors
+	///
+	/// Returns an error of kind `InvalidInput` if the sequence is empty.
+	pub fn evaluate(&self, time: f32) -> io::Result<Palette> {
+		let first = self.keys.first().ok_or_else(empty)?;
+		let next = match self.keys.iter().position(|k| k.time > time) {
+			Some(0) => return first.palette.copied(Vec::new()),
+			Some(i) => i,
+			None => return self.keys[self.keys.len() - 1]
+				.palette
+				.copied(Vec::new()),
+		};
+
+		let (from, to) = (&self.keys[next - 1], &self.keys[next]);
+		let progress = from.easing
+			.apply((time - from.time) / (to.time - from.time));
+		Palette::interpolate(&from.palette, &to.palette, progress)
+	}
+
+	/// Returns the given number of palettes evenly spaced over the timeline,
+	/// including the first and last keyframes.
+	pub fn sample(&self, count: usize) -> io::Result<Vec<Palette>> {
+		let (start, end) = self.span().ok_or_else(empty)?;
+		(0..count)
+			.map(|i| {
+				let t = if count > 1 { i as f32 / (count - 1) as f32 } else { 0.0 };
+				self.evaluate(start + (end - start) * t)
+			})
+			.collect()
+	}
+
+	/// Returns the times of the first and last keyframes, if any.
+	pub fn span(&self) -> Option<(f32, f32)> {
+		match (self.keys.first(), self.keys.last()) {
+			(Some(first), Some(last)) => Some((first.time, last.time)),
+			_ => None,
+		}
+	}
+
+	/// Returns the colors of each of the given number of palettes sampled 
+	/// evenly over the timeline, for the cells of the first keyframe. Cells 
+	/// missing from a sample keep the first keyframe's color.
+	pub fn sample_colors(&self, count: usize) 
+		-> io::Result<Vec<Vec<(Address, Color)>>>
+	{
+		let base = self.keys.first().ok_or_else(empty)?
+			.palette
+			.colors(&Selection::all());
+		Ok(self.sample(count)?
+			.iter()
+			.map(|palette| base.iter()
+				.map(|&(address, color)| 
+					(address, palette.color(address).unwrap_or(color)))
+				.collect())
+			.collect())
+	}
+
+	/// Writes the given number of palettes sampled over the timeline to the
+	/// given buffer as a PNG image, with one row per sample and one column 
+	/// per cell of the first keyframe.
+	#[cfg(feature = "images")]
+	pub fn write_strip_png<W>(&self, count: usize, out_buf: &mut W)
+		-> io::Result<()>
+		where W: io::Write
+	{
+		let samples = self.sample_colors(count)?;
+		let width = samples.first().map_or(0, |row| row.len());
+		let pixels: Vec<u8> = samples.iter()
+			.flat_map(|row| row.iter())
+			.flat_map(|&(_, c)| vec![c.red(), c.green(), c.blue()])
+			.collect();
+		write_rgb_png(out_buf, width as u32, count as u32, &pixels)
+	}
+
+	/// Writes the given number of palettes sampled over the timeline to the
+	/// given buffer as a PNG atlas of lookup tables with the given grid size.
+	/// Each sample's table maps the first keyframe's colors to the sample's
+	/// colors, and is laid out as by `Lut3d::write_png`. The tables are 
+	/// stacked vertically in time order.
+	///
+	/// # Panics
+	///
+	/// Panics if the size is less than 2.
+	#[cfg(feature = "images")]
+	pub fn write_lut_atlas_png<W>(
+		&self,
+		count: usize,
+		size: usize,
+		out_buf: &mut W)
+		-> io::Result<()>
+		where W: io::Write
+	{
+		let samples = self.sample_colors(count)?;
+		let base: Vec<Color> = samples.first()
+			.map_or(Vec::new(), |row| row.iter().map(|&(_, c)| c).collect());
+
+		let mut pixels = Vec::new();
+		for row in &samples {
+			let to: Vec<Color> = row.iter().map(|&(_, c)| c).collect();
+			pixels.extend(Lut3d::swap(&base, &to, size).pixels());
+		}
+		write_rgb_png(
+			out_buf,
+			(size * size) as u32,
+			(size * samples.len()) as u32,
+			&pixels)
+	}
+}
+
+
+/// Returns the error for evaluating an empty sequence.
+fn empty() -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidInput, "the sequence has no keyframes")
+}
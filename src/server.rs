@@ -0,0 +1,455 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides a small HTTP server exposing a palette to remote editors.
+//!
+//! The server owns a single palette and handles requests one at a time on the
+//! thread which runs it, so no locking is needed. The following endpoints are
+//! provided, each responding with JSON:
+//!
+//! | Request                 | Response                                     |
+//! |-------------------------|----------------------------------------------|
+//! | `GET /palette`          | The palette's name, revision, and cells.     |
+//! | `GET /cells/<cell>`     | The cell with the given alias, name, or      |
+//! |                         | address.                                     |
+//! | `POST /operations`      | Applies the operation in the request body,   |
+//! |                         | responding with the changed cells.           |
+//! | `GET /events`           | A server-sent event stream with a `change`   |
+//! |                         | event for each applied operation.            |
+//!
+//! Operations are JSON objects naming the operation by an `op` field:
+//!
+//! ```text
+//! {"op": "insert", "color": "#0C324E", "at": "0:0:1"}
+//! {"op": "delete", "cell": "0:0:1"}
+//! {"op": "move", "from": "0:0:1", "to": "0:1:0"}
+//! {"op": "undo"}
+//! {"op": "redo"}
+//! ```
+//!
+//! Colors may be any color expression, and may refer to the palette's named 
+//! cells. The `at` field of an insert is optional. Applying an operation 
+//! responds with, and sends as the data of its `change` event, the new 
+//! revision and the changed cells' colors; the changes are `null` for undo 
+//! and redo. Undo and redo are refused with status 400 if the palette has no
+//! history. Operation bodies larger than `MAX_BODY_SIZE` bytes are refused 
+//! with status 413.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use ::Palette;
+use address::{
+	Address,
+	Selection,
+};
+use eval::Evaluator;
+use export::resolve_cell;
+use format::default::parse_address;
+use operation::{
+	DeleteCell,
+	InsertColor,
+	MoveCell,
+	OperationReport,
+	PaletteOperation,
+};
+use provenance::Provenance;
+use utilities::hex;
+
+// Non-local imports.
+use serde_json::Value;
+use tiny_http::{
+	Header,
+	Method,
+	Request,
+	Response,
+	Server,
+};
+
+// Standard imports.
+use std::io;
+use std::io::{
+	Read,
+	Write,
+};
+use std::net::SocketAddr;
+use std::time::Duration;
+
+
+/// The maximum size of an operation request body, in bytes.
+pub const MAX_BODY_SIZE: u64 = 1024 * 1024;
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// PaletteServer
+////////////////////////////////////////////////////////////////////////////////
+/// An HTTP server exposing a palette for remote editing.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use palette::*;
+/// use palette::server::PaletteServer;
+///
+/// let pal = Palette::new("Example", Format::Default, true);
+/// let mut server = PaletteServer::bind("127.0.0.1:7878", pal).unwrap();
+/// server.run(|e| println!("request failed: {}", e)).unwrap();
+/// ```
+pub struct PaletteServer {
+	/// The underlying HTTP server.
+	server: Server,
+	/// The palette being edited.
+	palette: Palette,
+	/// The number of changes applied to the palette.
+	revision: u64,
+	/// The open event streams.
+	subscribers: Vec<Box<Write + Send>>,
+}
+
+
+impl PaletteServer {
+	/// Starts a server for the given palette listening on the given address.
+	/// The server does not handle requests until it is run.
+	///
+	/// # Errors
+	///
+	/// Returns an error if the address cannot be bound.
+	pub fn bind(address: &str, palette: Palette) -> io::Result<PaletteServer> {
+		let server = Server::http(address)
+			.map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
+		Ok(PaletteServer {
+			server: server,
+			palette: palette,
+			revision: 0,
+			subscribers: Vec::new(),
+		})
+	}
+
+	/// Returns the address the server is listening on.
+	pub fn local_addr(&self) -> Option<SocketAddr> {
+		self.server.server_addr().to_ip()
+	}
+
+	/// Returns a reference to the palette being edited.
+	pub fn palette(&self) -> &Palette {
+		&self.palette
+	}
+
+	/// Returns the number of changes applied to the palette by the server.
+	pub fn revision(&self) -> u64 {
+		self.revision
+	}
+
+	/// Stops the server, returning the edited palette.
+	pub fn into_palette(self) -> Palette {
+		self.palette
+	}
+
+	/// Handles requests until an error occurs receiving one. Errors handling
+	/// a request are passed to the given callback and do not stop the server.
+	pub fn run<F>(&mut self, mut on_error: F) -> io::Result<()>
+		where F: FnMut(io::Error)
+	{
+		loop {
+			let request = self.server.recv()?;
+			if let Err(e) = self.handle(request) {
+				on_error(e);
+			}
+		}
+	}
+
+	/// Waits up to the given duration for a request and handles it, returning
+	/// true if the palette was changed.
+	pub fn handle_next(&mut self, timeout: Duration) -> io::Result<bool> {
+		match self.server.recv_timeout(timeout)? {
+			Some(request) => self.handle(request),
+			None => Ok(false),
+		}
+	}
+
+	/// Handles the given request, returning true if the palette was changed.
+	fn handle(&mut self, mut request: Request) -> io::Result<bool> {
+		let path = request.url()
+			.split('?')
+			.next()
+			.unwrap_or("")
+			.trim_end_matches('/')
+			.to_owned();
+		let method = request.method().clone();
+
+		match (method, &path[..]) {
+			(Method::Get, "") | (Method::Get, "/palette") => {
+				let body = self.palette_json();
+				respond(request, 200, &body).map(|_| false)
+			},
+
+			(Method::Get, _) if path.starts_with("/cells/") => {
+				let target = decode(&path["/cells/".len()..]);
+				match resolve_cell(&self.palette, &target) {
+					Some(address) => {
+						let body = cell_json(&self.palette, address);
+						respond(request, 200, &body)
+					},
+					None => respond_error(request, 404, 
+						format!("no cell named '{}'", target)),
+				}.map(|_| false)
+			},
+
+			(Method::Post, "/operations") => {
+				let mut text = String::new();
+				let read = request.as_reader()
+					.take(MAX_BODY_SIZE + 1)
+					.read_to_string(&mut text);
+				if let Err(e) = read {
+					return respond_error(request, 400, e.to_string())
+						.map(|_| false);
+				}
+				if text.len() as u64 > MAX_BODY_SIZE {
+					return respond_error(request, 413, format!(
+							"request body exceeds {} bytes", MAX_BODY_SIZE))
+						.map(|_| false);
+				}
+				match self.apply(&text) {
+					Ok(body) => respond(request, 200, &body).map(|_| true),
+					Err(message) => respond_error(request, 400, message)
+						.map(|_| false),
+				}
+			},
+
+			(Method::Get, "/events") => {
+				let mut stream = request.into_writer();
+				let subscribed = write!(stream, 
+						"HTTP/1.1 200 OK\r\n\
+						Content-Type: text/event-stream\r\n\
+						Cache-Control: no-cache\r\n\r\n")
+					.and_then(|_| write!(stream, "retry: 1000\n\n"))
+					.and_then(|_| stream.flush());
+				if subscribed.is_ok() {
+					self.subscribers.push(stream);
+				}
+				Ok(false)
+			},
+
+			(_, "") 
+				| (_, "/palette") 
+				| (_, "/operations") 
+				| (_, "/events") => respond_error(request, 405, 
+					"method not allowed").map(|_| false),
+
+			_ => respond_error(request, 404, "not found").map(|_| false),
+		}
+	}
+
+	/// Applies the operation described by the given JSON text, notifies the
+	/// subscribers, and returns the response body.
+	fn apply(&mut self, text: &str) -> Result<Value, String> {
+		let request: Value = ::serde_json::from_str(text)
+			.map_err(|e| format!("invalid operation: {}", e))?;
+		let op = request.get("op")
+			.and_then(Value::as_str)
+			.ok_or("missing operation name")?
+			.to_owned();
+
+		let undoable = op == "undo" || op == "redo";
+		if undoable && self.palette.operation_history.is_none() {
+			return Err(format!("{} not supported: palette has no history", op));
+		}
+
+		let changes = match &op[..] {
+			"undo" => {
+				self.palette.undo().map_err(|e| e.to_string())?;
+				Value::Null
+			},
+			"redo" => {
+				self.palette.redo().map_err(|e| e.to_string())?;
+				Value::Null
+			},
+			_ => {
+				let operation = parse_operation(&self.palette, &op, &request)?;
+				let report = self.palette.apply(operation)
+					.map_err(|e| e.to_string())?;
+				let color = request.get("color").and_then(Value::as_str);
+				if let Some(text) = color {
+					for change in &report.changes {
+						self.palette.set_provenance(change.address, 
+							Provenance::Expression(text.to_owned()));
+					}
+				}
+				changes_json(&report)
+			},
+		};
+
+		self.revision += 1;
+		let event = json!({
+			"revision": self.revision,
+			"op": op,
+			"changes": changes,
+		});
+		self.notify(&event);
+		Ok(event)
+	}
+
+	/// Sends a change event to each subscriber, dropping those which have 
+	/// disconnected.
+	fn notify(&mut self, event: &Value) {
+		let message = format!("id: {}\nevent: change\ndata: {}\n\n", 
+			self.revision, event);
+		let mut open = Vec::with_capacity(self.subscribers.len());
+		for mut stream in self.subscribers.drain(..) {
+			if stream.write_all(message.as_bytes())
+				.and_then(|_| stream.flush())
+				.is_ok()
+			{
+				open.push(stream);
+			}
+		}
+		self.subscribers = open;
+	}
+
+	/// Returns the JSON representation of the palette.
+	fn palette_json(&self) -> Value {
+		let cells: Vec<Value> = self.palette.colors(&Selection::all())
+			.into_iter()
+			.map(|(address, _)| cell_json(&self.palette, address))
+			.collect();
+		json!({
+			"name": self.palette.name(),
+			"revision": self.revision,
+			"cells": cells,
+		})
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Operation parsing
+////////////////////////////////////////////////////////////////////////////////
+/// Returns the operation with the given name described by the given JSON 
+/// request.
+fn parse_operation(palette: &Palette, op: &str, request: &Value)
+	-> Result<Box<PaletteOperation>, String>
+{
+	let field = |name: &str| request.get(name)
+		.and_then(Value::as_str)
+		.ok_or_else(|| format!("missing '{}' for {}", name, op));
+	let cell = |name: &str| field(name).and_then(|target| 
+		resolve_cell(palette, target)
+			.ok_or_else(|| format!("no cell named '{}'", target)));
+
+	match op {
+		"insert" => {
+			let expression = field("color")?;
+			let color = Evaluator::new()
+				.palette(palette)
+				.eval(expression)
+				.map_err(|e| e.to_string())?
+				.as_color()?;
+			let mut insert = InsertColor::new(color);
+			if request.get("at").is_some() {
+				let at = field("at")?;
+				insert = insert.located_at(parse_address(at)?);
+			}
+			Ok(Box::new(insert))
+		},
+		"delete" => Ok(Box::new(DeleteCell::new(cell("cell")?))),
+		"move" => {
+			let from = cell("from")?;
+			let to: Address = parse_address(field("to")?)?;
+			Ok(Box::new(MoveCell::new(from, to)))
+		},
+		_ => Err(format!("unknown operation '{}'", op)),
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Responses
+////////////////////////////////////////////////////////////////////////////////
+/// Responds to the request with the given status and JSON body.
+fn respond(request: Request, status: u16, body: &Value) -> io::Result<()> {
+	let content_type = Header::from_bytes(
+			&b"Content-Type"[..], 
+			&b"application/json"[..])
+		.expect("valid header");
+	request.respond(Response::from_string(body.to_string())
+		.with_status_code(status)
+		.with_header(content_type))
+}
+
+
+/// Responds to the request with the given status and error message.
+fn respond_error<S>(request: Request, status: u16, message: S) 
+	-> io::Result<()>
+	where S: Into<String>
+{
+	respond(request, status, &json!({"error": message.into()}))
+}
+
+
+/// Returns the JSON representation of a palette cell.
+fn cell_json(palette: &Palette, address: Address) -> Value {
+	json!({
+		"address": address.to_string(),
+		"color": palette.color(address).map(hex),
+		"name": palette.cell_name(address),
+		"tags": palette.cell_tags(address),
+		"provenance": palette.provenance(address).map(|p| p.to_string()),
+	})
+}
+
+
+/// Returns the JSON representation of the changes made by an operation.
+fn changes_json(report: &OperationReport) -> Value {
+	Value::Array(report.changes.iter()
+		.map(|change| json!({
+			"address": change.address.to_string(),
+			"before": change.before.map(hex),
+			"after": change.after.map(hex),
+		}))
+		.collect())
+}
+
+
+/// Decodes the percent-encoded bytes of a URL path segment.
+fn decode(segment: &str) -> String {
+	let bytes = segment.as_bytes();
+	let mut decoded = Vec::with_capacity(bytes.len());
+	let mut i = 0;
+	while i < bytes.len() {
+		let escape = if bytes[i] == b'%' {
+			segment.get(i + 1..i + 3)
+				.and_then(|hex| u8::from_str_radix(hex, 16).ok())
+		} else {
+			None
+		};
+		match escape {
+			Some(byte) => { decoded.push(byte); i += 3; },
+			None => { decoded.push(bytes[i]); i += 1; },
+		}
+	}
+	String::from_utf8_lossy(&decoded).into_owned()
+}
@@ -0,0 +1,307 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides detached signatures for palette files.
+//!
+//! A signature for a file is stored beside it, in a file of the same name 
+//! with `.sig` appended, as a line of hexadecimal text. Signing and 
+//! verification are performed by implementations of `PaletteSigner` and 
+//! `PaletteVerifier`, allowing studios to plug in their own key management. 
+//! With the `signing` feature enabled, `Ed25519Signer` and `Ed25519Verifier` 
+//! provide Ed25519 signatures using keys stored as hexadecimal text.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use ::Palette;
+use format::Format;
+
+// Non-local imports.
+#[cfg(feature = "signing")]
+use ed25519_dalek::{
+	Signature,
+	Signer,
+	SigningKey,
+	Verifier,
+	VerifyingKey,
+};
+
+// Standard imports.
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::path::{
+	Path,
+	PathBuf,
+};
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// PaletteSigner
+////////////////////////////////////////////////////////////////////////////////
+/// Produces signatures for exported palette files.
+pub trait PaletteSigner {
+	/// Returns the signature of the given file contents.
+	fn sign(&self, bytes: &[u8]) -> io::Result<Vec<u8>>;
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// PaletteVerifier
+////////////////////////////////////////////////////////////////////////////////
+/// Checks the signatures of imported palette files.
+pub trait PaletteVerifier {
+	/// Returns whether the given signature is valid for the given file 
+	/// contents.
+	fn verify(&self, bytes: &[u8], signature: &[u8]) -> io::Result<bool>;
+}
+
+
+/// Returns the path of the detached signature for the file at the given path.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::sign::signature_path;
+/// use std::path::Path;
+///
+/// assert_eq!(signature_path("art/skin.pal"), Path::new("art/skin.pal.sig"));
+/// ```
+pub fn signature_path<P>(path: P) -> PathBuf where P: AsRef<Path> {
+	let mut name = OsString::from(path.as_ref());
+	name.push(".sig");
+	PathBuf::from(name)
+}
+
+
+/// Signs the file at the given path, writing its detached signature beside
+/// it. Returns the path of the signature.
+pub fn sign_file<P, S>(path: P, signer: &S) -> io::Result<PathBuf>
+	where
+		P: AsRef<Path>,
+		S: PaletteSigner + ?Sized,
+{
+	let signature = signer.sign(&fs::read(&path)?)?;
+	let target = signature_path(path);
+	fs::write(&target, format!("{}\n", to_hex(&signature)))?;
+	Ok(target)
+}
+
+
+/// Verifies the file at the given path against its detached signature.
+///
+/// # Errors
+///
+/// Returns a `NotFound` error if the file has no signature, and an 
+/// `InvalidData` error if the signature is malformed or does not match.
+pub fn verify_file<P, V>(path: P, verifier: &V) -> io::Result<()>
+	where
+		P: AsRef<Path>,
+		V: PaletteVerifier + ?Sized,
+{
+	let bytes = fs::read(&path)?;
+	verify_bytes(path, &bytes, verifier)
+}
+
+
+/// Verifies the given contents of the file at the given path against the 
+/// file's detached signature. Callers which go on to read the file should 
+/// read it once and pass the same bytes here, so that the bytes used are the
+/// bytes verified.
+///
+/// # Errors
+///
+/// Returns a `NotFound` error if the file has no signature, and an 
+/// `InvalidData` error if the signature is malformed or does not match.
+pub fn verify_bytes<P, V>(path: P, bytes: &[u8], verifier: &V) 
+	-> io::Result<()>
+	where
+		P: AsRef<Path>,
+		V: PaletteVerifier + ?Sized,
+{
+	let text = fs::read_to_string(signature_path(&path))?;
+	let signature = from_hex(text.trim()).ok_or_else(|| 
+		invalid("malformed signature"))?;
+	if verifier.verify(bytes, &signature)? {
+		Ok(())
+	} else {
+		Err(invalid("signature does not match"))
+	}
+}
+
+
+/// Reads the palette file at the given path in the default format after 
+/// verifying its detached signature. The file is read once, and the verified
+/// bytes are the ones parsed.
+///
+/// # Errors
+///
+/// Returns any error from `verify_file`, or from reading the palette.
+pub fn read_verified<P, V>(path: P, verifier: &V) -> io::Result<Palette>
+	where
+		P: AsRef<Path>,
+		V: PaletteVerifier + ?Sized,
+{
+	let bytes = fs::read(&path)?;
+	verify_bytes(&path, &bytes, verifier)?;
+	Format::Default.read_palette(&mut &bytes[..])
+}
+
+
+/// Returns the given bytes as lowercase hexadecimal text.
+fn to_hex(bytes: &[u8]) -> String {
+	bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+
+/// Parses bytes from hexadecimal text.
+fn from_hex(text: &str) -> Option<Vec<u8>> {
+	if text.len() % 2 != 0 || !text.is_ascii() { return None; }
+	(0..text.len())
+		.step_by(2)
+		.map(|i| u8::from_str_radix(&text[i..i + 2], 16).ok())
+		.collect()
+}
+
+
+/// Returns an `InvalidData` error with the given message.
+fn invalid(message: &str) -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+
+/// Parses a 32-byte key from hexadecimal text.
+#[cfg(feature = "signing")]
+fn parse_key(text: &str) -> io::Result<[u8; 32]> {
+	let bytes = from_hex(text.trim()).ok_or_else(|| invalid("malformed key"))?;
+	if bytes.len() != 32 { return Err(invalid("keys must be 32 bytes")); }
+	let mut key = [0; 32];
+	key.copy_from_slice(&bytes);
+	Ok(key)
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Ed25519Signer
+////////////////////////////////////////////////////////////////////////////////
+/// Signs palette files with an Ed25519 secret key.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::sign::*;
+///
+/// let signer = Ed25519Signer::from_hex(&"01".repeat(32)).unwrap();
+/// let verifier = signer.verifier();
+///
+/// let signature = signer.sign(b"cell 0:0:0 #0C324E\n").unwrap();
+/// assert!(verifier.verify(b"cell 0:0:0 #0C324E\n", &signature).unwrap());
+/// assert!(!verifier.verify(b"cell 0:0:0 #FFFFFF\n", &signature).unwrap());
+/// ```
+#[cfg(feature = "signing")]
+#[derive(Debug, Clone)]
+pub struct Ed25519Signer {
+	/// The secret key.
+	key: SigningKey,
+}
+
+
+#[cfg(feature = "signing")]
+impl Ed25519Signer {
+	/// Creates a new `Ed25519Signer` from a 32-byte secret key written as 
+	/// hexadecimal text.
+	pub fn from_hex(text: &str) -> io::Result<Self> {
+		Ok(Ed25519Signer {key: SigningKey::from_bytes(&parse_key(text)?)})
+	}
+
+	/// Reads a secret key written as hexadecimal text from the file at the 
+	/// given path.
+	pub fn read_key<P>(path: P) -> io::Result<Self> where P: AsRef<Path> {
+		Ed25519Signer::from_hex(&fs::read_to_string(path)?)
+	}
+
+	/// Returns a verifier for the signer's public key.
+	pub fn verifier(&self) -> Ed25519Verifier {
+		Ed25519Verifier {key: self.key.verifying_key()}
+	}
+}
+
+
+#[cfg(feature = "signing")]
+impl PaletteSigner for Ed25519Signer {
+	fn sign(&self, bytes: &[u8]) -> io::Result<Vec<u8>> {
+		Ok(self.key.sign(bytes).to_bytes().to_vec())
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Ed25519Verifier
+////////////////////////////////////////////////////////////////////////////////
+/// Verifies palette files with an Ed25519 public key.
+#[cfg(feature = "signing")]
+#[derive(Debug, Clone)]
+pub struct Ed25519Verifier {
+	/// The public key.
+	key: VerifyingKey,
+}
+
+
+#[cfg(feature = "signing")]
+impl Ed25519Verifier {
+	/// Creates a new `Ed25519Verifier` from a 32-byte public key written as
+	/// hexadecimal text.
+	pub fn from_hex(text: &str) -> io::Result<Self> {
+		VerifyingKey::from_bytes(&parse_key(text)?)
+			.map(|key| Ed25519Verifier {key: key})
+			.map_err(|_| invalid("invalid public key"))
+	}
+
+	/// Reads a public key written as hexadecimal text from the file at the 
+	/// given path.
+	pub fn read_key<P>(path: P) -> io::Result<Self> where P: AsRef<Path> {
+		Ed25519Verifier::from_hex(&fs::read_to_string(path)?)
+	}
+
+	/// Returns the public key as hexadecimal text.
+	pub fn to_hex(&self) -> String {
+		to_hex(self.key.as_bytes())
+	}
+}
+
+
+#[cfg(feature = "signing")]
+impl PaletteVerifier for Ed25519Verifier {
+	fn verify(&self, bytes: &[u8], signature: &[u8]) -> io::Result<bool> {
+		let signature = match Signature::from_slice(signature) {
+			Ok(signature) => signature,
+			Err(_) => return Ok(false),
+		};
+		Ok(self.key.verify(bytes, &signature).is_ok())
+	}
+}
@@ -0,0 +1,2450 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides color space conversions and color difference metrics.
+//!
+//! All conversions assume `Color`s are 8-bit sRGB values with a D65 white
+//! point. Conversions back into `Color` clamp each channel into the sRGB gamut.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use utilities::clamped;
+
+// Non-local imports.
+use color::Color;
+
+// Standard imports.
+use std::f32;
+use std::sync::OnceLock;
+
+
+/// The D65 reference white in XYZ.
+const D65_WHITE: [f32; 3] = [0.950_47, 1.0, 1.088_83];
+
+/// The CIE epsilon constant used in Lab conversion.
+const LAB_EPSILON: f32 = 216.0 / 24389.0;
+
+/// The CIE kappa constant used in Lab conversion.
+const LAB_KAPPA: f32 = 24389.0 / 27.0;
+
+
+/// Converts an sRGB-encoded channel in the range [0, 1] to linear light.
+#[inline]
+pub fn srgb_to_linear(value: f32) -> f32 {
+	if value <= 0.040_45 {
+		value / 12.92
+	} else {
+		((value + 0.055) / 1.055).powf(2.4)
+	}
+}
+
+/// Converts a linear light channel in the range [0, 1] to sRGB encoding.
+#[inline]
+pub fn linear_to_srgb(value: f32) -> f32 {
+	if value <= 0.003_130_8 {
+		value * 12.92
+	} else {
+		1.055 * value.powf(1.0 / 2.4) - 0.055
+	}
+}
+
+/// Returns the channels of the given `Color` as floats in the range [0, 1].
+#[inline]
+pub fn unit_channels(color: Color) -> [f32; 3] {
+	[
+		color.red() as f32 / 255.0,
+		color.green() as f32 / 255.0,
+		color.blue() as f32 / 255.0,
+	]
+}
+
+/// Returns a `Color` from the given channels in the range [0, 1]. Channels
+/// outside of that range are clamped.
+#[inline]
+pub fn from_unit_channels(channels: [f32; 3]) -> Color {
+	let q = |v: f32| (clamped(v, 0.0, 1.0) * 255.0).round() as u8;
+	Color::new(q(channels[0]), q(channels[1]), q(channels[2]))
+}
+
+/// Returns the hue distance between two angles in degrees, in the range
+/// [0, 180].
+#[inline]
+pub fn hue_distance(a: f32, b: f32) -> f32 {
+	let d = (a - b).abs() % 360.0;
+	if d > 180.0 { 360.0 - d } else { d }
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Hsl
+////////////////////////////////////////////////////////////////////////////////
+/// A color in the HSL color space. Hue is given in degrees in the range
+/// [0, 360), while saturation and lightness are in the range [0, 1].
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct Hsl {
+	/// The hue component.
+	pub h: f32,
+	/// The saturation component.
+	pub s: f32,
+	/// The lightness component.
+	pub l: f32,
+}
+
+
+impl Hsl {
+	/// Creates a new `Hsl` color.
+	pub const fn new(h: f32, s: f32, l: f32) -> Self {
+		Hsl {h: h, s: s, l: l}
+	}
+}
+
+
+impl Hsl {
+	/// Returns the `Hsl` color for the given sRGB channels in the range
+	/// [0, 1].
+	pub fn from_rgb(rgb: [f32; 3]) -> Self {
+		let [r, g, b] = rgb;
+		let max = r.max(g).max(b);
+		let min = r.min(g).min(b);
+		let l = (max + min) / 2.0;
+		let d = max - min;
+
+		if d <= f32::EPSILON {
+			return Hsl::new(0.0, 0.0, l);
+		}
+
+		let s = d / (1.0 - (2.0 * l - 1.0).abs());
+		let h = if max == r {
+			60.0 * (((g - b) / d) % 6.0)
+		} else if max == g {
+			60.0 * ((b - r) / d + 2.0)
+		} else {
+			60.0 * ((r - g) / d + 4.0)
+		};
+
+		Hsl::new(if h < 0.0 { h + 360.0 } else { h }, s, l)
+	}
+
+	/// Returns the sRGB channels of the color in the range [0, 1].
+	pub fn to_rgb(&self) -> [f32; 3] {
+		let h = ((self.h % 360.0) + 360.0) % 360.0;
+		let s = clamped(self.s, 0.0, 1.0);
+		let l = clamped(self.l, 0.0, 1.0);
+
+		let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+		let x = c * (1.0 - ((h / 60.0) % 2.0 - 1.0).abs());
+		let m = l - c / 2.0;
+		let (r, g, b) = match (h / 60.0) as u32 {
+			0 => (c, x, 0.0),
+			1 => (x, c, 0.0),
+			2 => (0.0, c, x),
+			3 => (0.0, x, c),
+			4 => (x, 0.0, c),
+			_ => (c, 0.0, x),
+		};
+		[r + m, g + m, b + m]
+	}
+}
+
+
+impl From<Color> for Hsl {
+	fn from(color: Color) -> Self {
+		Hsl::from_rgb(unit_channels(color))
+	}
+}
+
+
+impl From<Hsl> for Color {
+	fn from(hsl: Hsl) -> Self {
+		from_unit_channels(hsl.to_rgb())
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Hsv
+////////////////////////////////////////////////////////////////////////////////
+/// A color in the HSV color space. Hue is given in degrees in the range
+/// [0, 360), while saturation and value are in the range [0, 1].
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct Hsv {
+	/// The hue component.
+	pub h: f32,
+	/// The saturation component.
+	pub s: f32,
+	/// The value component.
+	pub v: f32,
+}
+
+
+impl Hsv {
+	/// Creates a new `Hsv` color.
+	pub const fn new(h: f32, s: f32, v: f32) -> Self {
+		Hsv {h: h, s: s, v: v}
+	}
+
+	/// Returns the `Hsv` color for the given sRGB channels in the range
+	/// [0, 1].
+	pub fn from_rgb(rgb: [f32; 3]) -> Self {
+		let hsl = Hsl::from_rgb(rgb);
+		let v = hsl.l + hsl.s * hsl.l.min(1.0 - hsl.l);
+		let s = if v > 0.0 { 2.0 * (1.0 - hsl.l / v) } else { 0.0 };
+		Hsv::new(hsl.h, s, v)
+	}
+
+	/// Returns the sRGB channels of the color, in the range [0, 1].
+	pub fn to_rgb(&self) -> [f32; 3] {
+		let s = clamped(self.s, 0.0, 1.0);
+		let v = clamped(self.v, 0.0, 1.0);
+		let l = v * (1.0 - s / 2.0);
+		let sl = if l > 0.0 && l < 1.0 { (v - l) / l.min(1.0 - l) } else { 0.0 };
+		Hsl::new(self.h, sl, l).to_rgb()
+	}
+}
+
+
+impl From<Color> for Hsv {
+	fn from(color: Color) -> Self {
+		Hsv::from_rgb(unit_channels(color))
+	}
+}
+
+
+impl From<Hsv> for Color {
+	fn from(hsv: Hsv) -> Self {
+		from_unit_channels(hsv.to_rgb())
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Xyz
+////////////////////////////////////////////////////////////////////////////////
+/// A color in the CIE 1931 XYZ color space, relative to a D65 white point.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct Xyz {
+	/// The X component.
+	pub x: f32,
+	/// The Y (luminance) component.
+	pub y: f32,
+	/// The Z component.
+	pub z: f32,
+}
+
+
+impl Xyz {
+	/// Creates a new `Xyz` color.
+	pub const fn new(x: f32, y: f32, z: f32) -> Self {
+		Xyz {x: x, y: y, z: z}
+	}
+
+	/// Creates a new `Xyz` color from linear sRGB channels.
+	pub fn from_linear_rgb(rgb: [f32; 3]) -> Self {
+		let [r, g, b] = rgb;
+		Xyz::new(
+			0.412_456_4 * r + 0.357_576_1 * g + 0.180_437_5 * b,
+			0.212_672_9 * r + 0.715_152_2 * g + 0.072_175_0 * b,
+			0.019_333_9 * r + 0.119_192_0 * g + 0.950_304_1 * b,
+		)
+	}
+
+	/// Returns the linear sRGB channels of the color. The channels are not
+	/// clamped, and will lie outside of [0, 1] for out-of-gamut colors.
+	pub fn to_linear_rgb(&self) -> [f32; 3] {
+		let (x, y, z) = (self.x, self.y, self.z);
+		[
+			 3.240_454_2 * x - 1.537_138_5 * y - 0.498_531_4 * z,
+			-0.969_266_0 * x + 1.876_010_8 * y + 0.041_556_0 * z,
+			 0.055_643_4 * x - 0.204_025_9 * y + 1.057_225_2 * z,
+		]
+	}
+}
+
+
+impl From<Color> for Xyz {
+	fn from(color: Color) -> Self {
+		let [r, g, b] = unit_channels(color);
+		Xyz::from_linear_rgb([
+			srgb_to_linear(r),
+			srgb_to_linear(g),
+			srgb_to_linear(b),
+		])
+	}
+}
+
+
+impl From<Xyz> for Color {
+	fn from(xyz: Xyz) -> Self {
+		let [r, g, b] = xyz.to_linear_rgb();
+		from_unit_channels([
+			linear_to_srgb(clamped(r, 0.0, 1.0)),
+			linear_to_srgb(clamped(g, 0.0, 1.0)),
+			linear_to_srgb(clamped(b, 0.0, 1.0)),
+		])
+	}
+}
+
+
+
+/// The D50 reference white in XYZ, used by ICC profiles and most palette
+/// file formats storing Lab values.
+const D50_WHITE: [f32; 3] = [0.964_22, 1.0, 0.825_21];
+
+
+impl Xyz {
+	/// Converts a color relative to a D50 white point into one relative to
+	/// D65 using the Bradford chromatic adaptation transform.
+	pub fn adapt_from_d50(&self) -> Xyz {
+		let v = mul3(&BRADFORD_D50_TO_D65, [self.x, self.y, self.z]);
+		Xyz::new(v[0], v[1], v[2])
+	}
+
+	/// Returns the D50-relative XYZ color for the given D50-relative Lab
+	/// components, as stored by ICC-based formats.
+	pub fn from_d50_lab(l: f32, a: f32, b: f32) -> Xyz {
+		let d65 = Xyz::from(Lab::new(l, a, b));
+		Xyz::new(
+			d65.x / D65_WHITE[0] * D50_WHITE[0],
+			d65.y / D65_WHITE[1] * D50_WHITE[1],
+			d65.z / D65_WHITE[2] * D50_WHITE[2],
+		)
+	}
+}
+
+
+/// The Bradford chromatic adaptation matrix from D50 to D65.
+const BRADFORD_D50_TO_D65: [[f32; 3]; 3] = [
+	[ 0.955_576_6, -0.023_039_3, 0.063_163_6],
+	[-0.028_289_5,  1.009_941_6, 0.021_007_7],
+	[ 0.012_298_2, -0.020_483_0, 1.329_909_8],
+];
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Lab
+////////////////////////////////////////////////////////////////////////////////
+/// A color in the CIE L*a*b* color space, relative to a D65 white point.
+/// Lightness is in the range [0, 100].
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct Lab {
+	/// The lightness component.
+	pub l: f32,
+	/// The green-red component.
+	pub a: f32,
+	/// The blue-yellow component.
+	pub b: f32,
+}
+
+
+impl Lab {
+	/// Creates a new `Lab` color.
+	pub const fn new(l: f32, a: f32, b: f32) -> Self {
+		Lab {l: l, a: a, b: b}
+	}
+
+	/// Returns the chroma of the color.
+	pub fn chroma(&self) -> f32 {
+		(self.a * self.a + self.b * self.b).sqrt()
+	}
+
+	/// Returns the hue angle of the color in degrees, in the range [0, 360).
+	pub fn hue(&self) -> f32 {
+		let h = self.b.atan2(self.a).to_degrees();
+		if h < 0.0 { h + 360.0 } else { h }
+	}
+
+	/// Returns whether the color lies within the sRGB gamut.
+	pub fn in_gamut(&self) -> bool {
+		const TOLERANCE: f32 = 1.0e-4;
+		Xyz::from(*self)
+			.to_linear_rgb()
+			.iter()
+			.all(|&v| v >= -TOLERANCE && v <= 1.0 + TOLERANCE)
+	}
+
+	/// Returns the color located at the ratio given by `amount` along the
+	/// line between this color and the given color.
+	pub fn lerp(&self, other: &Lab, amount: f32) -> Lab {
+		let t = clamped(amount, 0.0, 1.0);
+		Lab::new(
+			self.l + (other.l - self.l) * t,
+			self.a + (other.a - self.a) * t,
+			self.b + (other.b - self.b) * t,
+		)
+	}
+}
+
+
+impl From<Xyz> for Lab {
+	fn from(xyz: Xyz) -> Self {
+		let f = |t: f32| if t > LAB_EPSILON {
+			t.cbrt()
+		} else {
+			(LAB_KAPPA * t + 16.0) / 116.0
+		};
+		let fx = f(xyz.x / D65_WHITE[0]);
+		let fy = f(xyz.y / D65_WHITE[1]);
+		let fz = f(xyz.z / D65_WHITE[2]);
+
+		Lab::new(116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz))
+	}
+}
+
+
+impl From<Lab> for Xyz {
+	fn from(lab: Lab) -> Self {
+		let fy = (lab.l + 16.0) / 116.0;
+		let fx = fy + lab.a / 500.0;
+		let fz = fy - lab.b / 200.0;
+		let f_inv = |t: f32| if t * t * t > LAB_EPSILON {
+			t * t * t
+		} else {
+			(116.0 * t - 16.0) / LAB_KAPPA
+		};
+
+		Xyz::new(
+			f_inv(fx) * D65_WHITE[0],
+			f_inv(fy) * D65_WHITE[1],
+			f_inv(fz) * D65_WHITE[2],
+		)
+	}
+}
+
+
+impl From<Color> for Lab {
+	fn from(color: Color) -> Self {
+		Lab::from(Xyz::from(color))
+	}
+}
+
+
+impl From<Lab> for Color {
+	fn from(lab: Lab) -> Self {
+		Color::from(Xyz::from(lab))
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Oklab
+////////////////////////////////////////////////////////////////////////////////
+/// A color in the Oklab perceptual color space. Lightness is in the range
+/// [0, 1].
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct Oklab {
+	/// The lightness component.
+	pub l: f32,
+	/// The green-red component.
+	pub a: f32,
+	/// The blue-yellow component.
+	pub b: f32,
+}
+
+
+impl Oklab {
+	/// Creates a new `Oklab` color.
+	pub const fn new(l: f32, a: f32, b: f32) -> Self {
+		Oklab {l: l, a: a, b: b}
+	}
+
+	/// Creates a new `Oklab` color from linear sRGB channels.
+	pub fn from_linear_rgb(rgb: [f32; 3]) -> Self {
+		let [r, g, b] = rgb;
+		let l = (0.412_221_47 * r + 0.536_332_55 * g + 0.051_445_99 * b).cbrt();
+		let m = (0.211_903_5 * r + 0.680_699_5 * g + 0.107_396_96 * b).cbrt();
+		let s = (0.088_302_46 * r + 0.281_718_85 * g + 0.629_978_7 * b).cbrt();
+		Oklab::new(
+			0.210_454_26 * l + 0.793_617_8 * m - 0.004_072_047 * s,
+			1.977_998_5 * l - 2.428_592_2 * m + 0.450_593_7 * s,
+			0.025_904_037 * l + 0.782_771_77 * m - 0.808_675_77 * s,
+		)
+	}
+
+	/// Returns the linear sRGB channels of the color. The channels are not
+	/// clamped, and will lie outside of [0, 1] for out-of-gamut colors.
+	pub fn to_linear_rgb(&self) -> [f32; 3] {
+		let l = self.l + 0.396_337_78 * self.a + 0.215_803_76 * self.b;
+		let m = self.l - 0.105_561_346 * self.a - 0.063_854_17 * self.b;
+		let s = self.l - 0.089_484_18 * self.a - 1.291_485_5 * self.b;
+		let (l, m, s) = (l * l * l, m * m * m, s * s * s);
+		[
+			 4.076_741_7 * l - 3.307_711_6 * m + 0.230_969_94 * s,
+			-1.268_438 * l + 2.609_757_4 * m - 0.341_319_38 * s,
+			-0.004_196_086_3 * l - 0.703_418_6 * m + 1.707_614_7 * s,
+		]
+	}
+
+	/// Returns the Euclidean distance between this color and the given color,
+	/// which approximates their perceptual difference.
+	pub fn distance(&self, other: &Oklab) -> f32 {
+		let (dl, da, db) = (self.l - other.l, self.a - other.a, self.b - other.b);
+		(dl * dl + da * da + db * db).sqrt()
+	}
+
+	/// Returns the index of the color in the given slice nearest to this 
+	/// color, or None if the slice is empty.
+	pub fn nearest(&self, candidates: &[Oklab]) -> Option<usize> {
+		candidates.iter()
+			.map(|c| self.distance(c))
+			.enumerate()
+			.fold(None, |best: Option<(usize, f32)>, (i, d)| match best {
+				Some((_, bd)) if bd <= d => best,
+				_ => Some((i, d)),
+			})
+			.map(|(i, _)| i)
+	}
+
+	/// Returns the color located at the ratio given by `amount` along the
+	/// line between this color and the given color.
+	pub fn lerp(&self, other: &Oklab, amount: f32) -> Oklab {
+		let t = clamped(amount, 0.0, 1.0);
+		Oklab::new(
+			self.l + (other.l - self.l) * t,
+			self.a + (other.a - self.a) * t,
+			self.b + (other.b - self.b) * t,
+		)
+	}
+}
+
+
+impl From<Color> for Oklab {
+	fn from(color: Color) -> Self {
+		let [r, g, b] = unit_channels(color);
+		Oklab::from_linear_rgb([
+			srgb_to_linear(r),
+			srgb_to_linear(g),
+			srgb_to_linear(b),
+		])
+	}
+}
+
+
+impl From<Oklab> for Xyz {
+	fn from(oklab: Oklab) -> Self {
+		Xyz::from_linear_rgb(oklab.to_linear_rgb())
+	}
+}
+
+
+impl From<Oklab> for Lab {
+	fn from(oklab: Oklab) -> Self {
+		Lab::from(Xyz::from(oklab))
+	}
+}
+
+
+impl From<Oklab> for Color {
+	fn from(oklab: Oklab) -> Self {
+		Color::from(Xyz::from(oklab))
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Luv
+////////////////////////////////////////////////////////////////////////////////
+/// A color in the CIE L*u*v* color space, relative to a D65 white point.
+/// Lightness is in the range [0, 100].
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct Luv {
+	/// The lightness component.
+	pub l: f32,
+	/// The green-red component.
+	pub u: f32,
+	/// The blue-yellow component.
+	pub v: f32,
+}
+
+
+impl Luv {
+	/// Creates a new `Luv` color.
+	pub const fn new(l: f32, u: f32, v: f32) -> Self {
+		Luv {l: l, u: u, v: v}
+	}
+
+	/// Creates a new `Luv` color from its lightness, chroma, and hue angle in
+	/// degrees.
+	pub fn from_lch(l: f32, c: f32, h: f32) -> Self {
+		let (sin, cos) = h.to_radians().sin_cos();
+		Luv::new(l, c * cos, c * sin)
+	}
+
+	/// Returns the chroma of the color.
+	pub fn chroma(&self) -> f32 {
+		(self.u * self.u + self.v * self.v).sqrt()
+	}
+
+	/// Returns the hue angle of the color in degrees, in the range [0, 360).
+	pub fn hue(&self) -> f32 {
+		let h = self.v.atan2(self.u).to_degrees();
+		if h < 0.0 { h + 360.0 } else { h }
+	}
+}
+
+
+/// Returns the u'v' chromaticity of the D65 white point.
+fn white_chromaticity() -> (f32, f32) {
+	let [x, y, z] = D65_WHITE;
+	let d = x + 15.0 * y + 3.0 * z;
+	(4.0 * x / d, 9.0 * y / d)
+}
+
+
+impl From<Xyz> for Luv {
+	fn from(xyz: Xyz) -> Self {
+		let y = xyz.y / D65_WHITE[1];
+		let l = if y > LAB_EPSILON {
+			116.0 * y.cbrt() - 16.0
+		} else {
+			LAB_KAPPA * y
+		};
+		let d = xyz.x + 15.0 * xyz.y + 3.0 * xyz.z;
+		if l <= 0.0 || d <= 0.0 {
+			return Luv::new(l, 0.0, 0.0);
+		}
+
+		let (un, vn) = white_chromaticity();
+		Luv::new(
+			l,
+			13.0 * l * (4.0 * xyz.x / d - un),
+			13.0 * l * (9.0 * xyz.y / d - vn),
+		)
+	}
+}
+
+
+impl From<Luv> for Xyz {
+	fn from(luv: Luv) -> Self {
+		if luv.l <= 0.0 {
+			return Xyz::new(0.0, 0.0, 0.0);
+		}
+
+		let y = D65_WHITE[1] * if luv.l > LAB_KAPPA * LAB_EPSILON {
+			((luv.l + 16.0) / 116.0).powi(3)
+		} else {
+			luv.l / LAB_KAPPA
+		};
+		let (un, vn) = white_chromaticity();
+		let u = luv.u / (13.0 * luv.l) + un;
+		let v = luv.v / (13.0 * luv.l) + vn;
+
+		Xyz::new(
+			y * 9.0 * u / (4.0 * v),
+			y,
+			y * (12.0 - 3.0 * u - 20.0 * v) / (4.0 * v),
+		)
+	}
+}
+
+
+impl From<Color> for Luv {
+	fn from(color: Color) -> Self {
+		Luv::from(Xyz::from(color))
+	}
+}
+
+
+impl From<Luv> for Color {
+	fn from(luv: Luv) -> Self {
+		Color::from(Xyz::from(luv))
+	}
+}
+
+
+/// The lightness within which a color is treated as black or white by the
+/// HSLuv and HPLuv spaces.
+const LUV_LIGHTNESS_TOLERANCE: f32 = 1.0e-4;
+
+
+/// Returns the six lines bounding the sRGB gamut in the u*v* plane at the
+/// given lightness, as the coefficients `[a, b, c]` of `a·u + b·v + c = 0`.
+/// Each line is where one linear sRGB channel reaches 0 or 1.
+fn luv_gamut_bounds(l: f32) -> [[f32; 3]; 6] {
+	let y = Xyz::from(Luv::new(l, 0.0, 0.0)).y;
+	let (un, vn) = white_chromaticity();
+	let columns = [
+		Xyz::new(1.0, 0.0, 0.0).to_linear_rgb(),
+		Xyz::new(0.0, 1.0, 0.0).to_linear_rgb(),
+		Xyz::new(0.0, 0.0, 1.0).to_linear_rgb(),
+	];
+
+	let mut bounds = [[0.0; 3]; 6];
+	for channel in 0..3 {
+		let m1 = columns[0][channel];
+		let m2 = columns[1][channel];
+		let m3 = columns[2][channel];
+		for (i, &t) in [0.0, 1.0].iter().enumerate() {
+			// The channel in terms of the chromaticity u'v', scaled by 4v'.
+			let du = (9.0 * m1 - 3.0 * m3) * y;
+			let dv = (4.0 * m2 - 20.0 * m3) * y - 4.0 * t;
+			bounds[channel * 2 + i] = [
+				du / (13.0 * l),
+				dv / (13.0 * l),
+				du * un + dv * vn + 12.0 * m3 * y,
+			];
+		}
+	}
+	bounds
+}
+
+
+/// Returns the largest chroma of an sRGB color with the given lightness and
+/// hue angle in degrees.
+fn luv_max_chroma(l: f32, h: f32) -> f32 {
+	let (sin, cos) = h.to_radians().sin_cos();
+	luv_gamut_bounds(l).iter()
+		.map(|&[a, b, c]| -c / (a * cos + b * sin))
+		.filter(|r| r.is_finite() && *r >= 0.0)
+		.fold(f32::INFINITY, f32::min)
+}
+
+
+/// Returns the largest chroma for which every hue at the given lightness lies
+/// within the sRGB gamut.
+fn luv_max_safe_chroma(l: f32) -> f32 {
+	luv_gamut_bounds(l).iter()
+		.map(|&[a, b, c]| c.abs() / (a * a + b * b).sqrt())
+		.fold(f32::INFINITY, f32::min)
+}
+
+
+/// Interpolates between two hue, saturation, and lightness triples, taking the
+/// shortest path around the hue circle. An achromatic end takes its hue from
+/// the other end, so that ramps to and from grays keep a single hue.
+fn lerp_polar(start: [f32; 3], end: [f32; 3], amount: f32) -> [f32; 3] {
+	const ACHROMATIC: f32 = 1.0e-2;
+	let t = clamped(amount, 0.0, 1.0);
+	let [mut h0, s0, l0] = start;
+	let [mut h1, s1, l1] = end;
+	if s0 < ACHROMATIC { h0 = h1; }
+	if s1 < ACHROMATIC { h1 = h0; }
+
+	let mut dh = h1 - h0;
+	if dh > 180.0 { dh -= 360.0; }
+	if dh < -180.0 { dh += 360.0; }
+	let h = (h0 + dh * t + 360.0) % 360.0;
+	[h, s0 + (s1 - s0) * t, l0 + (l1 - l0) * t]
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Hsluv
+////////////////////////////////////////////////////////////////////////////////
+/// A color in the HSLuv color space, a cylindrical form of CIE L*u*v* whose
+/// saturation is scaled to the sRGB gamut. Unlike HSL, colors with equal
+/// lightness have equal perceived lightness. Hue is given in degrees in the
+/// range [0, 360), while saturation and lightness are in the range [0, 100].
+///
+/// # Example
+///
+/// ```rust
+/// use palette::Color;
+/// use palette::space::{Hsluv, Lab};
+///
+/// let red = Hsluv::from(Color::new(255, 0, 0));
+/// assert!((red.h - 12.18).abs() < 0.05);
+/// assert!((red.s - 100.0).abs() < 0.01);
+/// assert!((red.l - 53.24).abs() < 0.01);
+///
+/// let teal = Color::new(0, 128, 128);
+/// assert_eq!(Color::from(Hsluv::from(teal)), teal);
+///
+/// // Equal lightness looks equally light, whatever the hue.
+/// let orange = Lab::from(Color::from(Hsluv::new(40.0, 100.0, 60.0)));
+/// let blue = Lab::from(Color::from(Hsluv::new(250.0, 100.0, 60.0)));
+/// assert!((orange.l - blue.l).abs() < 0.5);
+/// ```
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct Hsluv {
+	/// The hue component.
+	pub h: f32,
+	/// The saturation component.
+	pub s: f32,
+	/// The lightness component.
+	pub l: f32,
+}
+
+
+impl Hsluv {
+	/// Creates a new `Hsluv` color.
+	pub const fn new(h: f32, s: f32, l: f32) -> Self {
+		Hsluv {h: h, s: s, l: l}
+	}
+
+	/// Returns the `Hsluv` color for the given `Luv` color.
+	pub fn from_luv(luv: Luv) -> Self {
+		let h = luv.hue();
+		if luv.l > 100.0 - LUV_LIGHTNESS_TOLERANCE {
+			return Hsluv::new(h, 0.0, 100.0);
+		}
+		if luv.l < LUV_LIGHTNESS_TOLERANCE {
+			return Hsluv::new(h, 0.0, 0.0);
+		}
+		Hsluv::new(h, luv.chroma() / luv_max_chroma(luv.l, h) * 100.0, luv.l)
+	}
+
+	/// Returns the `Luv` color for this color. Saturation and lightness are
+	/// clamped, so the color always lies within the sRGB gamut.
+	pub fn to_luv(&self) -> Luv {
+		let h = ((self.h % 360.0) + 360.0) % 360.0;
+		let s = clamped(self.s, 0.0, 100.0);
+		let l = clamped(self.l, 0.0, 100.0);
+		if l > 100.0 - LUV_LIGHTNESS_TOLERANCE || l < LUV_LIGHTNESS_TOLERANCE {
+			return Luv::new(l, 0.0, 0.0);
+		}
+		Luv::from_lch(l, luv_max_chroma(l, h) * s / 100.0, h)
+	}
+
+	/// Returns the color located at the ratio given by `amount` between this
+	/// color and the given color, taking the shortest path around the hue
+	/// circle.
+	pub fn lerp(&self, other: &Hsluv, amount: f32) -> Hsluv {
+		let [h, s, l] = lerp_polar(
+			[self.h, self.s, self.l],
+			[other.h, other.s, other.l],
+			amount);
+		Hsluv::new(h, s, l)
+	}
+}
+
+
+impl From<Xyz> for Hsluv {
+	fn from(xyz: Xyz) -> Self {
+		Hsluv::from_luv(Luv::from(xyz))
+	}
+}
+
+
+impl From<Hsluv> for Xyz {
+	fn from(hsluv: Hsluv) -> Self {
+		Xyz::from(hsluv.to_luv())
+	}
+}
+
+
+impl From<Color> for Hsluv {
+	fn from(color: Color) -> Self {
+		Hsluv::from(Xyz::from(color))
+	}
+}
+
+
+impl From<Hsluv> for Color {
+	fn from(hsluv: Hsluv) -> Self {
+		Color::from(Xyz::from(hsluv))
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Hpluv
+////////////////////////////////////////////////////////////////////////////////
+/// A color in the HPLuv color space, a variant of HSLuv whose saturation is
+/// scaled to the largest chroma available at every hue for a lightness. It
+/// covers only pastel colors, but keeps chroma as well as lightness uniform
+/// across hues. Hue is given in degrees in the range [0, 360), while
+/// saturation and lightness are in the range [0, 100]. sRGB colors more
+/// saturated than the pastel range have a saturation above 100.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::Color;
+/// use palette::space::Hpluv;
+///
+/// let rose = Color::new(230, 180, 190);
+/// assert_eq!(Color::from(Hpluv::from(rose)), rose);
+/// assert!(Hpluv::from(Color::new(255, 0, 0)).p > 100.0);
+///
+/// // Equal saturation gives equal chroma, whatever the hue.
+/// let a = Hpluv::new(30.0, 80.0, 60.0).to_luv();
+/// let b = Hpluv::new(250.0, 80.0, 60.0).to_luv();
+/// assert!((a.chroma() - b.chroma()).abs() < 1.0e-3);
+/// ```
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct Hpluv {
+	/// The hue component.
+	pub h: f32,
+	/// The saturation component.
+	pub p: f32,
+	/// The lightness component.
+	pub l: f32,
+}
+
+
+impl Hpluv {
+	/// Creates a new `Hpluv` color.
+	pub const fn new(h: f32, p: f32, l: f32) -> Self {
+		Hpluv {h: h, p: p, l: l}
+	}
+
+	/// Returns the `Hpluv` color for the given `Luv` color.
+	pub fn from_luv(luv: Luv) -> Self {
+		let h = luv.hue();
+		if luv.l > 100.0 - LUV_LIGHTNESS_TOLERANCE {
+			return Hpluv::new(h, 0.0, 100.0);
+		}
+		if luv.l < LUV_LIGHTNESS_TOLERANCE {
+			return Hpluv::new(h, 0.0, 0.0);
+		}
+		Hpluv::new(h, luv.chroma() / luv_max_safe_chroma(luv.l) * 100.0, luv.l)
+	}
+
+	/// Returns the `Luv` color for this color. Lightness is clamped, but
+	/// saturation is not, and saturations above 100 may lie outside of the
+	/// sRGB gamut.
+	pub fn to_luv(&self) -> Luv {
+		let h = ((self.h % 360.0) + 360.0) % 360.0;
+		let p = self.p.max(0.0);
+		let l = clamped(self.l, 0.0, 100.0);
+		if l > 100.0 - LUV_LIGHTNESS_TOLERANCE || l < LUV_LIGHTNESS_TOLERANCE {
+			return Luv::new(l, 0.0, 0.0);
+		}
+		Luv::from_lch(l, luv_max_safe_chroma(l) * p / 100.0, h)
+	}
+
+	/// Returns the color located at the ratio given by `amount` between this
+	/// color and the given color, taking the shortest path around the hue
+	/// circle.
+	pub fn lerp(&self, other: &Hpluv, amount: f32) -> Hpluv {
+		let [h, p, l] = lerp_polar(
+			[self.h, self.p, self.l],
+			[other.h, other.p, other.l],
+			amount);
+		Hpluv::new(h, p, l)
+	}
+}
+
+
+impl From<Xyz> for Hpluv {
+	fn from(xyz: Xyz) -> Self {
+		Hpluv::from_luv(Luv::from(xyz))
+	}
+}
+
+
+impl From<Hpluv> for Xyz {
+	fn from(hpluv: Hpluv) -> Self {
+		Xyz::from(hpluv.to_luv())
+	}
+}
+
+
+impl From<Color> for Hpluv {
+	fn from(color: Color) -> Self {
+		Hpluv::from(Xyz::from(color))
+	}
+}
+
+
+impl From<Hpluv> for Color {
+	fn from(hpluv: Hpluv) -> Self {
+		Color::from(Xyz::from(hpluv))
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Surround
+////////////////////////////////////////////////////////////////////////////////
+/// The luminance of the surround of a viewed color, relative to the white 
+/// point, as used by the CAM16 color appearance model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Surround {
+	/// A surround similar to the viewed colors, as for reflective prints or
+	/// a display in a lit room.
+	Average,
+	/// A dim surround, as for television viewed in a dim room.
+	Dim,
+	/// A dark surround, as for a projector in a darkened room.
+	Dark,
+}
+
+
+impl Surround {
+	/// Returns all of the surrounds.
+	pub fn all() -> &'static [Surround] {
+		&[Surround::Average, Surround::Dim, Surround::Dark]
+	}
+
+	/// Returns the name of the surround.
+	pub fn name(&self) -> &'static str {
+		match *self {
+			Surround::Average => "average",
+			Surround::Dim     => "dim",
+			Surround::Dark    => "dark",
+		}
+	}
+
+	/// Returns the surround with the given name, ignoring case.
+	pub fn from_name(name: &str) -> Option<Surround> {
+		Surround::all()
+			.iter()
+			.find(|s| s.name().eq_ignore_ascii_case(name))
+			.cloned()
+	}
+
+	/// Returns the degree of adaptation factor `F`, the impact of the 
+	/// surround `c`, and the chromatic induction factor `Nc`.
+	fn factors(&self) -> (f32, f32, f32) {
+		match *self {
+			Surround::Average => (1.0, 0.69, 1.0),
+			Surround::Dim     => (0.9, 0.59, 0.9),
+			Surround::Dark    => (0.8, 0.525, 0.8),
+		}
+	}
+}
+
+
+impl Default for Surround {
+	fn default() -> Self {
+		Surround::Average
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// ViewingConditions
+////////////////////////////////////////////////////////////////////////////////
+/// The conditions under which colors are viewed, which determine their 
+/// appearance in the CAM16 color appearance model.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::Color;
+/// use palette::space::{Cam16, Surround, ViewingConditions, Xyz};
+///
+/// let gray = Color::new(119, 119, 119);
+/// let dim = ViewingConditions::SRGB.surround(Surround::Dim);
+/// let bright = ViewingConditions::SRGB.adapting_luminance(1000.0);
+///
+/// // The same gray looks lighter in a dim room, and brighter, though no 
+/// // lighter, in bright light.
+/// let standard = Cam16::from_xyz(Xyz::from(gray), &ViewingConditions::SRGB);
+/// assert!(Cam16::from_xyz(Xyz::from(gray), &dim).j > standard.j);
+/// assert!(Cam16::from_xyz(Xyz::from(gray), &bright).q > standard.q);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ViewingConditions {
+	/// The white point. Only its chromaticity is used.
+	pub white: Xyz,
+	/// The luminance of the adapting field, in cd/m².
+	pub adapting_luminance: f32,
+	/// The luminance of the background, as a percentage of the white.
+	pub background_luminance: f32,
+	/// The surround.
+	pub surround: Surround,
+}
+
+
+impl ViewingConditions {
+	/// The conditions of a typical sRGB display: a D65 white, an adapting 
+	/// luminance of about 11.7 cd/m², a mid-gray background with an L* of 
+	/// 50, and an average surround.
+	pub const SRGB: ViewingConditions = ViewingConditions {
+		white: Xyz::new(D65_WHITE[0], D65_WHITE[1], D65_WHITE[2]),
+		adapting_luminance: 11.725_677,
+		background_luminance: 18.418_652,
+		surround: Surround::Average,
+	};
+
+	/// Creates new `ViewingConditions` with the given white point, adapting
+	/// luminance in cd/m², background luminance as a percentage of the 
+	/// white, and surround.
+	pub const fn new(
+		white: Xyz,
+		adapting_luminance: f32,
+		background_luminance: f32,
+		surround: Surround)
+		-> Self
+	{
+		ViewingConditions {
+			white: white,
+			adapting_luminance: adapting_luminance,
+			background_luminance: background_luminance,
+			surround: surround,
+		}
+	}
+
+	/// Sets the white point. Only its chromaticity is used.
+	pub const fn white(mut self, white: Xyz) -> Self {
+		self.white = white;
+		self
+	}
+
+	/// Sets the luminance of the adapting field, in cd/m².
+	pub const fn adapting_luminance(mut self, luminance: f32) -> Self {
+		self.adapting_luminance = luminance;
+		self
+	}
+
+	/// Sets the luminance of the background, as a percentage of the white.
+	pub const fn background_luminance(mut self, luminance: f32) -> Self {
+		self.background_luminance = luminance;
+		self
+	}
+
+	/// Sets the surround.
+	pub const fn surround(mut self, surround: Surround) -> Self {
+		self.surround = surround;
+		self
+	}
+
+	/// Computes the parameters of the model derived from the conditions.
+	fn parameters(&self) -> Cam16Parameters {
+		let (f, c, nc) = self.surround.factors();
+		let la = self.adapting_luminance.max(0.0);
+		let white = [
+			self.white.x / self.white.y * 100.0, 
+			100.0, 
+			self.white.z / self.white.y * 100.0,
+		];
+		let rgb_w = mul3(&M16, white);
+
+		let d = clamped(f * (1.0 - (1.0 / 3.6) * ((-la - 42.0) / 92.0).exp()),
+			0.0, 1.0);
+		let rgb_d = [
+			d * 100.0 / rgb_w[0] + 1.0 - d,
+			d * 100.0 / rgb_w[1] + 1.0 - d,
+			d * 100.0 / rgb_w[2] + 1.0 - d,
+		];
+
+		let k = 1.0 / (5.0 * la + 1.0);
+		let k4 = k * k * k * k;
+		let fl = k4 * la + 0.1 * (1.0 - k4) * (1.0 - k4) * (5.0 * la).cbrt();
+		let n = self.background_luminance.max(1.0e-4) / 100.0;
+		let nbb = 0.725 / n.powf(0.2);
+		let z = 1.48 + n.sqrt();
+
+		let rgb_aw = [
+			adapt(rgb_w[0] * rgb_d[0], fl),
+			adapt(rgb_w[1] * rgb_d[1], fl),
+			adapt(rgb_w[2] * rgb_d[2], fl),
+		];
+		let aw = (2.0 * rgb_aw[0] + rgb_aw[1] + 0.05 * rgb_aw[2]) * nbb;
+
+		Cam16Parameters {
+			n: n,
+			aw: aw,
+			nbb: nbb,
+			c: c,
+			nc: nc,
+			z: z,
+			fl: fl,
+			rgb_d: rgb_d,
+		}
+	}
+}
+
+
+impl Default for ViewingConditions {
+	fn default() -> Self {
+		ViewingConditions::SRGB
+	}
+}
+
+
+/// The parameters of the CAM16 model derived from a set of viewing 
+/// conditions.
+#[derive(Debug, Clone, Copy)]
+struct Cam16Parameters {
+	/// The background induction factor.
+	n: f32,
+	/// The achromatic response of the white.
+	aw: f32,
+	/// The brightness and chromatic background induction factor.
+	nbb: f32,
+	/// The impact of the surround.
+	c: f32,
+	/// The chromatic induction factor.
+	nc: f32,
+	/// The base exponential nonlinearity.
+	z: f32,
+	/// The luminance level adaptation factor.
+	fl: f32,
+	/// The degree of adaptation of each cone response.
+	rgb_d: [f32; 3],
+}
+
+
+/// The CAM16 matrix from XYZ to cone responses.
+const M16: [[f32; 3]; 3] = [
+	[ 0.401_288,  0.650_173, -0.051_461],
+	[-0.250_268,  1.204_414,  0.045_854],
+	[-0.002_079,  0.048_952,  0.953_127],
+];
+
+/// The CAM16 matrix from cone responses to XYZ.
+const M16_INVERSE: [[f32; 3]; 3] = [
+	[ 1.862_067_9, -1.011_254_6,  0.149_186_77],
+	[ 0.387_526_5,  0.621_447_4, -0.008_973_985],
+	[-0.015_841_5, -0.034_122_94,  1.049_964_4],
+];
+
+
+/// Applies the post-adaptation nonlinear compression to a cone response.
+fn adapt(value: f32, fl: f32) -> f32 {
+	let v = (fl * value.abs() / 100.0).powf(0.42);
+	value.signum() * 400.0 * v / (v + 27.13)
+}
+
+
+/// Inverts the post-adaptation nonlinear compression of a cone response.
+fn unadapt(value: f32, fl: f32) -> f32 {
+	let v = value.abs();
+	let base = (27.13 * v / (400.0 - v)).max(0.0);
+	value.signum() * 100.0 / fl * base.powf(1.0 / 0.42)
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Cam16
+////////////////////////////////////////////////////////////////////////////////
+/// A color's appearance under a set of viewing conditions, as described by
+/// the CAM16 color appearance model.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::Color;
+/// use palette::space::{Cam16, ViewingConditions, Xyz};
+///
+/// let conditions = ViewingConditions::SRGB;
+/// let red = Cam16::from_xyz(Xyz::from(Color::new(255, 0, 0)), &conditions);
+/// assert!((red.j - 46.445).abs() < 0.01);
+/// assert!((red.c - 113.357).abs() < 0.01);
+/// assert!((red.h - 27.408).abs() < 0.01);
+/// assert!((red.s - 91.885).abs() < 0.01);
+///
+/// let teal = Cam16::from_xyz(Xyz::from(Color::new(0, 128, 128)), &conditions);
+/// assert!((teal.s - 56.950).abs() < 0.01);
+/// assert!((teal.s - 100.0 * (teal.m / teal.q).sqrt()).abs() < 0.01);
+///
+/// let xyz = Cam16::new(red.j, red.c, red.h).to_xyz(&conditions);
+/// assert_eq!(Color::from(xyz), Color::new(255, 0, 0));
+/// ```
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct Cam16 {
+	/// The lightness `J`, in the range [0, 100].
+	pub j: f32,
+	/// The chroma `C`.
+	pub c: f32,
+	/// The hue angle `h` in degrees, in the range [0, 360).
+	pub h: f32,
+	/// The colorfulness `M`.
+	pub m: f32,
+	/// The saturation `s`.
+	pub s: f32,
+	/// The brightness `Q`.
+	pub q: f32,
+}
+
+
+impl Cam16 {
+	/// Creates a new `Cam16` color from its lightness, chroma, and hue. The 
+	/// other correlates are left zero, as they depend on the viewing 
+	/// conditions; use `to_xyz` and `from_xyz` to compute them.
+	pub const fn new(j: f32, c: f32, h: f32) -> Self {
+		Cam16 {j: j, c: c, h: h, m: 0.0, s: 0.0, q: 0.0}
+	}
+
+	/// Returns the appearance of the given color under the given viewing 
+	/// conditions.
+	pub fn from_xyz(xyz: Xyz, conditions: &ViewingConditions) -> Self {
+		Cam16::from_xyz_with(xyz, &conditions.parameters())
+	}
+
+	/// Returns the appearance of the given color for the given parameters.
+	fn from_xyz_with(xyz: Xyz, p: &Cam16Parameters) -> Self {
+		let rgb = mul3(&M16, [xyz.x * 100.0, xyz.y * 100.0, xyz.z * 100.0]);
+		let [r, g, b] = [
+			adapt(rgb[0] * p.rgb_d[0], p.fl),
+			adapt(rgb[1] * p.rgb_d[1], p.fl),
+			adapt(rgb[2] * p.rgb_d[2], p.fl),
+		];
+
+		let a = (11.0 * r - 12.0 * g + b) / 11.0;
+		let bb = (r + g - 2.0 * b) / 9.0;
+		let h = bb.atan2(a).to_degrees();
+		let h = if h < 0.0 { h + 360.0 } else { h };
+
+		let achromatic = (2.0 * r + g + 0.05 * b) * p.nbb;
+		let j = 100.0 * (achromatic / p.aw).max(0.0).powf(p.c * p.z);
+		let fl_root = p.fl.powf(0.25);
+		let q = 4.0 / p.c * (j / 100.0).sqrt() * (p.aw + 4.0) * fl_root;
+
+		let e_hue = 0.25 * ((h.to_radians() + 2.0).cos() + 3.8);
+		let u = (20.0 * r + 20.0 * g + 21.0 * b) / 20.0;
+		let t = 50000.0 / 13.0 * e_hue * p.nc * p.nbb 
+			* (a * a + bb * bb).sqrt() / (u + 0.305);
+		let alpha = t.powf(0.9) * (1.64 - 0.29f32.powf(p.n)).powf(0.73);
+		let c = alpha * (j / 100.0).sqrt();
+		let m = c * fl_root;
+		let s = if q > 0.0 { 100.0 * (m / q).sqrt() } else { 0.0 };
+
+		Cam16 {j: j, c: c, h: h, m: m, s: s, q: q}
+	}
+
+	/// Returns the XYZ color with this appearance under the given viewing 
+	/// conditions, using the lightness, chroma, and hue. The color may lie 
+	/// outside of the sRGB gamut.
+	pub fn to_xyz(&self, conditions: &ViewingConditions) -> Xyz {
+		self.to_xyz_with(&conditions.parameters())
+	}
+
+	/// Returns the XYZ color with this appearance for the given parameters.
+	fn to_xyz_with(&self, p: &Cam16Parameters) -> Xyz {
+		if self.j <= 0.0 { return Xyz::default(); }
+
+		let alpha = self.c.max(0.0) / (self.j / 100.0).sqrt();
+		let t = (alpha / (1.64 - 0.29f32.powf(p.n)).powf(0.73))
+			.powf(1.0 / 0.9);
+		let hr = self.h.to_radians();
+		let e_hue = 0.25 * ((hr + 2.0).cos() + 3.8);
+		let achromatic = p.aw * (self.j / 100.0).powf(1.0 / (p.c * p.z));
+		let p1 = e_hue * (50000.0 / 13.0) * p.nc * p.nbb;
+		let p2 = achromatic / p.nbb;
+
+		let (sin, cos) = hr.sin_cos();
+		let gamma = 23.0 * (p2 + 0.305) * t 
+			/ (23.0 * p1 + 11.0 * t * cos + 108.0 * t * sin);
+		let (a, b) = (gamma * cos, gamma * sin);
+		let rgb = [
+			unadapt((460.0 * p2 + 451.0 * a + 288.0 * b) / 1403.0, p.fl) 
+				/ p.rgb_d[0],
+			unadapt((460.0 * p2 - 891.0 * a - 261.0 * b) / 1403.0, p.fl) 
+				/ p.rgb_d[1],
+			unadapt((460.0 * p2 - 220.0 * a - 6300.0 * b) / 1403.0, p.fl) 
+				/ p.rgb_d[2],
+		];
+		let [x, y, z] = mul3(&M16_INVERSE, rgb);
+		Xyz::new(x / 100.0, y / 100.0, z / 100.0)
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Cam16Ucs
+////////////////////////////////////////////////////////////////////////////////
+/// A color in the CAM16-UCS uniform color space, in which euclidean distance
+/// approximates perceived color difference under a set of viewing 
+/// conditions. Lightness is in the range [0, 100].
+///
+/// # Example
+///
+/// ```rust
+/// use palette::Color;
+/// use palette::space::{Cam16Ucs, ViewingConditions};
+///
+/// let conditions = ViewingConditions::SRGB;
+/// let teal = Color::new(0, 128, 128);
+/// let ucs = Cam16Ucs::from_color(teal, &conditions);
+/// assert_eq!(ucs.to_color(&conditions), teal);
+///
+/// let gray = Cam16Ucs::from(Color::new(128, 128, 128));
+/// let near = Cam16Ucs::from(Color::new(130, 128, 128));
+/// assert!(gray.distance(&near) < 2.0);
+/// assert!(gray.distance(&ucs) > 10.0);
+/// ```
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct Cam16Ucs {
+	/// The lightness component `J'`.
+	pub j: f32,
+	/// The green-red component `a'`.
+	pub a: f32,
+	/// The blue-yellow component `b'`.
+	pub b: f32,
+}
+
+
+impl Cam16Ucs {
+	/// Creates a new `Cam16Ucs` color.
+	pub const fn new(j: f32, a: f32, b: f32) -> Self {
+		Cam16Ucs {j: j, a: a, b: b}
+	}
+
+	/// Returns the coordinates of the given color appearance.
+	pub fn from_cam16(cam: &Cam16, conditions: &ViewingConditions) -> Self {
+		Cam16Ucs::from_cam16_with(cam, &conditions.parameters())
+	}
+
+	/// Returns the coordinates of the given appearance for the given 
+	/// parameters.
+	fn from_cam16_with(cam: &Cam16, p: &Cam16Parameters) -> Self {
+		// Recompute colorfulness, which `Cam16::new` leaves unset.
+		let m = cam.c * p.fl.powf(0.25);
+		let mp = (1.0 + 0.0228 * m).ln() / 0.0228;
+		let (sin, cos) = cam.h.to_radians().sin_cos();
+		Cam16Ucs::new(1.7 * cam.j / (1.0 + 0.007 * cam.j), mp * cos, mp * sin)
+	}
+
+	/// Returns the color appearance with these coordinates.
+	pub fn to_cam16(&self, conditions: &ViewingConditions) -> Cam16 {
+		self.to_cam16_with(&conditions.parameters())
+	}
+
+	/// Returns the color appearance with these coordinates for the given 
+	/// parameters. Only the lightness, chroma, and hue are set.
+	fn to_cam16_with(&self, p: &Cam16Parameters) -> Cam16 {
+		let j = self.j / (1.7 - 0.007 * self.j);
+		let mp = (self.a * self.a + self.b * self.b).sqrt();
+		let m = ((0.0228 * mp).exp() - 1.0) / 0.0228;
+		let h = self.b.atan2(self.a).to_degrees();
+		let h = if h < 0.0 { h + 360.0 } else { h };
+		Cam16::new(j, m / p.fl.powf(0.25), h)
+	}
+
+	/// Returns the coordinates of the given color under the given viewing 
+	/// conditions.
+	pub fn from_xyz(xyz: Xyz, conditions: &ViewingConditions) -> Self {
+		let p = conditions.parameters();
+		Cam16Ucs::from_cam16_with(&Cam16::from_xyz_with(xyz, &p), &p)
+	}
+
+	/// Returns the XYZ color with these coordinates under the given viewing
+	/// conditions. The color may lie outside of the sRGB gamut.
+	pub fn to_xyz(&self, conditions: &ViewingConditions) -> Xyz {
+		let p = conditions.parameters();
+		self.to_cam16_with(&p).to_xyz_with(&p)
+	}
+
+	/// Returns the coordinates of the given color under the given viewing 
+	/// conditions.
+	pub fn from_color(color: Color, conditions: &ViewingConditions) -> Self {
+		Cam16Ucs::from_xyz(Xyz::from(color), conditions)
+	}
+
+	/// Returns the color with these coordinates under the given viewing 
+	/// conditions, clamped into the sRGB gamut.
+	pub fn to_color(&self, conditions: &ViewingConditions) -> Color {
+		Color::from(self.to_xyz(conditions))
+	}
+
+	/// Returns the CAM16-UCS color difference between this color and the 
+	/// given color. This is the euclidean distance `ΔE'` between them, with
+	/// the power correction `1.41·ΔE'^0.63` applied.
+	pub fn distance(&self, other: &Cam16Ucs) -> f32 {
+		let (dj, da) = (self.j - other.j, self.a - other.a);
+		let db = self.b - other.b;
+		1.41 * (dj * dj + da * da + db * db).sqrt().powf(0.63)
+	}
+
+	/// Returns the color located at the ratio given by `amount` along the
+	/// line between this color and the given color.
+	pub fn lerp(&self, other: &Cam16Ucs, amount: f32) -> Cam16Ucs {
+		let t = clamped(amount, 0.0, 1.0);
+		Cam16Ucs::new(
+			self.j + (other.j - self.j) * t,
+			self.a + (other.a - self.a) * t,
+			self.b + (other.b - self.b) * t,
+		)
+	}
+}
+
+
+impl From<Color> for Cam16Ucs {
+	fn from(color: Color) -> Self {
+		Cam16Ucs::from_color(color, &ViewingConditions::SRGB)
+	}
+}
+
+
+impl From<Cam16Ucs> for Color {
+	fn from(ucs: Cam16Ucs) -> Self {
+		ucs.to_color(&ViewingConditions::SRGB)
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// GamutMapping
+////////////////////////////////////////////////////////////////////////////////
+/// A strategy for converting colors lying outside of the sRGB gamut into
+/// displayable `Color`s.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GamutMapping {
+	/// Clamps each linear RGB channel into range. This is fast, but can shift
+	/// hue and lightness noticeably.
+	Clip,
+	/// Reduces chroma while preserving lightness and hue until the color lies
+	/// within the gamut.
+	ReduceChroma,
+	/// Finds a nearby in-gamut color with the smallest CIEDE2000 difference
+	/// from the original, allowing small changes in lightness.
+	ClosestDeltaE,
+}
+
+
+impl GamutMapping {
+	/// Returns the name of the strategy.
+	pub fn name(&self) -> &'static str {
+		match *self {
+			GamutMapping::Clip          => "clip",
+			GamutMapping::ReduceChroma  => "reduce-chroma",
+			GamutMapping::ClosestDeltaE => "closest-delta-e",
+		}
+	}
+
+	/// Returns the strategy with the given name, ignoring case.
+	pub fn from_name(name: &str) -> Option<GamutMapping> {
+		[
+			GamutMapping::Clip,
+			GamutMapping::ReduceChroma,
+			GamutMapping::ClosestDeltaE,
+		].iter()
+			.find(|g| g.name().eq_ignore_ascii_case(name))
+			.cloned()
+	}
+
+	/// Returns an in-gamut `Color` for the given `Lab` color.
+	///
+	/// # Example
+	///
+	/// ```rust
+	/// use palette::space::{GamutMapping, Lab};
+	///
+	/// // A green more saturated than sRGB can display.
+	/// let lab = Lab::new(60.0, -120.0, 80.0);
+	/// assert!(!lab.in_gamut());
+	///
+	/// let mapped = Lab::from(GamutMapping::ReduceChroma.map(lab));
+	/// assert!((mapped.l - 60.0).abs() < 1.0);
+	/// ```
+	pub fn map(&self, lab: Lab) -> Color {
+		self.map_wide(lab).quantize()
+	}
+
+	/// Returns an in-gamut sRGB `WideColor` for the given `Lab` color, without
+	/// quantizing its channels.
+	pub fn map_wide(&self, lab: Lab) -> WideColor {
+		let clip = |lab: Lab| {
+			let lin = Xyz::from(lab).to_linear_rgb();
+			WideColor::new(
+				linear_to_srgb(clamped(lin[0], 0.0, 1.0)),
+				linear_to_srgb(clamped(lin[1], 0.0, 1.0)),
+				linear_to_srgb(clamped(lin[2], 0.0, 1.0)),
+				RgbSpace::Srgb,
+			)
+		};
+		if lab.in_gamut() { return clip(lab); }
+
+		match *self {
+			GamutMapping::Clip => clip(lab),
+
+			GamutMapping::ReduceChroma => clip(reduce_chroma(lab)),
+
+			GamutMapping::ClosestDeltaE => {
+				let mut best = Lab::from(Xyz::from(clip(lab)));
+				let mut best_delta = delta_e_2000_lab(lab, best);
+				for i in 0..21 {
+					for &sign in &[-1.0, 1.0] {
+						let l = clamped(lab.l + sign * i as f32 * 0.5, 0.0, 100.0);
+						let candidate = reduce_chroma(Lab::new(l, lab.a, lab.b));
+						let delta = delta_e_2000_lab(lab, candidate);
+						if delta < best_delta {
+							best = candidate;
+							best_delta = delta;
+						}
+					}
+				}
+				clip(best)
+			},
+		}
+	}
+}
+
+
+impl Default for GamutMapping {
+	fn default() -> Self {
+		GamutMapping::ReduceChroma
+	}
+}
+
+
+/// Returns the color with the largest chroma not exceeding the given color's
+/// which lies within the sRGB gamut and shares its lightness and hue.
+fn reduce_chroma(lab: Lab) -> Lab {
+	let chroma = lab.chroma();
+	if chroma <= f32::EPSILON || lab.in_gamut() { return lab; }
+
+	let (ua, ub) = (lab.a / chroma, lab.b / chroma);
+	let (mut low, mut high) = (0.0, chroma);
+	for _ in 0..24 {
+		let mid = (low + high) / 2.0;
+		if Lab::new(lab.l, ua * mid, ub * mid).in_gamut() {
+			low = mid;
+		} else {
+			high = mid;
+		}
+	}
+	Lab::new(lab.l, ua * low, ub * low)
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Color difference
+////////////////////////////////////////////////////////////////////////////////
+/// Returns the CIE76 color difference between two colors. This is the
+/// euclidean distance between the colors in Lab space.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::Color;
+/// use palette::space::delta_e;
+///
+/// let a = Color::new(120, 40, 40);
+///
+/// assert_eq!(delta_e(a, a), 0.0);
+/// assert!(delta_e(a, Color::new(40, 40, 120)) > 50.0);
+/// ```
+pub fn delta_e(a: Color, b: Color) -> f32 {
+	let (a, b) = (Lab::from(a), Lab::from(b));
+	let (dl, da, db) = (a.l - b.l, a.a - b.a, a.b - b.b);
+	(dl * dl + da * da + db * db).sqrt()
+}
+
+/// Returns the CIEDE2000 color difference between two colors.
+pub fn delta_e_2000(a: Color, b: Color) -> f32 {
+	delta_e_2000_lab(Lab::from(a), Lab::from(b))
+}
+
+/// Returns the CIEDE2000 color difference between two `Lab` colors.
+pub fn delta_e_2000_lab(x: Lab, y: Lab) -> f32 {
+	let c_bar = (x.chroma() + y.chroma()) / 2.0;
+	let c_bar7 = c_bar.powi(7);
+	let g = 0.5 * (1.0 - (c_bar7 / (c_bar7 + 25.0f32.powi(7))).sqrt());
+
+	let a1 = x.a * (1.0 + g);
+	let a2 = y.a * (1.0 + g);
+	let c1 = (a1 * a1 + x.b * x.b).sqrt();
+	let c2 = (a2 * a2 + y.b * y.b).sqrt();
+	let h = |b: f32, a: f32| if b == 0.0 && a == 0.0 {
+		0.0
+	} else {
+		let h = b.atan2(a).to_degrees();
+		if h < 0.0 { h + 360.0 } else { h }
+	};
+	let h1 = h(x.b, a1);
+	let h2 = h(y.b, a2);
+
+	let dl = y.l - x.l;
+	let dc = c2 - c1;
+	let dh = if c1 * c2 == 0.0 {
+		0.0
+	} else if (h2 - h1).abs() <= 180.0 {
+		h2 - h1
+	} else if h2 - h1 > 180.0 {
+		h2 - h1 - 360.0
+	} else {
+		h2 - h1 + 360.0
+	};
+	let dh = 2.0 * (c1 * c2).sqrt() * (dh.to_radians() / 2.0).sin();
+
+	let l_bar = (x.l + y.l) / 2.0;
+	let c_bar = (c1 + c2) / 2.0;
+	let h_bar = if c1 * c2 == 0.0 {
+		h1 + h2
+	} else if (h1 - h2).abs() <= 180.0 {
+		(h1 + h2) / 2.0
+	} else if h1 + h2 < 360.0 {
+		(h1 + h2 + 360.0) / 2.0
+	} else {
+		(h1 + h2 - 360.0) / 2.0
+	};
+
+	let t = 1.0
+		- 0.17 * (h_bar - 30.0).to_radians().cos()
+		+ 0.24 * (2.0 * h_bar).to_radians().cos()
+		+ 0.32 * (3.0 * h_bar + 6.0).to_radians().cos()
+		- 0.20 * (4.0 * h_bar - 63.0).to_radians().cos();
+	let l50 = (l_bar - 50.0) * (l_bar - 50.0);
+	let sl = 1.0 + 0.015 * l50 / (20.0 + l50).sqrt();
+	let sc = 1.0 + 0.045 * c_bar;
+	let sh = 1.0 + 0.015 * c_bar * t;
+	let c_bar7 = c_bar.powi(7);
+	let rc = 2.0 * (c_bar7 / (c_bar7 + 25.0f32.powi(7))).sqrt();
+	let d_theta = 30.0 * (-((h_bar - 275.0) / 25.0).powi(2)).exp();
+	let rt = -(2.0 * d_theta).to_radians().sin() * rc;
+
+	let (l, c, h) = (dl / sl, dc / sc, dh / sh);
+	(l * l + c * c + h * h + rt * c * h).sqrt()
+}
+
+
+/// Returns the CAM16-UCS color difference between two colors, viewed under
+/// the standard sRGB viewing conditions.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::Color;
+/// use palette::space::delta_e_cam16;
+///
+/// let a = Color::new(120, 40, 40);
+///
+/// assert_eq!(delta_e_cam16(a, a), 0.0);
+/// assert!(delta_e_cam16(a, Color::new(40, 40, 120)) > 10.0);
+/// ```
+pub fn delta_e_cam16(a: Color, b: Color) -> f32 {
+	Cam16Ucs::from(a).distance(&Cam16Ucs::from(b))
+}
+
+
+/// Returns the WCAG relative luminance of the given color, in the range 
+/// [0, 1].
+pub fn relative_luminance(color: Color) -> f32 {
+	let [r, g, b] = unit_channels(color);
+	0.2126 * srgb_to_linear(r) 
+		+ 0.7152 * srgb_to_linear(g) 
+		+ 0.0722 * srgb_to_linear(b)
+}
+
+/// Returns the WCAG contrast ratio between two colors, in the range [1, 21].
+///
+/// # Example
+///
+/// ```rust
+/// use palette::Color;
+/// use palette::space::contrast_ratio;
+///
+/// let black = Color::new(0, 0, 0);
+/// let white = Color::new(255, 255, 255);
+///
+/// assert!((contrast_ratio(black, white) - 21.0).abs() < 0.01);
+/// assert_eq!(contrast_ratio(white, white), 1.0);
+/// ```
+pub fn contrast_ratio(a: Color, b: Color) -> f32 {
+	let (a, b) = (relative_luminance(a), relative_luminance(b));
+	(a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// ColorDifference
+////////////////////////////////////////////////////////////////////////////////
+/// A metric of the perceived difference between two colors.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::Color;
+/// use palette::space::{ColorDifference, Surround, ViewingConditions};
+///
+/// let (a, b) = (Color::new(60, 60, 60), Color::new(70, 60, 60));
+/// let dark = ViewingConditions::SRGB.surround(Surround::Dark);
+///
+/// let metric = ColorDifference::from_name("cam16-ucs").unwrap();
+/// assert_eq!(metric, ColorDifference::Cam16Ucs(ViewingConditions::SRGB));
+/// assert!(metric.difference(a, b) > 0.0);
+/// assert!(ColorDifference::Cam16Ucs(dark).difference(a, b) > 0.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColorDifference {
+	/// The CIE76 difference: euclidean distance in CIE L*a*b*.
+	Cie76,
+	/// The CIEDE2000 difference.
+	Ciede2000,
+	/// The CAM16-UCS difference under the given viewing conditions: the 
+	/// euclidean distance `ΔE'` in CAM16-UCS, corrected as `1.41·ΔE'^0.63`.
+	Cam16Ucs(ViewingConditions),
+}
+
+
+impl ColorDifference {
+	/// Returns all of the color difference metrics, using the standard sRGB
+	/// viewing conditions for CAM16-UCS.
+	pub fn all() -> &'static [ColorDifference] {
+		&[
+			ColorDifference::Cie76,
+			ColorDifference::Ciede2000,
+			ColorDifference::Cam16Ucs(ViewingConditions::SRGB),
+		]
+	}
+
+	/// Returns the name of the metric.
+	pub fn name(&self) -> &'static str {
+		match *self {
+			ColorDifference::Cie76       => "cie76",
+			ColorDifference::Ciede2000   => "ciede2000",
+			ColorDifference::Cam16Ucs(_) => "cam16-ucs",
+		}
+	}
+
+	/// Returns the metric with the given name, ignoring case. CAM16-UCS uses
+	/// the standard sRGB viewing conditions.
+	pub fn from_name(name: &str) -> Option<ColorDifference> {
+		ColorDifference::all()
+			.iter()
+			.find(|d| d.name().eq_ignore_ascii_case(name))
+			.cloned()
+	}
+
+	/// Returns the difference between the given colors.
+	pub fn difference(&self, a: Color, b: Color) -> f32 {
+		match *self {
+			ColorDifference::Cie76      => delta_e(a, b),
+			ColorDifference::Ciede2000  => delta_e_2000(a, b),
+			ColorDifference::Cam16Ucs(ref conditions) => 
+				Cam16Ucs::from_color(a, conditions)
+					.distance(&Cam16Ucs::from_color(b, conditions)),
+		}
+	}
+}
+
+
+impl Default for ColorDifference {
+	fn default() -> Self {
+		ColorDifference::Ciede2000
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Deficiency
+////////////////////////////////////////////////////////////////////////////////
+/// A type of color vision deficiency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Deficiency {
+	/// Absence of long-wavelength (red) cones.
+	Protanopia,
+	/// Absence of medium-wavelength (green) cones.
+	Deuteranopia,
+	/// Absence of short-wavelength (blue) cones.
+	Tritanopia,
+	/// Complete absence of color vision.
+	Achromatopsia,
+}
+
+
+impl Deficiency {
+	/// Returns the linear RGB transformation simulating the deficiency, from
+	/// Machado, Oliveira, and Fernandes (2009) at full severity.
+	fn matrix(&self) -> [[f32; 3]; 3] {
+		match *self {
+			Deficiency::Protanopia => [
+				[ 0.152_286,  1.052_583, -0.204_868],
+				[ 0.114_503,  0.786_281,  0.099_216],
+				[-0.003_882, -0.048_116,  1.051_998],
+			],
+			Deficiency::Deuteranopia => [
+				[ 0.367_322,  0.860_646, -0.227_968],
+				[ 0.280_085,  0.672_501,  0.047_413],
+				[-0.011_820,  0.042_940,  0.968_881],
+			],
+			Deficiency::Tritanopia => [
+				[ 1.255_528, -0.076_749, -0.178_779],
+				[-0.078_411,  0.930_809,  0.147_602],
+				[ 0.004_733,  0.691_367,  0.303_900],
+			],
+			Deficiency::Achromatopsia => [
+				[0.212_672_9, 0.715_152_2, 0.072_175_0],
+				[0.212_672_9, 0.715_152_2, 0.072_175_0],
+				[0.212_672_9, 0.715_152_2, 0.072_175_0],
+			],
+		}
+	}
+
+	/// Returns the given color as it would appear to a viewer with the
+	/// deficiency.
+	pub fn simulate(&self, color: Color) -> Color {
+		let c = unit_channels(color);
+		let lin = [
+			srgb_to_linear(c[0]),
+			srgb_to_linear(c[1]),
+			srgb_to_linear(c[2]),
+		];
+		let m = self.matrix();
+		let mut out = [0.0; 3];
+		for (i, row) in m.iter().enumerate() {
+			let v = row[0] * lin[0] + row[1] * lin[1] + row[2] * lin[2];
+			out[i] = linear_to_srgb(clamped(v, 0.0, 1.0));
+		}
+		from_unit_channels(out)
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// DisplayModel
+////////////////////////////////////////////////////////////////////////////////
+/// A model of a target display, used to preview how colors will appear on 
+/// that hardware.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::Color;
+/// use palette::space::DisplayModel;
+///
+/// let gray = Color::new(128, 128, 128);
+/// assert!(DisplayModel::Crt.simulate(gray).red() < 128);
+/// assert_eq!(DisplayModel::Lcd.simulate(gray), gray);
+///
+/// let dmg = DisplayModel::GameBoy;
+/// assert_eq!(dmg.simulate(Color::new(0, 0, 0)), Color::new(15, 56, 15));
+/// let white = Color::new(255, 255, 255);
+/// assert_eq!(dmg.simulate(white), Color::new(155, 188, 15));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DisplayModel {
+	/// A CRT monitor, with a phosphor gamma of 2.5.
+	Crt,
+	/// An early passive-matrix LCD, with a limited gamut.
+	Lcd,
+	/// The original Game Boy's green-tinted four-shade screen.
+	GameBoy,
+}
+
+
+impl DisplayModel {
+	/// Returns the name of the display model.
+	pub fn name(&self) -> &'static str {
+		match *self {
+			DisplayModel::Crt => "crt",
+			DisplayModel::Lcd => "lcd",
+			DisplayModel::GameBoy => "dmg",
+		}
+	}
+
+	/// Returns the display model with the given name, if any. The match is not
+	/// case sensitive.
+	pub fn from_name(name: &str) -> Option<DisplayModel> {
+		[DisplayModel::Crt, DisplayModel::Lcd, DisplayModel::GameBoy]
+			.iter()
+			.find(|d| d.name().eq_ignore_ascii_case(name))
+			.cloned()
+	}
+
+	/// Returns the given color as it would appear on the display.
+	pub fn simulate(&self, color: Color) -> Color {
+		let c = unit_channels(color);
+		match *self {
+			DisplayModel::Crt => {
+				// Decode with the phosphor response, then re-encode as sRGB.
+				let phosphor = |v: f32| linear_to_srgb(v.powf(2.5));
+				from_unit_channels([
+					phosphor(c[0]),
+					phosphor(c[1]),
+					phosphor(c[2]),
+				])
+			},
+
+			DisplayModel::Lcd => {
+				// Each primary bleeds into the others, pulling colors toward
+				// gray while leaving grays unchanged.
+				let m = [
+					[0.82, 0.125, 0.055],
+					[0.10, 0.85, 0.05],
+					[0.05, 0.12, 0.83],
+				];
+				let lin = [
+					srgb_to_linear(c[0]),
+					srgb_to_linear(c[1]),
+					srgb_to_linear(c[2]),
+				];
+				let mut out = [0.0; 3];
+				for (i, row) in m.iter().enumerate() {
+					let v = row[0] * lin[0] + row[1] * lin[1] + row[2] * lin[2];
+					out[i] = linear_to_srgb(clamped(v, 0.0, 1.0));
+				}
+				from_unit_channels(out)
+			},
+
+			DisplayModel::GameBoy => {
+				const SHADES: [(u8, u8, u8); 4] = [
+					(15, 56, 15),
+					(48, 98, 48),
+					(139, 172, 15),
+					(155, 188, 15),
+				];
+				let l = linear_to_srgb(relative_luminance(color));
+				let (r, g, b) = SHADES[((l * 4.0) as usize).min(3)];
+				Color::new(r, g, b)
+			},
+		}
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// RgbSpace
+////////////////////////////////////////////////////////////////////////////////
+/// An RGB color space, defined by its primaries and transfer function. All
+/// spaces use a D65 white point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RgbSpace {
+	/// The sRGB color space.
+	Srgb,
+	/// The Display P3 color space, using sRGB's transfer function.
+	DisplayP3,
+	/// The ITU-R BT.2020 color space.
+	Rec2020,
+}
+
+
+impl RgbSpace {
+	/// Returns the name of the color space.
+	pub fn name(&self) -> &'static str {
+		match *self {
+			RgbSpace::Srgb      => "srgb",
+			RgbSpace::DisplayP3 => "display-p3",
+			RgbSpace::Rec2020   => "rec2020",
+		}
+	}
+
+	/// Returns the color space with the given name, ignoring case.
+	pub fn from_name(name: &str) -> Option<RgbSpace> {
+		[RgbSpace::Srgb, RgbSpace::DisplayP3, RgbSpace::Rec2020].iter()
+			.find(|s| s.name().eq_ignore_ascii_case(name))
+			.cloned()
+	}
+
+	/// Converts an encoded channel to linear light. Values outside of [0, 1]
+	/// are extended symmetrically.
+	pub fn to_linear(&self, value: f32) -> f32 {
+		let (sign, v) = (value.signum(), value.abs());
+		sign * match *self {
+			RgbSpace::Srgb | RgbSpace::DisplayP3 => srgb_to_linear(v),
+			RgbSpace::Rec2020 => if v < REC2020_BETA * 4.5 {
+				v / 4.5
+			} else {
+				((v + REC2020_ALPHA - 1.0) / REC2020_ALPHA).powf(1.0 / 0.45)
+			},
+		}
+	}
+
+	/// Converts a linear light channel to its encoded value. Values outside of
+	/// [0, 1] are extended symmetrically.
+	pub fn from_linear(&self, value: f32) -> f32 {
+		let (sign, v) = (value.signum(), value.abs());
+		sign * match *self {
+			RgbSpace::Srgb | RgbSpace::DisplayP3 => linear_to_srgb(v),
+			RgbSpace::Rec2020 => if v < REC2020_BETA {
+				v * 4.5
+			} else {
+				REC2020_ALPHA * v.powf(0.45) - (REC2020_ALPHA - 1.0)
+			},
+		}
+	}
+
+	/// Returns the matrix converting linear channels in this space to XYZ.
+	fn to_xyz_matrix(&self) -> [[f32; 3]; 3] {
+		match *self {
+			RgbSpace::Srgb => [
+				[0.412_456_4, 0.357_576_1, 0.180_437_5],
+				[0.212_672_9, 0.715_152_2, 0.072_175_0],
+				[0.019_333_9, 0.119_192_0, 0.950_304_1],
+			],
+			RgbSpace::DisplayP3 => [
+				[0.486_570_9, 0.265_667_7, 0.198_217_3],
+				[0.228_974_6, 0.691_738_5, 0.079_286_9],
+				[0.000_000_0, 0.045_113_4, 1.043_944_4],
+			],
+			RgbSpace::Rec2020 => [
+				[0.636_958_0, 0.144_616_9, 0.168_881_0],
+				[0.262_700_2, 0.677_998_1, 0.059_301_7],
+				[0.000_000_0, 0.028_072_7, 1.060_985_1],
+			],
+		}
+	}
+
+	/// Returns the matrix converting XYZ to linear channels in this space.
+	fn from_xyz_matrix(&self) -> [[f32; 3]; 3] {
+		match *self {
+			RgbSpace::Srgb => [
+				[ 3.240_454_2, -1.537_138_5, -0.498_531_4],
+				[-0.969_266_0,  1.876_010_8,  0.041_556_0],
+				[ 0.055_643_4, -0.204_025_9,  1.057_225_2],
+			],
+			RgbSpace::DisplayP3 => [
+				[ 2.493_496_9, -0.931_383_6, -0.402_710_8],
+				[-0.829_489_0,  1.762_664_1,  0.023_624_7],
+				[ 0.035_845_8, -0.076_172_4,  0.956_884_5],
+			],
+			RgbSpace::Rec2020 => [
+				[ 1.716_651_2, -0.355_670_8, -0.253_366_3],
+				[-0.666_684_4,  1.616_481_2,  0.015_768_5],
+				[ 0.017_639_9, -0.042_770_6,  0.942_103_1],
+			],
+		}
+	}
+}
+
+
+impl Default for RgbSpace {
+	fn default() -> Self {
+		RgbSpace::Srgb
+	}
+}
+
+
+/// The alpha constant of the BT.2020 transfer function.
+const REC2020_ALPHA: f32 = 1.099_296_8;
+
+/// The beta constant of the BT.2020 transfer function.
+const REC2020_BETA: f32 = 0.018_053_97;
+
+
+/// Multiplies the given 3x3 matrix with the given vector.
+#[inline]
+fn mul3(m: &[[f32; 3]; 3], v: [f32; 3]) -> [f32; 3] {
+	[
+		m[0][0] * v[0] + m[0][1] * v[1] + m[0][2] * v[2],
+		m[1][0] * v[0] + m[1][1] * v[1] + m[1][2] * v[2],
+		m[2][0] * v[0] + m[2][1] * v[1] + m[2][2] * v[2],
+	]
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// WideColor
+////////////////////////////////////////////////////////////////////////////////
+/// An extended-range floating point color tagged with its RGB color space.
+///
+/// The channels are encoded with the space's transfer function, and may lie
+/// outside of [0, 1] to represent colors beyond the space's gamut or brighter
+/// than its reference white.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::Color;
+/// use palette::space::{RgbSpace, WideColor};
+///
+/// let red = WideColor::from(Color::new(255, 0, 0));
+/// let p3 = red.convert(RgbSpace::DisplayP3);
+///
+/// // sRGB red lies inside of the larger P3 gamut.
+/// assert!(p3.in_gamut());
+/// assert!(p3.r < 1.0);
+/// ```
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct WideColor {
+	/// The red channel.
+	pub r: f32,
+	/// The green channel.
+	pub g: f32,
+	/// The blue channel.
+	pub b: f32,
+	/// The color space of the channels.
+	pub space: RgbSpace,
+}
+
+
+impl WideColor {
+	/// Black in the sRGB color space.
+	pub const BLACK: WideColor = WideColor::srgb(0.0, 0.0, 0.0);
+
+	/// White in the sRGB color space.
+	pub const WHITE: WideColor = WideColor::srgb(1.0, 1.0, 1.0);
+
+	/// Creates a new `WideColor` in the given color space.
+	pub const fn new(r: f32, g: f32, b: f32, space: RgbSpace) -> Self {
+		WideColor {r: r, g: g, b: b, space: space}
+	}
+
+	/// Creates a new `WideColor` in the sRGB color space. Being a `const fn`,
+	/// this can be used to define color constants.
+	///
+	/// # Example
+	///
+	/// ```rust
+	/// use palette::Color;
+	/// use palette::space::WideColor;
+	///
+	/// const SKIN: WideColor = WideColor::srgb(1.0, 0.8, 0.6);
+	///
+	/// assert_eq!(SKIN.quantize(), Color::new(255, 204, 153));
+	/// assert_eq!(WideColor::WHITE.quantize(), Color::new(255, 255, 255));
+	/// ```
+	pub const fn srgb(r: f32, g: f32, b: f32) -> Self {
+		WideColor {r: r, g: g, b: b, space: RgbSpace::Srgb}
+	}
+
+	/// Returns the color's linear channels.
+	pub fn to_linear(&self) -> [f32; 3] {
+		[
+			self.space.to_linear(self.r),
+			self.space.to_linear(self.g),
+			self.space.to_linear(self.b),
+		]
+	}
+
+	/// Returns whether the color lies within the gamut of its color space.
+	pub fn in_gamut(&self) -> bool {
+		const TOLERANCE: f32 = 1.0e-4;
+		[self.r, self.g, self.b].iter()
+			.all(|&v| v >= -TOLERANCE && v <= 1.0 + TOLERANCE)
+	}
+
+	/// Returns the same color expressed in the given color space.
+	pub fn convert(&self, space: RgbSpace) -> WideColor {
+		if space == self.space { return *self; }
+		WideColor::from_xyz(Xyz::from(*self), space)
+	}
+
+	/// Returns the color in the given color space with the given XYZ values.
+	pub fn from_xyz(xyz: Xyz, space: RgbSpace) -> WideColor {
+		let lin = mul3(&space.from_xyz_matrix(), [xyz.x, xyz.y, xyz.z]);
+		WideColor::new(
+			space.from_linear(lin[0]),
+			space.from_linear(lin[1]),
+			space.from_linear(lin[2]),
+			space,
+		)
+	}
+
+	/// Returns the color as an 8-bit sRGB `Color`, using the given strategy
+	/// to bring it into the sRGB gamut.
+	pub fn to_color(&self, gamut_mapping: GamutMapping) -> Color {
+		self.to_srgb(gamut_mapping).quantize()
+	}
+
+	/// Returns the color as an in-gamut sRGB `WideColor`, using the given 
+	/// strategy to bring it into the sRGB gamut.
+	pub fn to_srgb(&self, gamut_mapping: GamutMapping) -> WideColor {
+		let srgb = self.convert(RgbSpace::Srgb);
+		if srgb.in_gamut() {
+			srgb
+		} else {
+			gamut_mapping.map_wide(Lab::from(Xyz::from(srgb)))
+		}
+	}
+
+	/// Returns the nearest 8-bit `Color` to this color's channels, clamping
+	/// them into range. The color space is ignored, so this should only be
+	/// called on sRGB colors.
+	pub fn quantize(&self) -> Color {
+		from_unit_channels([self.r, self.g, self.b])
+	}
+}
+
+
+impl From<Color> for WideColor {
+	fn from(color: Color) -> Self {
+		let [r, g, b] = unit_channels(color);
+		WideColor::new(r, g, b, RgbSpace::Srgb)
+	}
+}
+
+
+impl From<WideColor> for Xyz {
+	fn from(color: WideColor) -> Self {
+		let v = mul3(&color.space.to_xyz_matrix(), color.to_linear());
+		Xyz::new(v[0], v[1], v[2])
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Batch conversion
+////////////////////////////////////////////////////////////////////////////////
+// These functions convert whole slices of colors in separate passes over
+// plain arrays, so that each pass is a simple loop the compiler can 
+// vectorize. They produce the same results as converting each color alone.
+
+/// Returns the linear light value of each 8-bit sRGB channel value.
+fn linear_table() -> &'static [f32; 256] {
+	static TABLE: OnceLock<[f32; 256]> = OnceLock::new();
+	TABLE.get_or_init(|| {
+		let mut table = [0.0; 256];
+		for (i, v) in table.iter_mut().enumerate() {
+			*v = srgb_to_linear(i as f32 / 255.0);
+		}
+		table
+	})
+}
+
+
+/// Writes the linear sRGB channels of each of the given colors into the 
+/// corresponding element of the output slice.
+///
+/// # Panics
+///
+/// Panics if the slices have different lengths.
+pub fn colors_to_linear(colors: &[Color], out: &mut [[f32; 3]]) {
+	assert_eq!(colors.len(), out.len(), "slice lengths must match");
+	let table = linear_table();
+	for (color, rgb) in colors.iter().zip(out.iter_mut()) {
+		*rgb = [
+			table[color.red() as usize],
+			table[color.green() as usize],
+			table[color.blue() as usize],
+		];
+	}
+}
+
+
+/// Writes the `Lab` conversion of each of the given colors into the 
+/// corresponding element of the output slice.
+///
+/// # Panics
+///
+/// Panics if the slices have different lengths.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::Color;
+/// use palette::space::{colors_to_lab, labs_to_colors, Lab};
+///
+/// let colors = [Color::new(12, 50, 78), Color::new(255, 128, 0)];
+/// let mut labs = [Lab::default(); 2];
+/// colors_to_lab(&colors, &mut labs);
+/// assert_eq!(labs[0], Lab::from(colors[0]));
+///
+/// let mut back = [Color::new(0, 0, 0); 2];
+/// labs_to_colors(&labs, &mut back);
+/// assert_eq!(back, colors);
+/// ```
+pub fn colors_to_lab(colors: &[Color], out: &mut [Lab]) {
+	assert_eq!(colors.len(), out.len(), "slice lengths must match");
+	let table = linear_table();
+	for (color, lab) in colors.iter().zip(out.iter_mut()) {
+		let xyz = Xyz::from_linear_rgb([
+			table[color.red() as usize],
+			table[color.green() as usize],
+			table[color.blue() as usize],
+		]);
+		*lab = Lab::new(xyz.x, xyz.y, xyz.z);
+	}
+	for lab in out.iter_mut() {
+		*lab = Lab::from(Xyz::new(lab.l, lab.a, lab.b));
+	}
+}
+
+
+/// Writes the `Color` conversion of each of the given `Lab` colors into the
+/// corresponding element of the output slice.
+///
+/// # Panics
+///
+/// Panics if the slices have different lengths.
+pub fn labs_to_colors(labs: &[Lab], out: &mut [Color]) {
+	assert_eq!(labs.len(), out.len(), "slice lengths must match");
+	for (&lab, color) in labs.iter().zip(out.iter_mut()) {
+		*color = Color::from(lab);
+	}
+}
+
+
+/// Writes the `Oklab` conversion of each of the given colors into the 
+/// corresponding element of the output slice.
+///
+/// # Panics
+///
+/// Panics if the slices have different lengths.
+pub fn colors_to_oklab(colors: &[Color], out: &mut [Oklab]) {
+	assert_eq!(colors.len(), out.len(), "slice lengths must match");
+	let table = linear_table();
+	for (color, lab) in colors.iter().zip(out.iter_mut()) {
+		*lab = Oklab::from_linear_rgb([
+			table[color.red() as usize],
+			table[color.green() as usize],
+			table[color.blue() as usize],
+		]);
+	}
+}
+
+
+/// Writes the index of the nearest candidate to each of the given target 
+/// colors into the corresponding element of the output slice. If there are
+/// no candidates, every index is 0.
+///
+/// # Panics
+///
+/// Panics if the target and output slices have different lengths.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::Color;
+/// use palette::space::{nearest_oklab, Oklab};
+///
+/// let candidates = [
+/// 	Oklab::from(Color::new(0, 0, 0)),
+/// 	Oklab::from(Color::new(255, 255, 255)),
+/// ];
+/// let targets = [
+/// 	Oklab::from(Color::new(200, 210, 220)),
+/// 	Oklab::from(Color::new(20, 10, 0)),
+/// ];
+/// let mut nearest = [0; 2];
+/// nearest_oklab(&targets, &candidates, &mut nearest);
+///
+/// assert_eq!(nearest, [1, 0]);
+/// ```
+pub fn nearest_oklab(
+	targets: &[Oklab],
+	candidates: &[Oklab],
+	out: &mut [usize])
+{
+	assert_eq!(targets.len(), out.len(), "slice lengths must match");
+	for (target, index) in targets.iter().zip(out.iter_mut()) {
+		*index = target.nearest(candidates).unwrap_or(0);
+	}
+}
+
+
+/// Writes the CIEDE2000 difference between each pair of the given colors 
+/// into the output slice, as a row-major matrix with one row per color.
+///
+/// # Panics
+///
+/// Panics if the output slice does not hold exactly one element per pair.
+pub fn delta_e_2000_matrix(labs: &[Lab], out: &mut [f32]) {
+	let len = labs.len();
+	assert_eq!(len * len, out.len(), "output must hold one element per pair");
+	for i in 0..len {
+		out[i * len + i] = 0.0;
+		for j in (i + 1)..len {
+			let d = delta_e_2000_lab(labs[i], labs[j]);
+			out[i * len + j] = d;
+			out[j * len + i] = d;
+		}
+	}
+}
@@ -0,0 +1,299 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides palette-swap mappings between the cells of two palettes.
+//!
+//! A mapping pairs each colored cell of a source palette with a cell of a
+//! target palette. Pairs can be given explicitly, or matched by cell name, by
+//! address, or by nearest color. The finished mapping can be exported as a
+//! lookup table for shaders, or as an index remap table for indexed images.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use ::Palette;
+use address::{
+	Address,
+	Selection,
+};
+use export::Lut3d;
+use result::{
+	Error,
+	Result,
+};
+use space::Oklab;
+
+// Non-local imports.
+use color::Color;
+
+// Standard imports.
+use std::collections::BTreeMap;
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// SwapBuilder
+////////////////////////////////////////////////////////////////////////////////
+/// Builds a `SwapMapping` between two palettes.
+///
+/// Matching methods only assign source cells which have not already been
+/// mapped, so explicit pairs and earlier matching methods take priority.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::operation::*;
+/// use palette::swap::SwapBuilder;
+///
+/// let mut base = Palette::new("Base", Format::Default, true);
+/// base.apply(Box::new(InsertColor::new(Color::new(200, 150, 120)))).unwrap();
+/// base.apply(Box::new(InsertColor::new(Color::new(30, 30, 30)))).unwrap();
+/// base.set_name(Address::new(0, 0, 0), "skin");
+///
+/// let mut alien = Palette::new("Alien", Format::Default, true);
+/// alien.apply(Box::new(InsertColor::new(Color::new(10, 10, 10)))).unwrap();
+/// alien.apply(Box::new(InsertColor::new(Color::new(90, 200, 90)))).unwrap();
+/// alien.set_name(Address::new(0, 0, 1), "skin");
+///
+/// let mapping = SwapBuilder::new(&base, &alien)
+/// 	.match_names()
+/// 	.match_nearest()
+/// 	.build();
+///
+/// assert!(mapping.is_complete());
+/// assert_eq!(mapping.target_of(Address::new(0, 0, 0)), Some(Address::new(0, 0, 1)));
+/// assert_eq!(mapping.index_table(), vec![Some(1), Some(0)]);
+/// ```
+#[derive(Debug)]
+pub struct SwapBuilder<'a> {
+	/// The palette being mapped from.
+	source: &'a Palette,
+	/// The palette being mapped to.
+	target: &'a Palette,
+	/// The source cells to map.
+	selection: Selection,
+	/// The mapped pairs of source and target addresses.
+	pairs: BTreeMap<Address, Address>,
+}
+
+
+impl<'a> SwapBuilder<'a> {
+	/// Creates a new `SwapBuilder` mapping the cells of the source palette to
+	/// the cells of the target palette.
+	pub fn new(source: &'a Palette, target: &'a Palette) -> Self {
+		SwapBuilder {
+			source: source,
+			target: target,
+			selection: Selection::all(),
+			pairs: BTreeMap::new(),
+		}
+	}
+
+	/// Restricts the mapping to the source cells in the given selection.
+	pub fn selection(mut self, selection: Selection) -> Self {
+		self.selection = selection;
+		self
+	}
+
+	/// Maps the given source address to the given target address, replacing
+	/// any existing pair.
+	pub fn map(mut self, source: Address, target: Address) -> Self {
+		self.pairs.insert(source, target);
+		self
+	}
+
+	/// Maps each unmapped source cell to the target cell with the same name.
+	pub fn match_names(mut self) -> Self {
+		for (name, address) in self.source.named_cells() {
+			if self.pairs.contains_key(&address) { continue; }
+			if let Some(target) = self.target.address_of(&name) {
+				self.pairs.insert(address, target);
+			}
+		}
+		self
+	}
+
+	/// Maps each unmapped source cell to the target cell at the same address,
+	/// if it has a color.
+	pub fn match_addresses(mut self) -> Self {
+		for (address, _) in self.source.colors(&self.selection) {
+			if self.pairs.contains_key(&address) { continue; }
+			if self.target.color(address).is_some() {
+				self.pairs.insert(address, address);
+			}
+		}
+		self
+	}
+
+	/// Maps each unmapped source cell to the target cell with the nearest
+	/// color.
+	pub fn match_nearest(mut self) -> Self {
+		let targets = self.target.colors(&Selection::all());
+		let keys: Vec<Oklab> = targets.iter()
+			.map(|&(_, c)| Oklab::from(c))
+			.collect();
+
+		for (address, color) in self.source.colors(&self.selection) {
+			if self.pairs.contains_key(&address) { continue; }
+			if let Some(i) = Oklab::from(color).nearest(&keys) {
+				self.pairs.insert(address, targets[i].0);
+			}
+		}
+		self
+	}
+
+	/// Builds the mapping. Pairs whose source or target cell has no color are
+	/// discarded.
+	pub fn build(self) -> SwapMapping {
+		let sources = self.source.colors(&self.selection);
+		let targets = self.target.colors(&Selection::all());
+
+		let mut entries = Vec::new();
+		let mut unmapped = Vec::new();
+		for (index, &(address, color)) in sources.iter().enumerate() {
+			let target = self.pairs
+				.get(&address)
+				.and_then(|&t| targets.iter()
+					.position(|&(a, _)| a == t)
+					.map(|i| (i, targets[i])));
+
+			match target {
+				Some((target_index, (target, target_color))) 
+					=> entries.push(SwapEntry {
+						source: address,
+						source_index: index,
+						source_color: color,
+						target: target,
+						target_index: target_index,
+						target_color: target_color,
+					}),
+				None => unmapped.push(address),
+			}
+		}
+
+		SwapMapping {
+			source_len: sources.len(),
+			entries: entries,
+			unmapped: unmapped,
+		}
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// SwapEntry
+////////////////////////////////////////////////////////////////////////////////
+/// A single mapped pair of cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SwapEntry {
+	/// The source cell address.
+	pub source: Address,
+	/// The index of the source cell among the source palette's colors.
+	pub source_index: usize,
+	/// The source cell color.
+	pub source_color: Color,
+	/// The target cell address.
+	pub target: Address,
+	/// The index of the target cell among the target palette's colors.
+	pub target_index: usize,
+	/// The target cell color.
+	pub target_color: Color,
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// SwapMapping
+////////////////////////////////////////////////////////////////////////////////
+/// A mapping from the cells of one palette to the cells of another.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SwapMapping {
+	/// The number of colors in the source palette.
+	source_len: usize,
+	/// The mapped pairs, in source address order.
+	entries: Vec<SwapEntry>,
+	/// The source addresses with no mapping.
+	unmapped: Vec<Address>,
+}
+
+
+impl SwapMapping {
+	/// Returns the mapped pairs, in source address order.
+	pub fn entries(&self) -> &[SwapEntry] {
+		&self.entries
+	}
+
+	/// Returns the source addresses with no mapping.
+	pub fn unmapped(&self) -> &[Address] {
+		&self.unmapped
+	}
+
+	/// Returns whether every source cell is mapped.
+	pub fn is_complete(&self) -> bool {
+		self.unmapped.is_empty()
+	}
+
+	/// Returns an error if any source cell is not mapped.
+	///
+	/// # Errors
+	///
+	/// Returns an `UnmappedAddress` error for the first unmapped source cell.
+	pub fn validate(&self) -> Result<()> {
+		match self.unmapped.first() {
+			Some(&address) => Err(Error::UnmappedAddress(address)),
+			None => Ok(()),
+		}
+	}
+
+	/// Returns the target address mapped to the given source address.
+	pub fn target_of(&self, source: Address) -> Option<Address> {
+		self.entries.iter()
+			.find(|e| e.source == source)
+			.map(|e| e.target)
+	}
+
+	/// Returns a table giving the target color index for each source color
+	/// index, for remapping indexed images. Unmapped sources are None.
+	pub fn index_table(&self) -> Vec<Option<usize>> {
+		let mut table = vec![None; self.source_len];
+		for entry in &self.entries {
+			table[entry.source_index] = Some(entry.target_index);
+		}
+		table
+	}
+
+	/// Returns a `Lut3d` of the given size applying the mapping to arbitrary
+	/// colors, using the pair with the nearest source color.
+	pub fn to_lut(&self, size: usize) -> Lut3d {
+		let from: Vec<Color> = self.entries.iter()
+			.map(|e| e.source_color)
+			.collect();
+		let to: Vec<Color> = self.entries.iter()
+			.map(|e| e.target_color)
+			.collect();
+		Lut3d::swap(&from, &to, size)
+	}
+}
@@ -0,0 +1,403 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides palette templates.
+//!
+//! A template records the structure of a palette: its format, group names and
+//! dimensions, its cells, a set of named base colors, and the ramps derived
+//! from them. New palettes can then be instantiated from the template with
+//! different base colors, regenerating each ramp from the substituted colors.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use ::Palette;
+use address::{
+	Address,
+	Reference,
+};
+use curve::{
+	parse_point,
+	Curve,
+};
+use data::MetaData;
+use expression::Expression;
+use format::Format;
+use format::default;
+use operation::{
+	InsertRamp,
+	PaletteOperation,
+};
+use ramp::{
+	InterpolationSpace,
+	RampGenerator,
+};
+use result::{
+	Error,
+	Result,
+};
+use space::{
+	GamutMapping,
+	RgbSpace,
+	Surround,
+	ViewingConditions,
+	Xyz,
+};
+use utilities::canonical_float;
+
+// Non-local imports.
+use color::Color;
+
+// Standard imports.
+use std::collections::{
+	BTreeMap,
+	HashMap,
+};
+use std::io;
+use std::io::{
+	BufRead,
+	BufReader,
+};
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// TemplateRamp
+////////////////////////////////////////////////////////////////////////////////
+/// Describes a ramp to regenerate when a template is instantiated.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemplateRamp {
+	/// The address of the ramp's starting color.
+	pub start: Address,
+	/// The address of the ramp's ending color.
+	pub end: Address,
+	/// The number of colors in the ramp.
+	pub count: usize,
+	/// The address of the first ramp color.
+	pub location: Address,
+	/// The generator used to produce the ramp colors.
+	pub generator: RampGenerator,
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Template
+////////////////////////////////////////////////////////////////////////////////
+/// A reusable palette structure.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::operation::*;
+/// use palette::ramp::RampGenerator;
+/// use palette::template::{Template, TemplateRamp};
+///
+/// let mut pal = Palette::new("Character", Format::Default, true);
+/// pal.apply(Box::new(InsertColor::new(Color::new(0, 0, 0)))).unwrap();
+/// pal.apply(Box::new(InsertColor::new(Color::new(100, 100, 100)))).unwrap();
+///
+/// let template = Template::from_palette(&pal)
+/// 	.base("shadow", Address::new(0, 0, 0))
+/// 	.base("light", Address::new(0, 0, 1))
+/// 	.ramp(TemplateRamp {
+/// 		start: Address::new(0, 0, 0),
+/// 		end: Address::new(0, 0, 1),
+/// 		count: 3,
+/// 		location: Address::new(0, 1, 0),
+/// 		generator: RampGenerator::new(),
+/// 	});
+///
+/// let skin = template.instantiate("Skin", &[
+/// 	("shadow", Color::new(40, 20, 10)),
+/// 	("light", Color::new(240, 200, 160)),
+/// ]).unwrap();
+///
+/// assert_eq!(skin.color(Address::new(0, 1, 0)), Some(Color::new(40, 20, 10)));
+/// assert_eq!(skin.color(Address::new(0, 1, 2)), Some(Color::new(240, 200, 160)));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Template {
+	/// The format of instantiated palettes.
+	format: Format,
+	/// The working color space of instantiated palettes.
+	color_space: RgbSpace,
+	/// The group metadata of instantiated palettes.
+	metadata: HashMap<Reference, MetaData>,
+	/// The default expressions of each cell.
+	cells: BTreeMap<Address, Expression>,
+	/// The named base color addresses.
+	bases: BTreeMap<String, Address>,
+	/// The ramps to regenerate, in order.
+	ramps: Vec<TemplateRamp>,
+}
+
+
+impl Template {
+	/// Creates a new `Template` recording the structure and colors of the
+	/// given palette.
+	pub fn from_palette(palette: &Palette) -> Self {
+		let data = &palette.data;
+		let mut metadata = (*data.metadata).clone();
+		if let Some(meta) = metadata.get_mut(&Reference::all()) {
+			meta.name = None;
+		}
+
+		Template {
+			format: palette.format,
+			color_space: data.color_space,
+			metadata: metadata,
+			cells: data.cells
+				.iter()
+				.map(|(&address, cell)| (address, *cell.borrow()))
+				.collect(),
+			bases: BTreeMap::new(),
+			ramps: Vec::new(),
+		}
+	}
+
+	/// Declares a named base color, substituted at the given address when the
+	/// template is instantiated.
+	pub fn base<S>(mut self, name: S, address: Address) -> Self 
+		where S: Into<String>
+	{
+		self.bases.insert(name.into(), address);
+		self
+	}
+
+	/// Adds a ramp to regenerate when the template is instantiated. Ramps are
+	/// regenerated in the order they are added, so later ramps may use the
+	/// colors of earlier ones.
+	pub fn ramp(mut self, ramp: TemplateRamp) -> Self {
+		self.ramps.push(ramp);
+		self
+	}
+
+	/// Returns the names and addresses of the template's base colors.
+	pub fn bases(&self) -> &BTreeMap<String, Address> {
+		&self.bases
+	}
+
+	/// Returns the template's ramps.
+	pub fn ramps(&self) -> &[TemplateRamp] {
+		&self.ramps
+	}
+
+	/// Creates a new palette with the given name from the template, replacing
+	/// the named base colors with the given colors and regenerating each ramp.
+	/// Bases without a given color keep their color from the template.
+	///
+	/// # Errors
+	///
+	/// Returns an `UndefinedName` error if a given base name is not defined by
+	/// the template, or any error generated while regenerating the ramps.
+	pub fn instantiate<S>(&self, name: S, bases: &[(&str, Color)]) 
+		-> Result<Palette>
+		where S: Into<String>
+	{
+		let mut palette = Palette::new(name, self.format, true);
+		{
+			let data = &mut palette.data;
+			data.color_space = self.color_space;
+			for (group, meta) in &self.metadata {
+				let entry = data.metadata_mut()
+					.entry(group.clone())
+					.or_insert_with(Default::default);
+				let name = entry.name.take();
+				*entry = meta.clone();
+				if entry.name.is_none() { entry.name = name; }
+			}
+
+			for (&address, &expr) in &self.cells {
+				*data.create_cell(address)?.borrow_mut() = expr;
+			}
+
+			for &(base, color) in bases {
+				let address = *self.bases
+					.get(base)
+					.ok_or_else(|| Error::UndefinedName(base.to_owned()))?;
+				let cell = match data.cell_mut(address) {
+					Some(cell) => cell,
+					None => data.create_cell(address)?,
+				};
+				*cell.borrow_mut() = Expression::Color(color);
+			}
+
+			for ramp in &self.ramps {
+				InsertRamp::new(ramp.start, ramp.end, ramp.count)
+					.generator(ramp.generator.clone())
+					.located_at(ramp.location)
+					.overwrite(true)
+					.apply(data)?;
+			}
+		}
+		Ok(palette)
+	}
+
+	/// Writes the template to the given buffer. The template is stored in the
+	/// default palette format, followed by its base and ramp entries.
+	pub fn write<W>(&self, out_buf: &mut W) -> io::Result<()> 
+		where W: io::Write
+	{
+		let palette = self.instantiate("", &[])
+			.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+		default::write_palette(&palette, out_buf)?;
+
+		for (name, address) in &self.bases {
+			writeln!(out_buf, "base {} {}", default::quote(name), address)?;
+		}
+		for ramp in &self.ramps {
+			write!(out_buf, "ramp {} {} {} {} {} {}",
+				ramp.start,
+				ramp.end,
+				ramp.count,
+				ramp.location,
+				ramp.generator.interpolation_space().name(),
+				ramp.generator.gamut_mapping_strategy().name())?;
+			if let Some(curve) = ramp.generator.saturation() {
+				write!(out_buf, " saturation {}", curve)?;
+			}
+			if let Some(curve) = ramp.generator.value() {
+				write!(out_buf, " value {}", curve)?;
+			}
+			let conditions = ramp.generator.viewing_conditions();
+			if conditions != ViewingConditions::SRGB {
+				write!(out_buf, " viewing {} {} {} {} {} {}",
+					canonical_float(conditions.adapting_luminance),
+					canonical_float(conditions.background_luminance),
+					conditions.surround.name(),
+					canonical_float(conditions.white.x),
+					canonical_float(conditions.white.y),
+					canonical_float(conditions.white.z))?;
+			}
+			writeln!(out_buf)?;
+		}
+		Ok(())
+	}
+
+	/// Reads a template from the given buffer.
+	pub fn read<R>(in_buf: &mut R) -> io::Result<Template> 
+		where R: io::Read
+	{
+		let mut palette_text = String::new();
+		let mut bases = Vec::new();
+		let mut ramps = Vec::new();
+
+		for (i, line) in BufReader::new(in_buf).lines().enumerate() {
+			let line = line?;
+			let tokens = default::tokenize(&line)
+				.map_err(|e| default::invalid(i, e))?;
+			match tokens.first().map(|t| &t[..]) {
+				Some("base") => bases.push(read_base(&tokens)
+					.map_err(|e| default::invalid(i, e))?),
+				Some("ramp") => ramps.push(read_ramp(&tokens)
+					.map_err(|e| default::invalid(i, e))?),
+				// Keep line numbering intact for palette errors.
+				Some(_) => palette_text.push_str(&line),
+				None => (),
+			}
+			palette_text.push('\n');
+		}
+
+		let palette = default::read_palette(&mut palette_text.as_bytes())?;
+		let mut template = Template::from_palette(&palette);
+		for (name, address) in bases {
+			template = template.base(name, address);
+		}
+		template.ramps = ramps;
+		Ok(template)
+	}
+}
+
+
+/// Parses a base entry.
+fn read_base(tokens: &[String]) 
+	-> ::std::result::Result<(String, Address), String>
+{
+	Ok((
+		default::token(tokens, 1)?,
+		default::parse_address(&default::token(tokens, 2)?)?,
+	))
+}
+
+
+/// Parses a ramp entry.
+fn read_ramp(tokens: &[String]) -> ::std::result::Result<TemplateRamp, String> {
+	let space = default::token(tokens, 5)?;
+	let space = InterpolationSpace::from_name(&space)
+		.ok_or_else(|| format!("unknown interpolation space '{}'", space))?;
+	let mapping = default::token(tokens, 6)?;
+	let mapping = GamutMapping::from_name(&mapping)
+		.ok_or_else(|| format!("unknown gamut mapping '{}'", mapping))?;
+
+	let mut generator = RampGenerator::new()
+		.space(space)
+		.gamut_mapping(mapping);
+
+	// Read the optional saturation and value curves and viewing conditions.
+	let mut rest = tokens.iter().skip(7).peekable();
+	while let Some(keyword) = rest.next() {
+		if keyword == "viewing" {
+			let values: Vec<&String> = rest.by_ref().take(6).collect();
+			if values.len() < 6 {
+				return Err("missing viewing conditions".to_owned());
+			}
+			let surround = Surround::from_name(values[2])
+				.ok_or_else(|| format!("unknown surround '{}'", values[2]))?;
+			generator = generator.viewing(ViewingConditions::new(
+				Xyz::new(
+					default::parse(values[3])?,
+					default::parse(values[4])?,
+					default::parse(values[5])?),
+				default::parse(values[0])?,
+				default::parse(values[1])?,
+				surround));
+			continue;
+		}
+
+		let mut curve = Curve::default();
+		while let Some(point) = rest.peek()
+			.filter(|t| t.contains(':'))
+			.map(|t| parse_point(t))
+		{
+			let (x, y) = point?;
+			curve.insert_point(x, y);
+			rest.next();
+		}
+		generator = match &keyword[..] {
+			"saturation" => generator.saturation_curve(curve),
+			"value" => generator.value_curve(curve),
+			_ => return Err(format!("unknown ramp curve '{}'", keyword)),
+		};
+	}
+
+	Ok(TemplateRamp {
+		start: default::parse_address(&default::token(tokens, 1)?)?,
+		end: default::parse_address(&default::token(tokens, 2)?)?,
+		count: default::parse(&default::token(tokens, 3)?)?,
+		location: default::parse_address(&default::token(tokens, 4)?)?,
+		generator: generator,
+	})
+}
@@ -0,0 +1,114 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides Unicode handling for user-provided text such as cell names and 
+//! tags.
+//!
+//! Names are stored in Unicode Normalization Form C, so that names which look
+//! identical compare equal regardless of how they were typed. Truncation for
+//! formats with length limits is performed on grapheme cluster boundaries, so
+//! that accented letters and emoji are never split.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Non-local imports.
+use unicode_normalization::{
+	is_nfc,
+	UnicodeNormalization,
+};
+use unicode_segmentation::UnicodeSegmentation;
+
+
+
+/// Returns the given text in Unicode Normalization Form C.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::text::normalize;
+///
+/// // "e" followed by a combining acute accent.
+/// assert_eq!(normalize("cafe\u{301}"), "caf\u{E9}");
+/// ```
+pub fn normalize<S>(text: S) -> String where S: Into<String> {
+	let text = text.into();
+	if is_nfc(&text) { text } else { text.nfc().collect() }
+}
+
+
+/// Returns the longest prefix of the given text containing at most the given
+/// number of grapheme clusters.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::text::truncate_graphemes;
+///
+/// assert_eq!(truncate_graphemes("cafe\u{301} noir", 4), "cafe\u{301}");
+/// assert_eq!(truncate_graphemes("sky", 8), "sky");
+/// ```
+pub fn truncate_graphemes(text: &str, max: usize) -> &str {
+	match text.grapheme_indices(true).nth(max) {
+		Some((index, _)) => &text[..index],
+		None => text,
+	}
+}
+
+
+/// Returns the longest prefix of the given text made of whole grapheme 
+/// clusters which encodes to at most the given number of UTF-16 code units.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::text::truncate_utf16;
+///
+/// // Each flag is two astral code points, or four UTF-16 code units.
+/// assert_eq!(truncate_utf16("\u{1F1EF}\u{1F1F5}\u{1F1EB}\u{1F1F7}", 6), 
+/// 	"\u{1F1EF}\u{1F1F5}");
+/// ```
+pub fn truncate_utf16(text: &str, max_units: usize) -> &str {
+	let mut units = 0;
+	for (index, grapheme) in text.grapheme_indices(true) {
+		units += grapheme.encode_utf16().count();
+		if units > max_units { return &text[..index]; }
+	}
+	text
+}
+
+
+/// Returns the given text encoded as big-endian UTF-16.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::text::utf16_be;
+///
+/// assert_eq!(utf16_be("A\u{E9}"), vec![0x00, 0x41, 0x00, 0xE9]);
+/// ```
+pub fn utf16_be(text: &str) -> Vec<u8> {
+	text.encode_utf16()
+		.flat_map(|unit| vec![(unit >> 8) as u8, unit as u8])
+		.collect()
+}
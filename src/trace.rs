@@ -0,0 +1,110 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides timing instrumentation for palette operations, imports, and 
+//! exports.
+//!
+//! With the `trace` feature enabled, each activity is wrapped in a `tracing`
+//! span named `palette`, with `kind` and `name` fields, and an event is 
+//! emitted when it finishes recording the number of cells affected and the 
+//! elapsed time in microseconds. Without the feature, spans do nothing.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Non-local imports.
+#[cfg(feature = "trace")]
+use tracing;
+
+// Standard imports.
+#[cfg(feature = "trace")]
+use std::time::Instant;
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Span
+////////////////////////////////////////////////////////////////////////////////
+/// A timed span around a palette activity.
+pub(crate) struct Span {
+	/// The entered tracing span.
+	#[cfg(feature = "trace")]
+	span: tracing::span::EnteredSpan,
+	/// The time the span was entered.
+	#[cfg(feature = "trace")]
+	start: Instant,
+}
+
+
+impl Span {
+	/// Enters a new span for the activity of the given kind and name.
+	#[cfg(feature = "trace")]
+	pub(crate) fn enter(kind: &'static str, name: &str) -> Span {
+		Span {
+			span: tracing::info_span!("palette", kind = kind, name = name)
+				.entered(),
+			start: Instant::now(),
+		}
+	}
+
+	/// Enters a new span for the activity of the given kind and name.
+	#[cfg(not(feature = "trace"))]
+	#[inline]
+	pub(crate) fn enter(kind: &'static str, name: &str) -> Span {
+		let _ = (kind, name);
+		Span {}
+	}
+
+	/// Exits the span, recording the number of cells affected.
+	#[cfg(feature = "trace")]
+	pub(crate) fn finish(self, cells: usize) {
+		let elapsed = self.start.elapsed();
+		tracing::info!(
+			cells = cells,
+			elapsed_us = elapsed.as_micros() as u64,
+			"finished");
+		self.span.exit();
+	}
+
+	/// Exits the span, recording the number of cells affected.
+	#[cfg(not(feature = "trace"))]
+	#[inline]
+	pub(crate) fn finish(self, cells: usize) {
+		let _ = cells;
+	}
+
+	/// Exits the span, computing the number of cells affected only if the 
+	/// span is being recorded.
+	#[inline]
+	pub(crate) fn finish_with<F>(self, cells: F) where F: FnOnce() -> usize {
+		#[cfg(feature = "trace")]
+		{
+			let cells = cells();
+			self.finish(cells);
+		}
+		#[cfg(not(feature = "trace"))]
+		{
+			let _ = cells;
+		}
+	}
+}
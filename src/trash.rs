@@ -0,0 +1,83 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides `TrashedCell`, which holds a cell deleted from a palette so that
+//! it can be restored.
+//!
+//! Cells removed from a `Palette` by an operation, undo, or redo are moved to
+//! the palette's trash rather than destroyed. The trash is kept apart from the
+//! operation history, so trashed cells remain recoverable after the history is
+//! cleared, and are saved with the palette. Cells are kept in the trash until
+//! they are restored or purged.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Local imports.
+use address::Address;
+use cell::CellId;
+use expression::Expression;
+use provenance::Provenance;
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// TrashedCell
+////////////////////////////////////////////////////////////////////////////////
+/// A cell which has been deleted from a palette.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::*;
+/// use palette::operation::{DeleteCell, InsertColor};
+///
+/// let mut pal = Palette::new("Example", Format::Default, true);
+/// pal.apply(Box::new(InsertColor::new(Color::new(1, 2, 3)))).unwrap();
+/// pal.set_name(Address::new(0, 0, 0), "shadow");
+/// pal.apply(Box::new(DeleteCell::new(Address::new(0, 0, 0)))).unwrap();
+///
+/// assert_eq!(pal.trash().len(), 1);
+/// assert_eq!(pal.trash()[0].address, Address::new(0, 0, 0));
+/// assert_eq!(pal.trash()[0].name.as_ref().map(|n| &n[..]), Some("shadow"));
+///
+/// let restored = pal.restore_from_trash(0).unwrap();
+/// assert_eq!(restored, Some(Address::new(0, 0, 0)));
+/// assert_eq!(pal.color(Address::new(0, 0, 0)), Some(Color::new(1, 2, 3)));
+/// assert!(pal.trash().is_empty());
+/// ```
+#[derive(Debug, Clone)]
+pub struct TrashedCell {
+	/// The address the cell was deleted from.
+	pub address: Address,
+	/// The expression of the cell.
+	pub expression: Expression,
+	/// The name of the cell, if it had one.
+	pub name: Option<String>,
+	/// The origin of the cell's color, if it was known.
+	pub provenance: Option<Provenance>,
+	/// The `CellId` of the cell, which is reinstated when the cell is 
+	/// restored. Identifiers are not saved, so this is None for cells read
+	/// from a file.
+	pub id: Option<CellId>,
+}
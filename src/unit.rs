@@ -0,0 +1,207 @@
+// The MIT License (MIT)
+// 
+// Copyright (c) 2016 Skylor R. Schermer
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in 
+// all copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//
+////////////////////////////////////////////////////////////////////////////////
+//!
+//! Provides unit-aware numeric parameters.
+//!
+//! A `Quantity` is a number tagged with the unit it was written in. Each
+//! parameter that accepts a `Quantity` reads it on a particular scale, and 
+//! quantities are converted to that scale using their fraction of a full 
+//! range:
+//!
+//! | Unit      | Full range | As a fraction | As degrees  | As channel   |
+//! |-----------|------------|---------------|-------------|--------------|
+//! | `Number`  | (native)   | `n`           | `n`         | `n`          |
+//! | `Percent` | 100        | `p / 100`     | `p * 3.6`   | `p * 2.55`   |
+//! | `Degrees` | 360        | `d / 360`     | `d`         | `d / 360 * 255` |
+//! | `Channel` | 255        | `c / 255`     | `c / 255 * 360` | `c`      |
+//!
+//! Plain numbers are always read on the parameter's native scale, so `f32` 
+//! arguments keep their existing meaning.
+//!
+////////////////////////////////////////////////////////////////////////////////
+
+// Standard imports.
+use std::fmt;
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Unit
+////////////////////////////////////////////////////////////////////////////////
+/// The unit of a `Quantity`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Unit {
+	/// A plain number, read on the native scale of the parameter.
+	Number,
+	/// A percentage of the parameter's full range.
+	Percent,
+	/// An angle in degrees, where 360 is the full range.
+	Degrees,
+	/// An 8-bit channel value, where 255 is the full range.
+	Channel,
+}
+
+
+impl Unit {
+	/// Returns the suffix used to write the unit.
+	pub fn suffix(&self) -> &'static str {
+		match *self {
+			Unit::Number  => "",
+			Unit::Percent => "%",
+			Unit::Degrees => "deg",
+			Unit::Channel => "ch",
+		}
+	}
+
+	/// Returns the unit written with the given suffix.
+	pub fn from_suffix(suffix: &str) -> Option<Unit> {
+		match suffix {
+			""           => Some(Unit::Number),
+			"%"          => Some(Unit::Percent),
+			"deg" | "°"  => Some(Unit::Degrees),
+			"ch"         => Some(Unit::Channel),
+			_            => None,
+		}
+	}
+
+	/// Returns the value of the unit's full range, or None for plain numbers.
+	fn full_range(&self) -> Option<f32> {
+		match *self {
+			Unit::Number  => None,
+			Unit::Percent => Some(100.0),
+			Unit::Degrees => Some(360.0),
+			Unit::Channel => Some(255.0),
+		}
+	}
+}
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+// Quantity
+////////////////////////////////////////////////////////////////////////////////
+/// A number tagged with its unit.
+///
+/// # Example
+///
+/// ```rust
+/// use palette::unit::Quantity;
+///
+/// assert_eq!(Quantity::percent(50.0).as_fraction(), 0.5);
+/// assert_eq!(Quantity::percent(50.0).as_degrees(), 180.0);
+/// assert_eq!(Quantity::channel(51.0).as_fraction(), 0.2);
+/// assert_eq!(Quantity::from(0.25).as_fraction(), 0.25);
+/// assert_eq!(Quantity::from(0.25).as_degrees(), 0.25);
+///
+/// assert_eq!("30deg".parse(), Ok(Quantity::degrees(30.0)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quantity {
+	/// The value, written in the quantity's unit.
+	pub value: f32,
+	/// The unit of the value.
+	pub unit: Unit,
+}
+
+
+impl Quantity {
+	/// Creates a new `Quantity`.
+	pub fn new(value: f32, unit: Unit) -> Self {
+		Quantity {value: value, unit: unit}
+	}
+
+	/// Creates a plain number.
+	pub fn number(value: f32) -> Self {
+		Quantity::new(value, Unit::Number)
+	}
+
+	/// Creates a percentage.
+	pub fn percent(value: f32) -> Self {
+		Quantity::new(value, Unit::Percent)
+	}
+
+	/// Creates an angle in degrees.
+	pub fn degrees(value: f32) -> Self {
+		Quantity::new(value, Unit::Degrees)
+	}
+
+	/// Creates an 8-bit channel value.
+	pub fn channel(value: f32) -> Self {
+		Quantity::new(value, Unit::Channel)
+	}
+
+	/// Returns the quantity on a scale with the given full range.
+	pub fn on_scale(&self, full_range: f32) -> f32 {
+		match self.unit.full_range() {
+			Some(range) => self.value / range * full_range,
+			None => self.value,
+		}
+	}
+
+	/// Returns the quantity as a fraction of a full range.
+	pub fn as_fraction(&self) -> f32 {
+		self.on_scale(1.0)
+	}
+
+	/// Returns the quantity as an angle in degrees.
+	pub fn as_degrees(&self) -> f32 {
+		self.on_scale(360.0)
+	}
+
+	/// Returns the quantity as an 8-bit channel value.
+	pub fn as_channel(&self) -> f32 {
+		self.on_scale(255.0)
+	}
+}
+
+
+impl From<f32> for Quantity {
+	fn from(value: f32) -> Self {
+		Quantity::number(value)
+	}
+}
+
+
+impl fmt::Display for Quantity {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}{}", self.value, self.unit.suffix())
+	}
+}
+
+
+impl ::std::str::FromStr for Quantity {
+	type Err = String;
+
+	fn from_str(text: &str) -> Result<Self, Self::Err> {
+		let split = text
+			.find(|c: char| !(c.is_digit(10) || c == '.' || c == '-' || c == '+'))
+			.unwrap_or(text.len());
+		let (value, suffix) = text.split_at(split);
+		let unit = Unit::from_suffix(suffix)
+			.ok_or_else(|| format!("unknown unit '{}'", suffix))?;
+		let value = value.parse()
+			.map_err(|_| format!("invalid number '{}'", value))?;
+		Ok(Quantity::new(value, unit))
+	}
+}
@@ -25,6 +25,11 @@
 //! Defines general purpose functions for palette use.
 //!
 ////////////////////////////////////////////////////////////////////////////////
+
+// Non-local imports.
+use color::Color;
+
+// Standard imports.
 use std::f32;
 
 
@@ -144,3 +149,114 @@ pub fn lerp_f32(start: f32, end:f32, amount: f32) -> f32 {
 	let e = if start > end {start} else {end};
 	(((e-s) as f32) * a) as f32 + s
 }
+
+
+
+/// Returns the given color as an uppercase `#RRGGBB` hex string.
+///
+/// # Examples
+///
+/// ```rust
+/// # use palette::Color;
+/// # use palette::utilities::hex;
+/// assert_eq!(hex(Color::new(12, 50, 255)), "#0C32FF");
+/// ```
+pub fn hex(color: Color) -> String {
+	format!("#{:02X}{:02X}{:02X}", color.red(), color.green(), color.blue())
+}
+
+
+/// Returns the canonical text representation of the given float: rounded to
+/// six decimal places, without trailing zeros, and without a sign on zero. 
+/// Values which differ only by rounding noise produce the same text.
+///
+/// # Examples
+///
+/// ```rust
+/// # use palette::utilities::canonical_float;
+/// assert_eq!(canonical_float(0.5), "0.5");
+/// assert_eq!(canonical_float(0.1 + 0.2), "0.3");
+/// assert_eq!(canonical_float(-0.0), "0");
+/// assert_eq!(canonical_float(2.0), "2");
+/// ```
+pub fn canonical_float(value: f32) -> String {
+	if !value.is_finite() { return value.to_string(); }
+	let mut text = format!("{:.6}", value);
+	while text.ends_with('0') { text.pop(); }
+	if text.ends_with('.') { text.pop(); }
+	if text == "-0" { text.remove(0); }
+	text
+}
+
+
+/// Parses a color from a `#RRGGBB` or `#RGB` hex string. The leading `#` is
+/// optional.
+///
+/// # Examples
+///
+/// ```rust
+/// # use palette::Color;
+/// # use palette::utilities::parse_hex;
+/// assert_eq!(parse_hex("#0C32FF"), Some(Color::new(12, 50, 255)));
+/// assert_eq!(parse_hex("f80"), Some(Color::new(255, 136, 0)));
+/// assert_eq!(parse_hex("#12345"), None);
+/// ```
+pub fn parse_hex(text: &str) -> Option<Color> {
+	let digits = if text.starts_with('#') { &text[1..] } else { text };
+	if !digits.is_ascii() { return None; }
+	let channel = |s: &str| u8::from_str_radix(s, 16).ok();
+	match digits.len() {
+		3 => Some(Color::new(
+			channel(&digits[0..1])? * 17,
+			channel(&digits[1..2])? * 17,
+			channel(&digits[2..3])? * 17,
+		)),
+		6 => Some(Color::new(
+			channel(&digits[0..2])?,
+			channel(&digits[2..4])?,
+			channel(&digits[4..6])?,
+		)),
+		_ => None,
+	}
+}
+
+/// Returns whether the given text matches the given pattern, ignoring case. 
+/// The pattern may use `*` to match any text and `?` to match any single 
+/// character.
+///
+/// # Examples
+///
+/// ```rust
+/// # use palette::utilities::glob_matches;
+/// assert!(glob_matches("*.shadow?", "Skin.Shadow1"));
+/// assert!(!glob_matches("*.pal", "skin.txt"));
+/// ```
+pub fn glob_matches(pattern: &str, text: &str) -> bool {
+	let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+	let text: Vec<char> = text.to_lowercase().chars().collect();
+
+	// Greedy matching, backtracking to the most recent '*' on a mismatch.
+	let (mut p, mut n) = (0, 0);
+	let mut backtrack = None;
+	while n < text.len() {
+		match pattern.get(p) {
+			Some(&'*') => {
+				backtrack = Some((p, n));
+				p += 1;
+			},
+			Some(&c) if c == '?' || c == text[n] => {
+				p += 1;
+				n += 1;
+			},
+			_ => match backtrack {
+				Some((bp, bn)) => {
+					backtrack = Some((bp, bn + 1));
+					p = bp + 1;
+					n = bn + 1;
+				},
+				None => return false,
+			},
+		}
+	}
+	pattern[p..].iter().all(|&c| c == '*')
+}